@@ -1,9 +1,15 @@
 // Menu commands. One async fn per option — each prompts via `read_line`
 // and prints the result.
 
-use color_eyre::eyre::Result;
+use std::path::Path;
+use std::time::Duration;
 
-use tina_worker::TinaWorker;
+use color_eyre::eyre::{Result, bail};
+
+use tina_worker::{
+    AccountConnectionState, ExportFormat, IpcError, MessageType, TinaWorker, WorkerError,
+    WorkerEvent, recv_event,
+};
 
 use crate::io::read_line;
 
@@ -91,25 +97,110 @@ pub async fn list_chats(worker: &TinaWorker) -> Result<()> {
     Ok(())
 }
 
+pub async fn list_groups(worker: &TinaWorker) -> Result<()> {
+    let id = read_line("Account ID: ")?;
+    let groups = worker.list_groups_summary(id.trim()).await?;
+
+    if groups.is_empty() {
+        println!("No groups found");
+    } else {
+        println!("\nGroups ({}):", groups.len());
+        for g in &groups {
+            println!(
+                "  👥 {:>4}  {}",
+                g.participant_count,
+                g.subject.as_deref().unwrap_or(&g.chat_id)
+            );
+        }
+    }
+    Ok(())
+}
+
 pub async fn list_messages(worker: &TinaWorker) -> Result<()> {
     let account_id = read_line("Account ID: ")?;
     let chat_id = read_line("Chat ID: ")?;
+    let type_input = read_line("Type (empty = all): ")?;
+    let message_type = if type_input.is_empty() {
+        None
+    } else {
+        Some(parse_message_type(&type_input)?)
+    };
+    print_messages(worker, account_id.trim(), chat_id.trim(), message_type).await
+}
 
-    let messages = worker
-        .get_messages(account_id.trim(), chat_id.trim(), 20, 0)
+/// Nome canônico (`text`, `image`, …) ou erro listando os aceitos.
+pub fn parse_message_type(name: &str) -> Result<MessageType> {
+    match MessageType::from_name(name) {
+        Some(t) => Ok(t),
+        None => {
+            let known: Vec<&str> = MessageType::ALL.iter().map(|t| t.as_str()).collect();
+            bail!(
+                "unknown message type `{}` (expected one of: {})",
+                name.trim(),
+                known.join(", ")
+            )
+        }
+    }
+}
+
+/// Últimas 20 mensagens do chat, opcionalmente só de um tipo.
+pub async fn print_messages(
+    worker: &TinaWorker,
+    account_id: &str,
+    chat_id: &str,
+    message_type: Option<MessageType>,
+) -> Result<()> {
+    let types = message_type.map(|t| [t]);
+    let page = worker
+        .get_messages(account_id, chat_id, types.as_ref().map(|t| &t[..]), 20, 0)
         .await?;
 
-    if messages.is_empty() {
+    if page.items.is_empty() {
         println!("No messages found");
     } else {
-        println!("\nMessages ({}):", messages.len());
-        for msg in messages {
+        let more = if page.has_more {
+            ", more available"
+        } else {
+            ""
+        };
+        println!("\nMessages ({}{}):", page.items.len(), more);
+        for msg in page.items {
             let direction = if msg.is_from_me { "→" } else { "←" };
             println!(
                 "  {} [{}] {}: {}",
                 direction,
                 msg.message_type,
-                msg.sender_contact_id.as_deref().unwrap_or("?"),
+                msg.sender_name
+                    .as_deref()
+                    .or(msg.sender_contact_id.as_deref())
+                    .unwrap_or("?"),
+                msg.content.as_deref().unwrap_or("[media]")
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn list_starred(worker: &TinaWorker) -> Result<()> {
+    let account_id = read_line("Account ID: ")?;
+    let messages = worker
+        .get_starred_messages(account_id.trim(), 50, 0)
+        .await?;
+
+    if messages.is_empty() {
+        println!("No starred messages");
+    } else {
+        println!("\nStarred ({}):", messages.len());
+        for msg in messages {
+            let sender = if msg.is_from_me {
+                "me"
+            } else {
+                msg.sender_name.as_deref().unwrap_or("?")
+            };
+            println!(
+                "  ★ [{}] {}: {}",
+                msg.chat_id,
+                sender,
                 msg.content.as_deref().unwrap_or("[media]")
             );
         }
@@ -117,6 +208,15 @@ pub async fn list_messages(worker: &TinaWorker) -> Result<()> {
     Ok(())
 }
 
+pub async fn refetch_contacts_and_groups(worker: &TinaWorker) -> Result<()> {
+    let id = read_line("Account ID: ")?;
+    let id = id.trim();
+    let contacts = worker.request_contacts_refresh(id).await?;
+    let groups = worker.request_groups_refresh(id).await?;
+    println!("Refetched {contacts} contact(s) and {groups} group(s) from WhatsApp");
+    Ok(())
+}
+
 pub async fn reconcile_account(worker: &TinaWorker) -> Result<()> {
     let id = read_line("Account ID: ")?;
     worker.reconcile_account(id.trim()).await?;
@@ -132,9 +232,105 @@ pub async fn send_message(worker: &TinaWorker) -> Result<()> {
     let to = read_line("To (JID): ")?;
     let content = read_line("Message: ")?;
 
-    worker
-        .send_message(account_id.trim(), to.trim(), content.trim(), &[])
-        .await?;
-    println!("Message sent!");
+    let local_id = uuid::Uuid::now_v7().to_string();
+    match worker
+        .send_message(account_id.trim(), to.trim(), content.trim(), &[], &local_id)
+        .await
+    {
+        Ok(()) => println!("Message sent!"),
+        Err(WorkerError::SendFailed(reason)) => println!("Message not sent: {reason}"),
+        Err(WorkerError::Ipc(IpcError::Timeout)) => {
+            println!("No answer from WhatsApp yet; the message is still pending.")
+        }
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+pub async fn ipc_stats(worker: &TinaWorker) {
+    let stats = worker.ipc_stats().await;
+    println!("\nIPC stats:");
+    println!("  Commands sent:   {}", stats.commands_sent);
+    println!("  Events received: {}", stats.events_received);
+    println!("  Results matched: {}", stats.round_trips);
+    match stats.avg_round_trip {
+        Some(avg) => println!("  Avg round-trip:  {avg:?}"),
+        None => println!("  Avg round-trip:  (no results yet)"),
+    }
+}
+
+// ---- Subcomandos não interativos (`tina-cli backup|restore|export|broadcast`) ----
+
+pub async fn backup(worker: &TinaWorker, out: &Path) -> Result<()> {
+    worker.backup(out).await?;
+    println!("Backup written to {}", out.display());
+    Ok(())
+}
+
+/// Runs before `start()`, so the worker isn't flushing anything.
+pub async fn restore(worker: &TinaWorker, input: &Path) -> Result<()> {
+    worker.restore(input).await?;
+    println!("Database restored from {}", input.display());
+    Ok(())
+}
+
+pub async fn export_chat(
+    worker: &TinaWorker,
+    account_id: &str,
+    chat_id: &str,
+    format: ExportFormat,
+    out: &Path,
+) -> Result<()> {
+    worker.export_chat(account_id, chat_id, format, out).await?;
+    println!("Chat {chat_id} exported to {}", out.display());
+    Ok(())
+}
+
+/// Quanto esperar a conta conectar antes de desistir do broadcast.
+const BROADCAST_CONNECT_WAIT: Duration = Duration::from_secs(60);
+
+/// Runs after `start()`: connects the account, sends, prints one line
+/// per recipient.
+pub async fn broadcast(
+    worker: &TinaWorker,
+    account_id: &str,
+    recipients: &[String],
+    text: &str,
+) -> Result<()> {
+    let mut events = worker.subscribe_events();
+    worker.start_account(account_id).await?;
+    let connected = tokio::time::timeout(BROADCAST_CONNECT_WAIT, async {
+        while worker.connection_state(account_id) != AccountConnectionState::Connected {
+            match recv_event(&mut events).await {
+                Some(WorkerEvent::ConnectionStateChanged {
+                    account_id: id,
+                    state: AccountConnectionState::LoggedOut,
+                }) if id == account_id => return false,
+                Some(_) => {}
+                None => return false,
+            }
+        }
+        true
+    })
+    .await;
+    if connected != Ok(true) {
+        bail!("account {account_id} did not connect; pair it from the menu first");
+    }
+
+    let outcomes = worker.send_broadcast(account_id, recipients, text).await;
+    let sent = outcomes.iter().filter(|o| o.result.is_ok()).count();
+    for outcome in &outcomes {
+        match &outcome.result {
+            Ok(id) => println!("  {} sent ({id})", outcome.to),
+            Err(WorkerError::Ipc(IpcError::Timeout)) => {
+                println!("  {} pending: no answer from WhatsApp yet", outcome.to)
+            }
+            Err(e) => println!("  {} failed: {e}", outcome.to),
+        }
+    }
+    println!(
+        "Broadcast sent to {sent} of {} recipient(s)",
+        outcomes.len()
+    );
     Ok(())
 }
@@ -0,0 +1,117 @@
+// `tina-cli doctor`: checks what a first run needs, without starting
+// nanachi. One line per check; any critical failure makes the command
+// exit nonzero. Warnings (e.g. a binary that will be rebuilt) don't.
+
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use tina_worker::{NanachiManager, TinaWorker};
+
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Report {
+    failures: usize,
+}
+
+impl Report {
+    fn line(&mut self, status: Status, what: &str, detail: impl AsRef<str>) {
+        let mark = match status {
+            Status::Ok => "[ ok ]",
+            Status::Warn => "[warn]",
+            Status::Fail => {
+                self.failures += 1;
+                "[FAIL]"
+            }
+        };
+        println!("{mark} {what}: {}", detail.as_ref());
+    }
+}
+
+pub async fn run() -> Result<()> {
+    let mut report = Report { failures: 0 };
+
+    let go = go_version().await;
+    let nanachi = match NanachiManager::locate_dir(None) {
+        Ok(dir) => {
+            report.line(Status::Ok, "nanachi directory", dir.display().to_string());
+            Some(NanachiManager::new(dir))
+        }
+        Err(e) => {
+            report.line(Status::Fail, "nanachi directory", e.to_string());
+            None
+        }
+    };
+
+    // Go só é obrigatório quando o binário ainda precisa ser compilado.
+    let built = nanachi.as_ref().is_some_and(NanachiManager::is_built);
+    match (&go, built) {
+        (Some(version), _) => report.line(Status::Ok, "go toolchain", version),
+        (None, true) => report.line(Status::Warn, "go toolchain", "`go` not in PATH (binary is built)"),
+        (None, false) => report.line(Status::Fail, "go toolchain", "`go` not in PATH"),
+    }
+    if nanachi.is_some() {
+        if built {
+            report.line(Status::Ok, "nanachi binary", "built and up to date");
+        } else {
+            report.line(Status::Warn, "nanachi binary", "will be built on first start");
+        }
+    }
+
+    match TinaWorker::default_db_path() {
+        Ok(path) => {
+            match check_writable(&path) {
+                Ok(()) => report.line(Status::Ok, "database path", path.display().to_string()),
+                Err(e) => report.line(Status::Fail, "database path", format!("{}: {e}", path.display())),
+            }
+            match TinaWorker::new_offline(Some(path)).await {
+                Ok(worker) => {
+                    let accounts = worker.list_accounts().await.map(|a| a.len()).unwrap_or(0);
+                    report.line(Status::Ok, "database", format!("opens, {accounts} account(s)"));
+                }
+                Err(e) => report.line(Status::Fail, "database", e.to_string()),
+            }
+        }
+        Err(e) => report.line(Status::Fail, "database path", e.to_string()),
+    }
+
+    // Sem `bail!`: o checklist já explica, um backtrace só polui.
+    if report.failures > 0 {
+        println!("\n{} critical check(s) failed", report.failures);
+        std::process::exit(1);
+    }
+    println!("\nAll critical checks passed");
+    Ok(())
+}
+
+async fn go_version() -> Option<String> {
+    let out = tokio::process::Command::new("go")
+        .arg("version")
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// O diretório do banco existe (ou dá pra criar) e aceita arquivos
+/// novos; um banco já existente não está somente-leitura.
+fn check_writable(db_path: &Path) -> std::io::Result<()> {
+    if db_path.metadata().is_ok_and(|m| m.permissions().readonly()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "database file is read-only",
+        ));
+    }
+    let dir = match db_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".tina-doctor-{}", std::process::id()));
+    std::fs::File::create(&probe)?;
+    std::fs::remove_file(&probe)
+}
@@ -1,23 +1,38 @@
 // `WorkerEvent` printer: the CLI's only "UI" — translates each event
 // into a human-readable line on stdout.
 
-use tina_worker::WorkerEvent;
+use tina_worker::{WorkerEvent, progress_percent};
 
-pub fn handle_event(event: WorkerEvent) {
+use crate::qr::QrFormat;
+
+pub fn handle_event(event: WorkerEvent, qr_format: QrFormat) {
     match event {
         WorkerEvent::NanachiReady => println!("\nNanachi is ready!"),
         WorkerEvent::AccountReady { account_id } => {
             println!("\nAccount {} is ready", account_id);
         }
-        WorkerEvent::QrCode { account_id, qr } => {
-            println!("\nQR Code for account {}:", account_id);
-            print_qr_code(&qr);
+        WorkerEvent::QrCode {
+            account_id,
+            qr,
+            expires_in_secs,
+            ..
+        } => {
+            println!(
+                "\nQR Code for account {} (valid for {}s):",
+                account_id, expires_in_secs
+            );
+            crate::qr::show(&account_id, &qr, qr_format);
+        }
+        WorkerEvent::QrExpired { account_id } => {
+            crate::qr::discard(&account_id);
+            println!("\nQR Code for account {} expired", account_id);
         }
         WorkerEvent::Connected {
             account_id,
             phone_number,
             ..
         } => {
+            crate::qr::discard(&account_id);
             println!(
                 "\nConnected: {} (phone: {})",
                 account_id,
@@ -27,10 +42,44 @@ pub fn handle_event(event: WorkerEvent) {
         WorkerEvent::Disconnected { account_id, reason } => {
             println!("\nDisconnected: {} - {}", account_id, reason);
         }
-        WorkerEvent::LoggedOut { account_id } => {
-            println!("\nLogged out: {}", account_id);
+        WorkerEvent::HistoryChunkApplied {
+            account_id,
+            chunk_index,
+            is_last,
+            messages,
+        } => {
+            let end = if is_last { " (end of batch)" } else { "" };
+            println!(
+                "\n📥 History chunk {} applied for {}: {} message(s){}",
+                chunk_index, account_id, messages, end
+            );
+        }
+        WorkerEvent::ConnectTimedOut {
+            account_id,
+            stopped,
+        } => {
+            let stop = if stopped { ", account stopped" } else { "" };
+            println!("\nConnect timed out: {}{}", account_id, stop);
+        }
+        WorkerEvent::ConnectionStateChanged { account_id, state } => {
+            println!("\nConnection ({}): {:?}", account_id, state);
+        }
+        WorkerEvent::LoggedOut { account_id, purged } => {
+            let note = if purged { " (local data wiped)" } else { "" };
+            println!("\nLogged out: {}{}", account_id, note);
+        }
+        WorkerEvent::NetworkChanged { online, restarting } => {
+            if !online {
+                println!("\nNetwork down");
+            } else if restarting.is_empty() {
+                println!("\nNetwork back");
+            } else {
+                println!("\nNetwork back, restarting: {}", restarting.join(", "));
+            }
         }
-        WorkerEvent::ChatsUpserted { account_id, rows } => {
+        WorkerEvent::ChatsUpserted {
+            account_id, rows, ..
+        } => {
             println!("\n💬 {} chat(s) upserted for {}", rows.len(), account_id);
             for row in rows.iter().take(5) {
                 println!(
@@ -41,6 +90,12 @@ pub fn handle_event(event: WorkerEvent) {
                 );
             }
         }
+        WorkerEvent::ContactsSynced { account_id, count } => {
+            println!("\n👤 {} contact(s) synced for {}", count, account_id);
+        }
+        WorkerEvent::GroupsSynced { account_id, count } => {
+            println!("\n👥 {} group(s) synced for {}", count, account_id);
+        }
         WorkerEvent::StatusAuthorsUpserted { account_id, rows } => {
             println!("\n📸 {} status author(s) for {}", rows.len(), account_id);
         }
@@ -65,6 +120,9 @@ pub fn handle_event(event: WorkerEvent) {
                 account_id, messages_count
             );
         }
+        WorkerEvent::InitialSyncComplete { account_id } => {
+            println!("\nInitial sync complete for {}", account_id);
+        }
         WorkerEvent::HistorySyncProgress {
             sync_type, progress, ..
         } => {
@@ -75,13 +133,19 @@ pub fn handle_event(event: WorkerEvent) {
             stage,
             current,
             total,
-            indeterminate: _,
+            indeterminate,
+        } => match progress_percent(current, total).filter(|_| !indeterminate) {
+            Some(pct) => println!("\n🔧 {} ({}/{}, {}%)", stage, current, total, pct),
+            None if !stage.is_empty() => println!("\n🔧 {}", stage),
+            None => println!("\n🔧 Reconcile started"),
+        },
+        WorkerEvent::MessageSendFailed {
+            chat_id,
+            message_id,
+            error,
+            ..
         } => {
-            if total > 0 {
-                println!("\n🔧 {} ({}/{})", stage, current, total);
-            } else {
-                println!("\n🔧 {}", stage);
-            }
+            println!("\nSend of {} to {} failed: {}", message_id, chat_id, error);
         }
         WorkerEvent::Error { account_id, error } => {
             println!("\nError ({}): {}", account_id.unwrap_or_default(), error);
@@ -92,6 +156,16 @@ pub fn handle_event(event: WorkerEvent) {
         WorkerEvent::ReceiptUpdate { message_ids, status, .. } => {
             println!("\nReceipt: {} → {}", status, message_ids.join(","));
         }
+        WorkerEvent::MessageEdited {
+            chat_id,
+            new_content,
+            ..
+        } => {
+            println!("\n✏️  Edited in {}: {}", chat_id, new_content);
+        }
+        WorkerEvent::ChatCleared { chat_id, kept, .. } => {
+            println!("\n🧹 Cleared {} ({} starred kept)", chat_id, kept.len());
+        }
         WorkerEvent::MediaDownloadProgress {
             account_id,
             message_id,
@@ -155,10 +229,3 @@ pub fn handle_event(event: WorkerEvent) {
         }
     }
 }
-
-fn print_qr_code(qr: &str) {
-    if let Err(e) = qr2term::print_qr(qr) {
-        eprintln!("Failed to print QR code: {}", e);
-        println!("Raw QR data: {}", qr);
-    }
-}
@@ -1,7 +1,6 @@
-// stdin/stdout helpers + the nanachi-dir locator.
+// stdin/stdout helpers.
 
 use std::io::{self, Write};
-use std::path::PathBuf;
 
 use color_eyre::eyre::Result;
 
@@ -26,29 +25,10 @@ pub fn print_menu() {
     println!("║  6. List Chats                     ║");
     println!("║  7. Send Message                   ║");
     println!("║  8. Reconcile (whatsmeow → tina)   ║");
+    println!("║  9. Starred Messages               ║");
+    println!("║  g. List Groups                    ║");
+    println!("║  r. Refetch Contacts & Groups      ║");
+    println!("║  s. IPC Stats                      ║");
     println!("║  0. Exit                           ║");
     println!("╚════════════════════════════════════╝");
 }
-
-pub fn find_nanachi_dir() -> Result<PathBuf> {
-    let exe_path = std::env::current_exe()?;
-
-    let mut current = exe_path.parent();
-    while let Some(dir) = current {
-        let nanachi = dir.join("nanachi");
-        if nanachi.join("go.mod").exists() {
-            return Ok(nanachi);
-        }
-        current = dir.parent();
-    }
-
-    let cwd = std::env::current_dir()?;
-    let nanachi = cwd.join("nanachi");
-    if nanachi.join("go.mod").exists() {
-        return Ok(nanachi);
-    }
-
-    Err(color_eyre::eyre::eyre!(
-        "Could not find nanachi directory. Make sure you're running from the project root."
-    ))
-}
@@ -1,13 +1,187 @@
 // Tina CLI: bare-bones text menu for inspecting accounts, chats and
 // messages without the GTK UI. Useful for debugging the worker / DB
 // layers in isolation.
+//
+// Sem argumentos abre o menu. `backup --out PATH`, `restore --in PATH`,
+// `export --account ID --chat JID --format text|json|html --out PATH`
+// e `messages --account ID --chat JID [--type text|image|…]` rodam uma
+// vez e saem, sem subir o nanachi. `broadcast --account ID
+// --to A,B,C --text TEXT` sobe o nanachi, conecta a conta, envia e sai.
+// `--offline` (em qualquer posição) não procura nem sobe o nanachi: só
+// leitura do que já está no banco. `--qr-format terminal|png|raw`
+// (idem) escolhe como o QR de pareamento aparece. `doctor` confere Go,
+// nanachi e o banco e sai com erro se algo essencial faltar.
 
 mod commands;
+mod doctor;
 mod events;
 mod io;
+mod qr;
 
-use color_eyre::eyre::{Context, Result};
-use tina_worker::TinaWorker;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Context, Result, bail};
+use tina_worker::{ExportFormat, MessageType, NanachiManager, TinaWorker, WorkerError};
+
+enum OneShot {
+    Backup(PathBuf),
+    Restore(PathBuf),
+    Export(ExportArgs),
+    Broadcast(BroadcastArgs),
+    Messages(MessagesArgs),
+    Doctor,
+}
+
+struct ExportArgs {
+    account_id: String,
+    chat_id: String,
+    format: ExportFormat,
+    out: PathBuf,
+}
+
+const EXPORT_USAGE: &str =
+    "usage: tina-cli export --account ID --chat JID [--format text|json|html] --out PATH";
+
+/// Flags em qualquer ordem; `--format` é opcional (HTML).
+fn parse_export(args: &[String]) -> Result<ExportArgs> {
+    let (mut account_id, mut chat_id, mut out) = (None, None, None);
+    let mut format = ExportFormat::Html;
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let Some(value) = it.next() else {
+            bail!(EXPORT_USAGE);
+        };
+        match flag.as_str() {
+            "--account" => account_id = Some(value.clone()),
+            "--chat" => chat_id = Some(value.clone()),
+            "--format" => format = value.parse()?,
+            "--out" => out = Some(PathBuf::from(value)),
+            _ => bail!(EXPORT_USAGE),
+        }
+    }
+    match (account_id, chat_id, out) {
+        (Some(account_id), Some(chat_id), Some(out)) => Ok(ExportArgs {
+            account_id,
+            chat_id,
+            format,
+            out,
+        }),
+        _ => bail!(EXPORT_USAGE),
+    }
+}
+
+struct MessagesArgs {
+    account_id: String,
+    chat_id: String,
+    message_type: Option<MessageType>,
+}
+
+const MESSAGES_USAGE: &str = "usage: tina-cli messages --account ID --chat JID [--type TYPE]";
+
+/// `--type` é opcional (todos os tipos) e validado contra `MessageType`.
+fn parse_messages(args: &[String]) -> Result<MessagesArgs> {
+    let (mut account_id, mut chat_id, mut message_type) = (None, None, None);
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let Some(value) = it.next() else {
+            bail!(MESSAGES_USAGE);
+        };
+        match flag.as_str() {
+            "--account" => account_id = Some(value.clone()),
+            "--chat" => chat_id = Some(value.clone()),
+            "--type" => message_type = Some(commands::parse_message_type(value)?),
+            _ => bail!(MESSAGES_USAGE),
+        }
+    }
+    match (account_id, chat_id) {
+        (Some(account_id), Some(chat_id)) => Ok(MessagesArgs {
+            account_id,
+            chat_id,
+            message_type,
+        }),
+        _ => bail!(MESSAGES_USAGE),
+    }
+}
+
+struct BroadcastArgs {
+    account_id: String,
+    recipients: Vec<String>,
+    text: String,
+}
+
+const BROADCAST_USAGE: &str = "usage: tina-cli broadcast --account ID --to A,B,C --text TEXT";
+
+fn parse_broadcast(args: &[String]) -> Result<BroadcastArgs> {
+    let (mut account_id, mut recipients, mut text) = (None, None, None);
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let Some(value) = it.next() else {
+            bail!(BROADCAST_USAGE);
+        };
+        match flag.as_str() {
+            "--account" => account_id = Some(value.clone()),
+            "--to" => {
+                recipients = Some(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|r| !r.is_empty())
+                        .map(String::from)
+                        .collect::<Vec<_>>(),
+                )
+            }
+            "--text" => text = Some(value.clone()),
+            _ => bail!(BROADCAST_USAGE),
+        }
+    }
+    match (account_id, recipients, text) {
+        (Some(account_id), Some(recipients), Some(text))
+            if !recipients.is_empty() && !text.trim().is_empty() =>
+        {
+            Ok(BroadcastArgs {
+                account_id,
+                recipients,
+                text,
+            })
+        }
+        _ => bail!(BROADCAST_USAGE),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Option<OneShot>> {
+    let Some(cmd) = args.first() else {
+        return Ok(None);
+    };
+    let (flag, make): (&str, fn(PathBuf) -> OneShot) = match cmd.as_str() {
+        "backup" => ("--out", OneShot::Backup),
+        "restore" => ("--in", OneShot::Restore),
+        "export" => return Ok(Some(OneShot::Export(parse_export(&args[1..])?))),
+        "broadcast" => return Ok(Some(OneShot::Broadcast(parse_broadcast(&args[1..])?))),
+        "messages" => return Ok(Some(OneShot::Messages(parse_messages(&args[1..])?))),
+        "doctor" if args.len() == 1 => return Ok(Some(OneShot::Doctor)),
+        "doctor" => bail!("usage: tina-cli doctor"),
+        other => bail!(
+            "unknown command `{other}` (expected `backup`, `restore`, `export`, `messages`, `broadcast` or `doctor`)"
+        ),
+    };
+    match &args[1..] {
+        [f, path] if f == flag => Ok(Some(make(PathBuf::from(path)))),
+        _ => bail!("usage: tina-cli {cmd} {flag} PATH"),
+    }
+}
+
+/// Tira `--qr-format VALUE` de `args`, onde quer que esteja.
+fn take_qr_format(args: &mut Vec<String>) -> Result<qr::QrFormat> {
+    let Some(at) = args.iter().position(|a| a == "--qr-format") else {
+        return Ok(qr::QrFormat::default());
+    };
+    let Some(value) = args.get(at + 1) else {
+        bail!("usage: tina-cli --qr-format terminal|png|raw");
+    };
+    let format = value.parse()?;
+    args.drain(at..=at + 1);
+    Ok(format)
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -24,13 +198,53 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    let nanachi_dir = io::find_nanachi_dir()?;
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let offline = args.iter().any(|a| a == "--offline");
+    args.retain(|a| a != "--offline");
+    let qr_format = take_qr_format(&mut args)?;
+    let one_shot = parse_args(&args)?;
+    if matches!(one_shot, Some(OneShot::Doctor)) {
+        return doctor::run().await;
+    }
 
-    println!("Nanachi directory: {}", nanachi_dir.display());
+    let mut worker = if offline {
+        println!("Offline mode: browsing stored data, nanachi not started");
+        TinaWorker::new_offline(None)
+            .await
+            .wrap_err("Failed to create worker")?
+    } else {
+        let nanachi_dir = NanachiManager::locate_dir(None)?;
+        println!("Nanachi directory: {}", nanachi_dir.display());
+        TinaWorker::new(nanachi_dir, None)
+            .await
+            .wrap_err("Failed to create worker")?
+    };
 
-    let mut worker = TinaWorker::new(nanachi_dir)
-        .await
-        .wrap_err("Failed to create worker")?;
+    let broadcast = match one_shot {
+        Some(OneShot::Backup(out)) => return commands::backup(&worker, &out).await,
+        Some(OneShot::Restore(input)) => return commands::restore(&worker, &input).await,
+        Some(OneShot::Export(args)) => {
+            return commands::export_chat(
+                &worker,
+                &args.account_id,
+                &args.chat_id,
+                args.format,
+                &args.out,
+            )
+            .await;
+        }
+        Some(OneShot::Messages(args)) => {
+            return commands::print_messages(
+                &worker,
+                &args.account_id,
+                &args.chat_id,
+                args.message_type,
+            )
+            .await;
+        }
+        Some(OneShot::Broadcast(args)) => Some(args),
+        Some(OneShot::Doctor) | None => None,
+    };
 
     let mut event_rx = worker
         .take_event_receiver()
@@ -40,31 +254,60 @@ async fn main() -> Result<()> {
 
     tokio::spawn(async move {
         while let Some(event) = event_rx.recv().await {
-            events::handle_event(event);
+            events::handle_event(event, qr_format);
         }
     });
 
+    if let Some(args) = broadcast {
+        let result =
+            commands::broadcast(&worker, &args.account_id, &args.recipients, &args.text).await;
+        worker.stop().await?;
+        return result;
+    }
+
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
     loop {
         io::print_menu();
         let choice = io::read_line("Choice: ")?;
 
-        match choice.trim() {
-            "1" => commands::create_account(&worker).await?,
-            "2" => commands::list_accounts(&worker).await?,
-            "3" => commands::login_account(&worker).await?,
-            "4" => commands::list_contacts(&worker).await?,
-            "5" => commands::list_messages(&worker).await?,
-            "6" => commands::list_chats(&worker).await?,
-            "7" => commands::send_message(&worker).await?,
-            "8" => commands::reconcile_account(&worker).await?,
+        let result = match choice.trim() {
+            "1" => commands::create_account(&worker).await,
+            "2" => commands::list_accounts(&worker).await,
+            "3" => commands::login_account(&worker).await,
+            "4" => commands::list_contacts(&worker).await,
+            "5" => commands::list_messages(&worker).await,
+            "6" => commands::list_chats(&worker).await,
+            "7" => commands::send_message(&worker).await,
+            "8" => commands::reconcile_account(&worker).await,
+            "9" => commands::list_starred(&worker).await,
+            "g" => commands::list_groups(&worker).await,
+            "r" => commands::refetch_contacts_and_groups(&worker).await,
+            "s" => {
+                commands::ipc_stats(&worker).await;
+                Ok(())
+            }
             "0" => {
                 println!("Shutting down...");
                 worker.stop().await?;
                 break;
             }
-            _ => println!("Invalid choice"),
+            _ => {
+                println!("Invalid choice");
+                Ok(())
+            }
+        };
+        // Offline, login/envio falham sem derrubar o menu. Qualquer
+        // outro erro sai, mas só depois de parar o nanachi.
+        match result {
+            Err(e) if matches!(e.downcast_ref(), Some(WorkerError::OfflineMode)) => {
+                println!("Not available offline");
+            }
+            Err(e) => {
+                worker.stop().await?;
+                return Err(e);
+            }
+            Ok(()) => {}
         }
     }
 
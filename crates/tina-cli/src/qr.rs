@@ -0,0 +1,104 @@
+// Pairing QR output. `terminal` (default) draws it with unicode blocks;
+// terminals that can't show those get a PNG under the temp dir instead
+// (private to the user, deleted once the code expires or pairs),
+// and `raw` prints the string for piping into another QR tool. The
+// image uses the same raster as the GTK login card
+// (`tina_core::render_qr`).
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
+
+use color_eyre::eyre::{Result, bail};
+use tempfile::NamedTempFile;
+use tina_core::QrRaster;
+
+/// Edge of the PNG, in pixels; big enough for a phone across the room.
+const PNG_SIZE: usize = 400;
+
+/// Latest PNG per account. Dropping a `NamedTempFile` deletes it.
+static SAVED: LazyLock<Mutex<HashMap<String, NamedTempFile>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QrFormat {
+    #[default]
+    Terminal,
+    Png,
+    Raw,
+}
+
+impl FromStr for QrFormat {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "terminal" => Ok(Self::Terminal),
+            "png" => Ok(Self::Png),
+            "raw" => Ok(Self::Raw),
+            other => bail!("unknown QR format `{other}` (expected terminal, png or raw)"),
+        }
+    }
+}
+
+/// Shows `qr` in `format`, falling back terminal → PNG → raw when a
+/// step fails, so there's always something to scan or copy.
+pub fn show(account_id: &str, qr: &str, format: QrFormat) {
+    if format == QrFormat::Terminal {
+        match qr2term::print_qr(qr) {
+            Ok(()) => return,
+            Err(e) => eprintln!("Failed to print QR code: {}", e),
+        }
+    }
+    if format != QrFormat::Raw {
+        match save_png(account_id, qr) {
+            Ok(path) => {
+                println!("QR code saved to {}", path.display());
+                return;
+            }
+            Err(e) => eprintln!("Failed to save QR code image: {}", e),
+        }
+    }
+    println!("Raw QR data: {}", qr);
+}
+
+fn save_png(account_id: &str, qr: &str) -> Result<PathBuf> {
+    let Some(raster) = tina_core::render_qr(qr, PNG_SIZE) else {
+        bail!("QR data too long");
+    };
+    // Nome aleatório criado com O_EXCL e modo 0600: ninguém planta um
+    // symlink no caminho nem lê o QR (que vincula um aparelho à conta).
+    let mut file = tempfile::Builder::new()
+        .prefix("tina-qr-")
+        .suffix(".png")
+        .tempfile()?;
+    write_png(file.as_file_mut(), &raster)?;
+    let path = file.path().to_path_buf();
+    // Um arquivo por conta: guardar o novo apaga o vencido.
+    if let Ok(mut saved) = SAVED.lock() {
+        saved.insert(account_id.to_string(), file);
+    }
+    Ok(path)
+}
+
+/// Deletes the PNG saved for `account_id`, once the code is useless
+/// (expired, or the account paired).
+pub fn discard(account_id: &str) {
+    if let Ok(mut saved) = SAVED.lock() {
+        saved.remove(account_id);
+    }
+}
+
+/// 8-bit grayscale.
+fn write_png(out: impl Write, raster: &QrRaster) -> Result<()> {
+    let size = u32::try_from(raster.size)?;
+    let mut encoder = png::Encoder::new(out, size, size);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&raster.pixels)?;
+    writer.finish()?;
+    Ok(())
+}
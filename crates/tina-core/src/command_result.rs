@@ -0,0 +1,213 @@
+// Typed `data` of a `CommandResult`. nanachi fills it only for a few
+// commands, each with its own shape (nanachi/main.go), so it can only be
+// read knowing which command the reply answers:
+//
+// | command                                         | `data` on success                          |
+// |-------------------------------------------------|--------------------------------------------|
+// | `GetContacts`, `GetGroups`                      | `{"count": n}`                             |
+// | `ForwardMessage`                                | `{"results": [{to, message_id, …}]}`       |
+// | `GetGroupInviteLink`, `RevokeGroupInviteLink`   | `{"link": "…"}`                            |
+// | `JoinGroupByLink`                               | `{"group": GroupData}`                     |
+// | anything else (`GetQrCode`, `StartAccount`, …)  | nothing                                    |
+//
+// A refusal with a known cause carries `{"reason": code}` instead (plus
+// `group_jid` for `already_member`).
+
+use serde::Deserialize;
+
+use crate::events::{GroupData, IpcCommand};
+
+#[derive(Debug, Clone)]
+pub enum CommandResultData {
+    /// Nothing beyond `success` — the command only acknowledges, or
+    /// nanachi left `data` out.
+    Ack,
+    /// `GetContacts` / `GetGroups`: rows that came back (as upserts,
+    /// before the reply).
+    Count(u64),
+    /// `ForwardMessage`: one entry per recipient.
+    Forwarded(Vec<ForwardResult>),
+    /// `GetGroupInviteLink` / `RevokeGroupInviteLink`.
+    InviteLink(String),
+    /// `JoinGroupByLink`: metadata of the group just joined.
+    JoinedGroup(Box<GroupData>),
+    /// A failed command whose cause nanachi knows.
+    Refused(CommandRefusal),
+}
+
+/// How forwarding went for one recipient of a `ForwardMessage`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ForwardResult {
+    pub to: String,
+    #[serde(default)]
+    pub message_id: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// `data` of a failed command: a stable code (`not_admin`,
+/// `already_member`, …) and whatever detail goes with it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CommandRefusal {
+    pub reason: String,
+    #[serde(default)]
+    pub group_jid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CountData {
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct ForwardData {
+    results: Vec<ForwardResult>,
+}
+
+#[derive(Deserialize)]
+struct LinkData {
+    link: String,
+}
+
+#[derive(Deserialize)]
+struct GroupReply {
+    group: GroupData,
+}
+
+impl CommandResultData {
+    /// Reads `data` as the reply to `command`. Fails only when the
+    /// payload is there but doesn't have the shape that command answers
+    /// with; a missing one is `Ack`.
+    pub fn parse(
+        command: &IpcCommand,
+        success: bool,
+        data: Option<serde_json::Value>,
+    ) -> Result<Self, serde_json::Error> {
+        let Some(data) = data.filter(|d| !d.is_null()) else {
+            return Ok(Self::Ack);
+        };
+        if !success {
+            // Erro genérico não traz `reason`: fica só a string `error`.
+            return Ok(serde_json::from_value(data).map_or(Self::Ack, Self::Refused));
+        }
+        Ok(match command {
+            IpcCommand::GetContacts { .. } | IpcCommand::GetGroups { .. } => {
+                Self::Count(serde_json::from_value::<CountData>(data)?.count)
+            }
+            IpcCommand::ForwardMessage { .. } => {
+                Self::Forwarded(serde_json::from_value::<ForwardData>(data)?.results)
+            }
+            IpcCommand::GetGroupInviteLink { .. } | IpcCommand::RevokeGroupInviteLink { .. } => {
+                Self::InviteLink(serde_json::from_value::<LinkData>(data)?.link)
+            }
+            IpcCommand::JoinGroupByLink { .. } => {
+                Self::JoinedGroup(Box::new(serde_json::from_value::<GroupReply>(data)?.group))
+            }
+            _ => Self::Ack,
+        })
+    }
+
+    /// Refusal code, when the command failed for a known reason.
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Self::Refused(r) => Some(&r.reason),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WaIdentity;
+    use crate::events::IpcEvent;
+    use crate::protocol::{IpcMessage, IpcMessageContent};
+
+    fn result_of(line: &str) -> (bool, Option<serde_json::Value>) {
+        let message = IpcMessage::from_line(line).unwrap();
+        let IpcMessageContent::Event(IpcEvent::CommandResult { success, data, .. }) =
+            message.content
+        else {
+            panic!("not a CommandResult: {line}");
+        };
+        (success, data)
+    }
+
+    #[test]
+    fn get_qr_code_result_is_a_bare_ack() {
+        let command = IpcCommand::GetQrCode {
+            account_id: "acc1".into(),
+        };
+        let line = r#"{"id":"9","type":"CommandResult","payload":{"command_id":"c1","success":true,"data":null,"error":null}}"#;
+        let (success, data) = result_of(line);
+        let parsed = CommandResultData::parse(&command, success, data).unwrap();
+        assert!(matches!(parsed, CommandResultData::Ack));
+
+        // Falha sem `reason` (conta não iniciada) também não tem dado.
+        let line = r#"{"id":"9","type":"CommandResult","payload":{"command_id":"c1","success":false,"data":null,"error":"account not started"}}"#;
+        let (success, data) = result_of(line);
+        let parsed = CommandResultData::parse(&command, success, data).unwrap();
+        assert!(matches!(parsed, CommandResultData::Ack));
+    }
+
+    #[test]
+    fn data_is_read_by_the_command_it_answers() {
+        let group = || WaIdentity::parse("120363000000000000@g.us");
+        let link = CommandResultData::parse(
+            &IpcCommand::GetGroupInviteLink {
+                account_id: "acc1".into(),
+                group_jid: group(),
+            },
+            true,
+            Some(serde_json::json!({"link": "https://chat.whatsapp.com/abc"})),
+        )
+        .unwrap();
+        assert!(matches!(link, CommandResultData::InviteLink(l) if l.ends_with("/abc")));
+
+        let count = CommandResultData::parse(
+            &IpcCommand::GetContacts {
+                account_id: "acc1".into(),
+            },
+            true,
+            Some(serde_json::json!({"count": 42})),
+        )
+        .unwrap();
+        assert!(matches!(count, CommandResultData::Count(42)));
+
+        // O mesmo `{"count"}` não quer dizer nada pra outro comando.
+        let other = CommandResultData::parse(
+            &IpcCommand::GetQrCode {
+                account_id: "acc1".into(),
+            },
+            true,
+            Some(serde_json::json!({"count": 42})),
+        )
+        .unwrap();
+        assert!(matches!(other, CommandResultData::Ack));
+    }
+
+    #[test]
+    fn refusal_and_malformed_data() {
+        let join = IpcCommand::JoinGroupByLink {
+            account_id: "acc1".into(),
+            link: "https://chat.whatsapp.com/abc".into(),
+        };
+        let refused = CommandResultData::parse(
+            &join,
+            false,
+            Some(serde_json::json!({"reason": "already_member", "group_jid": "1@g.us"})),
+        )
+        .unwrap();
+        assert_eq!(refused.reason(), Some("already_member"));
+        assert!(matches!(
+            refused,
+            CommandResultData::Refused(CommandRefusal { group_jid: Some(ref j), .. }) if j == "1@g.us"
+        ));
+
+        assert!(
+            CommandResultData::parse(&join, true, Some(serde_json::json!({"link": "x"}))).is_err()
+        );
+    }
+}
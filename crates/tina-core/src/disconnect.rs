@@ -0,0 +1,135 @@
+// Why an account dropped off. nanachi reports `Disconnected` with a
+// free-form reason string (it's the wire format and stays that way);
+// the worker folds it into `DisconnectReason` so frontends can decide
+// between waiting for whatsmeow's auto-reconnect, starting the account
+// again, or sending the user back to the QR login.
+//
+// Reason strings nanachi emits (nanachi/event_handlers.go, manager.go):
+//
+// | string                        | source                         | variant        |
+// |-------------------------------|--------------------------------|----------------|
+// | `transport disconnected`      | `events.Disconnected`          | `NetworkError` |
+// | `connect failure 5xx`         | `events.ConnectFailure` (5xx)  | `NetworkError` |
+// | `stream replaced`             | `events.StreamReplaced`        | `Replaced`     |
+// | `temporary ban: …`            | `events.TemporaryBan`          | `Banned`       |
+// | `Stopped by user`, `Shutdown` | `StopAccount`, nanachi exit    | `Stopped`      |
+// | `Stopped while idle`          | worker's idle policy           | `Idle`         |
+// | `Network changed`             | worker's network-change restart| `Stopped`      |
+// | anything else                 |                                | `Unknown`      |
+//
+// An unlink normally arrives as its own `LoggedOut` IPC event; the
+// literal `logged out` is still mapped so a reason string carrying it
+// isn't lost in `Unknown`.
+
+use std::fmt;
+
+/// `reason` of the `StopAccount` the worker's idle policy sends; nanachi
+/// echoes it back in `Disconnected`.
+pub const IDLE_STOP_REASON: &str = "Stopped while idle";
+
+/// `reason` of the `StopAccount` the worker sends right before starting
+/// an account again after a network change.
+pub const NETWORK_CHANGE_REASON: &str = "Network changed";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// Socket dropped or the server was unavailable. whatsmeow
+    /// reconnects on its own.
+    NetworkError,
+    /// The session was unlinked; a new QR pairing is needed.
+    LoggedOut,
+    /// Another client opened the same session and took over.
+    Replaced,
+    /// Temporary ban from WhatsApp.
+    Banned,
+    /// We asked for it (`stop_account`, shutdown).
+    Stopped,
+    /// Stopped by the idle policy after no use for a while; the next
+    /// use starts it again.
+    Idle,
+    Unknown(String),
+}
+
+impl DisconnectReason {
+    pub fn parse(reason: &str) -> Self {
+        let lower = reason.trim().to_ascii_lowercase();
+        match lower.as_str() {
+            "transport disconnected" => Self::NetworkError,
+            "stream replaced" => Self::Replaced,
+            "logged out" => Self::LoggedOut,
+            "stopped by user" | "shutdown" | "network changed" => Self::Stopped,
+            "stopped while idle" => Self::Idle,
+            s if s.starts_with("temporary ban") => Self::Banned,
+            s if s
+                .strip_prefix("connect failure ")
+                .is_some_and(|code| code.starts_with('5')) =>
+            {
+                Self::NetworkError
+            }
+            _ => Self::Unknown(reason.to_string()),
+        }
+    }
+
+    /// whatsmeow keeps retrying by itself; the frontend should show
+    /// "connecting" and wait.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::NetworkError)
+    }
+
+    /// Only a fresh pairing brings the account back.
+    pub fn needs_login(&self) -> bool {
+        matches!(self, Self::LoggedOut)
+    }
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NetworkError => f.write_str("network error"),
+            Self::LoggedOut => f.write_str("logged out"),
+            Self::Replaced => f.write_str("session opened elsewhere"),
+            Self::Banned => f.write_str("temporarily banned"),
+            Self::Stopped => f.write_str("stopped"),
+            Self::Idle => f.write_str("stopped while idle"),
+            Self::Unknown(reason) => f.write_str(reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_reason_strings() {
+        let table = [
+            ("transport disconnected", DisconnectReason::NetworkError),
+            ("connect failure 503", DisconnectReason::NetworkError),
+            ("stream replaced", DisconnectReason::Replaced),
+            ("logged out", DisconnectReason::LoggedOut),
+            (
+                "temporary ban: 101 (expires in 23h59m)",
+                DisconnectReason::Banned,
+            ),
+            ("Stopped by user", DisconnectReason::Stopped),
+            ("Shutdown", DisconnectReason::Stopped),
+            (IDLE_STOP_REASON, DisconnectReason::Idle),
+            (NETWORK_CHANGE_REASON, DisconnectReason::Stopped),
+        ];
+        for (raw, expected) in table {
+            assert_eq!(DisconnectReason::parse(raw), expected, "{raw}");
+        }
+    }
+
+    #[test]
+    fn unknown_keeps_original_string() {
+        assert_eq!(
+            DisconnectReason::parse("connect failure 405"),
+            DisconnectReason::Unknown("connect failure 405".into())
+        );
+        let r = DisconnectReason::parse("Something Odd");
+        assert_eq!(r, DisconnectReason::Unknown("Something Odd".into()));
+        assert_eq!(r.to_string(), "Something Odd");
+        assert!(!r.is_transient() && !r.needs_login());
+    }
+}
@@ -60,8 +60,17 @@ impl MediaKind {
 #[serde(tag = "type", content = "payload")]
 pub enum IpcCommand {
     StartAccount { account_id: String },
-    StopAccount { account_id: String },
+    /// `reason` ends up in nanachi's `Disconnected`; `None` reads as a
+    /// stop by the user.
+    StopAccount {
+        account_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
     Logout { account_id: String },
+    /// Restart QR pairing for an account that hasn't paired yet, so a
+    /// fresh set of codes starts streaming after the last one expired.
+    GetQrCode { account_id: String },
     SendMessage {
         account_id: String,
         to: WaIdentity,
@@ -109,6 +118,17 @@ pub enum IpcCommand {
     /// de upsert. Usado pra reconstruir a tabela do tina a partir do que o
     /// whatsmeow.db já sabe — sem precisar de re-pareamento.
     Reconcile { account_id: String },
+    /// Stop streaming the history sync for the rest of this session;
+    /// later HistorySync blobs are dropped by nanachi. Messages already
+    /// sent stay.
+    CancelSync { account_id: String },
+    /// Re-busca os contatos (app state do servidor + store) sob demanda.
+    /// Os contatos vêm como `ContactsUpsert` em lotes antes do
+    /// `CommandResult`, que só traz `{"count": n}`.
+    GetContacts { account_id: String },
+    /// Como `GetContacts`, para os grupos (`GroupsUpsert`), direto do
+    /// servidor.
+    GetGroups { account_id: String },
     /// Pede ao nanachi pra baixar+decryptar a mídia de uma mensagem.
     /// O nanachi prefere sua cache in-memory (populada quando a mensagem
     /// chegou nesta sessão); se ela não tiver o proto, faz fallback no
@@ -134,6 +154,20 @@ pub enum IpcCommand {
         jid: WaIdentity,
         url: String,
     },
+    /// Nome, recado e URL da foto de um contato, direto do servidor.
+    /// nanachi emite `ProfileResult` e responde com `CommandResult`;
+    /// o que a privacidade do contato esconde vem `None`.
+    GetProfile {
+        account_id: String,
+        jid: WaIdentity,
+    },
+    /// Whether `phone` (digits only, country code first) is on
+    /// WhatsApp. nanachi emite `NumberCheckResult` e responde com
+    /// `CommandResult`.
+    CheckNumber {
+        account_id: String,
+        phone: String,
+    },
     /// Re-fetch metadata for a single chat (newsletter / group). The
     /// nanachi handler dispatches based on the JID server: routes
     /// `*@newsletter` to `GetNewsletterInfo`, `*@g.us` to
@@ -153,6 +187,82 @@ pub enum IpcCommand {
         sender_jid: WaIdentity,
         message_ids: Vec<String>,
     },
+    /// `MarkRead` for many chats in one line ("mark all as read").
+    /// nanachi sends one receipt per entry; like `MarkRead` the result
+    /// comes back before the receipts go out.
+    MarkChatsRead {
+        account_id: String,
+        chats: Vec<ReadReceiptItem>,
+    },
+    /// Pin (`pin: true`) or unpin a message for everyone in the chat.
+    /// `sender_jid` is `None` for our own messages; the Go side needs
+    /// it to build the message key for someone else's.
+    PinMessage {
+        account_id: String,
+        chat_jid: WaIdentity,
+        message_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sender_jid: Option<WaIdentity>,
+        pin: bool,
+    },
+    /// Star / unstar a message. Synced through app state, so the
+    /// phone and other linked devices see the same favorites.
+    StarMessage {
+        account_id: String,
+        chat_jid: WaIdentity,
+        message_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sender_jid: Option<WaIdentity>,
+        from_me: bool,
+        star: bool,
+    },
+    /// Forward one stored message to every chat in `to`. The Go side
+    /// rebuilds the proto from its download cache, else from
+    /// `raw_json` (media / structured kinds), else sends `content` as
+    /// text, and marks each copy as forwarded. Replies with a
+    /// `CommandResult` whose `data` is
+    /// `{"results": [{"to", "message_id", "timestamp", "error"}]}`,
+    /// one entry per recipient.
+    ForwardMessage {
+        account_id: String,
+        from_chat: WaIdentity,
+        message_id: String,
+        to: Vec<WaIdentity>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        raw_json: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+    },
+    /// Publish our composing state to a chat (`composing` when
+    /// `is_typing`, `paused` otherwise). Stateless on the Go side —
+    /// the caller owns debounce / rate limiting.
+    SetTyping {
+        account_id: String,
+        to: WaIdentity,
+        is_typing: bool,
+    },
+    /// Current invite link of a group we're in. Needs admin rights;
+    /// on success nanachi emits `GroupInviteLink` and replies with
+    /// `data = {"link"}`. A refusal carries `data = {"reason"}`
+    /// (`not_admin`, `not_in_group`).
+    GetGroupInviteLink {
+        account_id: String,
+        group_jid: WaIdentity,
+    },
+    /// Invalidate the group's invite link and issue a new one. Same
+    /// replies as `GetGroupInviteLink`, with the new link.
+    RevokeGroupInviteLink {
+        account_id: String,
+        group_jid: WaIdentity,
+    },
+    /// Join a group through its `chat.whatsapp.com/<code>` link. On
+    /// success replies with `data = {"group": GroupData}`; a refusal
+    /// carries `data = {"reason"}` (`already_member`, `invalid_link`,
+    /// `link_revoked`); `already_member` adds the `group_jid`.
+    JoinGroupByLink {
+        account_id: String,
+        link: String,
+    },
     Shutdown,
 }
 
@@ -160,7 +270,14 @@ pub enum IpcCommand {
 #[serde(tag = "type", content = "payload")]
 pub enum IpcEvent {
     Ready { account_id: String },
-    QrCode { account_id: String, qr: String },
+    QrCode {
+        account_id: String,
+        qr: String,
+        /// How long this code stays scannable (whatsmeow: ~60s for
+        /// the first, ~20s after). Absent from older nanachi builds.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
     PairingCode { account_id: String, code: String },
     Connected {
         account_id: String,
@@ -174,7 +291,18 @@ pub enum IpcEvent {
 
     ContactsUpsert { account_id: String, contacts: Vec<ContactData> },
     GroupsUpsert { account_id: String, groups: Vec<GroupData> },
-    MessagesUpsert { account_id: String, messages: Vec<MessageData> },
+    /// History sync streams each HistorySync blob as bounded chunks:
+    /// `chunk_index` counts from 0 and the final chunk (possibly empty)
+    /// has `is_last = true`. Realtime batches and older nanachi builds
+    /// omit both, which reads as a single final chunk.
+    MessagesUpsert {
+        account_id: String,
+        messages: Vec<MessageData>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chunk_index: Option<u32>,
+        #[serde(default = "default_true")]
+        is_last: bool,
+    },
 
     HistorySyncComplete { account_id: String, messages_count: usize },
 
@@ -237,6 +365,16 @@ pub enum IpcEvent {
         status: String,
     },
 
+    /// The sender edited `message_id`. `edited_at` is unix secs from
+    /// the edit's own timestamp, so out-of-order edits can be dropped.
+    MessageEdited {
+        account_id: String,
+        chat_jid: String,
+        message_id: String,
+        new_content: String,
+        edited_at: i64,
+    },
+
     /// Progresso de download de mídia. `total = 0` ⇒ desconhecido.
     MediaDownloadProgress {
         account_id: String,
@@ -271,6 +409,37 @@ pub enum IpcEvent {
         error: String,
     },
 
+    /// A group's invite link (`https://chat.whatsapp.com/<code>`),
+    /// fetched or freshly reset.
+    GroupInviteLink {
+        account_id: String,
+        group_jid: WaIdentity,
+        link: String,
+    },
+
+    /// Answer to `GetProfile`. A field the contact's privacy settings
+    /// hide (or that isn't set) is `None`.
+    ProfileResult {
+        account_id: String,
+        jid: WaIdentity,
+        #[serde(default)]
+        img_url: Option<String>,
+        #[serde(default)]
+        status: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    /// Answer to `CheckNumber`. `jid` is the number's canonical JID
+    /// when it's registered.
+    NumberCheckResult {
+        account_id: String,
+        phone: String,
+        exists: bool,
+        #[serde(default)]
+        jid: Option<WaIdentity>,
+    },
+
     CommandResult { command_id: String, success: bool, data: Option<serde_json::Value>, error: Option<String> },
 }
 
@@ -280,6 +449,15 @@ pub struct ChatPinItem {
     pub pinned: bool,
 }
 
+/// One entry of `IpcCommand::MarkChatsRead`: the same fields as a
+/// single `MarkRead`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadReceiptItem {
+    pub chat_jid: WaIdentity,
+    pub sender_jid: WaIdentity,
+    pub message_ids: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatReadHintItem {
     pub chat_jid: WaIdentity,
@@ -289,21 +467,32 @@ pub struct ChatReadHintItem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContactData {
     pub jid: WaIdentity,
+    #[serde(default)]
     pub lid: Option<WaIdentity>,
+    #[serde(default)]
     pub phone_number: Option<String>,
+    #[serde(default)]
     pub name: Option<String>,
+    #[serde(default)]
     pub notify: Option<String>,
+    #[serde(default)]
     pub verified_name: Option<String>,
+    #[serde(default)]
     pub img_url: Option<String>,
+    #[serde(default)]
     pub status: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupData {
     pub jid: WaIdentity,
+    #[serde(default)]
     pub subject: Option<String>,
+    #[serde(default)]
     pub owner: Option<WaIdentity>,
+    #[serde(default)]
     pub description: Option<String>,
+    #[serde(default)]
     pub avatar_url: Option<String>,
     #[serde(default)]
     pub participants: Vec<ParticipantData>,
@@ -312,7 +501,9 @@ pub struct GroupData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParticipantData {
     pub id: WaIdentity,
+    #[serde(default)]
     pub admin: Option<String>,
+    #[serde(default)]
     pub phone_number: Option<String>,
 }
 
@@ -321,10 +512,12 @@ pub struct MessageData {
     pub message_id: String,
     pub chat_jid: WaIdentity,
     pub sender_jid: WaIdentity,
+    #[serde(default)]
     pub content: Option<String>,
     pub message_type: String,
     pub timestamp: i64,
     pub is_from_me: bool,
+    #[serde(default)]
     pub raw_json: Option<String>,
     /// Inline preview bytes (JPEG / PNG) para image/video/sticker/document.
     /// Go envia como base64 (`[]byte` no JSON nativo do Go) e nós
@@ -377,3 +570,66 @@ pub struct MessageData {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub mentioned_jids: Vec<WaIdentity>,
 }
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_upsert_without_chunk_fields_is_a_final_chunk() {
+        let line = r#"{"type":"MessagesUpsert","payload":{"account_id":"acc1","messages":[]}}"#;
+        let IpcEvent::MessagesUpsert {
+            chunk_index,
+            is_last,
+            ..
+        } = serde_json::from_str(line).unwrap()
+        else {
+            panic!("wrong variant");
+        };
+        assert_eq!(chunk_index, None);
+        assert!(is_last);
+
+        let line = r#"{"type":"MessagesUpsert","payload":{"account_id":"acc1","messages":[],"chunk_index":3,"is_last":false}}"#;
+        let IpcEvent::MessagesUpsert {
+            chunk_index,
+            is_last,
+            ..
+        } = serde_json::from_str(line).unwrap()
+        else {
+            panic!("wrong variant");
+        };
+        assert_eq!(chunk_index, Some(3));
+        assert!(!is_last);
+    }
+
+    /// A newer nanachi may add fields and drop optional ones; neither
+    /// should cost us the event.
+    #[test]
+    fn payloads_from_a_newer_nanachi_still_deserialize() {
+        let contact: ContactData = serde_json::from_str(
+            r#"{"jid":"5511999990000@s.whatsapp.net","name":"Maria","about_set_at":1700000000}"#,
+        )
+        .unwrap();
+        assert_eq!(contact.name.as_deref(), Some("Maria"));
+        assert!(contact.lid.is_none() && contact.status.is_none());
+
+        let group: GroupData = serde_json::from_str(
+            r#"{"jid":"120363000000000001@g.us","is_community":true,
+                "participants":[{"id":"1@s.whatsapp.net","admin":"admin","lid":"9@lid"}]}"#,
+        )
+        .unwrap();
+        assert!(group.subject.is_none());
+        assert_eq!(group.participants[0].admin.as_deref(), Some("admin"));
+
+        let message: MessageData = serde_json::from_str(
+            r#"{"message_id":"M1","chat_jid":"1@s.whatsapp.net","sender_jid":"1@s.whatsapp.net",
+                "message_type":"text","timestamp":1,"is_from_me":false,"ephemeral_expiry":86400}"#,
+        )
+        .unwrap();
+        assert!(message.content.is_none() && message.raw_json.is_none());
+    }
+}
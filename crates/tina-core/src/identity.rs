@@ -141,8 +141,8 @@ impl WaIdentity {
     /// phonenumber crate); LIDs / channels render with a short hash
     /// suffix so the user never sees the full opaque ID.
     ///
-    /// `tina-gtk` augments this for phones via its `format` module —
-    /// see `tina-gtk::format::format_jid_or_phone`.
+    /// With the `phone` feature, `format_jid_or_phone` formats the
+    /// phones per country; `tina-gtk` routes them through it.
     pub fn display_short(&self) -> String {
         match self {
             WaIdentity::Phone(_) | WaIdentity::Hosted(_) => self.user().to_string(),
@@ -156,6 +156,44 @@ impl WaIdentity {
     }
 }
 
+/// Normalise what a user typed as a recipient into a JID. Full JIDs
+/// on a known server pass through untouched; phone numbers lose their
+/// formatting (`+`, spaces, dashes, dots, parentheses) and get
+/// `@s.whatsapp.net` appended. Anything else — letters, too few or
+/// too many digits for E.164, unknown servers — is `None`.
+pub fn normalize_recipient(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.contains('@') {
+        let id = WaIdentity::parse(input);
+        let valid = id.is_known() && (id == WaIdentity::Status || !id.user().is_empty());
+        return valid.then(|| input.to_string());
+    }
+    let digits: String = input
+        .chars()
+        .filter(|c| !matches!(c, '+' | ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+    let valid = (7..=15).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit());
+    valid.then(|| format!("{digits}@s.whatsapp.net"))
+}
+
+/// Host every group invite link lives under.
+pub const GROUP_INVITE_HOST: &str = "chat.whatsapp.com";
+
+/// The invite code in a `chat.whatsapp.com/<code>` link, with or
+/// without scheme; query strings (`?mode=…`) and a trailing slash are
+/// ignored. `None` for anything that isn't a group invite link.
+pub fn group_invite_code(link: &str) -> Option<&str> {
+    let link = link.trim();
+    let rest = link
+        .strip_prefix("https://")
+        .or_else(|| link.strip_prefix("http://"))
+        .unwrap_or(link);
+    let path = rest.strip_prefix(GROUP_INVITE_HOST)?.strip_prefix('/')?;
+    let code = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let valid = (1..=64).contains(&code.len()) && code.bytes().all(|b| b.is_ascii_alphanumeric());
+    valid.then_some(code)
+}
+
 impl fmt::Display for WaIdentity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.raw())
@@ -285,9 +323,55 @@ mod tests {
             WaIdentity::parse("status@broadcast"),
             WaIdentity::Status
         ));
+        assert!(matches!(
+            WaIdentity::parse("1700000000@broadcast"),
+            WaIdentity::Broadcast(_)
+        ));
+        assert_eq!(WaIdentity::parse("status@broadcast").kind(), "status");
+        assert_eq!(
+            WaIdentity::parse("1700000000@broadcast").kind(),
+            "broadcast"
+        );
         assert!(matches!(WaIdentity::parse("garbage"), WaIdentity::Unknown(_)));
     }
 
+    #[test]
+    fn normalizes_formatted_numbers() {
+        let want = Some("5511999999999@s.whatsapp.net".to_string());
+        assert_eq!(normalize_recipient("+55 11 99999-9999"), want);
+        assert_eq!(normalize_recipient("(55) 11.99999.9999"), want);
+        assert_eq!(normalize_recipient("  5511999999999 "), want);
+    }
+
+    #[test]
+    fn leaves_full_jids_untouched() {
+        for jid in [
+            "5511999999999@s.whatsapp.net",
+            "220280752451716@lid",
+            "120363400000000001@g.us",
+            "123-456@g.us",
+            "120363194378500802@newsletter",
+            "status@broadcast",
+        ] {
+            assert_eq!(normalize_recipient(jid).as_deref(), Some(jid));
+        }
+    }
+
+    #[test]
+    fn rejects_garbage_recipients() {
+        for bad in [
+            "",
+            "hello",
+            "55 11 abc",
+            "123",
+            "1234567890123456",
+            "@s.whatsapp.net",
+            "55@example.com",
+        ] {
+            assert_eq!(normalize_recipient(bad), None, "{bad:?}");
+        }
+    }
+
     #[test]
     fn raw_round_trips() {
         for raw in [
@@ -347,4 +431,26 @@ mod tests {
         let c = WaContact::from_jid(same.clone()).with_alt(same);
         assert!(c.alt.is_none());
     }
+
+    #[test]
+    fn invite_code_from_link() {
+        let code = "F4kEc0De1234567890AbCd";
+        for link in [
+            "https://chat.whatsapp.com/F4kEc0De1234567890AbCd",
+            " chat.whatsapp.com/F4kEc0De1234567890AbCd/ ",
+            "http://chat.whatsapp.com/F4kEc0De1234567890AbCd?mode=ac_t",
+        ] {
+            assert_eq!(group_invite_code(link), Some(code), "{link}");
+        }
+        for link in [
+            "",
+            "https://chat.whatsapp.com/",
+            "https://chat.whatsapp.com.evil.example/F4kE",
+            "https://wa.me/5561999999999",
+            "https://chat.whatsapp.com/abc/def",
+            "F4kEc0De1234567890AbCd",
+        ] {
+            assert_eq!(group_invite_code(link), None, "{link}");
+        }
+    }
 }
@@ -1,7 +1,30 @@
+mod command_result;
+mod disconnect;
 mod events;
 mod identity;
+mod messages;
+#[cfg(feature = "phone")]
+mod phone;
 mod protocol;
+#[cfg(feature = "qr")]
+mod qr;
+#[cfg(feature = "chrono")]
+mod time;
 
+pub use command_result::{CommandRefusal, CommandResultData, ForwardResult};
+pub use disconnect::{DisconnectReason, IDLE_STOP_REASON, NETWORK_CHANGE_REASON};
 pub use events::*;
-pub use identity::{WaContact, WaIdentity};
+pub use identity::{
+    GROUP_INVITE_HOST, WaContact, WaIdentity, group_invite_code, normalize_recipient,
+};
+pub use messages::{
+    DEFAULT_MAX_CONTENT_CHARS, MessageContent, MessageSender, MessageType, TRUNCATED_MARKER,
+    is_admin_role, parse_db_message, parse_db_sender, sanitize_content,
+};
+#[cfg(feature = "phone")]
+pub use phone::format_jid_or_phone;
 pub use protocol::*;
+#[cfg(feature = "qr")]
+pub use qr::{QR_MARGIN, QrRaster, render_qr};
+#[cfg(feature = "chrono")]
+pub use time::{datetime_local, datetime_utc, format_relative_timestamp, unix_now};
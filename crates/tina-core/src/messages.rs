@@ -0,0 +1,571 @@
+// Structured view of a stored message. nanachi flattens every message
+// into `content` + `message_type` for the chat list; for the kinds that
+// carry more than a line of text (location, contact card, poll) it also
+// persists the relevant proto subset in `raw_json`. `parse_db_message`
+// turns that back into something a view can render as a map pin or a
+// poll instead of the "[Location]" placeholder.
+//
+// `MessageType` is the typed form of that `message_type` column. The
+// column stays text; code converts with `MessageType::from_raw` where
+// the string comes in, instead of matching raw strings everywhere.
+//
+// `sanitize_content` is what goes into the `content` column: no control
+// characters and a length cap. The full text stays in `raw_json`.
+
+use serde_json::Value;
+
+use crate::WaIdentity;
+
+/// Kind of a stored message. `from_raw` also accepts the whatsmeow /
+/// baileys proto names (`imageMessage`, `extendedTextMessage`, …) and
+/// ignores case, so rows written by older builds read the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    Text,
+    Image,
+    Video,
+    Audio,
+    Document,
+    Sticker,
+    Contact,
+    Location,
+    Reaction,
+    Poll,
+    Unknown,
+}
+
+impl MessageType {
+    /// Every kind, in `as_str` order — the choices a type filter offers.
+    pub const ALL: [Self; 11] = [
+        Self::Text,
+        Self::Image,
+        Self::Video,
+        Self::Audio,
+        Self::Document,
+        Self::Sticker,
+        Self::Contact,
+        Self::Location,
+        Self::Reaction,
+        Self::Poll,
+        Self::Unknown,
+    ];
+
+    /// Strict inverse of `as_str` (case-insensitive), for validating
+    /// user input. Unlike `from_raw`, an unknown name is `None` rather
+    /// than `Unknown`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn from_raw(raw: &str) -> Self {
+        // `pollCreationMessageV3` tem o sufixo no meio.
+        let base = raw.trim().to_ascii_lowercase().replacen("message", "", 1);
+        match base.as_str() {
+            "text" | "conversation" | "extendedtext" | "chat" => Self::Text,
+            "image" => Self::Image,
+            "video" | "ptv" => Self::Video,
+            "audio" | "ptt" | "voice" => Self::Audio,
+            "document" | "documentwithcaption" => Self::Document,
+            "sticker" | "lottiesticker" => Self::Sticker,
+            "contact" | "contactsarray" => Self::Contact,
+            "location" | "livelocation" => Self::Location,
+            "reaction" => Self::Reaction,
+            "poll" | "pollcreation" | "pollcreationv2" | "pollcreationv3" => Self::Poll,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Canonical value for the `message_type` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Image => "image",
+            Self::Video => "video",
+            Self::Audio => "audio",
+            Self::Document => "document",
+            Self::Sticker => "sticker",
+            Self::Contact => "contact",
+            Self::Location => "location",
+            Self::Reaction => "reaction",
+            Self::Poll => "poll",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Carries a downloadable attachment.
+    pub fn is_media(self) -> bool {
+        matches!(
+            self,
+            Self::Image | Self::Video | Self::Audio | Self::Document | Self::Sticker
+        )
+    }
+
+    /// Media shown inline as a picture rather than a file row.
+    pub fn is_visual(self) -> bool {
+        matches!(self, Self::Image | Self::Video | Self::Sticker)
+    }
+
+    /// Emoji put in front of a chat-list preview; `None` for kinds
+    /// whose preview is just their text.
+    pub fn to_preview_icon(self) -> Option<&'static str> {
+        match self {
+            Self::Image => Some("📷"),
+            Self::Video => Some("🎬"),
+            Self::Audio => Some("🎤"),
+            Self::Document => Some("📄"),
+            Self::Sticker => Some("🎴"),
+            Self::Contact => Some("👤"),
+            Self::Location => Some("📍"),
+            Self::Poll => Some("📊"),
+            Self::Text | Self::Reaction | Self::Unknown => None,
+        }
+    }
+
+    /// Text nanachi stores as `content` when a message has none of its
+    /// own (no caption), e.g. `[Image]`.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            Self::Image => "[Image]",
+            Self::Video => "[Video]",
+            Self::Audio => "[Audio]",
+            Self::Document => "[Document]",
+            Self::Sticker => "[Sticker]",
+            Self::Contact => "[Contact]",
+            Self::Location => "[Location]",
+            _ => "[Media]",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageContent {
+    /// Plain text, captions and every kind without a richer variant.
+    Text(String),
+    Location {
+        lat: f64,
+        lng: f64,
+        name: Option<String>,
+        address: Option<String>,
+        /// Live-location share (the coordinates are the last update).
+        live: bool,
+    },
+    Contact {
+        name: String,
+        vcard: Option<String>,
+    },
+    Poll {
+        question: String,
+        options: Vec<String>,
+        /// 0 = any number of options may be picked.
+        selectable_count: u32,
+    },
+}
+
+impl MessageContent {
+    pub fn text(content: Option<&str>) -> Self {
+        Self::Text(content.unwrap_or_default().to_string())
+    }
+}
+
+/// Appended to `content` cut by `sanitize_content`.
+pub const TRUNCATED_MARKER: &str = "…(truncated)";
+
+/// Default cap of `sanitize_content`, in characters. Far above any
+/// real chat message; what gets cut is pasted logs and the like.
+pub const DEFAULT_MAX_CONTENT_CHARS: usize = 64 * 1024;
+
+/// Message text as stored: control characters other than newline and
+/// tab removed (a NUL cuts the string short in GTK labels), and at most
+/// `max_chars` characters kept, followed by [`TRUNCATED_MARKER`] when
+/// anything was cut.
+pub fn sanitize_content(content: &str, max_chars: usize) -> String {
+    let mut out = String::with_capacity(content.len().min(max_chars.saturating_mul(4)));
+    let mut kept = 0;
+    for c in content.chars() {
+        if c.is_control() && c != '\n' && c != '\t' {
+            continue;
+        }
+        if kept == max_chars {
+            out.push_str(TRUNCATED_MARKER);
+            break;
+        }
+        out.push(c);
+        kept += 1;
+    }
+    out
+}
+
+/// Builds the structured content of a stored message. Falls back to
+/// `Text(content)` when `raw_json` is absent, unparseable, or doesn't
+/// carry the submessage `message_type` promises.
+///
+/// `raw_json` is protojson with proto field names (`location_message`,
+/// `degrees_latitude`, …), as written by nanachi's `marshalProto`.
+pub fn parse_db_message(
+    message_type: &str,
+    content: Option<&str>,
+    raw_json: Option<&str>,
+) -> MessageContent {
+    let Some(raw) = raw_json.and_then(|r| serde_json::from_str::<Value>(r).ok()) else {
+        return MessageContent::text(content);
+    };
+    let parsed = match MessageType::from_raw(message_type) {
+        MessageType::Location => parse_location(&raw),
+        MessageType::Contact => parse_contact(&raw),
+        MessageType::Poll => parse_poll(&raw),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| MessageContent::text(content))
+}
+
+fn parse_location(raw: &Value) -> Option<MessageContent> {
+    let (loc, live) = match raw.get("location_message") {
+        Some(loc) => (loc, false),
+        None => (raw.get("live_location_message")?, true),
+    };
+    Some(MessageContent::Location {
+        lat: loc.get("degrees_latitude")?.as_f64()?,
+        lng: loc.get("degrees_longitude")?.as_f64()?,
+        name: non_empty(loc.get("name")),
+        address: non_empty(loc.get("address")),
+        live,
+    })
+}
+
+fn parse_contact(raw: &Value) -> Option<MessageContent> {
+    let card = raw.get("contact_message")?;
+    Some(MessageContent::Contact {
+        name: non_empty(card.get("display_name")).unwrap_or_default(),
+        vcard: non_empty(card.get("vcard")),
+    })
+}
+
+fn parse_poll(raw: &Value) -> Option<MessageContent> {
+    let poll = raw.get("poll_creation_message")?;
+    let options = poll
+        .get("options")
+        .and_then(Value::as_array)
+        .map(|opts| {
+            opts.iter()
+                .filter_map(|o| non_empty(o.get("option_name")))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(MessageContent::Poll {
+        question: non_empty(poll.get("name")).unwrap_or_default(),
+        options,
+        selectable_count: poll
+            .get("selectable_options_count")
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(0),
+    })
+}
+
+fn non_empty(v: Option<&Value>) -> Option<String> {
+    v.and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Who sent a stored message, as the thread header shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageSender {
+    /// The signed-in account, whatever the chat.
+    Me,
+    /// The other side of a DM (or the channel of a newsletter).
+    Contact { jid: String, name: String },
+    /// A group member. `admin` covers superadmins too.
+    Participant {
+        jid: String,
+        name: String,
+        admin: bool,
+    },
+}
+
+impl MessageSender {
+    /// `None` for `Me` and for group senders that came without a JID.
+    pub fn jid(&self) -> Option<&str> {
+        match self {
+            Self::Me => None,
+            Self::Contact { jid, .. } | Self::Participant { jid, .. } => {
+                Some(jid.as_str()).filter(|j| !j.is_empty())
+            }
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Self::Participant { admin: true, .. })
+    }
+}
+
+/// `admin` of a group participant as nanachi writes it (`admin` or
+/// `superadmin`; absent for plain members).
+pub fn is_admin_role(role: Option<&str>) -> bool {
+    matches!(role, Some("admin" | "superadmin"))
+}
+
+/// Builds the [`MessageSender`] of a stored message. `sender_name` is
+/// the already-resolved contact name; without one the JID's user part
+/// stands in. `is_admin` is only asked for group senders and answers
+/// from the group's participant list.
+pub fn parse_db_sender(
+    chat_jid: &str,
+    is_from_me: bool,
+    sender_jid: Option<&str>,
+    sender_name: Option<&str>,
+    is_admin: impl FnOnce(&str) -> bool,
+) -> MessageSender {
+    if is_from_me {
+        return MessageSender::Me;
+    }
+    let group = matches!(WaIdentity::parse(chat_jid), WaIdentity::Group(_));
+    // Num DM o remetente é o próprio chat; num grupo, sem JID não há
+    // como saber quem foi.
+    let jid = sender_jid
+        .or((!group).then_some(chat_jid))
+        .unwrap_or_default()
+        .to_string();
+    let name = sender_name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map_or_else(
+            || WaIdentity::parse(&jid).user().to_string(),
+            str::to_string,
+        );
+    if group {
+        let admin = !jid.is_empty() && is_admin(&jid);
+        MessageSender::Participant { jid, name, admin }
+    } else {
+        MessageSender::Contact { jid, name }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_strips_control_chars_but_keeps_layout() {
+        assert_eq!(sanitize_content("a\0b\u{7}c\r\n\td\u{85}", 100), "abc\n\td");
+        assert_eq!(sanitize_content("olá 👋", 100), "olá 👋");
+        // Exatamente no limite não ganha marcador.
+        assert_eq!(sanitize_content("abc", 3), "abc");
+        assert_eq!(
+            sanitize_content("abcd", 3),
+            format!("abc{TRUNCATED_MARKER}")
+        );
+    }
+
+    #[test]
+    fn sanitize_caps_a_megabyte_blob() {
+        let blob = "é\0".repeat(512 * 1024);
+        let out = sanitize_content(&blob, DEFAULT_MAX_CONTENT_CHARS);
+        let body = out.strip_suffix(TRUNCATED_MARKER).expect("marker");
+        assert_eq!(body.chars().count(), DEFAULT_MAX_CONTENT_CHARS);
+        assert!(body.chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn message_type_from_name_only_takes_canonical_names() {
+        for t in MessageType::ALL {
+            assert_eq!(MessageType::from_name(t.as_str()), Some(t));
+        }
+        assert_eq!(MessageType::from_name(" Image "), Some(MessageType::Image));
+        assert_eq!(MessageType::from_name("imageMessage"), None);
+        assert_eq!(MessageType::from_name("gif"), None);
+    }
+
+    #[test]
+    fn message_type_normalizes_raw_names() {
+        let cases = [
+            ("text", MessageType::Text),
+            ("conversation", MessageType::Text),
+            ("extendedText", MessageType::Text),
+            ("extendedTextMessage", MessageType::Text),
+            ("image", MessageType::Image),
+            ("imageMessage", MessageType::Image),
+            ("IMAGE", MessageType::Image),
+            (" video ", MessageType::Video),
+            ("videoMessage", MessageType::Video),
+            ("audioMessage", MessageType::Audio),
+            ("ptt", MessageType::Audio),
+            ("documentWithCaptionMessage", MessageType::Document),
+            ("stickerMessage", MessageType::Sticker),
+            ("lottieStickerMessage", MessageType::Sticker),
+            ("contactMessage", MessageType::Contact),
+            ("liveLocationMessage", MessageType::Location),
+            ("reactionMessage", MessageType::Reaction),
+            ("pollCreationMessageV3", MessageType::Poll),
+            ("poll", MessageType::Poll),
+            ("unknown", MessageType::Unknown),
+            ("", MessageType::Unknown),
+            ("protocolMessage", MessageType::Unknown),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(MessageType::from_raw(raw), expected, "{raw:?}");
+        }
+        // A forma canônica volta para o mesmo tipo.
+        for (_, ty) in cases {
+            assert_eq!(MessageType::from_raw(ty.as_str()), ty);
+        }
+    }
+
+    #[test]
+    fn preview_icons_are_single_emoji() {
+        let cases = [
+            (MessageType::Image, "\u{1F4F7}"),
+            (MessageType::Video, "\u{1F3AC}"),
+            (MessageType::Audio, "\u{1F3A4}"),
+            (MessageType::Document, "\u{1F4C4}"),
+            (MessageType::Sticker, "\u{1F3B4}"),
+            (MessageType::Contact, "\u{1F464}"),
+            (MessageType::Location, "\u{1F4CD}"),
+            (MessageType::Poll, "\u{1F4CA}"),
+        ];
+        for (ty, expected) in cases {
+            let icon = ty.to_preview_icon().unwrap();
+            assert_eq!(icon.as_bytes(), expected.as_bytes(), "{ty:?}");
+            // UTF-8 lido como Latin-1 vira "ðŸ…": nada na faixa U+0080..U+00FF.
+            assert!(!icon.chars().any(|c| ('\u{80}'..='\u{ff}').contains(&c)));
+        }
+        assert_eq!(MessageType::Text.to_preview_icon(), None);
+    }
+
+    #[test]
+    fn location_and_live_location() {
+        let raw = r#"{"location_message":{"degrees_latitude":-23.5505,"degrees_longitude":-46.6333,"name":"Praça da Sé","address":"São Paulo"}}"#;
+        assert_eq!(
+            parse_db_message("location", Some("[Location]"), Some(raw)),
+            MessageContent::Location {
+                lat: -23.5505,
+                lng: -46.6333,
+                name: Some("Praça da Sé".into()),
+                address: Some("São Paulo".into()),
+                live: false,
+            }
+        );
+
+        let live = r#"{"live_location_message":{"degrees_latitude":1.5,"degrees_longitude":2}}"#;
+        assert_eq!(
+            parse_db_message("location", Some("[Live Location]"), Some(live)),
+            MessageContent::Location {
+                lat: 1.5,
+                lng: 2.0,
+                name: None,
+                address: None,
+                live: true,
+            }
+        );
+    }
+
+    #[test]
+    fn contact_card() {
+        let raw = r#"{"contact_message":{"display_name":"Maria","vcard":"BEGIN:VCARD\nFN:Maria\nEND:VCARD"}}"#;
+        assert_eq!(
+            parse_db_message("contact", Some("[Contact]"), Some(raw)),
+            MessageContent::Contact {
+                name: "Maria".into(),
+                vcard: Some("BEGIN:VCARD\nFN:Maria\nEND:VCARD".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn poll_options() {
+        let raw = r#"{"poll_creation_message":{"name":"Almoço?","options":[{"option_name":"Sim"},{"option_name":"Não"},{"option_name":" "}],"selectable_options_count":1}}"#;
+        assert_eq!(
+            parse_db_message("poll", Some("Almoço?"), Some(raw)),
+            MessageContent::Poll {
+                question: "Almoço?".into(),
+                options: vec!["Sim".into(), "Não".into()],
+                selectable_count: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_text() {
+        let text = MessageContent::Text("[Location]".into());
+        assert_eq!(parse_db_message("location", Some("[Location]"), None), text);
+        assert_eq!(
+            parse_db_message("location", Some("[Location]"), Some("{not json")),
+            text
+        );
+        // JSON válido mas sem o submessage esperado.
+        assert_eq!(
+            parse_db_message(
+                "location",
+                Some("[Location]"),
+                Some(r#"{"image_message":{}}"#)
+            ),
+            text
+        );
+        assert_eq!(
+            parse_db_message("text", Some("oi"), Some("{}")),
+            MessageContent::Text("oi".into())
+        );
+    }
+
+    const GROUP: &str = "120363000000000001@g.us";
+
+    #[test]
+    fn dm_sender_is_the_contact() {
+        assert_eq!(
+            parse_db_sender(
+                "5511999990000@s.whatsapp.net",
+                false,
+                Some("5511999990000@s.whatsapp.net"),
+                Some("Maria"),
+                |_| panic!("DM não tem admins"),
+            ),
+            MessageSender::Contact {
+                jid: "5511999990000@s.whatsapp.net".into(),
+                name: "Maria".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn group_member_and_admin() {
+        let admins = ["5511888880000@s.whatsapp.net"];
+        let is_admin = |jid: &str| admins.contains(&jid);
+        let member = parse_db_sender(
+            GROUP,
+            false,
+            Some("5511777770000@s.whatsapp.net"),
+            None,
+            is_admin,
+        );
+        assert_eq!(
+            member,
+            MessageSender::Participant {
+                jid: "5511777770000@s.whatsapp.net".into(),
+                name: "5511777770000".into(),
+                admin: false,
+            }
+        );
+        let admin = parse_db_sender(GROUP, false, Some(admins[0]), Some(" João "), is_admin);
+        assert!(admin.is_admin());
+        assert_eq!(admin.jid(), Some(admins[0]));
+        assert!(matches!(admin, MessageSender::Participant { ref name, .. } if name == "João"));
+
+        // Remetente sem JID não herda o do grupo nem vira admin.
+        let unknown = parse_db_sender(GROUP, false, None, None, |_| true);
+        assert_eq!(unknown.jid(), None);
+        assert!(!unknown.is_admin());
+    }
+
+    #[test]
+    fn own_messages_are_me_in_any_chat() {
+        let sender = parse_db_sender(GROUP, true, None, None, |_| true);
+        assert_eq!(sender, MessageSender::Me);
+        assert!(!sender.is_admin());
+        assert!(is_admin_role(Some("superadmin")));
+        assert!(!is_admin_role(None));
+    }
+}
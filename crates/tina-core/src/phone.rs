@@ -0,0 +1,93 @@
+// Phone display, for everything that shows a number: the UI and the
+// contact name fallback in tina-db. Parsing and per-country grouping
+// come from the `phonenumber` crate; the few places where we disagree
+// with its international format (or where WhatsApp JIDs predate the
+// current numbering plan) are special-cased below.
+
+use phonenumber::{Mode, PhoneNumber};
+
+/// Formats the user part of a phone JID (or a bare number) for display:
+/// `5561996862399@s.whatsapp.net` → `+55 61 99686-2399`,
+/// `14155552671` → `+1 (415) 555-2671`, `442079460958` → `+44 20 7946 0958`.
+/// Numbers the crate doesn't recognize come back as `+<digits>`; anything
+/// that isn't digits (LIDs with letters, names) is returned untouched.
+pub fn format_jid_or_phone(jid_or_phone: &str) -> String {
+    // If it's a JID, extract the phone number part
+    let num_str = jid_or_phone.split('@').next().unwrap_or(jid_or_phone);
+    let base_num = num_str.split(':').next().unwrap_or(num_str);
+    let digits = base_num.strip_prefix('+').unwrap_or(base_num);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return jid_or_phone.to_string();
+    }
+    let plain = format!("+{digits}");
+
+    match parse_valid(&plain).or_else(|| legacy_brazilian_mobile(digits)) {
+        Some(phone) => display(&phone),
+        None => plain,
+    }
+}
+
+fn parse_valid(number: &str) -> Option<PhoneNumber> {
+    phonenumber::parse(None, number)
+        .ok()
+        .filter(|p| p.is_valid())
+}
+
+/// Celulares brasileiros antigos (8 dígitos, sem o 9 na frente) ainda
+/// aparecem em JIDs: `556196862399` → `+55 61 99686-2399`.
+fn legacy_brazilian_mobile(digits: &str) -> Option<PhoneNumber> {
+    if digits.len() != 12 || !digits.starts_with("55") {
+        return None;
+    }
+    let (prefix, suffix) = digits.split_at(4);
+    parse_valid(&format!("+{prefix}9{suffix}"))
+}
+
+/// International format, except the NANP (+1), where the usual written
+/// form keeps the area code in parentheses.
+fn display(phone: &PhoneNumber) -> String {
+    match phone.code().value() {
+        1 => format!("+1 {}", phone.format().mode(Mode::National)),
+        _ => phone.format().mode(Mode::International).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brazilian_numbers_keep_their_format() {
+        assert_eq!(
+            format_jid_or_phone("5561996862399@s.whatsapp.net"),
+            "+55 61 99686-2399"
+        );
+        // Device suffix and the pre-2016 8-digit mobile.
+        assert_eq!(
+            format_jid_or_phone("556196862399:12@s.whatsapp.net"),
+            "+55 61 99686-2399"
+        );
+    }
+
+    #[test]
+    fn formats_us_and_uk_numbers() {
+        assert_eq!(format_jid_or_phone("14155552671"), "+1 (415) 555-2671");
+        assert_eq!(format_jid_or_phone("+442079460958"), "+44 20 7946 0958");
+    }
+
+    #[test]
+    fn unknown_numbers_fall_back_to_plus_digits() {
+        assert_eq!(
+            format_jid_or_phone("999123456@s.whatsapp.net"),
+            "+999123456"
+        );
+        assert_eq!(format_jid_or_phone("5512"), "+5512");
+    }
+
+    #[test]
+    fn non_digit_input_is_untouched() {
+        assert_eq!(format_jid_or_phone("Fulano"), "Fulano");
+        assert_eq!(format_jid_or_phone("abc123@lid"), "abc123@lid");
+        assert_eq!(format_jid_or_phone(""), "");
+    }
+}
@@ -2,9 +2,20 @@ use serde::{Deserialize, Serialize};
 
 use crate::events::{IpcCommand, IpcEvent};
 
+/// Version of the line protocol spoken with nanachi. Bump it (here and
+/// `protocolVersion` in nanachi/ipc_types.go) when a change would make
+/// one side misread the other: a renamed or removed field, a field whose
+/// meaning changes. New optional fields don't need a bump — both sides
+/// ignore what they don't know.
+pub const PROTOCOL_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IpcMessage {
     pub id: String,
+    /// Sender's [`PROTOCOL_VERSION`]. `None` from a nanachi built
+    /// before the field existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<u32>,
     #[serde(flatten)]
     pub content: IpcMessageContent,
 }
@@ -20,6 +31,7 @@ impl IpcMessage {
     pub fn new_command(command: IpcCommand) -> Self {
         Self {
             id: generate_id(),
+            protocol_version: Some(PROTOCOL_VERSION),
             content: IpcMessageContent::Command(command),
         }
     }
@@ -27,6 +39,7 @@ impl IpcMessage {
     pub fn new_event(event: IpcEvent) -> Self {
         Self {
             id: generate_id(),
+            protocol_version: Some(PROTOCOL_VERSION),
             content: IpcMessageContent::Event(event),
         }
     }
@@ -48,3 +61,419 @@ fn generate_id() -> String {
         .as_nanos();
     format!("{:x}", nanos)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WaIdentity;
+    use crate::disconnect::IDLE_STOP_REASON;
+    use crate::events::{
+        ChatPinItem, ChatReadHintItem, ContactData, GroupData, MediaKind, MessageData,
+        ParticipantData, ReadReceiptItem,
+    };
+
+    fn acc() -> String {
+        "acc1".to_string()
+    }
+
+    fn jid(raw: &str) -> WaIdentity {
+        WaIdentity::parse(raw)
+    }
+
+    // Uma amostra por variante; variante nova entra aqui também.
+    fn all_commands() -> Vec<IpcCommand> {
+        let dm = || jid("5511999999999@s.whatsapp.net");
+        let group = || jid("120363000000000000@g.us");
+        vec![
+            IpcCommand::StartAccount { account_id: acc() },
+            IpcCommand::StopAccount {
+                account_id: acc(),
+                reason: Some(IDLE_STOP_REASON.into()),
+            },
+            IpcCommand::Logout { account_id: acc() },
+            IpcCommand::GetQrCode { account_id: acc() },
+            IpcCommand::SendMessage {
+                account_id: acc(),
+                to: dm(),
+                content: "oi".into(),
+                mentioned_jids: vec![dm()],
+                local_id: Some("local-1".into()),
+            },
+            IpcCommand::SendMedia {
+                account_id: acc(),
+                to: dm(),
+                kind: MediaKind::Voice,
+                path: "/tmp/a.ogg".into(),
+                caption: Some("ouve".into()),
+                mimetype: Some("audio/ogg".into()),
+                filename: None,
+            },
+            IpcCommand::Reconcile { account_id: acc() },
+            IpcCommand::CancelSync { account_id: acc() },
+            IpcCommand::GetContacts { account_id: acc() },
+            IpcCommand::GetGroups { account_id: acc() },
+            IpcCommand::DownloadMedia {
+                account_id: acc(),
+                message_id: "M1".into(),
+                raw_json: Some("{}".into()),
+            },
+            IpcCommand::FetchAvatar {
+                account_id: acc(),
+                jid: dm(),
+            },
+            IpcCommand::FetchAvatarFromURL {
+                account_id: acc(),
+                jid: jid("1203630000@newsletter"),
+                url: "https://example.com/a.jpg".into(),
+            },
+            IpcCommand::GetProfile {
+                account_id: acc(),
+                jid: dm(),
+            },
+            IpcCommand::CheckNumber {
+                account_id: acc(),
+                phone: "5511999999999".into(),
+            },
+            IpcCommand::RefreshChat {
+                account_id: acc(),
+                chat_jid: group(),
+            },
+            IpcCommand::MarkRead {
+                account_id: acc(),
+                chat_jid: group(),
+                sender_jid: dm(),
+                message_ids: vec!["M1".into(), "M2".into()],
+            },
+            IpcCommand::MarkChatsRead {
+                account_id: acc(),
+                chats: vec![ReadReceiptItem {
+                    chat_jid: dm(),
+                    sender_jid: dm(),
+                    message_ids: vec!["M3".into()],
+                }],
+            },
+            IpcCommand::PinMessage {
+                account_id: acc(),
+                chat_jid: group(),
+                message_id: "M1".into(),
+                sender_jid: Some(dm()),
+                pin: true,
+            },
+            IpcCommand::StarMessage {
+                account_id: acc(),
+                chat_jid: dm(),
+                message_id: "M1".into(),
+                sender_jid: None,
+                from_me: true,
+                star: false,
+            },
+            IpcCommand::ForwardMessage {
+                account_id: acc(),
+                from_chat: dm(),
+                message_id: "M1".into(),
+                to: vec![group(), dm()],
+                raw_json: None,
+                content: Some("oi".into()),
+            },
+            IpcCommand::SetTyping {
+                account_id: acc(),
+                to: dm(),
+                is_typing: true,
+            },
+            IpcCommand::GetGroupInviteLink {
+                account_id: acc(),
+                group_jid: group(),
+            },
+            IpcCommand::RevokeGroupInviteLink {
+                account_id: acc(),
+                group_jid: group(),
+            },
+            IpcCommand::JoinGroupByLink {
+                account_id: acc(),
+                link: "https://chat.whatsapp.com/AbCdEf".into(),
+            },
+            IpcCommand::Shutdown,
+        ]
+    }
+
+    fn all_events() -> Vec<IpcEvent> {
+        let dm = || jid("5511999999999@s.whatsapp.net");
+        let group = || jid("120363000000000000@g.us");
+        vec![
+            IpcEvent::Ready { account_id: acc() },
+            IpcEvent::QrCode {
+                account_id: acc(),
+                qr: "2@abc".into(),
+                timeout_secs: Some(60),
+            },
+            IpcEvent::PairingCode {
+                account_id: acc(),
+                code: "ABCD-EFGH".into(),
+            },
+            IpcEvent::Connected {
+                account_id: acc(),
+                phone_number: Some("5511999999999".into()),
+                jid: Some(dm()),
+                push_name: Some("Tina".into()),
+            },
+            IpcEvent::Disconnected {
+                account_id: acc(),
+                reason: "stream replaced".into(),
+            },
+            IpcEvent::LoggedOut { account_id: acc() },
+            IpcEvent::ContactsUpsert {
+                account_id: acc(),
+                contacts: vec![ContactData {
+                    jid: dm(),
+                    lid: Some(jid("123456789@lid")),
+                    phone_number: Some("5511999999999".into()),
+                    name: Some("Fulano".into()),
+                    notify: None,
+                    verified_name: None,
+                    img_url: None,
+                    status: None,
+                }],
+            },
+            IpcEvent::GroupsUpsert {
+                account_id: acc(),
+                groups: vec![GroupData {
+                    jid: group(),
+                    subject: Some("Grupo".into()),
+                    owner: Some(dm()),
+                    description: None,
+                    avatar_url: None,
+                    participants: vec![ParticipantData {
+                        id: dm(),
+                        admin: Some("superadmin".into()),
+                        phone_number: None,
+                    }],
+                }],
+            },
+            IpcEvent::MessagesUpsert {
+                account_id: acc(),
+                messages: vec![MessageData {
+                    message_id: "M1".into(),
+                    chat_jid: group(),
+                    sender_jid: dm(),
+                    content: Some("oi".into()),
+                    message_type: "image".into(),
+                    timestamp: 1_700_000_000,
+                    is_from_me: false,
+                    raw_json: None,
+                    thumbnail: Some(vec![0xff, 0xd8, 0xff]),
+                    media_mimetype: Some("image/jpeg".into()),
+                    media_filename: None,
+                    media_duration_secs: None,
+                    media_width: Some(640),
+                    media_height: Some(480),
+                    media_size_bytes: Some(1024),
+                    media_sha256: None,
+                    quoted_message_id: Some("M0".into()),
+                    quoted_sender_id: Some(dm()),
+                    quoted_preview: Some("antes".into()),
+                    mentioned_jids: vec![dm()],
+                }],
+                chunk_index: Some(2),
+                is_last: false,
+            },
+            IpcEvent::HistorySyncComplete {
+                account_id: acc(),
+                messages_count: 42,
+            },
+            IpcEvent::ChatsPinUpdate {
+                account_id: acc(),
+                items: vec![ChatPinItem {
+                    chat_jid: group(),
+                    pinned: true,
+                }],
+            },
+            IpcEvent::ChatsReadHint {
+                account_id: acc(),
+                items: vec![ChatReadHintItem {
+                    chat_jid: dm(),
+                    last_read_ts: 1_700_000_000,
+                }],
+            },
+            IpcEvent::HistorySyncProgress {
+                account_id: acc(),
+                sync_type: "INITIAL_BOOTSTRAP".into(),
+                progress: 50,
+                messages_count: 10,
+            },
+            IpcEvent::ReconcileProgress {
+                account_id: acc(),
+                stage: "contatos".into(),
+                current: 1,
+                total: 0,
+                indeterminate: true,
+            },
+            IpcEvent::Error {
+                account_id: None,
+                error: "boom".into(),
+            },
+            IpcEvent::Notice {
+                account_id: Some(acc()),
+                message: "ffmpeg not found".into(),
+            },
+            IpcEvent::ReceiptUpdate {
+                account_id: acc(),
+                message_ids: vec!["M1".into()],
+                status: "read".into(),
+            },
+            IpcEvent::MessageEdited {
+                account_id: acc(),
+                chat_jid: "5511999999999@s.whatsapp.net".into(),
+                message_id: "M1".into(),
+                new_content: "oi!".into(),
+                edited_at: 1_700_000_100,
+            },
+            IpcEvent::MediaDownloadProgress {
+                account_id: acc(),
+                message_id: "M1".into(),
+                current: 10,
+                total: 100,
+            },
+            IpcEvent::MediaDownloaded {
+                account_id: acc(),
+                message_id: "M1".into(),
+                path: "/tmp/M1.jpg".into(),
+                sha256: Some("ab".repeat(32)),
+                mimetype: Some("image/jpeg".into()),
+            },
+            IpcEvent::MediaDownloadFailed {
+                account_id: acc(),
+                message_id: "M1".into(),
+                error: "404".into(),
+            },
+            IpcEvent::AvatarUpdated {
+                account_id: acc(),
+                jid: dm(),
+                path: "/tmp/avatar.jpg".into(),
+            },
+            IpcEvent::AvatarFailed {
+                account_id: acc(),
+                jid: group(),
+                error: "not found".into(),
+            },
+            IpcEvent::GroupInviteLink {
+                account_id: acc(),
+                group_jid: group(),
+                link: "https://chat.whatsapp.com/AbCdEf".into(),
+            },
+            IpcEvent::ProfileResult {
+                account_id: acc(),
+                jid: dm(),
+                img_url: None,
+                status: Some("available".into()),
+                name: Some("Alice".into()),
+            },
+            IpcEvent::NumberCheckResult {
+                account_id: acc(),
+                phone: "5511999999999".into(),
+                exists: true,
+                jid: Some(dm()),
+            },
+            IpcEvent::CommandResult {
+                command_id: "18f0".into(),
+                success: false,
+                data: Some(serde_json::json!({ "reason": "not_admin" })),
+                error: Some("not an admin".into()),
+            },
+        ]
+    }
+
+    /// A variant back in the wrong enum, or with other field values,
+    /// serializes differently.
+    fn same(a: &IpcMessageContent, b: &IpcMessageContent) -> bool {
+        serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+    }
+
+    #[test]
+    fn every_command_round_trips_as_a_command() {
+        for command in all_commands() {
+            let message = IpcMessage::new_command(command);
+            let line = message.to_line();
+            let back = IpcMessage::from_line(&line).unwrap_or_else(|| panic!("unparsed: {line}"));
+            assert_eq!(back.id, message.id);
+            assert!(
+                matches!(back.content, IpcMessageContent::Command(_)),
+                "read back as an event: {line}"
+            );
+            assert!(same(&back.content, &message.content), "changed: {line}");
+        }
+    }
+
+    #[test]
+    fn every_event_round_trips_as_an_event() {
+        for event in all_events() {
+            let message = IpcMessage::new_event(event);
+            let line = message.to_line();
+            let back = IpcMessage::from_line(&line).unwrap_or_else(|| panic!("unparsed: {line}"));
+            assert_eq!(back.id, message.id);
+            assert_eq!(back.protocol_version, Some(PROTOCOL_VERSION));
+            assert!(
+                matches!(back.content, IpcMessageContent::Event(_)),
+                "read back as a command: {line}"
+            );
+            assert!(same(&back.content, &message.content), "changed: {line}");
+        }
+    }
+
+    /// `IpcMessageContent` is untagged: only the `type` names keep a
+    /// command from being read as an event, so none may be shared.
+    #[test]
+    fn command_and_event_type_names_are_disjoint() {
+        let type_of = |content: IpcMessageContent| {
+            serde_json::to_value(content).unwrap()["type"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let commands: Vec<String> = all_commands()
+            .into_iter()
+            .map(|c| type_of(IpcMessageContent::Command(c)))
+            .collect();
+        for event in all_events() {
+            let name = type_of(IpcMessageContent::Event(event));
+            assert!(!commands.contains(&name), "{name} is both");
+        }
+    }
+
+    /// O composer manda texto com quebras de linha; o protocolo é uma
+    /// mensagem por linha, então o `\n` tem que ir escapado no JSON.
+    #[test]
+    fn multiline_content_stays_on_one_line() {
+        let content = "primeira\nsegunda\n\n  quarta\r\n";
+        let message = IpcMessage::new_command(IpcCommand::SendMessage {
+            account_id: acc(),
+            to: jid("5511999999999@s.whatsapp.net"),
+            content: content.into(),
+            mentioned_jids: vec![],
+            local_id: None,
+        });
+        let line = message.to_line();
+        assert_eq!(line.matches('\n').count(), 1, "split: {line:?}");
+        assert!(line.ends_with('\n'));
+
+        let back = IpcMessage::from_line(&line).unwrap();
+        let IpcMessageContent::Command(IpcCommand::SendMessage { content: got, .. }) = back.content
+        else {
+            panic!("not a SendMessage: {line}");
+        };
+        assert_eq!(got, content);
+    }
+
+    #[test]
+    fn protocol_version_is_optional_on_the_wire() {
+        let line = r#"{"id":"1","type":"LoggedOut","payload":{"account_id":"a"}}"#;
+        let message = IpcMessage::from_line(line).unwrap();
+        assert_eq!(message.protocol_version, None);
+
+        let line =
+            r#"{"id":"1","protocol_version":7,"type":"LoggedOut","payload":{"account_id":"a"}}"#;
+        assert_eq!(
+            IpcMessage::from_line(line).unwrap().protocol_version,
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// Pairing QR as pixels, for the frontends that can't print it as text
+// (the GTK login card, the CLI's PNG fallback). Integer scale and a
+// 4-module quiet zone: WhatsApp's scanner is fussy about both.
+// Feature `qr`.
+
+use qrcode::QrCode;
+use qrcode::types::Color;
+
+/// Quiet zone around the code, in modules.
+pub const QR_MARGIN: usize = 4;
+
+/// Square 8-bit grayscale image, row by row: `0` for dark modules,
+/// `0xFF` for the rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrRaster {
+    /// Edge in pixels.
+    pub size: usize,
+    pub pixels: Vec<u8>,
+}
+
+/// Renders `data` at the largest integer scale whose edge stays within
+/// `max_size` pixels (at least one pixel per module). `None` when the
+/// data doesn't fit in a QR code.
+pub fn render_qr(data: &str, max_size: usize) -> Option<QrRaster> {
+    let code = QrCode::new(data).ok()?;
+    let width = code.width();
+    let modules = width + QR_MARGIN * 2;
+    let scale = (max_size / modules).max(1);
+    let size = modules * scale;
+    let mut pixels = vec![0xFF; size * size];
+    for y in 0..width {
+        for x in 0..width {
+            if code[(x, y)] != Color::Dark {
+                continue;
+            }
+            for dy in 0..scale {
+                let row = ((y + QR_MARGIN) * scale + dy) * size;
+                let start = row + (x + QR_MARGIN) * scale;
+                pixels[start..start + scale].fill(0);
+            }
+        }
+    }
+    Some(QrRaster { size, pixels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raster_keeps_the_quiet_zone_and_fits_the_cap() {
+        let qr = render_qr("2@abc,def,ghi,jkl", 220).unwrap();
+        assert!(qr.size <= 220);
+        assert_eq!(qr.pixels.len(), qr.size * qr.size);
+        let modules = QrCode::new("2@abc,def,ghi,jkl").unwrap().width() + QR_MARGIN * 2;
+        let scale = qr.size / modules;
+        assert_eq!(qr.size % modules, 0);
+        // Borda branca; o canto do finder pattern logo depois é escuro.
+        let margin = QR_MARGIN * scale;
+        assert!(qr.pixels[..margin * qr.size].iter().all(|&p| p == 0xFF));
+        assert_eq!(qr.pixels[margin * qr.size + margin], 0);
+    }
+
+    #[test]
+    fn tiny_cap_still_draws_every_module() {
+        let qr = render_qr("x", 1).unwrap();
+        let modules = QrCode::new("x").unwrap().width() + QR_MARGIN * 2;
+        assert_eq!(qr.size, modules);
+    }
+}
@@ -0,0 +1,95 @@
+// Message timestamps are epoch seconds everywhere (nanachi, the DB, the
+// wire). These turn them into chrono types and into the short label a
+// chat list shows, so every frontend reads them the same way. Feature
+// `chrono`.
+
+use chrono::{DateTime, Datelike, Local, Utc};
+
+/// Now, as the same epoch seconds.
+pub fn unix_now() -> i64 {
+    Utc::now().timestamp()
+}
+
+/// `None` for `0`/negative (no timestamp) and for values chrono can't
+/// represent.
+pub fn datetime_utc(timestamp: i64) -> Option<DateTime<Utc>> {
+    (timestamp > 0)
+        .then(|| DateTime::from_timestamp(timestamp, 0))
+        .flatten()
+}
+
+/// [`datetime_utc`] in the machine's timezone.
+pub fn datetime_local(timestamp: i64) -> Option<DateTime<Local>> {
+    datetime_utc(timestamp).map(|t| t.with_timezone(&Local))
+}
+
+/// Short label relative to `now`: the time for today, the weekday for
+/// the six days before, the date without the year within this year and
+/// the full date before that. Empty when there's no timestamp.
+pub fn format_relative_timestamp(timestamp: i64, now: DateTime<Local>) -> String {
+    let Some(local) = datetime_local(timestamp) else {
+        return String::new();
+    };
+    let day = local.date_naive();
+    let today = now.date_naive();
+    if day == today {
+        local.format("%H:%M").to_string()
+    } else if day < today && (today - day).num_days() < 7 {
+        local.format("%a").to_string()
+    } else if local.year() == now.year() {
+        local.format("%d/%m").to_string()
+    } else {
+        local.format("%d/%m/%y").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).single().unwrap()
+    }
+
+    #[test]
+    fn missing_timestamps_have_no_date() {
+        assert_eq!(datetime_utc(0), None);
+        assert_eq!(datetime_utc(-5), None);
+        assert_eq!(datetime_utc(i64::MAX), None);
+        assert_eq!(
+            datetime_utc(1_700_000_000).map(|t| t.timestamp()),
+            Some(1_700_000_000)
+        );
+        assert_eq!(format_relative_timestamp(0, Local::now()), "");
+    }
+
+    #[test]
+    fn same_day_shows_the_time() {
+        // Sexta, 16/10/2026.
+        let now = at(2026, 10, 16, 18, 30);
+        let ts = at(2026, 10, 16, 9, 5).timestamp();
+        assert_eq!(format_relative_timestamp(ts, now), "09:05");
+    }
+
+    #[test]
+    fn this_week_shows_the_weekday() {
+        let now = at(2026, 10, 16, 18, 30);
+        let yesterday = at(2026, 10, 15, 23, 59).timestamp();
+        assert_eq!(format_relative_timestamp(yesterday, now), "Thu");
+        let six_days = (at(2026, 10, 16, 0, 0) - Duration::days(6)).timestamp();
+        assert_eq!(format_relative_timestamp(six_days, now), "Sat");
+    }
+
+    #[test]
+    fn older_shows_the_date() {
+        let now = at(2026, 10, 16, 18, 30);
+        let last_week = at(2026, 10, 9, 12, 0).timestamp();
+        assert_eq!(format_relative_timestamp(last_week, now), "09/10");
+        let last_year = at(2025, 12, 31, 12, 0).timestamp();
+        assert_eq!(format_relative_timestamp(last_year, now), "31/12/25");
+        // Relógio adiantado: amanhã não vira "hoje".
+        let tomorrow = at(2026, 10, 17, 8, 0).timestamp();
+        assert_eq!(format_relative_timestamp(tomorrow, now), "17/10");
+    }
+}
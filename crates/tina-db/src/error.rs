@@ -13,6 +13,9 @@ pub enum DbError {
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[error("Invalid backup: {0}")]
+    InvalidBackup(String),
 }
 
 pub type Result<T> = std::result::Result<T, DbError>;
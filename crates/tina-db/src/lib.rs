@@ -5,7 +5,9 @@ mod schema;
 
 pub use error::DbError;
 pub use models::*;
-pub use repository::TinaDb;
+pub use repository::{
+    DATA_DIR_ENV, DB_PATH_ENV, MAX_PINNED_PER_CHAT, MUTED_FOREVER, TinaDb, data_dir,
+};
 
 #[cfg(test)]
 mod tests;
@@ -1,3 +1,6 @@
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -10,6 +13,42 @@ pub struct Account {
     pub updated_at: i64,
 }
 
+/// What [`TinaDb::purge_account_data`] wipes. Each flag empties its
+/// tables independently; the account row and its auth state are never
+/// touched.
+///
+/// [`TinaDb::purge_account_data`]: crate::TinaDb::purge_account_data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeOptions {
+    /// Messages, pins and the chat list built from them.
+    pub messages: bool,
+    /// Contacts and their PN/LID aliases.
+    pub contacts: bool,
+    /// Group metadata (subject, participants, invite link).
+    pub groups: bool,
+}
+
+impl PurgeOptions {
+    pub fn all() -> Self {
+        Self {
+            messages: true,
+            contacts: true,
+            groups: true,
+        }
+    }
+}
+
+/// Agregados por conta para a tela de perfil. Conta sem dados devolve
+/// tudo zerado (e `last_message_ts = None`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AccountStats {
+    pub message_count: i64,
+    pub chat_count: i64,
+    pub contact_count: i64,
+    pub group_count: i64,
+    pub last_message_ts: Option<i64>,
+}
+
 /// Tipo do chat. Inferido a partir do server do JID na criação.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -34,6 +73,19 @@ impl ChatKind {
         }
     }
 
+    /// Inverso de [`ChatKind::as_str`]; valores desconhecidos viram
+    /// `Unknown`.
+    pub fn from_db(s: &str) -> Self {
+        match s {
+            "dm" => ChatKind::Dm,
+            "group" => ChatKind::Group,
+            "newsletter" => ChatKind::Newsletter,
+            "broadcast" => ChatKind::Broadcast,
+            "status" => ChatKind::Status,
+            _ => ChatKind::Unknown,
+        }
+    }
+
     /// Inferência a partir da parte `@server` de um JID.
     pub fn infer_from_jid(jid: &str) -> Self {
         let server = jid.rsplit_once('@').map(|(_, s)| s).unwrap_or("");
@@ -69,10 +121,66 @@ pub struct Contact {
     pub avatar_path: Option<String>,
     pub status: Option<String>,
     pub is_local: bool,
+    /// `contact_name` was typed in by the user; server syncs leave it.
+    pub name_is_local: bool,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+impl Contact {
+    /// Name to show for this contact, falling back to the formatted
+    /// phone number and then the JID. See [`contact_display_name`] for
+    /// the order.
+    pub fn display_name(&self) -> String {
+        let name = contact_display_name(
+            self.contact_name.as_deref(),
+            self.verified_name.as_deref(),
+            self.push_name.as_deref(),
+            self.business_name.as_deref(),
+        );
+        if let Some(name) = name {
+            return name.to_string();
+        }
+        let phone = self
+            .phone_number
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .or(self.pn_jid.as_deref());
+        match phone {
+            Some(phone) => tina_core::format_jid_or_phone(phone),
+            None => self
+                .lid_jid
+                .clone()
+                .unwrap_or_else(|| self.contact_id.clone()),
+        }
+    }
+}
+
+/// The one name priority for a contact, shared by every query that
+/// resolves names (`COALESCE(contact_name, verified_name, push_name,
+/// business_name, …)`):
+///
+/// 1. `contact_name` — what the user saved it as. The one exception to
+///    verified first: a name the user typed wins, like on the phone;
+/// 2. `verified_name` — the business's verified name;
+/// 3. `push_name` — what the person calls themselves;
+/// 4. `business_name`.
+///
+/// Blank names are skipped. `None` leaves the phone / JID fallback to
+/// the caller.
+pub fn contact_display_name<'a>(
+    contact_name: Option<&'a str>,
+    verified_name: Option<&'a str>,
+    push_name: Option<&'a str>,
+    business_name: Option<&'a str>,
+) -> Option<&'a str> {
+    [contact_name, verified_name, push_name, business_name]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|n| !n.is_empty())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Chat {
     pub account_id: String,
@@ -133,6 +241,86 @@ pub struct ChatRow {
     /// the row hasn't received any message yet. Used by the chat-list
     /// preview to prefix `Author: …` for groups and newsletters.
     pub last_sender_name: Option<String>,
+    /// Mute deadline still in the future (see `TinaDb::set_chat_muted`).
+    pub muted: bool,
+}
+
+impl ChatRow {
+    pub fn order_key(&self) -> ChatOrderKey<'_> {
+        ChatOrderKey::new(
+            self.pinned,
+            self.last_message_ts.unwrap_or(0),
+            &self.name,
+            &self.chat_id,
+        )
+    }
+}
+
+/// Canonical chat-list order: pinned first, then most recent activity,
+/// then name, then `chat_id` so ties never swap places between
+/// refreshes. `list_chat_rows` sorts by the same key in SQL; the UI
+/// sorts its rows with it so an incremental upsert lands where a full
+/// reload would have put it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ChatOrderKey<'a> {
+    pinned: Reverse<bool>,
+    last_ts: Reverse<i64>,
+    name: &'a str,
+    chat_id: &'a str,
+}
+
+impl<'a> ChatOrderKey<'a> {
+    /// `last_ts` is the last message's timestamp, 0 for a chat with none.
+    pub fn new(pinned: bool, last_ts: i64, name: &'a str, chat_id: &'a str) -> Self {
+        Self {
+            pinned: Reverse(pinned),
+            last_ts: Reverse(last_ts),
+            name,
+            chat_id,
+        }
+    }
+}
+
+/// Header metadata for one chat, from [`TinaDb::get_chat_info`].
+/// Group fields are `None` for DMs; `phone_number` / `about` are
+/// `None` for groups and newsletters.
+///
+/// [`TinaDb::get_chat_info`]: crate::TinaDb::get_chat_info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatInfo {
+    pub chat_id: String,
+    pub kind: ChatKind,
+    /// Same resolution as `get_chat_name`.
+    pub name: Option<String>,
+    pub avatar_path: Option<String>,
+    pub muted_until: Option<i64>,
+    /// Local notification toggle (`set_chat_notify`). A notifier
+    /// should also skip the chat while `muted_until` is in the future.
+    pub notify: bool,
+    pub description: Option<String>,
+    pub participant_count: Option<i64>,
+    pub phone_number: Option<String>,
+    /// Contact's status text ("about").
+    pub about: Option<String>,
+}
+
+/// Chat with its resolved name and newest message, read from
+/// `messages` rather than the denormalized `chats.last_message_*`.
+/// The `last_*` fields are `None` for chats without messages.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ChatPreviewRow {
+    pub chat_jid: String,
+    pub is_group: bool,
+    /// Group subject / display name for groups and newsletters,
+    /// contact name → verified name → push name for DMs.
+    pub resolved_name: Option<String>,
+    pub last_message_id: Option<String>,
+    pub last_timestamp: Option<i64>,
+    pub last_message_content: Option<String>,
+    pub last_message_type: Option<String>,
+    pub last_message_from_me: bool,
+    /// Sender of the newest message; `None` when it was from me.
+    pub last_sender_name: Option<String>,
 }
 
 /// Metadados de mídia extraídos do proto. `Some(_)` em
@@ -194,7 +382,7 @@ pub struct MessageBatchResult {
     pub new_message_ids_per_chat: std::collections::HashMap<String, Vec<String>>,
 }
 
-/// Input para `run_contacts_batch`. Borrowed pra zero alocação extra.
+/// Input para `upsert_contacts_batch`. Borrowed pra zero alocação extra.
 #[derive(Debug, Clone, Copy)]
 pub struct ContactBatchInput<'a> {
     pub jid: &'a str,
@@ -251,14 +439,16 @@ pub struct MessageRow {
     pub media_thumbnail: Option<Vec<u8>>,
     /// Reply / quoted-message metadata. `None` for messages that
     /// aren't replying to anything; populated from the message's
-    /// `proto.contextInfo.quotedMessage` at ingest time.
+    /// `proto.contextInfo.quotedMessage` at ingest time. When the proto
+    /// carried no preview, `quoted_preview` is the start of the quoted
+    /// message's text if we have it locally.
     pub quoted_message_id: Option<String>,
     pub quoted_sender_id: Option<String>,
     pub quoted_preview: Option<String>,
     /// Display name of the quoted sender, resolved via JOIN through
-    /// `contact_aliases` → `contacts`. `None` when the cited author
-    /// isn't in our local contacts table (rare — usually we've seen
-    /// at least one message from them).
+    /// `contact_aliases` → `contacts`, else through the sender of the
+    /// quoted message itself. `None` when neither resolves (rare —
+    /// usually we've seen at least one message from them).
     pub quoted_sender_name: Option<String>,
     /// JSON-encoded `[String]` of mentioned JIDs.
     pub mentions_json: Option<String>,
@@ -266,6 +456,29 @@ pub struct MessageRow {
     /// `played`/`failed`. Default `sent` for incoming rows; only
     /// renderered for `from_me=true`.
     pub delivery_status: String,
+    /// Whether the message is in the chat's `pinned_messages` set.
+    pub is_pinned: bool,
+    pub is_starred: bool,
+    /// Unix secs of the sender's last edit; `None` if never edited.
+    /// `content` already holds the edited text.
+    pub edited_at: Option<i64>,
+    /// Local copy of a message we forwarded (`forward_message`).
+    pub forwarded: bool,
+    /// Proto subset for location / contact / poll rows, fed to
+    /// `tina_core::parse_db_message`. `None` for every other kind —
+    /// the media protos are only needed by the download path.
+    pub raw_json: Option<String>,
+}
+
+impl MessageRow {
+    /// `timestamp` as a date; `None` when the message has none.
+    pub fn datetime_utc(&self) -> Option<DateTime<Utc>> {
+        tina_core::datetime_utc(self.timestamp)
+    }
+
+    pub fn datetime_local(&self) -> Option<DateTime<Local>> {
+        tina_core::datetime_local(self.timestamp)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -290,9 +503,22 @@ pub struct Message {
     pub media_path: Option<String>,
     pub media_status: String,
     pub delivery_status: String,
+    pub starred: bool,
+    pub forwarded: bool,
     pub created_at: i64,
 }
 
+impl Message {
+    /// `timestamp` as a date; `None` when the message has none.
+    pub fn datetime_utc(&self) -> Option<DateTime<Utc>> {
+        tina_core::datetime_utc(self.timestamp)
+    }
+
+    pub fn datetime_local(&self) -> Option<DateTime<Local>> {
+        tina_core::datetime_local(self.timestamp)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Group {
     pub account_id: String,
@@ -301,6 +527,25 @@ pub struct Group {
     pub owner_contact_id: Option<String>,
     pub description: Option<String>,
     pub participants_json: Option<String>,
+    /// Last invite link nanachi handed us (admins only).
+    pub invite_link: Option<String>,
+}
+
+/// Group list entry: no participants JSON to parse, just the count
+/// SQLite took from it (`0` while the list hasn't synced).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GroupSummaryRow {
+    pub chat_id: String,
+    pub subject: Option<String>,
+    pub participant_count: i64,
+}
+
+/// A file `evict_media` dropped from the media cache ledger. The
+/// caller deletes it from disk.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct EvictedMedia {
+    pub path: String,
+    pub size_bytes: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -320,9 +565,9 @@ pub struct MentionCandidate {
     /// Raw `<user>@<server>` JID — what we send back over the wire
     /// in `contextInfo.mentionedJID` and key the inventory by.
     pub jid: String,
-    /// Resolved display name (contact_name → push_name → business
-    /// → verified → phone). Falls back to the JID's user-part when
-    /// no contact row exists yet.
+    /// Resolved display name ([`contact_display_name`], then the
+    /// formatted phone). Falls back to the JID's user-part when no
+    /// contact row exists yet.
     pub display_name: String,
     /// Digits portion of the JID, used as the visible mention chip
     /// when the user types `@<digits>`. Whatsapp's wire format
@@ -332,4 +577,54 @@ pub struct MentionCandidate {
     /// Cached avatar path if the worker has already pulled it; the
     /// popover renders a tiny avatar next to each row.
     pub avatar_path: Option<String>,
+    /// Group admin or superadmin per `participants_json`. Always
+    /// `false` for candidates from the message-sender fallback.
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+/// Last history-sync chunk seen for an account, persisted so a restart
+/// mid-sync can tell the user it's resuming instead of starting over.
+/// Cleared when `HistorySyncComplete` lands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub sync_type: String,
+    /// 0..100 as reported by whatsmeow.
+    pub progress: u32,
+    /// Cumulative messages received in the stream so far.
+    pub messages_count: usize,
+    pub updated_at: i64,
+}
+
+/// One page of a list the UI scrolls through. `has_more` is exact: the
+/// query asks for one row past the page, so a full last page doesn't
+/// cost the UI an empty round trip. `next_cursor` is what to ask for
+/// the next page with (an offset, or a keyset for message history);
+/// `None` on the last page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T, C = i64> {
+    pub items: Vec<T>,
+    pub has_more: bool,
+    pub next_cursor: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    /// Builds the page from `rows` fetched with `limit + 1`, in query
+    /// order: the row past `limit`, if it came back, is dropped and
+    /// sets `has_more`. `next_cursor` sees the kept rows.
+    pub fn from_overfetch(
+        mut rows: Vec<T>,
+        limit: i64,
+        next_cursor: impl FnOnce(&[T]) -> Option<C>,
+    ) -> Self {
+        let limit = usize::try_from(limit).unwrap_or(0);
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        let next_cursor = if has_more { next_cursor(&rows) } else { None };
+        Self {
+            items: rows,
+            has_more,
+            next_cursor,
+        }
+    }
 }
@@ -1,14 +1,40 @@
 // Accounts CRUD + identity persistence.
 
+use tina_core::unix_now;
+
 use crate::error::{DbError, Result};
-use crate::models::Account;
+use crate::models::{Account, AccountStats, PurgeOptions};
 
+use super::contacts::CONTACT_COUNT_SQL;
 use super::db::TinaDb;
-use super::util::now_ts;
+use super::groups::GROUP_COUNT_SQL;
+use super::settings::{initial_sync_key, sync_cursor_key};
+
+type PurgeSelector = fn(&PurgeOptions) -> bool;
+
+/// Tabelas com dados sincronizados por conta, na ordem de apagar, e a
+/// opção de `PurgeOptions` que cobre cada uma.
+const ACCOUNT_DATA_TABLES: &[(&str, PurgeSelector)] = &[
+    ("pinned_messages", |o| o.messages),
+    ("messages", |o| o.messages),
+    ("groups", |o| o.groups),
+    ("chat_aliases", |o| o.messages),
+    ("chats", |o| o.messages),
+    ("contact_aliases", |o| o.contacts),
+    ("contacts", |o| o.contacts),
+];
+
+/// Contato do próprio número da conta `?1`.
+const OWN_CONTACT_CTE: &str = "WITH own_contact AS (
+    SELECT ca.contact_id FROM contact_aliases ca
+    JOIN accounts a ON a.id = ca.account_id
+    WHERE ca.account_id = ?1 AND a.phone_number IS NOT NULL
+      AND ca.alias_jid = a.phone_number || '@s.whatsapp.net'
+)";
 
 impl TinaDb {
     pub async fn create_account(&self, id: &str, name: Option<&str>) -> Result<Account> {
-        let now = now_ts();
+        let now = unix_now();
         sqlx::query(
             "INSERT INTO accounts (id, name, created_at, updated_at) VALUES (?, ?, ?, ?)
              ON CONFLICT(id) DO UPDATE SET name = excluded.name, updated_at = excluded.updated_at",
@@ -30,6 +56,23 @@ impl TinaDb {
             .map_err(|_| DbError::AccountNotFound(id.to_string()))
     }
 
+    /// Só o nome de exibição muda; telefone, JID e o pareamento (no
+    /// store do whatsmeow) ficam como estão. Nome em branco volta a
+    /// `None`.
+    pub async fn rename_account(&self, id: &str, new_name: &str) -> Result<Account> {
+        let name = Some(new_name.trim()).filter(|n| !n.is_empty());
+        let res = sqlx::query("UPDATE accounts SET name = ?, updated_at = ? WHERE id = ?")
+            .bind(name)
+            .bind(unix_now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if res.rows_affected() == 0 {
+            return Err(DbError::AccountNotFound(id.to_string()));
+        }
+        self.get_account(id).await
+    }
+
     pub async fn list_accounts(&self) -> Result<Vec<Account>> {
         Ok(
             sqlx::query_as::<_, Account>("SELECT * FROM accounts ORDER BY created_at")
@@ -39,19 +82,30 @@ impl TinaDb {
     }
 
     pub async fn delete_account(&self, account_id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query("DELETE FROM accounts WHERE id = ?")
             .bind(account_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
+        // Uma conta recriada com o mesmo id sincroniza do zero.
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(initial_sync_key(account_id))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Grava número e JID do `Connected`. Mensagens já gravadas cujo
+    /// sender é esse número (ou um alias do contato dele) viram nossas:
+    /// o batch só as reconhece quando o número já é conhecido.
     pub async fn save_account_identity(
         &self,
         account_id: &str,
         phone_number: Option<&str>,
         jid: Option<&str>,
     ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query(
             "UPDATE accounts SET phone_number = COALESCE(?, phone_number),
                                 jid = COALESCE(?, jid),
@@ -59,10 +113,24 @@ impl TinaDb {
         )
         .bind(phone_number)
         .bind(jid)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        for sql in [
+            "UPDATE chats SET last_message_from_me = 1, last_sender_contact_id = NULL
+             WHERE account_id = ?1 AND last_message_from_me = 0
+               AND last_sender_contact_id IN (SELECT contact_id FROM own_contact)",
+            "UPDATE messages SET is_from_me = 1, sender_contact_id = NULL
+             WHERE account_id = ?1 AND is_from_me = 0
+               AND sender_contact_id IN (SELECT contact_id FROM own_contact)",
+        ] {
+            sqlx::query(&format!("{OWN_CONTACT_CTE} {sql}"))
+                .bind(account_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -70,10 +138,89 @@ impl TinaDb {
         sqlx::query(
             "UPDATE accounts SET phone_number = NULL, jid = NULL, updated_at = ? WHERE id = ?",
         )
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
+
+    /// Logout: forgets the paired identity, the pending history-sync
+    /// cursor and the initial-sync flag so the next login starts from a
+    /// clean QR round. With
+    /// `purge`, every chat, contact, group and message of the account
+    /// goes too; the account row itself stays.
+    pub async fn clear_auth_state(&self, account_id: &str, purge: bool) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE accounts SET phone_number = NULL, jid = NULL, updated_at = ? WHERE id = ?",
+        )
+        .bind(unix_now())
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM settings WHERE key IN (?, ?)")
+            .bind(sync_cursor_key(account_id))
+            .bind(initial_sync_key(account_id))
+            .execute(&mut *tx)
+            .await?;
+        if purge {
+            delete_account_data(&mut tx, account_id, PurgeOptions::all()).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// "Clear local data": empties the selected tables for the account
+    /// in one transaction. The account row and auth state stay, so the
+    /// next connect simply syncs again. Wiping messages also drops the
+    /// history-sync cursor, which would otherwise point past them.
+    pub async fn purge_account_data(&self, account_id: &str, opts: PurgeOptions) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        delete_account_data(&mut tx, account_id, opts).await?;
+        if opts.messages {
+            sqlx::query("DELETE FROM settings WHERE key = ?")
+                .bind(sync_cursor_key(account_id))
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Contagens agregadas de uma conta em uma única ida ao banco.
+    /// Erra com `AccountNotFound` se a conta não existe.
+    pub async fn get_account_stats(&self, account_id: &str) -> Result<AccountStats> {
+        self.get_account(account_id).await?;
+        // Mesmas contagens de `get_contact_count` / `get_group_count`.
+        let sql = format!(
+            "SELECT
+                (SELECT COUNT(*) FROM messages WHERE account_id = ?1) AS message_count,
+                (SELECT COUNT(*) FROM chats WHERE account_id = ?1) AS chat_count,
+                ({CONTACT_COUNT_SQL}) AS contact_count,
+                ({GROUP_COUNT_SQL}) AS group_count,
+                (SELECT MAX(timestamp) FROM messages WHERE account_id = ?1) AS last_message_ts"
+        );
+        let stats = sqlx::query_as::<_, AccountStats>(&sql)
+            .bind(account_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(stats)
+    }
+}
+
+async fn delete_account_data(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    account_id: &str,
+    opts: PurgeOptions,
+) -> Result<()> {
+    for (table, selected) in ACCOUNT_DATA_TABLES {
+        if selected(&opts) {
+            sqlx::query(&format!("DELETE FROM {table} WHERE account_id = ?"))
+                .bind(account_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,169 @@
+// Whole-database snapshot + restore. `VACUUM INTO` writes a consistent
+// copy while the app keeps running (WAL readers don't block it).
+// Restore copies the snapshot back table by table inside one
+// transaction on a single pooled connection, so the pool — and every
+// `Arc<TinaDb>` holding it — stays valid.
+//
+// Só funciona com banco em arquivo: a conexão `sqlite::memory:` abre
+// os alvos de VACUUM INTO / ATTACH também em memória.
+
+use std::path::Path;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+
+use crate::error::{DbError, Result};
+use crate::schema::SCHEMA_VERSION;
+
+use super::db::TinaDb;
+
+impl TinaDb {
+    /// Writes a compacted snapshot of the database to `path`. Fails if
+    /// `path` already exists.
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            return Err(DbError::InvalidBackup(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces every row with the contents of the snapshot at `path`.
+    /// The file must be a tina database at the current schema version;
+    /// anything else is rejected before a single row is touched.
+    ///
+    /// Callers must make sure nothing writes concurrently (the worker
+    /// refuses while nanachi is running).
+    pub async fn restore_from(&self, path: &Path) -> Result<()> {
+        validate_backup(path).await?;
+
+        let mut conn = self.pool.acquire().await?;
+        let restored = restore_on(&mut conn, path).await;
+        // A conexão volta pro pool: não pode ir com foreign_keys
+        // desligada nem com o `bak` anexado. Se nem isso der pra
+        // garantir, ela é fechada em vez de devolvida.
+        if restored.is_err() && reset_connection(&mut conn).await.is_err() {
+            conn.close_on_drop();
+        }
+        restored
+    }
+}
+
+async fn restore_on(conn: &mut SqliteConnection, path: &Path) -> Result<()> {
+    // foreign_keys só pode mudar fora de transação. Desliga pra o
+    // DELETE/INSERT não depender da ordem das tabelas.
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("ATTACH DATABASE ? AS bak")
+        .bind(path.to_string_lossy())
+        .execute(&mut *conn)
+        .await?;
+    copy_tables_from_attached(conn).await?;
+    sqlx::query("DETACH DATABASE bak")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Desfaz o que `restore_on` deixou pelo caminho depois de um erro,
+/// em qualquer ponto que ele tenha parado.
+async fn reset_connection(conn: &mut SqliteConnection) -> Result<()> {
+    let attached: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pragma_database_list WHERE name = 'bak')")
+            .fetch_one(&mut *conn)
+            .await?;
+    if attached {
+        sqlx::query("DETACH DATABASE bak")
+            .execute(&mut *conn)
+            .await?;
+    }
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Opens `path` read-only and checks it looks like one of ours.
+async fn validate_backup(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(DbError::InvalidBackup(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+    let opts = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&opts)
+        .await
+        .map_err(|e| DbError::InvalidBackup(format!("{}: {e}", path.display())))?;
+
+    let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(|e| DbError::InvalidBackup(format!("{}: {e}", path.display())))?;
+    if version != SCHEMA_VERSION {
+        return Err(DbError::InvalidBackup(format!(
+            "schema version {version}, expected {SCHEMA_VERSION}"
+        )));
+    }
+    let has_accounts: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'accounts')",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+    if !has_accounts {
+        return Err(DbError::InvalidBackup("missing accounts table".into()));
+    }
+    conn.close().await?;
+    Ok(())
+}
+
+/// Colunas explícitas: bancos migrados via `ALTER TABLE` têm as
+/// colunas novas no fim, bancos novos na ordem do `SCHEMA` — `SELECT *`
+/// embaralharia os valores.
+async fn copy_tables_from_attached(conn: &mut SqliteConnection) -> Result<()> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut tx = conn.begin().await?;
+    for table in &tables {
+        let cols: Vec<String> = sqlx::query_scalar(
+            "SELECT m.name FROM pragma_table_info(?, 'main') m \
+             JOIN pragma_table_info(?, 'bak') b ON b.name = m.name",
+        )
+        .bind(table)
+        .bind(table)
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query(&format!("DELETE FROM main.\"{table}\""))
+            .execute(&mut *tx)
+            .await?;
+        if cols.is_empty() {
+            continue;
+        }
+        let list = cols
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sqlx::query(&format!(
+            "INSERT INTO main.\"{table}\" ({list}) SELECT {list} FROM bak.\"{table}\""
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// Per-chat local preferences: mute and the notification toggle. Pure
+// local state — nothing here goes over IPC or into WhatsApp's app
+// state. A mute is a deadline, not a flag, so it lifts by itself once
+// `muted_until` passes without anyone having to clear it.
+
+use tina_core::unix_now;
+
+use crate::error::Result;
+
+use super::db::TinaDb;
+
+/// `muted_until` for "until I unmute it".
+pub const MUTED_FOREVER: i64 = i64::MAX;
+
+impl TinaDb {
+    /// Mutes `chat_id` until the unix timestamp `until` (see
+    /// [`MUTED_FOREVER`]); `None` unmutes. No-op for unknown chats.
+    pub async fn set_chat_muted(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        until: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE chats SET muted_until = ?, updated_at = ? WHERE account_id = ? AND chat_id = ?",
+        )
+        .bind(until)
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(chat_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// `true` while the chat's mute deadline is in the future.
+    pub async fn is_chat_muted(&self, account_id: &str, chat_id: &str) -> Result<bool> {
+        let muted: Option<bool> = sqlx::query_scalar(
+            "SELECT COALESCE(muted_until > ?, 0) FROM chats WHERE account_id = ? AND chat_id = ?",
+        )
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(chat_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(muted.unwrap_or(false))
+    }
+
+    /// Turns desktop notifications for `chat_id` on or off, independent
+    /// of any mute.
+    pub async fn set_chat_notify(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        notify: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE chats SET notify = ?, updated_at = ? WHERE account_id = ? AND chat_id = ?",
+        )
+        .bind(notify)
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(chat_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 // Chat resolver + display-name / pin / last-message updates +
 // the SELECT clause shared with sidebar row queries.
 
+use std::collections::HashMap;
+
+use tina_core::unix_now;
+
 use crate::error::Result;
-use crate::models::{Chat, ChatKind, ChatRow};
+use crate::models::{Chat, ChatInfo, ChatKind, ChatRow};
 
 use super::aliases::{link_alias_tx, register_chat_alias_tx};
 use super::db::TinaDb;
-use super::util::now_ts;
 
 impl TinaDb {
     /// Registra (ou recupera) um chat para um JID/LID. Idempotente.
@@ -75,10 +78,11 @@ impl TinaDb {
         name: Option<&str>,
     ) -> Result<()> {
         sqlx::query(
-            "UPDATE chats SET display_name = ?, updated_at = ? WHERE account_id = ? AND chat_id = ?",
+            "UPDATE chats SET display_name = NULLIF(TRIM(?), ''), updated_at = ? \
+             WHERE account_id = ? AND chat_id = ?",
         )
         .bind(name)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .execute(&self.pool)
@@ -86,6 +90,79 @@ impl TinaDb {
         Ok(())
     }
 
+    /// Human name for `jid`, or `None` when nothing is known yet.
+    /// Groups and newsletters resolve to the subject (falling back to
+    /// `chats.display_name`); user JIDs go through `contact_aliases`
+    /// and pick the address-book name, then the push name, then the
+    /// verified business name. Empty strings count as unknown.
+    pub async fn get_chat_name(&self, account_id: &str, jid: &str) -> Result<Option<String>> {
+        let sql = match ChatKind::infer_from_jid(jid) {
+            ChatKind::Group | ChatKind::Newsletter => {
+                r#"SELECT COALESCE(NULLIF(TRIM(g.subject), ''), NULLIF(TRIM(c.display_name), ''))
+                   FROM chat_aliases a
+                   LEFT JOIN groups g ON g.account_id = a.account_id AND g.chat_id = a.chat_id
+                   LEFT JOIN chats c ON c.account_id = a.account_id AND c.chat_id = a.chat_id
+                   WHERE a.account_id = ? AND a.alias_jid = ?"#
+            }
+            _ => {
+                r#"SELECT COALESCE(NULLIF(TRIM(ct.contact_name), ''),
+                                   NULLIF(TRIM(ct.verified_name), ''),
+                                   NULLIF(TRIM(ct.push_name), ''),
+                                   NULLIF(TRIM(ct.business_name), ''))
+                   FROM contact_aliases ca
+                   JOIN contacts ct ON ct.account_id = ca.account_id AND ct.contact_id = ca.contact_id
+                   WHERE ca.account_id = ? AND ca.alias_jid = ?"#
+            }
+        };
+        let name: Option<Option<String>> = sqlx::query_scalar(sql)
+            .bind(account_id)
+            .bind(jid)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(name.flatten())
+    }
+
+    /// Header metadata for the chat behind `jid` (any alias). Groups
+    /// carry description + participant count, DMs the contact's phone
+    /// and about text. `None` if no chat is known for `jid`.
+    pub async fn get_chat_info(&self, account_id: &str, jid: &str) -> Result<Option<ChatInfo>> {
+        let row: Option<ChatInfoRow> = sqlx::query_as(
+            r#"SELECT c.chat_id, c.kind, c.avatar_path, c.muted_until, c.notify,
+                      NULLIF(TRIM(g.description), '') AS description,
+                      CASE WHEN g.chat_id IS NULL THEN NULL
+                           ELSE COALESCE(json_array_length(g.participants_json), 0) END
+                        AS participant_count,
+                      ct.phone_number, NULLIF(TRIM(ct.status), '') AS about
+               FROM chat_aliases a
+               JOIN chats c ON c.account_id = a.account_id AND c.chat_id = a.chat_id
+               LEFT JOIN groups g ON g.account_id = c.account_id AND g.chat_id = c.chat_id
+               LEFT JOIN contact_aliases ca
+                 ON c.kind = 'dm' AND ca.account_id = a.account_id AND ca.alias_jid = a.alias_jid
+               LEFT JOIN contacts ct ON ct.account_id = ca.account_id AND ct.contact_id = ca.contact_id
+               WHERE a.account_id = ? AND a.alias_jid = ?"#,
+        )
+        .bind(account_id)
+        .bind(jid)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let name = self.get_chat_name(account_id, jid).await?;
+        Ok(Some(ChatInfo {
+            chat_id: row.chat_id,
+            kind: ChatKind::from_db(&row.kind),
+            name,
+            avatar_path: row.avatar_path,
+            muted_until: row.muted_until,
+            notify: row.notify,
+            description: row.description,
+            participant_count: row.participant_count,
+            phone_number: row.phone_number,
+            about: row.about,
+        }))
+    }
+
     /// Slide `chats.last_read_ts` forward to the chat's most recent
     /// message timestamp (or NOW if the chat is empty), which makes
     /// the auto-derived `unread_count` from `chat_row_select_clause`
@@ -110,7 +187,7 @@ impl TinaDb {
                WHERE account_id = ? AND chat_id = ?"#,
         )
         .bind(last_read_ts)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .execute(&self.pool)
@@ -134,8 +211,8 @@ impl TinaDb {
                  AND COALESCE(last_read_ts, 0)
                      < COALESCE(last_message_ts, 0)"#,
         )
-        .bind(now_ts())
-        .bind(now_ts())
+        .bind(unix_now())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .execute(&self.pool)
@@ -143,6 +220,68 @@ impl TinaDb {
         Ok(res.rows_affected())
     }
 
+    /// `clear_chat_unread` for every chat of the account in one UPDATE.
+    /// Returns how many chats had unread messages.
+    pub async fn mark_all_chats_read(&self, account_id: &str) -> Result<u64> {
+        let res = sqlx::query(
+            r#"UPDATE chats
+               SET last_read_ts = MAX(
+                       COALESCE(last_read_ts, 0),
+                       COALESCE(last_message_ts, ?1)
+                   ),
+                   updated_at = ?1
+               WHERE account_id = ?2
+                 AND COALESCE(last_read_ts, 0)
+                     < COALESCE(last_message_ts, 0)"#,
+        )
+        .bind(unix_now())
+        .bind(account_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    /// Incoming messages past their chat's `last_read_ts` — the ones
+    /// behind the unread badges — as `(chat_id, sender, message_id)`,
+    /// grouped by chat and sender. `sender` is `None` when the row has
+    /// no `sender_contact_id` (DMs from older builds).
+    pub async fn list_unread_incoming(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<(String, Option<String>, String)>> {
+        let rows = sqlx::query_as(
+            r#"SELECT m.chat_id, m.sender_contact_id, m.message_id
+               FROM messages m
+               JOIN chats c ON c.account_id = m.account_id AND c.chat_id = m.chat_id
+               WHERE m.account_id = ?
+                 AND m.is_from_me = 0
+                 AND m.timestamp > COALESCE(c.last_read_ts, 0)
+               ORDER BY m.chat_id, m.sender_contact_id, m.timestamp"#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Unread incoming messages per account, counted like the chat
+    /// list badges (past each chat's `last_read_ts`; the status feed
+    /// left out). Accounts with nothing unread are absent.
+    pub async fn unread_totals(&self) -> Result<HashMap<String, i64>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"SELECT c.account_id, COUNT(*)
+               FROM messages m
+               JOIN chats c ON c.account_id = m.account_id AND c.chat_id = m.chat_id
+               WHERE m.is_from_me = 0
+                 AND m.timestamp > COALESCE(c.last_read_ts, 0)
+                 AND c.kind != 'status'
+               GROUP BY c.account_id"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+
     pub async fn set_chat_pinned(
         &self,
         account_id: &str,
@@ -153,7 +292,7 @@ impl TinaDb {
             "UPDATE chats SET pinned = ?, updated_at = ? WHERE account_id = ? AND chat_id = ?",
         )
         .bind(if pinned { 1 } else { 0 })
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .execute(&self.pool)
@@ -188,7 +327,7 @@ impl TinaDb {
         .bind(timestamp)
         .bind(from_me)
         .bind(sender_contact_id)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .bind(timestamp)
@@ -238,9 +377,10 @@ impl TinaDb {
             )
             SELECT
                 COALESCE(c.pn_jid, c.lid_jid, agg.sender_contact_id) AS sender_jid,
-                COALESCE(NULLIF(c.push_name, ''),
-                         NULLIF(c.contact_name, ''),
+                COALESCE(NULLIF(c.contact_name, ''),
                          NULLIF(c.verified_name, ''),
+                         NULLIF(c.push_name, ''),
+                         NULLIF(c.business_name, ''),
                          NULLIF(c.phone_number, ''),
                          agg.sender_contact_id) AS name,
                 c.avatar_path AS avatar_path,
@@ -260,14 +400,48 @@ impl TinaDb {
             .await?)
     }
 
+    /// Chat list without the `status@broadcast` pseudo-chat; see
+    /// [`Self::list_chat_rows_with`] to include it.
     pub async fn list_chat_rows(&self, account_id: &str) -> Result<Vec<ChatRow>> {
-        let q = chat_row_select_clause(false);
+        self.list_chat_rows_with(account_id, false).await
+    }
+
+    /// `list_chat_rows`, with `include_status` opting the status feed
+    /// row back in.
+    pub async fn list_chat_rows_with(
+        &self,
+        account_id: &str,
+        include_status: bool,
+    ) -> Result<Vec<ChatRow>> {
+        let q = chat_row_select_clause(false, include_status);
         Ok(sqlx::query_as::<_, ChatRow>(&q)
             .bind(account_id)
             .fetch_all(&self.pool)
             .await?)
     }
 
+    /// `list_chat_rows` plus, with `include_contacts`, one `dm` row per
+    /// saved contact (address-book name) that has no chat yet — so a
+    /// freshly synced contact can be opened before any message exists.
+    /// Contact-only rows come after the real chats, sorted by name;
+    /// their `chat_id` is the contact's PN JID (LID when there is no PN),
+    /// the same key `register_chat_alias` will use on first message.
+    pub async fn list_all_chat_rows(
+        &self,
+        account_id: &str,
+        include_contacts: bool,
+    ) -> Result<Vec<ChatRow>> {
+        let mut rows = self.list_chat_rows(account_id).await?;
+        if include_contacts {
+            let contact_only = sqlx::query_as::<_, ChatRow>(CONTACT_ONLY_CHAT_ROWS_SQL)
+                .bind(account_id)
+                .fetch_all(&self.pool)
+                .await?;
+            rows.extend(contact_only);
+        }
+        Ok(rows)
+    }
+
     pub async fn get_chat_rows(
         &self,
         account_id: &str,
@@ -279,7 +453,7 @@ impl TinaDb {
         let placeholders = std::iter::repeat_n("?", chat_ids.len())
             .collect::<Vec<_>>()
             .join(",");
-        let q = chat_row_select_clause(true).replace("__IDS__", &placeholders);
+        let q = chat_row_select_clause(true, false).replace("__IDS__", &placeholders);
         let mut query = sqlx::query_as::<_, ChatRow>(&q).bind(account_id);
         for id in chat_ids {
             query = query.bind(id);
@@ -288,17 +462,53 @@ impl TinaDb {
     }
 }
 
-pub(super) fn chat_row_select_clause(filter_by_ids: bool) -> String {
+/// Saved contacts with no chat alias for either of their JIDs, shaped
+/// as `ChatRow` so callers can append them to the normal list.
+const CONTACT_ONLY_CHAT_ROWS_SQL: &str = r#"SELECT
+     COALESCE(ct.pn_jid, ct.lid_jid) AS chat_id,
+     'dm' AS kind,
+     COALESCE(ct.contact_name, ct.verified_name, ct.push_name, ct.business_name, ct.phone_number, ct.pn_jid, ct.lid_jid) AS name,
+     ct.avatar_url,
+     ct.avatar_path,
+     NULL AS last_message_preview,
+     NULL AS last_message_ts,
+     0 AS last_message_from_me,
+     NULL AS last_message_type,
+     NULL AS last_message_duration_secs,
+     0 AS unread_count,
+     0 AS pinned,
+     NULL AS last_sender_name,
+     0 AS muted
+   FROM contacts ct
+   WHERE ct.account_id = ?
+     AND COALESCE(ct.pn_jid, ct.lid_jid) IS NOT NULL
+     AND TRIM(COALESCE(ct.contact_name, '')) != ''
+     AND NOT EXISTS (
+       SELECT 1 FROM chat_aliases ca
+       WHERE ca.account_id = ct.account_id
+         AND ca.alias_jid IN (ct.pn_jid, ct.lid_jid)
+     )
+   ORDER BY name COLLATE NOCASE"#;
+
+pub(super) fn chat_row_select_clause(filter_by_ids: bool, include_status: bool) -> String {
     // Never surface newsletters that have no resolved display name — they
     // fall back to the raw JID as `name` which renders as "Channel #XXXXX"
     // and provides no useful signal to the user. Once a GroupsUpsert lands
     // with a real name the next get_chat_rows call will return the row.
     let nameless_newsletter =
         "NOT (c.kind = 'newsletter' AND (c.display_name IS NULL OR TRIM(c.display_name) = ''))";
+    // status@broadcast não é conversa: fica fora salvo pedido explícito.
+    let status = if include_status {
+        ""
+    } else {
+        " AND c.kind != 'status'"
+    };
     let where_clause = if filter_by_ids {
-        format!("WHERE c.account_id = ? AND c.chat_id IN (__IDS__) AND {nameless_newsletter}")
+        format!(
+            "WHERE c.account_id = ? AND c.chat_id IN (__IDS__) AND {nameless_newsletter}{status}"
+        )
     } else {
-        format!("WHERE c.account_id = ? AND {nameless_newsletter}")
+        format!("WHERE c.account_id = ? AND {nameless_newsletter}{status}")
     };
     // Two contact JOINs: one resolves the chat itself (DM name +
     // avatar), the second resolves the *sender* of the chat's last
@@ -312,8 +522,8 @@ pub(super) fn chat_row_select_clause(filter_by_ids: bool) -> String {
             COALESCE(
                 c.display_name,
                 ct.contact_name,
-                ct.push_name,
                 ct.verified_name,
+                ct.push_name,
                 ct.business_name,
                 ct.phone_number,
                 c.chat_id
@@ -346,13 +556,14 @@ pub(super) fn chat_row_select_clause(filter_by_ids: bool) -> String {
                 WHEN c.last_sender_contact_id IS NULL THEN NULL
                 ELSE COALESCE(
                     NULLIF(cs.contact_name, ''),
-                    NULLIF(cs.push_name, ''),
                     NULLIF(cs.verified_name, ''),
+                    NULLIF(cs.push_name, ''),
                     NULLIF(cs.business_name, ''),
                     NULLIF(cs.phone_number, ''),
                     c.last_sender_contact_id
                 )
-            END AS last_sender_name
+            END AS last_sender_name,
+            COALESCE(c.muted_until > CAST(strftime('%s', 'now') AS INTEGER), 0) AS muted
            FROM chats c
            LEFT JOIN contact_aliases ca
                   ON ca.account_id = c.account_id AND ca.alias_jid = c.chat_id
@@ -362,6 +573,20 @@ pub(super) fn chat_row_select_clause(filter_by_ids: bool) -> String {
                   ON cs.account_id = c.account_id
                  AND cs.contact_id = c.last_sender_contact_id
            {where_clause}
-           ORDER BY c.last_message_ts DESC NULLS LAST, c.updated_at DESC"#,
+           -- Mesma ordem de `ChatOrderKey`.
+           ORDER BY c.pinned DESC, COALESCE(c.last_message_ts, 0) DESC, name, c.chat_id"#,
     )
 }
+
+#[derive(sqlx::FromRow)]
+struct ChatInfoRow {
+    chat_id: String,
+    kind: String,
+    avatar_path: Option<String>,
+    muted_until: Option<i64>,
+    notify: bool,
+    description: Option<String>,
+    participant_count: Option<i64>,
+    phone_number: Option<String>,
+    about: Option<String>,
+}
@@ -1,14 +1,19 @@
 // Contacts: alias registration, individual upsert, batch upsert,
-// directory listing.
+// directory listing and paged search.
 
 use std::collections::HashMap;
 
+use tina_core::unix_now;
+
 use crate::error::Result;
 use crate::models::{ChatKind, Contact};
 
 use super::aliases::{link_alias_tx, register_contact_alias_tx};
 use super::db::TinaDb;
-use super::util::{derive_pn_lid, now_ts, repeat_csv};
+use super::util::{derive_pn_lid, like_pattern, repeat_csv};
+
+/// Contatos da conta `?1`. Também é subquery de `get_account_stats`.
+pub(super) const CONTACT_COUNT_SQL: &str = "SELECT COUNT(*) FROM contacts WHERE account_id = ?1";
 
 impl TinaDb {
     pub async fn register_contact_alias(
@@ -38,6 +43,12 @@ impl TinaDb {
     }
 
     /// Atualiza campos do contato preservando valores não-nulos pré-existentes.
+    /// `is_local` diz quem escreve: `true` é o usuário, `false` é o
+    /// servidor. Nome escrito pelo usuário fica marcado
+    /// (`name_is_local`) e o servidor não o troca mais, nem depois do
+    /// contato deixar de ser local. O usuário só marca o contato como
+    /// local enquanto o servidor não tem push/verified/business name
+    /// dele; push ou verified name vindos do servidor o tiram de local.
     pub async fn upsert_contact_fields(
         &self,
         account_id: &str,
@@ -55,18 +66,31 @@ impl TinaDb {
     ) -> Result<()> {
         sqlx::query(
             r#"UPDATE contacts SET
-                 pn_jid = COALESCE(?, pn_jid),
-                 lid_jid = COALESCE(?, lid_jid),
-                 phone_number = COALESCE(?, phone_number),
-                 push_name = COALESCE(?, push_name),
-                 contact_name = COALESCE(?, contact_name),
-                 business_name = COALESCE(?, business_name),
-                 verified_name = COALESCE(?, verified_name),
-                 avatar_url = COALESCE(?, avatar_url),
-                 status = COALESCE(?, status),
-                 is_local = ?,
-                 updated_at = ?
-               WHERE account_id = ? AND contact_id = ?"#,
+                 pn_jid = COALESCE(?1, pn_jid),
+                 lid_jid = COALESCE(?2, lid_jid),
+                 phone_number = COALESCE(?3, phone_number),
+                 push_name = COALESCE(NULLIF(TRIM(?4), ''), push_name),
+                 contact_name = CASE
+                   WHEN ?10 = 0 AND name_is_local = 1 THEN contact_name
+                   ELSE COALESCE(NULLIF(TRIM(?5), ''), contact_name)
+                 END,
+                 name_is_local = CASE
+                   WHEN ?10 = 1 AND NULLIF(TRIM(?5), '') IS NOT NULL THEN 1
+                   ELSE name_is_local
+                 END,
+                 business_name = COALESCE(NULLIF(TRIM(?6), ''), business_name),
+                 verified_name = COALESCE(NULLIF(TRIM(?7), ''), verified_name),
+                 avatar_url = COALESCE(?8, avatar_url),
+                 status = COALESCE(?9, status),
+                 is_local = CASE
+                   WHEN ?10 = 1 THEN
+                     COALESCE(NULLIF(TRIM(?4), ''), push_name, NULLIF(TRIM(?7), ''),
+                              verified_name, NULLIF(TRIM(?6), ''), business_name) IS NULL
+                   WHEN NULLIF(TRIM(?4), '') IS NOT NULL OR NULLIF(TRIM(?7), '') IS NOT NULL THEN 0
+                   ELSE is_local
+                 END,
+                 updated_at = ?11
+               WHERE account_id = ?12 AND contact_id = ?13"#,
         )
         .bind(pn_jid)
         .bind(lid_jid)
@@ -78,7 +102,7 @@ impl TinaDb {
         .bind(avatar_url)
         .bind(status)
         .bind(is_local)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(contact_id)
         .execute(&self.pool)
@@ -86,6 +110,41 @@ impl TinaDb {
         Ok(())
     }
 
+    /// Grava o perfil buscado no servidor para `jid` (criando o contato
+    /// se preciso) e devolve o `contact_id`. Foto e recado são
+    /// sobrescritos: `None` quer dizer que a privacidade do contato os
+    /// esconde agora, e o valor antigo não vale mais. O nome só entra
+    /// quando veio.
+    pub async fn set_contact_profile(
+        &self,
+        account_id: &str,
+        jid: &str,
+        img_url: Option<&str>,
+        status: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<String> {
+        let mut tx = self.pool.begin().await?;
+        let contact_id = register_contact_alias_tx(&mut tx, account_id, jid).await?;
+        sqlx::query(
+            r#"UPDATE contacts SET
+                 avatar_url = ?,
+                 status = ?,
+                 push_name = COALESCE(NULLIF(TRIM(?), ''), push_name),
+                 updated_at = ?
+               WHERE account_id = ? AND contact_id = ?"#,
+        )
+        .bind(img_url)
+        .bind(status)
+        .bind(name)
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(&contact_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(contact_id)
+    }
+
     pub async fn get_contact(&self, account_id: &str, contact_id: &str) -> Result<Option<Contact>> {
         Ok(sqlx::query_as::<_, Contact>(
             "SELECT * FROM contacts WHERE account_id = ? AND contact_id = ?",
@@ -115,9 +174,58 @@ impl TinaDb {
 
     pub async fn list_contacts(&self, account_id: &str) -> Result<Vec<Contact>> {
         Ok(sqlx::query_as::<_, Contact>(
-            "SELECT * FROM contacts WHERE account_id = ? ORDER BY COALESCE(contact_name, push_name, phone_number, contact_id)",
+            "SELECT * FROM contacts WHERE account_id = ? ORDER BY COALESCE(contact_name, push_name, phone_number, contact_id), contact_id",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Number of rows `list_contacts` would return, without loading them.
+    pub async fn get_contact_count(&self, account_id: &str) -> Result<i64> {
+        Ok(sqlx::query_scalar(CONTACT_COUNT_SQL)
+            .bind(account_id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    /// One page of the contact directory, in `list_contacts` order.
+    /// `query` matches (case-insensitively, as a substring) the saved
+    /// name, push name, business / verified name or phone number; a
+    /// query with digits in it also matches the phone ignoring `+`,
+    /// spaces and dashes. `None` or a blank query lists everyone.
+    pub async fn search_contacts(
+        &self,
+        account_id: &str,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Contact>> {
+        let query = query.map(str::trim).filter(|q| !q.is_empty());
+        let pattern = query.map(like_pattern);
+        // "+55 11 9…" não casaria com o `phone_number` só de dígitos.
+        let digits = query
+            .map(|q| q.chars().filter(char::is_ascii_digit).collect::<String>())
+            .filter(|d| !d.is_empty())
+            .map(|d| format!("%{d}%"));
+        Ok(sqlx::query_as::<_, Contact>(
+            r#"SELECT * FROM contacts
+               WHERE account_id = ?1
+                 AND (?2 IS NULL
+                      OR contact_name LIKE ?2 ESCAPE '\'
+                      OR push_name LIKE ?2 ESCAPE '\'
+                      OR business_name LIKE ?2 ESCAPE '\'
+                      OR verified_name LIKE ?2 ESCAPE '\'
+                      OR phone_number LIKE ?2 ESCAPE '\'
+                      OR phone_number LIKE ?3)
+               ORDER BY COALESCE(contact_name, push_name, phone_number, contact_id), contact_id
+               LIMIT ?4 OFFSET ?5"#,
         )
         .bind(account_id)
+        .bind(pattern)
+        .bind(digits)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await?)
     }
@@ -126,7 +234,7 @@ impl TinaDb {
     /// Antes: 4 statements/contato (lookup + maybe insert + update). Aqui:
     /// 1 SELECT bulk pra mapear aliases existentes + 1 INSERT…UPSERT chunked
     /// pros contatos + 1 INSERT…DO NOTHING chunked pros aliases.
-    pub async fn run_contacts_batch(
+    pub async fn upsert_contacts_batch(
         &self,
         account_id: &str,
         contacts: &[crate::ContactBatchInput<'_>],
@@ -160,7 +268,8 @@ impl TinaDb {
             resolved_ids.push(cid);
         }
 
-        // 4. Bulk UPSERT em `contacts`.
+        // 4. Bulk UPSERT em `contacts`. Contato local mantém o nome
+        //    salvo à mão; vira não-local quando chega push/verified name.
         upsert_contacts_chunked(&mut tx, account_id, contacts, &resolved_ids).await?;
 
         // 5. Bulk INSERT em `contact_aliases`.
@@ -206,9 +315,11 @@ async fn upsert_contacts_chunked(
     resolved_ids: &[String],
 ) -> Result<()> {
     const CONTACT_CHUNK: usize = 200;
-    let now = now_ts();
+    let now = unix_now();
     for (chunk_idx, chunk) in contacts.chunks(CONTACT_CHUNK).enumerate() {
-        let row_tpl = "(?,?,?,?,?,?,?,?,?,?,?,?,?)";
+        // Nome em branco vira NULL já no VALUES: `excluded.*` chega nulo.
+        let row_tpl =
+            "(?,?,?,?,?,NULLIF(TRIM(?),''),NULLIF(TRIM(?),''),NULLIF(TRIM(?),''),?,?,?,?,?)";
         let mut sql = String::from(
             "INSERT INTO contacts (account_id, contact_id, pn_jid, lid_jid, phone_number, push_name, contact_name, verified_name, avatar_url, status, is_local, created_at, updated_at) VALUES ",
         );
@@ -219,10 +330,18 @@ async fn upsert_contacts_chunked(
                 lid_jid = COALESCE(excluded.lid_jid, contacts.lid_jid),
                 phone_number = COALESCE(excluded.phone_number, contacts.phone_number),
                 push_name = COALESCE(excluded.push_name, contacts.push_name),
-                contact_name = COALESCE(excluded.contact_name, contacts.contact_name),
+                contact_name = CASE
+                  WHEN contacts.name_is_local = 1 THEN contacts.contact_name
+                  ELSE COALESCE(excluded.contact_name, contacts.contact_name)
+                END,
                 verified_name = COALESCE(excluded.verified_name, contacts.verified_name),
                 avatar_url = COALESCE(excluded.avatar_url, contacts.avatar_url),
                 status = COALESCE(excluded.status, contacts.status),
+                is_local = CASE
+                  WHEN excluded.push_name IS NOT NULL
+                    OR excluded.verified_name IS NOT NULL THEN 0
+                  ELSE contacts.is_local
+                END,
                 updated_at = excluded.updated_at"#,
         );
         let mut q = sqlx::query(&sql);
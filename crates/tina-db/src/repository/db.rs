@@ -3,15 +3,39 @@
 use directories::ProjectDirs;
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::{Pool, Sqlite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::{DbError, Result};
 use crate::schema::{
     MIGRATION_V2_TO_V3, MIGRATION_V3_TO_V4, MIGRATION_V4_TO_V5, MIGRATION_V5_TO_V6,
-    MIGRATION_V6_TO_V7, MIGRATION_V7_TO_V8, MIGRATION_V8_TO_V9, SCHEMA, SCHEMA_DROP,
-    SCHEMA_VERSION,
+    MIGRATION_V6_TO_V7, MIGRATION_V7_TO_V8, MIGRATION_V8_TO_V9, MIGRATION_V9_TO_V10,
+    MIGRATION_V10_TO_V11, MIGRATION_V11_TO_V12, MIGRATION_V12_TO_V13, MIGRATION_V13_TO_V14,
+    MIGRATION_V14_TO_V15, MIGRATION_V15_TO_V16, MIGRATION_V16_TO_V17, MIGRATION_V17_TO_V18,
+    MIGRATION_V18_TO_V19, SCHEMA, SCHEMA_DROP, SCHEMA_VERSION,
 };
 
+/// Variável de ambiente que sobrescreve o caminho padrão do banco.
+/// Útil pra rodar instâncias isoladas (testes, install portátil).
+pub const DB_PATH_ENV: &str = "TINA_DB_PATH";
+
+/// Variável de ambiente que troca o diretório base de todo o estado do
+/// app: banco, mídia, avatares, preferências e a sessão do whatsmeow
+/// (o nanachi herda o ambiente). Um valor por perfil isolado.
+pub const DATA_DIR_ENV: &str = "TINA_DATA_DIR";
+
+/// Diretório base do estado do app: `TINA_DATA_DIR` quando definida e
+/// não vazia, senão o diretório de dados da plataforma. `None` só se
+/// nem o `$HOME` der para descobrir.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    ProjectDirs::from("com.br", "zesmoi", "tina").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Path aceito por `new_with_path` / `TINA_DB_PATH` pra banco em memória.
+const MEMORY_PATH: &str = ":memory:";
+
 pub struct TinaDb {
     pub(super) pool: Pool<Sqlite>,
 }
@@ -19,25 +43,55 @@ pub struct TinaDb {
 impl TinaDb {
     pub async fn new() -> Result<Self> {
         let db_path = Self::get_db_path()?;
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent).ok();
+        Self::open_at(&db_path).await
+    }
+
+    /// Abre o banco em `path` quando informado; senão cai no caminho
+    /// padrão (`TINA_DB_PATH` ou o diretório de dados do usuário).
+    pub async fn new_with_optional_path(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(p) => Self::open_at(p).await,
+            None => Self::new().await,
         }
-        let url = format!("sqlite:{}?mode=rwc", db_path.display());
-        Self::open(&url).await
     }
 
+    /// `":memory:"` gives a private in-memory database that still goes
+    /// through migrations and `user_version`, unlike [`Self::in_memory`].
     pub async fn new_with_path(path: &str) -> Result<Self> {
+        if path == MEMORY_PATH {
+            // Uma conexão só, e que nunca fecha: cada conexão `:memory:`
+            // é um banco próprio, e o pool reciclando a conexão ociosa
+            // (ou velha) jogaria o banco fora.
+            let options = SqlitePoolOptions::new()
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+            return Self::open_with("sqlite::memory:", options).await;
+        }
         let url = format!("sqlite:{}?mode=rwc", path);
         Self::open(&url).await
     }
 
+    async fn open_at(db_path: &Path) -> Result<Self> {
+        if db_path == Path::new(MEMORY_PATH) {
+            return Self::new_with_path(MEMORY_PATH).await;
+        }
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        let url = format!("sqlite:{}?mode=rwc", db_path.display());
+        Self::open(&url).await
+    }
+
     /// Abre (ou cria) um pool, garantindo o schema na versão atual.
     /// Quando `user_version` não bate, dropamos tudo e recriamos.
     pub async fn open(url: &str) -> Result<Self> {
-        let pool = SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect(url)
-            .await?;
+        Self::open_with(url, SqlitePoolOptions::new().max_connections(5)).await
+    }
+
+    async fn open_with(url: &str, options: SqlitePoolOptions) -> Result<Self> {
+        let pool = options.connect(url).await?;
 
         configure_pragmas(&pool).await?;
         migrate(&pool).await?;
@@ -62,10 +116,15 @@ impl TinaDb {
         Ok(Self { pool })
     }
 
-    fn get_db_path() -> Result<PathBuf> {
-        let dirs = ProjectDirs::from("com.br", "zesmoi", "tina")
+    /// Caminho do banco: `TINA_DB_PATH` quando definida e não vazia,
+    /// senão `<data_dir>/tina.db` (ver [`data_dir`]).
+    pub fn get_db_path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os(DB_PATH_ENV).filter(|v| !v.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        let dir = data_dir()
             .ok_or_else(|| DbError::AccountNotFound("Could not find project dirs".into()))?;
-        Ok(dirs.data_dir().join("tina.db"))
+        Ok(dir.join("tina.db"))
     }
 
     pub fn pool(&self) -> &Pool<Sqlite> {
@@ -124,6 +183,16 @@ async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
             sqlx::raw_sql(MIGRATION_V6_TO_V7).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         3 => {
@@ -134,6 +203,16 @@ async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
             sqlx::raw_sql(MIGRATION_V6_TO_V7).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         4 => {
@@ -143,6 +222,16 @@ async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
             sqlx::raw_sql(MIGRATION_V6_TO_V7).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         5 => {
@@ -151,6 +240,16 @@ async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
             sqlx::raw_sql(MIGRATION_V6_TO_V7).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         6 => {
@@ -158,17 +257,154 @@ async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
             sqlx::raw_sql(MIGRATION_V6_TO_V7).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         7 => {
-            tracing::info!("Migrating tina.db from v7 → v9 (delivery_status + last_read_ts)");
+            tracing::info!("Migrating tina.db from v7 → v19");
             sqlx::raw_sql(MIGRATION_V7_TO_V8).execute(pool).await?;
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         8 => {
-            tracing::info!("Migrating tina.db from v8 → v9 (last_read_ts)");
+            tracing::info!("Migrating tina.db from v8 → v19");
             sqlx::raw_sql(MIGRATION_V8_TO_V9).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        9 => {
+            tracing::info!("Migrating tina.db from v9 → v19");
+            sqlx::raw_sql(MIGRATION_V9_TO_V10).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        10 => {
+            tracing::info!("Migrating tina.db from v10 → v19");
+            sqlx::raw_sql(MIGRATION_V10_TO_V11).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        11 => {
+            tracing::info!("Migrating tina.db from v11 → v19");
+            sqlx::raw_sql(MIGRATION_V11_TO_V12).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        12 => {
+            tracing::info!(
+                "Migrating tina.db from v12 → v19 (forwarded, notify, invite_link, media_files, shared media ledger, local names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V12_TO_V13).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        13 => {
+            tracing::info!(
+                "Migrating tina.db from v13 → v19 (notify, invite_link, media_files, shared media ledger, local names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V13_TO_V14).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        14 => {
+            tracing::info!(
+                "Migrating tina.db from v14 → v19 (invite_link, media_files, shared media ledger, local names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V14_TO_V15).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        15 => {
+            tracing::info!(
+                "Migrating tina.db from v15 → v19 (media_files, shared media ledger, local names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V15_TO_V16).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        16 => {
+            tracing::info!(
+                "Migrating tina.db from v16 → v19 (shared media ledger, local names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V16_TO_V17).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        17 => {
+            tracing::info!(
+                "Migrating tina.db from v17 → v19 (local contact names, rekeyed copies)"
+            );
+            sqlx::raw_sql(MIGRATION_V17_TO_V18).execute(pool).await?;
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
+            sqlx::raw_sql(SCHEMA).execute(pool).await?;
+        }
+        18 => {
+            tracing::info!("Migrating tina.db from v18 → v19 (rekeyed copies)");
+            sqlx::raw_sql(MIGRATION_V18_TO_V19).execute(pool).await?;
             sqlx::raw_sql(SCHEMA).execute(pool).await?;
         }
         other => {
@@ -2,11 +2,18 @@
 
 use std::collections::HashSet;
 
+use tina_core::unix_now;
+
 use crate::error::Result;
-use crate::models::ChatKind;
+use crate::models::{ChatKind, GroupSummaryRow};
 
 use super::db::TinaDb;
-use super::util::{derive_pn_lid, now_ts, repeat_csv};
+use super::util::{derive_pn_lid, repeat_csv};
+
+/// Chats de grupo da conta `?1` — conta os que já têm chat, mesmo sem
+/// metadados em `groups`. Também é subquery de `get_account_stats`.
+pub(super) const GROUP_COUNT_SQL: &str =
+    "SELECT COUNT(*) FROM chats WHERE account_id = ?1 AND kind = 'group'";
 
 impl TinaDb {
     pub async fn upsert_group(
@@ -38,6 +45,111 @@ impl TinaDb {
         Ok(())
     }
 
+    /// Guarda o link de convite do grupo. Cria a linha em `groups` se o
+    /// grupo ainda não veio em nenhum `GroupsUpsert`.
+    pub async fn set_group_invite_link(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        link: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO groups (account_id, chat_id, invite_link) VALUES (?, ?, ?)
+               ON CONFLICT(account_id, chat_id) DO UPDATE SET invite_link = excluded.invite_link"#,
+        )
+        .bind(account_id)
+        .bind(chat_id)
+        .bind(link)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_group_invite_link(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+    ) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT invite_link FROM groups WHERE account_id = ? AND chat_id = ?",
+        )
+        .bind(account_id)
+        .bind(chat_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten())
+    }
+
+    /// Number of group chats of the account, without loading them.
+    pub async fn get_group_count(&self, account_id: &str) -> Result<i64> {
+        Ok(sqlx::query_scalar(GROUP_COUNT_SQL)
+            .bind(account_id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    /// Grupos da conta com o número de participantes contado pelo
+    /// próprio SQLite (`json_array_length`), sem trazer o JSON. Ordem
+    /// alfabética pelo assunto; sem assunto vão para o fim.
+    pub async fn list_groups_summary(&self, account_id: &str) -> Result<Vec<GroupSummaryRow>> {
+        Ok(sqlx::query_as::<_, GroupSummaryRow>(
+            r#"SELECT chat_id, NULLIF(TRIM(subject), '') AS subject,
+                      COALESCE(json_array_length(participants_json), 0) AS participant_count
+               FROM groups
+               WHERE account_id = ?
+               ORDER BY subject IS NULL, subject COLLATE NOCASE, chat_id"#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Groups whose `participants_json` lists `contact_jid`, in the
+    /// same shape and order as `list_groups_summary`. Participants are
+    /// objects (`{"id", "phone_number", "admin"}`) and `id` is often the
+    /// LID, so the match also goes through the contact's other aliases
+    /// and through `phone_number` (bare digits of the PN). Groups not
+    /// synced yet (NULL or broken JSON) never match.
+    pub async fn get_common_groups(
+        &self,
+        account_id: &str,
+        contact_jid: &str,
+    ) -> Result<Vec<GroupSummaryRow>> {
+        Ok(sqlx::query_as::<_, GroupSummaryRow>(
+            r#"WITH ids(jid) AS (
+                   SELECT ?2
+                   UNION
+                   SELECT other.alias_jid
+                   FROM contact_aliases ca
+                   JOIN contact_aliases other
+                     ON other.account_id = ca.account_id AND other.contact_id = ca.contact_id
+                   WHERE ca.account_id = ?1 AND ca.alias_jid = ?2
+               ),
+               phones(digits) AS (
+                   SELECT substr(jid, 1, instr(jid, '@') - 1) FROM ids
+                   WHERE jid LIKE '%@s.whatsapp.net' OR jid LIKE '%@c.us'
+               )
+               SELECT g.chat_id, NULLIF(TRIM(g.subject), '') AS subject,
+                      json_array_length(g.participants_json) AS participant_count
+               FROM groups g
+               WHERE g.account_id = ?1
+                 AND json_valid(g.participants_json)
+                 AND EXISTS (
+                     SELECT 1 FROM json_each(g.participants_json) p
+                     WHERE (p.type = 'object'
+                            AND (json_extract(p.value, '$.id') IN (SELECT jid FROM ids)
+                                 OR json_extract(p.value, '$.phone_number') IN (SELECT digits FROM phones)))
+                        -- Snapshot antigo: lista de JIDs soltos.
+                        OR (p.type = 'text' AND p.value IN (SELECT jid FROM ids))
+                 )
+               ORDER BY subject IS NULL, subject COLLATE NOCASE, g.chat_id"#,
+        )
+        .bind(account_id)
+        .bind(contact_jid)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
     /// Aplica grupos/newsletters em UMA transação **com multi-row INSERT**.
     /// Cada grupo gera operações em 5 tabelas (chats, chat_aliases, groups,
     /// contacts pra participantes, contact_aliases pra participantes).
@@ -51,7 +163,7 @@ impl TinaDb {
             return Ok(Vec::new());
         }
         let mut tx = self.pool.begin().await?;
-        let now = now_ts();
+        let now = unix_now();
 
         upsert_chats_for_groups(&mut tx, account_id, groups, now).await?;
         insert_chat_self_aliases(&mut tx, account_id, groups).await?;
@@ -1,7 +1,11 @@
 // Media metadata: status flips, dedup-aware download apply, sha256
-// lookup, and avatar path persistence.
+// lookup, avatar path persistence, and the `media_files` ledger the
+// cache budget evicts from.
+
+use tina_core::unix_now;
 
 use crate::error::Result;
+use crate::models::EvictedMedia;
 
 use super::db::TinaDb;
 
@@ -31,6 +35,9 @@ impl TinaDb {
     /// settings dialog's "Clear media cache" action right after the
     /// files themselves have been deleted on disk.
     pub async fn clear_all_media_paths(&self) -> Result<u64> {
+        sqlx::query("DELETE FROM media_files")
+            .execute(&self.pool)
+            .await?;
         let res = sqlx::query(
             "UPDATE messages SET media_path = NULL, media_status = NULL \
              WHERE media_path IS NOT NULL OR media_status IS NOT NULL",
@@ -250,4 +257,112 @@ impl TinaDb {
         tx.commit().await?;
         Ok(())
     }
+
+    /// Registra um arquivo recém-baixado na cache (ou o re-baixado por
+    /// cima de um antigo), já contando como acessado agora. O arquivo é
+    /// da cache, não de uma conta: a segunda conta que baixa o mesmo
+    /// sha256 só renova a linha.
+    pub async fn record_media(
+        &self,
+        path: &str,
+        mimetype: Option<&str>,
+        size_bytes: i64,
+    ) -> Result<()> {
+        let now = unix_now();
+        sqlx::query(
+            r#"INSERT INTO media_files
+               (path, mimetype, size_bytes, downloaded_at, accessed_at)
+               VALUES (?, ?, ?, ?, ?)
+               ON CONFLICT(path) DO UPDATE SET
+                 mimetype = COALESCE(excluded.mimetype, mimetype),
+                 size_bytes = excluded.size_bytes,
+                 downloaded_at = excluded.downloaded_at,
+                 accessed_at = excluded.accessed_at"#,
+        )
+        .bind(path)
+        .bind(mimetype)
+        .bind(size_bytes)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Local path of a message's downloaded media, if any. Doesn't
+    /// check the disk or count as an access — see `touch_media`.
+    pub async fn get_media_path(
+        &self,
+        account_id: &str,
+        message_id: &str,
+    ) -> Result<Option<String>> {
+        let path: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT media_path FROM messages WHERE account_id = ? AND message_id = ?",
+        )
+        .bind(account_id)
+        .bind(message_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(path.flatten())
+    }
+
+    /// Marks a cached file as just used, pushing it to the back of the
+    /// eviction order.
+    pub async fn touch_media(&self, path: &str) -> Result<()> {
+        sqlx::query("UPDATE media_files SET accessed_at = ? WHERE path = ?")
+            .bind(unix_now())
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Bytes recorded in the media cache. Each file counts once, however
+    /// many accounts' messages point at it.
+    pub async fn media_cache_size(&self) -> Result<i64> {
+        let total: i64 = sqlx::query_scalar("SELECT COALESCE(SUM(size_bytes), 0) FROM media_files")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(total)
+    }
+
+    /// Mantém a cache em até `max_bytes`: fica com os arquivos usados
+    /// mais recentemente e esquece o resto — some do ledger e as
+    /// mensagens que apontavam pra eles, de qualquer conta, voltam a
+    /// `none` (baixáveis de novo). Os arquivos em disco são problema do
+    /// chamador.
+    pub async fn evict_media(&self, max_bytes: i64) -> Result<Vec<EvictedMedia>> {
+        let mut tx = self.pool.begin().await?;
+        let files: Vec<EvictedMedia> = sqlx::query_as(
+            "SELECT path, size_bytes FROM media_files
+             ORDER BY accessed_at DESC, downloaded_at DESC, rowid DESC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut kept = 0i64;
+        let mut evicted = Vec::new();
+        for file in files {
+            // Estrito: depois do primeiro que não cabe, saem todos os
+            // mais antigos, mesmo os pequenos.
+            if evicted.is_empty() && kept + file.size_bytes <= max_bytes {
+                kept += file.size_bytes;
+                continue;
+            }
+            sqlx::query("DELETE FROM media_files WHERE path = ?")
+                .bind(&file.path)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "UPDATE messages SET media_path = NULL, media_status = 'none'
+                 WHERE media_path = ?",
+            )
+            .bind(&file.path)
+            .execute(&mut *tx)
+            .await?;
+            evicted.push(file);
+        }
+        tx.commit().await?;
+        Ok(evicted)
+    }
 }
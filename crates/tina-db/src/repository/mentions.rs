@@ -7,8 +7,10 @@
 
 use std::collections::HashMap;
 
+use tina_core::{format_jid_or_phone, is_admin_role};
+
 use crate::error::Result;
-use crate::models::MentionCandidate;
+use crate::models::{MentionCandidate, contact_display_name};
 
 use super::db::TinaDb;
 use super::util::repeat_csv;
@@ -22,6 +24,9 @@ struct ParsedParticipant {
     id: String,
     #[serde(default)]
     phone_number: Option<String>,
+    /// `admin` / `superadmin`; absent for plain members.
+    #[serde(default)]
+    admin: Option<String>,
 }
 
 /// Row shape pulled by the bulk contacts JOIN. Matches the column
@@ -86,6 +91,7 @@ impl TinaDb {
                 .map(|(id,)| ParsedParticipant {
                     id,
                     phone_number: None,
+                    admin: None,
                 })
                 .collect();
         }
@@ -137,13 +143,15 @@ impl TinaDb {
             let user_part = p.id.split('@').next().unwrap_or(&p.id).to_string();
             let (display_name, phone, avatar_path) = match by_alias.get(&p.id) {
                 Some((_, contact_name, push, biz, verified, phone, avatar)) => {
-                    let display = contact_name
-                        .clone()
-                        .or_else(|| push.clone())
-                        .or_else(|| biz.clone())
-                        .or_else(|| verified.clone())
-                        .or_else(|| phone.clone())
-                        .unwrap_or_else(|| user_part.clone());
+                    let display = contact_display_name(
+                        contact_name.as_deref(),
+                        verified.as_deref(),
+                        push.as_deref(),
+                        biz.as_deref(),
+                    )
+                    .map(str::to_string)
+                    .or_else(|| phone.as_deref().map(format_jid_or_phone))
+                    .unwrap_or_else(|| user_part.clone());
                     let phone = phone
                         .clone()
                         .or_else(|| p.phone_number.clone())
@@ -151,8 +159,13 @@ impl TinaDb {
                     (display, phone, avatar.clone())
                 }
                 None => {
+                    // Sem telefone, `user_part` pode ser um LID: fica cru.
+                    let display = p
+                        .phone_number
+                        .as_deref()
+                        .map_or_else(|| user_part.clone(), format_jid_or_phone);
                     let phone = p.phone_number.clone().unwrap_or_else(|| user_part.clone());
-                    (phone.clone(), phone, None)
+                    (display, phone, None)
                 }
             };
             out.push(MentionCandidate {
@@ -160,6 +173,7 @@ impl TinaDb {
                 display_name,
                 phone,
                 avatar_path,
+                is_admin: is_admin_role(p.admin.as_deref()),
             });
         }
         // Stable order by display name so the popover doesn't shuffle
@@ -3,11 +3,10 @@
 // (last message, avatars, etc) lands on a single record.
 
 use sqlx::{Sqlite, Transaction};
+use tina_core::unix_now;
 
 use crate::error::Result;
 
-use super::util::now_ts;
-
 pub(super) async fn merge_chats_tx(
     tx: &mut Transaction<'_, Sqlite>,
     account_id: &str,
@@ -90,7 +89,7 @@ pub(super) async fn merge_contacts_tx(
     .bind(account_id)
     .bind(loser)
     .bind(winner)
-    .bind(now_ts())
+    .bind(unix_now())
     .execute(&mut **tx)
     .await?;
     sqlx::query("DELETE FROM contacts WHERE account_id = ? AND contact_id = ?")
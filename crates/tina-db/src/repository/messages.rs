@@ -1,10 +1,14 @@
 // Single-message insertion and read-paths used by the worker / UI.
 
+use std::collections::HashMap;
+
+use tina_core::unix_now;
+
 use crate::error::Result;
 use crate::models::{Message, MessageRow};
 
 use super::db::TinaDb;
-use super::util::now_ts;
+use super::messages_batch::preview_placeholder;
 
 impl TinaDb {
     /// Insere mensagem já com chat_id/sender resolvidos. Retorna `true`
@@ -35,7 +39,7 @@ impl TinaDb {
         .bind(timestamp)
         .bind(is_from_me)
         .bind(raw_json)
-        .bind(now_ts())
+        .bind(unix_now())
         .execute(&self.pool)
         .await?;
         Ok(res.rows_affected() > 0)
@@ -65,12 +69,205 @@ impl TinaDb {
         .bind(content)
         .bind(timestamp)
         .bind(mentions_json)
-        .bind(now_ts())
+        .bind(unix_now())
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    /// Replaces the text of an already stored message after the sender
+    /// edited it, and refreshes the chat preview when it was the last
+    /// message. Returns the message's `chat_id`, or `None` when the
+    /// message isn't stored (yet) or `edited_at` is older than an edit
+    /// already applied — both are safe to drop.
+    pub async fn edit_message_content(
+        &self,
+        account_id: &str,
+        message_id: &str,
+        new_content: &str,
+        edited_at: i64,
+    ) -> Result<Option<String>> {
+        let mut tx = self.pool.begin().await?;
+        let chat_id: Option<String> = sqlx::query_scalar(
+            r#"UPDATE messages SET content = ?, edited_at = ?
+               WHERE account_id = ? AND message_id = ?
+                 AND (edited_at IS NULL OR edited_at <= ?)
+               RETURNING chat_id"#,
+        )
+        .bind(new_content)
+        .bind(edited_at)
+        .bind(account_id)
+        .bind(message_id)
+        .bind(edited_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(chat_id) = &chat_id {
+            sqlx::query(
+                "UPDATE chats SET last_message_preview = ? \
+                 WHERE account_id = ? AND chat_id = ? AND last_message_id = ?",
+            )
+            .bind(new_content)
+            .bind(account_id)
+            .bind(chat_id)
+            .bind(message_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(chat_id)
+    }
+
+    /// Full stored row for one message, `None` if we never saw it.
+    pub async fn get_message(&self, account_id: &str, message_id: &str) -> Result<Option<Message>> {
+        Ok(sqlx::query_as::<_, Message>(
+            "SELECT * FROM messages WHERE account_id = ? AND message_id = ?",
+        )
+        .bind(account_id)
+        .bind(message_id)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// One of our own messages in `chat_jid`'s chat with exactly
+    /// `content`, stamped within `window_secs` of `timestamp` but stored
+    /// under another ID — the same logical message re-keyed between
+    /// live delivery and history sync. Returns the stored row's ID. The
+    /// chat is found through its aliases or, for a pending send to a
+    /// chat we don't know yet, the JID itself.
+    ///
+    /// A row matches at most one copy: once `mark_rekeyed_copy` records
+    /// one, only that same copy finds it again. A `message_id` that is
+    /// already stored is never a copy.
+    pub async fn find_similar_message(
+        &self,
+        account_id: &str,
+        chat_jid: &str,
+        message_id: &str,
+        content: &str,
+        timestamp: i64,
+        window_secs: i64,
+    ) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            r#"SELECT m.message_id FROM messages m
+               WHERE m.account_id = ?
+                 AND (m.chat_id = ? OR m.chat_id IN (
+                     SELECT chat_id FROM chat_aliases WHERE account_id = m.account_id AND alias_jid = ?
+                 ))
+                 AND m.is_from_me = 1
+                 AND m.timestamp BETWEEN ? AND ?
+                 AND m.content = ?
+                 AND m.message_id != ?
+                 AND (m.rekeyed_copy_id IS NULL OR m.rekeyed_copy_id = ?)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM messages s WHERE s.account_id = m.account_id AND s.message_id = ?
+                 )
+               ORDER BY m.rekeyed_copy_id IS NULL, ABS(m.timestamp - ?)
+               LIMIT 1"#,
+        )
+        .bind(account_id)
+        .bind(chat_jid)
+        .bind(chat_jid)
+        .bind(timestamp - window_secs)
+        .bind(timestamp + window_secs)
+        .bind(content)
+        .bind(message_id)
+        .bind(message_id)
+        .bind(message_id)
+        .bind(timestamp)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Records `copy_id` as the re-keyed copy `message_id` absorbed (see
+    /// `find_similar_message`). A row that already absorbed one keeps it.
+    pub async fn mark_rekeyed_copy(
+        &self,
+        account_id: &str,
+        message_id: &str,
+        copy_id: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE messages SET rekeyed_copy_id = ? \
+             WHERE account_id = ? AND message_id = ? AND rekeyed_copy_id IS NULL",
+        )
+        .bind(copy_id)
+        .bind(account_id)
+        .bind(message_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Local copy of a message we just forwarded to `to_chat_id`:
+    /// content, type, proto and media metadata come from the source
+    /// row, the copy is ours (`is_from_me`, `forwarded`) and gets its
+    /// own `message_id` — the one nanachi reported for that recipient.
+    /// Also moves the destination chat's preview. Returns `false` when
+    /// the source is gone or the copy already exists.
+    pub async fn insert_forwarded_copy(
+        &self,
+        account_id: &str,
+        source_message_id: &str,
+        new_message_id: &str,
+        to_chat_id: &str,
+        timestamp: i64,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let copied: Option<(String, Option<String>, Option<i64>)> = sqlx::query_as(
+            r#"INSERT OR IGNORE INTO messages
+               (account_id, message_id, chat_id, content, message_type, timestamp,
+                is_from_me, raw_json, media_mimetype, media_filename,
+                media_duration_secs, media_width, media_height, media_size_bytes,
+                media_sha256, media_path, media_status, media_thumbnail,
+                mentions_json, forwarded, created_at)
+               SELECT account_id, ?, ?, content, message_type, ?,
+                      1, raw_json, media_mimetype, media_filename,
+                      media_duration_secs, media_width, media_height, media_size_bytes,
+                      media_sha256, media_path, COALESCE(media_status, 'none'), media_thumbnail,
+                      mentions_json, 1, ?
+               FROM messages WHERE account_id = ? AND message_id = ?
+               RETURNING message_type, content, media_duration_secs"#,
+        )
+        .bind(new_message_id)
+        .bind(to_chat_id)
+        .bind(timestamp)
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(source_message_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((message_type, content, duration_secs)) = copied else {
+            return Ok(false);
+        };
+        let preview = content.unwrap_or_else(|| preview_placeholder(&message_type).to_string());
+        sqlx::query(
+            r#"UPDATE chats
+               SET last_message_id = ?,
+                   last_message_preview = ?,
+                   last_message_ts = ?,
+                   last_message_from_me = 1,
+                   last_sender_contact_id = NULL,
+                   last_message_type = ?,
+                   last_message_duration_secs = ?,
+                   updated_at = ?
+               WHERE account_id = ? AND chat_id = ?
+                 AND (last_message_ts IS NULL OR last_message_ts <= ?)"#,
+        )
+        .bind(new_message_id)
+        .bind(&preview)
+        .bind(timestamp)
+        .bind(&message_type)
+        .bind(duration_secs)
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(to_chat_id)
+        .bind(timestamp)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
     pub async fn get_messages_by_chat(
         &self,
         account_id: &str,
@@ -99,7 +296,12 @@ impl TinaDb {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<MessageRow>> {
-        let rows = sqlx::query_as::<_, MessageRow>(MESSAGE_ROWS_BY_CHAT_SQL)
+        let sql = format!(
+            "{}\nWHERE m.account_id = ? AND m.chat_id = ?\n\
+             ORDER BY m.timestamp DESC, m.message_id DESC\nLIMIT ? OFFSET ?",
+            message_rows_select(),
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&sql)
             .bind(account_id)
             .bind(chat_id)
             .bind(limit)
@@ -112,8 +314,11 @@ impl TinaDb {
         Ok(rows)
     }
 
-    /// Páginação para trás: mensagens com `timestamp < before_ts` (ou
-    /// todas as mais antigas se for None), em ordem ASC. Usado para
+    /// Páginação para trás, por keyset `(timestamp, message_id)`:
+    /// mensagens anteriores à mais antiga já carregada, em ordem ASC.
+    /// `before_id` é o ID dessa mensagem — desempata as do mesmo
+    /// segundo, que um corte só por `timestamp < before_ts` pularia.
+    /// Sem ele, cai no corte estrito por timestamp. Usado para
     /// virtualização na UI — quando o usuário scrolla pro topo, pedimos
     /// o próximo lote mais antigo.
     pub async fn get_message_rows_before(
@@ -121,12 +326,20 @@ impl TinaDb {
         account_id: &str,
         chat_id: &str,
         before_ts: i64,
+        before_id: Option<&str>,
         limit: i64,
     ) -> Result<Vec<MessageRow>> {
-        let rows = sqlx::query_as::<_, MessageRow>(MESSAGE_ROWS_BEFORE_SQL)
+        let sql = format!(
+            "{}\nWHERE m.account_id = ?1 AND m.chat_id = ?2\n\
+             AND (m.timestamp < ?3 OR (m.timestamp = ?3 AND m.message_id < ?4))\n\
+             ORDER BY m.timestamp DESC, m.message_id DESC\nLIMIT ?5",
+            message_rows_select(),
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&sql)
             .bind(account_id)
             .bind(chat_id)
             .bind(before_ts)
+            .bind(before_id)
             .bind(limit)
             .fetch_all(&self.pool)
             .await?;
@@ -135,31 +348,87 @@ impl TinaDb {
         Ok(rows)
     }
 
-    /// Páginação para frente: mensagens com `timestamp > after_ts` em
-    /// ordem ASC. Simétrico de `get_message_rows_before`, usado quando
-    /// o usuário scrolla para o fim do factory e a tab cortou as
-    /// últimas N rows pelo soft-cap — precisamos buscar de volta as
-    /// mais novas no DB.
+    /// Páginação para frente: mensagens depois de `(after_ts, after_id)`
+    /// no keyset `(timestamp, message_id)`, em ordem ASC. Sem
+    /// `after_id`, estritamente `timestamp > after_ts`. Simétrico de
+    /// `get_message_rows_before`, usado quando o usuário scrolla para
+    /// o fim do factory e a tab cortou as últimas N rows pelo soft-cap
+    /// — precisamos buscar de volta as mais novas no DB.
     pub async fn get_message_rows_after(
         &self,
         account_id: &str,
         chat_id: &str,
         after_ts: i64,
+        after_id: Option<&str>,
         limit: i64,
     ) -> Result<Vec<MessageRow>> {
         // Já vem em ordem cronológica ascendente — não precisa
         // reverter como o `_before` precisa (ele busca DESC para
         // pegar os mais recentes primeiro, depois reverte).
-        let rows = sqlx::query_as::<_, MessageRow>(MESSAGE_ROWS_AFTER_SQL)
+        let sql = format!(
+            "{}\nWHERE m.account_id = ?1 AND m.chat_id = ?2\n\
+             AND (m.timestamp > ?3 OR (m.timestamp = ?3 AND m.message_id > ?4))\n\
+             ORDER BY m.timestamp ASC, m.message_id ASC\nLIMIT ?5",
+            message_rows_select(),
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&sql)
             .bind(account_id)
             .bind(chat_id)
             .bind(after_ts)
+            .bind(after_id)
             .bind(limit)
             .fetch_all(&self.pool)
             .await?;
         Ok(rows)
     }
 
+    /// Janela em volta de `message_id`: até `context` mensagens antes,
+    /// a própria e até `context` depois, em ordem ASC pelo keyset
+    /// `(timestamp, message_id)`. Pra abrir o chat centrado num
+    /// resultado de busca. ID que não existe (ou de outro chat) devolve
+    /// vazio.
+    pub async fn get_messages_around(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        context: i64,
+    ) -> Result<Vec<MessageRow>> {
+        let Some(target) = self
+            .get_message(account_id, message_id)
+            .await?
+            .filter(|m| m.chat_id == chat_id)
+        else {
+            return Ok(Vec::new());
+        };
+        let context = context.max(0);
+        let mut rows = self
+            .get_message_rows_before(
+                account_id,
+                chat_id,
+                target.timestamp,
+                Some(message_id),
+                context,
+            )
+            .await?;
+        let sql = format!(
+            "{}\nWHERE m.account_id = ?1 AND m.chat_id = ?2\n\
+             AND (m.timestamp > ?3 OR (m.timestamp = ?3 AND m.message_id >= ?4))\n\
+             ORDER BY m.timestamp ASC, m.message_id ASC\nLIMIT ?5",
+            message_rows_select(),
+        );
+        let from_target = sqlx::query_as::<_, MessageRow>(&sql)
+            .bind(account_id)
+            .bind(chat_id)
+            .bind(target.timestamp)
+            .bind(message_id)
+            .bind(context + 1)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.extend(from_target);
+        Ok(rows)
+    }
+
     pub async fn get_message_rows_by_ids(
         &self,
         account_id: &str,
@@ -173,7 +442,7 @@ impl TinaDb {
             .join(",");
         let sql = format!(
             "{}\nWHERE m.account_id = ? AND m.message_id IN ({})\nORDER BY m.timestamp ASC",
-            message_rows_by_ids_select(),
+            message_rows_select(),
             placeholders,
         );
         let mut q = sqlx::query_as::<_, MessageRow>(&sql).bind(account_id);
@@ -183,6 +452,163 @@ impl TinaDb {
         Ok(q.fetch_all(&self.pool).await?)
     }
 
+    /// Mensagens de um chat, mais novas primeiro, só dos tipos em
+    /// `message_types` (valores canônicos da coluna, ver
+    /// `tina_core::MessageType::as_str`). `None` não filtra; lista
+    /// vazia não casa nada.
+    pub async fn get_messages_filtered(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_types: Option<&[&str]>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<MessageRow>> {
+        let type_filter = match message_types {
+            Some([]) => return Ok(Vec::new()),
+            Some(types) => format!(
+                " AND m.message_type IN ({})",
+                std::iter::repeat_n("?", types.len())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            None => String::new(),
+        };
+        let sql = format!(
+            "{}\nWHERE m.account_id = ? AND m.chat_id = ?{}\n\
+             ORDER BY m.timestamp DESC, m.message_id DESC\nLIMIT ? OFFSET ?",
+            message_rows_select(),
+            type_filter,
+        );
+        let mut q = sqlx::query_as::<_, MessageRow>(&sql)
+            .bind(account_id)
+            .bind(chat_id);
+        for t in message_types.unwrap_or_default() {
+            q = q.bind(*t);
+        }
+        Ok(q.bind(limit).bind(offset).fetch_all(&self.pool).await?)
+    }
+
+    /// Última mensagem de cada chat em `chat_ids`, numa query só (em
+    /// vez de um `get_messages_by_chat(.., 1, 0)` por chat). Chats sem
+    /// mensagem ficam de fora do mapa. Empate de timestamp vai para a
+    /// inserida por último.
+    pub async fn get_last_messages_for_chats(
+        &self,
+        account_id: &str,
+        chat_ids: &[String],
+    ) -> Result<HashMap<String, Message>> {
+        if chat_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders = std::iter::repeat_n("?", chat_ids.len())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT * FROM (\n\
+               SELECT *, ROW_NUMBER() OVER (\n\
+                 PARTITION BY chat_id ORDER BY timestamp DESC, id DESC\n\
+               ) AS rn\n\
+               FROM messages WHERE account_id = ? AND chat_id IN ({placeholders})\n\
+             ) WHERE rn = 1"
+        );
+        let mut q = sqlx::query_as::<_, Message>(&sql).bind(account_id);
+        for id in chat_ids {
+            q = q.bind(id);
+        }
+        let rows = q.fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(|m| (m.chat_id.clone(), m)).collect())
+    }
+
+    /// Messages stored for the chat `chat_jid` belongs to (any of its
+    /// aliases works). The UI compares it with what it loaded to know
+    /// whether older history is left.
+    pub async fn get_chat_message_count(&self, account_id: &str, chat_jid: &str) -> Result<i64> {
+        let n: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM messages WHERE account_id = ?1 AND chat_id = COALESCE(\
+                (SELECT chat_id FROM chat_aliases WHERE account_id = ?1 AND alias_jid = ?2), ?2)",
+        )
+        .bind(account_id)
+        .bind(chat_jid)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(n)
+    }
+
+    /// "Clear chat": deletes the messages of the chat `chat_jid` belongs
+    /// to (any alias works) and their pins, except starred ones. The
+    /// chat row stays, its last message now the newest one kept (or
+    /// none), and everything up to the clear counts as read so a
+    /// history re-sync of old messages doesn't light the badge up.
+    /// Returns how many messages went.
+    pub async fn delete_chat_messages(&self, account_id: &str, chat_jid: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let chat_id: String = sqlx::query_scalar(
+            "SELECT COALESCE(\
+                (SELECT chat_id FROM chat_aliases WHERE account_id = ?1 AND alias_jid = ?2), ?2)",
+        )
+        .bind(account_id)
+        .bind(chat_jid)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM pinned_messages WHERE account_id = ?1 AND message_id IN \
+                (SELECT message_id FROM messages \
+                 WHERE account_id = ?1 AND chat_id = ?2 AND starred = 0)",
+        )
+        .bind(account_id)
+        .bind(&chat_id)
+        .execute(&mut *tx)
+        .await?;
+        let deleted = sqlx::query(
+            "DELETE FROM messages WHERE account_id = ? AND chat_id = ? AND starred = 0",
+        )
+        .bind(account_id)
+        .bind(&chat_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let last = sqlx::query_as::<_, Message>(
+            "SELECT * FROM messages WHERE account_id = ? AND chat_id = ?
+             ORDER BY timestamp DESC, id DESC LIMIT 1",
+        )
+        .bind(account_id)
+        .bind(&chat_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"UPDATE chats
+               SET last_read_ts = MAX(COALESCE(last_read_ts, 0), COALESCE(last_message_ts, 0)),
+                   last_message_id = ?,
+                   last_message_preview = ?,
+                   last_message_ts = ?,
+                   last_message_from_me = ?,
+                   last_sender_contact_id = ?,
+                   last_message_type = ?,
+                   last_message_duration_secs = ?,
+                   updated_at = ?
+               WHERE account_id = ? AND chat_id = ?"#,
+        )
+        .bind(last.as_ref().map(|m| &m.message_id))
+        .bind(last.as_ref().map(|m| {
+            m.content
+                .as_deref()
+                .unwrap_or_else(|| preview_placeholder(&m.message_type))
+        }))
+        .bind(last.as_ref().map(|m| m.timestamp))
+        .bind(last.as_ref().is_some_and(|m| m.is_from_me))
+        .bind(last.as_ref().and_then(|m| m.sender_contact_id.as_ref()))
+        .bind(last.as_ref().map(|m| &m.message_type))
+        .bind(last.as_ref().and_then(|m| m.media_duration_secs))
+        .bind(unix_now())
+        .bind(account_id)
+        .bind(&chat_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
     pub async fn count_messages_for_chat(&self, account_id: &str, chat_id: &str) -> Result<i64> {
         let n: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM messages WHERE account_id = ? AND chat_id = ?",
@@ -195,12 +621,15 @@ impl TinaDb {
     }
 }
 
-fn message_rows_by_ids_select() -> &'static str {
+/// `SELECT … FROM messages m` with every join a `MessageRow` needs
+/// (sender, quoted sender, quoted message). Each query appends its own
+/// `WHERE` / `ORDER BY`.
+pub(super) fn message_rows_select() -> &'static str {
     r#"SELECT
          m.message_id,
          m.chat_id,
          m.sender_contact_id,
-         COALESCE(ct.contact_name, ct.push_name, ct.verified_name, ct.business_name, ct.phone_number) AS sender_name,
+         COALESCE(ct.contact_name, ct.verified_name, ct.push_name, ct.business_name, ct.phone_number) AS sender_name,
          COALESCE(ct.pn_jid, ct.lid_jid) AS sender_jid,
          ct.avatar_path AS sender_avatar_path,
          m.content,
@@ -219,10 +648,17 @@ fn message_rows_by_ids_select() -> &'static str {
          m.media_thumbnail,
          m.quoted_message_id,
          m.quoted_sender_id,
-         m.quoted_preview,
-         COALESCE(qct.contact_name, qct.push_name, qct.verified_name, qct.business_name, qct.phone_number) AS quoted_sender_name,
+         COALESCE(NULLIF(m.quoted_preview, ''), SUBSTR(qm.content, 1, 200)) AS quoted_preview,
+         COALESCE(qct.contact_name, qct.verified_name, qct.push_name, qct.business_name, qct.phone_number,
+                  qmct.contact_name, qmct.verified_name, qmct.push_name, qmct.business_name, qmct.phone_number) AS quoted_sender_name,
          m.mentions_json,
-         m.delivery_status
+         m.delivery_status,
+         m.starred AS is_starred,
+         m.edited_at,
+         m.forwarded,
+         CASE WHEN m.message_type IN ('location', 'contact', 'poll') THEN m.raw_json END AS raw_json,
+         EXISTS(SELECT 1 FROM pinned_messages pm
+                WHERE pm.account_id = m.account_id AND pm.message_id = m.message_id) AS is_pinned
        FROM messages m
        LEFT JOIN contacts ct
          ON ct.account_id = m.account_id AND ct.contact_id = m.sender_contact_id
@@ -230,119 +666,9 @@ fn message_rows_by_ids_select() -> &'static str {
          ON qca.account_id = m.account_id AND qca.alias_jid = m.quoted_sender_id
        LEFT JOIN contacts qct
          ON qct.account_id = m.account_id AND qct.contact_id = qca.contact_id
+       LEFT JOIN messages qm
+         ON qm.account_id = m.account_id AND qm.message_id = m.quoted_message_id
+       LEFT JOIN contacts qmct
+         ON qmct.account_id = m.account_id AND qmct.contact_id = qm.sender_contact_id
     "#
 }
-
-const MESSAGE_ROWS_BY_CHAT_SQL: &str = r#"SELECT
-     m.message_id,
-     m.chat_id,
-     m.sender_contact_id,
-     COALESCE(ct.contact_name, ct.push_name, ct.verified_name, ct.business_name, ct.phone_number) AS sender_name,
-     COALESCE(ct.pn_jid, ct.lid_jid) AS sender_jid,
-     ct.avatar_path AS sender_avatar_path,
-     m.content,
-     m.message_type,
-     m.timestamp,
-     m.is_from_me,
-     m.media_mimetype,
-     m.media_filename,
-     m.media_duration_secs,
-     m.media_width,
-     m.media_height,
-     m.media_size_bytes,
-     m.media_sha256,
-     m.media_path,
-     m.media_status,
-     m.media_thumbnail,
-     m.quoted_message_id,
-     m.quoted_sender_id,
-     m.quoted_preview,
-     COALESCE(qct.contact_name, qct.push_name, qct.verified_name, qct.business_name, qct.phone_number) AS quoted_sender_name,
-     m.mentions_json,
-         m.delivery_status
-   FROM messages m
-   LEFT JOIN contacts ct
-     ON ct.account_id = m.account_id AND ct.contact_id = m.sender_contact_id
-   LEFT JOIN contact_aliases qca
-     ON qca.account_id = m.account_id AND qca.alias_jid = m.quoted_sender_id
-   LEFT JOIN contacts qct
-     ON qct.account_id = m.account_id AND qct.contact_id = qca.contact_id
-   WHERE m.account_id = ? AND m.chat_id = ?
-   ORDER BY m.timestamp DESC
-   LIMIT ? OFFSET ?"#;
-
-const MESSAGE_ROWS_BEFORE_SQL: &str = r#"SELECT
-     m.message_id,
-     m.chat_id,
-     m.sender_contact_id,
-     COALESCE(ct.contact_name, ct.push_name, ct.verified_name, ct.business_name, ct.phone_number) AS sender_name,
-     COALESCE(ct.pn_jid, ct.lid_jid) AS sender_jid,
-     ct.avatar_path AS sender_avatar_path,
-     m.content,
-     m.message_type,
-     m.timestamp,
-     m.is_from_me,
-     m.media_mimetype,
-     m.media_filename,
-     m.media_duration_secs,
-     m.media_width,
-     m.media_height,
-     m.media_size_bytes,
-     m.media_sha256,
-     m.media_path,
-     m.media_status,
-     m.media_thumbnail,
-     m.quoted_message_id,
-     m.quoted_sender_id,
-     m.quoted_preview,
-     COALESCE(qct.contact_name, qct.push_name, qct.verified_name, qct.business_name, qct.phone_number) AS quoted_sender_name,
-     m.mentions_json,
-         m.delivery_status
-   FROM messages m
-   LEFT JOIN contacts ct
-     ON ct.account_id = m.account_id AND ct.contact_id = m.sender_contact_id
-   LEFT JOIN contact_aliases qca
-     ON qca.account_id = m.account_id AND qca.alias_jid = m.quoted_sender_id
-   LEFT JOIN contacts qct
-     ON qct.account_id = m.account_id AND qct.contact_id = qca.contact_id
-   WHERE m.account_id = ? AND m.chat_id = ? AND m.timestamp < ?
-   ORDER BY m.timestamp DESC
-   LIMIT ?"#;
-
-const MESSAGE_ROWS_AFTER_SQL: &str = r#"SELECT
-     m.message_id,
-     m.chat_id,
-     m.sender_contact_id,
-     COALESCE(ct.contact_name, ct.push_name, ct.verified_name, ct.business_name, ct.phone_number) AS sender_name,
-     COALESCE(ct.pn_jid, ct.lid_jid) AS sender_jid,
-     ct.avatar_path AS sender_avatar_path,
-     m.content,
-     m.message_type,
-     m.timestamp,
-     m.is_from_me,
-     m.media_mimetype,
-     m.media_filename,
-     m.media_duration_secs,
-     m.media_width,
-     m.media_height,
-     m.media_size_bytes,
-     m.media_sha256,
-     m.media_path,
-     m.media_status,
-     m.media_thumbnail,
-     m.quoted_message_id,
-     m.quoted_sender_id,
-     m.quoted_preview,
-     COALESCE(qct.contact_name, qct.push_name, qct.verified_name, qct.business_name, qct.phone_number) AS quoted_sender_name,
-     m.mentions_json,
-         m.delivery_status
-   FROM messages m
-   LEFT JOIN contacts ct
-     ON ct.account_id = m.account_id AND ct.contact_id = m.sender_contact_id
-   LEFT JOIN contact_aliases qca
-     ON qca.account_id = m.account_id AND qca.alias_jid = m.quoted_sender_id
-   LEFT JOIN contacts qct
-     ON qct.account_id = m.account_id AND qct.contact_id = qca.contact_id
-   WHERE m.account_id = ? AND m.chat_id = ? AND m.timestamp > ?
-   ORDER BY m.timestamp ASC
-   LIMIT ?"#;
@@ -4,13 +4,14 @@
 use std::collections::{HashMap, HashSet};
 
 use sqlx::{Row, Sqlite, Transaction};
+use tina_core::unix_now;
 
 use crate::error::Result;
 use crate::models::ChatKind;
 
 use super::aliases::{register_chat_alias_tx, register_contact_alias_tx};
 use super::db::TinaDb;
-use super::util::{now_ts, repeat_csv};
+use super::util::repeat_csv;
 
 struct Latest<'a> {
     ts: i64,
@@ -27,6 +28,59 @@ struct PendingInsert {
     idx: usize,
     chat_id: String,
     sender_contact_id: Option<String>,
+    /// `is_from_me` do input, ou o sender é o próprio número.
+    is_from_me: bool,
+}
+
+/// O próprio número da conta. nanachi pode marcar como de outra pessoa
+/// mensagens nossas que chegam pelo histórico depois de um novo
+/// pareamento; o batch as grava como nossas.
+struct OwnIdentity {
+    /// `<número>@s.whatsapp.net`.
+    pn_jid: String,
+    /// Contato do número, quando já existe: cobre também a forma LID,
+    /// que é alias dele.
+    contact_id: Option<String>,
+}
+
+impl OwnIdentity {
+    async fn load(tx: &mut Transaction<'_, Sqlite>, account_id: &str) -> Result<Option<Self>> {
+        let phone: Option<String> =
+            sqlx::query_scalar("SELECT phone_number FROM accounts WHERE id = ?")
+                .bind(account_id)
+                .fetch_optional(&mut **tx)
+                .await?
+                .flatten();
+        let Some(phone) = phone.filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        let pn_jid = format!("{phone}@s.whatsapp.net");
+        let contact_id = sqlx::query_scalar(
+            "SELECT contact_id FROM contact_aliases WHERE account_id = ? AND alias_jid = ?",
+        )
+        .bind(account_id)
+        .bind(&pn_jid)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(Some(Self { pn_jid, contact_id }))
+    }
+
+    fn is_sender(&self, jid: &str, contact_id: &str) -> bool {
+        if self.contact_id.as_deref() == Some(contact_id) {
+            return true;
+        }
+        // O sender pode vir com o device (`<número>:<device>@...`).
+        match jid.split_once('@') {
+            Some((user, server)) => {
+                let user = user.split(':').next().unwrap_or(user);
+                self.pn_jid
+                    .strip_suffix(server)
+                    .and_then(|u| u.strip_suffix('@'))
+                    == Some(user)
+            }
+            None => false,
+        }
+    }
 }
 
 impl TinaDb {
@@ -79,6 +133,7 @@ async fn resolve_chats_and_senders<'a>(
     let mut contact_cache: HashMap<&str, String> = HashMap::new();
     let mut latest: HashMap<String, Latest<'_>> = HashMap::new();
     let mut pending: Vec<PendingInsert> = Vec::with_capacity(messages.len());
+    let own = OwnIdentity::load(tx, account_id).await?;
 
     for (idx, msg) in messages.iter().enumerate() {
         let chat_id = if let Some(c) = chat_cache.get(msg.chat_jid) {
@@ -105,23 +160,19 @@ async fn resolve_chats_and_senders<'a>(
         } else {
             None
         };
-
-        let placeholder: &'static str = match msg.message_type {
-            "image" => "[Image]",
-            "video" => "[Video]",
-            "audio" => "[Audio]",
-            "document" => "[Document]",
-            "sticker" => "[Sticker]",
-            "contact" => "[Contact]",
-            "location" => "[Location]",
-            _ => "[Media]",
+        let (is_from_me, sender_contact_id) = match (&own, msg.sender_jid, sender_contact_id) {
+            (Some(own), Some(jid), Some(contact)) if own.is_sender(jid, &contact) => (true, None),
+            (_, _, contact) => (msg.is_from_me, contact),
         };
 
+        let placeholder = preview_placeholder(msg.message_type);
+
         let duration_secs = msg.media.as_ref().and_then(|m| m.duration_secs);
         update_latest(
             &mut latest,
             chat_id.clone(),
             msg,
+            is_from_me,
             placeholder,
             sender_contact_id.clone(),
             duration_secs,
@@ -131,6 +182,7 @@ async fn resolve_chats_and_senders<'a>(
             idx,
             chat_id,
             sender_contact_id,
+            is_from_me,
         });
     }
     Ok((pending, latest))
@@ -140,6 +192,7 @@ fn update_latest<'a>(
     latest: &mut HashMap<String, Latest<'a>>,
     chat_id: String,
     msg: &crate::MessageBatchInput<'a>,
+    from_me: bool,
     placeholder: &'static str,
     sender_contact_id: Option<String>,
     duration_secs: Option<i64>,
@@ -152,7 +205,7 @@ fn update_latest<'a>(
                 message_id: msg.message_id,
                 preview: msg.content,
                 placeholder,
-                from_me: msg.is_from_me,
+                from_me,
                 sender_contact_id,
                 message_type: msg.message_type,
                 duration_secs,
@@ -165,7 +218,7 @@ fn update_latest<'a>(
                     message_id: msg.message_id,
                     preview: msg.content,
                     placeholder,
-                    from_me: msg.is_from_me,
+                    from_me,
                     sender_contact_id,
                     message_type: msg.message_type,
                     duration_secs,
@@ -210,7 +263,7 @@ async fn bulk_insert_messages(
     pending: &[PendingInsert],
 ) -> Result<()> {
     const MSG_INSERT_CHUNK: usize = 200;
-    let now = now_ts();
+    let now = unix_now();
     for chunk in pending.chunks(MSG_INSERT_CHUNK) {
         let row_tpl = "(?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)";
         let mut sql = String::from(
@@ -236,7 +289,7 @@ async fn bulk_insert_messages(
                 .bind(m.content)
                 .bind(m.message_type)
                 .bind(m.timestamp)
-                .bind(m.is_from_me)
+                .bind(p.is_from_me)
                 .bind(m.raw_json)
                 .bind(now)
                 .bind(media.and_then(|x| x.mimetype))
@@ -306,7 +359,7 @@ async fn advance_active_chat_read_ts(
            WHERE account_id = ? AND chat_id = ?"#,
     )
     .bind(l.ts)
-    .bind(now_ts())
+    .bind(unix_now())
     .bind(account_id)
     .bind(active_chat)
     .execute(&mut **tx)
@@ -314,6 +367,11 @@ async fn advance_active_chat_read_ts(
     Ok(())
 }
 
+/// Sidebar preview for a last message with no text.
+pub(super) fn preview_placeholder(message_type: &str) -> &'static str {
+    tina_core::MessageType::from_raw(message_type).placeholder()
+}
+
 async fn flush_chat_last_message(
     tx: &mut Transaction<'_, Sqlite>,
     account_id: &str,
@@ -341,7 +399,7 @@ async fn flush_chat_last_message(
         .bind(&l.sender_contact_id)
         .bind(l.message_type)
         .bind(l.duration_secs)
-        .bind(now_ts())
+        .bind(unix_now())
         .bind(account_id)
         .bind(chat_id)
         .bind(l.ts)
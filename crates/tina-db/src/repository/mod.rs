@@ -10,13 +10,19 @@
 //
 //   * `db`              — pool open/migrate
 //   * `accounts`        — accounts CRUD
+//   * `backup`          — VACUUM INTO snapshot + in-place restore
+//   * `chat_settings`   — local mute / notification preferences
 //   * `chats`           — chat resolver, display name, last message,
 //                         row queries
 //   * `contacts`        — contacts resolver + bulk batch
 //   * `groups`          — groups/newsletters + DM lookup helper
 //   * `messages`        — single-message read + insert paths
 //   * `messages_batch`  — bulk message ingestion (history sync)
-//   * `media`           — download status + avatar persistence
+//   * `media`           — download status, cache ledger + eviction,
+//                         avatar persistence
+//   * `pins`            — messages pinned inside a chat
+//   * `previews`        — chat list with names + newest message
+//   * `stars`           — starred messages across chats
 //   * `aliases`         — resolver internals shared across submodules
 //   * `merge`           — alias-collision merge transactions
 //   * `util`            — small SQL/string helpers
@@ -26,6 +32,8 @@
 
 mod accounts;
 mod aliases;
+mod backup;
+mod chat_settings;
 mod chats;
 mod contacts;
 mod db;
@@ -35,7 +43,12 @@ mod mentions;
 mod merge;
 mod messages;
 mod messages_batch;
+mod pins;
+mod previews;
 mod settings;
+mod stars;
 mod util;
 
-pub use db::TinaDb;
+pub use chat_settings::MUTED_FOREVER;
+pub use db::{DATA_DIR_ENV, DB_PATH_ENV, TinaDb, data_dir};
+pub use pins::MAX_PINNED_PER_CHAT;
@@ -0,0 +1,80 @@
+// Pinned messages inside a chat (distinct from `chats.pinned`, which
+// pins the whole chat in the sidebar). The table only records which
+// message ids are pinned; the rows themselves come from `messages`.
+
+use crate::error::Result;
+use crate::models::MessageRow;
+
+use super::db::TinaDb;
+use super::messages::message_rows_select;
+
+/// WhatsApp keeps at most this many pinned messages per chat.
+pub const MAX_PINNED_PER_CHAT: i64 = 3;
+
+impl TinaDb {
+    /// Marks `message_id` as pinned. Re-pinning refreshes `pinned_at`.
+    /// The per-chat limit is the caller's job (see
+    /// [`MAX_PINNED_PER_CHAT`]).
+    pub async fn pin_message(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        pinned_at: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO pinned_messages (account_id, chat_id, message_id, pinned_at) \
+             VALUES (?, ?, ?, ?) \
+             ON CONFLICT(account_id, message_id) DO UPDATE SET pinned_at = excluded.pinned_at",
+        )
+        .bind(account_id)
+        .bind(chat_id)
+        .bind(message_id)
+        .bind(pinned_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns `true` if the message was pinned.
+    pub async fn unpin_message(&self, account_id: &str, message_id: &str) -> Result<bool> {
+        let res =
+            sqlx::query("DELETE FROM pinned_messages WHERE account_id = ? AND message_id = ?")
+                .bind(account_id)
+                .bind(message_id)
+                .execute(&self.pool)
+                .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn count_pinned_messages(&self, account_id: &str, chat_id: &str) -> Result<i64> {
+        let n: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pinned_messages WHERE account_id = ? AND chat_id = ?",
+        )
+        .bind(account_id)
+        .bind(chat_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(n)
+    }
+
+    /// Pinned messages of a chat, most recently pinned first. Pins
+    /// whose message isn't in `messages` (not synced yet) are skipped.
+    pub async fn get_pinned_messages(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+    ) -> Result<Vec<MessageRow>> {
+        let sql = format!(
+            "{}\nJOIN pinned_messages p\n  ON p.account_id = m.account_id AND p.message_id = m.message_id\n\
+             WHERE m.account_id = ? AND p.chat_id = ?\nORDER BY p.pinned_at DESC",
+            message_rows_select(),
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&sql)
+            .bind(account_id)
+            .bind(chat_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,96 @@
+// Chat list with names and last-message previews in one query. Unlike
+// `chat_row_select_clause`, which reads the denormalized
+// `chats.last_message_*` columns, the preview here comes straight from
+// the newest row in `messages`, so it stays right even when the
+// denormalized columns lag behind a batch.
+
+use crate::error::Result;
+use crate::models::ChatPreviewRow;
+
+use super::db::TinaDb;
+use super::util::repeat_csv;
+
+impl TinaDb {
+    /// Every chat of the account with its resolved name and newest
+    /// message, most recent first. The status feed isn't a chat and
+    /// is left out.
+    pub async fn get_chats_with_names(&self, account_id: &str) -> Result<Vec<ChatPreviewRow>> {
+        let sql = format!(
+            "{}\nWHERE c.account_id = ? AND {}\n{}",
+            PREVIEW_SELECT, NOT_STATUS, PREVIEW_ORDER
+        );
+        Ok(sqlx::query_as::<_, ChatPreviewRow>(&sql)
+            .bind(account_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Same rows as [`Self::get_chats_with_names`], restricted to
+    /// `chat_ids`. Unknown ids are skipped.
+    pub async fn get_chat_previews_batch(
+        &self,
+        account_id: &str,
+        chat_ids: &[&str],
+    ) -> Result<Vec<ChatPreviewRow>> {
+        const CHUNK: usize = 500;
+        let mut rows = Vec::with_capacity(chat_ids.len());
+        for chunk in chat_ids.chunks(CHUNK) {
+            let sql = format!(
+                "{}\nWHERE c.account_id = ? AND c.chat_id IN ({}) AND {}\n{}",
+                PREVIEW_SELECT,
+                repeat_csv("?", chunk.len()),
+                NOT_STATUS,
+                PREVIEW_ORDER,
+            );
+            let mut q = sqlx::query_as::<_, ChatPreviewRow>(&sql).bind(account_id);
+            for id in chunk {
+                q = q.bind(*id);
+            }
+            rows.extend(q.fetch_all(&self.pool).await?);
+        }
+        Ok(rows)
+    }
+}
+
+/// The `status@broadcast` feed is read through `get_status_updates`.
+const NOT_STATUS: &str = "c.kind != 'status'";
+
+const PREVIEW_ORDER: &str = "ORDER BY last_timestamp DESC NULLS LAST, c.chat_id";
+
+/// `ct` resolves the chat itself (DMs), `cs` the sender of the newest
+/// message. The newest message is picked through `idx_messages_chat`.
+const PREVIEW_SELECT: &str = r#"SELECT
+    c.chat_id AS chat_jid,
+    c.kind = 'group' AS is_group,
+    COALESCE(
+        NULLIF(TRIM(g.subject), ''),
+        NULLIF(TRIM(c.display_name), ''),
+        NULLIF(TRIM(ct.contact_name), ''),
+        NULLIF(TRIM(ct.verified_name), ''),
+        NULLIF(TRIM(ct.push_name), ''),
+        NULLIF(TRIM(ct.business_name), '')
+    ) AS resolved_name,
+    m.message_id AS last_message_id,
+    m.timestamp AS last_timestamp,
+    m.content AS last_message_content,
+    m.message_type AS last_message_type,
+    COALESCE(m.is_from_me, 0) AS last_message_from_me,
+    CASE WHEN m.is_from_me THEN NULL ELSE COALESCE(
+        NULLIF(cs.contact_name, ''),
+        NULLIF(cs.verified_name, ''),
+        NULLIF(cs.push_name, ''),
+        NULLIF(cs.business_name, ''),
+        NULLIF(cs.phone_number, ''),
+        m.sender_contact_id
+    ) END AS last_sender_name
+FROM chats c
+LEFT JOIN groups g ON g.account_id = c.account_id AND g.chat_id = c.chat_id
+LEFT JOIN contact_aliases ca ON ca.account_id = c.account_id AND ca.alias_jid = c.chat_id
+LEFT JOIN contacts ct ON ct.account_id = c.account_id AND ct.contact_id = ca.contact_id
+LEFT JOIN messages m ON m.id = (
+    SELECT mm.id FROM messages mm
+    WHERE mm.account_id = c.account_id AND mm.chat_id = c.chat_id
+    ORDER BY mm.timestamp DESC, mm.id DESC
+    LIMIT 1
+)
+LEFT JOIN contacts cs ON cs.account_id = c.account_id AND cs.contact_id = m.sender_contact_id"#;
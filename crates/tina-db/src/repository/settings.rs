@@ -3,9 +3,25 @@
 // they like (booleans as "0"/"1", JSON for structured values, etc.).
 
 use crate::error::Result;
+use crate::models::SyncCursor;
 
 use super::db::TinaDb;
 
+/// Chave da janela (segundos) em que o flush casa uma cópia
+/// re-chaveada com o envio original.
+const MESSAGE_DEDUP_WINDOW_KEY: &str = "message_dedup_window_secs";
+/// Janela padrão: cobre o arredondamento de timestamp entre o eco ao
+/// vivo e a cópia do history sync.
+const DEFAULT_MESSAGE_DEDUP_WINDOW_SECS: i64 = 2;
+
+pub(super) fn sync_cursor_key(account_id: &str) -> String {
+    format!("sync_cursor:{account_id}")
+}
+
+pub(super) fn initial_sync_key(account_id: &str) -> String {
+    format!("initial_sync_done:{account_id}")
+}
+
 impl TinaDb {
     pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
         let row: Option<(Option<String>,)> =
@@ -27,4 +43,58 @@ impl TinaDb {
         .await?;
         Ok(())
     }
+
+    /// Window for collapsing our own re-keyed duplicates on insert;
+    /// `0` turns the check off. Unset or unparseable means the default.
+    pub async fn message_dedup_window(&self) -> Result<i64> {
+        let raw = self.get_setting(MESSAGE_DEDUP_WINDOW_KEY).await?;
+        Ok(raw
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|secs| *secs >= 0)
+            .unwrap_or(DEFAULT_MESSAGE_DEDUP_WINDOW_SECS))
+    }
+
+    pub async fn set_message_dedup_window(&self, secs: i64) -> Result<()> {
+        self.put_setting(MESSAGE_DEDUP_WINDOW_KEY, &secs.max(0).to_string())
+            .await
+    }
+
+    /// Cursor do history sync em andamento, se houver. JSON inválido
+    /// (versão antiga, escrita truncada) conta como "sem cursor".
+    pub async fn get_sync_cursor(&self, account_id: &str) -> Result<Option<SyncCursor>> {
+        let raw = self.get_setting(&sync_cursor_key(account_id)).await?;
+        Ok(raw.and_then(|v| serde_json::from_str(&v).ok()))
+    }
+
+    pub async fn put_sync_cursor(&self, account_id: &str, cursor: &SyncCursor) -> Result<()> {
+        let value = serde_json::to_string(cursor)?;
+        self.put_setting(&sync_cursor_key(account_id), &value).await
+    }
+
+    pub async fn clear_sync_cursor(&self, account_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(sync_cursor_key(account_id))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the account's first full sync (contacts, groups and
+    /// history) finished in some session. Cleared on logout.
+    pub async fn initial_sync_done(&self, account_id: &str) -> Result<bool> {
+        let raw = self.get_setting(&initial_sync_key(account_id)).await?;
+        Ok(raw.is_some())
+    }
+
+    /// Records the first full sync. `true` only on the call that set
+    /// the flag, so the completion is announced once across restarts.
+    pub async fn mark_initial_sync_done(&self, account_id: &str) -> Result<bool> {
+        let done = sqlx::query(
+            "INSERT INTO settings (key, value) VALUES (?, '1') ON CONFLICT(key) DO NOTHING",
+        )
+        .bind(initial_sync_key(account_id))
+        .execute(&self.pool)
+        .await?;
+        Ok(done.rows_affected() > 0)
+    }
 }
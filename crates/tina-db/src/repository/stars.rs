@@ -0,0 +1,58 @@
+// Starred ("favorite") messages. Unlike pins, stars live on the
+// message row itself and the retrieval view spans every chat.
+
+use crate::error::Result;
+use crate::models::MessageRow;
+
+use super::db::TinaDb;
+use super::messages::message_rows_select;
+
+impl TinaDb {
+    /// Returns `true` if the message exists and its flag changed.
+    pub async fn star_message(&self, account_id: &str, message_id: &str) -> Result<bool> {
+        self.set_message_starred(account_id, message_id, true).await
+    }
+
+    pub async fn unstar_message(&self, account_id: &str, message_id: &str) -> Result<bool> {
+        self.set_message_starred(account_id, message_id, false).await
+    }
+
+    async fn set_message_starred(
+        &self,
+        account_id: &str,
+        message_id: &str,
+        starred: bool,
+    ) -> Result<bool> {
+        let res = sqlx::query(
+            "UPDATE messages SET starred = ? \
+             WHERE account_id = ? AND message_id = ? AND starred != ?",
+        )
+        .bind(starred)
+        .bind(account_id)
+        .bind(message_id)
+        .bind(starred)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Starred messages across all chats, newest first.
+    pub async fn get_starred_messages(
+        &self,
+        account_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<MessageRow>> {
+        let sql = format!(
+            "{}\nWHERE m.account_id = ? AND m.starred = 1\nORDER BY m.timestamp DESC\nLIMIT ? OFFSET ?",
+            message_rows_select(),
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&sql)
+            .bind(account_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+}
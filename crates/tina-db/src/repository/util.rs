@@ -37,9 +37,17 @@ pub(super) fn derive_pn_lid(jid: &str, alt: Option<&str>) -> (Option<String>, Op
     (pn, lid)
 }
 
-pub(super) fn now_ts() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs() as i64
+/// `%needle%` for a `LIKE … ESCAPE '\'`, with the user's own `%`, `_`
+/// and `\` taken literally.
+pub(super) fn like_pattern(needle: &str) -> String {
+    let mut s = String::with_capacity(needle.len() + 2);
+    s.push('%');
+    for c in needle.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s.push('%');
+    s
 }
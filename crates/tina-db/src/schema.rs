@@ -11,7 +11,21 @@
 /// - v9: last_read_ts em chats (auto-detect unread count via
 ///       COUNT(messages WHERE timestamp > last_read_ts), evita drift
 ///       do contador persistido).
-pub const SCHEMA_VERSION: i64 = 9;
+/// - v10: tabela pinned_messages (mensagens fixadas dentro do chat).
+/// - v11: starred em messages (mensagens favoritas, view global).
+/// - v12: edited_at em messages (edições vindas do WhatsApp).
+/// - v13: forwarded em messages (cópias locais de encaminhamentos).
+/// - v14: notify em chats (preferência local de notificação).
+/// - v15: invite_link em groups (cache do link de convite).
+/// - v16: tabela media_files (tamanho e último acesso da cache de mídia).
+/// - v17: media_files chaveada só pelo path (a cache do nanachi é uma
+///   só pra todas as contas).
+/// - v18: name_is_local em contacts (nome salvo pelo usuário, que o
+///   servidor não sobrescreve nem depois do contato deixar de ser
+///   local).
+/// - v19: rekeyed_copy_id em messages (cópia re-chaveada de um envio
+///   nosso que o flush já descartou).
+pub const SCHEMA_VERSION: i64 = 19;
 
 /// Comandos para *recriar* o schema do zero (não suporta migração in-place
 /// — quando `user_version` diverge, dropamos tudo e criamos de novo).
@@ -53,7 +67,10 @@ CREATE TABLE IF NOT EXISTS chats (
     last_read_ts INTEGER,
     pinned INTEGER NOT NULL DEFAULT 0,
     archived INTEGER NOT NULL DEFAULT 0,
+    -- Preferências locais (não vão pro WhatsApp). `muted_until` em
+    -- unix secs; passado o instante o chat volta a notificar sozinho.
     muted_until INTEGER,
+    notify INTEGER NOT NULL DEFAULT 1,
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     PRIMARY KEY (account_id, chat_id),
@@ -84,6 +101,7 @@ CREATE TABLE IF NOT EXISTS contacts (
     avatar_path TEXT,
     status TEXT,
     is_local INTEGER NOT NULL DEFAULT 0,
+    name_is_local INTEGER NOT NULL DEFAULT 0,
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     PRIMARY KEY (account_id, contact_id),
@@ -108,6 +126,7 @@ CREATE TABLE IF NOT EXISTS groups (
     owner_contact_id TEXT,
     description TEXT,
     participants_json TEXT,
+    invite_link TEXT,
     PRIMARY KEY (account_id, chat_id),
     FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
 );
@@ -161,6 +180,18 @@ CREATE TABLE IF NOT EXISTS messages (
     -- Incoming rows are inserted as 'sent' but the field is never read
     -- for them; only `from_me=1` rows render a status icon.
     delivery_status TEXT NOT NULL DEFAULT 'sent',
+    -- Favorita ("starred"). Qualquer limpeza futura de mensagens
+    -- antigas precisa pular `starred = 1`.
+    starred INTEGER NOT NULL DEFAULT 0,
+    -- Unix secs da última edição feita pelo autor. NULL = nunca editada;
+    -- `content` já guarda o texto novo.
+    edited_at INTEGER,
+    -- 1 = cópia local de uma mensagem que encaminhamos.
+    forwarded INTEGER NOT NULL DEFAULT 0,
+    -- ID da cópia re-chaveada (eco ao vivo vs history sync) que o flush
+    -- já descartou como duplicata desta mensagem nossa. Cada linha
+    -- absorve uma cópia só, então o mesmo texto enviado duas vezes fica.
+    rekeyed_copy_id TEXT,
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     UNIQUE(account_id, message_id),
     FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
@@ -173,11 +204,66 @@ CREATE INDEX IF NOT EXISTS idx_messages_sender ON messages(account_id, sender_co
 -- mesmo arquivo já baixado.
 CREATE INDEX IF NOT EXISTS idx_messages_media_sha ON messages(media_sha256)
     WHERE media_sha256 IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_messages_starred ON messages(account_id, timestamp)
+    WHERE starred = 1;
+
+-- Mensagens fixadas no topo do chat. WhatsApp limita a 3 por chat;
+-- o limite é aplicado no worker antes de mandar o IPC.
+CREATE TABLE IF NOT EXISTS pinned_messages (
+    account_id TEXT NOT NULL,
+    chat_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    pinned_at INTEGER NOT NULL,
+    PRIMARY KEY (account_id, message_id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_pinned_chat ON pinned_messages(account_id, chat_id, pinned_at);
 
 CREATE TABLE IF NOT EXISTS settings (
     key TEXT PRIMARY KEY NOT NULL,
     value TEXT
 );
+
+-- Arquivos de mídia baixados, um por caminho. A cache do nanachi é
+-- global e nomeia os arquivos pelo sha256, então o mesmo arquivo pode
+-- servir mensagens de várias contas — por isso não há account_id.
+-- `accessed_at` anda a cada uso; quando a cache passa do orçamento, os
+-- acessados há mais tempo saem primeiro (`evict_media`).
+CREATE TABLE IF NOT EXISTS media_files (
+    path TEXT PRIMARY KEY NOT NULL,
+    mimetype TEXT,
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    downloaded_at INTEGER NOT NULL,
+    accessed_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_media_files_accessed ON media_files(accessed_at);
+"#;
+
+pub const MIGRATION_V16_TO_V17: &str = r#"
+CREATE TABLE media_files_v17 (
+    path TEXT PRIMARY KEY NOT NULL,
+    mimetype TEXT,
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    downloaded_at INTEGER NOT NULL,
+    accessed_at INTEGER NOT NULL
+);
+INSERT INTO media_files_v17 (path, mimetype, size_bytes, downloaded_at, accessed_at)
+    SELECT path, MAX(mimetype), MAX(size_bytes), MIN(downloaded_at), MAX(accessed_at)
+    FROM media_files GROUP BY path;
+DROP TABLE media_files;
+ALTER TABLE media_files_v17 RENAME TO media_files;
+CREATE INDEX IF NOT EXISTS idx_media_files_accessed ON media_files(accessed_at);
+"#;
+
+/// Nomes de contatos ainda locais contam como salvos pelo usuário; os
+/// que já tinham virado não-locais antes da v18 não dá pra distinguir.
+pub const MIGRATION_V17_TO_V18: &str = r#"
+ALTER TABLE contacts ADD COLUMN name_is_local INTEGER NOT NULL DEFAULT 0;
+UPDATE contacts SET name_is_local = 1 WHERE is_local = 1 AND contact_name IS NOT NULL;
+"#;
+
+pub const MIGRATION_V18_TO_V19: &str = r#"
+ALTER TABLE messages ADD COLUMN rekeyed_copy_id TEXT;
 "#;
 
 pub const MIGRATION_V3_TO_V4: &str = r#"
@@ -215,6 +301,54 @@ ALTER TABLE chats ADD COLUMN last_read_ts INTEGER;
 UPDATE chats SET last_read_ts = COALESCE(last_message_ts, 0);
 "#;
 
+pub const MIGRATION_V9_TO_V10: &str = r#"
+CREATE TABLE IF NOT EXISTS pinned_messages (
+    account_id TEXT NOT NULL,
+    chat_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    pinned_at INTEGER NOT NULL,
+    PRIMARY KEY (account_id, message_id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_pinned_chat ON pinned_messages(account_id, chat_id, pinned_at);
+"#;
+
+pub const MIGRATION_V10_TO_V11: &str = r#"
+ALTER TABLE messages ADD COLUMN starred INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_messages_starred ON messages(account_id, timestamp)
+    WHERE starred = 1;
+"#;
+
+pub const MIGRATION_V11_TO_V12: &str = r#"
+ALTER TABLE messages ADD COLUMN edited_at INTEGER;
+"#;
+
+pub const MIGRATION_V12_TO_V13: &str = r#"
+ALTER TABLE messages ADD COLUMN forwarded INTEGER NOT NULL DEFAULT 0;
+"#;
+
+pub const MIGRATION_V13_TO_V14: &str = r#"
+ALTER TABLE chats ADD COLUMN notify INTEGER NOT NULL DEFAULT 1;
+"#;
+
+pub const MIGRATION_V14_TO_V15: &str = r#"
+ALTER TABLE groups ADD COLUMN invite_link TEXT;
+"#;
+
+pub const MIGRATION_V15_TO_V16: &str = r#"
+CREATE TABLE IF NOT EXISTS media_files (
+    account_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    mimetype TEXT,
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    downloaded_at INTEGER NOT NULL,
+    accessed_at INTEGER NOT NULL,
+    PRIMARY KEY (account_id, path),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_media_files_accessed ON media_files(accessed_at);
+"#;
+
 /// Migrações in-place pra evitar dropar o banco do usuário. Cada função roda
 /// dentro de uma transação no caller.
 pub const MIGRATION_V2_TO_V3: &str = r#"
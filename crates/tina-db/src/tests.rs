@@ -1,4 +1,4 @@
-use crate::{ChatKind, MessageBatchInput, TinaDb};
+use crate::{ChatKind, MUTED_FOREVER, MessageBatchInput, TinaDb};
 
 async fn fresh() -> TinaDb {
     let db = TinaDb::in_memory().await.expect("open in-memory db");
@@ -28,9 +28,46 @@ async fn infer_kind_from_jid() {
         ChatKind::infer_from_jid("status@broadcast"),
         ChatKind::Status
     );
+    assert_eq!(
+        ChatKind::infer_from_jid("1700000000@broadcast"),
+        ChatKind::Broadcast
+    );
     assert_eq!(ChatKind::infer_from_jid("foo@unknown"), ChatKind::Unknown);
 }
 
+#[tokio::test]
+async fn status_feed_is_left_out_of_the_chat_list_unless_asked() {
+    let db = fresh().await;
+    let dm = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    let status = db
+        .register_chat_alias("acc1", "status@broadcast", ChatKind::Status)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "d1", &dm, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "s1", &status, None, Some("🌅"), "text", 200, false, None)
+        .await
+        .unwrap();
+
+    let ids = |rows: Vec<crate::ChatRow>| {
+        let mut ids: Vec<String> = rows.into_iter().map(|r| r.chat_id).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids(db.list_chat_rows("acc1").await.unwrap()), vec![PN]);
+    assert_eq!(
+        ids(db.list_chat_rows_with("acc1", true).await.unwrap()),
+        vec![PN, "status@broadcast"]
+    );
+    let previews = db.get_chats_with_names("acc1").await.unwrap();
+    assert_eq!(previews.len(), 1);
+    assert_eq!(previews[0].chat_jid, PN);
+}
+
 // =================================================================
 // register_chat_alias / link_chat
 // =================================================================
@@ -157,6 +194,150 @@ async fn group_chat_keeps_jid_as_id() {
     assert_eq!(chat.kind, "group");
 }
 
+#[tokio::test]
+async fn group_invite_link_is_cached_and_replaced() {
+    let db = fresh().await;
+    assert_eq!(db.get_group_invite_link("acc1", GROUP).await.unwrap(), None);
+
+    db.upsert_group("acc1", GROUP, Some("Família"), None, None, None)
+        .await
+        .unwrap();
+    db.set_group_invite_link("acc1", GROUP, "https://chat.whatsapp.com/AAA")
+        .await
+        .unwrap();
+    // Revogado: o link novo substitui o antigo, o resto do grupo fica.
+    db.set_group_invite_link("acc1", GROUP, "https://chat.whatsapp.com/BBB")
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_group_invite_link("acc1", GROUP)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("https://chat.whatsapp.com/BBB")
+    );
+    let subject: Option<String> =
+        sqlx::query_scalar("SELECT subject FROM groups WHERE account_id = ? AND chat_id = ?")
+            .bind("acc1")
+            .bind(GROUP)
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+    assert_eq!(subject.as_deref(), Some("Família"));
+}
+
+#[tokio::test]
+async fn groups_summary_counts_participants_in_sql() {
+    let db = fresh().await;
+    db.upsert_group(
+        "acc1",
+        GROUP,
+        Some("Família"),
+        None,
+        None,
+        Some(r#"[{"id":"a"},{"id":"b"},{"id":"c"}]"#),
+    )
+    .await
+    .unwrap();
+    // Só o link de convite chegou: sem participantes nem assunto ainda.
+    let bare = "120363400000000002@g.us";
+    db.set_group_invite_link("acc1", bare, "https://chat.whatsapp.com/AAA")
+        .await
+        .unwrap();
+
+    let groups = db.list_groups_summary("acc1").await.unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].chat_id, GROUP);
+    assert_eq!(groups[0].subject.as_deref(), Some("Família"));
+    assert_eq!(groups[0].participant_count, 3);
+    assert_eq!(groups[1].chat_id, bare);
+    assert_eq!(groups[1].subject, None);
+    assert_eq!(groups[1].participant_count, 0);
+    assert!(db.list_groups_summary("acc2").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn common_groups_match_any_alias_of_the_contact() {
+    let db = fresh().await;
+    db.link_contact("acc1", PN, Some(LID)).await.unwrap();
+    let work = "120363400000000002@g.us";
+    let football = "120363400000000003@g.us";
+    // Participante pelo LID, que só o alias liga ao PN.
+    db.upsert_group(
+        "acc1",
+        GROUP,
+        Some("Família"),
+        None,
+        None,
+        Some(&format!(r#"[{{"id":"{LID}"}},{{"id":"{PN2}"}}]"#)),
+    )
+    .await
+    .unwrap();
+    // LID que o banco não conhece, mas com o número em `phone_number`.
+    db.upsert_group(
+        "acc1",
+        work,
+        Some("Trabalho"),
+        None,
+        None,
+        Some(r#"[{"id":"999@lid","phone_number":"5511999999999","admin":"admin"}]"#),
+    )
+    .await
+    .unwrap();
+    db.upsert_group(
+        "acc1",
+        football,
+        Some("Futebol"),
+        None,
+        None,
+        Some(&format!(r#"[{{"id":"{PN2}","phone_number":"5511888888888"}}]"#)),
+    )
+    .await
+    .unwrap();
+    // Ainda sem participantes: não entra nem quebra a query.
+    db.set_group_invite_link("acc1", "120363400000000004@g.us", "https://chat.whatsapp.com/AAA")
+        .await
+        .unwrap();
+
+    for jid in [PN, LID] {
+        let common = db.get_common_groups("acc1", jid).await.unwrap();
+        let ids: Vec<&str> = common.iter().map(|g| g.chat_id.as_str()).collect();
+        assert_eq!(ids, vec![GROUP, work], "{jid}");
+        assert_eq!(common[0].participant_count, 2);
+    }
+    let common = db.get_common_groups("acc1", PN2).await.unwrap();
+    assert_eq!(common.len(), 2);
+    assert!(db.get_common_groups("acc2", PN).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn mention_candidates_flag_group_admins() {
+    let db = fresh().await;
+    db.upsert_group(
+        "acc1",
+        GROUP,
+        Some("Família"),
+        None,
+        None,
+        Some(
+            r#"[{"id":"111@s.whatsapp.net","admin":"superadmin"},
+                {"id":"222@s.whatsapp.net","admin":"admin"},
+                {"id":"333@s.whatsapp.net"}]"#,
+        ),
+    )
+    .await
+    .unwrap();
+
+    let candidates = db.list_mention_candidates("acc1", GROUP, None).await.unwrap();
+    let admins: Vec<&str> = candidates
+        .iter()
+        .filter(|c| c.is_admin)
+        .map(|c| c.jid.as_str())
+        .collect();
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(admins, ["111@s.whatsapp.net", "222@s.whatsapp.net"]);
+}
+
 // =================================================================
 // register_contact_alias / link_contact
 // =================================================================
@@ -274,6 +455,317 @@ async fn upsert_contact_fields_preserves_existing() {
     assert_eq!(c.contact_name.as_deref(), Some("Contact Name"));
 }
 
+#[tokio::test]
+async fn contact_display_name_falls_back_level_by_level() {
+    let db = fresh().await;
+    // Sem nome nem telefone: o JID.
+    let lid = db.register_contact_alias("acc1", LID).await.unwrap();
+    let c = db.get_contact("acc1", &lid).await.unwrap().unwrap();
+    assert_eq!(c.display_name(), LID);
+
+    // Um JID de telefone já traz o número, formatado.
+    let id = db.register_contact_alias("acc1", PN).await.unwrap();
+    let name = || async {
+        db.get_contact("acc1", &id)
+            .await
+            .unwrap()
+            .unwrap()
+            .display_name()
+    };
+    assert_eq!(name().await, "+55 11 99999-9999");
+    assert_eq!(db.get_chat_name("acc1", PN).await.unwrap(), None);
+
+    // Cada passo acrescenta um nome de prioridade maior:
+    // (business, push, verified, contact).
+    let steps = [
+        ([Some("Biz Ltda"), None, None, None], "Biz Ltda"),
+        ([None, None, Some("Pushname"), None], "Pushname"),
+        ([None, Some("Biz Verified"), None, None], "Biz Verified"),
+        ([None, None, None, Some("Saved Name")], "Saved Name"),
+    ];
+    for ([business, verified, push, contact], want) in steps {
+        db.upsert_contact_fields(
+            "acc1", &id, None, None, None, push, contact, business, verified, None, None, false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(name().await, want);
+        // O SQL de nome do chat segue a mesma ordem.
+        let chat_name = db.get_chat_name("acc1", PN).await.unwrap();
+        assert_eq!(chat_name.as_deref(), Some(want));
+    }
+
+    // Nome em branco não conta.
+    assert_eq!(
+        crate::contact_display_name(Some("  "), Some("Biz Verified"), None, None),
+        Some("Biz Verified")
+    );
+}
+
+#[tokio::test]
+async fn blank_names_are_stored_as_null() {
+    let db = fresh().await;
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("  "), Some(""))])
+        .await
+        .unwrap();
+    let id = db.register_contact_alias("acc1", PN).await.unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!((c.push_name, c.contact_name), (None, None));
+
+    db.upsert_contact_fields(
+        "acc1",
+        &id,
+        None,
+        None,
+        None,
+        Some("Ana"),
+        None,
+        Some(" "),
+        Some("  "),
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    // Branco não apaga o que já havia.
+    db.set_contact_profile("acc1", PN, None, None, Some(" "))
+        .await
+        .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.push_name.as_deref(), Some("Ana"));
+    assert_eq!((c.business_name, c.verified_name), (None, None));
+
+    let chat = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.set_chat_display_name("acc1", &chat, Some("   "))
+        .await
+        .unwrap();
+    let row = db.get_chat("acc1", &chat).await.unwrap().unwrap();
+    assert_eq!(row.display_name, None);
+}
+
+fn server_contact<'a>(
+    push_name: Option<&'a str>,
+    contact_name: Option<&'a str>,
+) -> crate::ContactBatchInput<'a> {
+    crate::ContactBatchInput {
+        jid: PN,
+        lid: None,
+        phone_number: None,
+        push_name,
+        contact_name,
+        verified_name: None,
+        avatar_url: None,
+        status: None,
+    }
+}
+
+async fn save_local_name(db: &TinaDb, id: &str, name: &str) {
+    db.upsert_contact_fields(
+        "acc1",
+        id,
+        None,
+        None,
+        None,
+        None,
+        Some(name),
+        None,
+        None,
+        None,
+        None,
+        true,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn server_update_keeps_local_contact_name() {
+    let db = fresh().await;
+    let id = db.register_contact_alias("acc1", PN).await.unwrap();
+    save_local_name(&db, &id, "Tia Ana").await;
+
+    // Sem push/verified name: nome em branco não apaga, segue local.
+    db.upsert_contacts_batch("acc1", &[server_contact(None, Some(""))])
+        .await
+        .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+    assert!(c.is_local);
+
+    // Com push name vira não-local, mas o nome salvo à mão fica.
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("Ana"), Some("Ana S."))])
+        .await
+        .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+    assert_eq!(c.push_name.as_deref(), Some("Ana"));
+    assert!(!c.is_local);
+
+    // E segue assim nos próximos syncs, com o contato já não-local.
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("Ana"), Some("Ana Souza"))])
+        .await
+        .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+
+    // Caminho de um contato só segue a mesma regra.
+    let id2 = db.register_contact_alias("acc1", PN2).await.unwrap();
+    save_local_name(&db, &id2, "Encanador").await;
+    db.upsert_contact_fields(
+        "acc1",
+        &id2,
+        None,
+        None,
+        None,
+        None,
+        Some("Server Name"),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    let c = db.get_contact("acc1", &id2).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Encanador"));
+    assert!(c.is_local);
+}
+
+#[tokio::test]
+async fn local_name_over_server_contact() {
+    let db = fresh().await;
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("Ana"), None)])
+        .await
+        .unwrap();
+    let id = db.register_contact_alias("acc1", PN).await.unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert!(!c.is_local);
+
+    save_local_name(&db, &id, "Tia Ana").await;
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+    assert_eq!(c.push_name.as_deref(), Some("Ana"));
+
+    // Push name de novo: o contato é do servidor, o nome salvo fica.
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("Ana"), None)])
+        .await
+        .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+    assert!(!c.is_local);
+    assert!(c.name_is_local);
+
+    // Já não-local, o servidor mandando outro nome não troca o salvo.
+    db.upsert_contacts_batch("acc1", &[server_contact(Some("Ana"), Some("Ana S."))])
+        .await
+        .unwrap();
+    db.upsert_contact_fields(
+        "acc1",
+        &id,
+        None,
+        None,
+        None,
+        None,
+        Some("Ana Souza"),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!(c.contact_name.as_deref(), Some("Tia Ana"));
+    assert!(!c.is_local);
+}
+
+#[tokio::test]
+async fn search_contacts_filters_and_pages() {
+    let db = fresh().await;
+    let ana = db.register_contact_alias("acc1", PN).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1", &ana, None, None, None, Some("ana"), Some("Ana Souza"), None, None, None, None,
+        false,
+    )
+    .await
+    .unwrap();
+    let shop = db.register_contact_alias("acc1", PN2).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1", &shop, None, None, None, None, None, None, Some("Padaria 100%"), None, None,
+        false,
+    )
+    .await
+    .unwrap();
+    db.register_contact_alias("acc1", LID).await.unwrap();
+
+    let ids = |cs: Vec<crate::Contact>| cs.into_iter().map(|c| c.contact_id).collect::<Vec<_>>();
+    let search = |q: Option<&'static str>, limit, offset| {
+        let db = &db;
+        async move { ids(db.search_contacts("acc1", q, limit, offset).await.unwrap()) }
+    };
+
+    assert_eq!(search(Some("SOUZA"), 10, 0).await, vec![ana.clone()]);
+    assert_eq!(search(Some("padaria"), 10, 0).await, vec![shop.clone()]);
+    // `%` do usuário é literal, não curinga.
+    assert_eq!(search(Some("100%"), 10, 0).await, vec![shop.clone()]);
+    assert!(search(Some("a%z"), 10, 0).await.is_empty());
+    // Telefone formatado casa com o número só de dígitos.
+    assert_eq!(search(Some("+55 11 8888"), 10, 0).await, vec![shop.clone()]);
+
+    let everyone = search(None, 10, 0).await;
+    assert_eq!(everyone.len(), 3);
+    assert_eq!(search(Some("  "), 10, 0).await, everyone);
+    let page: Vec<_> = [search(None, 2, 0).await, search(None, 2, 2).await].concat();
+    assert_eq!(page, everyone);
+}
+
+#[tokio::test]
+async fn search_contacts_pages_stably_through_equal_names() {
+    let db = fresh().await;
+    let mut expected = Vec::new();
+    for i in 0..7 {
+        let id = db
+            .register_contact_alias("acc1", &format!("5511977770{i:03}@s.whatsapp.net"))
+            .await
+            .unwrap();
+        db.upsert_contact_fields(
+            "acc1",
+            &id,
+            None,
+            None,
+            None,
+            Some("Ana"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+        expected.push(id);
+    }
+    // Nomes iguais desempatam pelo `contact_id`.
+    expected.sort();
+
+    let mut paged = Vec::new();
+    for offset in (0..7).step_by(3) {
+        let page = db
+            .search_contacts("acc1", Some("ana"), 3, offset)
+            .await
+            .unwrap();
+        paged.extend(page.into_iter().map(|c| c.contact_id));
+    }
+    assert_eq!(paged, expected);
+}
+
 // =================================================================
 // list_chat_rows: nome de DM via JOIN, nome de grupo via display_name
 // =================================================================
@@ -389,6 +881,49 @@ async fn list_chat_rows_orders_by_last_message_ts_desc() {
     assert_eq!(ids, vec![PN2, GROUP, PN]);
 }
 
+#[tokio::test]
+async fn chat_rows_keep_canonical_order_after_incremental_update() {
+    let db = fresh().await;
+    let a = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let b = db.register_chat_alias("acc1", PN2, ChatKind::Dm).await.unwrap();
+    let g = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    for (chat, ts) in [(&a, 100), (&b, 200), (&g, 200)] {
+        db.update_chat_last_message("acc1", chat, "m", Some("x"), ts, false, None)
+            .await
+            .unwrap();
+    }
+    db.set_chat_pinned("acc1", &a, true).await.unwrap();
+
+    let order = |rows: &[crate::ChatRow]| -> Vec<String> {
+        rows.iter().map(|r| r.chat_id.clone()).collect()
+    };
+    let rows = db.list_chat_rows("acc1").await.unwrap();
+    let first = order(&rows);
+    assert_eq!(first[0], PN, "pinned chat on top despite being oldest");
+
+    // Nova mensagem no chat mais antigo entre os não fixados: a linha
+    // re-lida tem que cair onde um reload completo a colocaria.
+    let older = first[2].clone();
+    db.update_chat_last_message("acc1", &older, "m2", Some("y"), 300, false, None)
+        .await
+        .unwrap();
+    let mut rows = rows;
+    let updated = db
+        .get_chat_rows("acc1", std::slice::from_ref(&older))
+        .await
+        .unwrap();
+    rows.retain(|r| r.chat_id != older);
+    rows.extend(updated);
+    rows.sort_by(|x, y| x.order_key().cmp(&y.order_key()));
+
+    let reloaded = db.list_chat_rows("acc1").await.unwrap();
+    assert_eq!(order(&rows), order(&reloaded));
+    assert_eq!(order(&reloaded), [PN.to_string(), older, first[1].clone()]);
+}
+
 #[tokio::test]
 async fn update_chat_last_message_does_not_overwrite_with_older() {
     let db = fresh().await;
@@ -412,11 +947,311 @@ async fn update_chat_last_message_does_not_overwrite_with_older() {
 }
 
 // =================================================================
-// merge: lookup integrity post-merge
+// get_chat_name
 // =================================================================
 
 #[tokio::test]
-async fn merged_chat_lookup_via_either_alias() {
+async fn chat_name_for_group_uses_subject() {
+    let db = fresh().await;
+    let chat = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.set_chat_display_name("acc1", &chat, Some("Nome antigo"))
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_chat_name("acc1", GROUP).await.unwrap().as_deref(),
+        Some("Nome antigo")
+    );
+
+    db.upsert_group("acc1", &chat, Some("Time da firma"), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_chat_name("acc1", GROUP).await.unwrap().as_deref(),
+        Some("Time da firma")
+    );
+}
+
+#[tokio::test]
+async fn chat_name_for_contact_prefers_saved_name() {
+    let db = fresh().await;
+    let id = db.register_contact_alias("acc1", PN).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1",
+        &id,
+        None,
+        None,
+        None,
+        Some("Pushname"),
+        None,
+        None,
+        Some("Verified"),
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    // Nome verificado passa na frente do pushname.
+    assert_eq!(
+        db.get_chat_name("acc1", PN).await.unwrap().as_deref(),
+        Some("Verified")
+    );
+
+    db.upsert_contact_fields(
+        "acc1",
+        &id,
+        None,
+        None,
+        None,
+        None,
+        Some("João"),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        db.get_chat_name("acc1", PN).await.unwrap().as_deref(),
+        Some("João")
+    );
+}
+
+#[tokio::test]
+async fn chat_name_for_unknown_jid_is_none() {
+    let db = fresh().await;
+    assert_eq!(db.get_chat_name("acc1", PN2).await.unwrap(), None);
+    assert_eq!(db.get_chat_name("acc1", GROUP).await.unwrap(), None);
+
+    // Contato conhecido mas sem nenhum nome ainda.
+    db.register_contact_alias("acc1", PN).await.unwrap();
+    assert_eq!(db.get_chat_name("acc1", PN).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn chat_info_for_group_and_dm() {
+    let db = fresh().await;
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.upsert_group(
+        "acc1",
+        &group,
+        Some("Família"),
+        None,
+        Some("Só fotos do almoço"),
+        Some(r#"[{"id":"a"},{"id":"b"},{"id":"c"}]"#),
+    )
+    .await
+    .unwrap();
+    let info = db.get_chat_info("acc1", GROUP).await.unwrap().unwrap();
+    assert_eq!(info.kind, ChatKind::Group);
+    assert_eq!(info.name.as_deref(), Some("Família"));
+    assert_eq!(info.description.as_deref(), Some("Só fotos do almoço"));
+    assert_eq!(info.participant_count, Some(3));
+    assert!(info.phone_number.is_none());
+
+    db.register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    let cid = db.register_contact_alias("acc1", PN).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1",
+        &cid,
+        None,
+        None,
+        None,
+        Some("João"),
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    let info = db.get_chat_info("acc1", PN).await.unwrap().unwrap();
+    assert_eq!(info.kind, ChatKind::Dm);
+    assert_eq!(info.name.as_deref(), Some("João"));
+    assert_eq!(info.phone_number.as_deref(), Some("5511999999999"));
+    assert!(info.participant_count.is_none() && info.description.is_none());
+
+    assert!(db.get_chat_info("acc1", PN2).await.unwrap().is_none());
+}
+
+// =================================================================
+// get_chats_with_names / get_chat_previews_batch
+// =================================================================
+
+#[tokio::test]
+async fn chat_previews_resolve_names_and_last_message() {
+    let db = fresh().await;
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.upsert_group("acc1", &group, Some("Time da firma"), None, None, None)
+        .await
+        .unwrap();
+    let dm = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    let cid = db.register_contact_alias("acc1", PN).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1",
+        &cid,
+        None,
+        None,
+        None,
+        Some("Pushname"),
+        Some("João"),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+
+    db.insert_message("acc1", "g1", &group, Some(&cid), Some("bom dia"), "text", 100, false, None)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "g2", &group, Some(&cid), Some("reunião?"), "text", 300, false, None)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "d1", &dm, None, Some("oi"), "text", 200, true, None)
+        .await
+        .unwrap();
+
+    let rows = db.get_chats_with_names("acc1").await.unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let g = &rows[0];
+    assert_eq!(g.chat_jid, GROUP);
+    assert!(g.is_group);
+    assert_eq!(g.resolved_name.as_deref(), Some("Time da firma"));
+    assert_eq!(g.last_message_id.as_deref(), Some("g2"));
+    assert_eq!(g.last_timestamp, Some(300));
+    assert_eq!(g.last_message_content.as_deref(), Some("reunião?"));
+    assert!(!g.last_message_from_me);
+    assert_eq!(g.last_sender_name.as_deref(), Some("João"));
+
+    let d = &rows[1];
+    assert_eq!(d.chat_jid, PN);
+    assert!(!d.is_group);
+    assert_eq!(d.resolved_name.as_deref(), Some("João"));
+    assert_eq!(d.last_message_content.as_deref(), Some("oi"));
+    assert_eq!(d.last_message_type.as_deref(), Some("text"));
+    assert!(d.last_message_from_me);
+    assert_eq!(d.last_sender_name, None);
+
+    let batch = db
+        .get_chat_previews_batch("acc1", &[PN, "nope@s.whatsapp.net"])
+        .await
+        .unwrap();
+    assert_eq!(batch.len(), 1);
+    assert_eq!(batch[0].chat_jid, PN);
+    assert_eq!(batch[0].last_timestamp, Some(200));
+}
+
+// =================================================================
+// edit_message_content
+// =================================================================
+
+#[tokio::test]
+async fn edit_message_updates_row_and_chat_preview() {
+    let db = fresh().await;
+    let chat = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "m1", &chat, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+    db.update_chat_last_message("acc1", &chat, "m1", Some("oi"), 100, false, None)
+        .await
+        .unwrap();
+
+    let edited = db
+        .edit_message_content("acc1", "m1", "olá", 150)
+        .await
+        .unwrap();
+    assert_eq!(edited.as_deref(), Some(chat.as_str()));
+
+    let rows = db
+        .get_message_rows_by_ids("acc1", &["m1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].content.as_deref(), Some("olá"));
+    assert_eq!(rows[0].edited_at, Some(150));
+    let chat_rows = db.list_chat_rows("acc1").await.unwrap();
+    assert_eq!(chat_rows[0].last_message_preview.as_deref(), Some("olá"));
+
+    // Edição atrasada (mais velha que a aplicada) é descartada.
+    assert_eq!(
+        db.edit_message_content("acc1", "m1", "velho", 120)
+            .await
+            .unwrap(),
+        None
+    );
+    let rows = db
+        .get_message_rows_by_ids("acc1", &["m1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].content.as_deref(), Some("olá"));
+}
+
+#[tokio::test]
+async fn edit_of_unknown_message_is_ignored() {
+    let db = fresh().await;
+    assert_eq!(
+        db.edit_message_content("acc1", "nope", "x", 1)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn message_rows_carry_raw_json_only_for_structured_kinds() {
+    let db = fresh().await;
+    let chat = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    let loc = r#"{"location_message":{"degrees_latitude":1,"degrees_longitude":2}}"#;
+    let img = r#"{"image_message":{"mimetype":"image/jpeg"}}"#;
+    db.insert_message("acc1", "l1", &chat, None, Some("[Location]"), "location", 100, false, Some(loc))
+        .await
+        .unwrap();
+    db.insert_message("acc1", "i1", &chat, None, Some("[Image]"), "image", 101, false, Some(img))
+        .await
+        .unwrap();
+
+    let rows = db
+        .get_message_rows_by_ids("acc1", &["l1".to_string(), "i1".to_string()])
+        .await
+        .unwrap();
+    let by_id = |id: &str| rows.iter().find(|r| r.message_id == id).unwrap();
+    assert_eq!(by_id("l1").raw_json.as_deref(), Some(loc));
+    assert_eq!(by_id("i1").raw_json, None);
+}
+
+// =================================================================
+// merge: lookup integrity post-merge
+// =================================================================
+
+#[tokio::test]
+async fn merged_chat_lookup_via_either_alias() {
     let db = fresh().await;
     db.link_chat("acc1", PN, Some(LID), ChatKind::Dm)
         .await
@@ -543,58 +1378,15 @@ async fn run_message_batch_dedupes_resolves_and_aggregates_last_message() {
 }
 
 #[tokio::test]
-async fn run_message_batch_emits_active_chat_message_ids() {
-    let db = fresh().await;
-    let messages = vec![
-        MessageBatchInput {
-            message_id: "m-active-1",
-            chat_jid: PN,
-            sender_jid: Some(PN),
-            content: Some("a"),
-            message_type: "text",
-            timestamp: 100,
-            is_from_me: false,
-            raw_json: None,
-            media: None,
-            quoted_message_id: None,
-            quoted_sender_id: None,
-            quoted_preview: None,
-            mentions_json: None,
-        },
-        MessageBatchInput {
-            message_id: "m-other",
-            chat_jid: GROUP,
-            sender_jid: None,
-            content: Some("b"),
-            message_type: "text",
-            timestamp: 100,
-            is_from_me: true,
-            raw_json: None,
-            media: None,
-            quoted_message_id: None,
-            quoted_sender_id: None,
-            quoted_preview: None,
-            mentions_json: None,
-        },
-    ];
-    let res = db
-        .run_message_batch("acc1", Some(PN), &messages)
-        .await
-        .unwrap();
-    assert_eq!(res.active_chat_message_ids, vec!["m-active-1".to_string()]);
-    assert_eq!(res.affected_chat_ids.len(), 2);
-}
-
-#[tokio::test]
-async fn run_message_batch_skips_duplicates_via_insert_or_ignore() {
+async fn delete_chat_messages_clears_only_that_chat() {
     let db = fresh().await;
-    let msg = MessageBatchInput {
-        message_id: "dup",
-        chat_jid: PN,
-        sender_jid: Some(PN),
-        content: Some("primeira"),
+    let message = |id: &'static str, chat: &'static str, ts: i64| MessageBatchInput {
+        message_id: id,
+        chat_jid: chat,
+        sender_jid: Some(chat),
+        content: Some(id),
         message_type: "text",
-        timestamp: 100,
+        timestamp: ts,
         is_from_me: false,
         raw_json: None,
         media: None,
@@ -603,67 +1395,1525 @@ async fn run_message_batch_skips_duplicates_via_insert_or_ignore() {
         quoted_preview: None,
         mentions_json: None,
     };
-    let r1 = db.run_message_batch("acc1", None, &[msg]).await.unwrap();
-    assert_eq!(r1.affected_chat_ids.len(), 1);
+    db.run_message_batch(
+        "acc1",
+        None,
+        &[
+            message("a1", PN, 100),
+            message("a2", PN, 200),
+            message("a3", PN, 300),
+            message("b1", PN2, 150),
+        ],
+    )
+    .await
+    .unwrap();
+    db.star_message("acc1", "a1").await.unwrap();
+    db.pin_message("acc1", PN, "a3", 400).await.unwrap();
 
-    // Re-roda com o mesmo ID — INSERT OR IGNORE ⇒ não vira affected.
-    let r2 = db.run_message_batch("acc1", None, &[msg]).await.unwrap();
-    assert_eq!(r2.affected_chat_ids.len(), 0);
+    assert_eq!(db.delete_chat_messages("acc1", PN).await.unwrap(), 2);
 
-    let n = db.count_messages_for_chat("acc1", PN).await.unwrap();
+    // Favorita fica e vira a última mensagem do chat; o pin some.
+    let left = db.get_message_rows_by_chat("acc1", PN, 10, 0).await.unwrap();
+    assert_eq!(
+        left.iter().map(|r| r.message_id.as_str()).collect::<Vec<_>>(),
+        vec!["a1"]
+    );
+    assert!(db.get_pinned_messages("acc1", PN).await.unwrap().is_empty());
+    let chat = db.get_chat("acc1", PN).await.unwrap().unwrap();
+    assert_eq!(chat.last_message_id.as_deref(), Some("a1"));
+    assert_eq!(chat.last_message_ts, Some(100));
+    let row = db.get_chat_rows("acc1", &[PN.to_string()]).await.unwrap();
+    assert_eq!(row[0].unread_count, 0);
+
+    // O outro chat não é tocado.
+    assert_eq!(db.count_messages_for_chat("acc1", PN2).await.unwrap(), 1);
+    let other = db.get_chat("acc1", PN2).await.unwrap().unwrap();
+    assert_eq!(other.last_message_id.as_deref(), Some("b1"));
+
+    db.unstar_message("acc1", "a1").await.unwrap();
+    assert_eq!(db.delete_chat_messages("acc1", PN).await.unwrap(), 1);
+    let chat = db.get_chat("acc1", PN).await.unwrap().unwrap();
+    assert_eq!(chat.last_message_id, None);
+    assert_eq!(chat.last_message_ts, None);
+}
+
+#[tokio::test]
+async fn own_number_as_sender_is_stored_as_from_me() {
+    let db = fresh().await;
+    let message = |id: &'static str, sender: &'static str, ts: i64| MessageBatchInput {
+        message_id: id,
+        chat_jid: GROUP,
+        sender_jid: Some(sender),
+        content: Some("oi"),
+        message_type: "text",
+        timestamp: ts,
+        is_from_me: false,
+        raw_json: None,
+        media: None,
+        quoted_message_id: None,
+        quoted_sender_id: None,
+        quoted_preview: None,
+        mentions_json: None,
+    };
+    let from_me = |rows: Vec<crate::MessageRow>| {
+        rows.into_iter()
+            .map(|r| (r.message_id, r.is_from_me))
+            .collect::<Vec<_>>()
+    };
+    // m1 é nossa, mas veio marcada como de outra pessoa.
+    db.run_message_batch(
+        "acc1",
+        None,
+        &[message("m1", PN, 100), message("m2", PN2, 200)],
+    )
+    .await
+    .unwrap();
+
+    // Sem identidade conhecida, vale a flag que veio.
+    let rows = db
+        .get_message_rows_by_chat("acc1", GROUP, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        from_me(rows),
+        vec![("m1".into(), false), ("m2".into(), false)]
+    );
+    assert_eq!(db.unread_totals().await.unwrap().get("acc1"), Some(&2));
+
+    // O JID do `Connected` traz o device; o número é o que casa. O que
+    // já estava gravado é corrigido na hora.
+    db.save_account_identity(
+        "acc1",
+        Some("5511999999999"),
+        Some("5511999999999:12@s.whatsapp.net"),
+    )
+    .await
+    .unwrap();
+    let rows = db
+        .get_message_rows_by_chat("acc1", GROUP, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        from_me(rows),
+        vec![("m1".into(), true), ("m2".into(), false)]
+    );
+    assert_eq!(db.unread_totals().await.unwrap().get("acc1"), Some(&1));
+
+    // Daqui pra frente, já no insert: número com device e a forma LID.
+    db.link_contact("acc1", PN, Some(LID)).await.unwrap();
+    db.run_message_batch(
+        "acc1",
+        None,
+        &[
+            message("m3", "5511999999999:12@s.whatsapp.net", 300),
+            message("m4", LID, 400),
+        ],
+    )
+    .await
+    .unwrap();
+    let rows = db
+        .get_message_rows_by_ids("acc1", &["m3".to_string(), "m4".to_string()])
+        .await
+        .unwrap();
+    assert!(
+        rows.iter()
+            .all(|r| r.is_from_me && r.sender_contact_id.is_none())
+    );
+    assert_eq!(db.unread_totals().await.unwrap().get("acc1"), Some(&1));
+    let chat = db.get_chat("acc1", GROUP).await.unwrap().unwrap();
+    assert!(chat.last_message_from_me);
+}
+
+#[tokio::test]
+async fn run_message_batch_emits_active_chat_message_ids() {
+    let db = fresh().await;
+    let messages = vec![
+        MessageBatchInput {
+            message_id: "m-active-1",
+            chat_jid: PN,
+            sender_jid: Some(PN),
+            content: Some("a"),
+            message_type: "text",
+            timestamp: 100,
+            is_from_me: false,
+            raw_json: None,
+            media: None,
+            quoted_message_id: None,
+            quoted_sender_id: None,
+            quoted_preview: None,
+            mentions_json: None,
+        },
+        MessageBatchInput {
+            message_id: "m-other",
+            chat_jid: GROUP,
+            sender_jid: None,
+            content: Some("b"),
+            message_type: "text",
+            timestamp: 100,
+            is_from_me: true,
+            raw_json: None,
+            media: None,
+            quoted_message_id: None,
+            quoted_sender_id: None,
+            quoted_preview: None,
+            mentions_json: None,
+        },
+    ];
+    let res = db
+        .run_message_batch("acc1", Some(PN), &messages)
+        .await
+        .unwrap();
+    assert_eq!(res.active_chat_message_ids, vec!["m-active-1".to_string()]);
+    assert_eq!(res.affected_chat_ids.len(), 2);
+}
+
+#[tokio::test]
+async fn run_message_batch_skips_duplicates_via_insert_or_ignore() {
+    let db = fresh().await;
+    let msg = MessageBatchInput {
+        message_id: "dup",
+        chat_jid: PN,
+        sender_jid: Some(PN),
+        content: Some("primeira"),
+        message_type: "text",
+        timestamp: 100,
+        is_from_me: false,
+        raw_json: None,
+        media: None,
+        quoted_message_id: None,
+        quoted_sender_id: None,
+        quoted_preview: None,
+        mentions_json: None,
+    };
+    let r1 = db.run_message_batch("acc1", None, &[msg]).await.unwrap();
+    assert_eq!(r1.affected_chat_ids.len(), 1);
+
+    // Re-roda com o mesmo ID — INSERT OR IGNORE ⇒ não vira affected.
+    let r2 = db.run_message_batch("acc1", None, &[msg]).await.unwrap();
+    assert_eq!(r2.affected_chat_ids.len(), 0);
+
+    let n = db.count_messages_for_chat("acc1", PN).await.unwrap();
     assert_eq!(n, 1);
 }
 
 #[tokio::test]
-async fn find_dm_chat_ids_for_aliases_returns_dms_only() {
+async fn chat_message_count_matches_inserted_rows_through_any_alias() {
+    let db = fresh().await;
+    let chat = db
+        .link_chat("acc1", PN, Some(LID), ChatKind::Dm)
+        .await
+        .unwrap();
+    let other = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    for i in 0..3 {
+        let id = format!("m{i}");
+        db.insert_message("acc1", &id, &chat, None, Some("oi"), "text", 100 + i, false, None)
+            .await
+            .unwrap();
+    }
+    db.insert_message("acc1", "g1", &other, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+
+    assert_eq!(db.get_chat_message_count("acc1", PN).await.unwrap(), 3);
+    assert_eq!(db.get_chat_message_count("acc1", LID).await.unwrap(), 3);
+    assert_eq!(db.get_chat_message_count("acc1", GROUP).await.unwrap(), 1);
+    assert_eq!(
+        db.get_chat_message_count("acc1", "nope@s.whatsapp.net")
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn quoted_preview_falls_back_to_local_original() {
+    let db = fresh().await;
+    let original = MessageBatchInput {
+        message_id: "orig",
+        chat_jid: PN,
+        sender_jid: Some(PN),
+        content: Some("bora almoçar?"),
+        message_type: "text",
+        timestamp: 100,
+        is_from_me: false,
+        raw_json: None,
+        media: None,
+        quoted_message_id: None,
+        quoted_sender_id: None,
+        quoted_preview: None,
+        mentions_json: None,
+    };
+    // Proto sem preview nem participant: sai tudo da original.
+    let bare = MessageBatchInput {
+        message_id: "reply-bare",
+        sender_jid: None,
+        content: Some("bora"),
+        timestamp: 200,
+        is_from_me: true,
+        quoted_message_id: Some("orig"),
+        ..original
+    };
+    let with_preview = MessageBatchInput {
+        message_id: "reply-proto",
+        timestamp: 300,
+        quoted_preview: Some("do proto"),
+        ..bare
+    };
+    let orphan = MessageBatchInput {
+        message_id: "reply-orphan",
+        timestamp: 400,
+        quoted_message_id: Some("never-synced"),
+        ..bare
+    };
+    db.run_message_batch("acc1", None, &[original, bare, with_preview, orphan])
+        .await
+        .unwrap();
+
+    let ids = ["reply-bare", "reply-proto", "reply-orphan"].map(String::from);
+    let rows = db.get_message_rows_by_ids("acc1", &ids).await.unwrap();
+    let row = |id: &str| rows.iter().find(|r| r.message_id == id).unwrap();
+    assert_eq!(
+        row("reply-bare").quoted_preview.as_deref(),
+        Some("bora almoçar?")
+    );
+    assert!(row("reply-bare").quoted_sender_name.is_some());
+    assert_eq!(
+        row("reply-proto").quoted_preview.as_deref(),
+        Some("do proto")
+    );
+    // Original fora do banco: a UI mostra o texto genérico.
+    assert_eq!(row("reply-orphan").quoted_preview, None);
+    assert_eq!(row("reply-orphan").quoted_sender_name, None);
+}
+
+#[tokio::test]
+async fn find_similar_message_matches_each_own_row_once() {
+    let db = fresh().await;
+    let base = MessageBatchInput {
+        message_id: "live-echo",
+        chat_jid: PN,
+        sender_jid: None,
+        content: Some("chego em 5"),
+        message_type: "text",
+        timestamp: 1000,
+        is_from_me: true,
+        raw_json: None,
+        media: None,
+        quoted_message_id: None,
+        quoted_sender_id: None,
+        quoted_preview: None,
+        mentions_json: None,
+    };
+    let theirs = MessageBatchInput {
+        message_id: "their-msg",
+        sender_jid: Some(PN),
+        content: Some("ok"),
+        is_from_me: false,
+        ..base
+    };
+    db.run_message_batch("acc1", None, &[base, theirs])
+        .await
+        .unwrap();
+    let db = &db;
+    let similar = |id: &'static str, content: &'static str, ts: i64| async move {
+        db.find_similar_message("acc1", PN, id, content, ts, 2)
+            .await
+            .unwrap()
+    };
+
+    // Cópia do history sync: outro ID, mesmo texto, 1s depois.
+    assert_eq!(
+        similar("history-copy", "chego em 5", 1001).await.as_deref(),
+        Some("live-echo")
+    );
+    // A própria linha, ou um ID já guardado, não é cópia.
+    assert_eq!(similar("live-echo", "chego em 5", 1000).await, None);
+    assert_eq!(similar("their-msg", "chego em 5", 1000).await, None);
+    // Fora da janela, texto diferente ou mensagem de terceiros: não.
+    assert_eq!(similar("x", "chego em 5", 1010).await, None);
+    assert_eq!(similar("x", "chego em 10", 1000).await, None);
+    assert_eq!(similar("x", "ok", 1000).await, None);
+
+    // Depois de absorver uma cópia, só ela casa de novo.
+    db.mark_rekeyed_copy("acc1", "live-echo", "history-copy")
+        .await
+        .unwrap();
+    assert_eq!(
+        similar("history-copy", "chego em 5", 1001).await.as_deref(),
+        Some("live-echo")
+    );
+    assert_eq!(similar("other-copy", "chego em 5", 1001).await, None);
+}
+
+#[tokio::test]
+async fn message_dedup_window_defaults_and_can_be_turned_off() {
+    let db = fresh().await;
+    assert_eq!(db.message_dedup_window().await.unwrap(), 2);
+    db.set_message_dedup_window(0).await.unwrap();
+    assert_eq!(db.message_dedup_window().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn older_page_keeps_messages_sharing_the_boundary_second() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    for (id, ts) in [("a", 100), ("b", 200), ("c", 200), ("d", 200), ("e", 300)] {
+        db.insert_message("acc1", id, &dm, None, Some("x"), "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+
+    let ids = |rows: Vec<crate::MessageRow>| -> Vec<String> {
+        rows.into_iter().map(|m| m.message_id).collect()
+    };
+    let newest = db.get_message_rows_by_chat("acc1", &dm, 2, 0).await.unwrap();
+    assert_eq!(ids(newest), ["d", "e"]);
+
+    // A página seguinte começa no "c", do mesmo segundo que o "d".
+    let older = db
+        .get_message_rows_before("acc1", &dm, 200, Some("d"), 2)
+        .await
+        .unwrap();
+    assert_eq!(ids(older), ["b", "c"]);
+    let oldest = db
+        .get_message_rows_before("acc1", &dm, 200, Some("b"), 2)
+        .await
+        .unwrap();
+    assert_eq!(ids(oldest), ["a"]);
+
+    // Sem ID, o corte é estrito por timestamp.
+    let by_ts = db
+        .get_message_rows_before("acc1", &dm, 200, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(by_ts), ["a"]);
+}
+
+#[tokio::test]
+async fn newer_page_keeps_messages_sharing_the_boundary_second() {
+    let db = fresh().await;
+    let dm = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    for (id, ts) in [("a", 100), ("b", 200), ("c", 200), ("d", 200), ("e", 300)] {
+        db.insert_message("acc1", id, &dm, None, Some("x"), "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+
+    let ids = |rows: Vec<crate::MessageRow>| -> Vec<String> {
+        rows.into_iter().map(|m| m.message_id).collect()
+    };
+    let newer = db
+        .get_message_rows_after("acc1", &dm, 200, Some("b"), 2)
+        .await
+        .unwrap();
+    assert_eq!(ids(newer), ["c", "d"]);
+    let newest = db
+        .get_message_rows_after("acc1", &dm, 200, Some("d"), 2)
+        .await
+        .unwrap();
+    assert_eq!(ids(newest), ["e"]);
+
+    let by_ts = db
+        .get_message_rows_after("acc1", &dm, 100, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(by_ts), ["b", "c", "d", "e"]);
+}
+
+#[tokio::test]
+async fn messages_around_center_on_the_target() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    for i in 0..100 {
+        let id = format!("m{i:03}");
+        // Pares dividem o segundo: o desempate é pelo ID.
+        db.insert_message("acc1", &id, &dm, None, Some("x"), "text", 1000 + i / 2, false, None)
+            .await
+            .unwrap();
+    }
+    let ids = |rows: Vec<crate::MessageRow>| -> Vec<String> {
+        rows.into_iter().map(|m| m.message_id).collect()
+    };
+
+    let window = db.get_messages_around("acc1", &dm, "m050", 5).await.unwrap();
+    let expected: Vec<String> = (45..=55).map(|i| format!("m{i:03}")).collect();
+    assert_eq!(ids(window), expected);
+
+    // Perto das pontas a janela só encolhe daquele lado.
+    let head = db.get_messages_around("acc1", &dm, "m002", 5).await.unwrap();
+    assert_eq!(ids(head).first().map(String::as_str), Some("m000"));
+    let tail = db.get_messages_around("acc1", &dm, "m098", 5).await.unwrap();
+    assert_eq!(tail.len(), 7);
+
+    assert!(db.get_messages_around("acc1", &dm, "nope", 5).await.unwrap().is_empty());
+    let other = db.register_chat_alias("acc1", PN2, ChatKind::Dm).await.unwrap();
+    assert!(db.get_messages_around("acc1", &other, "m050", 5).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn filtered_messages_return_only_requested_types() {
+    let db = fresh().await;
+    let chat = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    for (id, kind, ts) in [
+        ("t1", "text", 100),
+        ("i1", "image", 110),
+        ("a1", "audio", 120),
+        ("v1", "video", 130),
+        ("d1", "document", 140),
+        ("i2", "image", 150),
+    ] {
+        db.insert_message("acc1", id, &chat, None, None, kind, ts, false, None)
+            .await
+            .unwrap();
+    }
+    // Outro chat com imagem não vaza para este.
+    let other = db.register_chat_alias("acc1", PN2, ChatKind::Dm).await.unwrap();
+    db.insert_message("acc1", "x1", &other, None, None, "image", 160, false, None)
+        .await
+        .unwrap();
+
+    let media = db
+        .get_messages_filtered("acc1", &chat, Some(&["image", "video"]), 50, 0)
+        .await
+        .unwrap();
+    let ids: Vec<_> = media.iter().map(|m| m.message_id.as_str()).collect();
+    assert_eq!(ids, ["i2", "v1", "i1"]);
+    assert!(
+        media
+            .iter()
+            .all(|m| matches!(m.message_type.as_str(), "image" | "video"))
+    );
+
+    let page = db
+        .get_messages_filtered("acc1", &chat, Some(&["image", "video"]), 1, 1)
+        .await
+        .unwrap();
+    assert_eq!(page[0].message_id, "v1");
+
+    let all = db
+        .get_messages_filtered("acc1", &chat, None, 50, 0)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 6);
+    assert!(
+        db.get_messages_filtered("acc1", &chat, Some(&[]), 50, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn last_messages_for_chats_pick_newest_per_chat() {
+    let db = fresh().await;
+    let a = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let b = db.register_chat_alias("acc1", PN2, ChatKind::Dm).await.unwrap();
+    let empty = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    for (id, chat, ts) in [
+        ("a1", &a, 100),
+        ("a2", &a, 300),
+        ("a3", &a, 200),
+        ("b1", &b, 150),
+        // Mesmo segundo: vence a inserida por último.
+        ("b2", &b, 150),
+    ] {
+        db.insert_message("acc1", id, chat, None, None, "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+    let last = db
+        .get_last_messages_for_chats("acc1", &[a.clone(), b.clone(), empty.clone()])
+        .await
+        .unwrap();
+    assert_eq!(last.len(), 2);
+    assert_eq!(last[&a].message_id, "a2");
+    assert_eq!(last[&b].message_id, "b2");
+    assert!(!last.contains_key(&empty));
+    assert!(
+        db.get_last_messages_for_chats("acc1", &[])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn media_cache_records_and_evicts_least_recently_used() {
+    let db = fresh().await;
+    let chat = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    for (id, path, size, accessed) in [
+        ("img-old", "/cache/aa/old.jpg", 40, 100),
+        ("img-mid", "/cache/bb/mid.jpg", 40, 200),
+        ("img-new", "/cache/cc/new.jpg", 40, 300),
+    ] {
+        db.insert_message("acc1", id, &chat, None, None, "image", 1, false, None)
+            .await
+            .unwrap();
+        db.apply_media_downloaded("acc1", id, path, None, Some("image/jpeg"))
+            .await
+            .unwrap();
+        db.record_media(path, Some("image/jpeg"), size)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE media_files SET accessed_at = ? WHERE path = ?")
+            .bind(accessed)
+            .bind(path)
+            .execute(db.pool())
+            .await
+            .unwrap();
+    }
+    let mid = db.get_media_path("acc1", "img-mid").await.unwrap();
+    assert_eq!(mid.as_deref(), Some("/cache/bb/mid.jpg"));
+    assert_eq!(db.get_media_path("acc1", "nope").await.unwrap(), None);
+    assert_eq!(db.media_cache_size().await.unwrap(), 120);
+
+    // Usar a mais antiga a salva; sai a do meio.
+    db.touch_media("/cache/aa/old.jpg").await.unwrap();
+    let evicted = db.evict_media(100).await.unwrap();
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].path, "/cache/bb/mid.jpg");
+    assert_eq!(db.get_media_path("acc1", "img-mid").await.unwrap(), None);
+    let old = db.get_media_path("acc1", "img-old").await.unwrap();
+    assert!(old.is_some());
+    assert_eq!(db.media_cache_size().await.unwrap(), 80);
+    assert!(db.evict_media(100).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn media_cache_file_shared_by_two_accounts_counts_once() {
+    let db = fresh().await;
+    db.create_account("acc2", None).await.unwrap();
+    let path = "/cache/dd/shared.jpg";
+    for account in ["acc1", "acc2"] {
+        let chat = db
+            .register_chat_alias(account, PN, ChatKind::Dm)
+            .await
+            .unwrap();
+        db.insert_message(account, "img", &chat, None, None, "image", 1, false, None)
+            .await
+            .unwrap();
+        db.apply_media_downloaded(account, "img", path, None, Some("image/jpeg"))
+            .await
+            .unwrap();
+        db.record_media(path, Some("image/jpeg"), 40).await.unwrap();
+    }
+    assert_eq!(db.media_cache_size().await.unwrap(), 40);
+
+    let evicted = db.evict_media(0).await.unwrap();
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].path, path);
+    for account in ["acc1", "acc2"] {
+        assert_eq!(db.get_media_path(account, "img").await.unwrap(), None);
+    }
+}
+
+#[tokio::test]
+async fn v16_media_ledger_merges_rows_of_the_same_file() {
+    let dir = std::env::temp_dir().join(format!("tina-db-v16-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tina.db");
+    let path = path.to_str().unwrap();
+
+    // Volta um banco novo pro formato da v16: uma linha por conta.
+    let db = TinaDb::new_with_path(path).await.unwrap();
+    db.create_account("acc1", None).await.unwrap();
+    db.create_account("acc2", None).await.unwrap();
+    sqlx::raw_sql(
+        "DROP TABLE media_files;
+         CREATE TABLE media_files (
+             account_id TEXT NOT NULL,
+             path TEXT NOT NULL,
+             mimetype TEXT,
+             size_bytes INTEGER NOT NULL DEFAULT 0,
+             downloaded_at INTEGER NOT NULL,
+             accessed_at INTEGER NOT NULL,
+             PRIMARY KEY (account_id, path)
+         );
+         INSERT INTO media_files VALUES
+             ('acc1', '/cache/a.jpg', 'image/jpeg', 40, 10, 100),
+             ('acc2', '/cache/a.jpg', NULL, 40, 20, 300),
+             ('acc2', '/cache/b.jpg', NULL, 5, 30, 30);
+         ALTER TABLE contacts DROP COLUMN name_is_local;
+         ALTER TABLE messages DROP COLUMN rekeyed_copy_id;
+         PRAGMA user_version = 16;",
+    )
+    .execute(db.pool())
+    .await
+    .unwrap();
+    db.pool().close().await;
+
+    let db = TinaDb::new_with_path(path).await.unwrap();
+    assert_eq!(db.media_cache_size().await.unwrap(), 45);
+    let (mimetype, downloaded, accessed): (Option<String>, i64, i64) = sqlx::query_as(
+        "SELECT mimetype, downloaded_at, accessed_at FROM media_files WHERE path = '/cache/a.jpg'",
+    )
+    .fetch_one(db.pool())
+    .await
+    .unwrap();
+    assert_eq!(mimetype.as_deref(), Some("image/jpeg"));
+    assert_eq!((downloaded, accessed), (10, 300));
+    db.pool().close().await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn find_dm_chat_ids_for_aliases_returns_dms_only() {
+    let db = fresh().await;
+    // 2 DMs e 1 grupo.
+    db.register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    db.register_chat_alias("acc1", LID, ChatKind::Dm)
+        .await
+        .unwrap();
+    db.register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+
+    // Bulk lookup deve trazer só os DMs, mesmo com o alias do grupo na lista.
+    let ids = db
+        .find_dm_chat_ids_for_aliases("acc1", &[PN, LID, GROUP, "naoexiste@s.whatsapp.net"])
+        .await
+        .unwrap();
+    let mut ids = ids;
+    ids.sort();
+    assert_eq!(ids, vec![LID.to_string(), PN.to_string()]);
+}
+
+#[tokio::test]
+async fn find_dm_chat_ids_for_aliases_resolves_via_alias_table() {
+    let db = fresh().await;
+    // DM criado com PN; alias adicional pra LID.
+    db.link_chat("acc1", PN, Some(LID), ChatKind::Dm)
+        .await
+        .unwrap();
+
+    let by_lid = db
+        .find_dm_chat_ids_for_aliases("acc1", &[LID])
+        .await
+        .unwrap();
+    assert_eq!(by_lid, vec![PN.to_string()]); // chat_id = PN, alcançado via alias LID
+}
+
+#[tokio::test]
+async fn linking_same_form_twice_is_noop() {
+    let db = fresh().await;
+    let id1 = db
+        .link_chat("acc1", PN, Some(PN), ChatKind::Dm)
+        .await
+        .unwrap();
+    let id2 = db
+        .link_chat("acc1", PN, Some(PN), ChatKind::Dm)
+        .await
+        .unwrap();
+    assert_eq!(id1, id2);
+    assert_eq!(id1, PN);
+}
+
+// =================================================================
+// get_account_stats
+// =================================================================
+
+#[tokio::test]
+async fn account_stats_are_zero_for_empty_account() {
+    let db = fresh().await;
+    let stats = db.get_account_stats("acc1").await.unwrap();
+    assert_eq!(stats, crate::AccountStats::default());
+}
+
+#[tokio::test]
+async fn account_stats_errors_for_missing_account() {
+    let db = fresh().await;
+    assert!(matches!(
+        db.get_account_stats("nope").await,
+        Err(crate::DbError::AccountNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn rename_account_keeps_identity_and_row() {
+    let db = fresh().await;
+    db.save_account_identity("acc1", Some("5511999999999"), Some(PN))
+        .await
+        .unwrap();
+
+    let renamed = db.rename_account("acc1", "  Trabalho ").await.unwrap();
+    assert_eq!(renamed.name.as_deref(), Some("Trabalho"));
+    assert_eq!(renamed.phone_number.as_deref(), Some("5511999999999"));
+    assert_eq!(renamed.jid.as_deref(), Some(PN));
+
+    let accounts = db.list_accounts().await.unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].name.as_deref(), Some("Trabalho"));
+
+    assert_eq!(db.rename_account("acc1", " ").await.unwrap().name, None);
+    assert!(matches!(
+        db.rename_account("nope", "x").await,
+        Err(crate::DbError::AccountNotFound(_))
+    ));
+    assert_eq!(db.list_accounts().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn account_stats_counts_messages_chats_and_groups() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.link_contact("acc1", PN, None).await.unwrap();
+    for (id, chat, ts) in [("m1", &dm, 100), ("m2", &dm, 300), ("m3", &group, 200)] {
+        db.insert_message("acc1", id, chat, None, Some("x"), "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+
+    let stats = db.get_account_stats("acc1").await.unwrap();
+    assert_eq!(stats.message_count, 3);
+    assert_eq!(stats.chat_count, 2);
+    assert_eq!(stats.group_count, 1);
+    assert_eq!(stats.contact_count, 1);
+    assert_eq!(stats.last_message_ts, Some(300));
+}
+
+#[tokio::test]
+async fn contact_and_group_counts_match_seeded_rows() {
     let db = fresh().await;
-    // 2 DMs e 1 grupo.
+    assert_eq!(db.get_contact_count("acc1").await.unwrap(), 0);
+    assert_eq!(db.get_group_count("acc1").await.unwrap(), 0);
+
+    for n in 0..3 {
+        db.register_contact_alias("acc1", &format!("55119000000{n}@s.whatsapp.net"))
+            .await
+            .unwrap();
+    }
+    for n in 0..2 {
+        db.register_chat_alias(
+            "acc1",
+            &format!("12036300000000000{n}@g.us"),
+            ChatKind::Group,
+        )
+        .await
+        .unwrap();
+    }
     db.register_chat_alias("acc1", PN, ChatKind::Dm)
         .await
         .unwrap();
-    db.register_chat_alias("acc1", LID, ChatKind::Dm)
+    // Contatos de outra conta não contam.
+    db.create_account("acc2", None).await.unwrap();
+    db.register_contact_alias("acc2", PN).await.unwrap();
+
+    assert_eq!(db.get_contact_count("acc1").await.unwrap(), 3);
+    assert_eq!(db.get_group_count("acc1").await.unwrap(), 2);
+    let stats = db.get_account_stats("acc1").await.unwrap();
+    assert_eq!((stats.contact_count, stats.group_count), (3, 2));
+}
+
+#[tokio::test]
+async fn contact_profile_clears_what_privacy_hides() {
+    let db = fresh().await;
+    let id = db
+        .set_contact_profile("acc1", PN, Some("https://pps/a.jpg"), Some("hi"), Some("Alice"))
+        .await
+        .unwrap();
+    let c = db.get_contact_by_alias("acc1", PN).await.unwrap().unwrap();
+    assert_eq!(c.contact_id, id);
+    assert_eq!(c.avatar_url.as_deref(), Some("https://pps/a.jpg"));
+    assert_eq!(c.status.as_deref(), Some("hi"));
+    assert_eq!(c.push_name.as_deref(), Some("Alice"));
+
+    // Foto e recado escondidos depois: somem; o nome fica.
+    db.set_contact_profile("acc1", PN, None, None, None)
         .await
         .unwrap();
+    let c = db.get_contact("acc1", &id).await.unwrap().unwrap();
+    assert_eq!((c.avatar_url, c.status), (None, None));
+    assert_eq!(c.push_name.as_deref(), Some("Alice"));
+}
+
+// =================================================================
+// unified chat list
+// =================================================================
+
+async fn saved_contact(db: &TinaDb, jid: &str, name: Option<&str>) {
+    let id = db.register_contact_alias("acc1", jid).await.unwrap();
+    db.upsert_contact_fields(
+        "acc1",
+        &id,
+        Some(jid),
+        None,
+        None,
+        Some("push"),
+        name,
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn all_chat_rows_append_contacts_without_chat() {
+    let db = fresh().await;
     db.register_chat_alias("acc1", GROUP, ChatKind::Group)
         .await
         .unwrap();
+    let dm = db.register_chat_alias("acc1", PN2, ChatKind::Dm).await.unwrap();
+    db.insert_message("acc1", "m1", &dm, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+    // PN2 já tem chat; PN só existe como contato salvo; LID sem nome
+    // de agenda não entra.
+    saved_contact(&db, PN2, Some("Bia")).await;
+    saved_contact(&db, PN, Some("Ana")).await;
+    saved_contact(&db, LID, None).await;
+
+    let without = db.list_all_chat_rows("acc1", false).await.unwrap();
+    assert_eq!(without.len(), 2);
+
+    let with = db.list_all_chat_rows("acc1", true).await.unwrap();
+    let ids: Vec<_> = with.iter().map(|r| r.chat_id.as_str()).collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&GROUP));
+    assert_eq!(ids.iter().filter(|id| **id == PN2).count(), 1);
+    let last = with.last().unwrap();
+    assert_eq!(last.chat_id, PN);
+    assert_eq!(last.kind, "dm");
+    assert_eq!(last.name, "Ana");
+    assert_eq!(last.last_message_ts, None);
+}
 
-    // Bulk lookup deve trazer só os DMs, mesmo com o alias do grupo na lista.
-    let ids = db
-        .find_dm_chat_ids_for_aliases("acc1", &[PN, LID, GROUP, "naoexiste@s.whatsapp.net"])
+// =================================================================
+// pinned / starred messages
+// =================================================================
+
+#[tokio::test]
+async fn pinned_messages_flag_rows_and_list_newest_first() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    for (id, ts) in [("m1", 100), ("m2", 200), ("m3", 300)] {
+        db.insert_message("acc1", id, &dm, None, Some("x"), "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+
+    db.pin_message("acc1", &dm, "m1", 10).await.unwrap();
+    db.pin_message("acc1", &dm, "m3", 20).await.unwrap();
+    assert_eq!(db.count_pinned_messages("acc1", &dm).await.unwrap(), 2);
+
+    let pinned = db.get_pinned_messages("acc1", &dm).await.unwrap();
+    let ids: Vec<_> = pinned.iter().map(|m| m.message_id.as_str()).collect();
+    assert_eq!(ids, ["m3", "m1"]);
+
+    let rows = db.get_message_rows_by_chat("acc1", &dm, 10, 0).await.unwrap();
+    let flagged: Vec<_> = rows
+        .iter()
+        .filter(|m| m.is_pinned)
+        .map(|m| m.message_id.as_str())
+        .collect();
+    assert_eq!(flagged.len(), 2);
+    assert!(!flagged.contains(&"m2"));
+
+    assert!(db.unpin_message("acc1", "m3").await.unwrap());
+    assert!(!db.unpin_message("acc1", "m3").await.unwrap());
+    assert_eq!(db.count_pinned_messages("acc1", &dm).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn starred_messages_span_chats_newest_first() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
         .await
         .unwrap();
-    let mut ids = ids;
-    ids.sort();
-    assert_eq!(ids, vec![LID.to_string(), PN.to_string()]);
+    for (id, chat, ts) in [("m1", &dm, 100), ("m2", &group, 200), ("m3", &dm, 300)] {
+        db.insert_message("acc1", id, chat, None, Some("x"), "text", ts, false, None)
+            .await
+            .unwrap();
+    }
+
+    assert!(db.star_message("acc1", "m1").await.unwrap());
+    assert!(db.star_message("acc1", "m2").await.unwrap());
+    // Idempotente; id desconhecido não afeta nada.
+    assert!(!db.star_message("acc1", "m2").await.unwrap());
+    assert!(!db.star_message("acc1", "nope").await.unwrap());
+
+    let starred = db.get_starred_messages("acc1", 10, 0).await.unwrap();
+    let ids: Vec<_> = starred.iter().map(|m| m.message_id.as_str()).collect();
+    assert_eq!(ids, ["m2", "m1"]);
+    assert!(starred.iter().all(|m| m.is_starred));
+
+    let page = db.get_starred_messages("acc1", 1, 1).await.unwrap();
+    assert_eq!(page[0].message_id, "m1");
+
+    assert!(db.unstar_message("acc1", "m2").await.unwrap());
+    let starred = db.get_starred_messages("acc1", 10, 0).await.unwrap();
+    assert_eq!(starred.len(), 1);
 }
 
+// =================================================================
+// forwarded copies
+// =================================================================
+
 #[tokio::test]
-async fn find_dm_chat_ids_for_aliases_resolves_via_alias_table() {
+async fn forwarded_text_copy_is_ours_and_moves_preview() {
     let db = fresh().await;
-    // DM criado com PN; alias adicional pra LID.
-    db.link_chat("acc1", PN, Some(LID), ChatKind::Dm)
+    let from = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
+        .await
+        .unwrap();
+    let to = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "src", &from, None, Some("olá"), "text", 100, false, None)
         .await
         .unwrap();
 
-    let by_lid = db
-        .find_dm_chat_ids_for_aliases("acc1", &[LID])
+    assert!(
+        db.insert_forwarded_copy("acc1", "src", "fwd1", &to, 200)
+            .await
+            .unwrap()
+    );
+    // Mesmo id de novo (eco do nanachi, retry) não duplica.
+    assert!(
+        !db.insert_forwarded_copy("acc1", "src", "fwd1", &to, 200)
+            .await
+            .unwrap()
+    );
+
+    let row = db
+        .get_message_rows_by_ids("acc1", &["fwd1".to_string()])
+        .await
+        .unwrap()
+        .remove(0);
+    assert_eq!(row.chat_id, to);
+    assert_eq!(row.content.as_deref(), Some("olá"));
+    assert!(row.is_from_me && row.forwarded);
+    let source = db.get_message("acc1", "src").await.unwrap().unwrap();
+    assert!(!source.forwarded);
+
+    let chat = db.get_chat("acc1", &to).await.unwrap().unwrap();
+    assert_eq!(chat.last_message_id.as_deref(), Some("fwd1"));
+    assert_eq!(chat.last_message_preview.as_deref(), Some("olá"));
+    assert!(chat.last_message_from_me);
+}
+
+#[tokio::test]
+async fn forwarded_media_copy_keeps_media_fields() {
+    let db = fresh().await;
+    let from = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
         .await
         .unwrap();
-    assert_eq!(by_lid, vec![PN.to_string()]); // chat_id = PN, alcançado via alias LID
+    let to = db
+        .register_chat_alias("acc1", PN2, ChatKind::Dm)
+        .await
+        .unwrap();
+    let img = r#"{"image_message":{"mimetype":"image/jpeg"}}"#;
+    db.insert_message("acc1", "img", &from, None, None, "image", 100, false, Some(img))
+        .await
+        .unwrap();
+    db.apply_media_downloaded("acc1", "img", "/cache/abc.jpg", None, Some("image/jpeg"))
+        .await
+        .unwrap();
+
+    assert!(
+        db.insert_forwarded_copy("acc1", "img", "fwd2", &to, 200)
+            .await
+            .unwrap()
+    );
+    let copy = db.get_message("acc1", "fwd2").await.unwrap().unwrap();
+    assert_eq!(copy.message_type, "image");
+    assert_eq!(copy.raw_json.as_deref(), Some(img));
+    assert_eq!(copy.media_path.as_deref(), Some("/cache/abc.jpg"));
+    assert_eq!(copy.media_status, "done");
+    assert_eq!(copy.media_mimetype.as_deref(), Some("image/jpeg"));
+    assert!(copy.is_from_me && copy.forwarded);
+
+    let chat = db.get_chat("acc1", &to).await.unwrap().unwrap();
+    assert_eq!(chat.last_message_preview.as_deref(), Some("[Image]"));
+    assert_eq!(chat.last_message_type.as_deref(), Some("image"));
 }
 
 #[tokio::test]
-async fn linking_same_form_twice_is_noop() {
+async fn forwarded_copy_of_unknown_message_is_a_no_op() {
     let db = fresh().await;
-    let id1 = db
-        .link_chat("acc1", PN, Some(PN), ChatKind::Dm)
+    let to = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
         .await
         .unwrap();
-    let id2 = db
-        .link_chat("acc1", PN, Some(PN), ChatKind::Dm)
+    assert!(
+        !db.insert_forwarded_copy("acc1", "missing", "fwd3", &to, 200)
+            .await
+            .unwrap()
+    );
+    assert!(db.get_message("acc1", "fwd3").await.unwrap().is_none());
+}
+
+// =================================================================
+// chat settings (mute / notify)
+// =================================================================
+
+#[tokio::test]
+async fn chat_mute_expires_on_its_own() {
+    let db = fresh().await;
+    let chat = db
+        .register_chat_alias("acc1", PN, ChatKind::Dm)
         .await
         .unwrap();
-    assert_eq!(id1, id2);
-    assert_eq!(id1, PN);
+    let now = tina_core::unix_now();
+
+    assert!(!db.is_chat_muted("acc1", &chat).await.unwrap());
+    db.set_chat_muted("acc1", &chat, Some(now + 3600)).await.unwrap();
+    assert!(db.is_chat_muted("acc1", &chat).await.unwrap());
+    let rows = db.list_chat_rows("acc1").await.unwrap();
+    assert!(rows.iter().find(|r| r.chat_id == chat).unwrap().muted);
+
+    // Prazo vencido: volta a notificar sem ninguém limpar a coluna.
+    db.set_chat_muted("acc1", &chat, Some(now - 1)).await.unwrap();
+    assert!(!db.is_chat_muted("acc1", &chat).await.unwrap());
+    let rows = db.list_chat_rows("acc1").await.unwrap();
+    assert!(!rows.iter().find(|r| r.chat_id == chat).unwrap().muted);
+
+    db.set_chat_muted("acc1", &chat, Some(MUTED_FOREVER)).await.unwrap();
+    assert!(db.is_chat_muted("acc1", &chat).await.unwrap());
+    db.set_chat_muted("acc1", &chat, None).await.unwrap();
+    assert!(!db.is_chat_muted("acc1", &chat).await.unwrap());
+}
+
+async fn chat_notify(db: &TinaDb, jid: &str) -> bool {
+    db.get_chat_info("acc1", jid).await.unwrap().unwrap().notify
+}
+
+#[tokio::test]
+async fn notify_toggle_is_independent_of_mute() {
+    let db = fresh().await;
+    let chat = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    assert!(chat_notify(&db, GROUP).await);
+
+    db.set_chat_notify("acc1", &chat, false).await.unwrap();
+    assert!(!chat_notify(&db, GROUP).await);
+    assert!(!db.is_chat_muted("acc1", &chat).await.unwrap());
+
+    db.set_chat_notify("acc1", &chat, true).await.unwrap();
+    db.set_chat_muted("acc1", &chat, Some(MUTED_FOREVER)).await.unwrap();
+    assert!(chat_notify(&db, GROUP).await);
+    assert!(db.is_chat_muted("acc1", &chat).await.unwrap());
+}
+
+#[tokio::test]
+async fn mark_all_chats_read_zeroes_badges_and_lists_receipts() {
+    let db = fresh().await;
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.insert_message("acc1", "d1", &dm, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+    db.update_chat_last_message("acc1", &dm, "d1", Some("oi"), 100, false, None)
+        .await
+        .unwrap();
+    for (id, ts, from_me) in [("g1", 200, false), ("g2", 210, false), ("g3", 220, true)] {
+        let sender = (!from_me).then_some(PN2);
+        db.insert_message("acc1", id, &group, sender, Some("x"), "text", ts, from_me, None)
+            .await
+            .unwrap();
+        db.update_chat_last_message("acc1", &group, id, Some("x"), ts, from_me, sender)
+            .await
+            .unwrap();
+    }
+
+    let mut unread = db.list_unread_incoming("acc1").await.unwrap();
+    unread.sort();
+    assert_eq!(
+        unread,
+        vec![
+            (group.clone(), Some(PN2.to_string()), "g1".to_string()),
+            (group.clone(), Some(PN2.to_string()), "g2".to_string()),
+            (dm.clone(), None, "d1".to_string()),
+        ]
+    );
+
+    assert_eq!(db.mark_all_chats_read("acc1").await.unwrap(), 2);
+    assert!(db.list_unread_incoming("acc1").await.unwrap().is_empty());
+    let rows = db.list_chat_rows("acc1").await.unwrap();
+    assert!(rows.iter().all(|r| r.unread_count == 0));
+    // Nada mais a ler: segunda chamada não mexe em nenhum chat.
+    assert_eq!(db.mark_all_chats_read("acc1").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn unread_totals_sum_badges_per_account() {
+    let db = fresh().await;
+    db.create_account("acc2", None).await.unwrap();
+    db.create_account("acc3", None).await.unwrap();
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    let group = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    let status = db
+        .register_chat_alias("acc1", "status@broadcast", ChatKind::Status)
+        .await
+        .unwrap();
+    let other = db.register_chat_alias("acc2", PN2, ChatKind::Dm).await.unwrap();
+    for (acc, id, chat, ts, from_me) in [
+        ("acc1", "d1", &dm, 100, false),
+        ("acc1", "d2", &dm, 110, true),
+        ("acc1", "g1", &group, 200, false),
+        ("acc1", "g2", &group, 210, false),
+        ("acc1", "s1", &status, 300, false),
+        ("acc2", "o1", &other, 100, false),
+    ] {
+        db.insert_message(acc, id, chat, None, Some("x"), "text", ts, from_me, None)
+            .await
+            .unwrap();
+    }
+    db.set_chat_last_read_ts("acc1", &group, 200).await.unwrap();
+
+    let totals = db.unread_totals().await.unwrap();
+    // d1 + g2; o seu, o já lido e o status não contam.
+    assert_eq!(totals.get("acc1"), Some(&2));
+    assert_eq!(totals.get("acc2"), Some(&1));
+    assert_eq!(totals.get("acc3"), None);
+}
+
+// =================================================================
+// sync cursor
+// =================================================================
+
+#[tokio::test]
+async fn sync_cursor_roundtrip_and_clear() {
+    let db = fresh().await;
+    assert_eq!(db.get_sync_cursor("acc1").await.unwrap(), None);
+
+    let cursor = crate::SyncCursor {
+        sync_type: "INITIAL_BOOTSTRAP".into(),
+        progress: 42,
+        messages_count: 1234,
+        updated_at: 1_700_000_000,
+    };
+    db.put_sync_cursor("acc1", &cursor).await.unwrap();
+    assert_eq!(db.get_sync_cursor("acc1").await.unwrap(), Some(cursor));
+    // Escopo por conta.
+    assert_eq!(db.get_sync_cursor("acc2").await.unwrap(), None);
+
+    db.clear_sync_cursor("acc1").await.unwrap();
+    assert_eq!(db.get_sync_cursor("acc1").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn initial_sync_flag_is_set_once_and_cleared_on_logout() {
+    let db = fresh().await;
+    assert!(!db.initial_sync_done("acc1").await.unwrap());
+    assert!(db.mark_initial_sync_done("acc1").await.unwrap());
+    assert!(!db.mark_initial_sync_done("acc1").await.unwrap());
+    assert!(db.initial_sync_done("acc1").await.unwrap());
+    assert!(!db.initial_sync_done("acc2").await.unwrap());
+
+    db.clear_auth_state("acc1", false).await.unwrap();
+    assert!(!db.initial_sync_done("acc1").await.unwrap());
+
+    db.mark_initial_sync_done("acc1").await.unwrap();
+    db.delete_account("acc1").await.unwrap();
+    assert!(!db.initial_sync_done("acc1").await.unwrap());
+}
+
+#[tokio::test]
+async fn clear_auth_state_keeps_or_purges_synced_data() {
+    let db = fresh().await;
+    db.create_account("acc2", None).await.unwrap();
+    for acc in ["acc1", "acc2"] {
+        db.save_account_identity(acc, Some("5511999999999"), Some(PN))
+            .await
+            .unwrap();
+        db.register_contact_alias(acc, PN).await.unwrap();
+        db.register_chat_alias(acc, GROUP, ChatKind::Group)
+            .await
+            .unwrap();
+    }
+    let cursor = crate::SyncCursor {
+        sync_type: "RECENT".into(),
+        progress: 40,
+        messages_count: 10,
+        updated_at: 1,
+    };
+    db.put_sync_cursor("acc1", &cursor).await.unwrap();
+
+    db.clear_auth_state("acc1", false).await.unwrap();
+    let acc = db.get_account("acc1").await.unwrap();
+    assert!(acc.jid.is_none() && acc.phone_number.is_none());
+    assert_eq!(db.get_sync_cursor("acc1").await.unwrap(), None);
+    assert_eq!(db.list_contacts("acc1").await.unwrap().len(), 1);
+    assert_eq!(db.list_chat_rows("acc1").await.unwrap().len(), 1);
+
+    db.clear_auth_state("acc1", true).await.unwrap();
+    assert!(db.list_contacts("acc1").await.unwrap().is_empty());
+    assert!(db.list_chat_rows("acc1").await.unwrap().is_empty());
+    assert!(db.get_chat_by_alias("acc1", GROUP).await.unwrap().is_none());
+    db.get_account("acc1").await.expect("account row survives purge");
+
+    // A outra conta não é tocada.
+    assert_eq!(db.list_contacts("acc2").await.unwrap().len(), 1);
+    assert!(db.get_account("acc2").await.unwrap().jid.is_some());
+}
+
+#[tokio::test]
+async fn purge_account_data_empties_only_selected_tables() {
+    let db = fresh().await;
+    db.save_account_identity("acc1", Some("5511999999999"), Some(PN))
+        .await
+        .unwrap();
+    db.register_contact_alias("acc1", PN).await.unwrap();
+    let dm = db.register_chat_alias("acc1", PN, ChatKind::Dm).await.unwrap();
+    db.insert_message("acc1", "m1", &dm, None, Some("oi"), "text", 100, false, None)
+        .await
+        .unwrap();
+    db.set_group_invite_link("acc1", GROUP, "https://chat.whatsapp.com/abc")
+        .await
+        .unwrap();
+
+    let only_messages = crate::PurgeOptions {
+        messages: true,
+        ..Default::default()
+    };
+    db.purge_account_data("acc1", only_messages).await.unwrap();
+    assert_eq!(db.count_messages_for_chat("acc1", &dm).await.unwrap(), 0);
+    assert!(db.list_chat_rows("acc1").await.unwrap().is_empty());
+    assert_eq!(db.list_contacts("acc1").await.unwrap().len(), 1);
+    assert!(db.get_group_invite_link("acc1", GROUP).await.unwrap().is_some());
+
+    let only_groups = crate::PurgeOptions {
+        groups: true,
+        ..Default::default()
+    };
+    db.purge_account_data("acc1", only_groups).await.unwrap();
+    assert!(db.get_group_invite_link("acc1", GROUP).await.unwrap().is_none());
+    assert_eq!(db.list_contacts("acc1").await.unwrap().len(), 1);
+
+    db.purge_account_data("acc1", crate::PurgeOptions::all())
+        .await
+        .unwrap();
+    assert!(db.list_contacts("acc1").await.unwrap().is_empty());
+    // Conta e pareamento sobrevivem: o próximo connect só sincroniza.
+    let acc = db.get_account("acc1").await.unwrap();
+    assert_eq!(acc.jid.as_deref(), Some(PN));
+}
+
+// =================================================================
+// TINA_DB_PATH
+// =================================================================
+
+#[tokio::test]
+async fn db_path_env_overrides_default_location() {
+    let dir = std::env::temp_dir().join(format!("tina-db-env-{}", std::process::id()));
+    let path = dir.join("nested").join("custom.db");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // SAFETY: nenhum outro teste lê ou escreve TINA_DB_PATH nem
+    // TINA_DATA_DIR.
+    unsafe { std::env::set_var(crate::DB_PATH_ENV, &path) };
+    assert_eq!(TinaDb::get_db_path().unwrap(), path);
+
+    let db = TinaDb::new().await.expect("open db at TINA_DB_PATH");
+    db.create_account("acc1", None).await.unwrap();
+    unsafe { std::env::remove_var(crate::DB_PATH_ENV) };
+
+    assert!(path.exists(), "db file should exist at {}", path.display());
+    drop(db);
+    let reopened = TinaDb::new_with_path(path.to_str().unwrap()).await.unwrap();
+    assert_eq!(reopened.list_accounts().await.unwrap().len(), 1);
+
+    // Sem TINA_DB_PATH, o banco vai para dentro de TINA_DATA_DIR.
+    let base = dir.join("profile");
+    unsafe { std::env::set_var(crate::DATA_DIR_ENV, &base) };
+    assert_eq!(crate::data_dir(), Some(base.clone()));
+    assert_eq!(TinaDb::get_db_path().unwrap(), base.join("tina.db"));
+    unsafe { std::env::remove_var(crate::DATA_DIR_ENV) };
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// =================================================================
+// Backup / restore
+// =================================================================
+
+#[tokio::test]
+async fn backup_then_restore_round_trips() {
+    let dir = std::env::temp_dir().join(format!("tina-db-backup-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let snapshot = dir.join("tina.bak");
+
+    let db_path = dir.join("tina.db");
+    let db = TinaDb::new_with_path(db_path.to_str().unwrap())
+        .await
+        .unwrap();
+    db.create_account("acc1", Some("test")).await.unwrap();
+    let chat_id = db
+        .register_chat_alias("acc1", GROUP, ChatKind::Group)
+        .await
+        .unwrap();
+    db.set_chat_display_name("acc1", &chat_id, Some("Família"))
+        .await
+        .unwrap();
+    let contact_id = db.register_contact_alias("acc1", PN).await.unwrap();
+
+    db.backup_to(&snapshot).await.unwrap();
+    assert!(db.backup_to(&snapshot).await.is_err(), "never overwrites");
+
+    // Muda tudo depois do snapshot.
+    db.create_account("acc2", None).await.unwrap();
+    db.set_chat_display_name("acc1", &chat_id, Some("Outro nome"))
+        .await
+        .unwrap();
+    db.delete_account("acc1").await.unwrap();
+
+    db.restore_from(&snapshot).await.unwrap();
+
+    let accounts = db.list_accounts().await.unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, "acc1");
+    assert_eq!(
+        db.get_chat_name("acc1", GROUP).await.unwrap().as_deref(),
+        Some("Família")
+    );
+    assert!(db.get_contact("acc1", &contact_id).await.unwrap().is_some());
+    // Pool continua utilizável depois do restore.
+    db.create_account("acc3", None).await.unwrap();
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn restore_rejects_foreign_files() {
+    let dir = std::env::temp_dir().join(format!("tina-db-badbak-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let db = fresh().await;
+
+    let missing = dir.join("missing.bak");
+    assert!(matches!(
+        db.restore_from(&missing).await,
+        Err(crate::DbError::InvalidBackup(_))
+    ));
+
+    let junk = dir.join("junk.bak");
+    std::fs::write(&junk, b"not a database").unwrap();
+    assert!(matches!(
+        db.restore_from(&junk).await,
+        Err(crate::DbError::InvalidBackup(_))
+    ));
+
+    // Nada foi tocado.
+    assert_eq!(db.list_accounts().await.unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn failed_restore_leaves_no_connection_without_foreign_keys() {
+    let dir = std::env::temp_dir().join(format!("tina-db-failbak-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let db = TinaDb::new_with_path(dir.join("tina.db").to_str().unwrap())
+        .await
+        .unwrap();
+    db.create_account("acc1", None).await.unwrap();
+
+    // Passa na validação, mas a cópia quebra na PK de `accounts`.
+    let snapshot = dir.join("dup.bak");
+    db.backup_to(&snapshot).await.unwrap();
+    let bak = TinaDb::new_with_path(snapshot.to_str().unwrap())
+        .await
+        .unwrap();
+    sqlx::raw_sql(
+        "PRAGMA foreign_keys = OFF;
+         DROP TABLE accounts;
+         CREATE TABLE accounts (id TEXT, name TEXT);
+         INSERT INTO accounts VALUES ('x', NULL), ('x', NULL);",
+    )
+    .execute(bak.pool())
+    .await
+    .unwrap();
+    bak.pool().close().await;
+
+    assert!(db.restore_from(&snapshot).await.is_err());
+    assert_eq!(db.list_accounts().await.unwrap().len(), 1);
+    // Todas as conexões do pool de uma vez, a do restore incluída.
+    let mut conns = Vec::new();
+    for _ in 0..5 {
+        conns.push(db.pool().acquire().await.unwrap());
+    }
+    for conn in &mut conns {
+        let fk: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&mut **conn)
+            .await
+            .unwrap();
+        assert!(fk);
+        let attached: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM pragma_database_list WHERE name = 'bak'")
+                .fetch_one(&mut **conn)
+                .await
+                .unwrap();
+        assert_eq!(attached, 0);
+    }
+    drop(conns);
+    db.pool().close().await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// =================================================================
+// new_with_path(":memory:") — pool migrado de verdade, sem arquivo
+// =================================================================
+
+#[tokio::test]
+async fn memory_path_runs_migrations_and_sets_version() {
+    let db = TinaDb::new_with_path(":memory:")
+        .await
+        .expect("open :memory: db");
+    let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(version, crate::schema::SCHEMA_VERSION);
+    let fk: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(fk, 1);
+    // A conexão do banco não é reciclada pelo pool.
+    let options = db.pool().options();
+    assert_eq!(options.get_min_connections(), 1);
+    assert_eq!(options.get_idle_timeout(), None);
+    assert_eq!(options.get_max_lifetime(), None);
+}
+
+#[tokio::test]
+async fn memory_path_databases_are_isolated() {
+    let a = TinaDb::new_with_path(":memory:").await.unwrap();
+    a.create_account("acc1", None).await.unwrap();
+    let b = TinaDb::new_with_path(":memory:").await.unwrap();
+    assert_eq!(a.list_accounts().await.unwrap().len(), 1);
+    assert!(b.list_accounts().await.unwrap().is_empty());
 }
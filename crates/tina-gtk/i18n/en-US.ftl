@@ -24,6 +24,7 @@ sync-reconnect-description =
        *[other] { $count } messages received
     }
 sync-skip = Skip
+sync-cancel = Stop syncing
 
 ## Repair page
 repair-title-starting = Starting…
@@ -33,7 +34,30 @@ repair-description-starting = Reading your data from WhatsApp.
 ## Toasts / notifications
 toast-webp-not-found = Warning: WebP support not found! Stickers may not load. Install webp-pixbuf-loader.
 toast-disconnected = Disconnected: { $reason }
+toast-disconnected-replaced = WhatsApp was opened on another device. Tina is offline.
+toast-disconnected-banned = WhatsApp temporarily banned this account.
 toast-download-failed = Download failed
+toast-send-failed = Not sent: “{ $text }”
+toast-forwarded = Forwarded to { $count } chat(s)
+toast-forward-partial = Forwarded to { $sent } chat(s); { $failed } failed
+toast-account-not-found = Account { $id } not found. Sign in again to continue.
+toast-worker-error = Something went wrong: { $error }
+toast-invite-link-copied = Invite link copied
+toast-not-group-admin = Only group admins can see the invite link
+toast-local-data-cleared = Local data cleared
+toast-connect-timed-out = WhatsApp didn't answer. You're offline until you retry.
+toast-account-renamed = Account renamed to { $name }
+toast-account-name-cleared = Account name cleared
+toast-group-joined = Joined { $name }
+toast-not-on-whatsapp = { $phone } is not on WhatsApp
+toast-marked-all-read =
+    { $count ->
+        [0] No unread chats
+        [one] Marked { $count } chat as read
+       *[other] Marked { $count } chats as read
+    }
+toast-invalid-invite-link = That isn’t a valid group invite link, or it was reset
+toast-already-group-member = You’re already in that group
 retry = Retry
 
 ## Login page
@@ -42,16 +66,32 @@ login-scan-qr = Scan the QR with WhatsApp on your phone.
 login-step-1 = 1.  Open WhatsApp on your phone
 login-step-2 = 2.  Tap Menu or Settings and pick Linked Devices
 login-step-3 = 3.  Point your phone at the screen
+login-qr-expired = This QR code expired.
+login-qr-refresh = Get a new code
+login-connect-timed-out = WhatsApp didn't answer. Check your connection and try again.
+login-connect-retry = Try again
 
 ## Profile menu
 profile-tooltip = Profile
 profile-not-connected = Not connected
 preferences = Preferences
 log-out = Log out
+new-chat-menu = New Chat…
+join-group-menu = Join Group via Link…
+mark-all-read-menu = Mark All as Read
+join-group-heading = Join Group
+join-group-body = Paste a chat.whatsapp.com invite link.
+join-group-join = Join
+new-chat-heading = New Chat
+new-chat-body = Type a phone number with its country code.
+new-chat-start = Start Chat
 
 ## Settings dialog
 settings-title = Preferences
 settings-general = General
+settings-account = Account
+settings-account-description = How this account is labelled in Tina. Your WhatsApp profile name doesn't change.
+settings-account-name = Account name
 settings-downloads = Downloads
 settings-downloads-description = When to fetch image, video and audio attachments.
 settings-download-method = Download method
@@ -74,6 +114,11 @@ settings-maintenance = Maintenance
 settings-repair = Repair
 settings-repair-subtitle = Re-pull contacts, groups and newsletter metadata from WhatsApp without re-pairing.
 settings-repair-run = Run
+settings-clear-data = Clear local data
+settings-clear-data-subtitle = Delete downloaded messages, chats and contacts. You stay signed in; they sync again on the next connect.
+settings-clear-data-run = Clear
+clear-data-heading = Clear local data?
+clear-data-body = Messages, chats, contacts and groups stored on this computer will be deleted. Your WhatsApp account is not affected.
 settings-about = About
 settings-version = Version
 settings-memory-group = Memory
@@ -94,6 +139,7 @@ sidebar-connecting = Connecting…
 sidebar-catching-up = Catching up
 sidebar-pulling-history = Pulling history
 sidebar-syncing = Syncing
+sidebar-resuming-sync = Resuming sync
 
 ## Chat area pane
 pane-toggle-sidebar = Toggle sidebar
@@ -105,18 +151,30 @@ context-open = Open
 context-open-new-tab = Open in New Tab
 context-pin = Pin
 context-unpin = Unpin
+context-mute = Mute
+context-unmute = Unmute
+context-copy-invite-link = Copy Invite Link
+context-contact-info = Contact Info
+
+## Message row context menu
+context-forward = Forward…
+
+## Forward dialog
+forward-heading = Forward to…
+forward-send = Forward
+forward-search-placeholder = Search chats
 
 ## Chat row preview
-preview-photo = 📷 Photo
-preview-voice-note = 🎤 Voice note
-preview-voice-duration = 🎤 { $min }:{ $sec }
-preview-video = 🎬 Video
-preview-video-duration = 🎬 Video { $min }:{ $sec }
-preview-sticker = 🎴 Sticker
-preview-document = 📄 Document
-preview-contact = 👤 Contact
-preview-location = 📍 Location
-preview-live-location = 📍 Live Location
+preview-photo = Photo
+preview-voice-note = Voice note
+preview-voice-duration = { $min }:{ $sec }
+preview-video = Video
+preview-video-duration = Video { $min }:{ $sec }
+preview-sticker = Sticker
+preview-document = Document
+preview-contact = Contact
+preview-location = Location
+preview-live-location = Live Location
 preview-you = You: { $text }
 preview-sender = { $short }: { $text }
 
@@ -166,7 +224,11 @@ send-heading-document = Send document
 ## Message bubble
 sender-you = You
 sender-unknown = Unknown
+sender-admin = admin
 quoted-replied-message = Replied message
+message-edited = edited
+message-location = Location
+message-live-location = Live location
 media-image = Image
 media-voice-audio = Voice / Audio
 media-video = Video
@@ -206,3 +268,12 @@ settings-language-system = System default
 settings-language-en = English
 settings-language-pt-br = Português (Brasil)
 toast-language-changed = Language changed. Restart Tina to apply.
+
+## Contact info dialog
+contact-info-no-status = No status
+contact-info-close = Close
+contact-info-common-groups =
+    { $count ->
+        [one] Group in common: { $groups }
+       *[other] { $count } groups in common: { $groups }
+    }
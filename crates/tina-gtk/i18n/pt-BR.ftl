@@ -24,6 +24,7 @@ sync-reconnect-description =
        *[other] { $count } mensagens recebidas
     }
 sync-skip = Pular
+sync-cancel = Parar sincronização
 
 ## Página de reparo
 repair-title-starting = Iniciando…
@@ -33,7 +34,30 @@ repair-description-starting = Lendo seus dados do WhatsApp.
 ## Avisos / notificações
 toast-webp-not-found = Aviso: Suporte a WebP não encontrado! Figurinhas podem não carregar. Instale webp-pixbuf-loader.
 toast-disconnected = Desconectado: { $reason }
+toast-disconnected-replaced = O WhatsApp foi aberto em outro dispositivo. O Tina está offline.
+toast-disconnected-banned = O WhatsApp baniu esta conta temporariamente.
 toast-download-failed = Falha no download
+toast-send-failed = Não enviada: “{ $text }”
+toast-forwarded = Encaminhada para { $count } chat(s)
+toast-forward-partial = Encaminhada para { $sent } chat(s); { $failed } falharam
+toast-account-not-found = Conta { $id } não encontrada. Entre novamente para continuar.
+toast-worker-error = Algo deu errado: { $error }
+toast-invite-link-copied = Link de convite copiado
+toast-not-group-admin = Só administradores do grupo podem ver o link de convite
+toast-local-data-cleared = Dados locais limpos
+toast-connect-timed-out = O WhatsApp não respondeu. Você fica offline até tentar de novo.
+toast-account-renamed = Conta renomeada para { $name }
+toast-account-name-cleared = Nome da conta removido
+toast-group-joined = Você entrou em { $name }
+toast-not-on-whatsapp = { $phone } não está no WhatsApp
+toast-marked-all-read =
+    { $count ->
+        [0] Nenhuma conversa não lida
+        [one] { $count } conversa marcada como lida
+       *[other] { $count } conversas marcadas como lidas
+    }
+toast-invalid-invite-link = Esse link de convite não é válido ou foi redefinido
+toast-already-group-member = Você já está nesse grupo
 retry = Tentar novamente
 
 ## Página de login
@@ -42,16 +66,32 @@ login-scan-qr = Escaneie o QR com o WhatsApp no seu telefone.
 login-step-1 = 1.  Abra o WhatsApp no seu telefone
 login-step-2 = 2.  Toque em Menu ou Configurações e escolha Aparelhos conectados
 login-step-3 = 3.  Aponte seu telefone para a tela
+login-qr-expired = Este código QR expirou.
+login-qr-refresh = Gerar novo código
+login-connect-timed-out = O WhatsApp não respondeu. Verifique sua conexão e tente de novo.
+login-connect-retry = Tentar de novo
 
 ## Menu de perfil
 profile-tooltip = Perfil
 profile-not-connected = Não conectado
 preferences = Preferências
 log-out = Sair
+new-chat-menu = Nova conversa…
+join-group-menu = Entrar em grupo por link…
+mark-all-read-menu = Marcar tudo como lido
+join-group-heading = Entrar em grupo
+join-group-body = Cole um link de convite do chat.whatsapp.com.
+join-group-join = Entrar
+new-chat-heading = Nova conversa
+new-chat-body = Digite um número de telefone com o código do país.
+new-chat-start = Iniciar conversa
 
 ## Diálogo de configurações
 settings-title = Preferências
 settings-general = Geral
+settings-account = Conta
+settings-account-description = Como esta conta aparece no Tina. Seu nome de perfil no WhatsApp não muda.
+settings-account-name = Nome da conta
 settings-downloads = Downloads
 settings-downloads-description = Quando baixar anexos de imagem, vídeo e áudio.
 settings-download-method = Método de download
@@ -74,6 +114,11 @@ settings-maintenance = Manutenção
 settings-repair = Reparar
 settings-repair-subtitle = Re-baixar contatos, grupos e metadados de canais do WhatsApp sem reconectar.
 settings-repair-run = Executar
+settings-clear-data = Limpar dados locais
+settings-clear-data-subtitle = Apaga mensagens, conversas e contatos baixados. Você continua conectado; eles voltam a sincronizar na próxima conexão.
+settings-clear-data-run = Limpar
+clear-data-heading = Limpar dados locais?
+clear-data-body = Mensagens, conversas, contatos e grupos guardados neste computador serão apagados. Sua conta do WhatsApp não é afetada.
 settings-about = Sobre
 settings-version = Versão
 settings-memory-group = Memória
@@ -94,6 +139,7 @@ sidebar-connecting = Conectando…
 sidebar-catching-up = Atualizando
 sidebar-pulling-history = Baixando histórico
 sidebar-syncing = Sincronizando
+sidebar-resuming-sync = Retomando sincronização
 
 ## Painel de chat
 pane-toggle-sidebar = Alternar barra lateral
@@ -105,18 +151,30 @@ context-open = Abrir
 context-open-new-tab = Abrir em nova aba
 context-pin = Fixar
 context-unpin = Desafixar
+context-mute = Silenciar
+context-unmute = Reativar notificações
+context-copy-invite-link = Copiar link de convite
+context-contact-info = Dados do contato
+
+## Menu de contexto da mensagem
+context-forward = Encaminhar…
+
+## Diálogo de encaminhamento
+forward-heading = Encaminhar para…
+forward-send = Encaminhar
+forward-search-placeholder = Buscar chats
 
 ## Preview da linha de chat
-preview-photo = 📷 Foto
-preview-voice-note = 🎤 Mensagem de voz
-preview-voice-duration = 🎤 { $min }:{ $sec }
-preview-video = 🎬 Vídeo
-preview-video-duration = 🎬 Vídeo { $min }:{ $sec }
-preview-sticker = 🎴 Figurinha
-preview-document = 📄 Documento
-preview-contact = 👤 Contato
-preview-location = 📍 Localização
-preview-live-location = 📍 Localização em tempo real
+preview-photo = Foto
+preview-voice-note = Mensagem de voz
+preview-voice-duration = { $min }:{ $sec }
+preview-video = Vídeo
+preview-video-duration = Vídeo { $min }:{ $sec }
+preview-sticker = Figurinha
+preview-document = Documento
+preview-contact = Contato
+preview-location = Localização
+preview-live-location = Localização em tempo real
 preview-you = Você: { $text }
 preview-sender = { $short }: { $text }
 
@@ -166,7 +224,11 @@ send-heading-document = Enviar documento
 ## Balão de mensagem
 sender-you = Você
 sender-unknown = Desconhecido
+sender-admin = admin
 quoted-replied-message = Mensagem citada
+message-edited = editada
+message-location = Localização
+message-live-location = Localização em tempo real
 media-image = Imagem
 media-voice-audio = Voz / Áudio
 media-video = Vídeo
@@ -206,3 +268,12 @@ settings-language-system = Padrão do sistema
 settings-language-en = English
 settings-language-pt-br = Português (Brasil)
 toast-language-changed = Idioma alterado. Reinicie o Tina para aplicar.
+
+## Contact info dialog
+contact-info-no-status = Sem recado
+contact-info-close = Fechar
+contact-info-common-groups =
+    { $count ->
+        [one] Grupo em comum: { $groups }
+       *[other] { $count } grupos em comum: { $groups }
+    }
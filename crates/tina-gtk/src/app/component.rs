@@ -11,7 +11,7 @@ use crate::fl;
 use gtk::glib;
 use relm4::prelude::*;
 
-use crate::components::login::LoginPage;
+use crate::components::login::{LoginOutput, LoginPage};
 use crate::components::main_page::{MainOutput, MainPage};
 use crate::components::settings::{Settings, SettingsInit, SettingsOutput};
 use crate::service::{Cmd, ServiceWorker};
@@ -89,6 +89,15 @@ impl SimpleComponent for AppModel {
                                 set_visible: model.reconnect_syncing,
                                 connect_clicked => AppMsg::SkipSync,
                             },
+                            gtk::Button {
+                                set_label: &fl!("sync-cancel"),
+                                set_halign: gtk::Align::Center,
+                                set_css_classes: &["pill"],
+                                set_margin_top: 8,
+                                #[watch]
+                                set_visible: !model.reconnect_syncing,
+                                connect_clicked => AppMsg::CancelSync,
+                            },
                         },
                     },
 
@@ -156,8 +165,22 @@ impl SimpleComponent for AppModel {
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let service = ServiceWorker::spawn(init.nanachi_dir, sender.input_sender().clone());
+        // A Wi-Fi ↔ cellular switch can leave whatsmeow on a dead socket
+        // for minutes; the worker restarts the live accounts when the
+        // monitor says a network is back.
+        {
+            let handle = service.handle.clone();
+            gtk::gio::NetworkMonitor::default().connect_network_changed(move |_, available| {
+                handle.send(Cmd::NetworkChanged { online: available });
+            });
+        }
 
-        let login = LoginPage::builder().launch(()).detach();
+        let login = LoginPage::builder()
+            .launch(())
+            .forward(sender.input_sender(), |out| match out {
+                LoginOutput::RefreshQr => AppMsg::RequestQrRefresh,
+                LoginOutput::RetryConnect => AppMsg::RetryConnect,
+            });
 
         let avatars = crate::inventory::AvatarInventory::new();
         let media = crate::inventory::MediaInventory::new();
@@ -229,18 +252,33 @@ impl SimpleComponent for AppModel {
                     local_id,
                 },
                 MainOutput::RequestPreferences => AppMsg::RequestPreferences,
+                MainOutput::RequestNewChat => AppMsg::RequestNewChat,
+                MainOutput::RequestJoinGroup => AppMsg::RequestJoinGroup,
+                MainOutput::RequestMarkAllRead => AppMsg::RequestMarkAllRead,
                 MainOutput::RequestLogout => AppMsg::RequestLogout,
                 MainOutput::RequestLoadStatuses => AppMsg::RequestLoadStatuses,
                 MainOutput::OpenStatusAuthor { sender_jid, name } => {
                     AppMsg::OpenStatusAuthor { sender_jid, name }
                 }
                 MainOutput::RequestMediaDownload(id) => AppMsg::RequestMediaDownload(id),
-                MainOutput::RequestLoadOlder { chat_id, before_ts } => {
-                    AppMsg::RequestLoadOlder { chat_id, before_ts }
-                }
-                MainOutput::RequestLoadNewer { chat_id, after_ts } => {
-                    AppMsg::RequestLoadNewer { chat_id, after_ts }
-                }
+                MainOutput::RequestLoadOlder {
+                    chat_id,
+                    before_ts,
+                    before_id,
+                } => AppMsg::RequestLoadOlder {
+                    chat_id,
+                    before_ts,
+                    before_id,
+                },
+                MainOutput::RequestLoadNewer {
+                    chat_id,
+                    after_ts,
+                    after_id,
+                } => AppMsg::RequestLoadNewer {
+                    chat_id,
+                    after_ts,
+                    after_id,
+                },
                 MainOutput::RequestFetchAvatar(jid) => AppMsg::RequestFetchAvatar(jid),
                 MainOutput::RequestFetchAvatarFromURL(jid, url) => {
                     AppMsg::RequestFetchAvatarFromURL(jid, url)
@@ -248,9 +286,21 @@ impl SimpleComponent for AppModel {
                 MainOutput::SetChatPinned { chat_id, pinned } => {
                     AppMsg::SetChatPinned { chat_id, pinned }
                 }
+                MainOutput::SetChatMuted { chat_id, muted } => {
+                    AppMsg::SetChatMuted { chat_id, muted }
+                }
+                MainOutput::CopyInviteLink(chat_id) => AppMsg::CopyInviteLink(chat_id),
+                MainOutput::ShowContactInfo(chat_id) => AppMsg::ShowContactInfo(chat_id),
                 MainOutput::RequestStickers { chat_id } => {
                     AppMsg::RequestStickers { chat_id }
                 }
+                MainOutput::RequestForward {
+                    chat_id,
+                    message_id,
+                } => AppMsg::RequestForward {
+                    from_chat: chat_id,
+                    message_id,
+                },
                 MainOutput::RequestMarkRead {
                     chat_id,
                     sender_jid,
@@ -260,6 +310,9 @@ impl SimpleComponent for AppModel {
                     sender_jid,
                     message_ids,
                 },
+                MainOutput::SetTyping { chat_id, is_typing } => {
+                    AppMsg::SetTyping { chat_id, is_typing }
+                }
             });
 
         // Held over the app's lifetime; presented on demand from the
@@ -273,6 +326,8 @@ impl SimpleComponent for AppModel {
                 SettingsOutput::Repair => AppMsg::RequestRepair,
                 SettingsOutput::ClearMedia => AppMsg::ClearMediaCache,
                 SettingsOutput::ClearAvatars => AppMsg::ClearAvatarCache,
+                SettingsOutput::ClearLocalData => AppMsg::ClearLocalData,
+                SettingsOutput::RenameAccount(name) => AppMsg::RenameAccount(name),
                 SettingsOutput::SetLanguage(s) => AppMsg::SetLanguage(s),
             });
 
@@ -352,6 +407,12 @@ impl SimpleComponent for AppModel {
     fn update(&mut self, msg: AppMsg, _sender: ComponentSender<Self>) {
         self.dispatch(msg);
     }
+
+    /// Window closed / app quitting: let the service stop nanachi
+    /// (`Shutdown` + drain) before the process exits under it.
+    fn shutdown(&mut self, _widgets: &mut Self::Widgets, _output: relm4::Sender<Self::Output>) {
+        self.service.shutdown();
+    }
 }
 
 // Suppress an unused warning: the macro already wires the toast_overlay
@@ -5,6 +5,7 @@
 use adw::prelude::*;
 use crate::fl;
 use relm4::prelude::*;
+use tina_worker::{DisconnectReason, IpcError, WorkerError};
 use tracing::info;
 
 use crate::components::login::LoginInput;
@@ -14,30 +15,40 @@ use crate::service::Cmd;
 use super::messages::{AppMsg, ConnectionStatus, Scene};
 use super::model::AppModel;
 
+/// Characters of the failed text echoed in the send-failed toast.
+const SEND_FAILED_SNIPPET: usize = 40;
+
 impl AppModel {
     pub(super) fn dispatch(&mut self, msg: AppMsg) {
         match msg {
             AppMsg::ShowQrLogin => self.scene = Scene::QrLogin,
             AppMsg::ShowInApp => self.scene = Scene::InApp,
             AppMsg::QrCode(qr) => self.handle_qr(qr),
+            AppMsg::QrExpired => {
+                let _ = self.login.sender().send(LoginInput::Expired);
+            }
+            AppMsg::RequestQrRefresh => {
+                let _ = self.login.sender().send(LoginInput::Reset);
+                self.service.handle.send(Cmd::RefreshQr);
+            }
+            AppMsg::ConnectTimedOut => {
+                if self.scene == Scene::QrLogin {
+                    let _ = self.login.sender().send(LoginInput::TimedOut);
+                } else {
+                    self.show_connect_timed_out_toast();
+                }
+            }
+            AppMsg::RetryConnect => {
+                let _ = self.login.sender().send(LoginInput::Reset);
+                self.service.handle.send(Cmd::RetryConnect);
+            }
             AppMsg::Connected {
                 account_id,
                 phone_number,
                 jid,
                 push_name,
             } => self.handle_connected(account_id, phone_number, jid, push_name),
-            AppMsg::Disconnected(reason) => {
-                // Whatsmeow auto-reconnects on transient drops. We
-                // surface this as `Connecting` rather than `Offline`
-                // so a flicker on the wire doesn't read as "you're
-                // logged out". `LoggedOut` is the explicit terminal.
-                self.connection = ConnectionStatus::Connecting;
-                let _ = self
-                    .main
-                    .sender()
-                    .send(MainInput::SetConnection(ConnectionStatus::Connecting));
-                self.toast(fl!("toast-disconnected", "reason" = reason));
-            }
+            AppMsg::Disconnected(reason) => self.handle_disconnected(reason),
             AppMsg::LoggedOut => self.handle_logged_out(),
             AppMsg::ChatsUpserted { rows, messages_written } => {
                 if self.reconnect_syncing && messages_written > 0 {
@@ -62,12 +73,14 @@ impl AppModel {
                 name,
                 kind,
                 messages,
+                total_messages,
             } => {
                 let _ = self.main.sender().send(MainInput::ChatOpened {
                     chat_id,
                     name,
                     kind,
                     messages,
+                    total_messages,
                 });
             }
             AppMsg::HistorySyncDone => {
@@ -86,6 +99,13 @@ impl AppModel {
                     .sender()
                     .send(MainInput::HistorySyncEnded);
             }
+            AppMsg::InitialSyncDone => {
+                info!(scene = ?self.scene, "[sync] InitialSyncDone — Cmd::LoadChats");
+                // Normally already InApp (history is one of the three
+                // parts); what matters is the chat-list reload, which
+                // resolves every row that went out with a bare number.
+                self.handle_history_sync_done();
+            }
             AppMsg::HistorySyncProgress {
                 sync_type,
                 progress,
@@ -122,6 +142,12 @@ impl AppModel {
                     progress,
                 });
             }
+            AppMsg::HistorySyncResuming { sync_type, progress } => {
+                let _ = self.main.sender().send(MainInput::HistorySyncResuming {
+                    sync_type,
+                    progress,
+                });
+            }
             AppMsg::SkipSync => {
                 info!("[sync] user skipped reconnect sync — Scene::Syncing → Scene::InApp");
                 self.reconnect_syncing = false;
@@ -129,6 +155,10 @@ impl AppModel {
                 self.scene = Scene::InApp;
                 self.service.handle.send(Cmd::LoadChats);
             }
+            AppMsg::CancelSync => {
+                info!("[sync] user cancelled the history sync");
+                self.service.handle.send(Cmd::CancelSync);
+            }
             AppMsg::RepairStarted => self.handle_repair_started(),
             AppMsg::RepairProgress {
                 stage,
@@ -141,6 +171,7 @@ impl AppModel {
                 self.error = Some(e);
                 self.scene = Scene::Error;
             }
+            AppMsg::WorkerFailed { context, error } => self.handle_worker_failed(context, error),
             AppMsg::Toast(text) => self.toast(text),
             AppMsg::OpenChatNew(id) => self.service.handle.send(Cmd::OpenChat(id)),
             AppMsg::CloseChat(id) => self.service.handle.send(Cmd::CloseChat(id)),
@@ -184,6 +215,31 @@ impl AppModel {
                 self.settings.widget().close();
                 self.service.handle.send(Cmd::Repair);
             }
+            AppMsg::RequestForward {
+                from_chat,
+                message_id,
+            } => {
+                self.service.handle.send(Cmd::LoadForwardTargets {
+                    from_chat,
+                    message_id,
+                });
+            }
+            AppMsg::ShowForwardDialog {
+                from_chat,
+                message_id,
+                rows,
+            } => self.handle_show_forward_dialog(from_chat, message_id, rows),
+            AppMsg::Forwarded { sent, failed } => {
+                if failed == 0 {
+                    self.toast(fl!("toast-forwarded", "count" = sent));
+                } else {
+                    self.toast(fl!(
+                        "toast-forward-partial",
+                        "sent" = sent,
+                        "failed" = failed
+                    ));
+                }
+            }
             AppMsg::RequestStickers { chat_id } => {
                 self.service.handle.send(Cmd::LoadStickers {
                     chat_id,
@@ -201,12 +257,32 @@ impl AppModel {
                     message_ids,
                 });
             }
+            AppMsg::SetTyping { chat_id, is_typing } => {
+                self.service.handle.send(Cmd::SetTyping { chat_id, is_typing });
+            }
             AppMsg::ReceiptUpdate { message_ids, status } => {
                 let _ = self
                     .main
                     .sender()
                     .send(MainInput::ReceiptUpdate { message_ids, status });
             }
+            AppMsg::MessageEdited {
+                chat_id,
+                message_id,
+                new_content,
+            } => {
+                let _ = self.main.sender().send(MainInput::MessageEdited {
+                    chat_id,
+                    message_id,
+                    new_content,
+                });
+            }
+            AppMsg::ChatCleared { chat_id, kept } => {
+                let _ = self
+                    .main
+                    .sender()
+                    .send(MainInput::ChatCleared { chat_id, kept });
+            }
             AppMsg::StickersLoaded { chat_id, items } => {
                 let _ = self
                     .main
@@ -214,6 +290,30 @@ impl AppModel {
                     .send(MainInput::StickersLoaded { chat_id, items });
             }
             AppMsg::RequestPreferences => self.handle_open_preferences(),
+            AppMsg::RequestNewChat => {
+                let handle = self.service.handle.clone();
+                crate::components::new_chat_dialog::present(&self.toast_overlay, move |phone| {
+                    handle.send(Cmd::StartChat { phone });
+                });
+            }
+            AppMsg::NotOnWhatsApp(phone) => {
+                self.toast(fl!("toast-not-on-whatsapp", "phone" = phone));
+            }
+            AppMsg::RequestJoinGroup => {
+                let handle = self.service.handle.clone();
+                crate::components::join_group_dialog::present(&self.toast_overlay, move |link| {
+                    handle.send(Cmd::JoinGroup { link });
+                });
+            }
+            AppMsg::GroupJoined(name) => {
+                self.toast(fl!("toast-group-joined", "name" = name));
+            }
+            AppMsg::RequestMarkAllRead => {
+                self.service.handle.send(Cmd::MarkAllRead { send_receipts: true });
+            }
+            AppMsg::AllChatsRead(count) => {
+                self.toast(fl!("toast-marked-all-read", "count" = count));
+            }
             AppMsg::RequestLoadStatuses => self.service.handle.send(Cmd::LoadStatuses),
             AppMsg::RequestRefreshChat(chat_jid) => {
                 self.service.handle.send(Cmd::RefreshChat { chat_jid });
@@ -231,7 +331,11 @@ impl AppModel {
                 self.media.set_download_method(m);
                 self.service.handle.send(Cmd::SetDownloadMethod(m));
             }
-            AppMsg::PreferencesLoaded { method, pid } => {
+            AppMsg::PreferencesLoaded {
+                method,
+                pid,
+                account_name,
+            } => {
                 self.media.set_download_method(method);
                 use crate::components::settings::SettingsInput;
                 let _ = self
@@ -242,6 +346,19 @@ impl AppModel {
                     .settings
                     .sender()
                     .send(SettingsInput::SetNanachiPid(pid));
+                let _ = self
+                    .settings
+                    .sender()
+                    .send(SettingsInput::SetAccountName(account_name));
+            }
+            AppMsg::RenameAccount(name) => {
+                self.service.handle.send(Cmd::RenameAccount { name });
+            }
+            AppMsg::AccountRenamed(name) => {
+                self.toast(match name {
+                    Some(name) => fl!("toast-account-renamed", "name" = name),
+                    None => fl!("toast-account-name-cleared"),
+                });
             }
             AppMsg::ClearMediaCache => {
                 self.settings.widget().close();
@@ -251,6 +368,11 @@ impl AppModel {
                 self.settings.widget().close();
                 self.service.handle.send(Cmd::ClearAvatarCache);
             }
+            AppMsg::ClearLocalData => self.confirm_clear_local_data(),
+            AppMsg::LocalDataCleared => {
+                let _ = self.main.sender().send(MainInput::ClearChats);
+                self.toast(fl!("toast-local-data-cleared"));
+            }
             AppMsg::SetLanguage(locale) => {
                 let path = self.data_dir.join("language");
                 let _ = if locale.is_empty() {
@@ -263,21 +385,56 @@ impl AppModel {
             AppMsg::SetChatPinned { chat_id, pinned } => {
                 self.service.handle.send(Cmd::SetChatPinned { chat_id, pinned });
             }
+            AppMsg::SetChatMuted { chat_id, muted } => {
+                // O menu só oferece "até eu desativar".
+                let until = muted.then_some(tina_db::MUTED_FOREVER);
+                self.service.handle.send(Cmd::MuteChat { chat_id, until });
+            }
+            AppMsg::CopyInviteLink(chat_id) => {
+                self.service.handle.send(Cmd::GetGroupInviteLink { chat_id });
+            }
+            AppMsg::InviteLinkReady(link) => {
+                if let Some(display) = gtk::gdk::Display::default() {
+                    display.clipboard().set_text(&link);
+                }
+                self.toast(fl!("toast-invite-link-copied"));
+            }
+            AppMsg::ShowContactInfo(chat_id) => {
+                self.service.handle.send(Cmd::FetchProfile { chat_id });
+            }
+            AppMsg::ProfileLoaded {
+                contact,
+                common_groups,
+            } => {
+                crate::components::contact_info_dialog::present(
+                    &self.toast_overlay,
+                    &contact,
+                    &common_groups,
+                );
+            }
             AppMsg::RequestMediaDownload(message_id) => {
                 self.service.handle.send(Cmd::DownloadMedia { message_id });
             }
-            AppMsg::RequestLoadOlder { chat_id, before_ts } => {
+            AppMsg::RequestLoadOlder {
+                chat_id,
+                before_ts,
+                before_id,
+            } => {
                 self.service.handle.send(Cmd::LoadOlder {
                     chat_id,
                     before_ts,
-                    limit: 50,
+                    before_id,
                 });
             }
-            AppMsg::RequestLoadNewer { chat_id, after_ts } => {
+            AppMsg::RequestLoadNewer {
+                chat_id,
+                after_ts,
+                after_id,
+            } => {
                 self.service.handle.send(Cmd::LoadNewer {
                     chat_id,
                     after_ts,
-                    limit: 50,
+                    after_id,
                 });
             }
             AppMsg::RequestFetchAvatar(jid) => {
@@ -352,6 +509,44 @@ impl AppModel {
                     });
                 self.show_download_failed_dialog(message_id, error);
             }
+            AppMsg::MessageSendFailed {
+                message_id,
+                content,
+                error,
+            } => self.show_send_failed_toast(message_id, content, error),
+        }
+    }
+
+    /// Branch on the typed worker error: a failed `initialize` (there's
+    /// no screen behind Init to go back to) and anything that means the
+    /// nanachi bridge is gone land on the error scene; everything else
+    /// is a toast and the user stays where they were.
+    fn handle_worker_failed(&mut self, context: &'static str, error: WorkerError) {
+        tracing::error!(context, "worker call failed: {error}");
+        match &error {
+            _ if context == "initialize" => {
+                self.error = Some(format!("{context}: {error}"));
+                self.scene = Scene::Error;
+            }
+            WorkerError::Ipc(
+                IpcError::SpawnFailed(_)
+                | IpcError::BuildFailed(_)
+                | IpcError::NanachiNotFound { .. }
+                | IpcError::ProcessNotRunning,
+            )
+            | WorkerError::NotReady
+            | WorkerError::ChannelClosed => {
+                self.error = Some(format!("{context}: {error}"));
+                self.scene = Scene::Error;
+            }
+            WorkerError::AccountNotFound(id)
+            | WorkerError::Db(tina_db::DbError::AccountNotFound(id)) => {
+                self.toast(fl!("toast-account-not-found", "id" = id.as_str()));
+            }
+            WorkerError::NotGroupAdmin(_) => self.toast(fl!("toast-not-group-admin")),
+            WorkerError::InvalidInviteLink(_) => self.toast(fl!("toast-invalid-invite-link")),
+            WorkerError::AlreadyGroupMember(_) => self.toast(fl!("toast-already-group-member")),
+            _ => self.toast(fl!("toast-worker-error", "error" = error.to_string())),
         }
     }
 
@@ -385,6 +580,47 @@ impl AppModel {
         self.toast_overlay.add_toast(toast);
     }
 
+    /// Same toast-with-Retry shape as a failed download; the red
+    /// marker on the bubble stays after the toast times out.
+    fn show_send_failed_toast(&self, message_id: String, content: String, error: String) {
+        tracing::warn!(%message_id, %error, "send failed");
+        let snippet: String = content.chars().take(SEND_FAILED_SNIPPET).collect();
+        let snippet = if snippet.len() < content.len() {
+            format!("{snippet}…")
+        } else {
+            snippet
+        };
+        let toast = adw::Toast::builder()
+            .title(&fl!("toast-send-failed", "text" = snippet))
+            .button_label(&fl!("retry"))
+            .timeout(6)
+            .priority(adw::ToastPriority::High)
+            .use_markup(false)
+            .build();
+        let handle = self.service.handle.clone();
+        toast.connect_button_clicked(move |_| {
+            handle.send(Cmd::RetrySend {
+                message_id: message_id.clone(),
+            });
+        });
+        self.toast_overlay.add_toast(toast);
+    }
+
+    /// Returning user whose reconnect never got an answer: the chat
+    /// list stays usable offline, so a toast with Retry rather than
+    /// kicking them back to the login scene.
+    fn show_connect_timed_out_toast(&self) {
+        let toast = adw::Toast::builder()
+            .title(&fl!("toast-connect-timed-out"))
+            .button_label(&fl!("retry"))
+            .timeout(0)
+            .priority(adw::ToastPriority::High)
+            .build();
+        let handle = self.service.handle.clone();
+        toast.connect_button_clicked(move |_| handle.send(Cmd::RetryConnect));
+        self.toast_overlay.add_toast(toast);
+    }
+
     fn handle_open_stories(&self, name: String, posts: Vec<tina_db::MessageRow>) {
         // Same anchor pattern as `lightbox.rs`: pass any widget that's
         // a descendant of the application window and let `AdwDialog`
@@ -393,6 +629,48 @@ impl AppModel {
         crate::components::stories::open_stories_viewer(&self.toast_overlay, &name, posts);
     }
 
+    /// Destructive and not undoable, so it goes through an alert first.
+    /// The account and its pairing survive; see `TinaWorker::clear_history`.
+    fn confirm_clear_local_data(&self) {
+        self.settings.widget().close();
+        let dialog = adw::AlertDialog::builder()
+            .heading(&fl!("clear-data-heading"))
+            .body(&fl!("clear-data-body"))
+            .close_response("cancel")
+            .default_response("cancel")
+            .build();
+        dialog.add_response("cancel", &fl!("send-cancel"));
+        dialog.add_response("clear", &fl!("settings-clear-data-run"));
+        dialog.set_response_appearance("clear", adw::ResponseAppearance::Destructive);
+        let handle = self.service.handle.clone();
+        dialog.connect_response(Some("clear"), move |_, _| {
+            handle.send(Cmd::ClearHistory);
+        });
+        dialog.present(Some(&self.toast_overlay));
+    }
+
+    fn handle_show_forward_dialog(
+        &self,
+        from_chat: String,
+        message_id: String,
+        rows: Vec<tina_db::ChatRow>,
+    ) {
+        let handle = self.service.handle.clone();
+        let source = from_chat.clone();
+        crate::components::forward_dialog::present(
+            &self.toast_overlay,
+            &from_chat,
+            rows,
+            move |to| {
+                handle.send(Cmd::ForwardMessage {
+                    from_chat: source.clone(),
+                    message_id: message_id.clone(),
+                    to,
+                });
+            },
+        );
+    }
+
     fn handle_open_preferences(&self) {
         // Recompute disk-usage / RSS rows right before the dialog
         // becomes visible. They'd be stale otherwise — values were
@@ -459,6 +737,34 @@ impl AppModel {
         }
     }
 
+    /// Whatsmeow auto-reconnects on transient drops, so those (and
+    /// reasons we don't recognise) surface as `Connecting` rather than
+    /// `Offline` — a flicker on the wire shouldn't read as "you're
+    /// logged out". Replaced / banned / stopped won't come back by
+    /// themselves and go `Offline`; an unlink goes to the QR login.
+    /// An idle stop is `Offline` too, silently — the next use wakes it.
+    fn handle_disconnected(&mut self, reason: DisconnectReason) {
+        if reason.needs_login() {
+            self.handle_logged_out();
+            return;
+        }
+        let status = match reason {
+            DisconnectReason::Replaced
+            | DisconnectReason::Banned
+            | DisconnectReason::Stopped
+            | DisconnectReason::Idle => ConnectionStatus::Offline,
+            _ => ConnectionStatus::Connecting,
+        };
+        self.connection = status;
+        let _ = self.main.sender().send(MainInput::SetConnection(status));
+        match reason {
+            DisconnectReason::Stopped | DisconnectReason::Idle => {}
+            DisconnectReason::Replaced => self.toast(fl!("toast-disconnected-replaced")),
+            DisconnectReason::Banned => self.toast(fl!("toast-disconnected-banned")),
+            other => self.toast(fl!("toast-disconnected", "reason" = other.to_string())),
+        }
+    }
+
     fn handle_logged_out(&mut self) {
         self.scene = Scene::QrLogin;
         let _ = self.login.sender().send(LoginInput::Reset);
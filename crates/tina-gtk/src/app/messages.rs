@@ -2,7 +2,7 @@
 
 use std::path::PathBuf;
 
-use tina_core::WaIdentity;
+use tina_core::{DisconnectReason, WaIdentity};
 use tina_db::{ChatRow, MentionCandidate, MessageRow, StatusAuthorRow};
 
 pub struct AppInit {
@@ -24,15 +24,12 @@ pub enum Scene {
 }
 
 /// Connection state for the sidebar headerbar subtitle. `Connecting`
-/// is the boot/reconnect state — distinct from `Offline`, which is
-/// reserved for an explicit "we've given up" signal (no current event
-/// path sets it; left in the enum so future logout/network monitoring
-/// can use it without rewiring callers).
+/// is the boot/reconnect state — distinct from `Offline`, which means
+/// nothing will reconnect on its own (session replaced, ban, stopped).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionStatus {
     Connecting,
     Connected,
-    #[allow(dead_code)]
     Offline,
 }
 
@@ -42,13 +39,18 @@ pub enum AppMsg {
     ShowQrLogin,
     ShowInApp,
     QrCode(String),
+    /// The QR on screen went past its validity window.
+    QrExpired,
+    /// `start_account` got no answer in time; the worker already
+    /// stopped the account.
+    ConnectTimedOut,
     Connected {
         account_id: String,
         phone_number: Option<String>,
         jid: Option<WaIdentity>,
         push_name: Option<String>,
     },
-    Disconnected(String),
+    Disconnected(DisconnectReason),
     LoggedOut,
     ChatsUpserted { rows: Vec<ChatRow>, messages_written: usize },
     StatusAuthorsUpserted(Vec<StatusAuthorRow>),
@@ -61,13 +63,24 @@ pub enum AppMsg {
         name: String,
         kind: String,
         messages: Vec<MessageRow>,
+        /// Messages stored for the chat; `messages` is the newest page.
+        total_messages: i64,
     },
     HistorySyncDone,
+    /// Contacts, groups and history all landed for the first time:
+    /// every chat name can be resolved now.
+    InitialSyncDone,
     HistorySyncProgress {
         sync_type: String,
         progress: u32,
         messages_count: usize,
     },
+    /// A cursor from an interrupted sync was found at startup.
+    /// Shows "Resuming sync" in the headerbar until the stream ends.
+    HistorySyncResuming {
+        sync_type: String,
+        progress: u32,
+    },
     RepairStarted,
     RepairProgress {
         stage: String,
@@ -77,10 +90,22 @@ pub enum AppMsg {
     },
     RepairEnded,
     FatalError(String),
+    /// A worker call failed. Kept typed (not stringified) so the
+    /// dispatcher can tell recoverable failures (missing account,
+    /// DB hiccup) from fatal ones (nanachi couldn't be spawned).
+    /// `context` names the operation for logs / the message.
+    WorkerFailed {
+        context: &'static str,
+        error: tina_worker::WorkerError,
+    },
     Toast(String),
     /// User pressed "Skip" on the reconnect-sync page. Drops the UI
     /// straight to InApp without waiting for HistorySyncDone.
     SkipSync,
+    /// User pressed "Stop syncing" on the initial-sync page. Asks the
+    /// worker to cancel; the `HistorySyncDone` it emits takes the UI
+    /// to InApp with whatever was already stored.
+    CancelSync,
 
     MediaDownloadProgress {
         message_id: String,
@@ -96,6 +121,12 @@ pub enum AppMsg {
         message_id: String,
         error: String,
     },
+    /// A text send was rejected; the bubble already shows `failed`.
+    MessageSendFailed {
+        message_id: String,
+        content: String,
+        error: String,
+    },
     RequestMediaDownload(String),
     OlderMessagesLoaded {
         chat_id: String,
@@ -110,10 +141,12 @@ pub enum AppMsg {
     RequestLoadOlder {
         chat_id: String,
         before_ts: i64,
+        before_id: Option<String>,
     },
     RequestLoadNewer {
         chat_id: String,
         after_ts: i64,
+        after_id: Option<String>,
     },
     AvatarReady {
         jid: WaIdentity,
@@ -134,6 +167,19 @@ pub enum AppMsg {
         status: String,
     },
 
+    /// The sender edited a message; patch the row in its open tab.
+    MessageEdited {
+        chat_id: String,
+        message_id: String,
+        new_content: String,
+    },
+    /// The chat's messages were cleared; `kept` (the starred ones) is
+    /// all its open tab should still show.
+    ChatCleared {
+        chat_id: String,
+        kept: Vec<MessageRow>,
+    },
+
     // From the UI:
     OpenChatNew(String),
     CloseChat(String),
@@ -159,7 +205,25 @@ pub enum AppMsg {
     },
     RequestRepair,
     RequestPreferences,
+    /// Open the "new chat" prompt; the prompt sends `Cmd::StartChat`
+    /// itself on confirm.
+    RequestNewChat,
+    /// The number typed in the new-chat prompt isn't on WhatsApp.
+    NotOnWhatsApp(String),
+    /// Open the "join group via link" prompt; the prompt sends
+    /// `Cmd::JoinGroup` itself on confirm.
+    RequestJoinGroup,
+    /// Worker joined the group; carries its display name for the toast.
+    GroupJoined(String),
+    /// Profile menu's "Mark all as read"; sends `Cmd::MarkAllRead`.
+    RequestMarkAllRead,
+    /// Worker cleared the unread badges of this many chats.
+    AllChatsRead(u64),
     RequestLogout,
+    /// Login page asked for a new QR after the last one expired.
+    RequestQrRefresh,
+    /// Login scene's retry after `ConnectTimedOut`.
+    RetryConnect,
     RequestLoadStatuses,
     /// Triggered by `ChatInventory` when it sees a chat without a
     /// resolved display name. Routed to `Cmd::RefreshChat`.
@@ -175,6 +239,17 @@ pub enum AppMsg {
         posts: Vec<MessageRow>,
     },
     SetChatPinned { chat_id: String, pinned: bool },
+    SetChatMuted { chat_id: String, muted: bool },
+    CopyInviteLink(String),
+    /// Worker answered `Cmd::GetGroupInviteLink`.
+    InviteLinkReady(String),
+    ShowContactInfo(String),
+    /// Worker answered `Cmd::FetchProfile`, with the groups the
+    /// account shares with the contact.
+    ProfileLoaded {
+        contact: tina_db::Contact,
+        common_groups: Vec<tina_db::GroupSummaryRow>,
+    },
 
     /// Settings dialog finished applying the user's choice.
     SetDownloadMethod(crate::components::settings::DownloadMethod),
@@ -183,7 +258,12 @@ pub enum AppMsg {
     PreferencesLoaded {
         method: crate::components::settings::DownloadMethod,
         pid: Option<u32>,
+        account_name: Option<String>,
     },
+    /// Settings "Account name" applied; sends `Cmd::RenameAccount`.
+    RenameAccount(String),
+    /// Worker stored the new name (`None` when cleared).
+    AccountRenamed(Option<String>),
     /// Settings dialog asked us to drop the on-disk media cache.
     ClearMediaCache,
     /// Sticker picker requested its catalog. Carries the active
@@ -198,14 +278,34 @@ pub enum AppMsg {
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// ChatTab composer started / stopped typing. Routed to
+    /// `Cmd::SetTyping` → IPC → `whatsmeow.Client.SendChatPresence`.
+    SetTyping { chat_id: String, is_typing: bool },
     /// Worker pushed the recently-received stickers up. Forwarded
     /// straight to the matching `ChatTab` so its picker can repaint.
     StickersLoaded {
         chat_id: String,
         items: Vec<(String, String)>,
     },
+    /// "Forward…" on a message row. Routed to
+    /// `Cmd::LoadForwardTargets`; the chat list comes back as
+    /// `ShowForwardDialog`.
+    RequestForward { from_chat: String, message_id: String },
+    /// Chat list for the forward picker is ready. The picker sends
+    /// `Cmd::ForwardMessage` itself on confirm.
+    ShowForwardDialog {
+        from_chat: String,
+        message_id: String,
+        rows: Vec<ChatRow>,
+    },
+    /// Worker finished a forward: per-recipient tally for the toast.
+    Forwarded { sent: usize, failed: usize },
     /// Settings dialog asked us to drop the on-disk avatar cache.
     ClearAvatarCache,
+    /// Settings "Clear local data": confirm, then `Cmd::ClearHistory`.
+    ClearLocalData,
+    /// Worker wiped the account's synced data; empty the chat list.
+    LocalDataCleared,
     /// User picked a language in Preferences. Locale key ("en-US",
     /// "pt-BR", or "" for system). Written to disk; restart required.
     SetLanguage(String),
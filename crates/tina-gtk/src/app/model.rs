@@ -106,10 +106,11 @@ impl AppModel {
     }
 
     pub(super) fn repair_progress_text(&self) -> String {
-        if self.repair_total > 0 && !self.repair_indeterminate {
-            format!("{} / {}", self.repair_current, self.repair_total)
-        } else {
-            String::new()
+        match tina_worker::progress_percent(self.repair_current, self.repair_total) {
+            Some(pct) if !self.repair_indeterminate => {
+                format!("{} / {} · {pct}%", self.repair_current, self.repair_total)
+            }
+            _ => String::new(),
         }
     }
 }
@@ -20,7 +20,8 @@ impl ChatArea {
                 name,
                 kind,
                 messages,
-            } => self.handle_chat_opened(chat_id, name, kind, messages, &sender),
+                total_messages,
+            } => self.handle_chat_opened(chat_id, name, kind, messages, total_messages, &sender),
             ChatAreaInput::MessagesAppended { chat_id, messages } => {
                 self.handle_messages_appended(chat_id, messages)
             }
@@ -71,16 +72,29 @@ impl ChatArea {
                 local_id,
             } => self.forward_send_media(chat_id, kind, path, caption, mimetype, filename, local_id, &sender),
             ChatAreaInput::RequestMediaDownload(id) => self.forward_media_download(id, &sender),
-            ChatAreaInput::RequestLoadOlder { chat_id, before_ts } => {
-                self.forward_load_older(chat_id, before_ts, &sender)
-            }
-            ChatAreaInput::RequestLoadNewer { chat_id, after_ts } => {
-                self.forward_load_newer(chat_id, after_ts, &sender)
-            }
+            ChatAreaInput::RequestLoadOlder {
+                chat_id,
+                before_ts,
+                before_id,
+            } => self.forward_load_older(chat_id, before_ts, before_id, &sender),
+            ChatAreaInput::RequestLoadNewer {
+                chat_id,
+                after_ts,
+                after_id,
+            } => self.forward_load_newer(chat_id, after_ts, after_id, &sender),
             ChatAreaInput::RequestFetchAvatar(jid) => self.forward_fetch_avatar(jid, &sender),
             ChatAreaInput::RequestStickers { chat_id } => {
                 self.forward_request_stickers(chat_id, &sender);
             }
+            ChatAreaInput::RequestForward {
+                chat_id,
+                message_id,
+            } => {
+                let _ = sender.output(super::super::messages::ChatAreaOutput::RequestForward {
+                    chat_id,
+                    message_id,
+                });
+            }
             ChatAreaInput::RequestMarkRead {
                 chat_id,
                 sender_jid,
@@ -92,12 +106,24 @@ impl ChatArea {
                     message_ids,
                 });
             }
+            ChatAreaInput::SetTyping { chat_id, is_typing } => {
+                let _ = sender.output(super::super::messages::ChatAreaOutput::SetTyping {
+                    chat_id,
+                    is_typing,
+                });
+            }
             ChatAreaInput::StickersLoaded { chat_id, items } => {
                 self.handle_stickers_loaded(chat_id, items);
             }
             ChatAreaInput::ReceiptUpdate { message_ids, status } => {
                 self.handle_receipt_update(message_ids, status);
             }
+            ChatAreaInput::MessageEdited {
+                chat_id,
+                message_id,
+                new_content,
+            } => self.handle_message_edited(chat_id, message_id, new_content),
+            ChatAreaInput::ChatCleared { chat_id, kept } => self.handle_chat_cleared(chat_id, kept),
             ChatAreaInput::SetUserJid(jid) => self.handle_set_user_jid(jid),
             ChatAreaInput::MentionCandidatesLoaded { chat_id, candidates } => {
                 self.handle_mention_candidates_loaded(chat_id, candidates);
@@ -19,7 +19,8 @@ impl ChatArea {
         if let Some((controller, _, _)) = self.open_tabs.get(&chat_id) {
             let _ = controller.sender().send(ChatTabInput::Append(messages));
         } else {
-            tracing::warn!(
+            // Normal para ecos de envio (encaminhar pra chats fechados).
+            tracing::debug!(
                 chat = %chat_id,
                 "MessagesAppended received for chat with no open tab",
             );
@@ -185,6 +186,38 @@ impl ChatArea {
         }
     }
 
+    pub(in crate::components::chat_area) fn handle_message_edited(
+        &mut self,
+        chat_id: String,
+        message_id: String,
+        new_content: String,
+    ) {
+        // Closed tabs pick the new text up from the DB on reopen.
+        if let Some((controller, _, _)) = self.open_tabs.get(&chat_id) {
+            let _ = controller.sender().send(
+                crate::components::chat_tab::ChatTabInput::MessageEdited {
+                    message_id,
+                    new_content,
+                },
+            );
+        }
+    }
+
+    pub(in crate::components::chat_area) fn handle_chat_cleared(
+        &mut self,
+        chat_id: String,
+        kept: Vec<MessageRow>,
+    ) {
+        // Closed tabs load whatever is left on reopen.
+        if let Some((controller, _, _)) = self.open_tabs.get(&chat_id) {
+            let total_messages = kept.len() as i64;
+            let _ = controller.sender().send(ChatTabInput::Reset {
+                rows: kept,
+                total_messages,
+            });
+        }
+    }
+
     pub(in crate::components::chat_area) fn handle_receipt_update(
         &mut self,
         message_ids: Vec<String>,
@@ -238,18 +271,28 @@ impl ChatArea {
         &mut self,
         chat_id: String,
         before_ts: i64,
+        before_id: Option<String>,
         sender: &ComponentSender<Self>,
     ) {
-        let _ = sender.output(ChatAreaOutput::RequestLoadOlder { chat_id, before_ts });
+        let _ = sender.output(ChatAreaOutput::RequestLoadOlder {
+            chat_id,
+            before_ts,
+            before_id,
+        });
     }
 
     pub(in crate::components::chat_area) fn forward_load_newer(
         &mut self,
         chat_id: String,
         after_ts: i64,
+        after_id: Option<String>,
         sender: &ComponentSender<Self>,
     ) {
-        let _ = sender.output(ChatAreaOutput::RequestLoadNewer { chat_id, after_ts });
+        let _ = sender.output(ChatAreaOutput::RequestLoadNewer {
+            chat_id,
+            after_ts,
+            after_id,
+        });
     }
 
     pub(in crate::components::chat_area) fn forward_fetch_avatar(
@@ -50,6 +50,7 @@ impl ChatArea {
         name: String,
         kind: String,
         messages: Vec<MessageRow>,
+        total_messages: i64,
         sender: &ComponentSender<Self>,
     ) {
         self.chat_meta
@@ -72,10 +73,20 @@ impl ChatArea {
                 name: name.clone(),
                 kind: kind.clone(),
             });
-            let _ = controller.sender().send(ChatTabInput::Reset(messages));
+            let _ = controller.sender().send(ChatTabInput::Reset {
+                rows: messages,
+                total_messages,
+            });
             page.set_title(&name);
         } else {
-            self.spawn_tab(chat_id.clone(), name.clone(), kind.clone(), messages, sender);
+            self.spawn_tab(
+                chat_id.clone(),
+                name.clone(),
+                kind.clone(),
+                messages,
+                total_messages,
+                sender,
+            );
         }
         self.refresh_pane_visibility();
         self.refresh_pane_header(0);
@@ -94,6 +105,7 @@ impl ChatArea {
         name: String,
         kind: String,
         messages: Vec<MessageRow>,
+        total_messages: i64,
         sender: &ComponentSender<Self>,
     ) {
         let target_pane = self.focused_pane;
@@ -103,6 +115,7 @@ impl ChatArea {
                 name: name.clone(),
                 kind: kind.clone(),
                 initial: messages,
+                total_messages,
                 avatars: self.avatars.clone(),
                 media: self.media.clone(),
                 mentions: self.mentions.clone(),
@@ -143,18 +156,37 @@ impl ChatArea {
                 ChatTabOutput::RequestMediaDownload(id) => {
                     ChatAreaInput::RequestMediaDownload(id)
                 }
-                ChatTabOutput::RequestLoadOlder { chat_id, before_ts } => {
-                    ChatAreaInput::RequestLoadOlder { chat_id, before_ts }
-                }
-                ChatTabOutput::RequestLoadNewer { chat_id, after_ts } => {
-                    ChatAreaInput::RequestLoadNewer { chat_id, after_ts }
-                }
+                ChatTabOutput::RequestLoadOlder {
+                    chat_id,
+                    before_ts,
+                    before_id,
+                } => ChatAreaInput::RequestLoadOlder {
+                    chat_id,
+                    before_ts,
+                    before_id,
+                },
+                ChatTabOutput::RequestLoadNewer {
+                    chat_id,
+                    after_ts,
+                    after_id,
+                } => ChatAreaInput::RequestLoadNewer {
+                    chat_id,
+                    after_ts,
+                    after_id,
+                },
                 ChatTabOutput::RequestFetchAvatar(jid) => {
                     ChatAreaInput::RequestFetchAvatar(jid)
                 }
                 ChatTabOutput::RequestStickers { chat_id } => {
                     ChatAreaInput::RequestStickers { chat_id }
                 }
+                ChatTabOutput::RequestForward {
+                    chat_id,
+                    message_id,
+                } => ChatAreaInput::RequestForward {
+                    chat_id,
+                    message_id,
+                },
                 ChatTabOutput::RequestMarkRead {
                     chat_id,
                     sender_jid,
@@ -164,6 +196,9 @@ impl ChatArea {
                     sender_jid,
                     message_ids,
                 },
+                ChatTabOutput::SetTyping { chat_id, is_typing } => {
+                    ChatAreaInput::SetTyping { chat_id, is_typing }
+                }
             });
         let widget = controller.widget().clone();
         let page = self.panes[target_pane].tab_view.append(&widget);
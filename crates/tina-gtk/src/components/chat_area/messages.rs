@@ -27,6 +27,7 @@ pub enum ChatAreaInput {
         name: String,
         kind: String,
         messages: Vec<MessageRow>,
+        total_messages: i64,
     },
     MessagesAppended {
         chat_id: String,
@@ -103,23 +104,29 @@ pub enum ChatAreaInput {
     RequestLoadOlder {
         chat_id: String,
         before_ts: i64,
+        before_id: Option<String>,
     },
     /// Forwarded from a ChatTab — symmetric of RequestLoadOlder.
     RequestLoadNewer {
         chat_id: String,
         after_ts: i64,
+        after_id: Option<String>,
     },
     /// Forwarded from a ChatTab — sender-avatar fetch.
     RequestFetchAvatar(WaIdentity),
     /// Forwarded from a ChatTab — sticker picker requested its
     /// catalog.
     RequestStickers { chat_id: String },
+    /// Forwarded from a ChatTab — "Forward…" picked on a message row.
+    RequestForward { chat_id: String, message_id: String },
     /// Forwarded from a ChatTab — read receipts for incoming rows.
     RequestMarkRead {
         chat_id: String,
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// Forwarded from a ChatTab — outgoing typing indicator.
+    SetTyping { chat_id: String, is_typing: bool },
     /// Worker pushed sticker catalog. Routed to the matching tab.
     StickersLoaded {
         chat_id: String,
@@ -130,6 +137,17 @@ pub enum ChatAreaInput {
         message_ids: Vec<String>,
         status: String,
     },
+    /// Message edited by its sender. Routed to the matching tab.
+    MessageEdited {
+        chat_id: String,
+        message_id: String,
+        new_content: String,
+    },
+    /// Chat cleared; its tab (if open) shows only `kept`.
+    ChatCleared {
+        chat_id: String,
+        kept: Vec<MessageRow>,
+    },
     /// Identity arrived (or changed). Stored for new tabs + forwarded
     /// to existing ones so from_me rows pick up the user avatar.
     SetUserJid(Option<WaIdentity>),
@@ -170,14 +188,18 @@ pub enum ChatAreaOutput {
     RequestLoadOlder {
         chat_id: String,
         before_ts: i64,
+        before_id: Option<String>,
     },
     RequestLoadNewer {
         chat_id: String,
         after_ts: i64,
+        after_id: Option<String>,
     },
     RequestFetchAvatar(WaIdentity),
     /// Forwarded sticker-picker request.
     RequestStickers { chat_id: String },
+    /// Forwarded "Forward…" request from a message row.
+    RequestForward { chat_id: String, message_id: String },
     /// Forwarded mark-read request — child of `ChatAreaOutput`. The
     /// `ChatAreaInput` carries the same variant for the controller-
     /// to-area hop.
@@ -186,6 +208,8 @@ pub enum ChatAreaOutput {
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// Forwarded typing-indicator request.
+    SetTyping { chat_id: String, is_typing: bool },
     /// The set of chat_ids currently open in tabs (across both panes).
     /// Emitted whenever a tab opens or closes so the sidebar can
     /// highlight + sort-to-top the active chats.
@@ -1,5 +1,5 @@
-// Right-click context menu (Open / Open in new tab / Pin) attached to
-// every chat row. Communicates with the sidebar via a process-wide
+// Right-click context menu (Open / Open in new tab / Pin / Mute, plus
+// Copy invite link on groups and Contact info on DMs) attached to every chat row. Communicates with the sidebar via a process-wide
 // `OnceLock` sender — a single sidebar instance per app makes the
 // global safe.
 
@@ -17,6 +17,9 @@ use crate::components::sidebar::SidebarInput;
 pub struct RowMenuTarget {
     pub chat_id: String,
     pub pinned: bool,
+    pub muted: bool,
+    pub is_group: bool,
+    pub is_dm: bool,
 }
 
 /// Sender registered once at sidebar init; `attach_context_menu`'s
@@ -60,11 +63,27 @@ pub fn attach_context_menu(root: &gtk::Box, target: Rc<RefCell<Option<RowMenuTar
 
     let (pin_btn, pin_label) = build_pin_button(target.clone(), &popover);
     menu.append(&pin_btn);
+    let (mute_btn, mute_label) = build_mute_button(target.clone(), &popover);
+    menu.append(&mute_btn);
+    let invite_btn = menu_row(
+        &fl!("context-copy-invite-link"),
+        Box::new(SidebarInput::CopyInviteLinkRequested),
+        target.clone(),
+        &popover,
+    );
+    menu.append(&invite_btn);
+    let info_btn = menu_row(
+        &fl!("context-contact-info"),
+        Box::new(SidebarInput::ContactInfoRequested),
+        target.clone(),
+        &popover,
+    );
+    menu.append(&info_btn);
 
     popover.set_child(Some(&menu));
     popover.set_parent(root);
 
-    attach_gesture(root, &popover, target, pin_label);
+    attach_gesture(root, &popover, target, pin_label, mute_label, invite_btn, info_btn);
 }
 
 /// A flat button shaped like a `GtkPopoverMenu` row — left-aligned
@@ -121,11 +140,40 @@ fn build_pin_button(
     (pin_btn, pin_label)
 }
 
+fn build_mute_button(
+    target: Rc<RefCell<Option<RowMenuTarget>>>,
+    popover: &gtk::Popover,
+) -> (gtk::Button, gtk::Label) {
+    let mute_label = gtk::Label::builder()
+        .label(&fl!("context-mute"))
+        .xalign(0.0)
+        .hexpand(true)
+        .build();
+    let mute_btn = gtk::Button::builder()
+        .css_classes(["flat"])
+        .child(&mute_label)
+        .build();
+    let pop = popover.clone();
+    mute_btn.connect_clicked(move |_| {
+        if let (Some(t), Some(sender)) = (target.borrow().clone(), CONTEXT_MENU_SENDER.get()) {
+            let _ = sender.send(SidebarInput::MuteChatRequested {
+                chat_id: t.chat_id,
+                muted: !t.muted,
+            });
+        }
+        pop.popdown();
+    });
+    (mute_btn, mute_label)
+}
+
 fn attach_gesture(
     root: &gtk::Box,
     popover: &gtk::Popover,
     target: Rc<RefCell<Option<RowMenuTarget>>>,
     pin_label: gtk::Label,
+    mute_label: gtk::Label,
+    invite_btn: gtk::Button,
+    info_btn: gtk::Button,
 ) {
     let gesture = gtk::GestureClick::new();
     gesture.set_button(gtk::gdk::BUTTON_SECONDARY);
@@ -136,6 +184,9 @@ fn attach_gesture(
             // `Button::set_label` — the latter swaps in a brand-new
             // child label and drops our xalign/hexpand styling.
             pin_label.set_label(&(if t.pinned { fl!("context-unpin") } else { fl!("context-pin") }));
+            mute_label.set_label(&(if t.muted { fl!("context-unmute") } else { fl!("context-mute") }));
+            invite_btn.set_visible(t.is_group);
+            info_btn.set_visible(t.is_dm);
         }
         let rect = gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1);
         pop.set_pointing_to(Some(&rect));
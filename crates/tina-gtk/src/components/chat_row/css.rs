@@ -3,10 +3,11 @@
 // inside a rounded corner pill, the active row uses the accent
 // background, and the notification count is a tiny rounded chip
 // rendered in the row's own color. Status authors keep the WhatsApp
-// brand-green ring; pin and unread badges sit in the same right-side
-// gutter.
+// brand-green ring; mute, pin and unread badges sit in the same
+// right-side gutter.
 
 pub const CSS_TAB_OPEN: &str = "tina-tab-open";
+pub const CSS_BADGE_MUTED: &str = "tina-badge-muted";
 
 pub const CHAT_ROW_CSS: &str = "
 /* ── Sidebar list rows ───────────────────────────────────────────── */
@@ -110,4 +111,8 @@ headerbar .title {
   font-size: 0.75em;
   font-weight: 700;
 }
+/* Muted chat: same chip, neutral grey instead of brand green. */
+.tina-unread-badge.tina-badge-muted {
+  background-color: alpha(@theme_fg_color, 0.35);
+}
 ";
@@ -1,11 +1,12 @@
 // `ChatRowItem` — the data passed to the typed list view. Implements
 // `Ord` so the SortListModel knows how to order rows
-// (pinned → active → newest → alpha).
+// (pinned → active → newest → alpha → chat_id).
 
 use std::cmp::Ordering;
 use crate::fl;
 
-use tina_db::ChatRow;
+use tina_core::MessageType;
+use tina_db::{ChatOrderKey, ChatRow};
 
 use crate::inventory::{AvatarInventory, MentionInventory};
 use crate::time::format_chat_timestamp;
@@ -20,6 +21,8 @@ pub struct ChatRowItem {
     pub last_ts: i64,
     pub unread: i64,
     pub pinned: bool,
+    /// Local mute in effect: bell icon, grey badge, context menu label.
+    pub muted: bool,
     pub avatar_path: Option<String>,
     /// `true` when the chat currently has a tab open in the chat area.
     /// Drives both the sort key (active chats float to the top) and the
@@ -44,6 +47,7 @@ impl ChatRowItem {
             last_ts,
             unread: row.unread_count,
             pinned: row.pinned,
+            muted: row.muted,
             avatar_path: row.avatar_path.clone(),
             is_active: false,
             avatars,
@@ -70,40 +74,55 @@ fn resolve_display_name(row: &ChatRow) -> String {
     wa_id::display(&WaIdentity::parse(&row.chat_id))
 }
 
+/// Kind implied by a nanachi placeholder such as `[Image]`.
+fn placeholder_kind(raw: &str) -> MessageType {
+    match raw {
+        "[Image]" => MessageType::Image,
+        "[Audio]" => MessageType::Audio,
+        "[Video]" => MessageType::Video,
+        "[Sticker]" => MessageType::Sticker,
+        "[Document]" => MessageType::Document,
+        "[Contact]" => MessageType::Contact,
+        "[Location]" | "[Live Location]" => MessageType::Location,
+        _ => MessageType::Text,
+    }
+}
+
 fn build_preview(row: &ChatRow) -> String {
     let raw = row.last_message_preview.clone().unwrap_or_default();
-    let mtype = row.last_message_type.as_deref().unwrap_or("");
-    let preview = match mtype {
-        "image" => fl!("preview-photo"),
-        "audio" => match row.last_message_duration_secs {
+    // Linhas antigas sem `last_message_type` só têm o placeholder.
+    let kind = match MessageType::from_raw(row.last_message_type.as_deref().unwrap_or("")) {
+        MessageType::Text | MessageType::Unknown => placeholder_kind(&raw),
+        kind => kind,
+    };
+    let label = match kind {
+        MessageType::Image => fl!("preview-photo"),
+        MessageType::Audio => match row.last_message_duration_secs {
             Some(s) if s > 0 => fl!("preview-voice-duration",
                 "min" = format!("{}", s / 60),
                 "sec" = format!("{:02}", s % 60)
             ),
             _ => fl!("preview-voice-note"),
         },
-        "video" => match row.last_message_duration_secs {
+        MessageType::Video => match row.last_message_duration_secs {
             Some(s) if s > 0 => fl!("preview-video-duration",
                 "min" = format!("{}", s / 60),
                 "sec" = format!("{:02}", s % 60)
             ),
             _ => fl!("preview-video"),
         },
-        "sticker" => fl!("preview-sticker"),
-        "document" => fl!("preview-document"),
-        "contact" => fl!("preview-contact"),
-        "location" => fl!("preview-location"),
-        _ => match raw.as_str() {
-            "[Image]" => fl!("preview-photo"),
-            "[Audio]" => fl!("preview-voice-note"),
-            "[Video]" => fl!("preview-video"),
-            "[Sticker]" => fl!("preview-sticker"),
-            "[Document]" => fl!("preview-document"),
-            "[Contact]" => fl!("preview-contact"),
-            "[Location]" => fl!("preview-location"),
-            "[Live Location]" => fl!("preview-live-location"),
-            other => other.to_string(),
-        },
+        MessageType::Sticker => fl!("preview-sticker"),
+        MessageType::Document => fl!("preview-document"),
+        MessageType::Contact => fl!("preview-contact"),
+        MessageType::Location if raw == "[Live Location]" => fl!("preview-live-location"),
+        MessageType::Location => fl!("preview-location"),
+        _ => raw.clone(),
+    };
+    // The emoji comes from tina-core, not the translations, so every
+    // locale shows the same one.
+    let preview = match kind.to_preview_icon() {
+        Some(icon) if !label.is_empty() => format!("{icon} {label}"),
+        _ => label,
     };
     if preview.is_empty() {
         return preview;
@@ -145,8 +164,9 @@ fn short_sender_name(name: &str) -> String {
 }
 
 // Sort order: pinned first → active (currently in a tab) next →
-// newest next → alpha last. Reverse-compare bools so `true` floats
-// first. The pinned-before-active ordering matches what users expect
+// then `tina_db::ChatOrderKey` (newest → alpha → chat_id), the same
+// order the DB hands rows out in. Reverse-compare bools so `true`
+// floats first. The pinned-before-active ordering matches what users expect
 // from messengers like Telegram/WhatsApp — explicit pins outrank
 // transient "I happen to be chatting here right now".
 impl ChatRowItem {
@@ -157,6 +177,7 @@ impl ChatRowItem {
     pub fn differs_from(&self, prev: &ChatRowItem) -> bool {
         self.last_ts != prev.last_ts
             || self.pinned != prev.pinned
+            || self.muted != prev.muted
             || self.name != prev.name
             || self.preview != prev.preview
             || self.unread != prev.unread
@@ -164,6 +185,12 @@ impl ChatRowItem {
     }
 }
 
+impl ChatRowItem {
+    fn order_key(&self) -> ChatOrderKey<'_> {
+        ChatOrderKey::new(self.pinned, self.last_ts, &self.name, &self.chat_id)
+    }
+}
+
 impl Ord for ChatRowItem {
     fn cmp(&self, other: &Self) -> Ordering {
         match other.pinned.cmp(&self.pinned) {
@@ -174,11 +201,7 @@ impl Ord for ChatRowItem {
             Ordering::Equal => {}
             o => return o,
         }
-        match other.last_ts.cmp(&self.last_ts) {
-            Ordering::Equal => {}
-            o => return o,
-        }
-        self.name.cmp(&other.name)
+        self.order_key().cmp(&other.order_key())
     }
 }
 impl PartialOrd for ChatRowItem {
@@ -7,7 +7,7 @@ use adw::prelude::*;
 use relm4::typed_view::list::RelmListItem;
 
 use super::context_menu::{attach_context_menu, RowMenuTarget};
-use super::css::CSS_TAB_OPEN;
+use super::css::{CSS_BADGE_MUTED, CSS_TAB_OPEN};
 use super::item::ChatRowItem;
 
 /// Holds the per-row state the sidebar needs to find at bind/unbind
@@ -21,6 +21,7 @@ pub struct ChatRowWidgets {
     pub preview: gtk::Label,
     pub badge: gtk::Label,
     pub pin_icon: gtk::Image,
+    pub mute_icon: gtk::Image,
     /// Shared with the per-row context-menu's gesture closures. Bind
     /// updates this to point at the row's current chat; unbind clears
     /// it so a stale popover can't act on a recycled widget.
@@ -45,7 +46,7 @@ impl RelmListItem for ChatRowItem {
         root.append(&body);
 
         let (name, timestamp) = build_top_row(&body);
-        let (preview, pin_icon, badge) = build_bottom_row(&body);
+        let (preview, mute_icon, pin_icon, badge) = build_bottom_row(&body);
 
         // Per-row context menu. The target chat is updated at bind time
         // through the shared cell; gesture handlers see whatever the
@@ -60,6 +61,7 @@ impl RelmListItem for ChatRowItem {
             preview,
             badge,
             pin_icon,
+            mute_icon,
             menu_target,
         };
         (root, widgets)
@@ -88,6 +90,14 @@ impl RelmListItem for ChatRowItem {
             widgets.badge.set_visible(false);
             widgets.pin_icon.set_visible(false);
         }
+        // Sino riscado sempre que silenciado; o badge fica cinza, como
+        // no WhatsApp, para não chamar atenção.
+        widgets.mute_icon.set_visible(self.muted);
+        if self.muted {
+            widgets.badge.add_css_class(CSS_BADGE_MUTED);
+        } else {
+            widgets.badge.remove_css_class(CSS_BADGE_MUTED);
+        }
 
         if self.is_active {
             root.add_css_class(CSS_TAB_OPEN);
@@ -98,6 +108,9 @@ impl RelmListItem for ChatRowItem {
         *widgets.menu_target.borrow_mut() = Some(RowMenuTarget {
             chat_id: self.chat_id.clone(),
             pinned: self.pinned,
+            muted: self.muted,
+            is_group: self.kind == "group",
+            is_dm: self.kind == "dm",
         });
     }
 
@@ -153,7 +166,7 @@ fn build_top_row(body: &gtk::Box) -> (gtk::Label, gtk::Label) {
     (name, timestamp)
 }
 
-fn build_bottom_row(body: &gtk::Box) -> (gtk::Label, gtk::Image, gtk::Label) {
+fn build_bottom_row(body: &gtk::Box) -> (gtk::Label, gtk::Image, gtk::Image, gtk::Label) {
     let bottom = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(8)
@@ -173,6 +186,11 @@ fn build_bottom_row(body: &gtk::Box) -> (gtk::Label, gtk::Image, gtk::Label) {
     preview.add_css_class("caption");
     bottom.append(&preview);
 
+    let mute_icon = gtk::Image::from_icon_name("notifications-disabled-symbolic");
+    mute_icon.add_css_class("dim-label");
+    mute_icon.set_visible(false);
+    bottom.append(&mute_icon);
+
     let pin_icon = gtk::Image::from_icon_name("view-pin-symbolic");
     pin_icon.add_css_class("dim-label");
     pin_icon.set_visible(false);
@@ -184,5 +202,5 @@ fn build_bottom_row(body: &gtk::Box) -> (gtk::Label, gtk::Image, gtk::Label) {
     badge.set_visible(false);
     bottom.append(&badge);
 
-    (preview, pin_icon, badge)
+    (preview, mute_icon, pin_icon, badge)
 }
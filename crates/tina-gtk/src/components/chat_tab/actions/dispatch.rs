@@ -2,7 +2,7 @@
 
 use relm4::ComponentSender;
 
-use super::super::messages::ChatTabInput;
+use super::super::messages::{ChatTabInput, ChatTabOutput};
 use super::super::model::ChatTab;
 
 impl ChatTab {
@@ -13,7 +13,10 @@ impl ChatTab {
     ) {
         match msg {
             ChatTabInput::SetMeta { name, kind } => self.handle_set_meta(name, kind),
-            ChatTabInput::Reset(rows) => self.handle_reset(rows, &sender),
+            ChatTabInput::Reset {
+                rows,
+                total_messages,
+            } => self.handle_reset(rows, total_messages, &sender),
             ChatTabInput::Append(rows) => self.handle_append(rows, &sender),
             ChatTabInput::Send => self.handle_send(&sender),
             ChatTabInput::PickAttachment(kind) => self.handle_pick_attachment(kind, &sender),
@@ -41,6 +44,10 @@ impl ChatTab {
             ChatTabInput::ReceiptUpdate { message_ids, status } => {
                 self.handle_receipt_update(message_ids, status);
             }
+            ChatTabInput::MessageEdited {
+                message_id,
+                new_content,
+            } => self.handle_message_edited(message_id, new_content),
             ChatTabInput::MediaReady {
                 message_ids,
                 path,
@@ -68,6 +75,12 @@ impl ChatTab {
             ChatTabInput::RequestMediaDownload(id) => {
                 self.handle_request_media_download(id, &sender)
             }
+            ChatTabInput::ForwardRequested(message_id) => {
+                let _ = sender.output(ChatTabOutput::RequestForward {
+                    chat_id: self.chat_id.clone(),
+                    message_id,
+                });
+            }
             ChatTabInput::JumpToMessage(id) => self.handle_jump_to_message(id),
             ChatTabInput::MentionCandidatesLoaded(candidates) => {
                 self.handle_mention_candidates_loaded(candidates);
@@ -76,6 +89,8 @@ impl ChatTab {
                 self.pending_mentions.insert(jid);
             }
             ChatTabInput::RebindRow(message_id) => self.handle_rebind_row(&message_id),
+            ChatTabInput::ComposerChanged => self.handle_composer_changed(&sender),
+            ChatTabInput::TypingIdle(generation) => self.handle_typing_idle(generation, &sender),
         }
     }
 }
@@ -104,6 +104,11 @@ mod tests {
             quoted_sender_name: None,
             mentions_json: None,
             delivery_status: "sent".into(),
+            is_pinned: false,
+            is_starred: false,
+            edited_at: None,
+            forwarded: false,
+            raw_json: None,
         }
     }
 
@@ -20,13 +20,14 @@ impl ChatTab {
     pub(in crate::components::chat_tab) fn handle_reset(
         &mut self,
         rows: Vec<MessageRow>,
+        total_messages: i64,
         sender: &ComponentSender<Self>,
     ) {
         self.oldest_ts = rows.iter().map(|r| r.timestamp).min();
         self.newest_ts = rows.iter().map(|r| r.timestamp).max();
-        self.reached_top = rows.len() < 50;
+        self.reached_top = rows.len() as i64 >= total_messages;
         self.loading_older = false;
-        // Reset always pulls the newest 50 from the worker — the
+        // Reset always pulls the newest page from the worker — the
         // factory tail is the actual DB tail at this moment. Live
         // pushes via `MessagesAppended` keep it that way; trim paths
         // (TrimBottom, Append cumulative cap) clear this.
@@ -149,10 +150,7 @@ impl ChatTab {
             {
                 continue;
             }
-            if !matches!(
-                row.message_type.as_str(),
-                "image" | "video" | "audio" | "sticker" | "document"
-            ) {
+            if !tina_core::MessageType::from_raw(&row.message_type).is_media() {
                 continue;
             }
             if !self.media.try_mark_auto_queued(&row.message_id) {
@@ -404,7 +402,7 @@ impl ChatTab {
     }
 
     pub(in crate::components::chat_tab) fn handle_send(&mut self, sender: &ComponentSender<Self>) {
-        let text = self.composer_buffer.text().to_string();
+        let text = self.composer_text();
         let trimmed = text.trim();
         if trimmed.is_empty() {
             return;
@@ -448,6 +446,7 @@ impl ChatTab {
         mentioned_jids.sort();
         mentioned_jids.dedup();
         self.pending_mentions.clear();
+        self.stop_typing(sender);
         let _ = sender.output(ChatTabOutput::Send {
             chat_id: self.chat_id.clone(),
             text: trimmed.to_string(),
@@ -492,6 +491,7 @@ impl ChatTab {
             sender_name: String::new(),
             sender_jid: self.user_jid.as_ref().map(|x| x.raw().to_string()),
             sender_avatar_path: local_avatar,
+            sender: tina_core::MessageSender::Me,
             chat_kind: self.kind.clone(),
             chat_display_name: if self.name.is_empty() {
                 None
@@ -518,6 +518,7 @@ impl ChatTab {
             media_filename: None,
             media_sha256: None,
             delivery_status: "pending".to_string(),
+            edited: false,
             thumbnail: None,
             quoted_message_id: None,
             quoted_sender_id: None,
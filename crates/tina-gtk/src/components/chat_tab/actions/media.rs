@@ -77,10 +77,11 @@ impl ChatTab {
                 // Don't downgrade: read > delivered > sent > pending.
                 // The wire status arrives out-of-order sometimes (a
                 // delivered receipt after a read one for the same
-                // message group).
+                // message group). The one allowed step back is a
+                // retry: `failed` → `pending`.
                 let cur = status_rank(&it.delivery_status);
                 let new = status_rank(&status);
-                new > cur
+                new > cur || (it.delivery_status == "failed" && status == "pending")
             },
             |it| {
                 it.delivery_status = status.clone();
@@ -88,6 +89,21 @@ impl ChatTab {
         );
     }
 
+    pub(in crate::components::chat_tab) fn handle_message_edited(
+        &mut self,
+        message_id: String,
+        new_content: String,
+    ) {
+        self.update_items_where(
+            |it| it.id == message_id,
+            |it| {
+                it.content = new_content.clone();
+                it.edited = true;
+                it.recompute_markup();
+            },
+        );
+    }
+
     pub(in crate::components::chat_tab) fn handle_request_media_download(
         &mut self,
         id: String,
@@ -13,3 +13,4 @@ mod media;
 mod mentions;
 mod scroll;
 mod stickers;
+mod typing;
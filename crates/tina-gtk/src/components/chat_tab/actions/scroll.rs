@@ -12,6 +12,14 @@ use super::super::messages::{ChatTabInput, ChatTabOutput};
 use super::super::model::ChatTab;
 use super::super::scroll::force_to_bottom;
 
+/// Rows the factory may hold before a trim kicks in.
+const MAX_KEEP: usize = 150;
+/// Rows a trim leaves behind.
+const TARGET: usize = 100;
+
+// Um trim nunca deixa menos que uma página inteira.
+const _: () = assert!(TARGET >= *tina_worker::MESSAGE_PAGE_SIZE_RANGE.end() as usize);
+
 impl ChatTab {
     pub(in crate::components::chat_tab) fn handle_stick_to_bottom(&mut self) {
         self.bottomed.set(true);
@@ -26,8 +34,6 @@ impl ChatTab {
         // to `TARGET`. Re-opens the scroll-up path (clears
         // `reached_top` because there's now older history we don't
         // have in memory).
-        const MAX_KEEP: usize = 150;
-        const TARGET: usize = 100;
         let count = self.list.len() as usize;
         if count <= MAX_KEEP {
             return;
@@ -63,7 +69,7 @@ impl ChatTab {
     /// `handle_prepend_older` after a fast scroll-up has pushed the
     /// factory past the soft cap. Drops the newest rows so paging back
     /// through history doesn't grow the GTK widget tree without bound
-    /// (a group chat with media stacks dozens of pages × 50 rows × the
+    /// (a group chat with media stacks dozens of pages × the
     /// per-bubble widgets into hundreds of MB very quickly).
     ///
     /// The user's viewport is in the upper portion of the factory at
@@ -72,13 +78,11 @@ impl ChatTab {
     /// shrinks; `value` stays put.
     ///
     /// Recovering the trimmed tail requires reopening the chat (the
-    /// existing `OpenChat` path repaints from the latest 50). Live
+    /// existing `OpenChat` path repaints the latest page). Live
     /// pushes via `MessagesAppended` still land — `seen_message_ids`
     /// gets the dropped IDs cleared so a re-emit of the same row isn't
     /// dedup'd away.
     pub(in crate::components::chat_tab) fn handle_trim_bottom(&mut self) {
-        const MAX_KEEP: usize = 150;
-        const TARGET: usize = 100;
         if self.bottomed.get() {
             return;
         }
@@ -185,21 +189,28 @@ impl ChatTab {
         let Some(after_ts) = self.newest_ts else {
             return;
         };
+        // Same tie-break as the older page, from the back row.
+        let after_id = self
+            .list_back()
+            .filter(|r| r.item.timestamp_unix == after_ts)
+            .map(|r| r.item.id);
         self.loading_newer = true;
         tracing::info!(
             chat = %self.chat_id,
             after_ts,
+            ?after_id,
             "ChatTab: requesting newer page",
         );
         let _ = sender.output(ChatTabOutput::RequestLoadNewer {
             chat_id: self.chat_id.clone(),
             after_ts,
+            after_id,
         });
     }
 
     /// Apply a `LoadNewer` response — append the rows at the back of
     /// the factory in chronological order, update `newest_ts`, and
-    /// flip `reached_bottom` when the worker returned a short batch.
+    /// flip `reached_bottom` when the handler saw a short batch.
     /// Mirrors `handle_prepend_older`'s structure but without the
     /// scroll-position lock (appending at the back doesn't shift the
     /// user's view: rows materialise below the visible area, the
@@ -214,7 +225,7 @@ impl ChatTab {
         sender: &ComponentSender<Self>,
     ) {
         self.loading_newer = false;
-        if reached_bottom || messages.len() < 50 {
+        if reached_bottom {
             self.reached_bottom = true;
         }
         if messages.is_empty() {
@@ -285,15 +296,23 @@ impl ChatTab {
         let Some(before_ts) = self.oldest_ts else {
             return;
         };
+        // Keyset tie-break: the front row's ID, so older rows from the
+        // same second as it still come back on the next page.
+        let before_id = self
+            .list_front()
+            .filter(|r| r.item.timestamp_unix == before_ts)
+            .map(|r| r.item.id);
         self.loading_older = true;
         tracing::info!(
             chat = %self.chat_id,
             before_ts,
+            ?before_id,
             "ChatTab: requesting older page",
         );
         let _ = sender.output(ChatTabOutput::RequestLoadOlder {
             chat_id: self.chat_id.clone(),
             before_ts,
+            before_id,
         });
     }
 
@@ -304,7 +323,7 @@ impl ChatTab {
         sender: &ComponentSender<Self>,
     ) {
         self.loading_older = false;
-        if reached_top || messages.len() < 50 {
+        if reached_top {
             self.reached_top = true;
         }
         if messages.is_empty() {
@@ -408,14 +427,14 @@ impl ChatTab {
         }
 
         // Soft-cap mirror of NearBottom. Without this, every NearTop
-        // → PrependOlder cycle adds 50 rows and never gives any back —
+        // → PrependOlder cycle adds a page of rows and never gives any back —
         // the factory grows past whatever the user has patience to
         // scroll through, and group chats run the process out of RAM.
         // The pop is deferred to a follow-up idle so the value-restore
         // above lands first; otherwise the upper-shrink from the trim
         // would fold into the (new_upper - old_upper) delta and drag
         // the user's view up by the trimmed pixels.
-        if self.list.len() > 150 {
+        if self.list.len() as usize > MAX_KEEP {
             let input = sender.input_sender().clone();
             glib::idle_add_local_once(move || {
                 let _ = input.send(ChatTabInput::TrimBottom);
@@ -0,0 +1,71 @@
+// Outgoing typing indicator. The composer's `changed` signal lands
+// here; we rate-limit `is_typing: true` to one per `TYPING_REFRESH`
+// and arm a `TYPING_IDLE` timer that sends `false` once the user
+// stops. Sending a message clears the state immediately.
+
+use std::time::Instant;
+
+use adw::prelude::*;
+use gtk::glib;
+use relm4::ComponentSender;
+
+use super::super::messages::{ChatTabInput, ChatTabOutput, TYPING_IDLE, TYPING_REFRESH};
+use super::super::model::ChatTab;
+
+impl ChatTab {
+    pub(in crate::components::chat_tab) fn handle_composer_changed(
+        &mut self,
+        sender: &ComponentSender<Self>,
+    ) {
+        if self.is_read_only() {
+            return;
+        }
+        // Cleared composer (send, select-all + delete): stop right away
+        // instead of waiting for the idle timer.
+        if self.composer_text().trim().is_empty() {
+            self.stop_typing(sender);
+            return;
+        }
+
+        let due = self
+            .typing_sent_at
+            .is_none_or(|at| at.elapsed() >= TYPING_REFRESH);
+        if due {
+            self.typing_sent_at = Some(Instant::now());
+            let _ = sender.output(ChatTabOutput::SetTyping {
+                chat_id: self.chat_id.clone(),
+                is_typing: true,
+            });
+        }
+
+        // Re-arm the idle timer. Older timers still fire but carry a
+        // stale generation and are dropped in `handle_typing_idle`.
+        self.typing_gen = self.typing_gen.wrapping_add(1);
+        let generation = self.typing_gen;
+        let input = sender.input_sender().clone();
+        glib::timeout_add_local_once(TYPING_IDLE, move || {
+            let _ = input.send(ChatTabInput::TypingIdle(generation));
+        });
+    }
+
+    pub(in crate::components::chat_tab) fn handle_typing_idle(
+        &mut self,
+        generation: u64,
+        sender: &ComponentSender<Self>,
+    ) {
+        if generation == self.typing_gen {
+            self.stop_typing(sender);
+        }
+    }
+
+    /// Send `is_typing: false` if the peer currently sees us typing.
+    pub(in crate::components::chat_tab) fn stop_typing(&mut self, sender: &ComponentSender<Self>) {
+        if self.typing_sent_at.take().is_some() {
+            self.typing_gen = self.typing_gen.wrapping_add(1);
+            let _ = sender.output(ChatTabOutput::SetTyping {
+                chat_id: self.chat_id.clone(),
+                is_typing: false,
+            });
+        }
+    }
+}
@@ -53,6 +53,15 @@ pub fn build_item(
     // rebuild — the next batch fetch / scrollback page will pick
     // up names freshly populated by `set_candidates`.
     item.resolve_mentions(|digits| mentions.name_for_digits(digits));
+    // Admin badge: same inventory, same staleness — a group whose
+    // participant list lands after this build shows it on the next one.
+    item.sender = tina_core::parse_db_sender(
+        &row.chat_id,
+        row.is_from_me,
+        row.sender_jid.as_deref(),
+        row.sender_name.as_deref(),
+        |jid| mentions.is_admin(&row.chat_id, jid),
+    );
 
     // For from_me messages the DB stores sender_contact_id=NULL (we
     // never auto-register a contact for the signed-in user), so the
@@ -1,11 +1,11 @@
-// Ctrl+V image paste for the composer entry.
+// Ctrl+V image paste for the composer.
 //
-// `gtk::Entry`'s built-in paste only consumes text formats. To support
+// `gtk::TextView`'s built-in paste only consumes text formats. To support
 // pasting screenshots / images copied from a browser we install a
-// capture-phase key controller that fires BEFORE the entry's default
+// capture-phase key controller that fires BEFORE the view's default
 // handler: when the clipboard exposes a `GdkTexture`, we save it to a
 // tmp file, route it through `ChatTabInput::AttachFile` (same path as
-// the file-picker), and stop the propagation so the entry doesn't end
+// the file-picker), and stop the propagation so the view doesn't end
 // up pasting the texture's debug-string representation.
 
 use gtk::gdk;
@@ -16,11 +16,11 @@ use relm4::Sender;
 
 use super::messages::ChatTabInput;
 
-pub fn wire_paste(entry: &gtk::Entry, input: Sender<ChatTabInput>) {
+pub fn wire_paste(composer: &gtk::TextView, input: Sender<ChatTabInput>) {
     let key_ctl = gtk::EventControllerKey::new();
     key_ctl.set_propagation_phase(gtk::PropagationPhase::Capture);
     key_ctl.connect_key_pressed(clone!(
-        #[weak] entry,
+        #[weak] composer,
         #[strong] input,
         #[upgrade_or] glib::Propagation::Proceed,
         move |_ctl, keyval, _kc, mods| {
@@ -30,7 +30,7 @@ pub fn wire_paste(entry: &gtk::Entry, input: Sender<ChatTabInput>) {
             if keyval != gdk::Key::v && keyval != gdk::Key::V {
                 return glib::Propagation::Proceed;
             }
-            let clipboard = entry.clipboard();
+            let clipboard = composer.clipboard();
             if !clipboard_has_image(&clipboard) {
                 return glib::Propagation::Proceed;
             }
@@ -52,7 +52,7 @@ pub fn wire_paste(entry: &gtk::Entry, input: Sender<ChatTabInput>) {
             glib::Propagation::Stop
         }
     ));
-    entry.add_controller(key_ctl);
+    composer.add_controller(key_ctl);
 }
 
 /// Probe the clipboard's advertised mime types. `read_texture_async`
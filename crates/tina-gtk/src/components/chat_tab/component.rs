@@ -1,5 +1,5 @@
 // One open chat — header strip with the contact's name, scrollable thread
-// of message bubbles, and a single-line composer. The "active chat" gating
+// of message bubbles, and a multiline composer. The "active chat" gating
 // (which thread receives push updates) is the parent's job; a tab just
 // renders whatever it's been handed.
 //
@@ -193,12 +193,45 @@ impl SimpleComponent for ChatTab {
                         connect_clicked => ChatTabInput::OpenStickerPicker,
                     },
 
-                    #[name(composer_entry)]
-                    gtk::Entry {
-                        set_buffer: &model.composer_buffer,
+                    // Multiline composer: Enter sends, Shift+Enter breaks
+                    // the line (see `composer_keys`). GtkTextView has no
+                    // placeholder property, so a dimmed label is overlaid
+                    // while the buffer is empty; the scroller grows with
+                    // the draft up to a few lines.
+                    gtk::Overlay {
                         set_hexpand: true,
-                        set_placeholder_text: Some(&fl!("compose-message-placeholder")),
-                        connect_activate => ChatTabInput::Send,
+                        set_valign: gtk::Align::Center,
+
+                        #[wrap(Some)]
+                        set_child = &gtk::Frame {
+                            gtk::ScrolledWindow {
+                                set_hscrollbar_policy: gtk::PolicyType::Never,
+                                set_propagate_natural_height: true,
+                                set_max_content_height: 120,
+
+                                #[name(composer_view)]
+                                gtk::TextView {
+                                    set_buffer: Some(&model.composer_buffer),
+                                    set_wrap_mode: gtk::WrapMode::WordChar,
+                                    set_accepts_tab: false,
+                                    set_top_margin: 8,
+                                    set_bottom_margin: 8,
+                                    set_left_margin: 10,
+                                    set_right_margin: 10,
+                                },
+                            },
+                        },
+
+                        add_overlay = &gtk::Label {
+                            set_label: &fl!("compose-message-placeholder"),
+                            add_css_class: "dim-label",
+                            set_halign: gtk::Align::Start,
+                            set_valign: gtk::Align::Center,
+                            set_margin_start: 11,
+                            set_can_target: false,
+                            #[watch]
+                            set_visible: model.composer_buffer.char_count() == 0,
+                        },
                     },
 
                     // Voice-record toggle. Tap to start, tap again to
@@ -365,7 +398,7 @@ impl SimpleComponent for ChatTab {
             name: init.name,
             kind: init.kind,
             list,
-            composer_buffer: gtk::EntryBuffer::default(),
+            composer_buffer: gtk::TextBuffer::new(None),
             avatars: init.avatars,
             media: init.media,
             mentions: init.mentions,
@@ -377,12 +410,14 @@ impl SimpleComponent for ChatTab {
             sender_handle: row_sender,
             seen_message_ids: seen,
             last_send: None,
+            typing_sent_at: None,
+            typing_gen: 0,
             oldest_ts,
             loading_older: false,
-            reached_top: false,
+            reached_top: init.initial.len() as i64 >= init.total_messages,
             newest_ts,
             loading_newer: false,
-            // Initial page from `OpenChat` always pulls the newest 50;
+            // Initial page from `OpenChat` always pulls the newest page;
             // therefore the list's tail starts as the DB's actual
             // tail. Live `MessagesAppended` keeps it that way until a
             // soft-cap trim drops the newest rows from the list.
@@ -429,13 +464,19 @@ impl SimpleComponent for ChatTab {
         model.sticker_grid = Some(sticker_grid);
 
         // Ctrl+V on the composer pastes images straight into the
-        // attach-preview flow. Default GtkEntry paste only handles
+        // attach-preview flow. Default GtkTextView paste only handles
         // text, so we install a capture-phase key controller that
         // checks the clipboard for image content first.
         super::clipboard_paste::wire_paste(
-            &widgets.composer_entry,
+            &widgets.composer_view,
             sender.input_sender().clone(),
         );
+        {
+            let input = sender.input_sender().clone();
+            model.composer_buffer.connect_changed(move |_| {
+                let _ = input.send(ChatTabInput::ComposerChanged);
+            });
+        }
 
         // `@`-mention autocomplete popover. Constructed lazily so
         // the entry widget exists before `set_parent`. Seeded with
@@ -444,11 +485,16 @@ impl SimpleComponent for ChatTab {
         // launched), so a freshly-opened group already filters
         // without round-tripping the worker.
         let mention_popover = crate::components::mention_popover::MentionPopover::new(
-            &widgets.composer_entry,
+            &widgets.composer_view,
             model.avatars.clone(),
             sender.input_sender().clone(),
         );
         mention_popover.set_candidates(model.mentions.candidates_for(&model.chat_id));
+        super::composer_keys::wire_send_keys(
+            &widgets.composer_view,
+            mention_popover.clone(),
+            sender.input_sender().clone(),
+        );
         model.mention_popover = Some(mention_popover);
 
         wire_changed(&widgets.scroll, bottomed.clone(), updated_value.clone());
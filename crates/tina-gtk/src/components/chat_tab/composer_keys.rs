@@ -0,0 +1,42 @@
+// Enter / Shift+Enter handling for the multiline composer.
+//
+// `gtk::TextView` inserts a newline on every Return, so plain Enter is
+// intercepted at capture phase and turned into `ChatTabInput::Send`;
+// Shift+Enter falls through to the view's default handler and breaks
+// the line. While the `@`-mention popover is open Enter belongs to it
+// (it picks the highlighted candidate), so we step aside.
+
+use gtk::gdk;
+use gtk::glib;
+use gtk::prelude::*;
+use relm4::Sender;
+
+use super::messages::ChatTabInput;
+use crate::components::mention_popover::MentionPopover;
+
+pub fn wire_send_keys(
+    composer: &gtk::TextView,
+    mentions: MentionPopover,
+    input: Sender<ChatTabInput>,
+) {
+    let key_ctl = gtk::EventControllerKey::new();
+    key_ctl.set_propagation_phase(gtk::PropagationPhase::Capture);
+    key_ctl.connect_key_pressed(move |_ctl, keyval, _kc, mods| {
+        if !is_enter(keyval) || mentions.is_open() {
+            return glib::Propagation::Proceed;
+        }
+        if mods.contains(gdk::ModifierType::SHIFT_MASK) {
+            return glib::Propagation::Proceed;
+        }
+        let _ = input.send(ChatTabInput::Send);
+        glib::Propagation::Stop
+    });
+    composer.add_controller(key_ctl);
+}
+
+fn is_enter(keyval: gdk::Key) -> bool {
+    matches!(
+        keyval,
+        gdk::Key::Return | gdk::Key::KP_Enter | gdk::Key::ISO_Enter
+    )
+}
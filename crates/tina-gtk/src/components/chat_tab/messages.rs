@@ -10,13 +10,26 @@ use crate::inventory::{AvatarInventory, MediaInventory, MentionInventory};
 /// Dissent's 10-minute grouping window.
 pub const COLLAPSE_WINDOW_SECS: i64 = 10 * 60;
 
+/// Composer idle time after which we tell the peer we stopped typing.
+pub const TYPING_IDLE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Minimum gap between two `is_typing: true` sends while the user keeps
+/// typing. WhatsApp clients expire the indicator on their own after
+/// ~25s, so a refresh every few seconds is enough to keep it lit.
+pub const TYPING_REFRESH: std::time::Duration = std::time::Duration::from_secs(4);
+
 #[derive(Debug)]
 pub enum ChatTabInput {
     SetMeta {
         name: String,
         kind: String,
     },
-    Reset(Vec<MessageRow>),
+    /// Replace the list with the newest page; `total_messages` is how
+    /// many the chat has stored.
+    Reset {
+        rows: Vec<MessageRow>,
+        total_messages: i64,
+    },
     Append(Vec<MessageRow>),
     Send,
     /// User picked one of the entries in the attach popover. Opens
@@ -59,6 +72,8 @@ pub enum ChatTabInput {
     },
     MediaFailed(String),
     RequestMediaDownload(String),
+    /// Row context menu picked "Forward…" for this message.
+    ForwardRequested(String),
     /// Reply quote-header was clicked — scroll the thread to the
     /// cited message, briefly highlighting it. No-op when the
     /// target isn't currently in the factory.
@@ -70,7 +85,7 @@ pub enum ChatTabInput {
     NearBottom,
     /// Deferred trim of the newest rows after a `PrependOlder` settled.
     /// Symmetric counterpart to NearBottom's top-prune: fast scroll-up
-    /// stacks 50-row pages on top forever, so we lop off the back when
+    /// stacks pages on top forever, so we lop off the back when
     /// the factory blows past the cap. Posted from `handle_prepend_older`
     /// via an idle callback so the scroll-position restore runs first.
     TrimBottom,
@@ -127,6 +142,10 @@ pub enum ChatTabInput {
         message_ids: Vec<String>,
         status: String,
     },
+    MessageEdited {
+        message_id: String,
+        new_content: String,
+    },
     /// Worker resolved the `@`-mention picker. Replaces whatever the
     /// tab had cached so the popover filter operates on fresh data.
     MentionCandidatesLoaded(Vec<MentionCandidate>),
@@ -134,6 +153,12 @@ pub enum ChatTabInput {
     /// rewrote the entry's text; this just records the JID so the
     /// next `Send` lifts it into `contextInfo.MentionedJID`.
     MentionInserted { jid: String },
+    /// Composer text changed (keystroke, paste, popover rewrite).
+    /// Drives the outgoing typing indicator.
+    ComposerChanged,
+    /// 5s idle timer fired. Carries the generation it was armed
+    /// with so stale timers (the user kept typing) are ignored.
+    TypingIdle(u64),
 }
 
 #[derive(Debug)]
@@ -167,11 +192,21 @@ pub enum ChatTabOutput {
     },
     Close { chat_id: String },
     RequestMediaDownload(String),
-    RequestLoadOlder { chat_id: String, before_ts: i64 },
-    RequestLoadNewer { chat_id: String, after_ts: i64 },
+    RequestLoadOlder {
+        chat_id: String,
+        before_ts: i64,
+        before_id: Option<String>,
+    },
+    RequestLoadNewer {
+        chat_id: String,
+        after_ts: i64,
+        after_id: Option<String>,
+    },
     /// Ask the worker to fetch a sender's profile picture. Deduped at
     /// the tab level so we only round-trip per JID once.
     RequestFetchAvatar(WaIdentity),
+    /// User wants to forward `message_id`; the app asks for targets.
+    RequestForward { chat_id: String, message_id: String },
     /// Sticker picker wants the catalog. Carries `chat_id` so the
     /// result can be routed back through the tree to the right
     /// `ChatTab` (which is what fired the request).
@@ -185,6 +220,10 @@ pub enum ChatTabOutput {
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// Publish our composing state to the peer. Rate-limited at the
+    /// tab: at most one `true` per `TYPING_REFRESH`, one `false` on
+    /// idle / send.
+    SetTyping { chat_id: String, is_typing: bool },
 }
 
 pub struct ChatTabInit {
@@ -192,6 +231,9 @@ pub struct ChatTabInit {
     pub name: String,
     pub kind: String,
     pub initial: Vec<MessageRow>,
+    /// Messages stored for the chat; when `initial` already holds them
+    /// all there's nothing older to page in.
+    pub total_messages: i64,
     pub avatars: AvatarInventory,
     pub media: MediaInventory,
     pub mentions: MentionInventory,
@@ -1,9 +1,10 @@
 // One open chat — header strip with the contact's name, scrollable
-// thread of message bubbles, and a single-line composer.
+// thread of message bubbles, and a multiline composer.
 
 mod actions;
 mod build;
 mod clipboard_paste;
+mod composer_keys;
 mod component;
 pub mod messages;
 mod model;
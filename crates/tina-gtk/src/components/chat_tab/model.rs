@@ -24,7 +24,7 @@ pub struct ChatTab {
     /// `MessageRowItem` values inside the `gio::ListStore` and
     /// re-realise on scroll-in.
     pub(super) list: TypedListView<MessageRowItem, gtk::NoSelection>,
-    pub(super) composer_buffer: gtk::EntryBuffer,
+    pub(super) composer_buffer: gtk::TextBuffer,
     pub(super) avatars: AvatarInventory,
     pub(super) media: MediaInventory,
     pub(super) mentions: MentionInventory,
@@ -51,6 +51,11 @@ pub struct ChatTab {
     pub(super) sender_handle: relm4::Sender<super::messages::ChatTabInput>,
     pub(super) seen_message_ids: HashSet<String>,
     pub(super) last_send: Option<(String, std::time::Instant)>,
+    /// Outgoing typing indicator state. `typing_sent_at` is when the
+    /// last `is_typing: true` went out (None ⇒ peer sees us idle);
+    /// `typing_gen` invalidates idle timers armed by older keystrokes.
+    pub(super) typing_sent_at: Option<std::time::Instant>,
+    pub(super) typing_gen: u64,
     pub(super) oldest_ts: Option<i64>,
     pub(super) loading_older: bool,
     pub(super) reached_top: bool,
@@ -60,7 +65,7 @@ pub struct ChatTab {
     pub(super) newest_ts: Option<i64>,
     pub(super) loading_newer: bool,
     /// `true` when we know the list tail is the actual DB tail —
-    /// either because the chat just opened (initial 50 always
+    /// either because the chat just opened (the initial page always
     /// includes the newest) or because a `LoadNewer` returned fewer
     /// rows than requested. Cleared whenever `TrimBottom` or
     /// `Append`'s autoscroll-cap drops the newest rows from the list.
@@ -123,6 +128,12 @@ impl ChatTab {
         matches!(self.kind.as_str(), "newsletter" | "status" | "broadcast")
     }
 
+    /// Full composer draft, embedded newlines included.
+    pub(super) fn composer_text(&self) -> String {
+        let (start, end) = self.composer_buffer.bounds();
+        self.composer_buffer.text(&start, &end, false).to_string()
+    }
+
     pub(super) fn read_only_label(&self) -> String {
         match self.kind.as_str() {
             "newsletter" => fl!("readonly-newsletter"),
@@ -0,0 +1,66 @@
+// "Contact info" for a DM, opened from the chat row's context menu once
+// the worker has answered `Cmd::FetchProfile`. Read-only: avatar, name,
+// phone, status text and the groups in common. Whatever the contact's
+// privacy settings hide (no picture, no status) simply doesn't show.
+
+use adw::prelude::*;
+
+use crate::fl;
+use crate::format::format_jid_or_phone;
+
+const AVATAR_SIZE: i32 = 96;
+
+pub fn present(
+    anchor: &impl IsA<gtk::Widget>,
+    contact: &tina_db::Contact,
+    common_groups: &[tina_db::GroupSummaryRow],
+) {
+    let name = contact.display_name();
+    let phone = contact
+        .pn_jid
+        .as_deref()
+        .or(contact.phone_number.as_deref())
+        .map(format_jid_or_phone);
+    let status = contact
+        .status
+        .clone()
+        .unwrap_or_else(|| fl!("contact-info-no-status"));
+    let mut body = match phone {
+        Some(phone) if phone != name => format!("{phone}\n\n{status}"),
+        _ => status,
+    };
+    if !common_groups.is_empty() {
+        let groups: Vec<&str> = common_groups
+            .iter()
+            .map(|g| g.subject.as_deref().unwrap_or(&g.chat_id))
+            .collect();
+        body.push_str("\n\n");
+        body.push_str(&fl!(
+            "contact-info-common-groups",
+            "count" = common_groups.len(),
+            "groups" = groups.join(", ")
+        ));
+    }
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(&name)
+        .body(&body)
+        .close_response("close")
+        .default_response("close")
+        .build();
+    dialog.add_response("close", &fl!("contact-info-close"));
+
+    let avatar = adw::Avatar::builder()
+        .size(AVATAR_SIZE)
+        .text(&name)
+        .show_initials(true)
+        .build();
+    if let Some(path) = contact.avatar_path.as_deref()
+        && let Ok(texture) = gtk::gdk::Texture::from_filename(path)
+    {
+        avatar.set_custom_image(Some(&texture.upcast::<gtk::gdk::Paintable>()));
+    }
+    dialog.set_extra_child(Some(&avatar));
+
+    dialog.present(Some(anchor));
+}
@@ -0,0 +1,111 @@
+// "Forward to…" picker. One-shot like the media send preview: built
+// per request with the chat list the worker just handed back, lets the
+// user tick any number of chats (with a name filter for long lists)
+// and reports the picked `chat_id`s through `on_forward`. Nothing is
+// kept around after the dialog closes.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+
+use crate::fl;
+
+use tina_db::ChatRow;
+
+const LIST_WIDTH: i32 = 360;
+const LIST_HEIGHT: i32 = 420;
+
+pub fn present(
+    anchor: &impl IsA<gtk::Widget>,
+    from_chat: &str,
+    rows: Vec<ChatRow>,
+    on_forward: impl Fn(Vec<String>) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(&fl!("forward-heading"))
+        .close_response("cancel")
+        .default_response("forward")
+        .build();
+    dialog.add_response("cancel", &fl!("send-cancel"));
+    dialog.add_response("forward", &fl!("forward-send"));
+    dialog.set_response_appearance("forward", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("forward", false);
+
+    let search = gtk::SearchEntry::builder()
+        .placeholder_text(&fl!("forward-search-placeholder"))
+        .build();
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+
+    let selected: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let mut entries: Vec<(adw::ActionRow, String)> = Vec::new();
+    // Status não é um destino de encaminhamento; o chat de origem
+    // também fica de fora.
+    for row in rows
+        .into_iter()
+        .filter(|r| r.chat_id != from_chat && r.kind != "status")
+    {
+        let check = gtk::CheckButton::builder()
+            .valign(gtk::Align::Center)
+            .build();
+        let action_row = adw::ActionRow::builder()
+            .title(&row.name)
+            // Nome de chat é texto do usuário, não markup.
+            .use_markup(false)
+            .activatable_widget(&check)
+            .build();
+        action_row.add_prefix(&check);
+        {
+            let selected = selected.clone();
+            let dialog = dialog.clone();
+            let chat_id = row.chat_id.clone();
+            check.connect_toggled(move |c| {
+                let mut picked = selected.borrow_mut();
+                if c.is_active() {
+                    picked.push(chat_id.clone());
+                } else {
+                    picked.retain(|id| id != &chat_id);
+                }
+                dialog.set_response_enabled("forward", !picked.is_empty());
+            });
+        }
+        list.append(&action_row);
+        entries.push((action_row, row.name.to_lowercase()));
+    }
+
+    search.connect_search_changed(move |s| {
+        let needle = s.text().to_lowercase();
+        for (row, name) in &entries {
+            row.set_visible(needle.is_empty() || name.contains(needle.as_str()));
+        }
+    });
+
+    let scroller = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(LIST_HEIGHT)
+        .child(&list)
+        .build();
+    let body = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .build();
+    body.append(&search);
+    body.append(&scroller);
+    body.set_size_request(LIST_WIDTH, -1);
+    dialog.set_extra_child(Some(&body));
+
+    dialog.connect_response(None, move |dlg, response| {
+        if response == "forward" {
+            let picked = std::mem::take(&mut *selected.borrow_mut());
+            if !picked.is_empty() {
+                on_forward(picked);
+            }
+        }
+        dlg.close();
+    });
+
+    dialog.present(Some(anchor));
+}
@@ -0,0 +1,63 @@
+// "Join group via link" prompt. One-shot like the forward picker: a
+// single entry for the `chat.whatsapp.com/…` link, with Join enabled
+// only while the text parses as an invite link, so the worker never
+// sees an obviously malformed one. Reports the link through `on_join`.
+
+use adw::prelude::*;
+
+use crate::fl;
+
+const ENTRY_WIDTH: i32 = 360;
+
+pub fn present(anchor: &impl IsA<gtk::Widget>, on_join: impl Fn(String) + 'static) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(&fl!("join-group-heading"))
+        .body(&fl!("join-group-body"))
+        .close_response("cancel")
+        .default_response("join")
+        .build();
+    dialog.add_response("cancel", &fl!("send-cancel"));
+    dialog.add_response("join", &fl!("join-group-join"));
+    dialog.set_response_appearance("join", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("join", false);
+
+    let entry = gtk::Entry::builder()
+        .placeholder_text("https://chat.whatsapp.com/…")
+        .activates_default(true)
+        .hexpand(true)
+        .build();
+    entry.set_size_request(ENTRY_WIDTH, -1);
+
+    // Link colado da área de transferência já habilita o botão.
+    if let Some(display) = gtk::gdk::Display::default() {
+        let entry = entry.clone();
+        display
+            .clipboard()
+            .read_text_async(None::<&gtk::gio::Cancellable>, move |text| {
+                if let Ok(Some(text)) = text
+                    && entry.text().is_empty()
+                    && tina_core::group_invite_code(&text).is_some()
+                {
+                    entry.set_text(text.trim());
+                }
+            });
+    }
+
+    let weak = dialog.downgrade();
+    entry.connect_changed(move |entry| {
+        if let Some(dialog) = weak.upgrade() {
+            let valid = tina_core::group_invite_code(&entry.text()).is_some();
+            dialog.set_response_enabled("join", valid);
+        }
+    });
+    dialog.set_extra_child(Some(&entry));
+
+    dialog.connect_response(None, move |dlg, response| {
+        if response == "join" {
+            on_join(entry.text().trim().to_string());
+        }
+        dlg.close();
+    });
+
+    dialog.present(Some(anchor));
+}
@@ -13,17 +13,31 @@ use relm4::prelude::*;
 pub enum LoginInput {
     SetQr(String),
     Reset,
+    /// The current code's validity ran out without a scan.
+    Expired,
+    /// WhatsApp never answered the connect attempt.
+    TimedOut,
+}
+
+#[derive(Debug)]
+pub enum LoginOutput {
+    /// User asked for a fresh code after the previous one expired.
+    RefreshQr,
+    /// User asked to start the account again after a connect timeout.
+    RetryConnect,
 }
 
 pub struct LoginPage {
     qr_texture: Option<gdk::Texture>,
+    expired: bool,
+    timed_out: bool,
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for LoginPage {
     type Init = ();
     type Input = LoginInput;
-    type Output = ();
+    type Output = LoginOutput;
 
     view! {
         adw::ToolbarView {
@@ -174,8 +188,58 @@ impl SimpleComponent for LoginPage {
                                 set_paintable: model.qr_texture.as_ref().map(|t| t.upcast_ref::<gdk::Paintable>()),
                             },
 
+                            add_named[Some("expired")] = &gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_spacing: 12,
+                                set_halign: gtk::Align::Center,
+                                set_valign: gtk::Align::Center,
+
+                                gtk::Label {
+                                    set_label: &fl!("login-qr-expired"),
+                                    set_wrap: true,
+                                    set_justify: gtk::Justification::Center,
+                                    add_css_class: "dim-label",
+                                },
+                                gtk::Button {
+                                    set_label: &fl!("login-qr-refresh"),
+                                    set_halign: gtk::Align::Center,
+                                    add_css_class: "pill",
+                                    add_css_class: "suggested-action",
+                                    connect_clicked[sender] => move |_| {
+                                        let _ = sender.output(LoginOutput::RefreshQr);
+                                    },
+                                },
+                            },
+
+                            add_named[Some("timed-out")] = &gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_spacing: 12,
+                                set_halign: gtk::Align::Center,
+                                set_valign: gtk::Align::Center,
+
+                                gtk::Label {
+                                    set_label: &fl!("login-connect-timed-out"),
+                                    set_wrap: true,
+                                    set_justify: gtk::Justification::Center,
+                                    add_css_class: "dim-label",
+                                },
+                                gtk::Button {
+                                    set_label: &fl!("login-connect-retry"),
+                                    set_halign: gtk::Align::Center,
+                                    add_css_class: "pill",
+                                    add_css_class: "suggested-action",
+                                    connect_clicked[sender] => move |_| {
+                                        let _ = sender.output(LoginOutput::RetryConnect);
+                                    },
+                                },
+                            },
+
                             #[watch]
-                            set_visible_child_name: if model.qr_texture.is_some() {
+                            set_visible_child_name: if model.timed_out {
+                                "timed-out"
+                            } else if model.expired {
+                                "expired"
+                            } else if model.qr_texture.is_some() {
                                 "qr"
                             } else {
                                 "loading"
@@ -190,9 +254,13 @@ impl SimpleComponent for LoginPage {
     fn init(
         _: Self::Init,
         root: Self::Root,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let model = LoginPage { qr_texture: None };
+        let model = LoginPage {
+            qr_texture: None,
+            expired: false,
+            timed_out: false,
+        };
         let widgets = view_output!();
         ComponentParts { model, widgets }
     }
@@ -201,9 +269,19 @@ impl SimpleComponent for LoginPage {
         match msg {
             LoginInput::SetQr(qr) => {
                 self.qr_texture = crate::qr::render_qr_texture(&qr);
+                self.expired = false;
+                self.timed_out = false;
             }
             LoginInput::Reset => {
                 self.qr_texture = None;
+                self.expired = false;
+                self.timed_out = false;
+            }
+            LoginInput::Expired => {
+                self.expired = true;
+            }
+            LoginInput::TimedOut => {
+                self.timed_out = true;
             }
         }
     }
@@ -26,6 +26,9 @@ impl MainPage {
                     .sender()
                     .send(SidebarInput::ChatsUpserted(rows));
             }
+            MainInput::ClearChats => {
+                let _ = self.sidebar.sender().send(SidebarInput::ClearChats);
+            }
             MainInput::StatusAuthorsUpserted(rows) => {
                 let _ = self
                     .sidebar
@@ -37,12 +40,14 @@ impl MainPage {
                 name,
                 kind,
                 messages,
+                total_messages,
             } => {
                 let _ = self.chat_area.sender().send(ChatAreaInput::ChatOpened {
                     chat_id,
                     name,
                     kind,
                     messages,
+                    total_messages,
                 });
             }
             MainInput::ChatOpened { chat_id: None, .. } => {
@@ -66,6 +71,23 @@ impl MainPage {
                     .sender()
                     .send(ChatAreaInput::ReceiptUpdate { message_ids, status });
             }
+            MainInput::MessageEdited {
+                chat_id,
+                message_id,
+                new_content,
+            } => {
+                let _ = self.chat_area.sender().send(ChatAreaInput::MessageEdited {
+                    chat_id,
+                    message_id,
+                    new_content,
+                });
+            }
+            MainInput::ChatCleared { chat_id, kept } => {
+                let _ = self
+                    .chat_area
+                    .sender()
+                    .send(ChatAreaInput::ChatCleared { chat_id, kept });
+            }
             MainInput::SetRepairing(r) => {
                 let _ = self.sidebar.sender().send(SidebarInput::SetRepairing(r));
             }
@@ -84,6 +106,18 @@ impl MainPage {
                         progress,
                     });
             }
+            MainInput::HistorySyncResuming {
+                sync_type,
+                progress,
+            } => {
+                let _ = self
+                    .sidebar
+                    .sender()
+                    .send(SidebarInput::HistorySyncResuming {
+                        sync_type,
+                        progress,
+                    });
+            }
             MainInput::HistorySyncEnded => {
                 let _ = self
                     .sidebar
@@ -224,6 +258,15 @@ impl MainPage {
             SidebarOutput::RequestPreferences => {
                 let _ = sender.output(MainOutput::RequestPreferences);
             }
+            SidebarOutput::RequestNewChat => {
+                let _ = sender.output(MainOutput::RequestNewChat);
+            }
+            SidebarOutput::RequestJoinGroup => {
+                let _ = sender.output(MainOutput::RequestJoinGroup);
+            }
+            SidebarOutput::RequestMarkAllRead => {
+                let _ = sender.output(MainOutput::RequestMarkAllRead);
+            }
             SidebarOutput::RequestLoadStatuses => {
                 let _ = sender.output(MainOutput::RequestLoadStatuses);
             }
@@ -242,6 +285,15 @@ impl MainPage {
             SidebarOutput::SetChatPinned { chat_id, pinned } => {
                 let _ = sender.output(MainOutput::SetChatPinned { chat_id, pinned });
             }
+            SidebarOutput::SetChatMuted { chat_id, muted } => {
+                let _ = sender.output(MainOutput::SetChatMuted { chat_id, muted });
+            }
+            SidebarOutput::CopyInviteLink(chat_id) => {
+                let _ = sender.output(MainOutput::CopyInviteLink(chat_id));
+            }
+            SidebarOutput::ShowContactInfo(chat_id) => {
+                let _ = sender.output(MainOutput::ShowContactInfo(chat_id));
+            }
         }
     }
 
@@ -291,11 +343,27 @@ impl MainPage {
             ChatAreaOutput::RequestMediaDownload(id) => {
                 let _ = sender.output(MainOutput::RequestMediaDownload(id));
             }
-            ChatAreaOutput::RequestLoadOlder { chat_id, before_ts } => {
-                let _ = sender.output(MainOutput::RequestLoadOlder { chat_id, before_ts });
+            ChatAreaOutput::RequestLoadOlder {
+                chat_id,
+                before_ts,
+                before_id,
+            } => {
+                let _ = sender.output(MainOutput::RequestLoadOlder {
+                    chat_id,
+                    before_ts,
+                    before_id,
+                });
             }
-            ChatAreaOutput::RequestLoadNewer { chat_id, after_ts } => {
-                let _ = sender.output(MainOutput::RequestLoadNewer { chat_id, after_ts });
+            ChatAreaOutput::RequestLoadNewer {
+                chat_id,
+                after_ts,
+                after_id,
+            } => {
+                let _ = sender.output(MainOutput::RequestLoadNewer {
+                    chat_id,
+                    after_ts,
+                    after_id,
+                });
             }
             ChatAreaOutput::RequestFetchAvatar(jid) => {
                 let _ = sender.output(MainOutput::RequestFetchAvatar(jid));
@@ -303,6 +371,15 @@ impl MainPage {
             ChatAreaOutput::RequestStickers { chat_id } => {
                 let _ = sender.output(MainOutput::RequestStickers { chat_id });
             }
+            ChatAreaOutput::RequestForward {
+                chat_id,
+                message_id,
+            } => {
+                let _ = sender.output(MainOutput::RequestForward {
+                    chat_id,
+                    message_id,
+                });
+            }
             ChatAreaOutput::RequestMarkRead {
                 chat_id,
                 sender_jid,
@@ -314,6 +391,9 @@ impl MainPage {
                     message_ids,
                 });
             }
+            ChatAreaOutput::SetTyping { chat_id, is_typing } => {
+                let _ = sender.output(MainOutput::SetTyping { chat_id, is_typing });
+            }
             ChatAreaOutput::ActiveTabsChanged(ids) => {
                 // Sidebar uses this to pin active chats to the top
                 // and paint the `tina-tab-open` highlight.
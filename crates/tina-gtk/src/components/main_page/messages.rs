@@ -29,12 +29,15 @@ pub enum MainInput {
         push_name: Option<String>,
     },
     ChatsUpserted(Vec<ChatRow>),
+    /// The account's local data was wiped; drop every chat row.
+    ClearChats,
     StatusAuthorsUpserted(Vec<StatusAuthorRow>),
     ChatOpened {
         chat_id: Option<String>,
         name: String,
         kind: String,
         messages: Vec<MessageRow>,
+        total_messages: i64,
     },
     MessagesAppended {
         chat_id: String,
@@ -52,9 +55,19 @@ pub enum MainInput {
         message_ids: Vec<String>,
         status: String,
     },
+    MessageEdited {
+        chat_id: String,
+        message_id: String,
+        new_content: String,
+    },
+    ChatCleared {
+        chat_id: String,
+        kept: Vec<MessageRow>,
+    },
     SetRepairing(bool),
     SetConnection(ConnectionStatus),
     HistorySyncProgress { sync_type: String, progress: u32 },
+    HistorySyncResuming { sync_type: String, progress: u32 },
     HistorySyncEnded,
     RepairProgress {
         stage: String,
@@ -117,6 +130,9 @@ pub enum MainOutput {
         local_id: Option<String>,
     },
     RequestPreferences,
+    RequestNewChat,
+    RequestJoinGroup,
+    RequestMarkAllRead,
     RequestLogout,
     RequestLoadStatuses,
     OpenStatusAuthor {
@@ -124,17 +140,34 @@ pub enum MainOutput {
         name: String,
     },
     RequestMediaDownload(String),
-    RequestLoadOlder { chat_id: String, before_ts: i64 },
-    RequestLoadNewer { chat_id: String, after_ts: i64 },
+    RequestLoadOlder {
+        chat_id: String,
+        before_ts: i64,
+        before_id: Option<String>,
+    },
+    RequestLoadNewer {
+        chat_id: String,
+        after_ts: i64,
+        after_id: Option<String>,
+    },
     RequestFetchAvatar(WaIdentity),
     RequestFetchAvatarFromURL(WaIdentity, String),
     SetChatPinned { chat_id: String, pinned: bool },
+    SetChatMuted { chat_id: String, muted: bool },
+    /// Fetch a group's invite link and put it on the clipboard.
+    CopyInviteLink(String),
+    /// Fetch a DM contact's profile and show it.
+    ShowContactInfo(String),
     /// Sticker-picker popover wants the recent-stickers catalog.
     RequestStickers { chat_id: String },
+    /// A message row asked to be forwarded.
+    RequestForward { chat_id: String, message_id: String },
     /// Tab observed unread incoming rows while bottomed.
     RequestMarkRead {
         chat_id: String,
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// Composer typing indicator for `chat_id`.
+    SetTyping { chat_id: String, is_typing: bool },
 }
@@ -9,14 +9,14 @@
 // through.
 //
 // Wiring (see `chat_tab::component`):
-//   - Anchored to the composer `gtk::Entry`.
-//   - Watches `notify::text` and `notify::cursor-position` on the
-//     entry's `EntryBuffer` to find the `@<query>` token at the
+//   - Anchored to the composer `gtk::TextView`.
+//   - Watches `changed` and `notify::cursor-position` on the
+//     composer's `TextBuffer` to find the `@<query>` token at the
 //     cursor.
 //   - Keyboard: ↑/↓ navigate, Enter/Tab insert, Escape inhibits
 //     the popover for the current word.
 //   - Selection replaces the `@<query>` substring with `@<digits> `
-//     in the entry buffer and pushes the JID up the channel so
+//     in the composer buffer and pushes the JID up the channel so
 //     `ChatTab` can record it on `pending_mentions`.
 
 use std::cell::RefCell;
@@ -41,10 +41,10 @@ pub struct MentionPopover {
 struct Inner {
     popover: gtk::Popover,
     list: gtk::ListBox,
-    /// Entry the popover autocompletes for. Held weakly through
+    /// Composer the popover autocompletes for. Held weakly through
     /// `popover.set_parent` already; we keep a clone here for the
     /// buffer-rewrite path on row activation.
-    entry: gtk::Entry,
+    composer: gtk::TextView,
     /// Avatar inventory shared with the rest of the GTK tree —
     /// the popover renders the same cached textures the bubbles
     /// use, so rows don't trigger fresh fetches.
@@ -57,7 +57,7 @@ struct Inner {
     /// tab can stash the JID in `pending_mentions`.
     sender: Sender<ChatTabInput>,
     /// Current `@<query>` window: `(start_byte, end_byte)` in the
-    /// entry's text. `None` when the popover is hidden.
+    /// composer's text. `None` when the popover is hidden.
     current_word: RefCell<Option<(usize, usize)>>,
     /// User pressed Esc on the current word — keep the popover
     /// suppressed until the cursor leaves the `@<…>` token.
@@ -66,7 +66,7 @@ struct Inner {
 
 impl MentionPopover {
     pub fn new(
-        entry: &gtk::Entry,
+        composer: &gtk::TextView,
         avatars: AvatarInventory,
         sender: Sender<ChatTabInput>,
     ) -> Self {
@@ -74,11 +74,11 @@ impl MentionPopover {
             .selection_mode(gtk::SelectionMode::Browse)
             .build();
         list.add_css_class("mention-popover");
-        // The popover doesn't own keyboard focus — the entry
+        // The popover doesn't own keyboard focus — the composer
         // does. If we let the ListBox/Popover steal focus on
         // popup, the user's next keystroke goes nowhere visible
-        // (the entry stops receiving characters). Disabling
-        // can-focus on both keeps the cursor in the entry while
+        // (the composer stops receiving characters). Disabling
+        // can-focus on both keeps the cursor in the composer while
         // still showing a selection highlight.
         list.set_can_focus(false);
 
@@ -115,10 +115,10 @@ impl MentionPopover {
             .child(&scrolled)
             .build();
         popover.set_can_focus(false);
-        popover.set_parent(entry);
+        popover.set_parent(composer);
         popover.set_width_request(360);
-        // Anchor the popover near the entry's left edge instead
-        // of the default centre — when the entry is wide the
+        // Anchor the popover near the composer's left edge instead
+        // of the default centre — when the composer is wide the
         // popover otherwise floats far to the right of the `@`
         // the user just typed (visible in the bug screenshot).
         popover.set_pointing_to(Some(&gtk::gdk::Rectangle::new(0, 0, 1, 1)));
@@ -126,7 +126,7 @@ impl MentionPopover {
         let inner = Rc::new(Inner {
             popover,
             list,
-            entry: entry.clone(),
+            composer: composer.clone(),
             avatars,
             candidates: RefCell::new(Vec::new()),
             sender,
@@ -134,13 +134,19 @@ impl MentionPopover {
             inhibit: RefCell::new(false),
         });
 
-        wire_entry(&inner);
+        wire_composer(&inner);
         wire_keys(&inner);
         wire_list_activation(&inner);
 
         Self { inner }
     }
 
+    /// True while the candidate list is showing — the composer's
+    /// Enter-to-send handler defers to the popover then.
+    pub fn is_open(&self) -> bool {
+        self.inner.popover.is_visible()
+    }
+
     /// Replace the candidate list (the popover repaints on the
     /// next text-change tick). Called when a fresh
     /// `MentionCandidatesLoaded` arrives from the worker.
@@ -153,29 +159,27 @@ impl MentionPopover {
     }
 }
 
-fn wire_entry(inner: &Rc<Inner>) {
-    let buffer = inner.entry.buffer();
+fn wire_composer(inner: &Rc<Inner>) {
+    let buffer = inner.composer.buffer();
     {
         let inner = inner.clone();
-        buffer.connect_notify_local(Some("text"), move |_, _| {
+        buffer.connect_changed(move |_| {
             inner.update_completion();
         });
     }
     {
-        let inner_for_closure = inner.clone();
-        inner
-            .entry
-            .connect_notify_local(Some("cursor-position"), move |_, _| {
-                inner_for_closure.update_completion();
-            });
+        let inner = inner.clone();
+        buffer.connect_notify_local(Some("cursor-position"), move |_, _| {
+            inner.update_completion();
+        });
     }
-    // Hide the popover when the entry loses focus — without this,
+    // Hide the popover when the composer loses focus — without this,
     // the popover is visually orphaned when the user clicks into
     // another widget (its `autohide=false` keeps it open).
     {
         let inner_for_closure = inner.clone();
-        inner.entry.connect_has_focus_notify(move |entry| {
-            if !entry.has_focus() {
+        inner.composer.connect_has_focus_notify(move |composer| {
+            if !composer.has_focus() {
                 inner_for_closure.popover.popdown();
             }
         });
@@ -189,7 +193,7 @@ fn wire_keys(inner: &Rc<Inner>) {
         let inner = inner.clone();
         key_ctl.connect_key_pressed(move |_, key, _, modifier| {
             // Bail on any non-trivial modifier — we don't want to
-            // intercept Ctrl-Enter (newline) or Shift-Tab.
+            // intercept Shift-Enter (newline) or Shift-Tab.
             if modifier
                 .difference(gtk::gdk::ModifierType::LOCK_MASK)
                 .bits()
@@ -225,7 +229,7 @@ fn wire_keys(inner: &Rc<Inner>) {
             }
         });
     }
-    inner.entry.add_controller(key_ctl);
+    inner.composer.add_controller(key_ctl);
 }
 
 fn wire_list_activation(inner: &Rc<Inner>) {
@@ -246,8 +250,10 @@ impl Inner {
     /// Re-derive `current_word`, filter candidates, repopulate the
     /// list, and pop the popover up/down accordingly.
     fn update_completion(self: &Rc<Self>) {
-        let text = self.entry.text().to_string();
-        let cursor = self.entry.position();
+        let buffer = self.composer.buffer();
+        let (start, end) = buffer.bounds();
+        let text = buffer.text(&start, &end, false).to_string();
+        let cursor = buffer.cursor_position();
         let cursor_byte = char_offset_to_byte(&text, cursor as usize);
 
         let word = find_at_word(&text, cursor_byte);
@@ -282,11 +288,11 @@ impl Inner {
         // otherwise the previous selection might be stale (out of
         // bounds or pointing at a row that was filtered out). We
         // deliberately do NOT call `grab_focus` on the row: that
-        // would move keyboard focus off the entry, and any
+        // would move keyboard focus off the composer, and any
         // subsequent characters the user types would land on the
         // ListBox (or be silently dropped) instead of being
         // inserted into the composer. The key controller on the
-        // entry already handles ↑/↓/Enter/Tab/Esc while the entry
+        // composer already handles ↑/↓/Enter/Tab/Esc while it
         // keeps focus.
         if let Some(first) = self.list.row_at_index(0) {
             self.list.select_row(Some(&first));
@@ -322,7 +328,7 @@ impl Inner {
             new_idx = count - 1;
         }
         // Just select — don't grab focus (see the comment in
-        // `update_completion`: focus must stay on the entry so
+        // `update_completion`: focus must stay on the composer so
         // the user can keep typing).
         if let Some(row) = self.list.row_at_index(new_idx) {
             self.list.select_row(Some(&row));
@@ -360,8 +366,10 @@ impl Inner {
         };
         let digits = jid.split('@').next().unwrap_or(jid).to_string();
 
-        // Splice `@<digits> ` over `[start..end]` in the entry text.
-        let text = self.entry.text().to_string();
+        // Splice `@<digits> ` over `[start..end]` in the composer text.
+        let buffer = self.composer.buffer();
+        let (buf_start, buf_end) = buffer.bounds();
+        let text = buffer.text(&buf_start, &buf_end, false).to_string();
         let mut new_text = String::with_capacity(text.len());
         new_text.push_str(&text[..start]);
         new_text.push('@');
@@ -370,12 +378,12 @@ impl Inner {
         new_text.push_str(&text[end..]);
 
         // Drive the buffer + cursor in one atomic-ish update so
-        // the next `notify::text` sees the post-replace state and
+        // the next `changed` sees the post-replace state and
         // doesn't reopen the popover for the same word.
-        self.entry.set_text(&new_text);
+        buffer.set_text(&new_text);
         let new_cursor_bytes = start + 1 + digits.len() + 1;
         let new_cursor_chars = byte_offset_to_char(&new_text, new_cursor_bytes);
-        self.entry.set_position(new_cursor_chars as i32);
+        buffer.place_cursor(&buffer.iter_at_offset(new_cursor_chars as i32));
         self.popover.popdown();
         *self.current_word.borrow_mut() = None;
 
@@ -613,6 +621,13 @@ mod tests {
         assert_eq!(&text[got.0 + 1..got.1], "al");
     }
 
+    #[test]
+    fn find_word_at_start_of_a_new_line() {
+        let text = "oi\n@al";
+        let got = find_at_word(text, text.len()).unwrap();
+        assert_eq!(got, (3, 6));
+    }
+
     #[test]
     fn find_word_rejects_email_like() {
         assert!(find_at_word("user@host", 9).is_none());
@@ -173,6 +173,22 @@ pub fn build_media_summary(row: &MessageRow) -> String {
     parts.join(" · ")
 }
 
+/// Name colors for group participants. Mid-tone GNOME palette shades,
+/// legible on both the light and the dark bubble background.
+const SENDER_COLORS: [&str; 8] = [
+    "#3584e4", "#26a269", "#c88800", "#e66100", "#e01b24", "#9141ac", "#986a44", "#2190a4",
+];
+
+/// Color of a participant's name, picked from the JID so the same
+/// person keeps it across chats and restarts. FNV-1a rather than
+/// `DefaultHasher`, whose output isn't promised to stay put.
+pub fn sender_color(jid: &str) -> &'static str {
+    let hash = jid.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    SENDER_COLORS[(hash % SENDER_COLORS.len() as u64) as usize]
+}
+
 pub fn format_size(bytes: i64) -> String {
     if bytes < 1024 {
         format!("{bytes} B")
@@ -219,4 +235,14 @@ mod tests {
         let out = apply_mentions_pango_resolved("plain @123 text", &[]);
         assert_eq!(out, "plain @123 text");
     }
+
+    #[test]
+    fn sender_color_is_stable_and_spread() {
+        let jid = "5511999999999@s.whatsapp.net";
+        assert_eq!(sender_color(jid), sender_color(jid));
+        let used: std::collections::HashSet<_> = (0..32)
+            .map(|i| sender_color(&format!("55119999900{i:02}@s.whatsapp.net")))
+            .collect();
+        assert!(used.len() > SENDER_COLORS.len() / 2, "only {used:?}");
+    }
 }
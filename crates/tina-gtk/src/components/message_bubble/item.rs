@@ -4,11 +4,12 @@
 
 use adw::prelude::*;
 use crate::fl;
+use tina_core::{MessageContent, MessageSender, MessageType};
 use tina_db::MessageRow;
 
 use crate::time::format_message_time;
 
-use super::format::{build_media_summary, glib_markup_escape};
+use super::format::{build_media_summary, glib_markup_escape, sender_color};
 
 #[derive(Debug, Clone)]
 pub struct MessageItem {
@@ -17,6 +18,10 @@ pub struct MessageItem {
     pub sender_name: String,
     pub sender_jid: Option<String>,
     pub sender_avatar_path: Option<String>,
+    /// Structured sender. `from_row` leaves every group participant as
+    /// a plain member; `build_item` fills the admin flag from the
+    /// mention inventory. Drives the name color and the admin badge.
+    pub sender: MessageSender,
     /// Chat kind (`dm`, `group`, `newsletter`, …) — used by the
     /// header to swap "Unknown" for the channel's own name when the
     /// row sits inside a newsletter (every post comes from the
@@ -82,6 +87,9 @@ pub struct MessageItem {
     /// optimistic echoes start at `pending` and flip as receipts
     /// arrive.
     pub delivery_status: String,
+    /// The sender edited the message after sending; the header shows
+    /// an "edited" tag next to the time.
+    pub edited: bool,
     /// Inline preview (JPEG/PNG bytes) for image/video/sticker/document.
     /// Rendered as a `gtk::Picture` placeholder while the user hasn't
     /// triggered the full download yet — much nicer than the generic
@@ -115,11 +123,15 @@ pub struct MessageItem {
 
 impl MessageItem {
     pub fn from_row(row: &MessageRow, is_collapsed: bool) -> Self {
-        let content = row.content.clone().unwrap_or_default();
-        let display = if content.is_empty() {
-            format!("[{}]", row.message_type)
-        } else {
-            content
+        let parsed = tina_core::parse_db_message(
+            &row.message_type,
+            row.content.as_deref(),
+            row.raw_json.as_deref(),
+        );
+        let display = match parsed {
+            MessageContent::Text(t) if t.is_empty() => format!("[{}]", row.message_type),
+            MessageContent::Text(t) => t,
+            structured => structured_text(&structured),
         };
         let mut item = Self {
             id: row.message_id.clone(),
@@ -129,6 +141,13 @@ impl MessageItem {
             ),
             sender_jid: row.sender_jid.clone(),
             sender_avatar_path: row.sender_avatar_path.clone(),
+            sender: tina_core::parse_db_sender(
+                &row.chat_id,
+                row.is_from_me,
+                row.sender_jid.as_deref(),
+                row.sender_name.as_deref(),
+                |_| false,
+            ),
             chat_kind: String::new(),
             chat_display_name: None,
             chat_avatar_path: None,
@@ -151,6 +170,7 @@ impl MessageItem {
             media_filename: row.media_filename.clone(),
             media_sha256: row.media_sha256.clone(),
             delivery_status: row.delivery_status.clone(),
+            edited: row.edited_at.is_some(),
             thumbnail: row.media_thumbnail.clone(),
             quoted_message_id: row.quoted_message_id.clone(),
             quoted_sender_id: row.quoted_sender_id.clone(),
@@ -247,35 +267,36 @@ impl MessageItem {
             .map(|t| t.upcast::<gtk::gdk::Paintable>())
     }
 
+    pub fn kind(&self) -> MessageType {
+        MessageType::from_raw(&self.message_type)
+    }
+
     pub fn is_media(&self) -> bool {
-        matches!(
-            self.message_type.as_str(),
-            "image" | "audio" | "video" | "sticker" | "document"
-        )
+        self.kind().is_media()
     }
 
     pub fn is_visual_media(&self) -> bool {
-        matches!(self.message_type.as_str(), "image" | "video" | "sticker")
+        self.kind().is_visual()
     }
 
     pub fn media_kind_label(&self) -> String {
-        match self.message_type.as_str() {
-            "image" => fl!("media-image"),
-            "audio" => fl!("media-voice-audio"),
-            "video" => fl!("media-video"),
-            "sticker" => fl!("media-sticker"),
-            "document" => fl!("media-document"),
+        match self.kind() {
+            MessageType::Image => fl!("media-image"),
+            MessageType::Audio => fl!("media-voice-audio"),
+            MessageType::Video => fl!("media-video"),
+            MessageType::Sticker => fl!("media-sticker"),
+            MessageType::Document => fl!("media-document"),
             _ => fl!("media-attachment"),
         }
     }
 
     pub fn placeholder_icon(&self) -> &'static str {
-        match self.message_type.as_str() {
-            "image" => "image-x-generic-symbolic",
-            "audio" => "audio-x-generic-symbolic",
-            "video" => "video-x-generic-symbolic",
-            "sticker" => "emoji-symbols-symbolic",
-            "document" => "text-x-generic-symbolic",
+        match self.kind() {
+            MessageType::Image => "image-x-generic-symbolic",
+            MessageType::Audio => "audio-x-generic-symbolic",
+            MessageType::Video => "video-x-generic-symbolic",
+            MessageType::Sticker => "emoji-symbols-symbolic",
+            MessageType::Document => "text-x-generic-symbolic",
             _ => "mail-attachment-symbolic",
         }
     }
@@ -339,10 +360,28 @@ impl MessageItem {
         // String allocations per tick per row is well below the
         // markdown converter's cost — leaving uncached is the right
         // tradeoff here.
+        let time = if self.edited {
+            format!("{} · {}", self.timestamp, fl!("message-edited"))
+        } else {
+            self.timestamp.clone()
+        };
+        let mut name = glib_markup_escape(&self.display_sender_name());
+        if let MessageSender::Participant { jid, .. } = &self.sender
+            && !jid.is_empty()
+        {
+            name = format!("<span foreground=\"{}\">{name}</span>", sender_color(jid));
+        }
+        let badge = if self.sender.is_admin() {
+            format!(
+                "  <span size=\"small\" alpha=\"70%\">{}</span>",
+                glib_markup_escape(&fl!("sender-admin"))
+            )
+        } else {
+            String::new()
+        };
         format!(
-            "<b>{}</b>  <span alpha=\"60%\" size=\"small\">{}</span>",
-            glib_markup_escape(&self.display_sender_name()),
-            glib_markup_escape(&self.timestamp),
+            "<b>{name}</b>{badge}  <span alpha=\"60%\" size=\"small\">{}</span>",
+            glib_markup_escape(&time),
         )
     }
 
@@ -350,3 +389,47 @@ impl MessageItem {
         &self.short_time
     }
 }
+
+/// WhatsApp-markdown body for location / contact / poll rows. Runs
+/// through the same `wa_markdown_to_pango` pipeline as text, so the
+/// map URL is auto-linked and `*…*` renders bold.
+fn structured_text(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Location {
+            lat,
+            lng,
+            name,
+            address,
+            live,
+        } => {
+            let title = name.clone().unwrap_or_else(|| {
+                if *live {
+                    fl!("message-live-location")
+                } else {
+                    fl!("message-location")
+                }
+            });
+            let mut out = format!("📍 *{title}*\n");
+            if let Some(address) = address {
+                out.push_str(address);
+                out.push('\n');
+            }
+            out.push_str(&format!(
+                "https://www.openstreetmap.org/?mlat={lat}&mlon={lng}#map=16/{lat}/{lng}"
+            ));
+            out
+        }
+        MessageContent::Contact { name, .. } => format!("👤 *{name}*"),
+        MessageContent::Poll {
+            question, options, ..
+        } => {
+            let mut out = format!("📊 *{question}*");
+            for option in options {
+                out.push_str("\n○ ");
+                out.push_str(option);
+            }
+            out
+        }
+        MessageContent::Text(t) => t.clone(),
+    }
+}
@@ -74,9 +74,9 @@ pub fn bind(item: &MessageRowItem, w: &mut MessageRowWidgets, root: &mut gtk::Bo
     let media_expanded = item.ui_state.get(&m.id).media_expanded;
     w.visual_media.set_visible(is_visual);
     if is_visual {
-        let kind = match m.message_type.as_str() {
-            "sticker" => super::super::message_media::MediaKind::Sticker,
-            "video" => super::super::message_media::MediaKind::Video,
+        let kind = match m.kind() {
+            tina_core::MessageType::Sticker => super::super::message_media::MediaKind::Sticker,
+            tina_core::MessageType::Video => super::super::message_media::MediaKind::Video,
             _ => super::super::message_media::MediaKind::Image,
         };
         w.visual_media.set_state(
@@ -279,6 +279,8 @@ pub fn build_root_and_widgets() -> (gtk::Box, MessageRowWidgets) {
     content_label.add_css_class("message-content");
     right_col.append(&content_label);
 
+    attach_row_menu(&message_box, &slot);
+
     let widgets = MessageRowWidgets {
         day_divider_box,
         day_divider_label,
@@ -310,6 +312,57 @@ pub fn build_root_and_widgets() -> (gtk::Box, MessageRowWidgets) {
     (root, widgets)
 }
 
+/// Right-click menu on the message body (currently just "Forward…").
+/// Capture phase + claim so the selectable content label doesn't pop
+/// its own copy menu over ours.
+fn attach_row_menu(message_box: &gtk::Box, slot: &Rc<RefCell<Option<RowContext>>>) {
+    let popover = gtk::Popover::builder()
+        .has_arrow(false)
+        .position(gtk::PositionType::Bottom)
+        .css_classes(["menu"])
+        .build();
+    let forward_label = gtk::Label::builder()
+        .label(&fl!("context-forward"))
+        .xalign(0.0)
+        .hexpand(true)
+        .build();
+    let forward_btn = gtk::Button::builder()
+        .css_classes(["flat"])
+        .child(&forward_label)
+        .build();
+    {
+        let slot_c = slot.clone();
+        let pop = popover.clone();
+        forward_btn.connect_clicked(move |_| {
+            if let Some((id, sender)) = slot_c
+                .borrow()
+                .as_ref()
+                .map(|c| (c.message_id.clone(), c.sender.clone()))
+            {
+                let _ = sender.send(ChatTabInput::ForwardRequested(id));
+            }
+            pop.popdown();
+        });
+    }
+    popover.set_child(Some(&forward_btn));
+    popover.set_parent(message_box);
+
+    let gesture = gtk::GestureClick::new();
+    gesture.set_button(gtk::gdk::BUTTON_SECONDARY);
+    gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let slot_c = slot.clone();
+    gesture.connect_pressed(move |g, _, x, y| {
+        if slot_c.borrow().is_none() {
+            return;
+        }
+        g.set_state(gtk::EventSequenceState::Claimed);
+        let rect = gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1);
+        popover.set_pointing_to(Some(&rect));
+        popover.popup();
+    });
+    message_box.add_controller(gesture);
+}
+
 /// Flip `media_expanded` for the row currently bound to this slot
 /// and ask the chat tab to rebind it (which causes the bind pass to
 /// swap visibility from compact / thumb → controls / video).
@@ -1,9 +1,13 @@
 pub mod chat_area;
 pub mod chat_row;
 pub mod chat_tab;
+pub mod contact_info_dialog;
+pub mod forward_dialog;
+pub mod join_group_dialog;
 pub mod login;
 pub mod main_page;
 pub mod mention_popover;
+pub mod new_chat_dialog;
 pub mod message_bubble;
 pub mod message_media;
 pub mod message_row;
@@ -0,0 +1,57 @@
+// "New chat" prompt. Same one-shot shape as the join-group prompt: a
+// single entry for the phone number, with Start enabled only while the
+// text normalizes to a phone JID. Reports the typed number through
+// `on_start`; whether it's on WhatsApp is the worker's call.
+
+use adw::prelude::*;
+
+use crate::fl;
+
+const ENTRY_WIDTH: i32 = 360;
+
+fn is_phone(text: &str) -> bool {
+    tina_core::normalize_recipient(text).is_some_and(|jid| {
+        matches!(
+            tina_core::WaIdentity::parse(&jid),
+            tina_core::WaIdentity::Phone(_)
+        )
+    })
+}
+
+pub fn present(anchor: &impl IsA<gtk::Widget>, on_start: impl Fn(String) + 'static) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(&fl!("new-chat-heading"))
+        .body(&fl!("new-chat-body"))
+        .close_response("cancel")
+        .default_response("start")
+        .build();
+    dialog.add_response("cancel", &fl!("send-cancel"));
+    dialog.add_response("start", &fl!("new-chat-start"));
+    dialog.set_response_appearance("start", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("start", false);
+
+    let entry = gtk::Entry::builder()
+        .placeholder_text("+55 11 99999-9999")
+        .input_purpose(gtk::InputPurpose::Phone)
+        .activates_default(true)
+        .hexpand(true)
+        .build();
+    entry.set_size_request(ENTRY_WIDTH, -1);
+
+    let weak = dialog.downgrade();
+    entry.connect_changed(move |entry| {
+        if let Some(dialog) = weak.upgrade() {
+            dialog.set_response_enabled("start", is_phone(&entry.text()));
+        }
+    });
+    dialog.set_extra_child(Some(&entry));
+
+    dialog.connect_response(None, move |dlg, response| {
+        if response == "start" {
+            on_start(entry.text().trim().to_string());
+        }
+        dlg.close();
+    });
+
+    dialog.present(Some(anchor));
+}
@@ -2,8 +2,8 @@
 // headerbar. Owns the signed-in user's identity (name/phone/avatar) so
 // the sidebar doesn't have to thread those fields through its view.
 //
-// Outputs are user intents — repair, logout — which the sidebar bubbles
-// up to the parent.
+// Outputs are user intents — preferences, joining a group, logout —
+// which the sidebar bubbles up to the parent.
 
 use adw::prelude::*;
 use crate::fl;
@@ -19,6 +19,9 @@ pub enum ProfileMenuInput {
     /// Avatar arrived for the signed-in user.
     SetAvatar(String),
     Preferences,
+    NewChat,
+    JoinGroup,
+    MarkAllRead,
     Logout,
     /// Reserved: the popover used to disable the Repair button while a
     /// reconcile was in flight. Repair now lives in the preferences
@@ -30,6 +33,9 @@ pub enum ProfileMenuInput {
 #[derive(Debug)]
 pub enum ProfileMenuOutput {
     Preferences,
+    NewChat,
+    JoinGroup,
+    MarkAllRead,
     Logout,
 }
 
@@ -167,6 +173,57 @@ impl SimpleComponent for ProfileMenu {
                         },
                     },
 
+                    gtk::Button {
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |btn| {
+                            if let Some(pop) = btn
+                                .ancestor(gtk::Popover::static_type())
+                                .and_downcast::<gtk::Popover>()
+                            {
+                                pop.popdown();
+                            }
+                            let _ = sender.input_sender().send(ProfileMenuInput::NewChat);
+                        },
+                        gtk::Label {
+                            set_label: &fl!("new-chat-menu"),
+                            set_xalign: 0.0,
+                        },
+                    },
+
+                    gtk::Button {
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |btn| {
+                            if let Some(pop) = btn
+                                .ancestor(gtk::Popover::static_type())
+                                .and_downcast::<gtk::Popover>()
+                            {
+                                pop.popdown();
+                            }
+                            let _ = sender.input_sender().send(ProfileMenuInput::JoinGroup);
+                        },
+                        gtk::Label {
+                            set_label: &fl!("join-group-menu"),
+                            set_xalign: 0.0,
+                        },
+                    },
+
+                    gtk::Button {
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |btn| {
+                            if let Some(pop) = btn
+                                .ancestor(gtk::Popover::static_type())
+                                .and_downcast::<gtk::Popover>()
+                            {
+                                pop.popdown();
+                            }
+                            let _ = sender.input_sender().send(ProfileMenuInput::MarkAllRead);
+                        },
+                        gtk::Label {
+                            set_label: &fl!("mark-all-read-menu"),
+                            set_xalign: 0.0,
+                        },
+                    },
+
                     gtk::Button {
                         add_css_class: "flat",
                         add_css_class: "destructive-action",
@@ -219,6 +276,15 @@ impl SimpleComponent for ProfileMenu {
             ProfileMenuInput::Preferences => {
                 let _ = sender.output(ProfileMenuOutput::Preferences);
             }
+            ProfileMenuInput::NewChat => {
+                let _ = sender.output(ProfileMenuOutput::NewChat);
+            }
+            ProfileMenuInput::JoinGroup => {
+                let _ = sender.output(ProfileMenuOutput::JoinGroup);
+            }
+            ProfileMenuInput::MarkAllRead => {
+                let _ = sender.output(ProfileMenuOutput::MarkAllRead);
+            }
             ProfileMenuInput::Logout => {
                 let _ = sender.output(ProfileMenuOutput::Logout);
             }
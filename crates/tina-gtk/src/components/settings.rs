@@ -1,6 +1,7 @@
 // Preferences dialog (AdwPreferencesDialog). Three pages:
-//   * General — download method + language
-//   * Storage — disk usage breakdown + Repair + clear-cache
+//   * General — account name + download method + language
+//   * Storage — disk usage breakdown + Repair + clear-cache + clear
+//     local data
 //   * About   — version + segmented memory bar
 
 use std::cell::RefCell;
@@ -126,11 +127,15 @@ pub enum SettingsInput {
     Refresh,
     SetDownloadMethod(DownloadMethod),
     SetNanachiPid(Option<u32>),
+    /// Current name of the active account, to prefill the entry.
+    SetAccountName(Option<String>),
+    RenameAccount(String),
     PickDownloadMethod(DownloadMethod),
     PickLanguage(LanguagePref),
     Repair,
     ClearMedia,
     ClearAvatars,
+    ClearLocalData,
 }
 
 #[derive(Debug)]
@@ -139,7 +144,9 @@ pub enum SettingsOutput {
     Repair,
     ClearMedia,
     ClearAvatars,
+    ClearLocalData,
     SetLanguage(String),
+    RenameAccount(String),
 }
 
 pub struct Settings {
@@ -161,6 +168,9 @@ pub struct Settings {
     // Strong refs so we can call queue_draw() from update().
     storage_bar: Option<gtk::DrawingArea>,
     memory_bar: Option<gtk::DrawingArea>,
+    // Filled from update() — a #[watch] would clobber what the user
+    // is typing on every message.
+    account_name_row: Option<adw::EntryRow>,
 }
 
 #[relm4::component(pub)]
@@ -180,6 +190,20 @@ impl SimpleComponent for Settings {
                 set_title: &fl!("settings-general"),
                 set_icon_name: Some("preferences-system-symbolic"),
 
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("settings-account"),
+                    set_description: Some(&fl!("settings-account-description")),
+
+                    #[name(account_name_entry)]
+                    adw::EntryRow {
+                        set_title: &fl!("settings-account-name"),
+                        set_show_apply_button: true,
+                        connect_apply[sender] => move |row| {
+                            sender.input(SettingsInput::RenameAccount(row.text().to_string()));
+                        },
+                    },
+                },
+
                 add = &adw::PreferencesGroup {
                     set_title: &fl!("settings-downloads"),
                     set_description: Some(&fl!("settings-downloads-description")),
@@ -316,6 +340,17 @@ impl SimpleComponent for Settings {
                             connect_clicked => SettingsInput::Repair,
                         },
                     },
+
+                    adw::ActionRow {
+                        set_title: &fl!("settings-clear-data"),
+                        set_subtitle: &fl!("settings-clear-data-subtitle"),
+                        add_suffix = &gtk::Button {
+                            set_label: &fl!("settings-clear-data-run"),
+                            set_valign: gtk::Align::Center,
+                            add_css_class: "destructive-action",
+                            connect_clicked => SettingsInput::ClearLocalData,
+                        },
+                    },
                 },
             },
 
@@ -424,6 +459,7 @@ impl SimpleComponent for Settings {
             memory_bar_data: Rc::new(RefCell::new(vec![])),
             storage_bar: None,
             memory_bar: None,
+            account_name_row: None,
         };
 
         let widgets = view_output!();
@@ -443,6 +479,7 @@ impl SimpleComponent for Settings {
             });
             model.memory_bar = Some(widgets.memory_bar_da.clone());
         }
+        model.account_name_row = Some(widgets.account_name_entry.clone());
 
         // Do NOT refresh at init — dir_size() walks the filesystem on the
         // main thread and would freeze the startup spinner. The parent calls
@@ -467,6 +504,14 @@ impl SimpleComponent for Settings {
                 self.nanachi_pid = pid;
                 self.refresh_nanachi_rss();
             }
+            SettingsInput::SetAccountName(name) => {
+                if let Some(row) = &self.account_name_row {
+                    row.set_text(name.as_deref().unwrap_or_default());
+                }
+            }
+            SettingsInput::RenameAccount(name) => {
+                let _ = sender.output(SettingsOutput::RenameAccount(name));
+            }
             SettingsInput::PickDownloadMethod(m) => {
                 if self.download_method != m {
                     self.download_method = m;
@@ -490,6 +535,9 @@ impl SimpleComponent for Settings {
             SettingsInput::ClearAvatars => {
                 let _ = sender.output(SettingsOutput::ClearAvatars);
             }
+            SettingsInput::ClearLocalData => {
+                let _ = sender.output(SettingsOutput::ClearLocalData);
+            }
         }
     }
 }
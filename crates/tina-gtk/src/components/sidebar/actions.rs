@@ -109,6 +109,7 @@ impl Sidebar {
 
     pub(super) fn handle_history_sync_ended(&mut self) {
         self.history_sync_progress = None;
+        self.history_sync_resuming = false;
         self.history_sync_type.clear();
     }
 
@@ -293,6 +294,15 @@ impl Sidebar {
             ProfileMenuOutput::Preferences => {
                 let _ = sender.output(SidebarOutput::RequestPreferences);
             }
+            ProfileMenuOutput::NewChat => {
+                let _ = sender.output(SidebarOutput::RequestNewChat);
+            }
+            ProfileMenuOutput::JoinGroup => {
+                let _ = sender.output(SidebarOutput::RequestJoinGroup);
+            }
+            ProfileMenuOutput::MarkAllRead => {
+                let _ = sender.output(SidebarOutput::RequestMarkAllRead);
+            }
             ProfileMenuOutput::Logout => {
                 let _ = sender.output(SidebarOutput::RequestLogout);
             }
@@ -307,6 +317,10 @@ impl Sidebar {
                 push_name,
             } => self.handle_set_identity(phone, jid, push_name, &sender),
             SidebarInput::ChatsUpserted(rows) => self.handle_chats_upserted(rows, &sender),
+            SidebarInput::ClearChats => {
+                self.list.clear();
+                self.status_list.clear();
+            }
             SidebarInput::SearchChanged(text) => self.handle_search_changed(text),
             SidebarInput::SetRepairing(r) => self.handle_set_repairing(r),
             SidebarInput::SetConnection(c) => self.handle_set_connection(c),
@@ -314,6 +328,13 @@ impl Sidebar {
                 sync_type,
                 progress,
             } => self.handle_history_sync_progress(sync_type, progress),
+            SidebarInput::HistorySyncResuming {
+                sync_type,
+                progress,
+            } => {
+                self.history_sync_resuming = true;
+                self.handle_history_sync_progress(sync_type, progress);
+            }
             SidebarInput::HistorySyncEnded => self.handle_history_sync_ended(),
             SidebarInput::SetChatFilter(f) => self.handle_set_chat_filter(f, &sender),
             SidebarInput::StatusAuthorsUpserted(rows) => {
@@ -345,6 +366,15 @@ impl Sidebar {
             SidebarInput::PinChatRequested { chat_id, pinned } => {
                 let _ = sender.output(SidebarOutput::SetChatPinned { chat_id, pinned });
             }
+            SidebarInput::MuteChatRequested { chat_id, muted } => {
+                let _ = sender.output(SidebarOutput::SetChatMuted { chat_id, muted });
+            }
+            SidebarInput::CopyInviteLinkRequested(chat_id) => {
+                let _ = sender.output(SidebarOutput::CopyInviteLink(chat_id));
+            }
+            SidebarInput::ContactInfoRequested(chat_id) => {
+                let _ = sender.output(SidebarOutput::ShowContactInfo(chat_id));
+            }
             SidebarInput::SetActiveChats(ids) => self.handle_set_active_chats(ids),
             SidebarInput::FromProfile(out) => self.handle_from_profile(out, &sender),
         }
@@ -227,7 +227,11 @@ impl SimpleComponent for Sidebar {
                         #[watch]
                         set_show_text: !model.repair_indeterminate && model.repair_total > 0,
                         #[watch]
-                        set_text: Some(&format!("{} / {}", model.repair_current, model.repair_total)),
+                        set_text: Some(&format!(
+                            "{}%",
+                            tina_worker::progress_percent(model.repair_current, model.repair_total)
+                                .unwrap_or(0)
+                        )),
                     },
                 },
             },
@@ -299,6 +303,7 @@ impl SimpleComponent for Sidebar {
             connection: crate::app::ConnectionStatus::Connecting,
             history_sync_progress: None,
             history_sync_type: String::new(),
+            history_sync_resuming: false,
             user_jid: None,
             avatars: init.avatars,
             chats: init.chats,
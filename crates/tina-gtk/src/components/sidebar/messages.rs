@@ -55,6 +55,7 @@ pub enum SidebarInput {
         push_name: Option<String>,
     },
     ChatsUpserted(Vec<ChatRow>),
+    ClearChats,
     StatusAuthorsUpserted(Vec<StatusAuthorRow>),
     SearchChanged(String),
     SetRepairing(bool),
@@ -65,6 +66,9 @@ pub enum SidebarInput {
     /// Sent on every chunk so the headerbar subtitle reflects the
     /// active stream when the user is already in-app.
     HistorySyncProgress { sync_type: String, progress: u32 },
+    /// Startup found a cursor from an interrupted sync. Same as
+    /// `HistorySyncProgress` but labels the subtitle "Resuming sync".
+    HistorySyncResuming { sync_type: String, progress: u32 },
     /// Sync stream wrapped up (or got pre-empted by `HistorySyncDone`).
     /// Clears the headerbar progress affordance.
     HistorySyncEnded,
@@ -103,6 +107,15 @@ pub enum SidebarInput {
         chat_id: String,
         pinned: bool,
     },
+    /// Right-click context menu picked "Mute" or "Unmute".
+    MuteChatRequested {
+        chat_id: String,
+        muted: bool,
+    },
+    /// Right-click context menu picked "Copy invite link" on a group.
+    CopyInviteLinkRequested(String),
+    /// Right-click context menu picked "Contact info" on a DM.
+    ContactInfoRequested(String),
     /// The set of chat_ids currently open as tabs in the chat area.
     /// Drives the "active" highlight + sort-to-top behaviour.
     SetActiveChats(Vec<String>),
@@ -115,6 +128,12 @@ pub enum SidebarOutput {
     OpenInCurrent(String),
     OpenInNewTab(String),
     RequestPreferences,
+    /// Profile menu's "New chat…".
+    RequestNewChat,
+    /// Profile menu's "Join group via link…".
+    RequestJoinGroup,
+    /// Profile menu's "Mark all as read".
+    RequestMarkAllRead,
     RequestLogout,
     /// User opened the Status tab — ask the worker to recompute the
     /// status authors list.
@@ -131,4 +150,10 @@ pub enum SidebarOutput {
         chat_id: String,
         pinned: bool,
     },
+    SetChatMuted {
+        chat_id: String,
+        muted: bool,
+    },
+    CopyInviteLink(String),
+    ShowContactInfo(String),
 }
@@ -62,6 +62,9 @@ pub struct Sidebar {
     /// "RECENT", …) — used so the subtitle can spell out the stage
     /// instead of an opaque percentage.
     pub(super) history_sync_type: String,
+    /// Set when startup found an interrupted sync cursor; the stream
+    /// that follows is labelled "Resuming sync" until it ends.
+    pub(super) history_sync_resuming: bool,
     pub(super) user_jid: Option<tina_core::WaIdentity>,
     pub(super) avatars: AvatarInventory,
     pub(super) chats: ChatInventory,
@@ -101,6 +104,7 @@ impl Sidebar {
             // because it's the most common path and the verbose label
             // adds noise.
             let label = match self.history_sync_type.as_str() {
+                _ if self.history_sync_resuming => fl!("sidebar-resuming-sync"),
                 "RECENT" => fl!("sidebar-catching-up"),
                 "FULL" | "ON_DEMAND" => fl!("sidebar-pulling-history"),
                 _ => fl!("sidebar-syncing"),
@@ -697,6 +697,17 @@ impl MentionInventory {
             .unwrap_or_default()
     }
 
+    /// `true` when `jid` is an admin of `chat_id` per the last loaded
+    /// candidate list. Unknown chats and plain members answer `false`,
+    /// so the badge only shows up once the list has arrived.
+    pub fn is_admin(&self, chat_id: &str, jid: &str) -> bool {
+        self.inner
+            .borrow()
+            .by_chat
+            .get(chat_id)
+            .is_some_and(|list| list.iter().any(|c| c.is_admin && c.jid == jid))
+    }
+
     /// Resolve a `@<digits>` mention to a display name. Returns
     /// `None` if the digits don't match any cached candidate, in
     /// which case the renderer keeps the raw digits.
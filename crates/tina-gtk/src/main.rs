@@ -33,42 +33,28 @@ use std::path::PathBuf;
 
 use color_eyre::eyre::Context;
 use relm4::RelmApp;
+use tina_worker::NanachiManager;
 use tracing_subscriber::EnvFilter;
 
 use crate::banner::print_banner;
 
 const APP_ID: &str = "br.com.zesmoi.Tina";
 
+/// Base of everything Tina writes (`TINA_DATA_DIR` or the platform
+/// data dir). The service's media/avatar cache paths go through here
+/// too, so one env var relocates the whole profile.
 fn tina_data_dir() -> PathBuf {
-    if let Some(dirs) = directories::ProjectDirs::from("com.br", "zesmoi", "tina") {
-        return dirs.data_dir().to_path_buf();
+    if let Some(dir) = tina_worker::data_dir() {
+        return dir;
     }
     if let Some(home) = std::env::var_os("HOME") {
+        tracing::warn!("no platform data dir, falling back to $HOME/.local/share/tina");
         return PathBuf::from(home).join(".local").join("share").join("tina");
     }
+    tracing::error!("Failed to determine data directory, falling back to current directory");
     PathBuf::from(".")
 }
 
-fn find_nanachi_dir() -> color_eyre::Result<PathBuf> {
-    let exe_path = std::env::current_exe()?;
-    let mut current = exe_path.parent();
-    while let Some(dir) = current {
-        let nanachi = dir.join("nanachi");
-        if nanachi.join("go.mod").exists() {
-            return Ok(nanachi);
-        }
-        current = dir.parent();
-    }
-    let cwd = std::env::current_dir()?;
-    let nanachi = cwd.join("nanachi");
-    if nanachi.join("go.mod").exists() {
-        return Ok(nanachi);
-    }
-    Err(color_eyre::eyre::eyre!(
-        "Could not find nanachi directory. Run from the project root."
-    ))
-}
-
 fn main() -> color_eyre::Result<()> {
     print_banner();
 
@@ -89,7 +75,7 @@ fn main() -> color_eyre::Result<()> {
         .filter(|s| !s.is_empty());
     i18n::init(saved_locale);
 
-    let nanachi_dir = find_nanachi_dir().wrap_err("locating nanachi dir")?;
+    let nanachi_dir = NanachiManager::locate_dir(None).wrap_err("locating nanachi dir")?;
 
     let app = RelmApp::new(APP_ID);
     relm4_icons::initialize_icons(icon_names::GRESOURCE_BYTES, icon_names::RESOURCE_PREFIX);
@@ -45,6 +45,8 @@ pub enum Cmd {
         mentioned_jids: Vec<String>,
         local_id: String,
     },
+    /// Re-send a text message whose send failed, under the same ID.
+    RetrySend { message_id: String },
     /// Send a media message (image / video / audio / voice / sticker
     /// / document). `path` is read by the Go side; the worker just
     /// forwards through IPC. `caption` is honoured for image / video
@@ -75,32 +77,79 @@ pub enum Cmd {
     /// Triggered by `ChatInventory` when it sees a render miss.
     RefreshChat { chat_jid: WaIdentity },
     /// Lazy-load older messages (page back). The UI passes the timestamp
-    /// of its currently-oldest row; the worker returns the next batch
-    /// strictly older than that.
+    /// and ID of its currently-oldest row; the worker returns the next
+    /// batch strictly older than that, same-second rows included. One
+    /// `TinaWorker::message_page_size` per request.
     LoadOlder {
         chat_id: String,
         before_ts: i64,
-        limit: i64,
+        before_id: Option<String>,
     },
     /// Lazy-load newer messages (page forward). Symmetric counterpart
-    /// to `LoadOlder`: the UI passes the timestamp of its currently-
-    /// newest row, and the worker returns the next batch strictly
-    /// newer than that. Triggered when the user scrolls past the
-    /// factory's last row after the soft-cap trimmed the tail.
+    /// to `LoadOlder`: the UI passes the timestamp and ID of its
+    /// currently-newest row, and the worker returns the next batch
+    /// strictly newer than that, same-second rows included. Triggered
+    /// when the user scrolls past the factory's last row after the
+    /// soft-cap trimmed the tail.
     LoadNewer {
         chat_id: String,
         after_ts: i64,
-        limit: i64,
+        after_id: Option<String>,
     },
     /// Persist a chat's pinned flag. After the DB write the UI will see
     /// the change on the next `LoadChats` / reconcile push.
     SetChatPinned { chat_id: String, pinned: bool },
+    /// Local mute until the unix timestamp `until`; `None` unmutes.
+    /// Answered with a fresh `ChatsUpserted` like `SetChatPinned`.
+    MuteChat { chat_id: String, until: Option<i64> },
+    /// Ask nanachi for a group's invite link; answered with
+    /// `AppMsg::InviteLinkReady` or `WorkerFailed`.
+    GetGroupInviteLink { chat_id: String },
+    /// Fetch a DM contact's profile (name, status, picture URL) for
+    /// the contact-info dialog (plus the groups in common); answered
+    /// with `AppMsg::ProfileLoaded`
+    /// or `WorkerFailed`. The worker rate-limits repeated fetches.
+    FetchProfile { chat_id: String },
+    /// Check a typed phone number with WhatsApp and open a chat with
+    /// it when registered; otherwise answered with
+    /// `AppMsg::NotOnWhatsApp`. Failures come back as `WorkerFailed`.
+    StartChat { phone: String },
+    /// Join a group through a `chat.whatsapp.com` link; answered with
+    /// `AppMsg::GroupJoined` or `WorkerFailed`. The new chat row
+    /// arrives through the worker's `ChatsUpserted`.
+    JoinGroup { link: String },
+    /// Clear every unread badge of the active account. `send_receipts`
+    /// also tells the senders; `false` only changes the local state.
+    /// Answered with `AppMsg::AllChatsRead` and a fresh `ChatsUpserted`.
+    MarkAllRead { send_receipts: bool },
+    /// Fetch the chat list for the forward picker; answered with
+    /// `AppMsg::ShowForwardDialog`.
+    LoadForwardTargets { from_chat: String, message_id: String },
+    /// Forward a stored message to the picked chats. The worker waits
+    /// for nanachi's per-recipient results and writes the local
+    /// copies; the tally lands as `AppMsg::Forwarded`.
+    ForwardMessage {
+        from_chat: String,
+        message_id: String,
+        to: Vec<String>,
+    },
     /// Logout the active account.
     Logout,
+    /// Stop the active account's history sync; batches already stored
+    /// stay.
+    CancelSync,
+    /// Ask nanachi for a fresh pairing QR for the active account.
+    RefreshQr,
+    /// `start_account` again for the active account, after the worker
+    /// gave up on the previous attempt.
+    RetryConnect,
     /// Read the persisted download method + current nanachi PID and
     /// push them up as `AppMsg`s for the settings dialog to display.
     /// Called when the user opens the preferences pane.
     LoadPreferences,
+    /// Store a new display name for the active account; answered with
+    /// `AppMsg::AccountRenamed` or `WorkerFailed`.
+    RenameAccount { name: String },
     /// Persist the user's download-method preference (settings dialog).
     /// The worker writes it to the `settings` table; consumers read on
     /// demand via `worker.get_setting`.
@@ -122,13 +171,23 @@ pub enum Cmd {
         sender_jid: String,
         message_ids: Vec<String>,
     },
+    /// Publish the composer's typing state for a chat. The tab
+    /// already debounces; this is a straight IPC passthrough.
+    SetTyping { chat_id: String, is_typing: bool },
     /// Wipe the on-disk avatar cache + null out `chats.avatar_path`,
     /// `contacts.avatar_path`. Avatars re-fetch on next render.
     ClearAvatarCache,
+    /// Wipe the active account's messages, chats, contacts and groups,
+    /// keeping the account and pairing. Answered with
+    /// `AppMsg::LocalDataCleared` or `WorkerFailed`.
+    ClearHistory,
     /// Resolve the `@`-mention picker candidates for a chat. Fired
     /// when a tab opens (groups only — DMs return an empty list).
     /// Result lands as `AppMsg::MentionCandidatesLoaded`.
     LoadMentionCandidates { chat_id: String },
+    /// `gio::NetworkMonitor` reported a change; the worker restarts
+    /// the accounts that were up once a network is available again.
+    NetworkChanged { online: bool },
     /// Shut down the worker thread.
     Shutdown,
 }
@@ -27,6 +27,9 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
         WorkerEvent::QrCode { qr, .. } => {
             let _ = app.send(AppMsg::QrCode(qr));
         }
+        WorkerEvent::QrExpired { .. } => {
+            let _ = app.send(AppMsg::QrExpired);
+        }
         WorkerEvent::Connected {
             account_id,
             phone_number,
@@ -50,6 +53,16 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
         WorkerEvent::ChatsUpserted { rows, messages_written, .. } => {
             let _ = app.send(AppMsg::ChatsUpserted { rows, messages_written });
         }
+        // Names already ride in on the same flush's ChatsUpserted.
+        WorkerEvent::ContactsSynced { .. } => {}
+        // Same for group metadata.
+        WorkerEvent::GroupsSynced { .. } => {}
+        // HistorySyncProgress already drives the syncing scene.
+        WorkerEvent::HistoryChunkApplied { .. } => {}
+        // Connected / Disconnected / LoggedOut already drive the scene.
+        WorkerEvent::ConnectionStateChanged { .. } => {}
+        // Same for the restarts it announces.
+        WorkerEvent::NetworkChanged { .. } => {}
         WorkerEvent::StatusAuthorsUpserted { rows, .. } => {
             let _ = app.send(AppMsg::StatusAuthorsUpserted(rows));
         }
@@ -68,6 +81,10 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
             let _ = app.send(AppMsg::HistorySyncDone);
             let _ = app.send(AppMsg::RepairEnded);
         }
+        WorkerEvent::InitialSyncComplete { .. } => {
+            info!("initial sync done");
+            let _ = app.send(AppMsg::InitialSyncDone);
+        }
         WorkerEvent::HistorySyncProgress {
             sync_type,
             progress,
@@ -95,6 +112,10 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
                 indeterminate,
             });
         }
+        WorkerEvent::ConnectTimedOut { account_id, .. } => {
+            tracing::warn!(%account_id, "connect attempt timed out");
+            let _ = app.send(AppMsg::ConnectTimedOut);
+        }
         WorkerEvent::Error { error, .. } => {
             error!(%error, "worker error");
             let _ = app.send(AppMsg::Toast(error));
@@ -107,6 +128,21 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
         } => {
             let _ = app.send(AppMsg::ReceiptUpdate { message_ids, status });
         }
+        WorkerEvent::MessageEdited {
+            chat_id,
+            message_id,
+            new_content,
+            ..
+        } => {
+            let _ = app.send(AppMsg::MessageEdited {
+                chat_id,
+                message_id,
+                new_content,
+            });
+        }
+        WorkerEvent::ChatCleared { chat_id, kept, .. } => {
+            let _ = app.send(AppMsg::ChatCleared { chat_id, kept });
+        }
         WorkerEvent::MediaDownloadProgress {
             message_id,
             current,
@@ -136,6 +172,18 @@ fn forward_one(app: &Sender<AppMsg>, event: WorkerEvent) {
         } => {
             let _ = app.send(AppMsg::MediaDownloadFailed { message_id, error });
         }
+        WorkerEvent::MessageSendFailed {
+            message_id,
+            content,
+            error,
+            ..
+        } => {
+            let _ = app.send(AppMsg::MessageSendFailed {
+                message_id,
+                content,
+                error,
+            });
+        }
         WorkerEvent::AvatarReady { jid, path, .. } => {
             let _ = app.send(AppMsg::AvatarReady { jid, path });
         }
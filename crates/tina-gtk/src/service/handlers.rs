@@ -11,9 +11,9 @@
 use std::sync::Arc;
 
 use relm4::Sender;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
-use tina_worker::TinaWorker;
+use tina_worker::{IpcError, TinaWorker, WorkerError};
 
 use crate::app::AppMsg;
 
@@ -28,7 +28,7 @@ pub(super) async fn initialize(
     worker: &Arc<TinaWorker>,
     app: &Sender<AppMsg>,
     state: &SharedState,
-) -> color_eyre::Result<()> {
+) -> tina_worker::Result<()> {
     let mut accounts = worker.list_accounts().await?;
     let account = if let Some(first) = accounts.drain(..).next() {
         first
@@ -55,6 +55,20 @@ pub(super) async fn initialize(
         if let Ok(rows) = worker.list_chat_rows(&account.id).await {
             let _ = app.send(AppMsg::ChatsUpserted { rows, messages_written: 0 });
         }
+        // Previous session died mid-sync: surface "resuming" right
+        // away instead of waiting for whatsmeow's first chunk.
+        if let Ok(Some(cursor)) = worker.get_sync_cursor(&account.id).await {
+            info!(
+                account_id = %account.id,
+                sync_type = %cursor.sync_type,
+                progress = cursor.progress,
+                "[sync] interrupted history sync found — resuming",
+            );
+            let _ = app.send(AppMsg::HistorySyncResuming {
+                sync_type: cursor.sync_type,
+                progress: cursor.progress,
+            });
+        }
     } else {
         info!(
             account_id = %account.id,
@@ -75,8 +89,11 @@ pub(super) async fn handle(
 ) -> bool {
     match cmd {
         Cmd::Initialize => {
-            if let Err(e) = initialize(worker, app, state).await {
-                let _ = app.send(AppMsg::FatalError(format!("initialize: {e}")));
+            if let Err(error) = initialize(worker, app, state).await {
+                let _ = app.send(AppMsg::WorkerFailed {
+                    context: "initialize",
+                    error,
+                });
             }
         }
         Cmd::LoadChats => load_chats(worker, app, state).await,
@@ -96,6 +113,7 @@ pub(super) async fn handle(
             mentioned_jids,
             local_id,
         } => send_text(worker, app, state, chat_id, text, mentioned_jids, local_id).await,
+        Cmd::RetrySend { message_id } => retry_send(worker, app, state, message_id).await,
         Cmd::SendMedia {
             chat_id,
             kind,
@@ -114,13 +132,13 @@ pub(super) async fn handle(
         Cmd::LoadOlder {
             chat_id,
             before_ts,
-            limit,
-        } => load_older(worker, app, state, chat_id, before_ts, limit).await,
+            before_id,
+        } => load_older(worker, app, state, chat_id, before_ts, before_id).await,
         Cmd::LoadNewer {
             chat_id,
             after_ts,
-            limit,
-        } => load_newer(worker, app, state, chat_id, after_ts, limit).await,
+            after_id,
+        } => load_newer(worker, app, state, chat_id, after_ts, after_id).await,
         Cmd::FetchAvatar { jid } => fetch_avatar(worker, state, jid).await,
         Cmd::FetchAvatarFromURL { jid, url } => {
             fetch_avatar_from_url(worker, state, jid, url).await
@@ -130,8 +148,31 @@ pub(super) async fn handle(
         Cmd::SetChatPinned { chat_id, pinned } => {
             set_chat_pinned(worker, app, state, chat_id, pinned).await
         }
-        Cmd::Logout => logout(worker, state).await,
-        Cmd::LoadPreferences => load_preferences(worker, app).await,
+        Cmd::MuteChat { chat_id, until } => mute_chat(worker, app, state, chat_id, until).await,
+        Cmd::GetGroupInviteLink { chat_id } => {
+            get_group_invite_link(worker, app, state, chat_id).await
+        }
+        Cmd::FetchProfile { chat_id } => fetch_profile(worker, app, state, chat_id).await,
+        Cmd::StartChat { phone } => start_chat(worker, app, state, phone).await,
+        Cmd::JoinGroup { link } => join_group(worker, app, state, link).await,
+        Cmd::MarkAllRead { send_receipts } => {
+            mark_all_read(worker, app, state, send_receipts).await
+        }
+        Cmd::LoadForwardTargets {
+            from_chat,
+            message_id,
+        } => load_forward_targets(worker, app, state, from_chat, message_id).await,
+        Cmd::ForwardMessage {
+            from_chat,
+            message_id,
+            to,
+        } => forward_message(worker, app, state, from_chat, message_id, to).await,
+        Cmd::Logout => logout(worker, app, state).await,
+        Cmd::CancelSync => cancel_sync(worker, app, state).await,
+        Cmd::RefreshQr => refresh_qr(worker, app, state).await,
+        Cmd::RetryConnect => retry_connect(worker, app, state).await,
+        Cmd::LoadPreferences => load_preferences(worker, app, state).await,
+        Cmd::RenameAccount { name } => rename_account(worker, app, state, name).await,
         Cmd::SetDownloadMethod(m) => set_download_method(worker, m).await,
         Cmd::ClearMediaCache => clear_media_cache(worker, app).await,
         Cmd::LoadStickers { chat_id, limit } => {
@@ -142,10 +183,15 @@ pub(super) async fn handle(
             sender_jid,
             message_ids,
         } => mark_chat_read(worker, state, chat_id, sender_jid, message_ids).await,
+        Cmd::SetTyping { chat_id, is_typing } => {
+            set_typing(worker, state, chat_id, is_typing).await
+        }
         Cmd::ClearAvatarCache => clear_avatar_cache(worker, app).await,
+        Cmd::ClearHistory => clear_history(worker, app, state).await,
         Cmd::LoadMentionCandidates { chat_id } => {
             load_mention_candidates(worker, app, state, chat_id).await
         }
+        Cmd::NetworkChanged { online } => worker.notify_network_changed(online).await,
         Cmd::Shutdown => return false,
     }
     true
@@ -214,6 +260,21 @@ async fn mark_chat_read(
     let _ = worker.clear_chat_unread(&account_id, &chat_id).await;
 }
 
+async fn set_typing(
+    worker: &Arc<TinaWorker>,
+    state: &SharedState,
+    chat_id: String,
+    is_typing: bool,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    // Presence is best-effort — a dropped indicator isn't worth a toast.
+    if let Err(e) = worker.set_typing(&account_id, &chat_id, is_typing).await {
+        debug!("set_typing: {e}");
+    }
+}
+
 async fn load_stickers(
     worker: &Arc<TinaWorker>,
     app: &Sender<AppMsg>,
@@ -298,13 +359,20 @@ async fn open_chat(
         .as_ref()
         .map(|r| (r.name.clone(), r.kind.clone()))
         .unwrap_or_else(|| (id.clone(), "unknown".into()));
-    // Initial page is small (50). The chat tab will lazy-load older
-    // batches as the user scrolls up; keeping the first paint cheap
-    // matters more than guaranteeing the whole history is in memory.
+    // Initial page is one `message_page_size` (50 by default). The
+    // chat tab will lazy-load older batches as the user scrolls up;
+    // keeping the first paint cheap matters more than guaranteeing the
+    // whole history is in memory.
     let messages = worker
-        .get_message_rows(&account_id, &id, 50, 0)
+        .get_message_rows(&account_id, &id, worker.message_page_size(), 0)
         .await
         .unwrap_or_default();
+    // Lets the tab know up front whether older history exists, so
+    // "load earlier" isn't offered for a chat that fits in one page.
+    let total_messages = worker
+        .get_chat_message_count(&account_id, &id)
+        .await
+        .unwrap_or(i64::MAX);
     // Drop the unread badge — the user's looking at the messages now.
     // We re-fetch ONLY the affected row from the DB and emit a
     // single-row upsert. Re-emitting the entire chat list (the old
@@ -320,6 +388,7 @@ async fn open_chat(
         name,
         kind,
         messages,
+        total_messages,
     });
     // Resolve mention-picker candidates in the background so the
     // composer's `@` popup has data ready by the time the user
@@ -353,22 +422,30 @@ async fn send_text(
     let Some(account_id) = active_account(state).await else {
         return;
     };
-    if let Err(e) = worker
-        .send_message(&account_id, &chat_id, &text, &mentioned_jids, &local_id)
-        .await
-    {
-        error!("send_message: {e}");
-        return;
-    }
-    // Belt-and-suspenders: wait for the dispatcher's 100ms flush window
-    // to elapse, then re-fetch the tail of the chat and re-emit
-    // MessagesAppended ourselves. ChatTab dedups by message_id, so
-    // this is a no-op when the dispatcher already routed the
-    // synthetic echo through.
+    // `send_message` espera a resposta do nanachi; fora do loop de Cmds
+    // para não travar o resto enquanto isso.
     let app = app.clone();
     let worker = worker.clone();
     tokio::spawn(async move {
-        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        match worker
+            .send_message(&account_id, &chat_id, &text, &mentioned_jids, &local_id)
+            .await
+        {
+            Ok(()) => {}
+            // Rejeição já chegou como `MessageSendFailed`; sem resposta a
+            // bolha fica pendente. Nos dois casos o echo já foi inserido.
+            Err(e @ (WorkerError::SendFailed(_) | WorkerError::Ipc(IpcError::Timeout))) => {
+                error!("send_message: {e}");
+            }
+            Err(e) => {
+                error!("send_message: {e}");
+                return;
+            }
+        }
+        // Belt-and-suspenders: re-fetch the tail of the chat and re-emit
+        // MessagesAppended ourselves. ChatTab dedups by message_id, so
+        // this is a no-op when the dispatcher already routed the
+        // synthetic echo through.
         match worker.get_message_rows(&account_id, &chat_id, 20, 0).await {
             Ok(messages) if !messages.is_empty() => {
                 let _ = app.send(AppMsg::MessagesAppended { chat_id, messages });
@@ -379,6 +456,34 @@ async fn send_text(
     });
 }
 
+async fn retry_send(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    message_id: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    let app = app.clone();
+    let worker = worker.clone();
+    tokio::spawn(async move {
+        match worker.retry_send(&account_id, &message_id).await {
+            Ok(()) => {}
+            // A rejeição do nanachi volta como outro `MessageSendFailed`.
+            Err(e @ (WorkerError::SendFailed(_) | WorkerError::Ipc(IpcError::Timeout))) => {
+                error!("retry_send: {e}");
+            }
+            Err(error) => {
+                let _ = app.send(AppMsg::WorkerFailed {
+                    context: "retry_send",
+                    error,
+                });
+            }
+        }
+    });
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn send_media(
     worker: &Arc<TinaWorker>,
@@ -433,9 +538,19 @@ async fn repair(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedSt
         return;
     };
     let _ = app.send(AppMsg::RepairStarted);
-    if let Err(e) = worker.reconcile_account(&account_id).await {
-        error!("reconcile: {e}");
+    // Reconcile re-emits whatever whatsmeow has stored; resync the
+    // contact list with the server first so that store is current.
+    // Groups already come from the server inside reconcile.
+    match worker.request_contacts_refresh(&account_id).await {
+        Ok(n) => info!(%account_id, contacts = n, "contacts refetched before repair"),
+        Err(e) => warn!(%account_id, "contacts refetch failed, repairing from cache: {e}"),
+    }
+    if let Err(error) = worker.reconcile_account(&account_id).await {
         let _ = app.send(AppMsg::RepairEnded);
+        let _ = app.send(AppMsg::WorkerFailed {
+            context: "reconcile",
+            error,
+        });
     }
 }
 
@@ -445,20 +560,27 @@ async fn load_older(
     state: &SharedState,
     chat_id: String,
     before_ts: i64,
-    limit: i64,
+    before_id: Option<String>,
 ) {
     let Some(account_id) = active_account(state).await else {
         return;
     };
+    let limit = worker.message_page_size();
     match worker
-        .get_message_rows_before(&account_id, &chat_id, before_ts, limit)
+        .get_message_rows_before(
+            &account_id,
+            &chat_id,
+            before_ts,
+            before_id.as_deref(),
+            limit,
+        )
         .await
     {
-        Ok(messages) => {
+        Ok(page) => {
             let _ = app.send(AppMsg::OlderMessagesLoaded {
                 chat_id,
-                messages,
-                reached_top: false,
+                messages: page.items,
+                reached_top: !page.has_more,
             });
         }
         Err(e) => error!("load_older: {e}"),
@@ -471,24 +593,21 @@ async fn load_newer(
     state: &SharedState,
     chat_id: String,
     after_ts: i64,
-    limit: i64,
+    after_id: Option<String>,
 ) {
     let Some(account_id) = active_account(state).await else {
         return;
     };
+    let limit = worker.message_page_size();
     match worker
-        .get_message_rows_after(&account_id, &chat_id, after_ts, limit)
+        .get_message_rows_after(&account_id, &chat_id, after_ts, after_id.as_deref(), limit)
         .await
     {
-        Ok(messages) => {
-            // The tab caps at `limit` rows per page; the worker
-            // returns at most `limit` so a short batch means we hit
-            // the actual tail. Mirror `load_older`'s `reached_top`
-            // semantic with `reached_bottom` here.
+        Ok(page) => {
             let _ = app.send(AppMsg::NewerMessagesLoaded {
                 chat_id,
-                messages,
-                reached_bottom: false,
+                messages: page.items,
+                reached_bottom: !page.has_more,
             });
         }
         Err(e) => error!("load_newer: {e}"),
@@ -577,16 +696,265 @@ async fn set_chat_pinned(
     }
 }
 
-async fn logout(worker: &Arc<TinaWorker>, state: &SharedState) {
+async fn mute_chat(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    chat_id: String,
+    until: Option<i64>,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    if let Err(e) = worker.mute_chat(&account_id, &chat_id, until).await {
+        error!("mute_chat: {e}");
+        return;
+    }
+    // Same refresh as a pin: the row's `muted` flag feeds the context
+    // menu label.
+    match worker.list_chat_rows(&account_id).await {
+        Ok(rows) => {
+            let _ = app.send(AppMsg::ChatsUpserted { rows, messages_written: 0 });
+        }
+        Err(e) => error!("list_chat_rows after mute: {e}"),
+    }
+}
+
+async fn get_group_invite_link(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    chat_id: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.get_group_invite_link(&account_id, &chat_id).await {
+        Ok(link) => {
+            let _ = app.send(AppMsg::InviteLinkReady(link));
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "get_group_invite_link",
+                error,
+            });
+        }
+    }
+}
+
+async fn fetch_profile(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    chat_id: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.fetch_profile(&account_id, &chat_id).await {
+        Ok(Some(contact)) => {
+            // Sem grupos em comum não é erro: o diálogo só omite a linha.
+            let common_groups = worker
+                .get_common_groups(&account_id, &chat_id)
+                .await
+                .unwrap_or_default();
+            let _ = app.send(AppMsg::ProfileLoaded {
+                contact,
+                common_groups,
+            });
+        }
+        Ok(None) => warn!("fetch_profile: no contact row for {chat_id}"),
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "fetch_profile",
+                error,
+            });
+        }
+    }
+}
+
+async fn start_chat(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    phone: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.check_number(&account_id, &phone).await {
+        Ok(Some(jid)) => open_chat(worker, app, state, jid).await,
+        Ok(None) => {
+            let _ = app.send(AppMsg::NotOnWhatsApp(phone));
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "check_number",
+                error,
+            });
+        }
+    }
+}
+
+async fn join_group(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    link: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.join_group(&account_id, &link).await {
+        Ok(group_jid) => {
+            let name = worker
+                .get_chat_row(&account_id, &group_jid)
+                .await
+                .ok()
+                .flatten()
+                .map_or(group_jid, |row| row.name);
+            let _ = app.send(AppMsg::GroupJoined(name));
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "join_group",
+                error,
+            });
+        }
+    }
+}
+
+async fn mark_all_read(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    send_receipts: bool,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.mark_all_read(&account_id, send_receipts).await {
+        Ok(count) => {
+            if count > 0
+                && let Ok(rows) = worker.list_chat_rows(&account_id).await
+            {
+                let _ = app.send(AppMsg::ChatsUpserted { rows, messages_written: 0 });
+            }
+            let _ = app.send(AppMsg::AllChatsRead(count));
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "mark_all_read",
+                error,
+            });
+        }
+    }
+}
+
+async fn load_forward_targets(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    from_chat: String,
+    message_id: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.list_chat_rows(&account_id).await {
+        Ok(rows) => {
+            let _ = app.send(AppMsg::ShowForwardDialog {
+                from_chat,
+                message_id,
+                rows,
+            });
+        }
+        Err(e) => error!("list_chat_rows for forward: {e}"),
+    }
+}
+
+async fn forward_message(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    from_chat: String,
+    message_id: String,
+    to: Vec<String>,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker
+        .forward_message(&account_id, &from_chat, &message_id, &to)
+        .await
+    {
+        Ok(outcomes) => {
+            for o in &outcomes {
+                if let Err(e) = &o.result {
+                    warn!(to = %o.to, "forward failed: {e}");
+                }
+            }
+            let sent = outcomes.iter().filter(|o| o.result.is_ok()).count();
+            let _ = app.send(AppMsg::Forwarded {
+                sent,
+                failed: outcomes.len() - sent,
+            });
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "forward_message",
+                error,
+            });
+        }
+    }
+}
+
+async fn logout(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
     if let Some(account_id) = active_account(state).await {
         worker.clear_open_chats(&account_id).await;
-        if let Err(e) = worker.logout_account(&account_id).await {
-            error!("logout: {e}");
+        if let Err(error) = worker.logout(&account_id, false).await {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "logout",
+                error,
+            });
         }
     }
 }
 
-async fn load_preferences(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>) {
+async fn cancel_sync(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
+    if let Some(account_id) = active_account(state).await
+        && let Err(error) = worker.cancel_sync(&account_id).await
+    {
+        let _ = app.send(AppMsg::WorkerFailed {
+            context: "cancel_sync",
+            error,
+        });
+    }
+}
+
+async fn refresh_qr(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
+    if let Some(account_id) = active_account(state).await
+        && let Err(error) = worker.refresh_qr(&account_id).await
+    {
+        let _ = app.send(AppMsg::WorkerFailed {
+            context: "refresh_qr",
+            error,
+        });
+    }
+}
+
+async fn retry_connect(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
+    if let Some(account_id) = active_account(state).await
+        && let Err(error) = worker.start_account(&account_id).await
+    {
+        let _ = app.send(AppMsg::WorkerFailed {
+            context: "retry_connect",
+            error,
+        });
+    }
+}
+
+async fn load_preferences(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
     use crate::components::settings::DownloadMethod;
     let method = worker
         .get_setting(DownloadMethod::KEY)
@@ -596,7 +964,41 @@ async fn load_preferences(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>) {
         .map(|s| DownloadMethod::from_str(&s))
         .unwrap_or(DownloadMethod::OnDemand);
     let pid = worker.nanachi_pid().await;
-    let _ = app.send(AppMsg::PreferencesLoaded { method, pid });
+    let account_name = match active_account(state).await {
+        Some(account_id) => worker
+            .get_account(&account_id)
+            .await
+            .ok()
+            .and_then(|a| a.name),
+        None => None,
+    };
+    let _ = app.send(AppMsg::PreferencesLoaded {
+        method,
+        pid,
+        account_name,
+    });
+}
+
+async fn rename_account(
+    worker: &Arc<TinaWorker>,
+    app: &Sender<AppMsg>,
+    state: &SharedState,
+    name: String,
+) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.rename_account(&account_id, &name).await {
+        Ok(account) => {
+            let _ = app.send(AppMsg::AccountRenamed(account.name));
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "rename_account",
+                error,
+            });
+        }
+    }
 }
 
 async fn set_download_method(
@@ -635,20 +1037,8 @@ fn rm_files_in(path: &std::path::Path) -> std::io::Result<u64> {
     Ok(count)
 }
 
-fn data_dir() -> std::path::PathBuf {
-    if let Some(dirs) = directories::ProjectDirs::from("com.br", "zesmoi", "tina") {
-        dirs.data_dir().to_path_buf()
-    } else if let Some(home) = std::env::var_os("HOME") {
-        warn!("directories::ProjectDirs failed, falling back to $HOME/.local/share/tina");
-        std::path::PathBuf::from(home).join(".local/share/tina")
-    } else {
-        error!("Failed to determine data directory, falling back to current directory");
-        std::path::PathBuf::from(".")
-    }
-}
-
 async fn clear_media_cache(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>) {
-    let path = data_dir().join("media");
+    let path = crate::tina_data_dir().join("media");
     let n = match rm_files_in(&path) {
         Ok(n) => n,
         Err(e) => {
@@ -663,8 +1053,25 @@ async fn clear_media_cache(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>) {
     let _ = app.send(AppMsg::Toast(format!("Cleared {n} media file(s)")));
 }
 
+async fn clear_history(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>, state: &SharedState) {
+    let Some(account_id) = active_account(state).await else {
+        return;
+    };
+    match worker.clear_history(&account_id).await {
+        Ok(()) => {
+            let _ = app.send(AppMsg::LocalDataCleared);
+        }
+        Err(error) => {
+            let _ = app.send(AppMsg::WorkerFailed {
+                context: "clear_history",
+                error,
+            });
+        }
+    }
+}
+
 async fn clear_avatar_cache(worker: &Arc<TinaWorker>, app: &Sender<AppMsg>) {
-    let path = data_dir().join("avatars");
+    let path = crate::tina_data_dir().join("avatars");
     let n = match rm_files_in(&path) {
         Ok(n) => n,
         Err(e) => {
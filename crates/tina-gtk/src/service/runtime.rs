@@ -21,7 +21,7 @@ use super::state::ServiceState;
 
 pub struct ServiceWorker {
     pub handle: ServiceHandle,
-    _thread: JoinHandle<()>,
+    thread: Option<JoinHandle<()>>,
 }
 
 impl ServiceWorker {
@@ -41,13 +41,25 @@ impl ServiceWorker {
             .expect("spawn service thread");
         Self {
             handle: ServiceHandle { tx },
-            _thread: thread,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sends `Cmd::Shutdown` and waits for the thread, which runs
+    /// `worker.stop()` on its way out. Idempotent.
+    pub fn shutdown(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        self.handle.send(Cmd::Shutdown);
+        if thread.join().is_err() {
+            tracing::error!("service thread panicked");
         }
     }
 }
 
 async fn run(nanachi_dir: PathBuf, mut rx: mpsc::UnboundedReceiver<Cmd>, app: Sender<AppMsg>) {
-    let mut worker = match TinaWorker::new(nanachi_dir).await {
+    let mut worker = match TinaWorker::new(nanachi_dir, None).await {
         Ok(w) => w,
         Err(e) => {
             let _ = app.send(AppMsg::FatalError(format!("worker init: {e}")));
@@ -1,22 +1,12 @@
 // Timestamp formatting helpers, ported from the Slint frontend so display
 // matches between the two during the cutover.
-use chrono::{DateTime, Datelike, Local, Utc};
+use chrono::{Datelike, Local};
 use crate::fl;
 
+/// Chat-list / status-row label, the cascade every frontend shares
+/// (`tina_core::format_relative_timestamp`).
 pub fn format_chat_timestamp(timestamp: i64) -> String {
-    if timestamp <= 0 {
-        return String::new();
-    }
-    let dt = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-    let local: DateTime<Local> = dt.into();
-    let now = Local::now();
-    if local.date_naive() == now.date_naive() {
-        local.format("%H:%M").to_string()
-    } else if local.year() == now.year() {
-        local.format("%d/%m").to_string()
-    } else {
-        local.format("%d/%m/%y").to_string()
-    }
+    tina_core::format_relative_timestamp(timestamp, Local::now())
 }
 
 /// `HH:MM` only — used by collapsed rows' hover-timestamp gutter,
@@ -24,11 +14,9 @@ pub fn format_chat_timestamp(timestamp: i64) -> String {
 /// for that gutter (`04/05 22:20` doesn't fit), and the date is
 /// already conveyed by the day-divider pill above the run.
 pub fn format_short_time(timestamp: i64) -> String {
-    if timestamp <= 0 {
+    let Some(local) = tina_core::datetime_local(timestamp) else {
         return String::new();
-    }
-    let dt = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-    let local: DateTime<Local> = dt.into();
+    };
     local.format("%H:%M").to_string()
 }
 
@@ -37,11 +25,9 @@ pub fn format_short_time(timestamp: i64) -> String {
 /// "Today / Yesterday / weekday / full date" cascade so the user gets
 /// the loosest pretty form available.
 pub fn format_day_divider(timestamp: i64) -> String {
-    if timestamp <= 0 {
+    let Some(local) = tina_core::datetime_local(timestamp) else {
         return String::new();
-    }
-    let dt = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-    let local: DateTime<Local> = dt.into();
+    };
     let now = Local::now();
     let today = now.date_naive();
     let day = local.date_naive();
@@ -62,20 +48,16 @@ pub fn format_day_divider(timestamp: i64) -> String {
 /// (`YYYY-MM-DD`) so callers can compare two timestamps for "are these
 /// in the same local day" without dragging chrono types around.
 pub fn local_day_key(timestamp: i64) -> String {
-    if timestamp <= 0 {
+    let Some(local) = tina_core::datetime_local(timestamp) else {
         return String::new();
-    }
-    let dt = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-    let local: DateTime<Local> = dt.into();
+    };
     local.format("%Y-%m-%d").to_string()
 }
 
 pub fn format_message_time(timestamp: i64) -> String {
-    if timestamp <= 0 {
+    let Some(local) = tina_core::datetime_local(timestamp) else {
         return String::new();
-    }
-    let dt = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-    let local: DateTime<Local> = dt.into();
+    };
     let now = Local::now();
     if local.date_naive() == now.date_naive() {
         local.format("%H:%M").to_string()
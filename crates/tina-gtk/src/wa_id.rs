@@ -1,8 +1,8 @@
 // Re-export of the typed JID module that lives in tina-core. Kept
 // at this path so widgets keep importing `crate::wa_id::WaIdentity`
 // while the canonical definition crosses the IPC boundary in
-// tina-core. Display helpers that need GTK-only crates (phone
-// formatting via the `phonenumber` crate) layer on top.
+// tina-core. Display helpers that format phones per country
+// (`format_jid_or_phone`) layer on top.
 
 pub use tina_core::{WaContact, WaIdentity};
 
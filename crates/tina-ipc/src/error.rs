@@ -11,6 +11,9 @@ pub enum IpcError {
     #[error("Failed to spawn process: {0}")]
     SpawnFailed(String),
 
+    #[error("Failed to connect to nanachi at {0}")]
+    ConnectFailed(String),
+
     #[error("Nanachi build failed: {0}")]
     BuildFailed(String),
 
@@ -22,6 +25,9 @@ pub enum IpcError {
 
     #[error("Timeout")]
     Timeout,
+
+    #[error("nanachi directory not found (searched: {})", .searched.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
+    NanachiNotFound { searched: Vec<std::path::PathBuf> },
 }
 
 pub type Result<T> = std::result::Result<T, IpcError>;
@@ -1,7 +1,18 @@
 mod error;
+#[cfg(feature = "test-util")]
+mod mock;
 mod nanachi;
 mod process;
+mod stats;
+mod transport;
 
 pub use error::IpcError;
-pub use nanachi::{CommandTiming, NanachiManager};
+#[cfg(feature = "test-util")]
+pub use mock::MockNanachi;
+pub use nanachi::{
+    CommandSender, CommandTiming, DEFAULT_EVENT_CAPACITY, NANACHI_DIR_ENV, NanachiManager,
+    ParsedLine, truncate_line,
+};
 pub use process::SLOW_IPC_THRESHOLD;
+pub use stats::{IpcMetrics, IpcStats};
+pub use transport::{NANACHI_ADDR_ENV, NanachiTransport};
@@ -0,0 +1,193 @@
+// In-process stand-in for nanachi (feature `test-util`), plugged in as
+// `NanachiTransport::Mock`. Nothing is built or spawned: the lines the
+// manager writes are parsed back into `IpcMessage`s for the test to
+// inspect, and the test pushes event lines into the manager's reader
+// channel as if nanachi had printed them. Same wire format as the real
+// thing, so whatever reads the events (the worker's dispatcher) can't
+// tell the difference.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{Notify, mpsc};
+use tokio::task::JoinHandle;
+
+use tina_core::{IpcCommand, IpcEvent, IpcMessage, IpcMessageContent};
+
+use crate::error::{IpcError, Result};
+
+/// Test handle on a fake nanachi. Clones share the same state: keep one
+/// in the test, hand another to `NanachiTransport::Mock`.
+#[derive(Clone, Default)]
+pub struct MockNanachi {
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    /// Canal de eventos do manager; presente entre `start` e `stop`.
+    events: Mutex<Option<mpsc::Sender<String>>>,
+    sent: Mutex<Vec<IpcMessage>>,
+    written: Notify,
+}
+
+impl MockNanachi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delivers `event` as if nanachi had printed it. Fails with
+    /// `ProcessNotRunning` while the manager isn't started.
+    pub async fn emit(&self, event: IpcEvent) -> Result<()> {
+        self.emit_line(&IpcMessage::new_event(event).to_line())
+            .await
+    }
+
+    /// Raw line, for payloads easier to write as JSON (serde defaults
+    /// fill the rest) or deliberately malformed ones.
+    pub async fn emit_line(&self, line: &str) -> Result<()> {
+        let events = self
+            .shared
+            .events
+            .lock()
+            .ok()
+            .and_then(|e| e.clone())
+            .ok_or(IpcError::ProcessNotRunning)?;
+        events
+            .send(line.trim_end().to_string())
+            .await
+            .map_err(|_| IpcError::ChannelClosed)
+    }
+
+    /// Answers `message` with a `CommandResult`.
+    pub async fn reply(
+        &self,
+        message: &IpcMessage,
+        success: bool,
+        data: Option<serde_json::Value>,
+    ) -> Result<()> {
+        self.emit(IpcEvent::CommandResult {
+            command_id: message.id.clone(),
+            success,
+            data,
+            error: (!success).then(|| "mock failure".to_string()),
+        })
+        .await
+    }
+
+    /// Every command written so far, oldest first.
+    pub fn commands(&self) -> Vec<IpcCommand> {
+        self.shared
+            .sent
+            .lock()
+            .map(|sent| {
+                sent.iter()
+                    .filter_map(|m| match &m.content {
+                        IpcMessageContent::Command(c) => Some(c.clone()),
+                        IpcMessageContent::Event(_) => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// First command written (already or within `timeout`) that
+    /// matches `pred`, with its message id for `reply`.
+    pub async fn wait_for_command(
+        &self,
+        timeout: Duration,
+        pred: impl Fn(&IpcCommand) -> bool,
+    ) -> Option<IpcMessage> {
+        self.wait_for_nth_command(timeout, 0, pred).await
+    }
+
+    /// Like `wait_for_command`, but skips the first `n` matches — for
+    /// a command the worker writes again (a retry, say).
+    pub async fn wait_for_nth_command(
+        &self,
+        timeout: Duration,
+        n: usize,
+        pred: impl Fn(&IpcCommand) -> bool,
+    ) -> Option<IpcMessage> {
+        let find = || {
+            self.shared.sent.lock().ok().and_then(|sent| {
+                sent.iter()
+                    .filter(|m| matches!(&m.content, IpcMessageContent::Command(c) if pred(c)))
+                    .nth(n)
+                    .cloned()
+            })
+        };
+        let wait = async {
+            loop {
+                // Registra antes de olhar: um write entre a busca e o
+                // await não se perde.
+                let written = self.shared.written.notified();
+                if let Some(found) = find() {
+                    return found;
+                }
+                written.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.ok()
+    }
+
+    /// Hooks the mock up to a starting manager.
+    pub(crate) fn attach(&self, event_tx: mpsc::Sender<String>) -> MockLink {
+        if let Ok(mut events) = self.shared.events.lock() {
+            *events = Some(event_tx);
+        }
+        let (line_tx, mut line_rx) = mpsc::channel::<String>(1000);
+        let shared = self.shared.clone();
+        let task = tokio::spawn(async move {
+            while let Some(line) = line_rx.recv().await {
+                match serde_json::from_str::<IpcMessage>(line.trim_end()) {
+                    Ok(message) => {
+                        if let Ok(mut sent) = shared.sent.lock() {
+                            sent.push(message);
+                        }
+                        shared.written.notify_waiters();
+                    }
+                    Err(e) => tracing::warn!("mock nanachi: unparseable line: {e}"),
+                }
+            }
+        });
+        MockLink {
+            line_tx,
+            task,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for MockNanachi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MockNanachi")
+    }
+}
+
+/// Same mock, not just an equal-looking one.
+impl PartialEq for MockNanachi {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl Eq for MockNanachi {}
+
+/// The manager's side of the mock while started.
+pub(crate) struct MockLink {
+    pub(crate) line_tx: mpsc::Sender<String>,
+    task: JoinHandle<()>,
+    shared: Arc<Shared>,
+}
+
+impl Drop for MockLink {
+    /// Parado: `emit` volta a falhar como com o processo morto.
+    fn drop(&mut self) {
+        self.task.abort();
+        if let Ok(mut events) = self.shared.events.lock() {
+            *events = None;
+        }
+    }
+}
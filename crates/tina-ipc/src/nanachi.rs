@@ -1,15 +1,17 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 
-use tina_core::{IpcCommand, IpcEvent, IpcMessage};
+use tina_core::{IpcCommand, IpcEvent, IpcMessage, IpcMessageContent};
 
 use crate::error::{IpcError, Result};
-use crate::process::ProcessHandle;
+use crate::process::{ProcessHandle, send_line};
+use crate::stats::{IpcMetrics, IpcStats};
+use crate::transport::{Link, NanachiTransport, TcpConnection};
 
 /// Metadata de comando em voo: nome do tipo (`StartAccount`, `Reconcile`, …)
 /// e instante de envio. Usado pra calcular round-trip quando chega o
@@ -20,29 +22,139 @@ pub struct CommandTiming {
     pub sent_at: Instant,
 }
 
+/// Lado de escrita do stdin do nanachi + o mapa de comandos em voo.
+/// Barato de clonar; depois que o processo morre os envios falham com
+/// `ChannelClosed`.
+#[derive(Clone)]
+pub struct CommandSender {
+    stdin_tx: mpsc::Sender<String>,
+    outstanding: Arc<Mutex<HashMap<String, CommandTiming>>>,
+    metrics: IpcMetrics,
+}
+
+impl CommandSender {
+    pub async fn send_command(&self, command: IpcCommand) -> Result<()> {
+        self.send_message(IpcMessage::new_command(command)).await
+    }
+
+    /// Envia uma mensagem já montada. Pra quem precisa do `id` antes do
+    /// envio — esperar o `CommandResult` sem corrida com o dispatcher.
+    pub async fn send_message(&self, message: IpcMessage) -> Result<()> {
+        let line = message.to_line();
+        // Registra antes do write — se o write bloquear, o reloj já está rodando.
+        if let IpcMessageContent::Command(command) = &message.content
+            && let Ok(mut map) = self.outstanding.lock()
+        {
+            map.insert(
+                message.id.clone(),
+                CommandTiming {
+                    kind: command_kind(command),
+                    sent_at: Instant::now(),
+                },
+            );
+        }
+        send_line(&self.stdin_tx, &line).await?;
+        self.metrics.record_command();
+        Ok(())
+    }
+}
+
+/// Variável de ambiente que aponta direto pro diretório do nanachi,
+/// pulando a busca. Útil pra installs onde o binário não fica perto
+/// do source tree.
+pub const NANACHI_DIR_ENV: &str = "TINA_NANACHI_DIR";
+
+/// Linhas do nanachi que cabem no canal de eventos antes do leitor
+/// esperar o dispatcher.
+pub const DEFAULT_EVENT_CAPACITY: usize = 1000;
+
+/// Tentativas de `go build` quando a falha parece de rede (download
+/// de módulos no primeiro build). A espera dobra a cada tentativa.
+const BUILD_ATTEMPTS: u32 = 3;
+const BUILD_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Trechos do stderr do `go` que indicam falha de rede, não de código.
+const NETWORK_ERROR_MARKERS: &[&str] = &[
+    "dial tcp",
+    "i/o timeout",
+    "connection refused",
+    "connection reset",
+    "no such host",
+    "network is unreachable",
+    "tls handshake timeout",
+    "temporary failure in name resolution",
+    "context deadline exceeded",
+    "unexpected eof",
+];
+
+/// Why one `go build` failed: `Hard` won't get better by retrying
+/// (no `go`, a compile error), `Retryable` looks like the network.
+enum BuildFailure {
+    Hard(String),
+    Retryable(String),
+}
+
 pub struct NanachiManager {
     nanachi_dir: PathBuf,
-    process: Option<ProcessHandle>,
+    transport: NanachiTransport,
+    link: Option<Link>,
     event_tx: mpsc::Sender<String>,
     event_rx: Option<mpsc::Receiver<String>>,
     /// `command_id` → metadata; cresce no `send_command`, drena no
     /// `take_command_timing`. `std::sync::Mutex` é ok aqui — locks são
     /// curtíssimos (insert/remove de uma entrada).
     outstanding: Arc<Mutex<HashMap<String, CommandTiming>>>,
+    metrics: IpcMetrics,
 }
 
 impl NanachiManager {
+    /// Transport from `TINA_NANACHI_ADDR` (see [`NanachiTransport::from_env`]).
     pub fn new(nanachi_dir: PathBuf) -> Self {
-        let (event_tx, event_rx) = mpsc::channel(1000);
+        Self::with_transport(nanachi_dir, NanachiTransport::from_env())
+    }
+
+    pub fn with_transport(nanachi_dir: PathBuf, transport: NanachiTransport) -> Self {
+        Self::with_capacity(nanachi_dir, transport, DEFAULT_EVENT_CAPACITY)
+    }
+
+    /// `event_capacity` bounds the channel between the stdout/socket
+    /// reader and whoever took [`Self::take_event_receiver`]. When it
+    /// fills, the reader stops reading and nanachi blocks on its write.
+    pub fn with_capacity(
+        nanachi_dir: PathBuf,
+        transport: NanachiTransport,
+        event_capacity: usize,
+    ) -> Self {
+        let (event_tx, event_rx) = mpsc::channel(event_capacity.max(1));
         Self {
             nanachi_dir,
-            process: None,
+            transport,
+            link: None,
             event_tx,
             event_rx: Some(event_rx),
             outstanding: Arc::new(Mutex::new(HashMap::new())),
+            metrics: IpcMetrics::default(),
         }
     }
 
+    /// Acha o diretório do nanachi (o que contém `go.mod`). Ordem:
+    /// `TINA_NANACHI_DIR`, depois `<ancestral>/nanachi` subindo a partir
+    /// de `start` (padrão: diretório do executável), depois `<cwd>/nanachi`.
+    /// Quando nada bate, o erro lista todos os caminhos tentados.
+    pub fn locate_dir(start: Option<PathBuf>) -> Result<PathBuf> {
+        let env_dir = std::env::var_os(NANACHI_DIR_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        let start = match start {
+            Some(s) => Some(s),
+            None => std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+        };
+        let cwd = std::env::current_dir().ok();
+        locate_dir_in(env_dir, start.as_deref(), cwd.as_deref())
+    }
+
     pub fn take_event_receiver(&mut self) -> Option<mpsc::Receiver<String>> {
         self.event_rx.take()
     }
@@ -52,6 +164,42 @@ impl NanachiManager {
         self.nanachi_dir.join(name)
     }
 
+    /// Binário do nanachi existe e é mais novo que o `go.mod` e todo
+    /// `.go` do diretório. Falso quando `ensure_dependencies` vai
+    /// rodar `go build`.
+    pub fn is_built(&self) -> bool {
+        let go_mod = self.nanachi_dir.join("go.mod");
+        let Some(bt) = self.binary_path().metadata().and_then(|m| m.modified()).ok() else {
+            return false;
+        };
+        // Rebuild if go.mod OR any .go source file is newer than
+        // the binary. Checking only go.mod misses edits to *.go
+        // files (the common case during development).
+        let go_mod_newer = go_mod
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|mt| mt > bt)
+            .unwrap_or(false);
+        let source_newer = std::fs::read_dir(&self.nanachi_dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .and_then(|x| x.to_str())
+                    .map(|x| x == "go")
+                    .unwrap_or(false)
+            })
+            .any(|e| {
+                e.metadata()
+                    .and_then(|m| m.modified())
+                    .map(|mt| mt > bt)
+                    .unwrap_or(false)
+            });
+        !(go_mod_newer || source_newer)
+    }
+
     pub async fn ensure_dependencies(&self) -> Result<()> {
         let go_mod = self.nanachi_dir.join("go.mod");
         if !go_mod.exists() {
@@ -60,41 +208,7 @@ impl NanachiManager {
             ));
         }
 
-        let bin = self.binary_path();
-        let bin_mtime = bin.metadata().and_then(|m| m.modified()).ok();
-        let needs_build = match bin_mtime {
-            None => true,
-            Some(bt) => {
-                // Rebuild if go.mod OR any .go source file is newer than
-                // the binary. Checking only go.mod misses edits to *.go
-                // files (the common case during development).
-                let go_mod_newer = go_mod
-                    .metadata()
-                    .and_then(|m| m.modified())
-                    .map(|mt| mt > bt)
-                    .unwrap_or(false);
-                let source_newer = std::fs::read_dir(&self.nanachi_dir)
-                    .into_iter()
-                    .flatten()
-                    .filter_map(|e| e.ok())
-                    .filter(|e| {
-                        e.path()
-                            .extension()
-                            .and_then(|x| x.to_str())
-                            .map(|x| x == "go")
-                            .unwrap_or(false)
-                    })
-                    .any(|e| {
-                        e.metadata()
-                            .and_then(|m| m.modified())
-                            .map(|mt| mt > bt)
-                            .unwrap_or(false)
-                    });
-                go_mod_newer || source_newer
-            }
-        };
-
-        if needs_build {
+        if !self.is_built() {
             tracing::info!("Building nanachi (whatsmeow) Go binary...");
             self.run_go_build().await?;
         }
@@ -102,7 +216,35 @@ impl NanachiManager {
         Ok(())
     }
 
+    /// `go build` with a bounded retry when the failure looks like the
+    /// network (module download on a flaky connection). Returns the
+    /// last stderr when every attempt fails.
     async fn run_go_build(&self) -> Result<()> {
+        let mut delay = BUILD_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.go_build_once().await {
+                Ok(()) => {
+                    tracing::info!("nanachi build completed");
+                    return Ok(());
+                }
+                Err(BuildFailure::Retryable(_)) if attempt < BUILD_ATTEMPTS => {
+                    tracing::info!(
+                        "nanachi build attempt {attempt}/{BUILD_ATTEMPTS} hit a network error, \
+                         retrying in {delay:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(BuildFailure::Hard(stderr) | BuildFailure::Retryable(stderr)) => {
+                    return Err(IpcError::BuildFailed(stderr));
+                }
+            }
+        }
+    }
+
+    async fn go_build_once(&self) -> std::result::Result<(), BuildFailure> {
         let bin_name = if cfg!(windows) { "nanachi.exe" } else { "nanachi" };
 
         let output = Command::new("go")
@@ -112,19 +254,44 @@ impl NanachiManager {
             .stderr(Stdio::piped())
             .output()
             .await
-            .map_err(|e| IpcError::BuildFailed(format!("failed to invoke `go`: {e}")))?;
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    BuildFailure::Hard("`go` not found in PATH".to_string())
+                }
+                _ => BuildFailure::Hard(format!("failed to invoke `go`: {e}")),
+            })?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(IpcError::BuildFailed(stderr.into_owned()));
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if looks_like_network_failure(&stderr) {
+            Err(BuildFailure::Retryable(stderr))
+        } else {
+            Err(BuildFailure::Hard(stderr))
         }
+    }
 
-        tracing::info!("nanachi build completed");
-        Ok(())
+    pub fn transport(&self) -> &NanachiTransport {
+        &self.transport
     }
 
     pub async fn start(&mut self) -> Result<()> {
-        if self.process.is_some() {
+        if self.link.is_some() {
+            return Ok(());
+        }
+
+        if let NanachiTransport::Tcp(addr) = &self.transport {
+            tracing::info!("Connecting to nanachi at {addr}...");
+            let conn = TcpConnection::connect(addr, self.event_tx.clone()).await?;
+            self.link = Some(Link::Tcp(conn));
+            tracing::info!("Connected to nanachi at {addr}");
+            return Ok(());
+        }
+
+        #[cfg(feature = "test-util")]
+        if let NanachiTransport::Mock(mock) = &self.transport {
+            self.link = Some(Link::Mock(mock.attach(self.event_tx.clone())));
             return Ok(());
         }
 
@@ -143,39 +310,48 @@ impl NanachiManager {
         )
         .await?;
 
-        self.process = Some(handle);
+        self.link = Some(Link::Process(handle));
 
         tracing::info!("Nanachi process started");
         Ok(())
     }
 
     pub async fn stop(&mut self) -> Result<()> {
-        if let Some(mut process) = self.process.take() {
+        // Serviço independente (TCP): só larga a conexão, sem `Shutdown`.
+        if matches!(self.link, Some(Link::Process(_))) {
             let _ = self.send_command(IpcCommand::Shutdown).await;
             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            let _ = process.kill().await;
-            tracing::info!("Nanachi process stopped");
+        }
+        match self.link.take() {
+            Some(Link::Process(mut process)) => {
+                let _ = process.kill().await;
+                tracing::info!("Nanachi process stopped");
+            }
+            Some(Link::Tcp(_)) => tracing::info!("Disconnected from nanachi"),
+            #[cfg(feature = "test-util")]
+            Some(Link::Mock(_)) => {}
+            None => {}
         }
         Ok(())
     }
 
     pub async fn send_command(&self, command: IpcCommand) -> Result<()> {
-        let process = self.process.as_ref().ok_or(IpcError::ProcessNotRunning)?;
-        let kind = command_kind(&command);
-        let message = IpcMessage::new_command(command);
-        let id = message.id.clone();
-        let line = message.to_line();
-        // Registra antes do write — se o write bloquear, o reloj já está rodando.
-        if let Ok(mut map) = self.outstanding.lock() {
-            map.insert(
-                id,
-                CommandTiming {
-                    kind,
-                    sent_at: Instant::now(),
-                },
-            );
-        }
-        process.send(&line).await
+        self.command_sender()
+            .ok_or(IpcError::ProcessNotRunning)?
+            .send_command(command)
+            .await
+    }
+
+    /// Handle clonável pra mandar comandos sem segurar o manager. O
+    /// worker guarda um depois do `start` e o hot path (send_message,
+    /// mark_read, …) não passa mais pelo `RwLock<NanachiManager>`.
+    /// `None` se o nanachi não foi iniciado / conectado.
+    pub fn command_sender(&self) -> Option<CommandSender> {
+        self.link.as_ref().map(|link| CommandSender {
+            stdin_tx: link.line_sender(),
+            outstanding: self.outstanding.clone(),
+            metrics: self.metrics.clone(),
+        })
     }
 
     /// Drena a metadata de um comando completo (ao receber `CommandResult`).
@@ -194,40 +370,139 @@ impl NanachiManager {
         self.outstanding.clone()
     }
 
+    /// Contadores compartilhados: o dispatcher do worker registra os
+    /// eventos e os round-trips que ele correlaciona.
+    pub fn metrics_handle(&self) -> IpcMetrics {
+        self.metrics.clone()
+    }
+
+    /// Commands, events and round-trip latency since this manager was
+    /// created (they carry over a restart of nanachi).
+    pub fn stats(&self) -> IpcStats {
+        self.metrics.snapshot()
+    }
+
     /// PID of the running nanachi subprocess; `None` if not started or
     /// already exited. Used by the settings dialog to read RSS from
     /// `/proc/<pid>/status`.
     pub fn child_pid(&self) -> Option<u32> {
-        self.process.as_ref().and_then(|p| p.pid())
+        match &self.link {
+            Some(Link::Process(p)) => p.pid(),
+            _ => None,
+        }
     }
 
     pub fn is_running(&mut self) -> bool {
-        if let Some(ref mut process) = self.process {
-            match process.try_wait() {
-                Ok(None) => true,
-                _ => {
-                    self.process = None;
-                    false
-                }
-            }
-        } else {
-            false
+        let alive = match &mut self.link {
+            Some(Link::Process(process)) => matches!(process.try_wait(), Ok(None)),
+            Some(Link::Tcp(conn)) => conn.is_open(),
+            #[cfg(feature = "test-util")]
+            Some(Link::Mock(_)) => true,
+            None => return false,
+        };
+        if !alive {
+            self.link = None;
         }
+        alive
     }
 
     pub fn parse_event(line: &str) -> Option<IpcEvent> {
-        let msg: IpcMessage = match serde_json::from_str(line) {
-            Ok(m) => m,
-            Err(e) => {
-                tracing::warn!("parse_event failed: {e} — line: {}", &line[..line.len().min(200)]);
-                return None;
+        match Self::parse_line(line) {
+            ParsedLine::Event { event, .. } => Some(event),
+            ParsedLine::Garbage { error } => {
+                tracing::warn!("parse_event failed: {error} — line: {}", truncate_line(line));
+                None
             }
-        };
-        match msg.content {
-            tina_core::IpcMessageContent::Event(event) => Some(event),
-            _ => None,
+            ParsedLine::NotAnEvent | ParsedLine::Blank => None,
+        }
+    }
+
+    /// Classifica uma linha do stdout do nanachi sem logar nada — quem
+    /// chama decide o que é ruído e o que é problema.
+    pub fn parse_line(line: &str) -> ParsedLine {
+        if line.trim().is_empty() {
+            return ParsedLine::Blank;
+        }
+        match serde_json::from_str::<IpcMessage>(line) {
+            Ok(msg) => match msg.content {
+                IpcMessageContent::Event(event) => ParsedLine::Event {
+                    event,
+                    protocol_version: msg.protocol_version,
+                },
+                IpcMessageContent::Command(_) => ParsedLine::NotAnEvent,
+            },
+            Err(e) => ParsedLine::Garbage { error: e.to_string() },
+        }
+    }
+}
+
+/// What one line of nanachi's stdout turned out to be.
+#[derive(Debug)]
+pub enum ParsedLine {
+    Event {
+        event: IpcEvent,
+        /// What the line claimed; see `tina_core::PROTOCOL_VERSION`.
+        protocol_version: Option<u32>,
+    },
+    /// A well-formed IPC message that just isn't an event (a command
+    /// echoed back). Not an error.
+    NotAnEvent,
+    /// Empty or whitespace-only line.
+    Blank,
+    /// Not an IPC message at all: a line cut short by a crash, a stray
+    /// debug print, or an event this build doesn't know (protocol
+    /// mismatch).
+    Garbage { error: String },
+}
+
+/// Os primeiros 200 caracteres de `line`, pra log. Corta em fronteira
+/// de char — a linha pode ter acento/emoji.
+pub fn truncate_line(line: &str) -> &str {
+    const MAX_CHARS: usize = 200;
+    match line.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
+fn looks_like_network_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    NETWORK_ERROR_MARKERS.iter().any(|m| stderr.contains(m))
+}
+
+fn is_nanachi_dir(dir: &Path) -> bool {
+    dir.join("go.mod").is_file()
+}
+
+fn locate_dir_in(env_dir: Option<PathBuf>, start: Option<&Path>, cwd: Option<&Path>) -> Result<PathBuf> {
+    let mut searched = Vec::new();
+
+    if let Some(dir) = env_dir {
+        if is_nanachi_dir(&dir) {
+            return Ok(dir);
         }
+        searched.push(dir);
     }
+
+    let mut current = start;
+    while let Some(dir) = current {
+        let candidate = dir.join("nanachi");
+        if is_nanachi_dir(&candidate) {
+            return Ok(candidate);
+        }
+        searched.push(candidate);
+        current = dir.parent();
+    }
+
+    if let Some(cwd) = cwd {
+        let candidate = cwd.join("nanachi");
+        if is_nanachi_dir(&candidate) {
+            return Ok(candidate);
+        }
+        searched.push(candidate);
+    }
+
+    Err(IpcError::NanachiNotFound { searched })
 }
 
 fn command_kind(c: &IpcCommand) -> &'static str {
@@ -235,14 +510,28 @@ fn command_kind(c: &IpcCommand) -> &'static str {
         IpcCommand::StartAccount { .. } => "StartAccount",
         IpcCommand::StopAccount { .. } => "StopAccount",
         IpcCommand::Logout { .. } => "Logout",
+        IpcCommand::GetQrCode { .. } => "GetQrCode",
         IpcCommand::SendMessage { .. } => "SendMessage",
         IpcCommand::SendMedia { .. } => "SendMedia",
         IpcCommand::MarkRead { .. } => "MarkRead",
+        IpcCommand::MarkChatsRead { .. } => "MarkChatsRead",
         IpcCommand::Reconcile { .. } => "Reconcile",
+        IpcCommand::CancelSync { .. } => "CancelSync",
+        IpcCommand::GetContacts { .. } => "GetContacts",
+        IpcCommand::GetGroups { .. } => "GetGroups",
         IpcCommand::DownloadMedia { .. } => "DownloadMedia",
         IpcCommand::FetchAvatar { .. } => "FetchAvatar",
         IpcCommand::FetchAvatarFromURL { .. } => "FetchAvatarFromURL",
+        IpcCommand::GetProfile { .. } => "GetProfile",
+        IpcCommand::CheckNumber { .. } => "CheckNumber",
         IpcCommand::RefreshChat { .. } => "RefreshChat",
+        IpcCommand::SetTyping { .. } => "SetTyping",
+        IpcCommand::PinMessage { .. } => "PinMessage",
+        IpcCommand::StarMessage { .. } => "StarMessage",
+        IpcCommand::ForwardMessage { .. } => "ForwardMessage",
+        IpcCommand::GetGroupInviteLink { .. } => "GetGroupInviteLink",
+        IpcCommand::RevokeGroupInviteLink { .. } => "RevokeGroupInviteLink",
+        IpcCommand::JoinGroupByLink { .. } => "JoinGroupByLink",
         IpcCommand::Shutdown => "Shutdown",
     }
 }
@@ -254,7 +543,155 @@ impl Drop for NanachiManager {
         // Linux. The previous `let _ = process.kill()` pattern was a
         // bug — it produced an async Future that was dropped unpolled,
         // so the kill never happened. The kill_on_drop guard does the
-        // right thing automatically; we just take the Option.
-        let _ = self.process.take();
+        // right thing automatically; we just take the Option. A TCP
+        // link closes its socket on drop the same way.
+        let _ = self.link.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temp tree `<root>/app/bin` + `<root>/nanachi/go.mod`, limpo no drop.
+    struct TempTree(PathBuf);
+
+    impl TempTree {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("tina-ipc-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(root.join("app/bin")).unwrap();
+            std::fs::create_dir_all(root.join("nanachi")).unwrap();
+            std::fs::write(root.join("nanachi/go.mod"), "module nanachi\n").unwrap();
+            Self(root)
+        }
+    }
+
+    impl Drop for TempTree {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn is_built_until_a_source_is_newer_than_the_binary() {
+        let tree = TempTree::new("built");
+        let dir = tree.0.join("nanachi");
+        let manager = NanachiManager::new(dir.clone());
+        assert!(!manager.is_built());
+
+        std::fs::write(dir.join("main.go"), "package main\n").unwrap();
+        let bin = std::fs::File::create(manager.binary_path()).unwrap();
+        let later = std::time::SystemTime::now() + Duration::from_secs(60);
+        bin.set_modified(later).unwrap();
+        assert!(manager.is_built());
+
+        let src = std::fs::File::options().write(true).open(dir.join("main.go")).unwrap();
+        src.set_modified(later + Duration::from_secs(60)).unwrap();
+        assert!(!manager.is_built());
+    }
+
+    #[test]
+    fn locate_dir_walks_up_from_start() {
+        let tree = TempTree::new("walk");
+        let found = locate_dir_in(None, Some(&tree.0.join("app/bin")), None).unwrap();
+        assert_eq!(found, tree.0.join("nanachi"));
+    }
+
+    #[test]
+    fn locate_dir_prefers_env_override() {
+        let tree = TempTree::new("env");
+        let other = tree.0.join("app/nanachi");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("go.mod"), "module nanachi\n").unwrap();
+
+        let found = locate_dir_in(Some(other.clone()), Some(&tree.0.join("app/bin")), None).unwrap();
+        assert_eq!(found, other);
+    }
+
+    #[test]
+    fn parse_line_tells_garbage_from_non_events() {
+        let event = r#"{"id":"1","type":"Connected","payload":{"account_id":"a","jid":"1@s.whatsapp.net"}}"#;
+        assert!(matches!(
+            NanachiManager::parse_line(event),
+            ParsedLine::Event { protocol_version: None, .. }
+        ));
+
+        let command = IpcMessage::new_command(IpcCommand::StopAccount { account_id: "a".into(), reason: None }).to_line();
+        assert!(matches!(NanachiManager::parse_line(&command), ParsedLine::NotAnEvent));
+
+        assert!(matches!(NanachiManager::parse_line("  "), ParsedLine::Blank));
+        for junk in ["debug: hello", r#"{"id":"1","type":"Conne"#, r#"{"id":"1","type":"FromTheFuture","payload":{}}"#] {
+            assert!(matches!(NanachiManager::parse_line(junk), ParsedLine::Garbage { .. }), "{junk}");
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_transport_carries_lines_both_ways() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let mut manager = NanachiManager::with_transport(PathBuf::new(), NanachiTransport::Tcp(addr));
+        let mut events = manager.take_event_receiver().unwrap();
+
+        let (start, accepted) = tokio::join!(manager.start(), listener.accept());
+        start.unwrap();
+        let (socket, _) = accepted.unwrap();
+        let (read_half, mut write_half) = socket.into_split();
+
+        manager
+            .send_command(IpcCommand::StopAccount { account_id: "a".into(), reason: None })
+            .await
+            .unwrap();
+        let mut incoming = BufReader::new(read_half).lines();
+        let line = incoming.next_line().await.unwrap().unwrap();
+        assert!(line.contains("StopAccount"), "{line}");
+
+        write_half.write_all(b"{\"hello\":1}\n").await.unwrap();
+        assert_eq!(events.recv().await.unwrap(), r#"{"hello":1}"#);
+
+        assert!(manager.is_running());
+        manager.stop().await.unwrap();
+        assert!(manager.command_sender().is_none());
+        // Sem `Shutdown` pro serviço: a conexão só fecha.
+        assert_eq!(incoming.next_line().await.unwrap(), None);
+    }
+
+    #[test]
+    fn build_failures_are_retried_only_when_they_look_like_the_network() {
+        assert!(looks_like_network_failure(
+            "go: go.mau.fi/whatsmeow@v0.0.0: Get \"https://proxy.golang.org/...\": dial tcp: lookup proxy.golang.org: no such host"
+        ));
+        assert!(looks_like_network_failure("read tcp 10.0.0.2:5123: i/o timeout"));
+        assert!(!looks_like_network_failure(
+            "./main.go:12:2: undefined: handleCommand"
+        ));
+        assert!(!looks_like_network_failure(
+            "go: module example.com/nope: reading https://proxy.golang.org/example.com/nope/@v/list: 404 Not Found"
+        ));
+    }
+
+    #[test]
+    fn truncate_line_respects_char_boundaries() {
+        let line = "é".repeat(300);
+        assert_eq!(truncate_line(&line).chars().count(), 200);
+        assert_eq!(truncate_line("curta"), "curta");
+    }
+
+    #[test]
+    fn locate_dir_falls_back_to_cwd_and_reports_searched_paths() {
+        let tree = TempTree::new("cwd");
+        let found = locate_dir_in(None, None, Some(&tree.0)).unwrap();
+        assert_eq!(found, tree.0.join("nanachi"));
+
+        let missing = tree.0.join("app/bin");
+        let bogus_env = tree.0.join("nope");
+        match locate_dir_in(Some(bogus_env.clone()), None, Some(&missing)) {
+            Err(IpcError::NanachiNotFound { searched }) => {
+                assert_eq!(searched, vec![bogus_env, missing.join("nanachi")]);
+            }
+            other => panic!("expected NanachiNotFound, got {other:?}"),
+        }
     }
 }
@@ -82,17 +82,11 @@ impl ProcessHandle {
         Ok(Self { child, stdin_tx })
     }
 
-    pub async fn send(&self, line: &str) -> Result<()> {
-        let msg = if line.ends_with('\n') {
-            line.to_string()
-        } else {
-            format!("{}\n", line)
-        };
-
-        self.stdin_tx
-            .send(msg)
-            .await
-            .map_err(|_| IpcError::ChannelClosed)
+    /// Clone of the stdin channel. Writing through it needs no access
+    /// to the handle itself, so senders can live outside whatever lock
+    /// guards the process.
+    pub fn stdin_sender(&self) -> mpsc::Sender<String> {
+        self.stdin_tx.clone()
     }
 
     pub async fn kill(&mut self) -> Result<()> {
@@ -109,3 +103,13 @@ impl ProcessHandle {
         self.child.id()
     }
 }
+
+pub(crate) async fn send_line(stdin_tx: &mpsc::Sender<String>, line: &str) -> Result<()> {
+    let msg = if line.ends_with('\n') {
+        line.to_string()
+    } else {
+        format!("{}\n", line)
+    };
+
+    stdin_tx.send(msg).await.map_err(|_| IpcError::ChannelClosed)
+}
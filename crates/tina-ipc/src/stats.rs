@@ -0,0 +1,163 @@
+// Contadores de throughput do IPC. Só atômicos — `CommandSender` e o
+// dispatcher do worker mexem neles a cada linha, então nada de lock
+// no hot path. O round-trip é uma média móvel exponencial (peso 1/8,
+// como o SRTT do TCP): reage a uma fase lenta sem guardar histórico.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Shared handle to the counters. Cheap to clone; every clone updates
+/// the same numbers.
+#[derive(Debug, Clone, Default)]
+pub struct IpcMetrics {
+    inner: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    commands_sent: AtomicU64,
+    events_received: AtomicU64,
+    round_trips: AtomicU64,
+    /// Média em microssegundos; só vale quando `round_trips > 0`.
+    avg_round_trip_us: AtomicU64,
+    peak_event_queue: AtomicU64,
+    dropped_events: AtomicU64,
+}
+
+impl IpcMetrics {
+    pub fn record_command(&self) {
+        self.inner.commands_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_event(&self) {
+        self.inner.events_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Lines waiting in the event channel when one was taken out; keeps
+    /// the largest seen.
+    pub fn record_queue_depth(&self, depth: usize) {
+        let depth = u64::try_from(depth).unwrap_or(u64::MAX);
+        self.inner
+            .peak_event_queue
+            .fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// A progress event skipped because the consumer's channel was full.
+    pub fn record_dropped_event(&self) {
+        self.inner.dropped_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// One command→`CommandResult` round trip.
+    pub fn record_round_trip(&self, rtt: Duration) {
+        let sample = u64::try_from(rtt.as_micros()).unwrap_or(u64::MAX);
+        let first = self.inner.round_trips.fetch_add(1, Ordering::Relaxed) == 0;
+        let _ = self.inner.avg_round_trip_us.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |avg| {
+                Some(if first {
+                    sample
+                } else {
+                    avg - avg / 8 + sample / 8
+                })
+            },
+        );
+    }
+
+    pub fn snapshot(&self) -> IpcStats {
+        let round_trips = self.inner.round_trips.load(Ordering::Relaxed);
+        IpcStats {
+            commands_sent: self.inner.commands_sent.load(Ordering::Relaxed),
+            events_received: self.inner.events_received.load(Ordering::Relaxed),
+            round_trips,
+            avg_round_trip: (round_trips > 0).then(|| {
+                Duration::from_micros(self.inner.avg_round_trip_us.load(Ordering::Relaxed))
+            }),
+            peak_event_queue: self.inner.peak_event_queue.load(Ordering::Relaxed),
+            dropped_events: self.inner.dropped_events.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Point-in-time copy of the IPC counters since the manager was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IpcStats {
+    pub commands_sent: u64,
+    pub events_received: u64,
+    /// Commands whose `CommandResult` came back and was matched.
+    pub round_trips: u64,
+    /// Moving average of the command→`CommandResult` latency; `None`
+    /// until the first result arrives.
+    pub avg_round_trip: Option<Duration>,
+    /// Deepest the nanachi→worker event channel has been.
+    pub peak_event_queue: u64,
+    /// Progress events dropped instead of waiting on a full channel.
+    pub dropped_events: u64,
+}
+
+impl fmt::Display for IpcStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} commands sent, {} events received, {} results",
+            self.commands_sent, self.events_received, self.round_trips
+        )?;
+        if let Some(avg) = self.avg_round_trip {
+            write!(f, ", avg round-trip {avg:?}")?;
+        }
+        if self.peak_event_queue > 0 {
+            write!(f, ", peak queue {}", self.peak_event_queue)?;
+        }
+        if self.dropped_events > 0 {
+            write!(f, ", {} progress events dropped", self.dropped_events)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_average_starts_at_first_sample_and_moves_slowly() {
+        let metrics = IpcMetrics::default();
+        assert_eq!(metrics.snapshot().avg_round_trip, None);
+
+        metrics.record_command();
+        metrics.record_command();
+        metrics.record_event();
+        metrics.record_round_trip(Duration::from_millis(8));
+        assert_eq!(
+            metrics.snapshot().avg_round_trip,
+            Some(Duration::from_millis(8))
+        );
+
+        // Uma amostra lenta puxa 1/8 do caminho.
+        metrics.record_round_trip(Duration::from_millis(88));
+        let stats = metrics.snapshot();
+        assert_eq!(stats.avg_round_trip, Some(Duration::from_millis(18)));
+        assert_eq!(stats.commands_sent, 2);
+        assert_eq!(stats.events_received, 1);
+        assert_eq!(stats.round_trips, 2);
+    }
+
+    #[test]
+    fn queue_depth_keeps_the_peak() {
+        let metrics = IpcMetrics::default();
+        metrics.record_queue_depth(3);
+        metrics.record_queue_depth(40);
+        metrics.record_queue_depth(7);
+        metrics.record_dropped_event();
+        let stats = metrics.snapshot();
+        assert_eq!(stats.peak_event_queue, 40);
+        assert_eq!(stats.dropped_events, 1);
+        assert!(
+            stats
+                .to_string()
+                .ends_with("peak queue 40, 1 progress events dropped")
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// How the line-delimited JSON reaches nanachi. By default it's the
+// child's stdin/stdout (`ProcessHandle`); with `TINA_NANACHI_ADDR` set
+// the manager connects to a nanachi started on its own with
+// `nanachi -listen <addr>` instead, so the bridge (and the WhatsApp
+// sessions in it) outlive the app. Both sides of the wire carry exactly
+// the same lines either way.
+
+use std::time::Instant;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::error::{IpcError, Result};
+use crate::process::{ProcessHandle, SLOW_IPC_THRESHOLD};
+
+/// Variável de ambiente com `host:porta` de um nanachi já rodando.
+/// Vazia ou ausente: sobe o nanachi como subprocesso.
+pub const NANACHI_ADDR_ENV: &str = "TINA_NANACHI_ADDR";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NanachiTransport {
+    /// Build and spawn nanachi, talk over its stdin/stdout.
+    #[default]
+    Stdio,
+    /// Connect to a nanachi listening on this `host:port`. Stopping
+    /// only drops the connection; the service keeps running.
+    Tcp(String),
+    /// In-process fake for tests: nothing is built or spawned.
+    #[cfg(feature = "test-util")]
+    Mock(crate::mock::MockNanachi),
+}
+
+impl NanachiTransport {
+    /// `Tcp` when `TINA_NANACHI_ADDR` is set, `Stdio` otherwise.
+    pub fn from_env() -> Self {
+        std::env::var(NANACHI_ADDR_ENV)
+            .ok()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .map_or(Self::Stdio, Self::Tcp)
+    }
+}
+
+/// Socket to a standalone nanachi. Same shape as `ProcessHandle`: a
+/// writer task drains an mpsc of lines, a reader task pushes incoming
+/// lines to the manager's event channel.
+pub(crate) struct TcpConnection {
+    line_tx: mpsc::Sender<String>,
+    reader: JoinHandle<()>,
+    writer: JoinHandle<()>,
+}
+
+impl TcpConnection {
+    pub(crate) async fn connect(addr: &str, event_tx: mpsc::Sender<String>) -> Result<Self> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| IpcError::ConnectFailed(format!("{addr}: {e}")))?;
+        // Linhas pequenas e interativas (typing, read) — sem Nagle.
+        let _ = stream.set_nodelay(true);
+        let (read_half, mut write_half) = stream.into_split();
+
+        let (line_tx, mut line_rx) = mpsc::channel::<String>(1000);
+        let writer = tokio::spawn(async move {
+            while let Some(line) = line_rx.recv().await {
+                let start = Instant::now();
+                if write_half.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+                let elapsed = start.elapsed();
+                if elapsed > SLOW_IPC_THRESHOLD {
+                    tracing::warn!(
+                        "🐌 socket→nanachi write {:?} ({} bytes)",
+                        elapsed,
+                        line.len()
+                    );
+                }
+            }
+        });
+
+        let reader = tokio::spawn(async move {
+            let mut lines = BufReader::new(read_half).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if event_tx.send(line).await.is_err() {
+                    break;
+                }
+            }
+            tracing::warn!("nanachi connection closed");
+        });
+
+        Ok(Self {
+            line_tx,
+            reader,
+            writer,
+        })
+    }
+
+    /// The peer hasn't closed the socket.
+    pub(crate) fn is_open(&self) -> bool {
+        !self.reader.is_finished()
+    }
+}
+
+impl Drop for TcpConnection {
+    /// A metade de leitura vive na task; sem o abort o socket ficaria
+    /// aberto depois do `stop`.
+    fn drop(&mut self) {
+        self.reader.abort();
+        self.writer.abort();
+    }
+}
+
+/// The live link `NanachiManager` holds once started.
+pub(crate) enum Link {
+    Process(ProcessHandle),
+    Tcp(TcpConnection),
+    #[cfg(feature = "test-util")]
+    Mock(crate::mock::MockLink),
+}
+
+impl Link {
+    /// Where `CommandSender` writes its lines.
+    pub(crate) fn line_sender(&self) -> mpsc::Sender<String> {
+        match self {
+            Self::Process(p) => p.stdin_sender(),
+            Self::Tcp(c) => c.line_tx.clone(),
+            #[cfg(feature = "test-util")]
+            Self::Mock(m) => m.line_tx.clone(),
+        }
+    }
+}
@@ -8,12 +8,57 @@ pub enum WorkerError {
     #[error("IPC error: {0}")]
     Ipc(#[from] tina_ipc::IpcError),
 
-    #[error("Worker not started")]
-    NotStarted,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("nanachi isn't ready; start the worker and wait for NanachiReady")]
+    NotReady,
+
+    #[error("Offline mode: nanachi isn't running, only stored data is available")]
+    OfflineMode,
 
     #[error("Account not found: {0}")]
     AccountNotFound(String),
 
+    #[error("Message not found: {0}")]
+    MessageNotFound(String),
+
+    #[error("Message {message_id} isn't in chat {chat_id}")]
+    MessageNotInChat { message_id: String, chat_id: String },
+
+    #[error("Message {0} can't be re-sent")]
+    NotRetryable(String),
+
+    #[error("Unknown export format: {0}")]
+    InvalidExportFormat(String),
+
+    #[error("Invalid recipient: {0:?}")]
+    InvalidRecipient(String),
+
+    #[error("Chat {chat_id} already has {limit} pinned messages")]
+    PinLimitReached { chat_id: String, limit: i64 },
+
+    #[error("Only group admins can do that in {0}")]
+    NotGroupAdmin(String),
+
+    #[error("Not a valid group invite link: {0:?}")]
+    InvalidInviteLink(String),
+
+    #[error("Already a member of {0}")]
+    AlreadyGroupMember(String),
+
+    #[error("Cannot restore while nanachi is running; stop the worker first")]
+    RestoreWhileRunning,
+
+    #[error("nanachi rejected the command: {0}")]
+    CommandFailed(String),
+
+    #[error("Message not sent: {0}")]
+    SendFailed(String),
+
+    #[error("No reply from nanachi in time")]
+    ReplyTimeout,
+
     #[error("Channel closed")]
     ChannelClosed,
 }
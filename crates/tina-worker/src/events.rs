@@ -1,20 +1,54 @@
-use tina_core::WaIdentity;
+use tina_core::{DisconnectReason, WaIdentity};
 use tina_db::{ChatRow, MentionCandidate, MessageRow, StatusAuthorRow};
 
+use crate::worker::AccountConnectionState;
+
 #[derive(Debug, Clone)]
 pub enum WorkerEvent {
     NanachiReady,
     AccountReady { account_id: String },
 
-    QrCode { account_id: String, qr: String },
+    /// `issued_at` (unix secs) + `expires_in_secs` give the code's
+    /// validity window; `QrExpired` follows if it lapses unused.
+    QrCode {
+        account_id: String,
+        qr: String,
+        issued_at: i64,
+        expires_in_secs: u64,
+    },
+    /// The last QR code for `account_id` expired without pairing.
+    /// Call `TinaWorker::refresh_qr` to start a new round.
+    QrExpired { account_id: String },
     Connected {
         account_id: String,
         phone_number: Option<String>,
         jid: Option<WaIdentity>,
         push_name: Option<String>,
     },
-    Disconnected { account_id: String, reason: String },
-    LoggedOut { account_id: String },
+    Disconnected {
+        account_id: String,
+        reason: DisconnectReason,
+    },
+    /// `start_account` got no answer within `connect_timeout`.
+    /// `stopped`: the worker stopped the account, a retry starts over.
+    ConnectTimedOut {
+        account_id: String,
+        stopped: bool,
+    },
+    /// Emitted only when the state actually changes.
+    ConnectionStateChanged {
+        account_id: String,
+        state: AccountConnectionState,
+    },
+    /// `purged`: chats, contacts and messages were wiped too.
+    LoggedOut { account_id: String, purged: bool },
+    /// The frontend reported a connectivity change
+    /// (`TinaWorker::notify_network_changed`). `restarting`: accounts
+    /// about to be stopped and started again; empty when going offline.
+    NetworkChanged {
+        online: bool,
+        restarting: Vec<String>,
+    },
 
     /// Snapshot completo (lista inicial) ou parcial (após batch) de chats.
     ChatsUpserted {
@@ -26,6 +60,17 @@ pub enum WorkerEvent {
         messages_written: usize,
     },
 
+    /// A coalesced batch of contact updates was committed. Emitted once
+    /// per flush; the affected DM rows arrive in the `ChatsUpserted` of
+    /// the same flush.
+    ContactsSynced { account_id: String, count: usize },
+
+    /// Same as `ContactsSynced`, for group metadata — a flush of
+    /// `GroupsUpsert`, or a group we just joined via `join_group`. A
+    /// bulk load flushes every 20 groups, so the counts add up as it
+    /// goes instead of landing all at once.
+    GroupsSynced { account_id: String, count: usize },
+
     /// One row per contact who has posted to `status@broadcast`.
     /// Drives the Status tab's vertical author list.
     StatusAuthorsUpserted {
@@ -36,7 +81,8 @@ pub enum WorkerEvent {
     /// Mensagens novas para um chat com tab aberta na UI (registrado via
     /// `add_open_chat`). Chats fechados não geram este evento durante sync —
     /// a UI lê os snapshots via `ChatsUpserted` e re-fetch ao abrir.
-    /// `messages` já vêm com `sender_name` resolvido.
+    /// Exceção: o eco otimista dos nossos envios sai sempre, com ou sem
+    /// tab. `messages` já vêm com `sender_name` resolvido.
     MessagesAppended {
         account_id: String,
         chat_id: String,
@@ -45,6 +91,15 @@ pub enum WorkerEvent {
 
     HistorySyncComplete { account_id: String, messages_count: usize },
 
+    /// The account's first full load is in the DB: its contacts, its
+    /// groups and the history sync have each arrived at least once
+    /// (`ContactsSynced`, `GroupsSynced` and `HistorySyncComplete`
+    /// alone can't tell the bulk load from a later update). Sent once
+    /// per account, restarts included, again only after a logout and a
+    /// new pairing; see
+    /// `TinaWorker::initial_sync_complete` for late subscribers.
+    InitialSyncComplete { account_id: String },
+
     /// Live percentage from whatsmeow's `HistorySync.Progress` (0..100),
     /// emitted per chunk. Drives the syncing-scene progress bar.
     HistorySyncProgress {
@@ -54,8 +109,19 @@ pub enum WorkerEvent {
         messages_count: usize,
     },
 
+    /// History-sync chunks up to `chunk_index` are in the DB.
+    /// `messages` counts what those chunks carried since the previous
+    /// one of these; `is_last` closes the HistorySync blob.
+    HistoryChunkApplied {
+        account_id: String,
+        chunk_index: u32,
+        is_last: bool,
+        messages: usize,
+    },
+
     /// Atualização de progresso de uma reconciliação em andamento.
-    /// `total = 0` ⇒ indeterminado (spinner).
+    /// `total = 0` ⇒ indeterminado (spinner). `reconcile_account` manda
+    /// um com `stage` vazio antes do comando, pra barra zerar.
     ReconcileProgress {
         account_id: String,
         stage: String,
@@ -64,6 +130,17 @@ pub enum WorkerEvent {
         indeterminate: bool,
     },
 
+    /// nanachi couldn't send one of our text messages; its row is now
+    /// `failed`. Carries the text so the UI can offer
+    /// `TinaWorker::retry_send` without another lookup.
+    MessageSendFailed {
+        account_id: String,
+        chat_id: String,
+        message_id: String,
+        content: String,
+        error: String,
+    },
+
     Error { account_id: Option<String>, error: String },
     Notice { account_id: Option<String>, message: String },
     /// Receipt-driven delivery status update.
@@ -73,6 +150,27 @@ pub enum WorkerEvent {
         status: String,
     },
 
+    /// A stored message was edited by its sender. The row's `content`
+    /// and `edited_at` are already updated; the chat preview follows
+    /// in the same flush's `ChatsUpserted`.
+    MessageEdited {
+        account_id: String,
+        chat_id: String,
+        message_id: String,
+        new_content: String,
+        edited_at: i64,
+    },
+
+    /// `TinaWorker::clear_chat` emptied `chat_id`. `kept` are the
+    /// starred messages that stay, oldest first — what an open view of
+    /// the chat should now show. The chat-list row follows in a
+    /// `ChatsUpserted`.
+    ChatCleared {
+        account_id: String,
+        chat_id: String,
+        kept: Vec<MessageRow>,
+    },
+
     /// Progresso ao vivo de um download de mídia.
     MediaDownloadProgress {
         account_id: String,
@@ -118,3 +216,74 @@ pub enum WorkerEvent {
     },
 
 }
+
+impl WorkerEvent {
+    /// 0..=100 for the progress events (`HistorySyncProgress`,
+    /// `ReconcileProgress`); `None` for everything else and for an
+    /// indeterminate reconcile stage.
+    pub fn progress_percent(&self) -> Option<u32> {
+        match self {
+            Self::HistorySyncProgress { progress, .. } => Some((*progress).min(100)),
+            Self::ReconcileProgress {
+                current,
+                total,
+                indeterminate: false,
+                ..
+            } => progress_percent(*current, *total),
+            _ => None,
+        }
+    }
+}
+
+/// `current` out of `total` as a whole percentage, clamped to 0..=100.
+/// `None` when `total` isn't positive (nothing to measure against).
+pub fn progress_percent(current: i64, total: i64) -> Option<u32> {
+    if total <= 0 {
+        return None;
+    }
+    Some((current.clamp(0, total) * 100 / total) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_percent_clamps_and_needs_a_total() {
+        assert_eq!(progress_percent(0, 200), Some(0));
+        assert_eq!(progress_percent(50, 200), Some(25));
+        assert_eq!(progress_percent(199, 200), Some(99));
+        assert_eq!(progress_percent(200, 200), Some(100));
+        // Contagens fora da faixa não passam de 0..=100.
+        assert_eq!(progress_percent(250, 200), Some(100));
+        assert_eq!(progress_percent(-3, 200), Some(0));
+        assert_eq!(progress_percent(5, 0), None);
+        assert_eq!(progress_percent(5, -1), None);
+    }
+
+    #[test]
+    fn event_progress_covers_history_and_determinate_reconcile() {
+        let reconcile = |current, total, indeterminate| WorkerEvent::ReconcileProgress {
+            account_id: "acc1".into(),
+            stage: "Importando grupos…".into(),
+            current,
+            total,
+            indeterminate,
+        };
+        assert_eq!(reconcile(30, 120, false).progress_percent(), Some(25));
+        assert_eq!(reconcile(30, 120, true).progress_percent(), None);
+        assert_eq!(reconcile(0, 0, false).progress_percent(), None);
+        let history = WorkerEvent::HistorySyncProgress {
+            account_id: "acc1".into(),
+            sync_type: "RECENT".into(),
+            progress: 140,
+            messages_count: 0,
+        };
+        assert_eq!(history.progress_percent(), Some(100));
+        let synced = WorkerEvent::GroupsSynced {
+            account_id: "acc1".into(),
+            count: 20,
+        };
+        assert_eq!(synced.progress_percent(), None);
+    }
+}
@@ -2,9 +2,15 @@ mod error;
 mod events;
 mod worker;
 
-pub use error::WorkerError;
-pub use events::WorkerEvent;
-pub use worker::TinaWorker;
+pub use error::{Result, WorkerError};
+pub use events::{WorkerEvent, progress_percent};
+pub use worker::{
+    AccountConnectionState, AccountStatus, BroadcastOutcome, DEFAULT_MESSAGE_PAGE_SIZE,
+    ExportFormat, ForwardOutcome, MESSAGE_PAGE_SIZE_ENV, MESSAGE_PAGE_SIZE_RANGE, TinaWorker, WorkerConfig, recv_event,
+};
 
-pub use tina_core::{ContactData, GroupData, MessageData};
-pub use tina_db::{Account, Chat, ChatKind, ChatRow, Contact, Group, Message, MessageRow};
+pub use tina_ipc::{IpcError, IpcStats, NANACHI_DIR_ENV, NanachiManager};
+pub use tina_core::{ContactData, DisconnectReason, GroupData, MessageData, MessageType};
+pub use tina_db::{
+    Account, Chat, ChatKind, ChatRow, Contact, DATA_DIR_ENV, Group, Message, MessageRow, Page, data_dir,
+};
@@ -32,7 +32,7 @@ pub(super) async fn process_contacts(
         })
         .collect();
 
-    let aliases = db.run_contacts_batch(account_id, &inputs).await?;
+    let aliases = db.upsert_contacts_batch(account_id, &inputs).await?;
 
     // Lookup bulk de DM chats afetados (read-only, fora da transação).
     const CHUNK: usize = 500;
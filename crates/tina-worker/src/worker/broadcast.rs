@@ -0,0 +1,73 @@
+// The same text to several chats. Unlike `forward_message` nothing is
+// copied from a stored message: each recipient gets its own optimistic
+// row and its own `SendMessage`, exactly like `send_message`. The
+// commands go out `WorkerConfig::broadcast_interval` apart so WhatsApp
+// doesn't see a burst from one account; the replies are awaited only
+// after the last one is written, so a slow recipient doesn't hold up
+// the others.
+
+use crate::error::{Result, WorkerError};
+
+use super::core::TinaWorker;
+
+/// How a broadcast went for one recipient.
+#[derive(Debug)]
+pub struct BroadcastOutcome {
+    /// Normalized JID, or the raw input when it didn't parse.
+    pub to: String,
+    /// The local message ID, or why this recipient failed (the same
+    /// errors `send_message` returns).
+    pub result: Result<String>,
+}
+
+impl TinaWorker {
+    /// Sends `content` to every chat in `recipients` (JIDs or phone
+    /// numbers; duplicates are sent once). Never fails as a whole:
+    /// every distinct recipient gets its own `BroadcastOutcome`, in the
+    /// order they were given.
+    pub async fn send_broadcast(
+        &self,
+        account_id: &str,
+        recipients: &[String],
+        content: &str,
+    ) -> Vec<BroadcastOutcome> {
+        // Uma vaga por destinatário distinto, na ordem da entrada; as
+        // respostas chegam na ordem que o nanachi quiser.
+        let mut outcomes: Vec<Option<BroadcastOutcome>> = Vec::with_capacity(recipients.len());
+        let mut targets: Vec<(usize, String)> = Vec::new();
+        for raw in recipients {
+            match tina_core::normalize_recipient(raw) {
+                Some(jid) if !targets.iter().any(|(_, t)| *t == jid) => {
+                    targets.push((outcomes.len(), jid));
+                    outcomes.push(None);
+                }
+                Some(_) => {}
+                None => outcomes.push(Some(BroadcastOutcome {
+                    to: raw.clone(),
+                    result: Err(WorkerError::InvalidRecipient(raw.clone())),
+                })),
+            }
+        }
+
+        let mut in_flight = Vec::with_capacity(targets.len());
+        for (i, (slot, to)) in targets.into_iter().enumerate() {
+            if i > 0 && !self.broadcast_interval.is_zero() {
+                tokio::time::sleep(self.broadcast_interval).await;
+            }
+            let local_id = uuid::Uuid::now_v7().to_string();
+            match self
+                .begin_send_message(account_id, &to, content, &[], &local_id)
+                .await
+            {
+                Ok(sent) => in_flight.push((slot, to, local_id, sent)),
+                Err(e) => outcomes[slot] = Some(BroadcastOutcome { to, result: Err(e) }),
+            }
+        }
+
+        for (slot, to, local_id, sent) in in_flight {
+            let result = self.finish_text_send(sent).await.map(|()| local_id);
+            outcomes[slot] = Some(BroadcastOutcome { to, result });
+        }
+        outcomes.into_iter().flatten().collect()
+    }
+}
@@ -1,10 +1,12 @@
 // `DirtyBuffer`: per-account accumulator for IPC events that benefit
 // from being applied as a single SQLite transaction (messages/contacts/
-// groups). Realtime events (Connected, QR, etc.) bypass it.
+// groups). Message edits ride along so they land after the row they
+// edit. Realtime events (Connected, QR, etc.) bypass it. Text is
+// sanitized on the way in (see `tina_core::sanitize_content`).
 
 use std::collections::HashMap;
 
-use tina_core::{ContactData, GroupData, MessageData};
+use tina_core::{ContactData, GroupData, MessageData, sanitize_content};
 
 /// Janela de flush do `DirtyBuffer`: durante sync, eventos bulk chegam
 /// centenas por segundo. Acumular 100ms permite mesclar várias
@@ -16,20 +18,97 @@ pub(super) const FLUSH_WINDOW: std::time::Duration = std::time::Duration::from_m
 /// antes de forçar flush — evita acumular MB sem aplicar.
 pub(super) const FLUSH_THRESHOLD: usize = 5000;
 
+/// Contatos têm teto próprio, bem menor: no connect o nanachi manda
+/// centenas de `ContactsUpsert` de um contato só. 100 por transação
+/// mantém o `ContactsSynced` frequente sem virar um commit por contato.
+pub(super) const CONTACT_FLUSH_THRESHOLD: usize = 100;
+
+/// Mesma ideia para grupos: cada um traz a lista de participantes, e o
+/// nanachi os manda em lotes de 10. Um flush a cada 20 dá ao
+/// `GroupsSynced` o mesmo ritmo de progresso dos contatos.
+pub(super) const GROUP_FLUSH_THRESHOLD: usize = 20;
+
+/// Edição pendente. Fica no buffer pra ser aplicada DEPOIS das
+/// mensagens do mesmo flush — uma edição que chega colada na mensagem
+/// original (history sync, reconnect) não se perde.
+pub(super) struct PendingEdit {
+    pub(super) message_id: String,
+    pub(super) new_content: String,
+    pub(super) edited_at: i64,
+}
+
+/// Último chunk de history sync visto por conta desde o flush
+/// anterior. Vira um `HistoryChunkApplied` depois que as mensagens
+/// entram no banco.
+#[derive(Clone, Copy)]
+pub(super) struct ChunkMark {
+    pub(super) chunk_index: u32,
+    pub(super) is_last: bool,
+    /// Mensagens de chunks acumuladas desde o último flush.
+    pub(super) messages: usize,
+}
+
 #[derive(Default)]
 pub(super) struct DirtyBuffer {
     pub(super) messages: HashMap<String, Vec<MessageData>>,
     pub(super) contacts: HashMap<String, Vec<ContactData>>,
     pub(super) groups: HashMap<String, Vec<GroupData>>,
+    pub(super) edits: HashMap<String, Vec<PendingEdit>>,
+    pub(super) chunks: HashMap<String, ChunkMark>,
+    /// `WorkerConfig::max_content_chars`.
+    max_content_chars: usize,
 }
 
 impl DirtyBuffer {
+    pub(super) fn new(max_content_chars: usize) -> Self {
+        Self {
+            max_content_chars,
+            ..Self::default()
+        }
+    }
+
+    /// Enfileira mensagens com o `content` já limpo; o `raw_json`
+    /// segue intacto com o texto completo.
+    pub(super) fn push_messages(&mut self, account_id: String, mut messages: Vec<MessageData>) {
+        for m in &mut messages {
+            if let Some(content) = &mut m.content {
+                *content = sanitize_content(content, self.max_content_chars);
+            }
+        }
+        self.messages
+            .entry(account_id)
+            .or_default()
+            .extend(messages);
+    }
+
+    pub(super) fn push_edit(&mut self, account_id: String, mut edit: PendingEdit) {
+        edit.new_content = sanitize_content(&edit.new_content, self.max_content_chars);
+        self.edits.entry(account_id).or_default().push(edit);
+    }
+
+
     pub(super) fn is_empty(&self) -> bool {
-        self.messages.is_empty() && self.contacts.is_empty() && self.groups.is_empty()
+        self.messages.is_empty()
+            && self.contacts.is_empty()
+            && self.groups.is_empty()
+            && self.edits.is_empty()
+            && self.chunks.is_empty()
+    }
+    /// Um stream de history sync terminou — vale flushar já em vez de
+    /// esperar a janela.
+    pub(super) fn stream_ended(&self) -> bool {
+        self.chunks.values().any(|c| c.is_last)
+    }
+    pub(super) fn contacts_count(&self) -> usize {
+        self.contacts.values().map(|v| v.len()).sum()
+    }
+    pub(super) fn groups_count(&self) -> usize {
+        self.groups.values().map(|v| v.len()).sum()
     }
     pub(super) fn total_count(&self) -> usize {
         self.messages.values().map(|v| v.len()).sum::<usize>()
             + self.contacts.values().map(|v| v.len()).sum::<usize>()
             + self.groups.values().map(|v| v.len()).sum::<usize>()
+            + self.edits.values().map(|v| v.len()).sum::<usize>()
     }
 }
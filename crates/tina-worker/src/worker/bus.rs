@@ -0,0 +1,162 @@
+// Event fan-out. Everything inside the worker sends `WorkerEvent`s into
+// one internal mpsc; a task drains it into the receiver handed out by
+// `take_event_receiver` (once, as before) and into a broadcast that
+// any number of `subscribe_events` callers can listen on.
+//
+// The two sides behave differently on purpose. The mpsc keeps its
+// back-pressure: while its receiver lives and isn't drained, the task
+// waits, and so does the dispatcher behind it. It only exists once
+// someone takes it — a frontend that only subscribes never has a
+// queue filling up with nobody to drain it. The broadcast never
+// waits: a subscriber that falls more than the bus capacity behind
+// loses the oldest events (`Lagged`), which `recv_event` logs and
+// skips.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{broadcast, mpsc};
+
+use crate::events::WorkerEvent;
+
+use super::pressure::QueuePressure;
+
+/// The `take_event_receiver` side of the fan-out.
+#[derive(Clone)]
+pub(super) struct LegacyChannel {
+    capacity: usize,
+    state: Arc<Mutex<LegacyState>>,
+}
+
+enum LegacyState {
+    /// Nobody took the receiver yet: events only go to the broadcast.
+    Untaken,
+    Open(mpsc::Sender<WorkerEvent>),
+    /// Receiver taken and dropped.
+    Closed,
+}
+
+impl LegacyChannel {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Arc::new(Mutex::new(LegacyState::Untaken)),
+        }
+    }
+
+    /// The receiver, the first time only. Events sent before the call
+    /// aren't queued for it.
+    pub(super) fn take_receiver(&self) -> Option<mpsc::Receiver<WorkerEvent>> {
+        let mut state = self.state.lock().ok()?;
+        if !matches!(*state, LegacyState::Untaken) {
+            return None;
+        }
+        let (tx, rx) = mpsc::channel(self.capacity);
+        *state = LegacyState::Open(tx);
+        Some(rx)
+    }
+
+    fn sender(&self) -> Option<mpsc::Sender<WorkerEvent>> {
+        match &*self.state.lock().ok()? {
+            LegacyState::Open(tx) => Some(tx.clone()),
+            LegacyState::Untaken | LegacyState::Closed => None,
+        }
+    }
+
+    fn close(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = LegacyState::Closed;
+        }
+    }
+}
+
+pub(super) fn spawn_fanout(
+    mut inner_rx: mpsc::Receiver<WorkerEvent>,
+    legacy: LegacyChannel,
+    bus: broadcast::Sender<WorkerEvent>,
+    bus_capacity: usize,
+) {
+    tokio::spawn(async move {
+        let mut ui_pressure = QueuePressure::new("worker→UI");
+        let mut bus_pressure = QueuePressure::new("worker→subscribers");
+        while let Some(event) = inner_rx.recv().await {
+            if bus.receiver_count() > 0 {
+                let _ = bus.send(event.clone());
+                bus_pressure.observe(bus.len(), bus_capacity);
+            }
+            let Some(tx) = legacy.sender() else {
+                continue;
+            };
+            if tx.send(event).await.is_err() {
+                // Receiver descartado: segue só com o broadcast.
+                legacy.close();
+            } else {
+                ui_pressure.observe_sender(&tx);
+            }
+        }
+    });
+}
+
+/// Next event from a `TinaWorker::subscribe_events` receiver. Lag is
+/// logged with the number of events lost and skipped; `None` once the
+/// worker is gone.
+pub async fn recv_event(rx: &mut broadcast::Receiver<WorkerEvent>) -> Option<WorkerEvent> {
+    loop {
+        match rx.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!("event subscriber lagged, {n} event(s) dropped");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn event(i: usize) -> WorkerEvent {
+        WorkerEvent::AccountReady {
+            account_id: i.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn untaken_receiver_never_blocks_subscribers() {
+        let (inner_tx, inner_rx) = mpsc::channel(2);
+        let legacy = LegacyChannel::new(2);
+        let (bus, mut sub) = broadcast::channel(64);
+        spawn_fanout(inner_rx, legacy, bus, 64);
+        for i in 0..20 {
+            tokio::time::timeout(Duration::from_secs(1), inner_tx.send(event(i)))
+                .await
+                .expect("fan-out stalled")
+                .unwrap();
+        }
+        for i in 0..20 {
+            let Some(WorkerEvent::AccountReady { account_id }) = recv_event(&mut sub).await else {
+                panic!("expected AccountReady");
+            };
+            assert_eq!(account_id, i.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn receiver_is_handed_out_once_and_dropping_it_unblocks() {
+        let (inner_tx, inner_rx) = mpsc::channel(2);
+        let legacy = LegacyChannel::new(2);
+        let rx = legacy.take_receiver().unwrap();
+        assert!(legacy.take_receiver().is_none());
+        let (bus, _) = broadcast::channel(64);
+        spawn_fanout(inner_rx, legacy, bus, 64);
+        drop(rx);
+        for i in 0..20 {
+            tokio::time::timeout(Duration::from_secs(1), inner_tx.send(event(i)))
+                .await
+                .expect("fan-out stalled")
+                .unwrap();
+        }
+    }
+}
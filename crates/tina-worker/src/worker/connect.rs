@@ -0,0 +1,192 @@
+// Connect timeout. `start_account` arms a timer per account; any event
+// nanachi sends for that account (QR, pairing code, Connected, even an
+// error) disarms it. If it fires, whatsmeow never answered: the UI gets
+// `WorkerEvent::ConnectTimedOut` and, when configured, the account is
+// stopped so a retry starts from scratch.
+// Same generation scheme as `QrWatch`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use tina_core::IpcCommand;
+use tina_ipc::CommandSender;
+
+use crate::events::WorkerEvent;
+
+use super::connection::{AccountConnectionState, ConnectionStates};
+
+#[derive(Clone, Default)]
+pub(super) struct ConnectWatch {
+    generations: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+/// O que o timer precisa para parar a conta sozinho. `None` só avisa.
+pub(super) struct TimeoutStop {
+    pub(super) commands: CommandSender,
+    pub(super) connections: ConnectionStates,
+}
+
+impl ConnectWatch {
+    pub(super) fn arm(
+        &self,
+        account_id: &str,
+        timeout: Duration,
+        stop: Option<TimeoutStop>,
+        event_tx: &mpsc::Sender<WorkerEvent>,
+    ) {
+        let generation = self.bump(account_id);
+        let generations = self.generations.clone();
+        let event_tx = event_tx.clone();
+        let account_id = account_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let current = generations
+                .lock()
+                .ok()
+                .and_then(|g| g.get(&account_id).copied());
+            if current != Some(generation) {
+                return;
+            }
+            tracing::warn!("{account_id}: no answer from WhatsApp after {timeout:?}");
+            let stopped = stop.is_some();
+            if let Some(stop) = stop {
+                let command = IpcCommand::StopAccount {
+                    account_id: account_id.clone(),
+                    reason: None,
+                };
+                if let Err(e) = stop.commands.send_command(command).await {
+                    tracing::warn!("{account_id}: stop after connect timeout failed: {e}");
+                }
+                stop.connections
+                    .set(&account_id, AccountConnectionState::Disconnected, &event_tx)
+                    .await;
+            }
+            let _ = event_tx
+                .send(WorkerEvent::ConnectTimedOut {
+                    account_id,
+                    stopped,
+                })
+                .await;
+        });
+    }
+
+    /// Chegou algo da conta (ou ela foi parada): o timer não dispara.
+    pub(super) fn disarm(&self, account_id: &str) {
+        // Conta sem timer armado não precisa de entrada no mapa.
+        if let Ok(mut g) = self.generations.lock()
+            && let Some(n) = g.get_mut(account_id)
+        {
+            *n = n.wrapping_add(1);
+        }
+    }
+
+    /// nanachi parou: nenhum timer pendente vale mais. Avança as
+    /// gerações em vez de limpar o mapa: limpo, o próximo `arm` voltaria
+    /// à geração 1 e casaria com um timer de antes da parada.
+    pub(super) fn reset(&self) {
+        if let Ok(mut g) = self.generations.lock() {
+            for n in g.values_mut() {
+                *n = n.wrapping_add(1);
+            }
+        }
+    }
+
+    fn bump(&self, account_id: &str) -> u64 {
+        let Ok(mut g) = self.generations.lock() else {
+            return 0;
+        };
+        let n = g.entry(account_id.to_string()).or_default();
+        *n = n.wrapping_add(1);
+        *n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn armed(watch: &ConnectWatch, account_id: &str) -> mpsc::Receiver<WorkerEvent> {
+        let (tx, rx) = mpsc::channel(8);
+        watch.arm(account_id, TIMEOUT, None, &tx);
+        rx
+    }
+
+    async fn settle() {
+        tokio::time::sleep(TIMEOUT * 2).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fires_once_after_the_timeout() {
+        let watch = ConnectWatch::default();
+        let mut rx = armed(&watch, "acc1");
+        tokio::time::sleep(TIMEOUT / 2).await;
+        assert!(rx.try_recv().is_err());
+        settle().await;
+        let Ok(WorkerEvent::ConnectTimedOut {
+            account_id,
+            stopped,
+        }) = rx.try_recv()
+        else {
+            panic!("expected ConnectTimedOut");
+        };
+        assert_eq!(account_id, "acc1");
+        assert!(!stopped);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disarm_and_reset_cancel_the_timer() {
+        let watch = ConnectWatch::default();
+        let mut disarmed = armed(&watch, "acc1");
+        let mut other = armed(&watch, "acc2");
+        watch.disarm("acc1");
+        settle().await;
+        assert!(disarmed.try_recv().is_err());
+        assert!(matches!(
+            other.try_recv(),
+            Ok(WorkerEvent::ConnectTimedOut { account_id, .. }) if account_id == "acc2"
+        ));
+
+        let mut reset = armed(&watch, "acc1");
+        watch.reset();
+        settle().await;
+        assert!(reset.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timer_from_before_a_reset_never_matches_a_new_attempt() {
+        let watch = ConnectWatch::default();
+        let mut stale = armed(&watch, "acc1");
+        tokio::time::sleep(TIMEOUT / 2).await;
+        watch.reset();
+        let mut fresh = armed(&watch, "acc1");
+        // Prazo do primeiro timer: nada dispara.
+        tokio::time::sleep(TIMEOUT / 2 + Duration::from_secs(1)).await;
+        assert!(stale.try_recv().is_err());
+        assert!(fresh.try_recv().is_err());
+        settle().await;
+        assert!(matches!(
+            fresh.try_recv(),
+            Ok(WorkerEvent::ConnectTimedOut { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rearming_keeps_only_the_latest_timer() {
+        let watch = ConnectWatch::default();
+        let mut first = armed(&watch, "acc1");
+        tokio::time::sleep(TIMEOUT / 2).await;
+        let mut second = armed(&watch, "acc1");
+        settle().await;
+        assert!(first.try_recv().is_err());
+        assert!(matches!(
+            second.try_recv(),
+            Ok(WorkerEvent::ConnectTimedOut { .. })
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+// Per-account connection state, kept in memory by the worker. Updated
+// from `start_account` and the realtime IPC events; a
+// `ConnectionStateChanged` goes out only when the state actually
+// changes, so repeated `Disconnected`s during a flaky reconnect don't
+// churn the UI.
+//
+// Also whether nanachi itself said `Ready` since the last start:
+// commands sent before that wait for it (see `TinaWorker::is_ready`).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+
+use crate::events::WorkerEvent;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccountConnectionState {
+    /// Not started in this nanachi session, stopped, or dropped for
+    /// good (replaced, banned).
+    #[default]
+    Disconnected,
+    /// `StartAccount` sent, QR round in progress, or whatsmeow is
+    /// retrying after a transient drop.
+    Connecting,
+    Connected,
+    /// Unlinked; only a new pairing brings it back.
+    LoggedOut,
+}
+
+impl AccountConnectionState {
+    /// `start_account` would be a no-op (or a second socket).
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Connecting | Self::Connected)
+    }
+}
+
+/// An account row plus what the worker knows about it right now — what
+/// an account switcher shows. See `TinaWorker::list_accounts_with_status`.
+#[derive(Debug, Clone)]
+pub struct AccountStatus {
+    pub account: tina_db::Account,
+    pub connection: AccountConnectionState,
+    pub is_connected: bool,
+    /// A history sync is running: the account is up and nanachi left a
+    /// sync cursor that `HistorySyncComplete` hasn't cleared yet.
+    pub is_syncing: bool,
+    /// Sum of the account's chat-list unread badges.
+    pub unread_total: i64,
+}
+
+#[derive(Clone)]
+pub(super) struct ConnectionStates {
+    inner: Arc<Mutex<HashMap<String, AccountConnectionState>>>,
+    nanachi_ready: Arc<watch::Sender<bool>>,
+}
+
+impl Default for ConnectionStates {
+    fn default() -> Self {
+        Self {
+            inner: Arc::default(),
+            nanachi_ready: Arc::new(watch::channel(false).0),
+        }
+    }
+}
+
+impl ConnectionStates {
+    pub(super) fn nanachi_ready(&self) -> bool {
+        *self.nanachi_ready.borrow()
+    }
+
+    pub(super) fn set_nanachi_ready(&self, ready: bool) {
+        self.nanachi_ready.send_replace(ready);
+    }
+
+    /// Espera o `Ready` do nanachi por até `timeout`; `false` se não veio.
+    pub(super) async fn wait_nanachi_ready(&self, timeout: Duration) -> bool {
+        let mut rx = self.nanachi_ready.subscribe();
+        matches!(
+            tokio::time::timeout(timeout, rx.wait_for(|ready| *ready)).await,
+            Ok(Ok(_))
+        )
+    }
+
+    pub(super) fn get(&self, account_id: &str) -> AccountConnectionState {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|m| m.get(account_id).copied())
+            .unwrap_or_default()
+    }
+
+    /// Contas em `Connecting` ou `Connected`, em ordem.
+    #[cfg(feature = "network-watch")]
+    pub(super) fn active_accounts(&self) -> Vec<String> {
+        let Ok(m) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let mut active: Vec<String> = m
+            .iter()
+            .filter(|(_, state)| state.is_active())
+            .map(|(id, _)| id.clone())
+            .collect();
+        active.sort();
+        active
+    }
+
+    /// Grava `state` e avisa a UI se mudou.
+    pub(super) async fn set(
+        &self,
+        account_id: &str,
+        state: AccountConnectionState,
+        event_tx: &mpsc::Sender<WorkerEvent>,
+    ) {
+        let previous = match self.inner.lock() {
+            Ok(mut m) => m.insert(account_id.to_string(), state),
+            Err(_) => return,
+        };
+        if previous.unwrap_or_default() != state {
+            let _ = event_tx
+                .send(WorkerEvent::ConnectionStateChanged {
+                    account_id: account_id.to_string(),
+                    state,
+                })
+                .await;
+        }
+    }
+
+    /// nanachi parou: nenhuma conta segue conectada e o próximo start
+    /// espera outro `Ready`. Sem eventos — quem chamou `stop` já sabe.
+    pub(super) fn reset(&self) {
+        if let Ok(mut m) = self.inner.lock() {
+            m.clear();
+        }
+        self.set_nanachi_ready(false);
+    }
+}
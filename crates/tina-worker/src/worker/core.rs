@@ -3,71 +3,420 @@
 // modules.
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{RwLock, broadcast, mpsc};
 
-use tina_core::IpcCommand;
-use tina_db::{ChatRow, MentionCandidate, TinaDb};
-use tina_ipc::NanachiManager;
+use tina_core::{IpcCommand, IpcMessage, MessageType};
+use tina_db::{ChatRow, MentionCandidate, MessageRow, Page, TinaDb};
+use tina_ipc::{CommandSender, NanachiManager, NanachiTransport};
 
-use crate::error::Result;
+use crate::error::{Result, WorkerError};
 use crate::events::WorkerEvent;
 
-use super::dispatcher::dispatcher_loop;
+use super::bus::{LegacyChannel, spawn_fanout};
+use super::connect::{ConnectWatch, TimeoutStop};
+use super::connection::{AccountConnectionState, AccountStatus, ConnectionStates};
+use super::dispatcher::{DispatchCtx, dispatcher_loop};
+use super::feed::ChatFeed;
+use super::idle::IdleWatch;
+use super::initial_sync::{InitialSyncs, SyncPart};
+use super::logout::PendingLogouts;
+#[cfg(feature = "network-watch")]
+use super::network::NetworkWatch;
+use super::number_check::NumberChecks;
+use super::profile::ProfileFetches;
+use super::qr::QrWatch;
+use super::replies::{CommandReply, PendingReplies};
+use super::send::InFlightSend;
+use super::sync_cancel::SyncCancels;
+
+/// Quanto `logout` espera pelo `LoggedOut` do nanachi.
+const LOGOUT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Quanto pin/star esperam o nanachi aceitar o patch de app state.
+const APP_STATE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sem sessão, o primeiro QR chega em poucos segundos; com sessão, o
+/// `Connected`. Passou disso, o socket travou no caminho.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Comandos mandados logo depois do `start` esperam o `Ready` do
+/// nanachi por até isso antes de desistir com `NotReady`.
+const READY_WAIT: Duration = Duration::from_secs(30);
+
+/// Entre um envio e o próximo num broadcast.
+const DEFAULT_BROADCAST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 2 GiB de mídia baixada antes de começar a apagar as menos usadas.
+const DEFAULT_MEDIA_CACHE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Messages per page when the UI opens a chat or scrolls for more.
+pub const DEFAULT_MESSAGE_PAGE_SIZE: i64 = 50;
+
+/// Range `WorkerConfig::message_page_size` is clamped to: a page has to
+/// fill a screen, and a huge one defeats the point of paging. The GTK
+/// chat view trims its window back to 100 rows, so a page can't be
+/// bigger than that.
+pub const MESSAGE_PAGE_SIZE_RANGE: std::ops::RangeInclusive<i64> = 10..=100;
+
+/// Variável de ambiente com o tamanho de página; ausente ou inválida
+/// fica no padrão.
+pub const MESSAGE_PAGE_SIZE_ENV: &str = "TINA_MESSAGE_PAGE_SIZE";
+
+/// Status posts expire on WhatsApp after a day.
+const STATUS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Teto de posts que `get_status_updates` devolve.
+const STATUS_UPDATES_LIMIT: i64 = 500;
+
+/// Channel sizes, the connect timeout, the stored-text cap, the media
+/// cache budget, the broadcast pacing, the message page size and how
+/// nanachi is reached. Bigger
+/// buffers absorb a history-sync burst without stalling, at the cost
+/// of memory and of the UI lagging further behind; smaller ones push
+/// back sooner. Progress events never wait on a full channel (they're
+/// dropped and counted), everything else does, so nothing that
+/// changes state is lost either way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerConfig {
+    /// `WorkerEvent`s queued for the UI. When full, the dispatcher
+    /// waits on the UI and stops reading from nanachi.
+    pub event_capacity: usize,
+    /// nanachi lines queued for the dispatcher. When full, the reader
+    /// stops reading and nanachi blocks writing its stdout/socket.
+    pub ipc_event_capacity: usize,
+    /// Events each `subscribe_events` receiver may fall behind before
+    /// it starts losing the oldest ones. Never slows the worker down.
+    pub bus_capacity: usize,
+    /// How long `start_account` waits for nanachi to say anything about
+    /// the account before emitting `WorkerEvent::ConnectTimedOut`.
+    /// `None` waits forever.
+    pub connect_timeout: Option<Duration>,
+    /// Also stop the account when the connect timeout fires, so the
+    /// next `start_account` is a clean retry.
+    pub stop_on_connect_timeout: bool,
+    /// Characters of message text kept in `messages.content`; the rest
+    /// is cut (see `tina_core::sanitize_content`). `raw_json` keeps
+    /// the full text either way.
+    pub max_content_chars: usize,
+    /// Size the downloaded-media cache may reach before the least
+    /// recently used files are deleted. `None` never evicts.
+    pub media_cache_bytes: Option<u64>,
+    /// Pause between two recipients of `send_broadcast`, to keep the
+    /// account from looking like a spammer. Zero sends back to back.
+    pub broadcast_interval: Duration,
+    /// Messages frontends load per page of a chat (see
+    /// [`TinaWorker::message_page_size`]). Clamped to
+    /// [`MESSAGE_PAGE_SIZE_RANGE`]; defaults to `TINA_MESSAGE_PAGE_SIZE`,
+    /// else 50.
+    pub message_page_size: i64,
+    /// Subprocess or standalone service; defaults to
+    /// [`NanachiTransport::from_env`].
+    pub transport: NanachiTransport,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            event_capacity: 5000,
+            ipc_event_capacity: tina_ipc::DEFAULT_EVENT_CAPACITY,
+            bus_capacity: 1024,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            stop_on_connect_timeout: true,
+            max_content_chars: tina_core::DEFAULT_MAX_CONTENT_CHARS,
+            media_cache_bytes: Some(DEFAULT_MEDIA_CACHE_BYTES),
+            broadcast_interval: DEFAULT_BROADCAST_INTERVAL,
+            message_page_size: std::env::var(MESSAGE_PAGE_SIZE_ENV)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(DEFAULT_MESSAGE_PAGE_SIZE),
+            transport: NanachiTransport::from_env(),
+        }
+    }
+}
 
 pub struct TinaWorker {
     pub(super) db: Arc<TinaDb>,
     pub(super) nanachi: Arc<RwLock<NanachiManager>>,
+    /// Cópia do lado de escrita do stdin, preenchida no `start`. Os
+    /// envios clonam daqui e não tocam o `nanachi` — só `start`/`stop`
+    /// pegam o write lock, então contas diferentes mandam em paralelo.
+    pub(super) commands: Arc<std::sync::RwLock<Option<CommandSender>>>,
     pub(super) event_tx: mpsc::Sender<WorkerEvent>,
-    pub(super) event_rx: Option<mpsc::Receiver<WorkerEvent>>,
+    /// Receiver de `take_event_receiver`; ver `bus`.
+    pub(super) legacy: LegacyChannel,
+    /// Cópia de todo evento para os `subscribe_events`; ver `bus`.
+    pub(super) bus: broadcast::Sender<WorkerEvent>,
     /// Chats atualmente abertos como tab na UI, por conta. Apenas chats
     /// presentes aqui recebem `MessagesAppended` no flush — durante
     /// sync, dezenas de chats fechados receberiam eventos inúteis e a
     /// UI travava.
     pub(super) open_chats: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    pub(super) feed: ChatFeed,
+    pub(super) logouts: PendingLogouts,
+    pub(super) connections: ConnectionStates,
+    pub(super) replies: PendingReplies,
+    pub(super) connect_watch: ConnectWatch,
+    pub(super) sync_cancels: SyncCancels,
+    pub(super) idle: IdleWatch,
+    #[cfg(feature = "network-watch")]
+    pub(super) network: NetworkWatch,
+    pub(super) profiles: ProfileFetches,
+    pub(super) number_checks: NumberChecks,
+    pub(super) initial_syncs: InitialSyncs,
+    pub(super) connect_timeout: Option<Duration>,
+    pub(super) stop_on_connect_timeout: bool,
+    pub(super) max_content_chars: usize,
+    pub(super) media_cache_bytes: Option<u64>,
+    pub(super) broadcast_interval: Duration,
+    pub(super) message_page_size: i64,
+    /// `new_offline`: nanachi is never started; see `ensure_online`.
+    pub(super) offline: bool,
+}
+
+fn clamp_page_size(size: i64) -> i64 {
+    let clamped = size.clamp(*MESSAGE_PAGE_SIZE_RANGE.start(), *MESSAGE_PAGE_SIZE_RANGE.end());
+    if clamped != size {
+        tracing::warn!("message page size {size} out of range, using {clamped}");
+    }
+    clamped
 }
 
 impl TinaWorker {
-    pub async fn new(nanachi_dir: PathBuf) -> Result<Self> {
-        let db = TinaDb::new().await?;
-        let nanachi = NanachiManager::new(nanachi_dir);
-        let (event_tx, event_rx) = mpsc::channel(5000);
+    /// `db_path` explícito tem prioridade; `None` usa `TINA_DB_PATH` ou
+    /// o caminho padrão do usuário.
+    pub async fn new(nanachi_dir: PathBuf, db_path: Option<PathBuf>) -> Result<Self> {
+        Self::with_config(nanachi_dir, db_path, WorkerConfig::default()).await
+    }
+
+    pub async fn with_config(
+        nanachi_dir: PathBuf,
+        db_path: Option<PathBuf>,
+        config: WorkerConfig,
+    ) -> Result<Self> {
+        let db = TinaDb::new_with_optional_path(db_path.as_deref()).await?;
+        let nanachi = NanachiManager::with_capacity(
+            nanachi_dir,
+            config.transport,
+            config.ipc_event_capacity,
+        );
+        let (event_tx, inner_rx) = mpsc::channel(config.event_capacity.max(1));
+        let legacy = LegacyChannel::new(config.event_capacity);
+        let bus_capacity = config.bus_capacity.max(1);
+        let (bus, _) = broadcast::channel(bus_capacity);
+        spawn_fanout(inner_rx, legacy.clone(), bus.clone(), bus_capacity);
         Ok(Self {
             db: Arc::new(db),
             nanachi: Arc::new(RwLock::new(nanachi)),
+            commands: Arc::new(std::sync::RwLock::new(None)),
             event_tx,
-            event_rx: Some(event_rx),
+            legacy,
+            bus,
             open_chats: Arc::new(RwLock::new(HashMap::new())),
+            feed: ChatFeed::new(),
+            logouts: PendingLogouts::default(),
+            connections: ConnectionStates::default(),
+            replies: PendingReplies::default(),
+            connect_watch: ConnectWatch::default(),
+            sync_cancels: SyncCancels::default(),
+            idle: IdleWatch::default(),
+            #[cfg(feature = "network-watch")]
+            network: NetworkWatch::default(),
+            profiles: ProfileFetches::default(),
+            number_checks: NumberChecks::default(),
+            initial_syncs: InitialSyncs::default(),
+            connect_timeout: config.connect_timeout,
+            stop_on_connect_timeout: config.stop_on_connect_timeout,
+            max_content_chars: config.max_content_chars,
+            media_cache_bytes: config.media_cache_bytes,
+            broadcast_interval: config.broadcast_interval,
+            message_page_size: clamp_page_size(config.message_page_size),
+            offline: false,
         })
     }
 
+    /// Read-only worker over the stored data: no nanachi (and no bun or
+    /// Go toolchain needed). `start` does nothing and anything that
+    /// would talk to WhatsApp fails with `WorkerError::OfflineMode`;
+    /// chats, messages and search read the DB as usual.
+    pub async fn new_offline(db_path: Option<PathBuf>) -> Result<Self> {
+        let mut worker =
+            Self::with_config(PathBuf::new(), db_path, WorkerConfig::default()).await?;
+        worker.offline = true;
+        Ok(worker)
+    }
+
+    /// Where `new(_, None)` opens the database: `TINA_DB_PATH`, else
+    /// `<data_dir>/tina.db`. Nothing is opened or created.
+    pub fn default_db_path() -> Result<PathBuf> {
+        Ok(TinaDb::get_db_path()?)
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// How many messages a frontend should ask for when it opens a chat
+    /// or loads the next page; a shorter page means the history ran out.
+    pub fn message_page_size(&self) -> i64 {
+        self.message_page_size
+    }
+
+    /// The back-pressured event receiver: while it's alive and not
+    /// drained, the worker waits for it. `Some` on the first call only;
+    /// events from before the call aren't queued for it. Frontends that
+    /// only need `subscribe_events` never have to take it.
     pub fn take_event_receiver(&mut self) -> Option<mpsc::Receiver<WorkerEvent>> {
-        self.event_rx.take()
+        self.legacy.take_receiver()
+    }
+
+    /// Another receiver of every `WorkerEvent` from now on, alongside
+    /// the one `take_event_receiver` hands out. Read it with
+    /// [`recv_event`](crate::recv_event) to get lag logged instead of
+    /// returned as an error.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<WorkerEvent> {
+        self.bus.subscribe()
     }
 
     pub async fn start(&self) -> Result<()> {
+        if self.offline {
+            tracing::info!("offline mode: not starting nanachi");
+            return Ok(());
+        }
         let mut nanachi = self.nanachi.write().await;
+        self.connections.set_nanachi_ready(false);
         nanachi.start().await?;
+        if let Ok(mut commands) = self.commands.write() {
+            *commands = nanachi.command_sender();
+        }
         let ipc_rx = nanachi.take_event_receiver();
         let outstanding = nanachi.outstanding_handle();
+        let metrics = nanachi.metrics_handle();
 
         if let Some(rx) = ipc_rx {
-            let db = self.db.clone();
-            let event_tx = self.event_tx.clone();
-            let open_chats = self.open_chats.clone();
-            tokio::spawn(dispatcher_loop(db, event_tx, open_chats, outstanding, rx));
+            let ctx = DispatchCtx {
+                db: self.db.clone(),
+                event_tx: self.event_tx.clone(),
+                open_chats: self.open_chats.clone(),
+                feed: self.feed.clone(),
+                qr_watch: QrWatch::default(),
+                logouts: self.logouts.clone(),
+                connections: self.connections.clone(),
+                replies: self.replies.clone(),
+                connect_watch: self.connect_watch.clone(),
+                sync_cancels: self.sync_cancels.clone(),
+                number_checks: self.number_checks.clone(),
+                initial_syncs: self.initial_syncs.clone(),
+                outstanding,
+                metrics,
+                max_content_chars: self.max_content_chars,
+            };
+            tokio::spawn(dispatcher_loop(ctx, rx));
         }
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
         let mut nanachi = self.nanachi.write().await;
+        if let Ok(mut commands) = self.commands.write() {
+            *commands = None;
+        }
+        self.connections.reset();
+        self.connect_watch.reset();
+        self.sync_cancels.reset();
+        self.idle.reset();
         nanachi.stop().await?;
         Ok(())
     }
 
+    /// `true` once nanachi has started and said `Ready`. Commands sent
+    /// before that wait for it; sent without a started worker they
+    /// fail with `WorkerError::NotReady`.
+    pub fn is_ready(&self) -> bool {
+        let started = self.commands.read().map(|c| c.is_some()).unwrap_or(false);
+        started && self.connections.nanachi_ready()
+    }
+
+    /// Manda um comando pro nanachi sem segurar o `RwLock` do manager
+    /// durante o await.
+    pub(super) async fn send_command(&self, command: IpcCommand) -> Result<()> {
+        self.command_sender().await?.send_command(command).await?;
+        Ok(())
+    }
+
+    /// Como `send_command`, mas espera o `CommandResult` correspondente
+    /// por até `timeout`.
+    pub(super) async fn send_and_wait(
+        &self,
+        command: IpcCommand,
+        timeout: Duration,
+    ) -> Result<CommandReply> {
+        let sender = self.command_sender().await?;
+        let message = IpcMessage::new_command(command);
+        let id = message.id.clone();
+        let reply = self.replies.register(&message);
+        if let Err(e) = sender.send_message(message).await {
+            self.replies.forget(&id);
+            return Err(e.into());
+        }
+        match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(reply)) => Ok(reply),
+            // Sender dropado: o dispatcher morreu junto com o nanachi.
+            Ok(Err(_)) => Err(WorkerError::ChannelClosed),
+            Err(_) => {
+                self.replies.forget(&id);
+                Err(WorkerError::ReplyTimeout)
+            }
+        }
+    }
+
+    /// O stdin do nanachi, depois do `Ready`. Sem `start` falha na
+    /// hora; recém-iniciado, espera até `READY_WAIT`.
+    pub(super) async fn command_sender(&self) -> Result<CommandSender> {
+        self.ensure_online()?;
+        let sender = self
+            .commands
+            .read()
+            .ok()
+            .and_then(|c| c.clone())
+            .ok_or(WorkerError::NotReady)?;
+        if !self.connections.wait_nanachi_ready(READY_WAIT).await {
+            return Err(WorkerError::NotReady);
+        }
+        Ok(sender)
+    }
+
+    /// Para quem grava no DB antes de mandar o comando: no modo offline
+    /// falha antes de deixar uma linha `pending` que nunca sai.
+    pub(super) fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(WorkerError::OfflineMode);
+        }
+        Ok(())
+    }
+
+    // ---- Backup / restore ----
+
+    /// Snapshot of the whole database into `path` (must not exist).
+    /// Safe while running.
+    pub async fn backup(&self, path: &Path) -> Result<()> {
+        Ok(self.db.backup_to(path).await?)
+    }
+
+    /// Replaces the database contents with the snapshot at `path`.
+    /// Refused while nanachi is running: the dispatcher would keep
+    /// flushing rows on top of the restored state.
+    pub async fn restore(&self, path: &Path) -> Result<()> {
+        let running = self.commands.read().map(|c| c.is_some()).unwrap_or(true);
+        if running {
+            return Err(WorkerError::RestoreWhileRunning);
+        }
+        Ok(self.db.restore_from(path).await?)
+    }
+
     // ---- Account management (delegado a tina-db) ----
 
     pub async fn create_account(
@@ -82,42 +431,213 @@ impl TinaWorker {
         Ok(self.db.get_account(account_id).await?)
     }
 
+    /// Change the account's display name; blank clears it.
+    pub async fn rename_account(&self, account_id: &str, name: &str) -> Result<tina_db::Account> {
+        Ok(self.db.rename_account(account_id, name).await?)
+    }
+
     pub async fn list_accounts(&self) -> Result<Vec<tina_db::Account>> {
         Ok(self.db.list_accounts().await?)
     }
 
+    /// `list_accounts` merged with the live connection state, whether a
+    /// history sync is running and the unread total of each account.
+    pub async fn list_accounts_with_status(&self) -> Result<Vec<AccountStatus>> {
+        let accounts = self.db.list_accounts().await?;
+        let unread = self.db.unread_totals().await?;
+        let mut out = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let connection = self.connections.get(&account.id);
+            // Cursor de uma sessão que morreu no meio fica no banco até
+            // o próximo sync; só conta com a conta de pé.
+            let is_syncing = connection.is_active()
+                && self.db.get_sync_cursor(&account.id).await?.is_some();
+            out.push(AccountStatus {
+                unread_total: unread.get(&account.id).copied().unwrap_or(0),
+                connection,
+                is_connected: connection == AccountConnectionState::Connected,
+                is_syncing,
+                account,
+            });
+        }
+        Ok(out)
+    }
+
     pub async fn delete_account(&self, account_id: &str) -> Result<()> {
+        self.idle.clear(account_id);
+        self.profiles.forget_account(account_id);
+        self.number_checks.forget_account(account_id);
+        self.initial_syncs.forget_account(account_id);
+        #[cfg(feature = "network-watch")]
+        self.network.forget_account(account_id);
         Ok(self.db.delete_account(account_id).await?)
     }
 
+    pub async fn get_account_stats(&self, account_id: &str) -> Result<tina_db::AccountStats> {
+        Ok(self.db.get_account_stats(account_id).await?)
+    }
+
+    pub async fn get_contact_count(&self, account_id: &str) -> Result<i64> {
+        Ok(self.db.get_contact_count(account_id).await?)
+    }
+
+    pub async fn get_group_count(&self, account_id: &str) -> Result<i64> {
+        Ok(self.db.get_group_count(account_id).await?)
+    }
+
+    /// No-op while the account is already connecting or connected, so
+    /// a second caller can't open a second socket.
     pub async fn start_account(&self, account_id: &str) -> Result<()> {
         let _ = self.db.get_account(account_id).await?;
-        let nanachi = self.nanachi.read().await;
-        nanachi
+        if self.connections.get(account_id).is_active() {
+            tracing::debug!("start_account({account_id}): already active, skipping");
+            return Ok(());
+        }
+        let commands = self.command_sender().await?;
+        // Arma antes de mandar: um QR rápido chegaria antes do timer e
+        // não teria o que desarmar.
+        if let Some(timeout) = self.connect_timeout {
+            let stop = self.stop_on_connect_timeout.then(|| TimeoutStop {
+                commands: commands.clone(),
+                connections: self.connections.clone(),
+            });
+            self.connect_watch
+                .arm(account_id, timeout, stop, &self.event_tx);
+        }
+        self.sync_cancels.clear(account_id);
+        if let Err(e) = commands
             .send_command(IpcCommand::StartAccount {
                 account_id: account_id.to_string(),
             })
-            .await?;
+            .await
+        {
+            self.connect_watch.disarm(account_id);
+            return Err(e.into());
+        }
+        self.set_connection(account_id, AccountConnectionState::Connecting)
+            .await;
         Ok(())
     }
 
+    async fn set_connection(&self, account_id: &str, state: AccountConnectionState) {
+        self.connections
+            .set(account_id, state, &self.event_tx)
+            .await;
+    }
+
+    /// Last known connection state. `Disconnected` for accounts never
+    /// started since nanachi came up.
+    pub fn connection_state(&self, account_id: &str) -> AccountConnectionState {
+        self.connections.get(account_id)
+    }
+
     pub async fn stop_account(&self, account_id: &str) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::StopAccount {
+        self.connect_watch.disarm(account_id);
+        #[cfg(feature = "network-watch")]
+        self.network.forget_account(account_id);
+        self.send_command(IpcCommand::StopAccount {
+            account_id: account_id.to_string(),
+            reason: None,
+        })
+        .await?;
+        self.set_connection(account_id, AccountConnectionState::Disconnected)
+            .await;
+        Ok(())
+    }
+
+    /// Unlinks the device and waits for nanachi's `LoggedOut`, which
+    /// clears the auth state (and, with `purge`, the account's chats,
+    /// contacts and messages) before `WorkerEvent::LoggedOut` goes out.
+    /// If nanachi doesn't answer within 10 s the local cleanup runs
+    /// anyway — the session is gone either way.
+    pub async fn logout(&self, account_id: &str, purge: bool) -> Result<()> {
+        let done = self.logouts.register(account_id, purge);
+        if let Err(e) = self
+            .send_command(IpcCommand::Logout {
+                account_id: account_id.to_string(),
+            })
+            .await
+        {
+            self.logouts.take(account_id);
+            return Err(e);
+        }
+        if let Ok(Ok(())) = tokio::time::timeout(LOGOUT_TIMEOUT, done).await {
+            return Ok(());
+        }
+        // Sem entrada = o handler do LoggedOut chegou primeiro.
+        let Some(pending) = self.logouts.take(account_id) else {
+            return Ok(());
+        };
+        tracing::warn!("no LoggedOut from nanachi for {account_id}; clearing locally");
+        self.db.clear_auth_state(account_id, pending.purge).await?;
+        self.set_connection(account_id, AccountConnectionState::LoggedOut)
+            .await;
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::LoggedOut {
                 account_id: account_id.to_string(),
+                purged: pending.purge,
             })
+            .await;
+        Ok(())
+    }
+
+    /// "Clear local data": wipes the account's messages, chats,
+    /// contacts and groups but keeps the account row and the pairing,
+    /// so nothing has to be scanned again — the next connect syncs
+    /// contacts and groups back, messages return as they arrive.
+    pub async fn clear_history(&self, account_id: &str) -> Result<()> {
+        self.db.get_account(account_id).await?;
+        self.db
+            .purge_account_data(account_id, tina_db::PurgeOptions::all())
             .await?;
         Ok(())
     }
 
-    pub async fn logout_account(&self, account_id: &str) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::Logout {
+    /// "Clear chat": deletes the messages of one chat (`chat_jid` may be
+    /// any of its aliases) and marks it read; starred messages stay.
+    /// Local only — the phone keeps its copy. Returns how many went;
+    /// emits `ChatCleared` and the updated chat row.
+    pub async fn clear_chat(&self, account_id: &str, chat_jid: &str) -> Result<u64> {
+        let deleted = self.db.delete_chat_messages(account_id, chat_jid).await?;
+        let chat_id = self
+            .db
+            .get_chat_by_alias(account_id, chat_jid)
+            .await?
+            .map_or_else(|| chat_jid.to_string(), |c| c.chat_id);
+        let kept = self
+            .db
+            .get_message_rows_by_chat(account_id, &chat_id, self.message_page_size, 0)
+            .await?;
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::ChatCleared {
                 account_id: account_id.to_string(),
+                chat_id: chat_id.clone(),
+                kept,
             })
-            .await?;
+            .await;
+        let rows = self.db.get_chat_rows(account_id, &[chat_id]).await?;
+        if !rows.is_empty() {
+            let _ = self
+                .event_tx
+                .send(WorkerEvent::ChatsUpserted {
+                    account_id: account_id.to_string(),
+                    rows,
+                    messages_written: 0,
+                })
+                .await;
+        }
+        Ok(deleted)
+    }
+
+    /// Nova rodada de QR depois de `QrExpired`. Só vale pra conta
+    /// ainda não pareada; o nanachi recusa o resto.
+    pub async fn refresh_qr(&self, account_id: &str) -> Result<()> {
+        self.send_command(IpcCommand::GetQrCode {
+            account_id: account_id.to_string(),
+        })
+        .await?;
         Ok(())
     }
 
@@ -125,15 +645,31 @@ impl TinaWorker {
     /// newsletters) e emite eventos de upsert. Cura nomes faltando sem
     /// precisar de re-pareamento.
     pub async fn reconcile_account(&self, account_id: &str) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::Reconcile {
+        // Antes do comando: o primeiro estágio do nanachi não pode chegar
+        // antes deste marco inicial.
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::ReconcileProgress {
                 account_id: account_id.to_string(),
+                stage: String::new(),
+                current: 0,
+                total: 0,
+                indeterminate: true,
             })
-            .await?;
+            .await;
+        self.send_command(IpcCommand::Reconcile {
+            account_id: account_id.to_string(),
+        })
+        .await?;
         Ok(())
     }
 
+    /// `to` pode ser um JID ou um número de telefone em qualquer
+    /// formatação; entrada inválida falha com `InvalidRecipient` antes
+    /// de tocar o banco.
+    ///
+    /// Returns once nanachi answers: `SendFailed` when it rejected the
+    /// message, `Ipc(Timeout)` when it never did.
     pub async fn send_message(
         &self,
         account_id: &str,
@@ -142,45 +678,86 @@ impl TinaWorker {
         mentioned_jids: &[String],
         local_id: &str,
     ) -> Result<()> {
-        let ts = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or_default();
+        self.wake_if_idle(account_id, true).await;
+        let sent = self
+            .begin_send_message(account_id, to, content, mentioned_jids, local_id)
+            .await?;
+        self.finish_text_send(sent).await
+    }
+
+    /// `send_message` up to writing the command: optimistic row, echo,
+    /// `SendMessage` out. `send_broadcast` starts several before
+    /// waiting on any.
+    pub(super) async fn begin_send_message(
+        &self,
+        account_id: &str,
+        to: &str,
+        content: &str,
+        mentioned_jids: &[String],
+        local_id: &str,
+    ) -> Result<InFlightSend> {
+        self.ensure_online()?;
+        // Aceita número digitado ("+55 11 99999-9999") além de JID;
+        // o resto do caminho (insert otimista, IPC) usa só o normalizado.
+        let to = tina_core::normalize_recipient(to)
+            .ok_or_else(|| WorkerError::InvalidRecipient(to.to_string()))?;
+        let to = to.as_str();
+        // A row guarda o texto como o flush o guardaria (é contra ela que
+        // o flush casa cópias re-chaveadas); só o WhatsApp recebe o original.
+        let stored = tina_core::sanitize_content(content, self.max_content_chars);
+        let ts = tina_core::unix_now();
         let mentions_json = if mentioned_jids.is_empty() {
             None
         } else {
             serde_json::to_string(mentioned_jids).ok()
         };
-        if let Err(e) = self
+        match self
             .db
             .insert_pending_text_message(
                 account_id,
                 local_id,
                 to,
-                content,
+                &stored,
                 ts,
                 mentions_json.as_deref(),
             )
             .await
         {
-            tracing::warn!("optimistic insert failed: {e}");
+            Ok(()) => self.emit_pending_echo(account_id, to, local_id).await,
+            Err(e) => tracing::warn!("optimistic insert failed: {e}"),
         }
 
-        let nanachi = self.nanachi.read().await;
-        let mentioned: Vec<tina_core::WaIdentity> = mentioned_jids
-            .iter()
-            .map(|j| tina_core::WaIdentity::parse(j))
-            .collect();
-        nanachi
-            .send_command(IpcCommand::SendMessage {
-                account_id: account_id.to_string(),
-                to: tina_core::WaIdentity::parse(to),
-                content: content.to_string(),
-                mentioned_jids: mentioned,
-                local_id: Some(local_id.to_string()),
-            })
-            .await?;
-        Ok(())
+        self.begin_text_send(account_id, to, content, mentioned_jids, local_id)
+            .await
+    }
+
+    /// Push the freshly inserted pending row as `MessagesAppended` so
+    /// the chat shows it before the IPC round-trip. Unlike the flush
+    /// path this isn't gated on `add_open_chat`: a send is one row the
+    /// caller asked for, and frontends that never register chats (the
+    /// CLI) still see it. `local_id` doubles as the whatsmeow message
+    /// ID, so the receipt/sync for the real message lands on this row
+    /// instead of creating a second one; frontends that already drew
+    /// their own echo dedupe by `message_id`.
+    pub(super) async fn emit_pending_echo(&self, account_id: &str, chat_id: &str, local_id: &str) {
+        match self
+            .db
+            .get_message_rows_by_ids(account_id, &[local_id.to_string()])
+            .await
+        {
+            Ok(messages) if !messages.is_empty() => {
+                let _ = self
+                    .event_tx
+                    .send(WorkerEvent::MessagesAppended {
+                        account_id: account_id.to_string(),
+                        chat_id: chat_id.to_string(),
+                        messages,
+                    })
+                    .await;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("pending echo lookup failed: {e}"),
+        }
     }
 
     /// Send a Read receipt for `message_ids` in `chat_jid`. Groups
@@ -197,15 +774,155 @@ impl TinaWorker {
         if message_ids.is_empty() {
             return Ok(());
         }
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::MarkRead {
-                account_id: account_id.to_string(),
-                chat_jid: tina_core::WaIdentity::parse(chat_jid),
-                sender_jid: tina_core::WaIdentity::parse(sender_jid),
-                message_ids,
-            })
+        self.send_command(IpcCommand::MarkRead {
+            account_id: account_id.to_string(),
+            chat_jid: tina_core::WaIdentity::parse(chat_jid),
+            sender_jid: tina_core::WaIdentity::parse(sender_jid),
+            message_ids,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Pin / unpin a message inside a chat. Pinning a new message when
+    /// the chat already holds `MAX_PINNED_PER_CHAT` fails with
+    /// `PinLimitReached` before anything goes over IPC. The local pin
+    /// is only written after WhatsApp accepts it.
+    pub async fn pin_message(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        pin: bool,
+    ) -> Result<()> {
+        let row = self
+            .db
+            .get_message_rows_by_ids(account_id, &[message_id.to_string()])
+            .await?
+            .into_iter()
+            .next();
+        let already_pinned = row.as_ref().is_some_and(|r| r.is_pinned);
+        if pin && !already_pinned {
+            let count = self.db.count_pinned_messages(account_id, chat_id).await?;
+            if count >= tina_db::MAX_PINNED_PER_CHAT {
+                return Err(WorkerError::PinLimitReached {
+                    chat_id: chat_id.to_string(),
+                    limit: tina_db::MAX_PINNED_PER_CHAT,
+                });
+            }
+        }
+        // Our own messages go without a sender; whatsmeow marks the
+        // key FromMe in that case.
+        let sender_jid = row
+            .filter(|r| !r.is_from_me)
+            .and_then(|r| r.sender_jid)
+            .map(|j| tina_core::WaIdentity::parse(&j));
+
+        let reply = self
+            .send_and_wait(
+                IpcCommand::PinMessage {
+                    account_id: account_id.to_string(),
+                    chat_jid: tina_core::WaIdentity::parse(chat_id),
+                    message_id: message_id.to_string(),
+                    sender_jid,
+                    pin,
+                },
+                APP_STATE_TIMEOUT,
+            )
+            .await?;
+        if !reply.success {
+            return Err(WorkerError::CommandFailed(
+                reply.error.unwrap_or_else(|| "pin failed".into()),
+            ));
+        }
+
+        if pin {
+            self.db
+                .pin_message(account_id, chat_id, message_id, tina_core::unix_now())
+                .await?;
+        } else {
+            self.db.unpin_message(account_id, message_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_pinned_messages(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+    ) -> Result<Vec<tina_db::MessageRow>> {
+        Ok(self.db.get_pinned_messages(account_id, chat_id).await?)
+    }
+
+    /// Star / unstar a message. The local flag is only written after
+    /// nanachi accepts the app-state patch.
+    pub async fn star_message(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        star: bool,
+    ) -> Result<()> {
+        let row = self
+            .db
+            .get_message_rows_by_ids(account_id, &[message_id.to_string()])
+            .await?
+            .into_iter()
+            .next();
+        let from_me = row.as_ref().is_some_and(|r| r.is_from_me);
+        let sender_jid = row
+            .filter(|r| !r.is_from_me)
+            .and_then(|r| r.sender_jid)
+            .map(|j| tina_core::WaIdentity::parse(&j));
+
+        let reply = self
+            .send_and_wait(
+                IpcCommand::StarMessage {
+                    account_id: account_id.to_string(),
+                    chat_jid: tina_core::WaIdentity::parse(chat_id),
+                    message_id: message_id.to_string(),
+                    sender_jid,
+                    from_me,
+                    star,
+                },
+                APP_STATE_TIMEOUT,
+            )
             .await?;
+        if !reply.success {
+            return Err(WorkerError::CommandFailed(
+                reply.error.unwrap_or_else(|| "star failed".into()),
+            ));
+        }
+
+        if star {
+            self.db.star_message(account_id, message_id).await?;
+        } else {
+            self.db.unstar_message(account_id, message_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_starred_messages(
+        &self,
+        account_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<tina_db::MessageRow>> {
+        Ok(self
+            .db
+            .get_starred_messages(account_id, limit, offset)
+            .await?)
+    }
+
+    /// Tell the peer we're (not) typing in `to`. The UI is responsible
+    /// for debouncing; this is a plain passthrough.
+    pub async fn set_typing(&self, account_id: &str, to: &str, is_typing: bool) -> Result<()> {
+        self.send_command(IpcCommand::SetTyping {
+            account_id: account_id.to_string(),
+            to: tina_core::WaIdentity::parse(to),
+            is_typing,
+        })
+        .await?;
         Ok(())
     }
 
@@ -219,18 +936,16 @@ impl TinaWorker {
         mimetype: Option<&str>,
         filename: Option<&str>,
     ) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::SendMedia {
-                account_id: account_id.to_string(),
-                to: tina_core::WaIdentity::parse(to),
-                kind,
-                path: path.to_string(),
-                caption: caption.map(|s| s.to_string()),
-                mimetype: mimetype.map(|s| s.to_string()),
-                filename: filename.map(|s| s.to_string()),
-            })
-            .await?;
+        self.send_command(IpcCommand::SendMedia {
+            account_id: account_id.to_string(),
+            to: tina_core::WaIdentity::parse(to),
+            kind,
+            path: path.to_string(),
+            caption: caption.map(|s| s.to_string()),
+            mimetype: mimetype.map(|s| s.to_string()),
+            filename: filename.map(|s| s.to_string()),
+        })
+        .await?;
         Ok(())
     }
 
@@ -238,13 +953,11 @@ impl TinaWorker {
     /// nanachi é quem faz dedup por sha256 do binário antes de baixar
     /// de novo.
     pub async fn fetch_avatar(&self, account_id: &str, jid: &str) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::FetchAvatar {
-                account_id: account_id.to_string(),
-                jid: tina_core::WaIdentity::parse(jid),
-            })
-            .await?;
+        self.send_command(IpcCommand::FetchAvatar {
+            account_id: account_id.to_string(),
+            jid: tina_core::WaIdentity::parse(jid),
+        })
+        .await?;
         Ok(())
     }
 
@@ -256,27 +969,23 @@ impl TinaWorker {
         jid: &str,
         url: &str,
     ) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::FetchAvatarFromURL {
-                account_id: account_id.to_string(),
-                jid: tina_core::WaIdentity::parse(jid),
-                url: url.to_string(),
-            })
-            .await?;
+        self.send_command(IpcCommand::FetchAvatarFromURL {
+            account_id: account_id.to_string(),
+            jid: tina_core::WaIdentity::parse(jid),
+            url: url.to_string(),
+        })
+        .await?;
         Ok(())
     }
 
     /// Pull a chat's metadata from whatsmeow (newsletter or group).
     /// The Go side picks the right API based on the JID's server.
     pub async fn refresh_chat(&self, account_id: &str, chat_jid: &str) -> Result<()> {
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::RefreshChat {
-                account_id: account_id.to_string(),
-                chat_jid: tina_core::WaIdentity::parse(chat_jid),
-            })
-            .await?;
+        self.send_command(IpcCommand::RefreshChat {
+            account_id: account_id.to_string(),
+            chat_jid: tina_core::WaIdentity::parse(chat_jid),
+        })
+        .await?;
         Ok(())
     }
 
@@ -286,6 +995,54 @@ impl TinaWorker {
         Ok(self.db.list_chat_rows(account_id).await?)
     }
 
+    /// Photos and videos of a chat for the media gallery, newest first.
+    pub async fn get_media_messages(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<MessageRow>> {
+        let types = [MessageType::Image.as_str(), MessageType::Video.as_str()];
+        Ok(self
+            .db
+            .get_messages_filtered(account_id, chat_id, Some(&types), limit, offset)
+            .await?)
+    }
+
+    /// Last message of each chat in `chat_ids`, keyed by chat. One
+    /// query for the whole list; chats with no messages are absent.
+    pub async fn get_last_messages_for_chats(
+        &self,
+        account_id: &str,
+        chat_ids: &[String],
+    ) -> Result<HashMap<String, tina_db::Message>> {
+        Ok(self
+            .db
+            .get_last_messages_for_chats(account_id, chat_ids)
+            .await?)
+    }
+
+    /// Groups with their participant counts, for lists that don't need
+    /// the members themselves.
+    pub async fn list_groups_summary(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<tina_db::GroupSummaryRow>> {
+        Ok(self.db.list_groups_summary(account_id).await?)
+    }
+
+    /// Groups the account shares with `contact_jid` (PN or LID), for
+    /// the contact-info view. Only groups whose member list has synced
+    /// are known.
+    pub async fn get_common_groups(
+        &self,
+        account_id: &str,
+        contact_jid: &str,
+    ) -> Result<Vec<tina_db::GroupSummaryRow>> {
+        Ok(self.db.get_common_groups(account_id, contact_jid).await?)
+    }
+
     pub async fn list_status_authors(
         &self,
         account_id: &str,
@@ -293,6 +1050,22 @@ impl TinaWorker {
         Ok(self.db.list_status_authors(account_id).await?)
     }
 
+    /// Status posts of the last 24 hours, oldest first. The chat
+    /// list leaves `status@broadcast` out; this is the way in.
+    pub async fn get_status_updates(&self, account_id: &str) -> Result<Vec<tina_db::MessageRow>> {
+        let since = tina_core::unix_now() - STATUS_TTL.as_secs() as i64;
+        Ok(self
+            .db
+            .get_message_rows_after(
+                account_id,
+                tina_core::WaIdentity::Status.raw(),
+                since,
+                None,
+                STATUS_UPDATES_LIMIT,
+            )
+            .await?)
+    }
+
     /// Reset `chats.unread_count` for a chat (called from open-chat
     /// + mark-read paths). Returns whether the count actually changed
     /// so callers can skip a redundant ChatsUpserted broadcast.
@@ -300,6 +1073,44 @@ impl TinaWorker {
         Ok(self.db.clear_chat_unread(account_id, chat_id).await? > 0)
     }
 
+    /// Zero every unread badge of the account at once. With
+    /// `send_receipts` the peers also get read receipts for the
+    /// messages behind those badges, in one `MarkChatsRead`; without
+    /// it the change stays local. Returns how many chats were unread.
+    pub async fn mark_all_read(&self, account_id: &str, send_receipts: bool) -> Result<u64> {
+        // Lista antes do UPDATE: depois dele nada mais passa da marca.
+        let unread = if send_receipts {
+            self.db.list_unread_incoming(account_id).await?
+        } else {
+            Vec::new()
+        };
+        let count = self.db.mark_all_chats_read(account_id).await?;
+
+        let mut chats: Vec<tina_core::ReadReceiptItem> = Vec::new();
+        for (chat_id, sender, message_id) in unread {
+            // DM sem remetente gravado: o próprio chat é o remetente.
+            let sender = sender.unwrap_or_else(|| chat_id.clone());
+            match chats.last_mut() {
+                Some(last) if last.chat_jid.raw() == chat_id && last.sender_jid.raw() == sender => {
+                    last.message_ids.push(message_id);
+                }
+                _ => chats.push(tina_core::ReadReceiptItem {
+                    chat_jid: tina_core::WaIdentity::parse(&chat_id),
+                    sender_jid: tina_core::WaIdentity::parse(&sender),
+                    message_ids: vec![message_id],
+                }),
+            }
+        }
+        if !chats.is_empty() {
+            self.send_command(IpcCommand::MarkChatsRead {
+                account_id: account_id.to_string(),
+                chats,
+            })
+            .await?;
+        }
+        Ok(count)
+    }
+
     pub async fn list_recent_sticker_paths(
         &self,
         account_id: &str,
@@ -320,17 +1131,81 @@ impl TinaWorker {
         Ok(())
     }
 
-    pub async fn get_messages(
+    /// Mute a chat until the unix timestamp `until`
+    /// ([`tina_db::MUTED_FOREVER`] for "until unmuted"); `None` unmutes.
+    /// Local only — WhatsApp's own mute state isn't touched.
+    pub async fn mute_chat(
         &self,
         account_id: &str,
         chat_id: &str,
-        limit: i64,
-        offset: i64,
-    ) -> Result<Vec<tina_db::Message>> {
-        Ok(self
-            .db
-            .get_messages_by_chat(account_id, chat_id, limit, offset)
-            .await?)
+        until: Option<i64>,
+    ) -> Result<()> {
+        self.db.set_chat_muted(account_id, chat_id, until).await?;
+        Ok(())
+    }
+
+    /// Per-chat notification toggle, independent of the mute deadline.
+    pub async fn set_chat_notify(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        notify: bool,
+    ) -> Result<()> {
+        self.db.set_chat_notify(account_id, chat_id, notify).await?;
+        Ok(())
+    }
+
+    /// Window, in seconds, within which one of our own messages that
+    /// shows up again under a new ID is treated as the same message
+    /// and dropped on flush. `0` disables the check.
+    pub async fn set_message_dedup_window(&self, secs: i64) -> Result<()> {
+        self.db.set_message_dedup_window(secs).await?;
+        Ok(())
+    }
+
+    /// Newest first. `message_types` keeps only those kinds; `None`
+    /// lists everything. `next_cursor` is the next page's `offset`.
+    pub async fn get_messages(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_types: Option<&[MessageType]>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<MessageRow>> {
+        self.wake_if_idle(account_id, false).await;
+        let types: Option<Vec<&str>> =
+            message_types.map(|ts| ts.iter().map(|t| t.as_str()).collect());
+        let rows = self
+            .db
+            .get_messages_filtered(
+                account_id,
+                chat_id,
+                types.as_deref(),
+                limit.saturating_add(1),
+                offset,
+            )
+            .await?;
+        Ok(Page::from_overfetch(rows, limit, |rows| {
+            Some(offset + rows.len() as i64)
+        }))
+    }
+
+    /// Up to `context` messages on each side of `message_id`, the
+    /// message itself in the middle, oldest first — for opening a chat
+    /// on a search result. Empty if the message isn't in that chat.
+    pub async fn get_chat_messages_around(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        context: i64,
+    ) -> Result<Vec<MessageRow>> {
+        self.wake_if_idle(account_id, false).await;
+        Ok(self
+            .db
+            .get_messages_around(account_id, chat_id, message_id, context)
+            .await?)
     }
 
     /// Mensagens com `sender_name` já resolvido — pra renderização da
@@ -348,38 +1223,71 @@ impl TinaWorker {
             .await?)
     }
 
-    /// Página anterior: mensagens com timestamp estritamente menor que
-    /// `before_ts`, em ordem ASC. Usado pela UI quando o usuário scrolla
-    /// pro topo do thread e queremos carregar mais histórico.
+    /// Total stored for the chat; with the loaded count it tells the
+    /// UI whether "load earlier" has anything left.
+    pub async fn get_chat_message_count(&self, account_id: &str, chat_jid: &str) -> Result<i64> {
+        Ok(self.db.get_chat_message_count(account_id, chat_jid).await?)
+    }
+
+    /// Página anterior à mensagem mais antiga carregada (`before_ts`,
+    /// `before_id`), em ordem ASC. Usado pela UI quando o usuário
+    /// scrolla pro topo do thread e queremos carregar mais histórico.
+    /// `next_cursor` é o `(timestamp, message_id)` da mais antiga da
+    /// página.
     pub async fn get_message_rows_before(
         &self,
         account_id: &str,
         chat_id: &str,
         before_ts: i64,
+        before_id: Option<&str>,
         limit: i64,
-    ) -> Result<Vec<tina_db::MessageRow>> {
-        Ok(self
+    ) -> Result<Page<MessageRow, (i64, String)>> {
+        let mut rows = self
             .db
-            .get_message_rows_before(account_id, chat_id, before_ts, limit)
-            .await?)
+            .get_message_rows_before(
+                account_id,
+                chat_id,
+                before_ts,
+                before_id,
+                limit.saturating_add(1),
+            )
+            .await?;
+        // A linha extra é a mais antiga: vem primeiro em ASC.
+        rows.reverse();
+        let mut page = Page::from_overfetch(rows, limit, |rows| {
+            rows.last().map(|m| (m.timestamp, m.message_id.clone()))
+        });
+        page.items.reverse();
+        Ok(page)
     }
 
-    /// Próxima página descendente: mensagens com timestamp
-    /// estritamente maior que `after_ts`, em ordem ASC. Simétrico de
+    /// Próxima página descendente: mensagens depois de
+    /// `(after_ts, after_id)`, em ordem ASC. Simétrico de
     /// `get_message_rows_before`, usado pela UI quando o usuário
     /// scrolla pro fundo do thread e o factory cortou as últimas N
     /// pelo soft-cap (precisamos buscar de volta as mais recentes).
+    /// O cursor é a mais nova da página.
     pub async fn get_message_rows_after(
         &self,
         account_id: &str,
         chat_id: &str,
         after_ts: i64,
+        after_id: Option<&str>,
         limit: i64,
-    ) -> Result<Vec<tina_db::MessageRow>> {
-        Ok(self
+    ) -> Result<Page<MessageRow, (i64, String)>> {
+        let rows = self
             .db
-            .get_message_rows_after(account_id, chat_id, after_ts, limit)
-            .await?)
+            .get_message_rows_after(
+                account_id,
+                chat_id,
+                after_ts,
+                after_id,
+                limit.saturating_add(1),
+            )
+            .await?;
+        Ok(Page::from_overfetch(rows, limit, |rows| {
+            rows.last().map(|m| (m.timestamp, m.message_id.clone()))
+        }))
     }
 
     pub async fn get_chat(
@@ -390,6 +1298,98 @@ impl TinaWorker {
         Ok(self.db.get_chat(account_id, chat_id).await?)
     }
 
+    /// Every chat with its resolved name and newest message, in one
+    /// query (no per-chat lookups).
+    pub async fn get_chats_basic(&self, account_id: &str) -> Result<Vec<tina_db::ChatPreviewRow>> {
+        Ok(self.db.get_chats_with_names(account_id).await?)
+    }
+
+    /// Name + newest message for just `chat_ids`.
+    pub async fn get_chat_previews(
+        &self,
+        account_id: &str,
+        chat_ids: &[&str],
+    ) -> Result<Vec<tina_db::ChatPreviewRow>> {
+        Ok(self
+            .db
+            .get_chat_previews_batch(account_id, chat_ids)
+            .await?)
+    }
+
+    /// Display name for `jid`: group subject for groups/newsletters,
+    /// contact name → verified name → push name for users. `None`
+    /// while nothing is known about the JID.
+    pub async fn get_chat_name(&self, account_id: &str, jid: &str) -> Result<Option<String>> {
+        Ok(self.db.get_chat_name(account_id, jid).await?)
+    }
+
+    /// Header metadata for one chat opened by JID: resolved name and
+    /// kind, plus description / participant count for groups and
+    /// phone / about text for DMs.
+    pub async fn get_chat_info(
+        &self,
+        account_id: &str,
+        jid: &str,
+    ) -> Result<Option<tina_db::ChatInfo>> {
+        Ok(self.db.get_chat_info(account_id, jid).await?)
+    }
+
+    /// One page of saved contacts matching `query` (names or phone,
+    /// case-insensitive); backs incremental search in a contact picker.
+    /// `next_cursor` is the next page's `offset`.
+    pub async fn search_contacts(
+        &self,
+        account_id: &str,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<tina_db::Contact>> {
+        let rows = self
+            .db
+            .search_contacts(account_id, query, limit.saturating_add(1), offset)
+            .await?;
+        Ok(Page::from_overfetch(rows, limit, |rows| {
+            Some(offset + rows.len() as i64)
+        }))
+    }
+
+    /// Saves a contact the user typed in by hand (a number that may not
+    /// be on WhatsApp yet) so a chat can be started with it. `phone`
+    /// goes through the same normalization as a send recipient. Only
+    /// the saved name is set — push/verified names from the server are
+    /// left alone, and a contact the server already knows stays
+    /// non-local.
+    pub async fn add_local_contact(&self, account_id: &str, phone: &str, name: &str) -> Result<()> {
+        let jid = tina_core::normalize_recipient(phone)
+            .filter(|j| {
+                matches!(
+                    tina_core::WaIdentity::parse(j),
+                    tina_core::WaIdentity::Phone(_)
+                )
+            })
+            .ok_or_else(|| WorkerError::InvalidRecipient(phone.to_string()))?;
+        let contact_id = self.db.register_contact_alias(account_id, &jid).await?;
+        let digits = tina_core::WaIdentity::parse(&jid).user().to_string();
+        let name = name.trim();
+        self.db
+            .upsert_contact_fields(
+                account_id,
+                &contact_id,
+                Some(&jid),
+                None,
+                Some(&digits),
+                None,
+                (!name.is_empty()).then_some(name),
+                None,
+                None,
+                None,
+                None,
+                true,
+            )
+            .await?;
+        Ok(())
+    }
+
     /// Resolved `@`-mention candidates for a chat. Empty for DMs /
     /// newsletters; for groups, returns each participant joined
     /// against the contacts table so the popover can show names
@@ -406,6 +1406,20 @@ impl TinaWorker {
             .await?)
     }
 
+    /// Chat list that also covers saved contacts without any chat
+    /// yet (`include_contacts`). Groups and channels already have a
+    /// chat row from the moment they're synced.
+    pub async fn get_all_chats(
+        &self,
+        account_id: &str,
+        include_contacts: bool,
+    ) -> Result<Vec<ChatRow>> {
+        Ok(self
+            .db
+            .list_all_chat_rows(account_id, include_contacts)
+            .await?)
+    }
+
     pub async fn get_chat_row(&self, account_id: &str, chat_id: &str) -> Result<Option<ChatRow>> {
         let rows = self
             .db
@@ -419,6 +1433,7 @@ impl TinaWorker {
     /// incremental; chats ausentes do set são silenciosamente ignorados
     /// no flush — a UI já tem o snapshot via `ChatsUpserted`.
     pub async fn add_open_chat(&self, account_id: &str, chat_id: &str) {
+        self.wake_if_idle(account_id, false).await;
         let mut guard = self.open_chats.write().await;
         guard
             .entry(account_id.to_string())
@@ -441,8 +1456,79 @@ impl TinaWorker {
         guard.remove(account_id);
     }
 
+    /// Push counterpart of `get_messages`: yields every new row
+    /// persisted for `chat_id` from now on, sender name resolved.
+    /// Independent of `add_open_chat`; dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe_chat(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+    ) -> mpsc::Receiver<tina_db::MessageRow> {
+        self.feed.subscribe(account_id, chat_id)
+    }
+
     // ---- Settings (key/value) ----
 
+    /// Cursor of an interrupted history sync, if the last session
+    /// died before `HistorySyncComplete`. The UI uses it to show
+    /// "resuming sync" right away on startup.
+    pub async fn get_sync_cursor(&self, account_id: &str) -> Result<Option<tina_db::SyncCursor>> {
+        Ok(self.db.get_sync_cursor(account_id).await?)
+    }
+
+    /// Stops the running history sync: nanachi drops further history
+    /// for this session and whatever is still in the pipe is ignored.
+    /// Messages already stored stay. Ends with `HistorySyncComplete`
+    /// carrying the count synced so far, as if the sync had finished.
+    pub async fn cancel_sync(&self, account_id: &str) -> Result<()> {
+        self.sync_cancels.cancel(account_id);
+        let sent = self
+            .send_command(IpcCommand::CancelSync {
+                account_id: account_id.to_string(),
+            })
+            .await;
+        if let Err(e) = sent {
+            self.sync_cancels.clear(account_id);
+            return Err(e);
+        }
+        let messages_count = self
+            .db
+            .get_sync_cursor(account_id)
+            .await?
+            .map_or(0, |c| c.messages_count);
+        self.db.clear_sync_cursor(account_id).await?;
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::HistorySyncComplete {
+                account_id: account_id.to_string(),
+                messages_count,
+            })
+            .await;
+        // O histórico conta como carregado a partir daqui.
+        if self.initial_syncs.mark(account_id, SyncPart::History)
+            && InitialSyncs::persist(&self.db, account_id).await
+        {
+            let _ = self
+                .event_tx
+                .send(WorkerEvent::InitialSyncComplete {
+                    account_id: account_id.to_string(),
+                })
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Whether `InitialSyncComplete` already went out for `account_id`,
+    /// in this session or an earlier one, for a frontend that subscribed
+    /// after it.
+    pub async fn initial_sync_complete(&self, account_id: &str) -> Result<bool> {
+        if self.initial_syncs.is_complete(account_id) {
+            return Ok(true);
+        }
+        Ok(self.db.initial_sync_done(account_id).await?)
+    }
+
     pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
         Ok(self.db.get_setting(key).await?)
     }
@@ -459,6 +1545,11 @@ impl TinaWorker {
         Ok(self.db.clear_all_avatar_paths().await?)
     }
 
+    /// IPC throughput and command latency since the worker was created.
+    pub async fn ipc_stats(&self) -> tina_ipc::IpcStats {
+        self.nanachi.read().await.stats()
+    }
+
     /// Best-effort PID of the running nanachi subprocess, for memory
     /// readouts in the settings dialog. `None` while nanachi hasn't
     /// been started yet.
@@ -466,3 +1557,1345 @@ impl TinaWorker {
         self.nanachi.read().await.child_pid()
     }
 }
+
+impl Drop for TinaWorker {
+    /// Dropped without `stop()`: run it anyway on the current runtime,
+    /// so nanachi still gets `Shutdown` and time to disconnect. With
+    /// no runtime left (or if the runtime goes down before the task
+    /// runs) the manager's own drop kills the child, so it never
+    /// outlives us either way.
+    fn drop(&mut self) {
+        // Os watchers de ociosidade seguram o `event_tx` e os comandos;
+        // sem isso ficariam rodando depois do worker.
+        self.idle.reset();
+        let started = self
+            .commands
+            .write()
+            .ok()
+            .and_then(|mut c| c.take())
+            .is_some();
+        if !started {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        tracing::warn!("TinaWorker dropped without stop(); stopping nanachi");
+        let nanachi = self.nanachi.clone();
+        handle.spawn(async move {
+            if let Err(e) = nanachi.write().await.stop().await {
+                tracing::error!("nanachi stop on drop: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tina_core::{IpcEvent, IpcMessageContent};
+
+    /// Is `pid` still a live (non-zombie) process?
+    fn alive(pid: &str) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .is_ok_and(|stat| !stat.rsplit(')').next().unwrap_or("").trim_start().starts_with('Z'))
+    }
+
+    #[tokio::test]
+    async fn accounts_with_status_merge_connection_sync_and_unread() {
+        let worker = TinaWorker::new_offline(Some(":memory:".into())).await.unwrap();
+        for id in ["up", "syncing", "down", "stale"] {
+            worker.create_account(id, None).await.unwrap();
+        }
+        let db = &worker.db;
+        let chat = db
+            .register_chat_alias("up", "5511999999999@s.whatsapp.net", tina_db::ChatKind::Dm)
+            .await
+            .unwrap();
+        for (id, ts) in [("m1", 100), ("m2", 110)] {
+            db.insert_message("up", id, &chat, None, Some("oi"), "text", ts, false, None)
+                .await
+                .unwrap();
+        }
+        let cursor = tina_db::SyncCursor {
+            sync_type: "INITIAL_BOOTSTRAP".into(),
+            progress: 40,
+            messages_count: 10,
+            updated_at: 0,
+        };
+        // "stale": cursor de uma sessão que morreu, conta parada.
+        for id in ["syncing", "stale"] {
+            db.put_sync_cursor(id, &cursor).await.unwrap();
+        }
+        worker.set_connection("up", AccountConnectionState::Connected).await;
+        worker
+            .set_connection("syncing", AccountConnectionState::Connecting)
+            .await;
+
+        let mut statuses = worker.list_accounts_with_status().await.unwrap();
+        statuses.sort_by_key(|s| s.account.id.clone());
+        let summary: Vec<_> = statuses
+            .iter()
+            .map(|s| (s.account.id.as_str(), s.is_connected, s.is_syncing, s.unread_total))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("down", false, false, 0),
+                ("stale", false, false, 0),
+                ("syncing", false, true, 0),
+                ("up", true, false, 2),
+            ]
+        );
+        assert_eq!(statuses[2].connection, AccountConnectionState::Connecting);
+    }
+
+    #[tokio::test]
+    async fn offline_download_fails_without_touching_the_row() {
+        let worker = TinaWorker::new_offline(Some(":memory:".into()))
+            .await
+            .unwrap();
+        worker.create_account("acc1", None).await.unwrap();
+        let chat = worker
+            .db
+            .register_chat_alias(
+                "acc1",
+                "5511999999999@s.whatsapp.net",
+                tina_db::ChatKind::Dm,
+            )
+            .await
+            .unwrap();
+        worker
+            .db
+            .insert_message("acc1", "img", &chat, None, None, "image", 100, false, None)
+            .await
+            .unwrap();
+        let status = || async {
+            let rows = worker
+                .db
+                .get_message_rows_by_ids("acc1", &["img".to_string()])
+                .await
+                .unwrap();
+            rows[0].media_status.clone()
+        };
+        let before = status().await;
+
+        let err = worker.download_media("acc1", "img").await.unwrap_err();
+        assert!(matches!(err, WorkerError::OfflineMode), "{err}");
+        assert!(matches!(
+            worker.get_media("acc1", "img").await,
+            Err(WorkerError::OfflineMode)
+        ));
+        assert_eq!(status().await, before);
+    }
+
+    const PEER: &str = "5511999999999@s.whatsapp.net";
+
+    /// Started worker on an in-memory DB, talking to `mock`, with nanachi
+    /// already `Ready` and account "acc1" created.
+    async fn mocked_worker(mock: &tina_ipc::MockNanachi) -> TinaWorker {
+        let config = WorkerConfig {
+            transport: NanachiTransport::Mock(mock.clone()),
+            connect_timeout: None,
+            ..WorkerConfig::default()
+        };
+        let worker = TinaWorker::with_config(PathBuf::new(), Some(":memory:".into()), config)
+            .await
+            .unwrap();
+        worker.create_account("acc1", None).await.unwrap();
+        worker.start().await.unwrap();
+        mock.emit(IpcEvent::Ready {
+            account_id: String::new(),
+        })
+        .await
+        .unwrap();
+        worker
+    }
+
+    /// Mensagem recebida de `PEER`, já gravada.
+    async fn incoming_message(mock: &tina_ipc::MockNanachi, worker: &TinaWorker, id: &str) {
+        let line = format!(
+            r#"{{"id":"e-{id}","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{{"message_id":"{id}","chat_jid":"{PEER}","sender_jid":"{PEER}","content":"{id}","message_type":"text","timestamp":100,"is_from_me":false}}]}}}}"#
+        );
+        mock.emit_line(&line).await.unwrap();
+        for _ in 0..100 {
+            if worker.db.get_message("acc1", id).await.unwrap().is_some() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("{id} never stored");
+    }
+
+    /// Row de `id` como a UI a vê.
+    async fn message_row(worker: &TinaWorker, id: &str) -> tina_db::MessageRow {
+        let ids = [id.to_string()];
+        let rows = worker
+            .db
+            .get_message_rows_by_ids("acc1", &ids)
+            .await
+            .unwrap();
+        rows.into_iter().next().expect("no such message")
+    }
+
+    #[tokio::test]
+    async fn subscribe_only_frontend_does_not_stall_the_dispatcher() {
+        let mock = tina_ipc::MockNanachi::new();
+        let config = WorkerConfig {
+            transport: NanachiTransport::Mock(mock.clone()),
+            connect_timeout: None,
+            event_capacity: 2,
+            ..WorkerConfig::default()
+        };
+        let worker = TinaWorker::with_config(PathBuf::new(), Some(":memory:".into()), config)
+            .await
+            .unwrap();
+        let mut events = worker.subscribe_events();
+        worker.start().await.unwrap();
+        // Bem mais eventos que `event_capacity`, sem ninguém pegar o
+        // `take_event_receiver`.
+        for i in 0..20 {
+            mock.emit(IpcEvent::QrCode {
+                account_id: "acc1".into(),
+                qr: format!("2@{i}"),
+                timeout_secs: None,
+            })
+            .await
+            .unwrap();
+        }
+        let mut seen = 0;
+        while seen < 20 {
+            let event =
+                tokio::time::timeout(Duration::from_secs(2), crate::recv_event(&mut events))
+                    .await
+                    .expect("dispatcher stalled")
+                    .unwrap();
+            if matches!(event, WorkerEvent::QrCode { .. }) {
+                seen += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn upserted_messages_land_in_the_db() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let line = format!(
+            r#"{{"id":"e1","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{{"message_id":"m1","chat_jid":"{PEER}","sender_jid":"{PEER}","content":"oi","message_type":"text","timestamp":100,"is_from_me":false}}]}}}}"#
+        );
+        mock.emit_line(&line).await.unwrap();
+
+        // O dispatcher junta as linhas e grava no flush (FLUSH_WINDOW).
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            rows = worker
+                .get_messages("acc1", PEER, None, 50, 0)
+                .await
+                .unwrap()
+                .items;
+            if !rows.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].message_id, "m1");
+        assert_eq!(rows[0].content.as_deref(), Some("oi"));
+    }
+
+    #[tokio::test]
+    async fn pages_report_has_more_until_the_last_one() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let messages: Vec<String> = (1..=3)
+            .map(|i| {
+                format!(
+                    r#"{{"message_id":"m{i}","chat_jid":"{PEER}","sender_jid":"{PEER}","content":"{i}","message_type":"text","timestamp":{},"is_from_me":false}}"#,
+                    i * 100
+                )
+            })
+            .collect();
+        let line = format!(
+            r#"{{"id":"e1","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{}]}}}}"#,
+            messages.join(",")
+        );
+        mock.emit_line(&line).await.unwrap();
+        for _ in 0..50 {
+            if worker.get_chat_message_count("acc1", PEER).await.unwrap() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let first = worker.get_messages("acc1", PEER, None, 2, 0).await.unwrap();
+        assert_eq!(first.items.len(), 2);
+        assert!(first.has_more);
+        assert_eq!(first.next_cursor, Some(2));
+        let last = worker.get_messages("acc1", PEER, None, 2, 2).await.unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert!(!last.has_more);
+        assert_eq!(last.next_cursor, None);
+        // Página cheia que é a última: nada de "tem mais" falso.
+        let full = worker.get_messages("acc1", PEER, None, 3, 0).await.unwrap();
+        assert_eq!(full.items.len(), 3);
+        assert!(!full.has_more);
+
+        let newest = worker
+            .get_message_rows_before("acc1", PEER, 400, None, 2)
+            .await
+            .unwrap();
+        let ids: Vec<_> = newest.items.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, ["m2", "m3"]);
+        assert!(newest.has_more);
+        let (ts, id) = newest.next_cursor.unwrap();
+        assert_eq!((ts, id.as_str()), (200, "m2"));
+        let oldest = worker
+            .get_message_rows_before("acc1", PEER, ts, Some(&id), 2)
+            .await
+            .unwrap();
+        assert_eq!(oldest.items.len(), 1);
+        assert_eq!(oldest.items[0].message_id, "m1");
+        assert!(!oldest.has_more);
+
+        let after = worker
+            .get_message_rows_after("acc1", PEER, 100, Some("m1"), 1)
+            .await
+            .unwrap();
+        assert_eq!(after.items.len(), 1);
+        assert!(after.has_more);
+        let (ts, id) = after.next_cursor.unwrap();
+        assert_eq!((ts, id.as_str()), (200, "m2"));
+        let rest = worker
+            .get_message_rows_after("acc1", PEER, ts, Some(&id), 2)
+            .await
+            .unwrap();
+        assert_eq!(rest.items.len(), 1);
+        assert_eq!(rest.items[0].message_id, "m3");
+        assert!(!rest.has_more);
+    }
+
+    #[tokio::test]
+    async fn send_message_writes_the_command_and_waits_for_the_reply() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        let send = tokio::spawn({
+            let worker = worker.clone();
+            async move {
+                worker
+                    .send_message("acc1", "+55 11 99999-9999", "olá", &[], "local-1")
+                    .await
+            }
+        });
+
+        let message = mock
+            .wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::SendMessage { .. })
+            })
+            .await
+            .expect("no SendMessage written");
+        let IpcMessageContent::Command(IpcCommand::SendMessage {
+            account_id,
+            to,
+            content,
+            local_id,
+            ..
+        }) = &message.content
+        else {
+            unreachable!();
+        };
+        assert_eq!(account_id, "acc1");
+        assert_eq!(to.to_string(), PEER);
+        assert_eq!(content, "olá");
+        assert_eq!(local_id.as_deref(), Some("local-1"));
+        // Ainda esperando a resposta do nanachi.
+        assert!(!send.is_finished());
+
+        mock.reply(&message, true, None).await.unwrap();
+        send.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn pending_echo_reaches_subscribers_without_an_open_chat() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        let mut events = worker.subscribe_events();
+        let send = tokio::spawn({
+            let worker = worker.clone();
+            async move {
+                worker
+                    .send_message("acc1", PEER, "olá", &[], "local-1")
+                    .await
+            }
+        });
+
+        let messages = loop {
+            let event =
+                tokio::time::timeout(Duration::from_secs(5), crate::recv_event(&mut events))
+                    .await
+                    .expect("no MessagesAppended")
+                    .unwrap();
+            if let WorkerEvent::MessagesAppended {
+                chat_id, messages, ..
+            } = event
+            {
+                assert_eq!(chat_id, PEER);
+                break messages;
+            }
+        };
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message_id, "local-1");
+        assert_eq!(messages[0].delivery_status, "pending");
+
+        let message = mock
+            .wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::SendMessage { .. })
+            })
+            .await
+            .expect("no SendMessage written");
+        mock.reply(&message, true, None).await.unwrap();
+        send.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn retry_send_resends_a_failed_message_under_its_id() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        let mut events = worker.subscribe_events();
+        fn is_send(c: &IpcCommand) -> bool {
+            matches!(c, IpcCommand::SendMessage { local_id: Some(id), .. } if id == "local-1")
+        }
+        let status = || async {
+            let ids = ["local-1".to_string()];
+            let rows = worker
+                .db
+                .get_message_rows_by_ids("acc1", &ids)
+                .await
+                .unwrap();
+            rows[0].delivery_status.clone()
+        };
+
+        let send = tokio::spawn({
+            let worker = worker.clone();
+            async move {
+                worker
+                    .send_message("acc1", PEER, "olá", &[], "local-1")
+                    .await
+            }
+        });
+        let first = mock
+            .wait_for_command(Duration::from_secs(5), is_send)
+            .await
+            .expect("no SendMessage written");
+        mock.reply(&first, false, None).await.unwrap();
+        assert!(matches!(
+            send.await.unwrap(),
+            Err(WorkerError::SendFailed(_))
+        ));
+        assert_eq!(status().await, "failed");
+        let failed = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = crate::recv_event(&mut events).await {
+                if let WorkerEvent::MessageSendFailed {
+                    message_id,
+                    content,
+                    ..
+                } = event
+                {
+                    return (message_id, content);
+                }
+            }
+            panic!("event bus closed");
+        })
+        .await
+        .expect("no MessageSendFailed");
+        assert_eq!(failed, ("local-1".to_string(), "olá".to_string()));
+
+        assert!(matches!(
+            worker.retry_send("acc1", "missing").await,
+            Err(WorkerError::MessageNotFound(_))
+        ));
+        let retry = tokio::spawn({
+            let worker = worker.clone();
+            async move { worker.retry_send("acc1", "local-1").await }
+        });
+        let second = mock
+            .wait_for_nth_command(Duration::from_secs(5), 1, is_send)
+            .await
+            .expect("retry wrote no SendMessage");
+        assert_eq!(status().await, "pending");
+        mock.reply(&second, true, None).await.unwrap();
+        retry.await.unwrap().unwrap();
+
+        // Mesma bolha, e uma que não falhou não é reenviada.
+        assert_eq!(
+            worker
+                .db
+                .count_messages_for_chat("acc1", PEER)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(matches!(
+            worker.retry_send("acc1", "local-1").await,
+            Err(WorkerError::NotRetryable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn star_is_only_stored_once_nanachi_accepts_it() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        incoming_message(&mock, &worker, "m1").await;
+        fn is_star(c: &IpcCommand) -> bool {
+            matches!(c, IpcCommand::StarMessage { star: true, .. })
+        }
+
+        for (attempt, accepted) in [false, true].into_iter().enumerate() {
+            let star = tokio::spawn({
+                let worker = worker.clone();
+                async move { worker.star_message("acc1", PEER, "m1", true).await }
+            });
+            let command = mock
+                .wait_for_nth_command(Duration::from_secs(5), attempt, is_star)
+                .await
+                .expect("no StarMessage written");
+            mock.reply(&command, accepted, None).await.unwrap();
+            let result = star.await.unwrap();
+            assert_eq!(result.is_ok(), accepted);
+            if !accepted {
+                assert!(matches!(result, Err(WorkerError::CommandFailed(_))));
+            }
+            assert_eq!(message_row(&worker, "m1").await.is_starred, accepted);
+        }
+    }
+
+    #[tokio::test]
+    async fn pin_is_only_stored_once_whatsapp_accepts_it() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        incoming_message(&mock, &worker, "m1").await;
+        fn is_pin(c: &IpcCommand) -> bool {
+            matches!(c, IpcCommand::PinMessage { pin: true, .. })
+        }
+
+        for (attempt, accepted) in [false, true].into_iter().enumerate() {
+            let pin = tokio::spawn({
+                let worker = worker.clone();
+                async move { worker.pin_message("acc1", PEER, "m1", true).await }
+            });
+            let command = mock
+                .wait_for_nth_command(Duration::from_secs(5), attempt, is_pin)
+                .await
+                .expect("no PinMessage written");
+            mock.reply(&command, accepted, None).await.unwrap();
+            let result = pin.await.unwrap();
+            assert_eq!(result.is_ok(), accepted);
+            if !accepted {
+                assert!(matches!(result, Err(WorkerError::CommandFailed(_))));
+            }
+            assert_eq!(message_row(&worker, "m1").await.is_pinned, accepted);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejection_is_recorded_after_the_caller_gives_up() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        let mut events = worker.subscribe_events();
+        let send = tokio::spawn({
+            let worker = worker.clone();
+            async move {
+                worker
+                    .send_message("acc1", PEER, "olá", &[], "local-1")
+                    .await
+            }
+        });
+        let message = mock
+            .wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::SendMessage { .. })
+            })
+            .await
+            .expect("no SendMessage written");
+        send.abort();
+        let _ = send.await;
+
+        mock.reply(&message, false, None).await.unwrap();
+        let failed = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = crate::recv_event(&mut events).await {
+                if let WorkerEvent::MessageSendFailed { message_id, .. } = event {
+                    return message_id;
+                }
+            }
+            panic!("event bus closed");
+        })
+        .await
+        .expect("no MessageSendFailed");
+        assert_eq!(failed, "local-1");
+        let ids = ["local-1".to_string()];
+        let rows = worker
+            .db
+            .get_message_rows_by_ids("acc1", &ids)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].delivery_status, "failed");
+    }
+
+    #[cfg(feature = "network-watch")]
+    #[tokio::test]
+    async fn network_return_restarts_accounts_in_parallel() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        for id in ["acc2", "acc3"] {
+            worker.create_account(id, None).await.unwrap();
+        }
+        for id in ["acc1", "acc2", "acc3"] {
+            worker.start_account(id).await.unwrap();
+        }
+        let stops = |mock: &tina_ipc::MockNanachi| {
+            mock.commands()
+                .into_iter()
+                .filter_map(|c| match c {
+                    IpcCommand::StopAccount { account_id, .. } => Some(account_id),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        worker.notify_network_changed(false).await;
+        // Parada à mão durante a queda: fica parada.
+        let stop = tokio::spawn({
+            let worker = worker.clone();
+            async move { worker.stop_account("acc3").await }
+        });
+        stop.await.unwrap().unwrap();
+        assert_eq!(stops(&mock), ["acc3"]);
+
+        // Volta sem esperar os StopAccount serem respondidos.
+        tokio::time::timeout(Duration::from_secs(1), worker.notify_network_changed(true))
+            .await
+            .expect("notify_network_changed waited for the restarts");
+        // Os dois StopAccount saem antes de qualquer resposta.
+        let mut pending = Vec::new();
+        for id in ["acc1", "acc2"] {
+            let message = mock
+                .wait_for_command(
+                    Duration::from_secs(2),
+                    |c| matches!(c, IpcCommand::StopAccount { account_id, .. } if account_id == id),
+                )
+                .await
+                .expect("no StopAccount written");
+            pending.push(message);
+        }
+        for message in &pending {
+            mock.reply(message, true, None).await.unwrap();
+        }
+        let starts = |id: &str| {
+            mock.commands()
+                .iter()
+                .filter(
+                    |c| matches!(c, IpcCommand::StartAccount { account_id } if account_id == id),
+                )
+                .count()
+        };
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while starts("acc1") < 2 || starts("acc2") < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("accounts not started again");
+        assert_eq!(starts("acc3"), 1);
+
+        // Mesmo sinal de novo: nada a fazer.
+        worker.notify_network_changed(true).await;
+        assert_eq!(stops(&mock).len(), 3);
+    }
+
+    /// `send_message` with nanachi accepting it.
+    async fn send_confirmed(
+        worker: &Arc<TinaWorker>,
+        mock: &tina_ipc::MockNanachi,
+        text: &str,
+        local_id: &str,
+    ) {
+        let send = tokio::spawn({
+            let (worker, text, local_id) = (worker.clone(), text.to_string(), local_id.to_string());
+            async move {
+                worker
+                    .send_message("acc1", PEER, &text, &[], &local_id)
+                    .await
+            }
+        });
+        let message = mock
+            .wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::SendMessage { local_id: Some(id), .. } if id == local_id)
+            })
+            .await
+            .expect("no SendMessage written");
+        mock.reply(&message, true, None).await.unwrap();
+        send.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn rekeyed_copies_of_our_sends_are_dropped_once_each() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        send_confirmed(&worker, &mock, "chego em 5", "local-1").await;
+        // Mesmo texto enviado duas vezes de verdade.
+        send_confirmed(&worker, &mock, "ok", "local-2").await;
+        send_confirmed(&worker, &mock, "ok", "local-3").await;
+        // Carimbo dos ecos otimistas; as cópias chegam com o do servidor.
+        let now = worker
+            .get_messages("acc1", PEER, None, 1, 0)
+            .await
+            .unwrap()
+            .items[0]
+            .timestamp;
+
+        // Ecos ao vivo, cópias re-chaveadas e um "ok" do outro lado,
+        // tudo no mesmo lote.
+        let own = |id: &str, text: &str, ts: i64| {
+            format!(
+                r#"{{"message_id":"{id}","chat_jid":"{PEER}","sender_jid":"","content":"{text}","message_type":"text","timestamp":{ts},"is_from_me":true}}"#
+            )
+        };
+        let messages = [
+            own("local-1", "chego em 5", now),
+            own("history-1", "chego em 5", now + 1),
+            own("local-2", "ok", now),
+            own("local-3", "ok", now),
+            own("history-2", "ok", now + 1),
+            format!(
+                r#"{{"message_id":"theirs","chat_jid":"{PEER}","sender_jid":"{PEER}","content":"ok","message_type":"text","timestamp":{now},"is_from_me":false}}"#
+            ),
+        ];
+        let line = format!(
+            r#"{{"id":"e1","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{}]}}}}"#,
+            messages.join(",")
+        );
+        mock.emit_line(&line).await.unwrap();
+
+        let mut ids = Vec::new();
+        for _ in 0..50 {
+            let page = worker
+                .get_messages("acc1", PEER, None, 50, 0)
+                .await
+                .unwrap();
+            if page.items.iter().any(|m| m.message_id == "theirs") {
+                ids = page.items.into_iter().map(|m| m.message_id).collect();
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        ids.sort();
+        assert_eq!(ids, ["local-1", "local-2", "local-3", "theirs"]);
+    }
+
+    #[tokio::test]
+    async fn history_copies_after_a_restart_are_dropped_against_stored_sends() {
+        let dir = std::env::temp_dir().join(format!("tina-worker-rekey-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tina.db");
+        let started = |mock: &tina_ipc::MockNanachi| {
+            let (mock, path) = (mock.clone(), path.clone());
+            async move {
+                let config = WorkerConfig {
+                    transport: NanachiTransport::Mock(mock.clone()),
+                    connect_timeout: None,
+                    ..WorkerConfig::default()
+                };
+                let worker = TinaWorker::with_config(PathBuf::new(), Some(path), config)
+                    .await
+                    .unwrap();
+                worker.start().await.unwrap();
+                mock.emit(IpcEvent::Ready {
+                    account_id: String::new(),
+                })
+                .await
+                .unwrap();
+                Arc::new(worker)
+            }
+        };
+
+        // Envios ao vivo, com o mesmo texto duas vezes de verdade.
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = started(&mock).await;
+        worker.create_account("acc1", None).await.unwrap();
+        send_confirmed(&worker, &mock, "chego em 5", "local-1").await;
+        send_confirmed(&worker, &mock, "ok", "local-2").await;
+        send_confirmed(&worker, &mock, "ok", "local-3").await;
+        let now = message_row(&worker, "local-3").await.timestamp;
+        worker.stop().await.unwrap();
+        drop(worker);
+
+        // O history sync depois de reiniciar traz cada envio re-chaveado.
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = started(&mock).await;
+        let own = |id: &str, text: &str| {
+            format!(
+                r#"{{"message_id":"{id}","chat_jid":"{PEER}","sender_jid":"","content":"{text}","message_type":"text","timestamp":{},"is_from_me":true}}"#,
+                now + 1
+            )
+        };
+        let upsert = |messages: &[String]| {
+            format!(
+                r#"{{"id":"e1","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{}]}}}}"#,
+                messages.join(",")
+            )
+        };
+        let history = [
+            own("history-1", "chego em 5"),
+            own("history-2", "ok"),
+            own("history-3", "ok"),
+        ];
+        mock.emit_line(&upsert(&history)).await.unwrap();
+        incoming_message(&mock, &worker, "theirs-1").await;
+        // A mesma cópia de novo continua fora; um terceiro "ok" não tem
+        // envio sobrando pra absorver e fica.
+        let again = [own("history-2", "ok"), own("phone-4", "ok")];
+        mock.emit_line(&upsert(&again)).await.unwrap();
+        incoming_message(&mock, &worker, "theirs-2").await;
+
+        let page = worker
+            .get_messages("acc1", PEER, None, 50, 0)
+            .await
+            .unwrap();
+        let mut ids: Vec<String> = page.items.into_iter().map(|m| m.message_id).collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            [
+                "local-1", "local-2", "local-3", "phone-4", "theirs-1", "theirs-2"
+            ]
+        );
+        worker.stop().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn own_sends_are_stored_sanitized_and_still_deduped() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        send_confirmed(&worker, &mock, "linha 1\r\nlinha 2", "local-1").await;
+        let row = message_row(&worker, "local-1").await;
+        assert_eq!(row.content.as_deref(), Some("linha 1\nlinha 2"));
+
+        // Cópia re-chaveada com o texto cru, como o WhatsApp devolve.
+        let line = format!(
+            r#"{{"id":"e1","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{{"message_id":"history-1","chat_jid":"{PEER}","sender_jid":"","content":"linha 1\r\nlinha 2","message_type":"text","timestamp":{},"is_from_me":true}}]}}}}"#,
+            row.timestamp
+        );
+        mock.emit_line(&line).await.unwrap();
+        incoming_message(&mock, &worker, "theirs").await;
+        let copy = worker.db.get_message("acc1", "history-1").await.unwrap();
+        assert!(copy.is_none());
+    }
+
+    #[tokio::test]
+    async fn message_page_size_is_clamped() {
+        for (asked, got) in [(0, 10), (25, 25), (100_000, 100)] {
+            let config = WorkerConfig {
+                message_page_size: asked,
+                ..WorkerConfig::default()
+            };
+            let worker = TinaWorker::with_config(PathBuf::new(), Some(":memory:".into()), config)
+                .await
+                .unwrap();
+            assert_eq!(worker.message_page_size(), got);
+        }
+    }
+
+    #[tokio::test]
+    async fn check_number_asks_once_and_remembers_the_answer() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = Arc::new(mocked_worker(&mock).await);
+        let check = tokio::spawn({
+            let worker = worker.clone();
+            async move { worker.check_number("acc1", "+55 (11) 99999-9999").await }
+        });
+
+        let message = mock
+            .wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::CheckNumber { phone, .. } if phone == "5511999999999")
+            })
+            .await
+            .expect("no CheckNumber written");
+        mock.emit(IpcEvent::NumberCheckResult {
+            account_id: "acc1".into(),
+            phone: "5511999999999".into(),
+            exists: true,
+            jid: Some(tina_core::WaIdentity::parse(PEER)),
+        })
+        .await
+        .unwrap();
+        mock.reply(&message, true, None).await.unwrap();
+        assert_eq!(check.await.unwrap().unwrap().as_deref(), Some(PEER));
+
+        // Mesmo número de novo: resposta guardada, nada vai pro servidor.
+        let again = worker.check_number("acc1", "5511999999999").await.unwrap();
+        assert_eq!(again.as_deref(), Some(PEER));
+        let asked = mock
+            .commands()
+            .iter()
+            .filter(|c| matches!(c, IpcCommand::CheckNumber { .. }))
+            .count();
+        assert_eq!(asked, 1);
+
+        assert!(matches!(
+            worker.check_number("acc1", "120363000000000000@g.us").await,
+            Err(WorkerError::InvalidRecipient(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn initial_sync_completes_after_contacts_groups_and_history() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let mut events = worker.subscribe_events();
+        let contacts = format!(
+            r#"{{"id":"e1","type":"ContactsUpsert","payload":{{"account_id":"acc1","contacts":[{{"jid":"{PEER}","name":"Ana"}}]}}}}"#
+        );
+        mock.emit_line(&contacts).await.unwrap();
+        mock.emit(IpcEvent::HistorySyncComplete {
+            account_id: "acc1".into(),
+            messages_count: 0,
+        })
+        .await
+        .unwrap();
+        assert!(!worker.initial_sync_complete("acc1").await.unwrap());
+        mock.emit(IpcEvent::GroupsUpsert {
+            account_id: "acc1".into(),
+            groups: Vec::new(),
+        })
+        .await
+        .unwrap();
+
+        let done = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = crate::recv_event(&mut events).await {
+                if let WorkerEvent::InitialSyncComplete { account_id } = event {
+                    return account_id;
+                }
+            }
+            String::new()
+        })
+        .await
+        .expect("no InitialSyncComplete");
+        assert_eq!(done, "acc1");
+        assert!(worker.initial_sync_complete("acc1").await.unwrap());
+        // O contato ainda no buffer foi gravado antes do aviso.
+        assert_eq!(worker.get_contact_count("acc1").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn cancel_mid_sync_drops_the_rest_of_the_history() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let mut events = worker.subscribe_events();
+        let upsert = |id: &str, ts: i64, chunk: Option<u32>| {
+            let chunk = chunk.map_or(String::new(), |c| {
+                format!(r#","chunk_index":{c},"is_last":false"#)
+            });
+            format!(
+                r#"{{"id":"e-{id}","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{{"message_id":"{id}","chat_jid":"{PEER}","sender_jid":"{PEER}","content":"{id}","message_type":"text","timestamp":{ts},"is_from_me":false}}]{chunk}}}}}"#
+            )
+        };
+        let progress = |progress, messages_count| IpcEvent::HistorySyncProgress {
+            account_id: "acc1".into(),
+            sync_type: "INITIAL_BOOTSTRAP".into(),
+            progress,
+            messages_count,
+        };
+        let stored = |id: &'static str| {
+            let worker = &worker;
+            async move {
+                for _ in 0..100 {
+                    if worker.db.get_message("acc1", id).await.unwrap().is_some() {
+                        return;
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                panic!("{id} never stored");
+            }
+        };
+
+        mock.emit_line(&upsert("h1", 100, Some(0))).await.unwrap();
+        mock.emit(progress(30, 1)).await.unwrap();
+        stored("h1").await;
+
+        worker.cancel_sync("acc1").await.unwrap();
+        assert!(
+            mock.wait_for_command(Duration::from_secs(5), |c| {
+                matches!(c, IpcCommand::CancelSync { .. })
+            })
+            .await
+            .is_some()
+        );
+        // O que ainda estava no pipe chega depois do cancel.
+        mock.emit_line(&upsert("h2", 200, Some(1))).await.unwrap();
+        mock.emit(progress(60, 2)).await.unwrap();
+        mock.emit(IpcEvent::HistorySyncComplete {
+            account_id: "acc1".into(),
+            messages_count: 2,
+        })
+        .await
+        .unwrap();
+        // Mensagem ao vivo, sem chunk: entra normalmente.
+        mock.emit_line(&upsert("live", 300, None)).await.unwrap();
+        stored("live").await;
+
+        assert!(worker.db.get_message("acc1", "h2").await.unwrap().is_none());
+        assert_eq!(worker.get_sync_cursor("acc1").await.unwrap(), None);
+        let mut completions = Vec::new();
+        let mut progress_seen = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                WorkerEvent::HistorySyncComplete { messages_count, .. } => {
+                    completions.push(messages_count)
+                }
+                WorkerEvent::HistorySyncProgress { progress, .. } => progress_seen.push(progress),
+                _ => {}
+            }
+        }
+        // Só o fim que o próprio cancel manda, com o que já tinha vindo.
+        assert_eq!(completions, [1]);
+        assert_eq!(progress_seen, [30]);
+    }
+
+    #[tokio::test]
+    async fn broadcast_outcomes_follow_the_input_order() {
+        const OTHER: &str = "5511888888888@s.whatsapp.net";
+        let mock = tina_ipc::MockNanachi::new();
+        let mut worker = mocked_worker(&mock).await;
+        worker.broadcast_interval = Duration::ZERO;
+        let worker = Arc::new(worker);
+        let recipients = vec![
+            PEER.to_string(),
+            "not a number".to_string(),
+            OTHER.to_string(),
+            // O mesmo PEER digitado: sai uma vez só.
+            "+55 11 99999-9999".to_string(),
+        ];
+        let send = tokio::spawn({
+            let worker = worker.clone();
+            async move { worker.send_broadcast("acc1", &recipients, "olá").await }
+        });
+
+        fn sent_to(jid: &'static str) -> impl Fn(&IpcCommand) -> bool {
+            move |c| matches!(c, IpcCommand::SendMessage { to, .. } if to.raw() == jid)
+        }
+        let first = mock
+            .wait_for_command(Duration::from_secs(5), sent_to(PEER))
+            .await
+            .expect("no SendMessage to PEER");
+        let second = mock
+            .wait_for_command(Duration::from_secs(5), sent_to(OTHER))
+            .await
+            .expect("no SendMessage to OTHER");
+        // O segundo responde antes do primeiro.
+        mock.reply(&second, true, None).await.unwrap();
+        mock.reply(&first, false, None).await.unwrap();
+
+        let outcomes = send.await.unwrap();
+        let to: Vec<&str> = outcomes.iter().map(|o| o.to.as_str()).collect();
+        assert_eq!(to, [PEER, "not a number", OTHER]);
+        assert!(matches!(
+            outcomes[0].result,
+            Err(WorkerError::SendFailed(_))
+        ));
+        assert!(matches!(
+            outcomes[1].result,
+            Err(WorkerError::InvalidRecipient(_))
+        ));
+        assert!(outcomes[2].result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn chat_feed_pushes_only_its_chat_with_the_sender_resolved() {
+        const OTHER: &str = "5511888888888@s.whatsapp.net";
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let upsert = |id: &str, chat: &str| {
+            format!(
+                r#"{{"id":"e-{id}","type":"MessagesUpsert","payload":{{"account_id":"acc1","messages":[{{"message_id":"{id}","chat_jid":"{chat}","sender_jid":"{chat}","content":"{id}","message_type":"text","timestamp":100,"is_from_me":false}}]}}}}"#
+            )
+        };
+        let contacts = format!(
+            r#"{{"id":"c1","type":"ContactsUpsert","payload":{{"account_id":"acc1","contacts":[{{"jid":"{PEER}","name":"Ana"}}]}}}}"#
+        );
+        mock.emit_line(&contacts).await.unwrap();
+        mock.emit_line(&upsert("m1", PEER)).await.unwrap();
+        let mut first = None;
+        for _ in 0..100 {
+            first = worker.db.get_message("acc1", "m1").await.unwrap();
+            if first.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let chat_id = first.expect("m1 never stored").chat_id;
+
+        let mut feed = worker.subscribe_chat("acc1", &chat_id);
+        mock.emit_line(&upsert("m2", OTHER)).await.unwrap();
+        mock.emit_line(&upsert("m3", PEER)).await.unwrap();
+        let row = tokio::time::timeout(Duration::from_secs(5), feed.recv())
+            .await
+            .expect("nothing pushed")
+            .unwrap();
+        assert_eq!(row.message_id, "m3");
+        assert_eq!(row.sender_name.as_deref(), Some("Ana"));
+        assert!(feed.try_recv().is_err());
+
+        drop(feed);
+        for _ in 0..100 {
+            if !worker.feed.is_watched("acc1", &chat_id) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("dropped receiver still subscribed");
+    }
+
+    #[tokio::test]
+    async fn garbage_from_nanachi_raises_one_error() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let mut events = worker.subscribe_events();
+        for i in 0..50 {
+            let line = format!("console.log debug {i}");
+            mock.emit_line(&line).await.unwrap();
+        }
+        // Um evento de verdade depois: quando ele chega, o lixo já passou.
+        mock.emit(IpcEvent::Ready {
+            account_id: String::new(),
+        })
+        .await
+        .unwrap();
+
+        let mut errors = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = crate::recv_event(&mut events).await {
+                match event {
+                    WorkerEvent::Error { account_id, .. } => errors.push(account_id),
+                    // O `Ready` do setup pode chegar antes do alarme.
+                    WorkerEvent::NanachiReady if !errors.is_empty() => return,
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("no error, or no NanachiReady after it");
+        assert_eq!(errors, [None]);
+    }
+
+    #[tokio::test]
+    async fn forward_checks_the_source_chat() {
+        const OTHER: &str = "5511888888888@s.whatsapp.net";
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        worker
+            .db
+            .insert_pending_text_message("acc1", "m1", PEER, "oi", 100, None)
+            .await
+            .unwrap();
+
+        let to = [OTHER.to_string()];
+        let err = worker
+            .forward_message("acc1", OTHER, "m1", &to)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WorkerError::MessageNotInChat { .. }), "{err}");
+        let forwarded = |c: &IpcCommand| matches!(c, IpcCommand::ForwardMessage { .. });
+        assert!(
+            mock.wait_for_command(Duration::from_millis(100), forwarded)
+                .await
+                .is_none()
+        );
+
+        let forward = worker.forward_message("acc1", PEER, "m1", &to);
+        let reply = async {
+            let message = mock
+                .wait_for_command(Duration::from_secs(5), forwarded)
+                .await
+                .expect("no ForwardMessage");
+            let data = serde_json::json!({
+                "results": [{"to": OTHER, "message_id": "f1", "timestamp": 200}]
+            });
+            mock.reply(&message, true, Some(data)).await.unwrap();
+        };
+        let (outcomes, ()) = tokio::join!(forward, reply);
+        let outcomes = outcomes.unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].result.as_deref(), Ok("f1"));
+    }
+
+    #[tokio::test]
+    async fn single_contact_upserts_coalesce_into_capped_flushes() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let mut events = worker.subscribe_events();
+        // 300 updates de um contato só, como no connect.
+        for i in 0..300 {
+            let line = format!(
+                r#"{{"id":"c{i}","type":"ContactsUpsert","payload":{{"account_id":"acc1","contacts":[{{"jid":"55119{i:08}@s.whatsapp.net","notify":"Contato {i}"}}]}}}}"#
+            );
+            mock.emit_line(&line).await.unwrap();
+        }
+
+        let counts = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut counts = Vec::new();
+            while counts.iter().sum::<usize>() < 300 {
+                match crate::recv_event(&mut events).await {
+                    Some(WorkerEvent::ContactsSynced { count, .. }) => counts.push(count),
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            counts
+        })
+        .await
+        .expect("contacts never synced");
+        // Uma transação por flush: nem uma por contato, nem passando do teto.
+        assert_eq!(counts.iter().sum::<usize>(), 300);
+        assert!(counts.len() >= 3 && counts.len() <= 30, "{counts:?}");
+        assert!(counts.iter().all(|&c| c <= 100), "{counts:?}");
+        assert_eq!(worker.get_contact_count("acc1").await.unwrap(), 300);
+    }
+
+    #[tokio::test]
+    async fn bulk_group_load_reports_progress_per_flush() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        let mut events = worker.subscribe_events();
+        // Como o nanachi manda: lotes de 10.
+        for chunk in 0..5 {
+            let groups = (0..10)
+                .map(|i| tina_core::GroupData {
+                    jid: tina_core::WaIdentity::parse(&format!("1203630000000{chunk}{i}@g.us")),
+                    subject: Some(format!("Grupo {chunk}{i}")),
+                    owner: None,
+                    description: None,
+                    avatar_url: None,
+                    participants: Vec::new(),
+                })
+                .collect();
+            mock.emit(IpcEvent::GroupsUpsert {
+                account_id: "acc1".into(),
+                groups,
+            })
+            .await
+            .unwrap();
+        }
+
+        let counts = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut counts = Vec::new();
+            while counts.iter().sum::<usize>() < 50 {
+                match crate::recv_event(&mut events).await {
+                    Some(WorkerEvent::GroupsSynced { count, .. }) => counts.push(count),
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            counts
+        })
+        .await
+        .expect("groups never synced");
+        assert_eq!(counts.iter().sum::<usize>(), 50);
+        assert!(counts.len() >= 3, "{counts:?}");
+        assert!(counts.iter().all(|&c| c <= 20), "{counts:?}");
+        assert_eq!(worker.get_group_count("acc1").await.unwrap(), 50);
+    }
+
+    #[tokio::test]
+    async fn initial_sync_is_not_announced_again_after_a_restart() {
+        let mock = tina_ipc::MockNanachi::new();
+        let worker = mocked_worker(&mock).await;
+        // Sessão anterior já concluiu a carga inicial.
+        assert!(worker.db.mark_initial_sync_done("acc1").await.unwrap());
+        assert!(worker.initial_sync_complete("acc1").await.unwrap());
+        let mut events = worker.subscribe_events();
+        mock.emit(IpcEvent::ContactsUpsert {
+            account_id: "acc1".into(),
+            contacts: Vec::new(),
+        })
+        .await
+        .unwrap();
+        mock.emit(IpcEvent::GroupsUpsert {
+            account_id: "acc1".into(),
+            groups: Vec::new(),
+        })
+        .await
+        .unwrap();
+        mock.emit(IpcEvent::HistorySyncComplete {
+            account_id: "acc1".into(),
+            messages_count: 0,
+        })
+        .await
+        .unwrap();
+        mock.emit(IpcEvent::LoggedOut {
+            account_id: "acc1".into(),
+        })
+        .await
+        .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = crate::recv_event(&mut events).await {
+                match event {
+                    WorkerEvent::InitialSyncComplete { .. } => panic!("announced twice"),
+                    WorkerEvent::LoggedOut { .. } => return,
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("no LoggedOut");
+        // O logout zera a marca: o próximo pareamento carrega tudo de novo.
+        assert!(!worker.initial_sync_complete("acc1").await.unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropped_worker_leaves_no_nanachi_behind() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("tina-worker-drop-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("go.mod"), "module nanachi\n").unwrap();
+        // Stand-in binary (newer than go.mod, so no `go build`) that
+        // logs every command and never exits on its own: it has to see
+        // `Shutdown` and then be killed.
+        let bin = dir.join("nanachi");
+        let script = "#!/bin/sh\necho $$ > pid\nwhile read -r line; do echo \"$line\" >> seen; done\nexec sleep 30\n";
+        std::fs::write(&bin, script).unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let worker = TinaWorker::new(dir.clone(), Some(dir.join("tina.db")))
+            .await
+            .unwrap();
+        worker.start().await.unwrap();
+        let mut pid = String::new();
+        for _ in 0..50 {
+            pid = std::fs::read_to_string(dir.join("pid")).unwrap_or_default();
+            if !pid.trim().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let pid = pid.trim().to_string();
+        assert!(alive(&pid), "nanachi should be running");
+
+        drop(worker);
+        let mut gone = false;
+        for _ in 0..50 {
+            if !alive(&pid) {
+                gone = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let seen = std::fs::read_to_string(dir.join("seen")).unwrap_or_default();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(seen.contains("Shutdown"), "nanachi never got Shutdown: {seen:?}");
+        assert!(gone, "nanachi {pid} outlived its worker");
+    }
+}
@@ -5,33 +5,106 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tokio::sync::{RwLock, mpsc};
 use tokio::time;
 
-use tina_core::IpcEvent;
+use tina_core::{IpcEvent, PROTOCOL_VERSION};
 use tina_db::TinaDb;
-use tina_ipc::{NanachiManager, SLOW_IPC_THRESHOLD};
+use tina_ipc::{IpcMetrics, NanachiManager, ParsedLine, SLOW_IPC_THRESHOLD, truncate_line};
 
 use crate::events::WorkerEvent;
 
-use super::buffer::{DirtyBuffer, FLUSH_THRESHOLD, FLUSH_WINDOW};
+use super::buffer::{
+    CONTACT_FLUSH_THRESHOLD, ChunkMark, DirtyBuffer, FLUSH_THRESHOLD, FLUSH_WINDOW,
+    GROUP_FLUSH_THRESHOLD, PendingEdit,
+};
+use super::feed::ChatFeed;
 use super::flush::flush;
+use super::initial_sync::InitialSyncs;
+use super::line_health::LineHealth;
+use super::qr::QrWatch;
+use super::connect::ConnectWatch;
+use super::connection::ConnectionStates;
+use super::logout::PendingLogouts;
+use super::pressure::QueuePressure;
+use super::number_check::NumberChecks;
 use super::realtime::handle_realtime_event;
+use super::replies::PendingReplies;
+use super::sync_cancel::SyncCancels;
+
+/// De quanto em quanto tempo o resumo de `IpcStats` vai pro log.
+const STATS_LOG_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Uma vez por dispatcher. Um nanachi de outro build segue funcionando
+/// enquanto os campos baterem; o aviso é pra quando não baterem.
+fn warn_protocol_mismatch(theirs: Option<u32>) {
+    match theirs {
+        Some(v) => tracing::warn!(
+            "nanachi fala o protocolo IPC v{v}, este build espera v{PROTOCOL_VERSION}"
+        ),
+        None => tracing::warn!(
+            "nanachi não informa a versão do protocolo IPC (anterior à v{PROTOCOL_VERSION}?)"
+        ),
+    }
+}
+
+/// What the dispatcher shares with the rest of the worker: handles
+/// cloned out of `TinaWorker` when nanachi starts, plus the QR timer,
+/// which only the dispatcher arms.
+pub(super) struct DispatchCtx {
+    pub(super) db: Arc<TinaDb>,
+    pub(super) event_tx: mpsc::Sender<WorkerEvent>,
+    pub(super) open_chats: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    pub(super) feed: ChatFeed,
+    pub(super) qr_watch: QrWatch,
+    pub(super) logouts: PendingLogouts,
+    pub(super) connections: ConnectionStates,
+    pub(super) replies: PendingReplies,
+    pub(super) connect_watch: ConnectWatch,
+    pub(super) sync_cancels: SyncCancels,
+    pub(super) number_checks: NumberChecks,
+    pub(super) initial_syncs: InitialSyncs,
+    pub(super) outstanding: Arc<std::sync::Mutex<HashMap<String, tina_ipc::CommandTiming>>>,
+    pub(super) metrics: IpcMetrics,
+    /// `WorkerConfig::max_content_chars`.
+    pub(super) max_content_chars: usize,
+}
+
+impl DispatchCtx {
+    /// Aplica o buffer; um erro só vai pro log, o dispatcher segue.
+    async fn flush(&self, buffer: &mut DirtyBuffer) {
+        let result = flush(
+            &self.db,
+            &self.event_tx,
+            &self.open_chats,
+            &self.feed,
+            buffer,
+        )
+        .await;
+        if let Err(e) = result {
+            tracing::error!("flush error: {}", e);
+        }
+    }
+}
 
 /// Dispatcher: dono único do `DirtyBuffer` + timer de flush + correlação
 /// de CommandResult. IPC reader nunca espera DB; eventos bulk acumulam
 /// até flush. Eventos realtime processam inline.
-pub(super) async fn dispatcher_loop(
-    db: Arc<TinaDb>,
-    event_tx: mpsc::Sender<WorkerEvent>,
-    open_chats: Arc<RwLock<HashMap<String, HashSet<String>>>>,
-    outstanding: Arc<std::sync::Mutex<HashMap<String, tina_ipc::CommandTiming>>>,
-    mut raw_rx: mpsc::Receiver<String>,
-) {
-    let mut buffer = DirtyBuffer::default();
+pub(super) async fn dispatcher_loop(ctx: DispatchCtx, mut raw_rx: mpsc::Receiver<String>) {
+    let metrics = &ctx.metrics;
+    let mut buffer = DirtyBuffer::new(ctx.max_content_chars);
+    let mut line_health = LineHealth::default();
+    let mut protocol_warned = false;
     let mut deadline: Option<time::Instant> = None;
+    let mut stats_tick = time::interval_at(
+        time::Instant::now() + STATS_LOG_INTERVAL,
+        STATS_LOG_INTERVAL,
+    );
+    stats_tick.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+    let mut last_stats = metrics.snapshot();
+    let mut ipc_pressure = QueuePressure::new("nanachi→worker");
 
     loop {
         // Sleep condicional: só fica pendente se buffer tem conteúdo.
@@ -47,27 +120,90 @@ pub(super) async fn dispatcher_loop(
             biased;
             line = raw_rx.recv() => {
                 let Some(line) = line else { break };
-                let Some(event) = NanachiManager::parse_event(&line) else { continue };
+                metrics.record_queue_depth(raw_rx.len());
+                ipc_pressure.observe(raw_rx.len(), raw_rx.max_capacity());
+                let event = match NanachiManager::parse_line(&line) {
+                    ParsedLine::Event { event, protocol_version } => {
+                        if !protocol_warned && protocol_version != Some(PROTOCOL_VERSION) {
+                            protocol_warned = true;
+                            warn_protocol_mismatch(protocol_version);
+                        }
+                        line_health.record_ok(Instant::now());
+                        metrics.record_event();
+                        event
+                    }
+                    ParsedLine::NotAnEvent => {
+                        line_health.record_ok(Instant::now());
+                        continue;
+                    }
+                    ParsedLine::Blank => continue,
+                    ParsedLine::Garbage { error } => {
+                        let alarm = line_health.record_failure(Instant::now());
+                        tracing::warn!(
+                            "linha ilegível do nanachi (#{}): {error} — line: {}",
+                            line_health.total_failures(),
+                            truncate_line(&line)
+                        );
+                        if alarm {
+                            let _ = ctx
+                                .event_tx
+                                .send(WorkerEvent::Error {
+                                    account_id: None,
+                                    error: "Most messages from nanachi could not be read; \
+                                            it may be a different version than this app"
+                                        .to_string(),
+                                })
+                                .await;
+                        }
+                        continue;
+                    }
+                };
 
-                record_command_rtt(&event, &outstanding);
+                record_command_rtt(&event, &ctx.outstanding, metrics);
+                ctx.number_checks.record(&event);
+                ctx.replies.resolve(&event);
+                if let Some(account_id) = event_account(&event) {
+                    ctx.connect_watch.disarm(account_id);
+                }
 
                 let started = Instant::now();
                 let kind = event_kind(&event);
+                let synced = ctx.initial_syncs.record(&event);
 
-                let bulked = route_event(&db, &event_tx, &mut buffer, event).await;
+                let bulked = route_event(&ctx, &mut buffer, event).await;
 
                 if bulked {
                     if deadline.is_none() && !buffer.is_empty() {
                         deadline = Some(time::Instant::now() + FLUSH_WINDOW);
                     }
-                    if buffer.total_count() >= FLUSH_THRESHOLD {
-                        if let Err(e) = flush(&db, &event_tx, &open_chats, &mut buffer).await {
-                            tracing::error!("flush error: {}", e);
-                        }
+                    if buffer.total_count() >= FLUSH_THRESHOLD
+                        || buffer.contacts_count() >= CONTACT_FLUSH_THRESHOLD
+                        || buffer.groups_count() >= GROUP_FLUSH_THRESHOLD
+                        || buffer.stream_ended()
+                    {
+                        ctx.flush(&mut buffer).await;
                         deadline = None;
                     }
                 }
 
+                let synced = match synced {
+                    Some(account_id) if InitialSyncs::persist(&ctx.db, &account_id).await => {
+                        Some(account_id)
+                    }
+                    _ => None,
+                };
+                if let Some(account_id) = synced {
+                    // Os nomes têm que estar no DB quando a UI reagir.
+                    if !buffer.is_empty() {
+                        ctx.flush(&mut buffer).await;
+                        deadline = None;
+                    }
+                    let _ = ctx
+                        .event_tx
+                        .send(WorkerEvent::InitialSyncComplete { account_id })
+                        .await;
+                }
+
                 let elapsed = started.elapsed();
                 if elapsed > SLOW_IPC_THRESHOLD {
                     tracing::warn!(
@@ -78,39 +214,67 @@ pub(super) async fn dispatcher_loop(
                 }
             }
             _ = timer, if deadline.is_some() => {
-                if let Err(e) = flush(&db, &event_tx, &open_chats, &mut buffer).await {
-                    tracing::error!("flush error: {}", e);
-                }
+                ctx.flush(&mut buffer).await;
                 deadline = None;
             }
+            _ = stats_tick.tick() => {
+                // Ocioso desde o último resumo: nada a dizer.
+                let stats = metrics.snapshot();
+                if stats.commands_sent != last_stats.commands_sent
+                    || stats.events_received != last_stats.events_received
+                {
+                    tracing::info!("IPC: {stats}");
+                }
+                last_stats = stats;
+            }
         }
     }
 
     // Drain final ao fechar.
     if !buffer.is_empty() {
-        let _ = flush(&db, &event_tx, &open_chats, &mut buffer).await;
+        ctx.flush(&mut buffer).await;
     }
 }
 
 /// Route `event` to either the DirtyBuffer (returns `true`) or the
 /// realtime handler (returns `false`).
-async fn route_event(
-    db: &TinaDb,
-    event_tx: &mpsc::Sender<WorkerEvent>,
-    buffer: &mut DirtyBuffer,
-    event: IpcEvent,
-) -> bool {
+async fn route_event(ctx: &DispatchCtx, buffer: &mut DirtyBuffer, event: IpcEvent) -> bool {
     match event {
+        // Sync cancelado: o resto do histórico que ainda estava no pipe
+        // não entra, e o progresso/fim atrasados não reabrem a tela.
+        IpcEvent::MessagesUpsert {
+            ref account_id,
+            chunk_index: Some(_),
+            ..
+        }
+        | IpcEvent::HistorySyncProgress { ref account_id, .. }
+        | IpcEvent::HistorySyncComplete { ref account_id, .. }
+            if ctx.sync_cancels.is_cancelled(account_id) =>
+        {
+            tracing::debug!(
+                "{account_id}: history sync cancelled, dropping {}",
+                event_kind(&event)
+            );
+            false
+        }
         IpcEvent::MessagesUpsert {
             account_id,
             messages,
+            chunk_index,
+            is_last,
         } => {
+            if let Some(chunk_index) = chunk_index {
+                let mark = buffer.chunks.entry(account_id.clone()).or_insert(ChunkMark {
+                    chunk_index,
+                    is_last,
+                    messages: 0,
+                });
+                mark.chunk_index = chunk_index;
+                mark.is_last = is_last;
+                mark.messages += messages.len();
+            }
             if !messages.is_empty() {
-                buffer
-                    .messages
-                    .entry(account_id)
-                    .or_default()
-                    .extend(messages);
+                buffer.push_messages(account_id, messages);
             }
             true
         }
@@ -136,8 +300,25 @@ async fn route_event(
             }
             true
         }
+        IpcEvent::MessageEdited {
+            account_id,
+            message_id,
+            new_content,
+            edited_at,
+            ..
+        } => {
+            buffer.push_edit(
+                account_id,
+                PendingEdit {
+                    message_id,
+                    new_content,
+                    edited_at,
+                },
+            );
+            true
+        }
         other => {
-            if let Err(e) = handle_realtime_event(db, event_tx, other).await {
+            if let Err(e) = handle_realtime_event(ctx, other).await {
                 tracing::error!("realtime handler error: {}", e);
             }
             false
@@ -148,6 +329,7 @@ async fn route_event(
 fn record_command_rtt(
     event: &IpcEvent,
     outstanding: &Arc<std::sync::Mutex<HashMap<String, tina_ipc::CommandTiming>>>,
+    metrics: &IpcMetrics,
 ) {
     let IpcEvent::CommandResult { ref command_id, .. } = *event else {
         return;
@@ -159,6 +341,7 @@ fn record_command_rtt(
         return;
     };
     let rtt = t.sent_at.elapsed();
+    metrics.record_round_trip(rtt);
     if rtt > SLOW_IPC_THRESHOLD {
         tracing::warn!("🐌 IPC round-trip {} → {:?}", t.kind, rtt);
     } else {
@@ -166,6 +349,40 @@ fn record_command_rtt(
     }
 }
 
+/// Conta a que o evento se refere, quando ele diz.
+fn event_account(e: &IpcEvent) -> Option<&str> {
+    match e {
+        IpcEvent::Ready { account_id }
+        | IpcEvent::QrCode { account_id, .. }
+        | IpcEvent::PairingCode { account_id, .. }
+        | IpcEvent::Connected { account_id, .. }
+        | IpcEvent::Disconnected { account_id, .. }
+        | IpcEvent::LoggedOut { account_id }
+        | IpcEvent::ContactsUpsert { account_id, .. }
+        | IpcEvent::GroupsUpsert { account_id, .. }
+        | IpcEvent::MessagesUpsert { account_id, .. }
+        | IpcEvent::HistorySyncComplete { account_id, .. }
+        | IpcEvent::HistorySyncProgress { account_id, .. }
+        | IpcEvent::ChatsPinUpdate { account_id, .. }
+        | IpcEvent::ChatsReadHint { account_id, .. }
+        | IpcEvent::ReconcileProgress { account_id, .. }
+        | IpcEvent::ReceiptUpdate { account_id, .. }
+        | IpcEvent::MessageEdited { account_id, .. }
+        | IpcEvent::MediaDownloadProgress { account_id, .. }
+        | IpcEvent::MediaDownloaded { account_id, .. }
+        | IpcEvent::MediaDownloadFailed { account_id, .. }
+        | IpcEvent::AvatarUpdated { account_id, .. }
+        | IpcEvent::AvatarFailed { account_id, .. }
+        | IpcEvent::GroupInviteLink { account_id, .. }
+        | IpcEvent::ProfileResult { account_id, .. }
+        | IpcEvent::NumberCheckResult { account_id, .. } => Some(account_id),
+        IpcEvent::Error { account_id, .. } | IpcEvent::Notice { account_id, .. } => {
+            account_id.as_deref()
+        }
+        IpcEvent::CommandResult { .. } => None,
+    }
+}
+
 pub(super) fn event_kind(e: &IpcEvent) -> &'static str {
     match e {
         IpcEvent::Ready { .. } => "Ready",
@@ -185,11 +402,15 @@ pub(super) fn event_kind(e: &IpcEvent) -> &'static str {
         IpcEvent::Error { .. } => "Error",
         IpcEvent::Notice { .. } => "Notice",
         IpcEvent::ReceiptUpdate { .. } => "ReceiptUpdate",
+        IpcEvent::MessageEdited { .. } => "MessageEdited",
         IpcEvent::MediaDownloadProgress { .. } => "MediaDownloadProgress",
         IpcEvent::MediaDownloaded { .. } => "MediaDownloaded",
         IpcEvent::MediaDownloadFailed { .. } => "MediaDownloadFailed",
         IpcEvent::AvatarUpdated { .. } => "AvatarUpdated",
         IpcEvent::AvatarFailed { .. } => "AvatarFailed",
+        IpcEvent::GroupInviteLink { .. } => "GroupInviteLink",
+        IpcEvent::ProfileResult { .. } => "ProfileResult",
+        IpcEvent::NumberCheckResult { .. } => "NumberCheckResult",
         IpcEvent::CommandResult { .. } => "CommandResult",
     }
 }
@@ -2,6 +2,13 @@
 // (skip the IPC call if another message already has the same sha256
 // downloaded) and pre-marks the row as `downloading` so the UI can
 // flip its spinner before the IPC roundtrip.
+//
+// Cache budget: every finished download is recorded in `media_files`
+// (see `realtime`), every cache hit counts as an access, and before
+// asking nanachi for a new file the least recently used ones past
+// `WorkerConfig::media_cache_bytes` are deleted.
+
+use std::path::Path;
 
 use tina_core::IpcCommand;
 
@@ -11,13 +18,59 @@ use crate::events::WorkerEvent;
 use super::core::TinaWorker;
 
 impl TinaWorker {
+    /// Local path of a message's media when it's on disk. Otherwise
+    /// starts the download and returns `None`; the path arrives with
+    /// `WorkerEvent::MediaReady`.
+    pub async fn get_media(&self, account_id: &str, message_id: &str) -> Result<Option<String>> {
+        if let Some(path) = self.db.get_media_path(account_id, message_id).await?
+            && Path::new(&path).exists()
+        {
+            self.db.touch_media(&path).await?;
+            return Ok(Some(path));
+        }
+        self.download_media(account_id, message_id).await?;
+        Ok(None)
+    }
+
+    /// Deletes the least recently used cached files until the cache
+    /// fits `WorkerConfig::media_cache_bytes`. Returns how many went.
+    pub async fn enforce_media_budget(&self) -> Result<usize> {
+        let Some(budget) = self.media_cache_bytes else {
+            return Ok(0);
+        };
+        let budget = i64::try_from(budget).unwrap_or(i64::MAX);
+        let evicted = self.db.evict_media(budget).await?;
+        for file in &evicted {
+            // Já fora do banco; arquivo que sumiu sozinho não é erro.
+            if let Err(e) = tokio::fs::remove_file(&file.path).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!("evict {}: {e}", file.path);
+            }
+        }
+        if !evicted.is_empty() {
+            let freed: i64 = evicted.iter().map(|f| f.size_bytes).sum();
+            tracing::info!(
+                "media cache: evicted {} file(s), {freed} bytes",
+                evicted.len()
+            );
+        }
+        Ok(evicted.len())
+    }
+
     /// Solicita download de mídia. Faz dedup local primeiro: se outra
     /// mensagem com o mesmo sha256 já tem `media_path`, reaproveita
-    /// esse caminho sem chamar o nanachi.
+    /// esse caminho sem chamar o nanachi. No modo offline só a cache
+    /// serve; o resto falha antes de mexer na cache ou no status.
     pub async fn download_media(&self, account_id: &str, message_id: &str) -> Result<()> {
         if self.try_serve_from_cache(account_id, message_id).await? {
             return Ok(());
         }
+        self.ensure_online()?;
+
+        if let Err(e) = self.enforce_media_budget().await {
+            tracing::warn!("media cache budget: {e}");
+        }
 
         // Marca como downloading pra UI exibir spinner enquanto IPC volta.
         self.db
@@ -29,14 +82,12 @@ impl TinaWorker {
         // chat row from before the current process started).
         let raw_json = self.db.get_message_raw_json(account_id, message_id).await?;
 
-        let nanachi = self.nanachi.read().await;
-        nanachi
-            .send_command(IpcCommand::DownloadMedia {
-                account_id: account_id.to_string(),
-                message_id: message_id.to_string(),
-                raw_json,
-            })
-            .await?;
+        self.send_command(IpcCommand::DownloadMedia {
+            account_id: account_id.to_string(),
+            message_id: message_id.to_string(),
+            raw_json,
+        })
+        .await?;
         Ok(())
     }
 
@@ -55,7 +106,8 @@ impl TinaWorker {
         };
         // (1) The row already has a local copy.
         if let Some(path) = row.media_path.as_deref()
-            && std::path::Path::new(path).exists() {
+            && Path::new(path).exists() {
+                self.db.touch_media(path).await?;
                 let _ = self
                     .event_tx
                     .send(WorkerEvent::MediaReady {
@@ -72,6 +124,7 @@ impl TinaWorker {
             && let Some(existing_path) =
                 self.db.find_existing_media_path(account_id, sha).await?
             {
+                self.db.touch_media(&existing_path).await?;
                 let affected = self
                     .db
                     .apply_media_downloaded(
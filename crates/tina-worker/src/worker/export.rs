@@ -0,0 +1,457 @@
+// Chat export: the whole history of one chat rendered as plain text,
+// JSON or a self-contained HTML page, then written to a file. The DB
+// only hands out the `MessageRow`s (oldest first after the reverse);
+// formats, local times and markup live here. Media isn't embedded —
+// rows point at the downloaded file by path, or just name it when it
+// was never downloaded. Replies carry the quoted message (sender +
+// snippet) as `get_messages_filtered` resolves it: the stored preview,
+// else the start of the quoted row.
+//
+// HTML: every string that came from a message, a contact or a file
+// name goes through `escape_html`, attributes included. Media links
+// are percent-encoded first (`media_href`), so a path with spaces,
+// `#` or `?` still points at the file.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+use tina_core::MessageType;
+use tina_db::MessageRow;
+
+use crate::error::{Result, WorkerError};
+
+use super::core::TinaWorker;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = WorkerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "html" | "htm" => Ok(Self::Html),
+            other => Err(WorkerError::InvalidExportFormat(other.to_string())),
+        }
+    }
+}
+
+impl TinaWorker {
+    /// Writes the whole history of `chat_id` to `out` in `format`
+    /// (overwriting it). Media is linked by path, not copied.
+    pub async fn export_chat(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        format: ExportFormat,
+        out: &Path,
+    ) -> Result<()> {
+        let rendered = self.render_chat(account_id, chat_id, format).await?;
+        tokio::fs::write(out, rendered).await?;
+        Ok(())
+    }
+
+    /// Every message of `chat_id`, oldest first, in `format`.
+    async fn render_chat(
+        &self,
+        account_id: &str,
+        chat_id: &str,
+        format: ExportFormat,
+    ) -> Result<String> {
+        let title = self
+            .db
+            .get_chat_name(account_id, chat_id)
+            .await?
+            .unwrap_or_else(|| chat_id.to_string());
+        let mut rows = self
+            .db
+            .get_messages_filtered(account_id, chat_id, None, i64::MAX, 0)
+            .await?;
+        rows.reverse();
+        Ok(match format {
+            ExportFormat::Text => render_text(&title, &rows),
+            ExportFormat::Json => render_json(&title, &rows)?,
+            ExportFormat::Html => render_html(&title, &rows),
+        })
+    }
+}
+
+fn sender(row: &MessageRow) -> &str {
+    if row.is_from_me {
+        return "You";
+    }
+    row.sender_name
+        .as_deref()
+        .or(row.sender_jid.as_deref())
+        .unwrap_or("?")
+}
+
+/// Quem e o quê a mensagem cita; `None` quando não é resposta.
+fn quoted(row: &MessageRow) -> Option<ExportedQuote<'_>> {
+    Some(ExportedQuote {
+        id: row.quoted_message_id.as_deref()?,
+        sender: row
+            .quoted_sender_name
+            .as_deref()
+            .or(row.quoted_sender_id.as_deref())
+            .unwrap_or("?"),
+        text: row.quoted_preview.as_deref().unwrap_or_default(),
+    })
+}
+
+/// `2026-10-16 14:03`, hora local.
+fn when(row: &MessageRow) -> String {
+    row.datetime_local()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Nome do arquivo de mídia: o original, senão o do cache.
+fn media_name(row: &MessageRow) -> Option<&str> {
+    row.media_filename.as_deref().or_else(|| {
+        row.media_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .and_then(|n| n.to_str())
+    })
+}
+
+fn render_text(title: &str, rows: &[MessageRow]) -> String {
+    let mut out = format!("{title}\n\n");
+    for row in rows {
+        if let Some(quote) = quoted(row) {
+            let _ = writeln!(out, "> {}:", quote.sender);
+            for line in quote.text.lines() {
+                let _ = writeln!(out, "> {line}");
+            }
+        }
+        let _ = write!(out, "[{}] {}: ", when(row), sender(row));
+        let kind = MessageType::from_raw(&row.message_type);
+        if kind.is_media() {
+            let _ = write!(out, "[{}", kind.as_str());
+            if let Some(name) = media_name(row) {
+                let _ = write!(out, ": {name}");
+            }
+            out.push_str("] ");
+        }
+        out.push_str(row.content.as_deref().unwrap_or_default());
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Serialize)]
+struct ExportedChat<'a> {
+    chat: &'a str,
+    messages: Vec<ExportedMessage<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedMessage<'a> {
+    id: &'a str,
+    timestamp: i64,
+    from_me: bool,
+    sender: &'a str,
+    #[serde(rename = "type")]
+    kind: &'a str,
+    content: Option<&'a str>,
+    media_file: Option<&'a str>,
+    quoted: Option<ExportedQuote<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedQuote<'a> {
+    id: &'a str,
+    sender: &'a str,
+    text: &'a str,
+}
+
+fn render_json(title: &str, rows: &[MessageRow]) -> Result<String> {
+    let chat = ExportedChat {
+        chat: title,
+        messages: rows
+            .iter()
+            .map(|row| ExportedMessage {
+                id: &row.message_id,
+                timestamp: row.timestamp,
+                from_me: row.is_from_me,
+                sender: sender(row),
+                kind: &row.message_type,
+                content: row.content.as_deref(),
+                media_file: row.media_path.as_deref(),
+                quoted: quoted(row),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&chat).map_err(std::io::Error::from)?)
+}
+
+const HTML_STYLE: &str = "\
+body{margin:0;background:#efeae2;font:15px/1.4 system-ui,sans-serif;color:#111}\
+h1{margin:0;padding:16px 24px;background:#075e54;color:#fff;font-size:18px}\
+main{display:flex;flex-direction:column;gap:6px;max-width:760px;margin:0 auto;padding:16px}\
+.msg{max-width:75%;padding:6px 10px;border-radius:8px;background:#fff;\
+box-shadow:0 1px 1px rgba(0,0,0,.1);white-space:pre-wrap;overflow-wrap:anywhere}\
+.msg.me{align-self:flex-end;background:#d9fdd3}\
+.msg.them{align-self:flex-start}\
+.sender{font-weight:600;font-size:13px;color:#075e54}\
+blockquote{margin:0 0 4px;padding:4px 8px;border-left:3px solid #25d366;\
+border-radius:4px;background:rgba(0,0,0,.05);font-size:13px}\
+.media{font-size:13px}\
+time{display:block;text-align:right;font-size:11px;color:#667}";
+
+fn render_html(title: &str, rows: &[MessageRow]) -> String {
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n\
+         <body>\n<h1>{title}</h1>\n<main>\n"
+    );
+    for row in rows {
+        let side = if row.is_from_me { "me" } else { "them" };
+        let _ = write!(out, "<div class=\"msg {side}\">");
+        if !row.is_from_me {
+            let _ = write!(
+                out,
+                "<div class=\"sender\">{}</div>",
+                escape_html(sender(row))
+            );
+        }
+        if let Some(quote) = quoted(row) {
+            let _ = write!(
+                out,
+                "<blockquote><div class=\"sender\">{}</div>{}</blockquote>",
+                escape_html(quote.sender),
+                escape_html(quote.text)
+            );
+        }
+        let kind = MessageType::from_raw(&row.message_type);
+        if kind.is_media() {
+            let label = escape_html(media_name(row).unwrap_or(kind.as_str()));
+            match row.media_path.as_deref() {
+                Some(path) => {
+                    let _ = write!(
+                        out,
+                        "<div class=\"media\"><a href=\"{}\">{label}</a></div>",
+                        escape_html(&media_href(path))
+                    );
+                }
+                None => {
+                    let _ = write!(out, "<div class=\"media\">{label}</div>");
+                }
+            }
+        }
+        if let Some(content) = row.content.as_deref().filter(|c| !c.is_empty()) {
+            let _ = write!(out, "<div>{}</div>", escape_html(content));
+        }
+        let _ = writeln!(out, "<time>{}</time></div>", when(row));
+    }
+    out.push_str("</main>\n</body>\n</html>\n");
+    out
+}
+
+/// Caminho de arquivo como URL: tudo fora de `A-Z a-z 0-9 - . _ ~ /`
+/// vira `%XX`, byte a byte (UTF-8 incluso). Absoluto ganha `file://`.
+fn media_href(path: &str) -> String {
+    let mut out = String::with_capacity(path.len() + 7);
+    if path.starts_with('/') {
+        out.push_str("file://");
+    }
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            b => {
+                let _ = write!(out, "%{b:02X}");
+            }
+        }
+    }
+    out
+}
+
+/// Escapa texto para conteúdo e atributos entre aspas duplas.
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use tina_db::{ChatKind, MessageBatchInput};
+
+    use super::*;
+
+    const PEER: &str = "5511999999999@s.whatsapp.net";
+
+    async fn worker() -> TinaWorker {
+        let worker = TinaWorker::new_offline(Some(":memory:".into()))
+            .await
+            .unwrap();
+        worker.db.create_account("acc1", None).await.unwrap();
+        worker
+    }
+
+    #[tokio::test]
+    async fn html_export_escapes_message_text() {
+        let worker = worker().await;
+        let chat = worker
+            .db
+            .register_chat_alias("acc1", PEER, ChatKind::Dm)
+            .await
+            .unwrap();
+        let evil = "<script>alert(\"x\")</script> & co";
+        for (id, content, ts, mine) in [("m1", evil, 100, false), ("m2", "oi", 200, true)] {
+            worker
+                .db
+                .insert_message(
+                    "acc1",
+                    id,
+                    &chat,
+                    None,
+                    Some(content),
+                    "text",
+                    ts,
+                    mine,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let html = worker
+            .render_chat("acc1", &chat, ExportFormat::Html)
+            .await
+            .unwrap();
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; co"));
+        // Mais antiga primeiro; a minha fica à direita.
+        let them = html.find("class=\"msg them\"").unwrap();
+        let me = html.find("class=\"msg me\"").unwrap();
+        assert!(them < me);
+
+        let text = worker
+            .render_chat("acc1", &chat, ExportFormat::Text)
+            .await
+            .unwrap();
+        assert!(text.contains(evil));
+        assert_eq!("HTML".parse::<ExportFormat>().unwrap(), ExportFormat::Html);
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+
+    #[tokio::test]
+    async fn html_export_links_media_by_an_encoded_path() {
+        let worker = worker().await;
+        let chat = worker
+            .db
+            .register_chat_alias("acc1", PEER, ChatKind::Dm)
+            .await
+            .unwrap();
+        worker
+            .db
+            .insert_message("acc1", "img", &chat, None, None, "image", 100, false, None)
+            .await
+            .unwrap();
+        worker
+            .db
+            .apply_media_downloaded("acc1", "img", "/cache/fé \"x\"#1.jpg", None, None)
+            .await
+            .unwrap();
+
+        let html = worker
+            .render_chat("acc1", &chat, ExportFormat::Html)
+            .await
+            .unwrap();
+        assert!(html.contains("<a href=\"file:///cache/f%C3%A9%20%22x%22%231.jpg\">"));
+        assert!(html.contains("&quot;x&quot;#1.jpg</a>"));
+    }
+
+    #[tokio::test]
+    async fn export_keeps_the_quoted_message_of_a_reply() {
+        let worker = worker().await;
+        let reply = |id, content, ts, quoted: Option<&'static str>| MessageBatchInput {
+            message_id: id,
+            chat_jid: PEER,
+            sender_jid: Some(PEER),
+            content: Some(content),
+            message_type: "text",
+            timestamp: ts,
+            is_from_me: false,
+            raw_json: None,
+            media: None,
+            quoted_message_id: quoted,
+            quoted_sender_id: quoted.map(|_| PEER),
+            quoted_preview: None,
+            mentions_json: None,
+        };
+        let messages = vec![
+            reply("q1", "vamos <almoçar>?", 100, None),
+            reply("r1", "bora", 200, Some("q1")),
+        ];
+        worker
+            .db
+            .run_message_batch("acc1", None, &messages)
+            .await
+            .unwrap();
+        let chat = worker
+            .db
+            .get_chat("acc1", PEER)
+            .await
+            .unwrap()
+            .unwrap()
+            .chat_id;
+
+        let text = worker
+            .render_chat("acc1", &chat, ExportFormat::Text)
+            .await
+            .unwrap();
+        let quote = text.find("> vamos <almoçar>?\n").unwrap();
+        assert!(quote < text.rfind("bora").unwrap());
+
+        let json: serde_json::Value = serde_json::from_str(
+            &worker
+                .render_chat("acc1", &chat, ExportFormat::Json)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        let messages = json["messages"].as_array().unwrap();
+        assert!(messages[0]["quoted"].is_null());
+        assert_eq!(messages[1]["quoted"]["id"], "q1");
+        assert_eq!(messages[1]["quoted"]["text"], "vamos <almoçar>?");
+
+        let html = worker
+            .render_chat("acc1", &chat, ExportFormat::Html)
+            .await
+            .unwrap();
+        assert!(html.contains("vamos &lt;almoçar&gt;?</blockquote>"));
+    }
+}
@@ -0,0 +1,147 @@
+// Per-chat push feed. `subscribe_chat` hands out an mpsc receiver that
+// only sees new rows for one chat; the flush publishes into a single
+// broadcast and a small forwarding task per subscriber filters it.
+// Rows come from `get_message_rows_by_ids`, so sender names are
+// already resolved through the contacts JOIN.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{broadcast, mpsc};
+
+use tina_db::MessageRow;
+
+/// Capacidade do broadcast. Um subscriber que fica mais de N lotes
+/// atrás perde os mais antigos (`Lagged`) em vez de segurar o flush.
+const FEED_CAPACITY: usize = 256;
+/// Buffer do mpsc entregue ao caller.
+const SUBSCRIBER_CAPACITY: usize = 512;
+
+type ChatKey = (String, String);
+
+#[derive(Debug)]
+struct FeedItem {
+    account_id: String,
+    chat_id: String,
+    messages: Vec<MessageRow>,
+}
+
+#[derive(Clone)]
+pub(super) struct ChatFeed {
+    tx: broadcast::Sender<Arc<FeedItem>>,
+    /// `(account_id, chat_id)` → número de subscribers vivos. O flush
+    /// só busca rows de chats presentes aqui (ou abertos na UI).
+    watched: Arc<Mutex<HashMap<ChatKey, usize>>>,
+}
+
+impl ChatFeed {
+    pub(super) fn new() -> Self {
+        let (tx, _) = broadcast::channel(FEED_CAPACITY);
+        Self {
+            tx,
+            watched: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub(super) fn is_watched(&self, account_id: &str, chat_id: &str) -> bool {
+        self.watched
+            .lock()
+            .is_ok_and(|w| w.contains_key(&(account_id.to_string(), chat_id.to_string())))
+    }
+
+    pub(super) fn publish(&self, account_id: &str, chat_id: &str, messages: &[MessageRow]) {
+        if messages.is_empty() || !self.is_watched(account_id, chat_id) {
+            return;
+        }
+        // Err = ninguém inscrito (o último acabou de sair); nada a fazer.
+        let _ = self.tx.send(Arc::new(FeedItem {
+            account_id: account_id.to_string(),
+            chat_id: chat_id.to_string(),
+            messages: messages.to_vec(),
+        }));
+    }
+
+    /// Dropar o receiver encerra a task de forwarding e desinscreve.
+    pub(super) fn subscribe(&self, account_id: &str, chat_id: &str) -> mpsc::Receiver<MessageRow> {
+        let key = (account_id.to_string(), chat_id.to_string());
+        let (out_tx, out_rx) = mpsc::channel(SUBSCRIBER_CAPACITY);
+        let mut feed = self.tx.subscribe();
+        if let Ok(mut w) = self.watched.lock() {
+            *w.entry(key.clone()).or_default() += 1;
+        }
+
+        let watched = self.watched.clone();
+        tokio::spawn(async move {
+            'forward: loop {
+                let item = tokio::select! {
+                    _ = out_tx.closed() => break,
+                    item = feed.recv() => item,
+                };
+                match item {
+                    Ok(item) => {
+                        if item.account_id != key.0 || item.chat_id != key.1 {
+                            continue;
+                        }
+                        for row in &item.messages {
+                            if out_tx.send(row.clone()).await.is_err() {
+                                break 'forward;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!(
+                            "chat feed {}/{} lagged, {} batch(es) dropped",
+                            key.0,
+                            key.1,
+                            n
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            if let Ok(mut w) = watched.lock()
+                && let Some(n) = w.get_mut(&key)
+            {
+                *n -= 1;
+                if *n == 0 {
+                    w.remove(&key);
+                }
+            }
+        });
+        out_rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    async fn unwatched(feed: &ChatFeed, account_id: &str, chat_id: &str) -> bool {
+        for _ in 0..100 {
+            if !feed.is_watched(account_id, chat_id) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn chat_stays_watched_until_the_last_receiver_drops() {
+        let feed = ChatFeed::new();
+        assert!(!feed.is_watched("acc1", "c1"));
+        let first = feed.subscribe("acc1", "c1");
+        let second = feed.subscribe("acc1", "c1");
+        assert!(feed.is_watched("acc1", "c1"));
+        assert!(!feed.is_watched("acc1", "c2"));
+        assert!(!feed.is_watched("acc2", "c1"));
+
+        drop(first);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(feed.is_watched("acc1", "c1"));
+        drop(second);
+        assert!(unwatched(&feed, "acc1", "c1").await);
+    }
+}
@@ -1,6 +1,7 @@
 // Flush: applies the entire `DirtyBuffer` as a single transaction
-// per account, then emits exactly one `ChatsUpserted` per affected
-// account.
+// per account, then emits exactly one `ChatsUpserted` (and, when
+// contacts or groups were written, one `ContactsSynced` /
+// `GroupsSynced`) per affected account.
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -8,6 +9,7 @@ use std::time::{Duration, Instant};
 
 use tokio::sync::{RwLock, mpsc};
 
+use tina_core::{MessageData, MessageType};
 use tina_db::TinaDb;
 use tina_ipc::SLOW_IPC_THRESHOLD;
 
@@ -16,6 +18,7 @@ use crate::events::WorkerEvent;
 
 use super::batch::{process_contacts, process_groups};
 use super::buffer::DirtyBuffer;
+use super::feed::ChatFeed;
 
 /// Aplica todo o buffer numa transação coletiva por account_id,
 /// emitindo um único `ChatsUpserted` por account no final.
@@ -23,6 +26,7 @@ pub(super) async fn flush(
     db: &TinaDb,
     event_tx: &mpsc::Sender<WorkerEvent>,
     open_chats: &Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    feed: &ChatFeed,
     buffer: &mut DirtyBuffer,
 ) -> Result<()> {
     let started = Instant::now();
@@ -34,18 +38,105 @@ pub(super) async fn flush(
     let mut msgs_per_account: HashMap<String, usize> = HashMap::new();
     let open_snapshot = open_chats.read().await.clone();
 
-    flush_messages(db, event_tx, buffer, &mut affected, &open_snapshot, &mut msgs_per_account).await?;
-    flush_contacts(db, buffer, &mut affected).await?;
-    flush_groups(db, buffer, &mut affected).await?;
+    drop_own_duplicates(db, &mut buffer.messages).await?;
+    flush_messages(
+        db,
+        event_tx,
+        feed,
+        buffer,
+        &mut affected,
+        &open_snapshot,
+        &mut msgs_per_account,
+    )
+    .await?;
+    flush_edits(db, event_tx, buffer, &mut affected).await?;
+    let contacts_per_account = flush_contacts(db, buffer, &mut affected).await?;
+    let groups_per_account = flush_groups(db, buffer, &mut affected).await?;
     emit_chats_upserted(db, event_tx, affected, msgs_per_account).await;
+    for (account_id, mark) in std::mem::take(&mut buffer.chunks) {
+        let _ = event_tx
+            .send(WorkerEvent::HistoryChunkApplied {
+                account_id,
+                chunk_index: mark.chunk_index,
+                is_last: mark.is_last,
+                messages: mark.messages,
+            })
+            .await;
+    }
+    for (account_id, count) in contacts_per_account {
+        let _ = event_tx
+            .send(WorkerEvent::ContactsSynced { account_id, count })
+            .await;
+    }
+    for (account_id, count) in groups_per_account {
+        let _ = event_tx
+            .send(WorkerEvent::GroupsSynced { account_id, count })
+            .await;
+    }
 
     log_flush_duration(started.elapsed(), count_msgs, count_contacts, count_groups);
     Ok(())
 }
 
+/// Descarta mensagens nossas que já estão no banco sob outro ID: o
+/// mesmo texto, no mesmo chat, a até a janela de dedup de distância. O
+/// WhatsApp às vezes re-chaveia a mensagem entre o eco ao vivo e o
+/// history sync, e o UNIQUE em `message_id` deixa passar as duas. Como
+/// a comparação é com o banco, vale também pra cópia que chega depois
+/// de reiniciar. Cada linha guardada absorve uma cópia só
+/// (`mark_rekeyed_copy`), então o mesmo texto enviado duas vezes fica.
+/// Só olha `is_from_me` com conteúdo — texto de terceiros repete de
+/// verdade ("ok", "kkk") e não tem como distinguir.
+async fn drop_own_duplicates(
+    db: &TinaDb,
+    messages: &mut HashMap<String, Vec<MessageData>>,
+) -> Result<()> {
+    let window_secs = db.message_dedup_window().await?;
+    if window_secs <= 0 {
+        return Ok(());
+    }
+    for (account_id, msgs) in messages.iter_mut() {
+        let mut kept = Vec::with_capacity(msgs.len());
+        for m in std::mem::take(msgs) {
+            let content = match m.content.as_deref() {
+                Some(c) if m.is_from_me && !c.is_empty() => c,
+                _ => {
+                    kept.push(m);
+                    continue;
+                }
+            };
+            let original = db
+                .find_similar_message(
+                    account_id,
+                    m.chat_jid.raw(),
+                    &m.message_id,
+                    content,
+                    m.timestamp,
+                    window_secs,
+                )
+                .await?;
+            let Some(original) = original else {
+                kept.push(m);
+                continue;
+            };
+            db.mark_rekeyed_copy(account_id, &original, &m.message_id)
+                .await?;
+            tracing::debug!(
+                "dedup: {} duplica {} em {}",
+                m.message_id,
+                original,
+                m.chat_jid.raw()
+            );
+        }
+        *msgs = kept;
+    }
+    Ok(())
+}
+
 async fn flush_messages(
     db: &TinaDb,
     event_tx: &mpsc::Sender<WorkerEvent>,
+    feed: &ChatFeed,
     buffer: &mut DirtyBuffer,
     affected: &mut HashMap<String, HashSet<String>>,
     open_snapshot: &HashMap<String, HashSet<String>>,
@@ -101,7 +192,7 @@ async fn flush_messages(
                         Some(m.sender_jid.raw())
                     },
                     content: m.content.as_deref(),
-                    message_type: &m.message_type,
+                    message_type: MessageType::from_raw(&m.message_type).as_str(),
                     timestamp: m.timestamp,
                     is_from_me: m.is_from_me,
                     raw_json: m.raw_json.as_deref(),
@@ -126,17 +217,25 @@ async fn flush_messages(
         // novas — emitir para todos enchia o canal e fazia a UI travar
         // mesmo descartando do outro lado. O snapshot de chats já chega
         // via ChatsUpserted; chats fechados re-carregam via OpenChat
-        // quando o usuário abrir a tab.
-        let Some(open_set) = open_for_account else {
-            let _ = res.active_chat_message_ids;
-            continue;
-        };
+        // quando o usuário abrir a tab. Chats com `subscribe_chat`
+        // ativo também buscam as rows, mas só pro feed.
         for (chat_id, msg_ids) in res.new_message_ids_per_chat {
-            if msg_ids.is_empty() || !open_set.contains(&chat_id) {
+            if msg_ids.is_empty() {
+                continue;
+            }
+            let is_open = open_for_account.is_some_and(|set| set.contains(&chat_id));
+            let is_watched = feed.is_watched(&account_id, &chat_id);
+            if !is_open && !is_watched {
                 continue;
             }
             let rows = db.get_message_rows_by_ids(&account_id, &msg_ids).await?;
-            if !rows.is_empty() {
+            if rows.is_empty() {
+                continue;
+            }
+            if is_watched {
+                feed.publish(&account_id, &chat_id, &rows);
+            }
+            if is_open {
                 let _ = event_tx
                     .send(WorkerEvent::MessagesAppended {
                         account_id: account_id.clone(),
@@ -151,39 +250,86 @@ async fn flush_messages(
     Ok(())
 }
 
-async fn flush_contacts(
+/// Aplica as edições depois das mensagens do mesmo flush. Edição de
+/// mensagem que não temos (ou mais velha que a já aplicada) é
+/// descartada — não há onde pendurar.
+async fn flush_edits(
     db: &TinaDb,
+    event_tx: &mpsc::Sender<WorkerEvent>,
     buffer: &mut DirtyBuffer,
     affected: &mut HashMap<String, HashSet<String>>,
 ) -> Result<()> {
+    let edits = std::mem::take(&mut buffer.edits);
+    for (account_id, list) in edits {
+        for edit in list {
+            let Some(chat_id) = db
+                .edit_message_content(
+                    &account_id,
+                    &edit.message_id,
+                    &edit.new_content,
+                    edit.edited_at,
+                )
+                .await?
+            else {
+                tracing::debug!("edit for unknown message {} ignored", edit.message_id);
+                continue;
+            };
+            affected
+                .entry(account_id.clone())
+                .or_default()
+                .insert(chat_id.clone());
+            let _ = event_tx
+                .send(WorkerEvent::MessageEdited {
+                    account_id: account_id.clone(),
+                    chat_id,
+                    message_id: edit.message_id,
+                    new_content: edit.new_content,
+                    edited_at: edit.edited_at,
+                })
+                .await;
+        }
+    }
+    Ok(())
+}
+
+/// Devolve quantos contatos foram gravados por account.
+async fn flush_contacts(
+    db: &TinaDb,
+    buffer: &mut DirtyBuffer,
+    affected: &mut HashMap<String, HashSet<String>>,
+) -> Result<HashMap<String, usize>> {
     let contacts = std::mem::take(&mut buffer.contacts);
+    let mut written = HashMap::new();
     for (account_id, list) in contacts {
+        written.insert(account_id.clone(), list.len());
         let chat_ids = process_contacts(db, &account_id, list).await?;
         affected
             .entry(account_id.clone())
             .or_default()
             .extend(chat_ids);
     }
-    Ok(())
+    Ok(written)
 }
 
 async fn flush_groups(
     db: &TinaDb,
     buffer: &mut DirtyBuffer,
     affected: &mut HashMap<String, HashSet<String>>,
-) -> Result<()> {
+) -> Result<HashMap<String, usize>> {
     let groups = std::mem::take(&mut buffer.groups);
+    let mut written = HashMap::new();
     for (account_id, list) in groups {
+        written.insert(account_id.clone(), list.len());
         let chat_ids = process_groups(db, &account_id, list).await?;
         affected
             .entry(account_id.clone())
             .or_default()
             .extend(chat_ids);
     }
-    Ok(())
+    Ok(written)
 }
 
-async fn emit_chats_upserted(
+pub(super) async fn emit_chats_upserted(
     db: &TinaDb,
     event_tx: &mpsc::Sender<WorkerEvent>,
     affected: HashMap<String, HashSet<String>>,
@@ -0,0 +1,160 @@
+// Forwarding a stored message to several chats in one go. Unlike
+// `send_message` there's no optimistic row: the copies only exist once
+// nanachi says which recipients accepted them (and under which
+// message IDs), so the worker waits for the `CommandResult` and writes
+// one local `forwarded` row per success.
+
+use std::time::Duration;
+
+use tina_core::{CommandResultData, IpcCommand, WaIdentity};
+use tina_db::ChatKind;
+
+use crate::error::{Result, WorkerError};
+use crate::events::WorkerEvent;
+
+use super::core::TinaWorker;
+
+/// Um upload de mídia por destinatário — folga pra vídeos.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How forwarding went for one recipient.
+#[derive(Debug, Clone)]
+pub struct ForwardOutcome {
+    /// Normalized JID, or the raw input when it didn't parse.
+    pub to: String,
+    /// The new message's ID, or why this recipient failed.
+    pub result: std::result::Result<String, String>,
+}
+
+impl TinaWorker {
+    /// Forwards `message_id` (stored in `from_chat`) to every chat in
+    /// `recipients` — JIDs or phone numbers, like `send_message`.
+    /// Fails as a whole only when the source message isn't stored (in
+    /// `from_chat`: its JID or chat ID) or nanachi rejects the command;
+    /// a bad or unreachable recipient
+    /// shows up as an `Err` in its own `ForwardOutcome`.
+    pub async fn forward_message(
+        &self,
+        account_id: &str,
+        from_chat: &str,
+        message_id: &str,
+        recipients: &[String],
+    ) -> Result<Vec<ForwardOutcome>> {
+        let source = self
+            .db
+            .get_message(account_id, message_id)
+            .await?
+            .ok_or_else(|| WorkerError::MessageNotFound(message_id.to_string()))?;
+        // O nanachi monta o encaminhamento com `from_chat`; outro chat
+        // mandaria outra mensagem, ou nada.
+        let in_chat = source.chat_id == from_chat
+            || self
+                .db
+                .get_chat_by_alias(account_id, from_chat)
+                .await?
+                .is_some_and(|chat| chat.chat_id == source.chat_id);
+        if !in_chat {
+            return Err(WorkerError::MessageNotInChat {
+                message_id: message_id.to_string(),
+                chat_id: from_chat.to_string(),
+            });
+        }
+
+        let mut outcomes = Vec::with_capacity(recipients.len());
+        let mut targets: Vec<String> = Vec::new();
+        for raw in recipients {
+            match tina_core::normalize_recipient(raw) {
+                Some(jid) if !targets.contains(&jid) => targets.push(jid),
+                Some(_) => {}
+                None => outcomes.push(ForwardOutcome {
+                    to: raw.clone(),
+                    result: Err(format!("invalid recipient: {raw:?}")),
+                }),
+            }
+        }
+        if targets.is_empty() {
+            return Ok(outcomes);
+        }
+
+        let reply = self
+            .send_and_wait(
+                IpcCommand::ForwardMessage {
+                    account_id: account_id.to_string(),
+                    from_chat: WaIdentity::parse(from_chat),
+                    message_id: message_id.to_string(),
+                    to: targets.iter().map(|t| WaIdentity::parse(t)).collect(),
+                    raw_json: source.raw_json.clone(),
+                    content: source.content.clone(),
+                },
+                FORWARD_TIMEOUT,
+            )
+            .await?;
+        if !reply.success {
+            return Err(WorkerError::CommandFailed(
+                reply.error.unwrap_or_else(|| "forward failed".into()),
+            ));
+        }
+        let results = match reply.data {
+            CommandResultData::Forwarded(results) => results,
+            _ => Vec::new(),
+        };
+
+        let now = tina_core::unix_now();
+        let mut touched_chats = Vec::new();
+        for target in targets {
+            let Some(r) = results.iter().find(|r| r.to == target) else {
+                outcomes.push(ForwardOutcome {
+                    to: target,
+                    result: Err("no result from nanachi".into()),
+                });
+                continue;
+            };
+            let new_id = match (&r.message_id, &r.error) {
+                (Some(id), None) => id.clone(),
+                (_, err) => {
+                    outcomes.push(ForwardOutcome {
+                        to: target,
+                        result: Err(err.clone().unwrap_or_else(|| "send failed".into())),
+                    });
+                    continue;
+                }
+            };
+
+            let chat_id = self
+                .db
+                .register_chat_alias(account_id, &target, ChatKind::infer_from_jid(&target))
+                .await?;
+            let ts = r.timestamp.unwrap_or(now);
+            if self
+                .db
+                .insert_forwarded_copy(account_id, message_id, &new_id, &chat_id, ts)
+                .await?
+            {
+                self.emit_pending_echo(account_id, &chat_id, &new_id).await;
+                touched_chats.push(chat_id);
+            }
+            outcomes.push(ForwardOutcome {
+                to: target,
+                result: Ok(new_id),
+            });
+        }
+
+        if !touched_chats.is_empty() {
+            match self.db.get_chat_rows(account_id, &touched_chats).await {
+                Ok(rows) if !rows.is_empty() => {
+                    let _ = self
+                        .event_tx
+                        .send(WorkerEvent::ChatsUpserted {
+                            account_id: account_id.to_string(),
+                            rows,
+                            messages_written: 0,
+                        })
+                        .await;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("chat rows after forward failed: {e}"),
+            }
+        }
+        Ok(outcomes)
+    }
+}
@@ -0,0 +1,144 @@
+// Group administration over nanachi: reading and resetting the invite
+// link, and joining a group through one. All wait for the
+// `CommandResult`; the link itself is cached in `groups.invite_link` by
+// the `GroupInviteLink` event handler, which the dispatcher runs before
+// the result reaches us.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tina_core::{
+    CommandRefusal, CommandResultData, GROUP_INVITE_HOST, IpcCommand, WaIdentity, group_invite_code,
+};
+
+use crate::error::{Result, WorkerError};
+use crate::events::WorkerEvent;
+
+use super::batch::process_groups;
+use super::core::TinaWorker;
+use super::flush::emit_chats_upserted;
+use super::replies::CommandReply;
+
+/// Uma ida ao servidor do WhatsApp, sem upload.
+const GROUP_ADMIN_TIMEOUT: Duration = Duration::from_secs(20);
+
+impl TinaWorker {
+    /// The group's current invite link. Fails with `NotGroupAdmin`
+    /// when our account can't see it.
+    pub async fn get_group_invite_link(&self, account_id: &str, group_jid: &str) -> Result<String> {
+        let reply = self
+            .send_and_wait(
+                IpcCommand::GetGroupInviteLink {
+                    account_id: account_id.to_string(),
+                    group_jid: WaIdentity::parse(group_jid),
+                },
+                GROUP_ADMIN_TIMEOUT,
+            )
+            .await?;
+        invite_link_from(reply, group_jid)
+    }
+
+    /// Invalidates the current invite link and returns the new one.
+    pub async fn revoke_group_invite_link(
+        &self,
+        account_id: &str,
+        group_jid: &str,
+    ) -> Result<String> {
+        let reply = self
+            .send_and_wait(
+                IpcCommand::RevokeGroupInviteLink {
+                    account_id: account_id.to_string(),
+                    group_jid: WaIdentity::parse(group_jid),
+                },
+                GROUP_ADMIN_TIMEOUT,
+            )
+            .await?;
+        invite_link_from(reply, group_jid)
+    }
+
+    /// Last invite link seen for the group, without asking nanachi.
+    pub async fn cached_group_invite_link(
+        &self,
+        account_id: &str,
+        group_jid: &str,
+    ) -> Result<Option<String>> {
+        Ok(self.db.get_group_invite_link(account_id, group_jid).await?)
+    }
+
+    /// Joins the group behind a `chat.whatsapp.com` invite link and
+    /// returns its JID. The link is checked locally before anything is
+    /// sent; the group row is written straight from the metadata in the
+    /// reply, so the chat shows up without waiting for a reconcile.
+    pub async fn join_group(&self, account_id: &str, link: &str) -> Result<String> {
+        let code = group_invite_code(link)
+            .ok_or_else(|| WorkerError::InvalidInviteLink(link.trim().to_string()))?;
+        let reply = self
+            .send_and_wait(
+                IpcCommand::JoinGroupByLink {
+                    account_id: account_id.to_string(),
+                    link: format!("https://{GROUP_INVITE_HOST}/{code}"),
+                },
+                GROUP_ADMIN_TIMEOUT,
+            )
+            .await?;
+        if !reply.success {
+            return Err(match reply.reason() {
+                Some("already_member") => WorkerError::AlreadyGroupMember(match reply.data {
+                    CommandResultData::Refused(CommandRefusal {
+                        group_jid: Some(jid),
+                        ..
+                    }) => jid,
+                    _ => code.to_string(),
+                }),
+                Some("invalid_link" | "link_revoked") => {
+                    WorkerError::InvalidInviteLink(link.trim().to_string())
+                }
+                _ => WorkerError::CommandFailed(
+                    reply.error.unwrap_or_else(|| "join group failed".into()),
+                ),
+            });
+        }
+        let CommandResultData::JoinedGroup(group) = reply.data else {
+            return Err(WorkerError::CommandFailed(
+                "nanachi sent no group metadata".into(),
+            ));
+        };
+        let group_jid = group.jid.raw().to_string();
+
+        let affected = process_groups(&self.db, account_id, vec![*group]).await?;
+        emit_chats_upserted(
+            &self.db,
+            &self.event_tx,
+            HashMap::from([(account_id.to_string(), affected)]),
+            HashMap::new(),
+        )
+        .await;
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::GroupsSynced {
+                account_id: account_id.to_string(),
+                count: 1,
+            })
+            .await;
+        Ok(group_jid)
+    }
+}
+
+fn invite_link_from(reply: CommandReply, group_jid: &str) -> Result<String> {
+    if !reply.success {
+        return Err(match reply.reason() {
+            Some("not_admin") => WorkerError::NotGroupAdmin(group_jid.to_string()),
+            _ => WorkerError::CommandFailed(
+                reply
+                    .error
+                    .unwrap_or_else(|| "invite link request failed".into()),
+            ),
+        });
+    }
+    match reply.data {
+        CommandResultData::InviteLink(link) => Ok(link),
+        _ => Err(WorkerError::CommandFailed(
+            "nanachi sent no invite link".into(),
+        )),
+    }
+}
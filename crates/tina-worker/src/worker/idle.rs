@@ -0,0 +1,415 @@
+// Idle stop, opt-in per account. `set_idle_policy` starts a watcher
+// that stops the account once `idle_after` goes by without a user
+// interaction — `get_messages`, `send_message` and `add_open_chat`
+// count as one. The `StopAccount` carries `IDLE_STOP_REASON`, so
+// nanachi's `Disconnected` reaches the UI as `DisconnectReason::Idle`.
+// The next interaction starts the account again through
+// `start_account`: nanachi loads the stored device and the session
+// comes back without a new pairing. Accounts stopped any other way are
+// left alone. Same generation scheme as `ConnectWatch`; one watcher task
+// per policy, which ends when the policy is replaced, cleared or reset
+// (`TinaWorker::stop`, or the worker dropped). While the account sits
+// stopped the watcher sleeps until an interaction wakes it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use tokio::sync::{Notify, mpsc};
+use tokio::time::Instant;
+
+use tina_core::{IDLE_STOP_REASON, IpcCommand};
+use tina_ipc::CommandSender;
+
+use crate::events::WorkerEvent;
+
+use super::bus::recv_event;
+use super::connection::{AccountConnectionState, ConnectionStates};
+use super::core::TinaWorker;
+
+/// Teto da espera pelo `Connected` quando um envio acorda a conta. O
+/// envio segue depois disso mesmo sem socket: a resposta do nanachi
+/// decide, como em qualquer envio. Um connect timeout menor vale.
+const IDLE_WAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Policy {
+    idle_after: Duration,
+    last_interaction: Instant,
+    /// Parada por ociosidade que nenhuma interação desfez ainda.
+    stopped: bool,
+    generation: u64,
+    /// Acorda o watcher: política trocada ou removida, ou interação
+    /// depois da parada.
+    wake: Arc<Notify>,
+}
+
+#[derive(Clone, Default)]
+pub(super) struct IdleWatch {
+    policies: Arc<Mutex<HashMap<String, Policy>>>,
+    /// Fonte das gerações. Fica fora do mapa pra sobreviver a `clear` e
+    /// `reset`: um watcher antigo que só acorda depois de uma política
+    /// nova nunca vê a própria geração de volta.
+    generations: Arc<AtomicU64>,
+}
+
+/// O que o watcher precisa para parar a conta. `commands` é lido na
+/// hora: o nanachi pode ter reiniciado desde o `set_idle_policy`.
+pub(super) struct IdleStop {
+    pub(super) commands: Arc<RwLock<Option<CommandSender>>>,
+    pub(super) connections: ConnectionStates,
+    pub(super) event_tx: mpsc::Sender<WorkerEvent>,
+}
+
+enum Check {
+    Gone,
+    /// Até o prazo, ou até ser acordado (`None`: só acordado).
+    Wait(Option<Duration>),
+    Due,
+}
+
+impl IdleWatch {
+    pub(super) fn set(&self, account_id: &str, idle_after: Duration, stop: IdleStop) {
+        let Ok(mut policies) = self.policies.lock() else {
+            return;
+        };
+        let generation = self.generations.fetch_add(1, Ordering::Relaxed);
+        let wake = Arc::new(Notify::new());
+        let previous = policies.insert(
+            account_id.to_string(),
+            Policy {
+                idle_after,
+                last_interaction: Instant::now(),
+                stopped: false,
+                generation,
+                wake: wake.clone(),
+            },
+        );
+        drop(policies);
+        // O watcher da política anterior vê a geração nova e sai.
+        if let Some(previous) = previous {
+            previous.wake.notify_one();
+        }
+
+        let watch = self.clone();
+        let account_id = account_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                match watch.check(&account_id, generation) {
+                    Check::Gone => return,
+                    Check::Wait(left) => {
+                        let deadline = async {
+                            match left {
+                                Some(d) => tokio::time::sleep(d).await,
+                                None => std::future::pending().await,
+                            }
+                        };
+                        tokio::select! {
+                            _ = deadline => {}
+                            _ = wake.notified() => {}
+                        }
+                    }
+                    Check::Due => watch.stop_idle(&account_id, generation, &stop).await,
+                }
+            }
+        });
+    }
+
+    pub(super) fn clear(&self, account_id: &str) {
+        let removed = self
+            .policies
+            .lock()
+            .ok()
+            .and_then(|mut policies| policies.remove(account_id));
+        if let Some(policy) = removed {
+            policy.wake.notify_one();
+        }
+    }
+
+    /// nanachi parou: as políticas saem junto, e os watchers com elas.
+    pub(super) fn reset(&self) {
+        let Ok(mut policies) = self.policies.lock() else {
+            return;
+        };
+        for (_, policy) in policies.drain() {
+            policy.wake.notify_one();
+        }
+    }
+
+    /// Uma interação do usuário. `true` quando a conta estava parada
+    /// por ociosidade e deve voltar.
+    pub(super) fn touch(&self, account_id: &str) -> bool {
+        let Ok(mut policies) = self.policies.lock() else {
+            return false;
+        };
+        let Some(policy) = policies.get_mut(account_id) else {
+            return false;
+        };
+        policy.last_interaction = Instant::now();
+        let woke = std::mem::take(&mut policy.stopped);
+        if woke {
+            policy.wake.notify_one();
+        }
+        woke
+    }
+
+    fn check(&self, account_id: &str, generation: u64) -> Check {
+        let Ok(policies) = self.policies.lock() else {
+            return Check::Gone;
+        };
+        let Some(policy) = policies
+            .get(account_id)
+            .filter(|p| p.generation == generation)
+        else {
+            return Check::Gone;
+        };
+        // Parada: só uma interação muda algo, e ela acorda o watcher.
+        if policy.stopped {
+            return Check::Wait(None);
+        }
+        let left =
+            (policy.last_interaction + policy.idle_after).saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            Check::Due
+        } else {
+            Check::Wait(Some(left))
+        }
+    }
+
+    async fn stop_idle(&self, account_id: &str, generation: u64, stop: &IdleStop) {
+        let active = stop.connections.get(account_id).is_active();
+        {
+            let Ok(mut policies) = self.policies.lock() else {
+                return;
+            };
+            let Some(policy) = policies
+                .get_mut(account_id)
+                .filter(|p| p.generation == generation)
+            else {
+                return;
+            };
+            // Não está de pé (parada à mão, nunca iniciada, deslogada):
+            // nada a parar, o prazo recomeça.
+            if !active {
+                policy.last_interaction = Instant::now();
+                return;
+            }
+            policy.stopped = true;
+        }
+        let Some(commands) = stop.commands.read().ok().and_then(|c| c.clone()) else {
+            return;
+        };
+        tracing::info!("{account_id}: no interaction within the idle window, stopping");
+        let command = IpcCommand::StopAccount {
+            account_id: account_id.to_string(),
+            reason: Some(IDLE_STOP_REASON.to_string()),
+        };
+        if let Err(e) = commands.send_command(command).await {
+            tracing::warn!("{account_id}: idle stop failed: {e}");
+            // Continua de pé: tenta de novo depois de outro período.
+            self.touch(account_id);
+            return;
+        }
+        stop.connections
+            .set(
+                account_id,
+                AccountConnectionState::Disconnected,
+                &stop.event_tx,
+            )
+            .await;
+    }
+}
+
+impl TinaWorker {
+    /// Stops `account_id` once it goes `idle_after` without user
+    /// interaction (`get_messages`, `send_message`, `add_open_chat`) and
+    /// starts it again on the next one. Replaces an earlier policy for
+    /// the account and starts the clock now; `Duration::ZERO` turns the
+    /// policy off.
+    pub fn set_idle_policy(&self, account_id: &str, idle_after: Duration) {
+        if idle_after.is_zero() {
+            self.idle.clear(account_id);
+            return;
+        }
+        let stop = IdleStop {
+            commands: self.commands.clone(),
+            connections: self.connections.clone(),
+            event_tx: self.event_tx.clone(),
+        };
+        self.idle.set(account_id, idle_after, stop);
+    }
+
+    /// Counts as an interaction for the idle policy and, if the account
+    /// was stopped by it, starts it again. `wait_connected` holds until
+    /// `Connected`, for at most `IDLE_WAKE_TIMEOUT` (or the connect
+    /// timeout, if shorter): a send needs the socket, a read doesn't.
+    pub(super) async fn wake_if_idle(&self, account_id: &str, wait_connected: bool) {
+        if !self.idle.touch(account_id) {
+            return;
+        }
+        tracing::info!("{account_id}: used after an idle stop, starting again");
+        let mut events = self.subscribe_events();
+        if let Err(e) = self.start_account(account_id).await {
+            tracing::warn!("{account_id}: start after idle stop failed: {e}");
+            return;
+        }
+        if !wait_connected {
+            return;
+        }
+        let connected = async {
+            while let Some(event) = recv_event(&mut events).await {
+                if matches!(&event, WorkerEvent::Connected { account_id: a, .. } if a == account_id)
+                {
+                    return;
+                }
+            }
+        };
+        let timeout = self
+            .connect_timeout
+            .map_or(IDLE_WAKE_TIMEOUT, |t| t.min(IDLE_WAKE_TIMEOUT));
+        if tokio::time::timeout(timeout, connected).await.is_err() {
+            tracing::warn!("{account_id}: not connected {timeout:?} after waking from idle");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    /// Conta "a" de pé, sem nanachi: a parada só marca a política.
+    async fn watched() -> (IdleWatch, mpsc::Receiver<WorkerEvent>) {
+        let (event_tx, event_rx) = mpsc::channel(16);
+        let connections = ConnectionStates::default();
+        connections
+            .set("a", AccountConnectionState::Connected, &event_tx)
+            .await;
+        let watch = IdleWatch::default();
+        let stop = IdleStop {
+            commands: Arc::new(RwLock::new(None)),
+            connections,
+            event_tx,
+        };
+        watch.set("a", WINDOW, stop);
+        (watch, event_rx)
+    }
+
+    /// Deixa o watcher rodar depois de mexer no relógio.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn is_stopped(watch: &IdleWatch) -> bool {
+        watch
+            .policies
+            .lock()
+            .unwrap()
+            .get("a")
+            .is_some_and(|p| p.stopped)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_after_the_window_and_an_interaction_wakes_it() {
+        let (watch, _events) = watched().await;
+        settle().await;
+        tokio::time::advance(WINDOW - Duration::from_secs(1)).await;
+        settle().await;
+        assert!(!is_stopped(&watch));
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        settle().await;
+        assert!(is_stopped(&watch));
+        // Parada, fica parada: nenhum período extra desfaz isso.
+        tokio::time::advance(WINDOW * 3).await;
+        settle().await;
+        assert!(is_stopped(&watch));
+
+        assert!(watch.touch("a"));
+        assert!(!watch.touch("a"));
+        assert!(!is_stopped(&watch));
+        // O watcher acordou e conta o prazo de novo.
+        tokio::time::advance(WINDOW + Duration::from_secs(1)).await;
+        settle().await;
+        assert!(is_stopped(&watch));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_interaction_pushes_the_deadline() {
+        let (watch, _events) = watched().await;
+        settle().await;
+        tokio::time::advance(Duration::from_secs(40)).await;
+        assert!(!watch.touch("a"));
+        tokio::time::advance(Duration::from_secs(40)).await;
+        settle().await;
+        assert!(!is_stopped(&watch));
+        tokio::time::advance(Duration::from_secs(21)).await;
+        settle().await;
+        assert!(is_stopped(&watch));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_account_that_is_not_up_is_left_alone() {
+        let (watch, events) = watched().await;
+        drop(events);
+        let connections = ConnectionStates::default();
+        let (event_tx, _rx) = mpsc::channel(16);
+        watch.set(
+            "a",
+            WINDOW,
+            IdleStop {
+                commands: Arc::new(RwLock::new(None)),
+                connections,
+                event_tx,
+            },
+        );
+        settle().await;
+        tokio::time::advance(WINDOW * 2).await;
+        settle().await;
+        assert!(!is_stopped(&watch));
+        assert!(!watch.touch("a"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watchers_exit_on_clear_replace_and_reset() {
+        let (event_tx, _rx) = mpsc::channel(16);
+        let stop = || IdleStop {
+            commands: Arc::new(RwLock::new(None)),
+            connections: ConnectionStates::default(),
+            event_tx: event_tx.clone(),
+        };
+        let (watch, _events) = watched().await;
+        settle().await;
+        // O watch do teste mais o do watcher.
+        assert_eq!(Arc::strong_count(&watch.policies), 2);
+
+        watch.clear("a");
+        settle().await;
+        assert_eq!(Arc::strong_count(&watch.policies), 1);
+        assert!(!watch.touch("a"));
+
+        // Política nova antes do watcher da anterior acordar: ele não
+        // pode tomar a geração dela pela sua.
+        watch.set("a", WINDOW, stop());
+        settle().await;
+        watch.clear("a");
+        watch.set("a", WINDOW, stop());
+        settle().await;
+        assert_eq!(Arc::strong_count(&watch.policies), 2);
+
+        let (watch, _events) = watched().await;
+        watch.set("a", WINDOW, stop());
+        watch.set("b", WINDOW, stop());
+        settle().await;
+        // A primeira política de "a" foi trocada: sobra um watcher por conta.
+        assert_eq!(Arc::strong_count(&watch.policies), 3);
+
+        watch.reset();
+        settle().await;
+        assert_eq!(Arc::strong_count(&watch.policies), 1);
+        assert!(!watch.touch("a") && !watch.touch("b"));
+    }
+}
@@ -0,0 +1,132 @@
+// First full load of an account: its contact list, its groups and the
+// history sync. Each arrives on its own (`ContactsUpsert`,
+// `GroupsUpsert`, `HistorySyncComplete`), interleaved with incremental
+// updates that look the same, so the dispatcher ticks them off here and
+// emits `InitialSyncComplete` once, when the last of the three lands.
+// The parts are counted in memory, but the completion is persisted per
+// account (`TinaDb::mark_initial_sync_done`): after a restart the
+// incremental loads tick the parts off again and nothing is announced.
+// A logout clears the flag and starts the count over: the next pairing
+// loads everything again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tina_core::IpcEvent;
+use tina_db::TinaDb;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SyncPart {
+    Contacts,
+    Groups,
+    History,
+}
+
+#[derive(Clone, Default)]
+pub(super) struct InitialSyncs {
+    inner: Arc<Mutex<HashMap<String, Progress>>>,
+}
+
+#[derive(Default)]
+struct Progress {
+    contacts: bool,
+    groups: bool,
+    history: bool,
+    /// `InitialSyncComplete` já saiu.
+    announced: bool,
+}
+
+impl InitialSyncs {
+    /// Ticks `part` off for `account_id`. `true` exactly once per
+    /// account: on the call that completes the three parts.
+    pub(super) fn mark(&self, account_id: &str, part: SyncPart) -> bool {
+        let Ok(mut state) = self.inner.lock() else {
+            return false;
+        };
+        let progress = state.entry(account_id.to_string()).or_default();
+        match part {
+            SyncPart::Contacts => progress.contacts = true,
+            SyncPart::Groups => progress.groups = true,
+            SyncPart::History => progress.history = true,
+        }
+        if progress.announced || !(progress.contacts && progress.groups && progress.history) {
+            return false;
+        }
+        progress.announced = true;
+        true
+    }
+
+    /// Feeds an IPC event in; returns the account whose initial sync it
+    /// completed, if any.
+    pub(super) fn record(&self, event: &IpcEvent) -> Option<String> {
+        let (account_id, part) = match event {
+            IpcEvent::ContactsUpsert { account_id, .. } => (account_id, SyncPart::Contacts),
+            IpcEvent::GroupsUpsert { account_id, .. } => (account_id, SyncPart::Groups),
+            IpcEvent::HistorySyncComplete { account_id, .. } => (account_id, SyncPart::History),
+            IpcEvent::LoggedOut { account_id } => {
+                self.forget_account(account_id);
+                return None;
+            }
+            _ => return None,
+        };
+        self.mark(account_id, part).then(|| account_id.clone())
+    }
+
+    /// Persists a completion `mark` or `record` reported. `false` when
+    /// the account already finished in an earlier session and the event
+    /// shouldn't go out again; a DB failure errs on announcing.
+    pub(super) async fn persist(db: &TinaDb, account_id: &str) -> bool {
+        db.mark_initial_sync_done(account_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("initial sync de {} não gravado: {}", account_id, e);
+                true
+            })
+    }
+
+    pub(super) fn is_complete(&self, account_id: &str) -> bool {
+        self.inner
+            .lock()
+            .is_ok_and(|state| state.get(account_id).is_some_and(|p| p.announced))
+    }
+
+    pub(super) fn forget_account(&self, account_id: &str) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.remove(account_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_once_after_all_three_parts() {
+        let syncs = InitialSyncs::default();
+        assert!(!syncs.mark("a", SyncPart::Contacts));
+        // Lotes incrementais repetem partes já vistas.
+        assert!(!syncs.mark("a", SyncPart::Contacts));
+        assert!(!syncs.mark("a", SyncPart::History));
+        assert!(!syncs.is_complete("a"));
+        assert!(syncs.mark("a", SyncPart::Groups));
+        assert!(syncs.is_complete("a"));
+        assert!(!syncs.mark("a", SyncPart::Groups));
+        // Outra conta conta do zero.
+        assert!(!syncs.mark("b", SyncPart::Groups));
+    }
+
+    #[test]
+    fn logout_starts_over() {
+        let syncs = InitialSyncs::default();
+        for part in [SyncPart::Contacts, SyncPart::Groups, SyncPart::History] {
+            syncs.mark("a", part);
+        }
+        assert!(syncs.is_complete("a"));
+        syncs.record(&IpcEvent::LoggedOut {
+            account_id: "a".into(),
+        });
+        assert!(!syncs.is_complete("a"));
+        assert!(!syncs.mark("a", SyncPart::Contacts));
+    }
+}
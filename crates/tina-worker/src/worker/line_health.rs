@@ -0,0 +1,104 @@
+// Bookkeeping for nanachi stdout lines that aren't IPC messages. A
+// stray one (a debug print, the tail of a line cut by a crash) only
+// gets a warning; when most of what arrives over a window is garbage,
+// the two sides are most likely speaking different protocol versions,
+// and the UI hears about it once per window.
+
+use std::time::{Duration, Instant};
+
+/// Window over which the failure rate is measured.
+const WINDOW: Duration = Duration::from_secs(60);
+/// Below this many failures in a window it's noise, whatever the rate.
+const MIN_FAILURES: u32 = 20;
+/// Fraction of the window's lines that must fail to raise the alarm.
+const MAX_FAILURE_RATIO: f64 = 0.5;
+
+#[derive(Default)]
+pub(super) struct LineHealth {
+    /// Unparseable lines since the dispatcher started.
+    total_failures: u64,
+    window_start: Option<Instant>,
+    lines: u32,
+    failures: u32,
+    reported: bool,
+}
+
+impl LineHealth {
+    pub(super) fn record_ok(&mut self, now: Instant) {
+        self.roll(now);
+        self.lines += 1;
+    }
+
+    /// Conta uma linha ilegível. `true` quando a janela atual acabou de
+    /// cruzar o limite — só uma vez por janela.
+    pub(super) fn record_failure(&mut self, now: Instant) -> bool {
+        self.roll(now);
+        self.lines += 1;
+        self.failures += 1;
+        self.total_failures += 1;
+        if self.reported || self.failures < MIN_FAILURES {
+            return false;
+        }
+        let ratio = f64::from(self.failures) / f64::from(self.lines);
+        self.reported = ratio > MAX_FAILURE_RATIO;
+        self.reported
+    }
+
+    pub(super) fn total_failures(&self) -> u64 {
+        self.total_failures
+    }
+
+    fn roll(&mut self, now: Instant) {
+        let expired = self
+            .window_start
+            .is_none_or(|start| now.duration_since(start) >= WINDOW);
+        if expired {
+            self.window_start = Some(now);
+            self.lines = 0;
+            self.failures = 0;
+            self.reported = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failures(health: &mut LineHealth, now: Instant, n: u32) -> u32 {
+        (0..n).filter(|_| health.record_failure(now)).count() as u32
+    }
+
+    #[test]
+    fn a_few_stray_lines_are_only_counted() {
+        let mut health = LineHealth::default();
+        let now = Instant::now();
+        assert_eq!(failures(&mut health, now, MIN_FAILURES - 1), 0);
+        assert_eq!(health.total_failures(), u64::from(MIN_FAILURES - 1));
+    }
+
+    #[test]
+    fn alarm_needs_most_of_the_window_to_fail() {
+        let mut health = LineHealth::default();
+        let now = Instant::now();
+        for _ in 0..MIN_FAILURES * 2 {
+            health.record_ok(now);
+        }
+        // Metade ilegível não basta; passa do limite na seguinte.
+        assert_eq!(failures(&mut health, now, MIN_FAILURES * 2), 0);
+        assert!(health.record_failure(now));
+    }
+
+    #[test]
+    fn alarm_fires_once_per_window() {
+        let mut health = LineHealth::default();
+        let start = Instant::now();
+        assert_eq!(failures(&mut health, start, MIN_FAILURES * 3), 1);
+        assert_eq!(failures(&mut health, start + WINDOW / 2, MIN_FAILURES), 0);
+
+        let next = start + WINDOW;
+        assert_eq!(failures(&mut health, next, MIN_FAILURES - 1), 0);
+        assert!(health.record_failure(next));
+        assert_eq!(health.total_failures(), u64::from(MIN_FAILURES * 5));
+    }
+}
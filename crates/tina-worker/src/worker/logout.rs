@@ -0,0 +1,39 @@
+// Logout requested from our side. `TinaWorker::logout` registers the
+// account here before sending `IpcCommand::Logout`; when nanachi
+// answers with `LoggedOut` the realtime handler takes the entry, wipes
+// the auth state with the requested `purge` and wakes the caller. A
+// `LoggedOut` with no entry is a remote logout (unlinked from the
+// phone) and keeps the synced data.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+pub(super) struct PendingLogout {
+    pub(super) purge: bool,
+    pub(super) done: oneshot::Sender<()>,
+}
+
+#[derive(Clone, Default)]
+pub(super) struct PendingLogouts {
+    inner: Arc<Mutex<HashMap<String, PendingLogout>>>,
+}
+
+impl PendingLogouts {
+    /// Um segundo logout da mesma conta substitui o primeiro; o
+    /// receiver antigo vê o sender dropado e sai pelo caminho de erro.
+    pub(super) fn register(&self, account_id: &str, purge: bool) -> oneshot::Receiver<()> {
+        let (done, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.inner.lock() {
+            pending.insert(account_id.to_string(), PendingLogout { purge, done });
+        }
+        rx
+    }
+
+    /// Quem tirar a entrada faz a limpeza — o handler do `LoggedOut` ou
+    /// o próprio `logout` quando o nanachi não responde a tempo.
+    pub(super) fn take(&self, account_id: &str) -> Option<PendingLogout> {
+        self.inner.lock().ok()?.remove(account_id)
+    }
+}
@@ -4,19 +4,71 @@
 //
 // Submodules:
 //   * `core`        — `TinaWorker` struct + small forwarding methods
-//   * `download`    — `download_media` with cache/dedup short-circuits
+//   * `bus`         — fan-out of `WorkerEvent`s to `subscribe_events`
+//   * `download`    — `download_media` with cache/dedup short-circuits,
+//                     `get_media`, cache budget eviction
 //   * `dispatcher`  — IPC reader → DirtyBuffer → flush
+//   * `line_health` — counts unparseable nanachi lines, flags protocol drift
+//   * `pressure`    — channel fill warnings, lossy progress events
 //   * `realtime`    — handlers for low-volume events (Connected, QR, …)
+//   * `qr`          — per-account QR expiry timers
+//   * `logout`      — logouts waiting for nanachi's `LoggedOut`
+//   * `replies`     — commands awaiting their `CommandResult`
+//   * `forward`     — `forward_message` and its per-recipient outcome
+//   * `export`      — `export_chat`: one chat as text / JSON / HTML
+//   * `broadcast`   — `send_broadcast`: one text, many chats, paced
+//   * `send`        — text send reply wait, `failed` marking, `retry_send`
+//   * `groups`      — group invite links, joining by link
+//   * `pull`        — on-demand contact/group refetch from the server
+//   * `profile`     — `fetch_profile`, rate-limited per contact
+//   * `number_check` — `check_number`, spaced per account
+//   * `connection`  — in-memory per-account connection state
+//   * `connect`     — `start_account` timeout when nanachi stays silent
+//   * `idle`        — opt-in stop of accounts left unused, restart on use
+//   * `network`     — restart of live accounts after a network change
+//                     (feature `network-watch`)
+//   * `sync_cancel` — accounts whose history sync the user cancelled
+//   * `initial_sync` — first contacts + groups + history load per account
 //   * `batch`       — pure DB-batch helpers (contacts/groups)
 //   * `flush`       — apply buffer + emit `ChatsUpserted`
+//   * `feed`        — per-chat push stream behind `subscribe_chat`
 //   * `buffer`      — the buffer struct + flush thresholds
 
 mod batch;
+mod broadcast;
+mod bus;
 mod buffer;
+mod connect;
+mod connection;
 mod core;
 mod dispatcher;
 mod download;
+mod export;
+mod feed;
 mod flush;
+mod forward;
+mod groups;
+mod idle;
+mod initial_sync;
+mod line_health;
+mod logout;
+#[cfg(feature = "network-watch")]
+mod network;
+mod number_check;
+mod pressure;
+mod profile;
+mod pull;
+mod qr;
 mod realtime;
+mod replies;
+mod send;
+mod sync_cancel;
 
-pub use core::TinaWorker;
+pub use broadcast::BroadcastOutcome;
+pub use bus::recv_event;
+pub use connection::{AccountConnectionState, AccountStatus};
+pub use core::{
+    DEFAULT_MESSAGE_PAGE_SIZE, MESSAGE_PAGE_SIZE_ENV, MESSAGE_PAGE_SIZE_RANGE, TinaWorker, WorkerConfig,
+};
+pub use export::ExportFormat;
+pub use forward::ForwardOutcome;
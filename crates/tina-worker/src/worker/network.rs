@@ -0,0 +1,173 @@
+// Restart after a network change (feature `network-watch`). whatsmeow
+// only notices a dead socket when a keepalive times out, and after the
+// network comes back it can sit on the old route for minutes. The
+// frontend, which sees the OS connectivity signal, calls
+// `notify_network_changed`: going offline records the accounts that were
+// up; coming back stops and starts each of them again, in parallel, so
+// the new socket goes out over the new route. A repeated signal without
+// a drop in between is ignored. Stopping or deleting an account while
+// offline takes it off the list. nanachi keeps the device, so no
+// pairing is involved.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tina_core::{IpcCommand, NETWORK_CHANGE_REASON};
+
+use crate::error::Result;
+use crate::events::WorkerEvent;
+
+use super::connection::AccountConnectionState;
+use super::core::TinaWorker;
+
+/// Espera pelo `CommandResult` do `StopAccount` antes do novo start.
+const STOP_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Default)]
+pub(super) struct NetworkWatch {
+    inner: Arc<Mutex<NetworkState>>,
+}
+
+#[derive(Default)]
+struct NetworkState {
+    offline: bool,
+    /// Contas de pé quando a rede caiu.
+    dropped: BTreeSet<String>,
+}
+
+impl NetworkWatch {
+    /// Registra a mudança. `None`: nada mudou; senão as contas a
+    /// reiniciar — vazio ao cair.
+    fn transition(&self, online: bool, active: Vec<String>) -> Option<Vec<String>> {
+        let Ok(mut state) = self.inner.lock() else {
+            return None;
+        };
+        if state.offline != online {
+            return None;
+        }
+        state.offline = !online;
+        if !online {
+            state.dropped = active.into_iter().collect();
+            return Some(Vec::new());
+        }
+        let mut restart = std::mem::take(&mut state.dropped);
+        restart.extend(active);
+        Some(restart.into_iter().collect())
+    }
+
+    /// A conta parada ou apagada à mão não volta sozinha com a rede.
+    pub(super) fn forget_account(&self, account_id: &str) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.dropped.remove(account_id);
+        }
+    }
+}
+
+impl TinaWorker {
+    /// Tells the worker the OS connectivity changed; `online` is whether
+    /// a network is available now. Going offline remembers which accounts
+    /// were up; coming back stops and starts those accounts again, each
+    /// in its own task, so this returns without waiting for them. A
+    /// repeated signal with no change in between does nothing. Sends
+    /// `WorkerEvent::NetworkChanged` first; the restarts then show up as
+    /// the usual `Disconnected`, `ConnectionStateChanged` and `Connected`.
+    pub async fn notify_network_changed(self: &Arc<Self>, online: bool) {
+        if self.offline {
+            return;
+        }
+        let active = self.connections.active_accounts();
+        let Some(restarting) = self.network.transition(online, active) else {
+            return;
+        };
+        tracing::info!(online, ?restarting, "network changed");
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::NetworkChanged {
+                online,
+                restarting: restarting.clone(),
+            })
+            .await;
+        for account_id in restarting {
+            let worker = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = worker.restart_after_network_change(&account_id).await {
+                    tracing::warn!("{account_id}: restart after network change failed: {e}");
+                }
+            });
+        }
+    }
+
+    async fn restart_after_network_change(&self, account_id: &str) -> Result<()> {
+        let state = self.connections.get(account_id);
+        if state == AccountConnectionState::LoggedOut {
+            return Ok(());
+        }
+        if state.is_active() {
+            self.connect_watch.disarm(account_id);
+            // O `Disconnected` do stop passa pelo realtime antes do
+            // `CommandResult` ser entregue: ao voltar daqui a conta já
+            // está `Disconnected`.
+            self.send_and_wait(
+                IpcCommand::StopAccount {
+                    account_id: account_id.to_string(),
+                    reason: Some(NETWORK_CHANGE_REASON.to_string()),
+                },
+                STOP_REPLY_TIMEOUT,
+            )
+            .await?;
+        }
+        // nanachi sem client pra conta não manda `Disconnected`; sem
+        // isso o `start_account` acharia que ela ainda está de pé.
+        self.connections
+            .set(
+                account_id,
+                AccountConnectionState::Disconnected,
+                &self.event_tx,
+            )
+            .await;
+        self.start_account(account_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn coming_back_restarts_what_was_up_when_the_network_dropped() {
+        let watch = NetworkWatch::default();
+        assert_eq!(watch.transition(false, ids(&["a", "b"])), Some(vec![]));
+        // Queda repetida não muda o que foi guardado.
+        assert_eq!(watch.transition(false, ids(&[])), None);
+        assert_eq!(
+            watch.transition(true, ids(&["c"])),
+            Some(ids(&["a", "b", "c"]))
+        );
+        // Já de volta: o que ficou guardado foi consumido.
+        assert_eq!(watch.transition(false, ids(&[])), Some(vec![]));
+        assert_eq!(watch.transition(true, ids(&[])), Some(vec![]));
+    }
+
+    #[test]
+    fn online_without_a_drop_restarts_nothing() {
+        let watch = NetworkWatch::default();
+        assert_eq!(watch.transition(true, ids(&["a"])), None);
+        assert_eq!(watch.transition(true, ids(&["a"])), None);
+        assert_eq!(watch.transition(false, ids(&["a"])), Some(vec![]));
+        assert_eq!(watch.transition(true, ids(&[])), Some(ids(&["a"])));
+        assert_eq!(watch.transition(true, ids(&["a"])), None);
+    }
+
+    #[test]
+    fn forgotten_accounts_are_not_restarted() {
+        let watch = NetworkWatch::default();
+        watch.transition(false, ids(&["a", "b"]));
+        watch.forget_account("a");
+        assert_eq!(watch.transition(true, ids(&[])), Some(ids(&["b"])));
+    }
+}
@@ -0,0 +1,180 @@
+// Whether a phone number is on WhatsApp, for starting a chat with it.
+// nanachi answers `CheckNumber` with a `NumberCheckResult` (the canonical
+// JID when registered), which the dispatcher records here before the
+// `CommandResult` resolves the wait. WhatsApp flags accounts that probe
+// many numbers in a row, so lookups from one account go out at least
+// `CHECK_SPACING` apart and an answer is reused for `CHECK_TTL`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tina_core::{IpcCommand, IpcEvent, WaIdentity};
+
+use crate::error::{Result, WorkerError};
+
+use super::core::TinaWorker;
+
+/// Uma ida ao servidor (usync).
+const CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Intervalo mínimo entre duas consultas da mesma conta.
+const CHECK_SPACING: Duration = Duration::from_secs(3);
+
+/// Tempo em que uma resposta vale sem perguntar de novo.
+const CHECK_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Default)]
+pub(super) struct NumberChecks {
+    inner: Arc<Mutex<ChecksState>>,
+}
+
+#[derive(Default)]
+struct ChecksState {
+    /// `(account_id, phone)` → JID (ou `None`: sem WhatsApp) e quando.
+    answers: HashMap<(String, String), (Option<String>, Instant)>,
+    /// Próxima consulta liberada, por conta.
+    next_slot: HashMap<String, Instant>,
+}
+
+impl NumberChecks {
+    /// Stored answer still within `CHECK_TTL`.
+    fn cached(&self, account_id: &str, phone: &str) -> Option<Option<String>> {
+        let state = self.inner.lock().ok()?;
+        state
+            .answers
+            .get(&(account_id.to_string(), phone.to_string()))
+            .filter(|(_, at)| at.elapsed() < CHECK_TTL)
+            .map(|(jid, _)| jid.clone())
+    }
+
+    /// Takes the account's next free slot and says when it starts;
+    /// callers sleep until then.
+    fn reserve_slot(&self, account_id: &str) -> Instant {
+        let now = Instant::now();
+        let Ok(mut state) = self.inner.lock() else {
+            return now;
+        };
+        let slot = state
+            .next_slot
+            .get(account_id)
+            .map_or(now, |next| (*next).max(now));
+        state
+            .next_slot
+            .insert(account_id.to_string(), slot + CHECK_SPACING);
+        slot
+    }
+
+    /// Keeps the answer of a `NumberCheckResult`; other events are
+    /// ignored.
+    pub(super) fn record(&self, event: &IpcEvent) {
+        let IpcEvent::NumberCheckResult {
+            account_id,
+            phone,
+            exists,
+            jid,
+        } = event
+        else {
+            return;
+        };
+        let jid = jid
+            .as_ref()
+            .filter(|_| *exists)
+            .map(|j| j.raw().to_string());
+        if let Ok(mut state) = self.inner.lock() {
+            state.answers.retain(|_, (_, at)| at.elapsed() < CHECK_TTL);
+            state
+                .answers
+                .insert((account_id.clone(), phone.clone()), (jid, Instant::now()));
+        }
+    }
+
+    /// Conta removida: nada dela vale mais.
+    pub(super) fn forget_account(&self, account_id: &str) {
+        if let Ok(mut state) = self.inner.lock() {
+            state
+                .answers
+                .retain(|(account, _), _| account != account_id);
+            state.next_slot.remove(account_id);
+        }
+    }
+}
+
+impl TinaWorker {
+    /// Asks WhatsApp whether `phone` has an account. `phone` goes
+    /// through the same normalization as a send recipient and must be a
+    /// number, not a group. Returns the JID to open the chat with, or
+    /// `None` when the number isn't on WhatsApp. Lookups from one
+    /// account are spaced a few seconds apart, and the same number is
+    /// only asked again after an hour.
+    pub async fn check_number(&self, account_id: &str, phone: &str) -> Result<Option<String>> {
+        self.ensure_online()?;
+        let jid = tina_core::normalize_recipient(phone)
+            .filter(|j| matches!(WaIdentity::parse(j), WaIdentity::Phone(_)))
+            .ok_or_else(|| WorkerError::InvalidRecipient(phone.to_string()))?;
+        let digits = WaIdentity::parse(&jid).user().to_string();
+        if let Some(answer) = self.number_checks.cached(account_id, &digits) {
+            return Ok(answer);
+        }
+        let slot = self.number_checks.reserve_slot(account_id);
+        tokio::time::sleep_until(slot.into()).await;
+        // Outra chamada pro mesmo número pode ter respondido na espera.
+        if let Some(answer) = self.number_checks.cached(account_id, &digits) {
+            return Ok(answer);
+        }
+        let reply = self
+            .send_and_wait(
+                IpcCommand::CheckNumber {
+                    account_id: account_id.to_string(),
+                    phone: digits.clone(),
+                },
+                CHECK_TIMEOUT,
+            )
+            .await?;
+        if !reply.success {
+            return Err(WorkerError::CommandFailed(
+                reply.error.unwrap_or_else(|| "number check failed".into()),
+            ));
+        }
+        self.number_checks
+            .cached(account_id, &digits)
+            .ok_or_else(|| WorkerError::CommandFailed("no NumberCheckResult from nanachi".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups_from_one_account_are_spaced() {
+        let checks = NumberChecks::default();
+        let first = checks.reserve_slot("a");
+        let second = checks.reserve_slot("a");
+        assert_eq!(second - first, CHECK_SPACING);
+        // Outra conta não espera pela primeira.
+        assert!(checks.reserve_slot("b") < second);
+    }
+
+    #[test]
+    fn answers_are_kept_per_account() {
+        let checks = NumberChecks::default();
+        let result = |account: &str, jid: Option<&str>| IpcEvent::NumberCheckResult {
+            account_id: account.into(),
+            phone: "5511999999999".into(),
+            exists: jid.is_some(),
+            jid: jid.map(WaIdentity::parse),
+        };
+        checks.record(&result("a", Some("5511999999999@s.whatsapp.net")));
+        checks.record(&result("b", None));
+        assert_eq!(
+            checks.cached("a", "5511999999999"),
+            Some(Some("5511999999999@s.whatsapp.net".into()))
+        );
+        assert_eq!(checks.cached("b", "5511999999999"), Some(None));
+        assert_eq!(checks.cached("c", "5511999999999"), None);
+
+        checks.forget_account("a");
+        assert_eq!(checks.cached("a", "5511999999999"), None);
+    }
+}
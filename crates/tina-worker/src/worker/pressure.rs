@@ -0,0 +1,68 @@
+// Fill level of the bounded channels around the dispatcher: nanachi
+// lines coming in (watched by the dispatcher) and `WorkerEvent`s going
+// out to the UI (watched by the fan-out in `bus`, on the queues the UI
+// actually drains). A full channel is silent by itself — the sender
+// just awaits — so this warns once when one crosses the high-water mark
+// and once when it drains, instead of on every line while it stays
+// backed up.
+//
+// Progress events go through `emit_progress`: they're superseded by
+// the next one anyway, so when the UI channel is full they're dropped
+// (and counted) rather than holding up the dispatcher behind them.
+
+use tokio::sync::mpsc;
+
+use tina_ipc::IpcMetrics;
+
+use crate::events::WorkerEvent;
+
+/// Fraction of the capacity above which a channel counts as backed up.
+const HIGH_WATER: f64 = 0.8;
+/// Back below this, the warning re-arms.
+const LOW_WATER: f64 = 0.5;
+
+pub(super) struct QueuePressure {
+    name: &'static str,
+    high: bool,
+}
+
+impl QueuePressure {
+    pub(super) fn new(name: &'static str) -> Self {
+        Self { name, high: false }
+    }
+
+    pub(super) fn observe(&mut self, depth: usize, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        let ratio = depth as f64 / capacity as f64;
+        if !self.high && ratio > HIGH_WATER {
+            self.high = true;
+            tracing::warn!(
+                "{} channel {depth}/{capacity} full — consumer falling behind",
+                self.name
+            );
+        } else if self.high && ratio < LOW_WATER {
+            self.high = false;
+            tracing::info!("{} channel drained ({depth}/{capacity})", self.name);
+        }
+    }
+
+    /// Queued items on the sending side of `tx`.
+    pub(super) fn observe_sender<T>(&mut self, tx: &mpsc::Sender<T>) {
+        let capacity = tx.max_capacity();
+        self.observe(capacity - tx.capacity(), capacity);
+    }
+}
+
+/// `try_send` for events that only report progress. A full channel
+/// drops the event and counts it in `IpcStats::dropped_events`.
+pub(super) fn emit_progress(
+    event_tx: &mpsc::Sender<WorkerEvent>,
+    metrics: &IpcMetrics,
+    event: WorkerEvent,
+) {
+    if let Err(mpsc::error::TrySendError::Full(_)) = event_tx.try_send(event) {
+        metrics.record_dropped_event();
+    }
+}
@@ -0,0 +1,83 @@
+// Contact profile on demand (name, status text, picture URL), for the
+// contact-info view. nanachi answers `GetProfile` with a
+// `ProfileResult`, which the realtime handler writes to the contact row
+// before the `CommandResult` reaches us; we then read the row back.
+// Each (account, JID) is asked at most once per `PROFILE_TTL`: inside
+// that window the stored row is returned as is. Fields the contact's
+// privacy settings hide come back `None` and clear what was stored.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tina_core::{IpcCommand, WaIdentity};
+use tina_db::Contact;
+
+use crate::error::{Result, WorkerError};
+
+use super::core::TinaWorker;
+
+/// Uma ida ao servidor (info + foto).
+const PROFILE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Tempo em que um perfil buscado vale sem perguntar de novo.
+const PROFILE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Default)]
+pub(super) struct ProfileFetches {
+    /// Último fetch bem-sucedido por `(account_id, jid)`.
+    fetched: Arc<Mutex<HashMap<(String, String), Instant>>>,
+}
+
+impl ProfileFetches {
+    fn is_fresh(&self, account_id: &str, jid: &str) -> bool {
+        let Ok(fetched) = self.fetched.lock() else {
+            return false;
+        };
+        fetched
+            .get(&(account_id.to_string(), jid.to_string()))
+            .is_some_and(|at| at.elapsed() < PROFILE_TTL)
+    }
+
+    fn mark(&self, account_id: &str, jid: &str) {
+        if let Ok(mut fetched) = self.fetched.lock() {
+            fetched.retain(|_, at| at.elapsed() < PROFILE_TTL);
+            fetched.insert((account_id.to_string(), jid.to_string()), Instant::now());
+        }
+    }
+
+    /// Conta removida: nada dela vale mais.
+    pub(super) fn forget_account(&self, account_id: &str) {
+        if let Ok(mut fetched) = self.fetched.lock() {
+            fetched.retain(|(account, _), _| account != account_id);
+        }
+    }
+}
+
+impl TinaWorker {
+    /// Fetches `jid`'s profile from WhatsApp, stores it on the contact
+    /// row and returns the row. Repeated calls for the same JID within
+    /// an hour return the stored row without asking the server again.
+    pub async fn fetch_profile(&self, account_id: &str, jid: &str) -> Result<Option<Contact>> {
+        if !self.profiles.is_fresh(account_id, jid) {
+            let reply = self
+                .send_and_wait(
+                    IpcCommand::GetProfile {
+                        account_id: account_id.to_string(),
+                        jid: WaIdentity::parse(jid),
+                    },
+                    PROFILE_TIMEOUT,
+                )
+                .await?;
+            if !reply.success {
+                return Err(WorkerError::CommandFailed(
+                    reply
+                        .error
+                        .unwrap_or_else(|| "profile request failed".into()),
+                ));
+            }
+            self.profiles.mark(account_id, jid);
+        }
+        Ok(self.db.get_contact_by_alias(account_id, jid).await?)
+    }
+}
@@ -0,0 +1,62 @@
+// On-demand refetch, for when push events were missed (nanachi was
+// down, a sync was cut short). The data itself doesn't ride in the
+// `CommandResult`: nanachi sends it as the usual chunked
+// `ContactsUpsert` / `GroupsUpsert` ahead of the result, so it lands
+// through the same buffer and flush as a live sync. The result only
+// carries how many went out.
+
+use std::time::Duration;
+
+use tina_core::{CommandResultData, IpcCommand};
+
+use crate::error::{Result, WorkerError};
+
+use super::core::TinaWorker;
+use super::replies::CommandReply;
+
+/// O nanachi dá 60 s para o servidor; aqui sobra margem para os lotes.
+const PULL_TIMEOUT: Duration = Duration::from_secs(75);
+
+impl TinaWorker {
+    /// Re-syncs the contact list with WhatsApp and re-imports it.
+    /// Returns how many contacts nanachi sent; they reach the DB with
+    /// the next buffer flush, followed by `ContactsSynced`.
+    pub async fn request_contacts_refresh(&self, account_id: &str) -> Result<usize> {
+        let reply = self
+            .send_and_wait(
+                IpcCommand::GetContacts {
+                    account_id: account_id.to_string(),
+                },
+                PULL_TIMEOUT,
+            )
+            .await?;
+        pulled_count(reply, "contacts refresh failed")
+    }
+
+    /// Fetches the joined groups (metadata and participants) from the
+    /// server. Same contract as [`Self::request_contacts_refresh`],
+    /// ending in `GroupsSynced`.
+    pub async fn request_groups_refresh(&self, account_id: &str) -> Result<usize> {
+        let reply = self
+            .send_and_wait(
+                IpcCommand::GetGroups {
+                    account_id: account_id.to_string(),
+                },
+                PULL_TIMEOUT,
+            )
+            .await?;
+        pulled_count(reply, "groups refresh failed")
+    }
+}
+
+fn pulled_count(reply: CommandReply, fallback: &str) -> Result<usize> {
+    if !reply.success {
+        return Err(WorkerError::CommandFailed(
+            reply.error.unwrap_or_else(|| fallback.into()),
+        ));
+    }
+    Ok(match reply.data {
+        CommandResultData::Count(n) => usize::try_from(n).unwrap_or(0),
+        _ => 0,
+    })
+}
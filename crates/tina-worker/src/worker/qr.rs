@@ -0,0 +1,62 @@
+// QR validity tracking. whatsmeow rotates pairing codes on its own;
+// each `QrCode` we forward arms a timer for that code's window. If
+// neither a newer code nor `Connected` shows up in time, the UI gets
+// `QrExpired` and can ask for a fresh round via `refresh_qr`. Keyed
+// by account so parallel logins don't cancel each other's timers.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::events::WorkerEvent;
+
+/// Janela usada quando o nanachi não manda `timeout_secs` (builds
+/// antigos). É o valor dos códigos depois do primeiro.
+pub(super) const DEFAULT_QR_VALIDITY: Duration = Duration::from_secs(20);
+
+#[derive(Clone, Default)]
+pub(super) struct QrWatch {
+    /// Geração por conta. Cada código novo (ou `disarm`) incrementa;
+    /// o timer só dispara se ainda for a geração que ele armou.
+    generations: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl QrWatch {
+    pub(super) fn arm(
+        &self,
+        account_id: &str,
+        validity: Duration,
+        event_tx: &mpsc::Sender<WorkerEvent>,
+    ) {
+        let generation = self.bump(account_id);
+        let generations = self.generations.clone();
+        let event_tx = event_tx.clone();
+        let account_id = account_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(validity).await;
+            let current = generations
+                .lock()
+                .ok()
+                .and_then(|g| g.get(&account_id).copied());
+            if current == Some(generation) {
+                let _ = event_tx.send(WorkerEvent::QrExpired { account_id }).await;
+            }
+        });
+    }
+
+    /// Cancela o timer pendente (pareou, deslogou).
+    pub(super) fn disarm(&self, account_id: &str) {
+        self.bump(account_id);
+    }
+
+    fn bump(&self, account_id: &str) -> u64 {
+        let Ok(mut g) = self.generations.lock() else {
+            return 0;
+        };
+        let n = g.entry(account_id.to_string()).or_default();
+        *n = n.wrapping_add(1);
+        *n
+    }
+}
@@ -2,24 +2,54 @@
 // through the DirtyBuffer).
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::mpsc;
 
-use tina_core::{IpcEvent, WaIdentity};
-use tina_db::TinaDb;
+use tina_core::{DisconnectReason, IpcEvent, WaIdentity, unix_now};
+use tina_db::{SyncCursor, TinaDb};
 
 use crate::error::Result;
 use crate::events::WorkerEvent;
 
-pub(super) async fn handle_realtime_event(
-    db: &TinaDb,
-    event_tx: &mpsc::Sender<WorkerEvent>,
-    event: IpcEvent,
-) -> Result<()> {
+use super::connection::{AccountConnectionState, ConnectionStates};
+use super::dispatcher::DispatchCtx;
+use super::pressure::emit_progress;
+use super::qr::DEFAULT_QR_VALIDITY;
+
+pub(super) async fn handle_realtime_event(ctx: &DispatchCtx, event: IpcEvent) -> Result<()> {
+    let DispatchCtx {
+        db,
+        event_tx,
+        qr_watch,
+        logouts,
+        connections,
+        metrics,
+        ..
+    } = ctx;
     match event {
-        IpcEvent::Ready { account_id } => handle_ready(event_tx, account_id).await,
-        IpcEvent::QrCode { account_id, qr } => {
-            let _ = event_tx.send(WorkerEvent::QrCode { account_id, qr }).await;
+        IpcEvent::Ready { account_id } => handle_ready(event_tx, connections, account_id).await,
+        IpcEvent::QrCode {
+            account_id,
+            qr,
+            timeout_secs,
+        } => {
+            let validity = timeout_secs
+                .filter(|s| *s > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_QR_VALIDITY);
+            qr_watch.arm(&account_id, validity, event_tx);
+            connections
+                .set(&account_id, AccountConnectionState::Connecting, event_tx)
+                .await;
+            let _ = event_tx
+                .send(WorkerEvent::QrCode {
+                    account_id,
+                    qr,
+                    issued_at: unix_now(),
+                    expires_in_secs: validity.as_secs(),
+                })
+                .await;
         }
         IpcEvent::PairingCode { account_id, code } => {
             tracing::info!("Pairing code for {}: {}", account_id, code);
@@ -30,22 +60,47 @@ pub(super) async fn handle_realtime_event(
             jid,
             push_name,
         } => {
+            qr_watch.disarm(&account_id);
+            connections
+                .set(&account_id, AccountConnectionState::Connected, event_tx)
+                .await;
             handle_connected(db, event_tx, account_id, phone_number, jid, push_name).await?;
         }
         IpcEvent::Disconnected { account_id, reason } => {
+            let reason = DisconnectReason::parse(&reason);
+            let state = if reason.needs_login() {
+                AccountConnectionState::LoggedOut
+            } else if reason.is_transient() {
+                AccountConnectionState::Connecting
+            } else {
+                AccountConnectionState::Disconnected
+            };
+            connections.set(&account_id, state, event_tx).await;
             let _ = event_tx
                 .send(WorkerEvent::Disconnected { account_id, reason })
                 .await;
         }
         IpcEvent::LoggedOut { account_id } => {
-            db.clear_account_identity(&account_id).await?;
-            let _ = event_tx.send(WorkerEvent::LoggedOut { account_id }).await;
+            qr_watch.disarm(&account_id);
+            connections
+                .set(&account_id, AccountConnectionState::LoggedOut, event_tx)
+                .await;
+            let pending = logouts.take(&account_id);
+            let purged = pending.as_ref().is_some_and(|p| p.purge);
+            db.clear_auth_state(&account_id, purged).await?;
+            let _ = event_tx
+                .send(WorkerEvent::LoggedOut { account_id, purged })
+                .await;
+            if let Some(p) = pending {
+                let _ = p.done.send(());
+            }
         }
         // Bulkables são consumidos pelo dispatcher antes de chegarem
         // aqui; se aparecerem no realtime handler é bug do roteamento.
         IpcEvent::ContactsUpsert { .. }
         | IpcEvent::GroupsUpsert { .. }
-        | IpcEvent::MessagesUpsert { .. } => {
+        | IpcEvent::MessagesUpsert { .. }
+        | IpcEvent::MessageEdited { .. } => {
             tracing::error!("bulk event reached realtime handler — routing bug");
         }
         IpcEvent::ReconcileProgress {
@@ -55,20 +110,25 @@ pub(super) async fn handle_realtime_event(
             total,
             indeterminate,
         } => {
-            let _ = event_tx
-                .send(WorkerEvent::ReconcileProgress {
+            emit_progress(
+                event_tx,
+                metrics,
+                WorkerEvent::ReconcileProgress {
                     account_id,
                     stage,
                     current,
                     total,
                     indeterminate,
-                })
-                .await;
+                },
+            );
         }
         IpcEvent::HistorySyncComplete {
             account_id,
             messages_count,
         } => {
+            if let Err(e) = db.clear_sync_cursor(&account_id).await {
+                tracing::warn!("clear_sync_cursor: {e}");
+            }
             let _ = event_tx
                 .send(WorkerEvent::HistorySyncComplete {
                     account_id,
@@ -82,14 +142,27 @@ pub(super) async fn handle_realtime_event(
             progress,
             messages_count,
         } => {
-            let _ = event_tx
-                .send(WorkerEvent::HistorySyncProgress {
+            // Persist before forwarding so a crash right after the UI
+            // repaints still leaves a cursor to resume from.
+            let cursor = SyncCursor {
+                sync_type: sync_type.clone(),
+                progress,
+                messages_count,
+                updated_at: unix_now(),
+            };
+            if let Err(e) = db.put_sync_cursor(&account_id, &cursor).await {
+                tracing::warn!("put_sync_cursor: {e}");
+            }
+            emit_progress(
+                event_tx,
+                metrics,
+                WorkerEvent::HistorySyncProgress {
                     account_id,
                     sync_type,
                     progress,
                     messages_count,
-                })
-                .await;
+                },
+            );
         }
         IpcEvent::ChatsPinUpdate { account_id, items } => {
             handle_chats_pin_update(db, event_tx, account_id, items).await;
@@ -132,14 +205,16 @@ pub(super) async fn handle_realtime_event(
             current,
             total,
         } => {
-            let _ = event_tx
-                .send(WorkerEvent::MediaDownloadProgress {
+            emit_progress(
+                event_tx,
+                metrics,
+                WorkerEvent::MediaDownloadProgress {
                     account_id,
                     message_id,
                     current,
                     total,
-                })
-                .await;
+                },
+            );
         }
         IpcEvent::MediaDownloaded {
             account_id,
@@ -199,6 +274,41 @@ pub(super) async fn handle_realtime_event(
                 })
                 .await;
         }
+        IpcEvent::GroupInviteLink {
+            account_id,
+            group_jid,
+            link,
+        } => {
+            if let Err(e) = db
+                .set_group_invite_link(&account_id, group_jid.raw(), &link)
+                .await
+            {
+                tracing::error!("set_group_invite_link: {e}");
+            }
+        }
+        IpcEvent::ProfileResult {
+            account_id,
+            jid,
+            img_url,
+            status,
+            name,
+        } => {
+            if let Err(e) = db
+                .set_contact_profile(
+                    &account_id,
+                    jid.raw(),
+                    img_url.as_deref(),
+                    status.as_deref(),
+                    name.as_deref(),
+                )
+                .await
+            {
+                tracing::error!("set_contact_profile: {e}");
+            }
+        }
+        // Guardado pelo dispatcher (`NumberChecks::record`) antes de
+        // chegar aqui.
+        IpcEvent::NumberCheckResult { .. } => {}
         IpcEvent::CommandResult {
             command_id,
             success,
@@ -217,8 +327,13 @@ pub(super) async fn handle_realtime_event(
     Ok(())
 }
 
-async fn handle_ready(event_tx: &mpsc::Sender<WorkerEvent>, account_id: String) {
+async fn handle_ready(
+    event_tx: &mpsc::Sender<WorkerEvent>,
+    connections: &ConnectionStates,
+    account_id: String,
+) {
     if account_id.is_empty() {
+        connections.set_nanachi_ready(true);
         let _ = event_tx.send(WorkerEvent::NanachiReady).await;
     } else {
         let _ = event_tx
@@ -343,6 +458,15 @@ async fn handle_media_downloaded(
             tracing::error!("apply_media_downloaded: {e}");
             vec![message_id.clone()]
         });
+    // Entra no ledger da cache; o orçamento é aplicado no próximo
+    // `download_media`.
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map(|m| i64::try_from(m.len()).unwrap_or(i64::MAX))
+        .unwrap_or(0);
+    if let Err(e) = db.record_media(&path, mimetype.as_deref(), size).await {
+        tracing::error!("record_media: {e}");
+    }
     let _ = event_tx
         .send(WorkerEvent::MediaReady {
             account_id,
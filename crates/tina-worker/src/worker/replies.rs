@@ -0,0 +1,108 @@
+// Commands whose caller waits for nanachi's answer. Everything else is
+// fire-and-forget; here the caller builds the `IpcMessage` itself,
+// registers it before sending and the dispatcher hands over the
+// matching `CommandResult`. Registering first means a fast reply can't
+// slip past before anyone is listening. The command is kept with the
+// registration because `data` only has a shape relative to it (see
+// `tina_core::CommandResultData`).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use tina_core::{CommandResultData, IpcCommand, IpcEvent, IpcMessage, IpcMessageContent};
+
+#[derive(Debug)]
+pub(super) struct CommandReply {
+    pub(super) success: bool,
+    pub(super) data: CommandResultData,
+    pub(super) error: Option<String>,
+}
+
+impl CommandReply {
+    /// Código estável que o nanachi põe em `data.reason` quando a
+    /// recusa é conhecida (`not_admin`, …); `None` pra erro genérico.
+    pub(super) fn reason(&self) -> Option<&str> {
+        self.data.reason()
+    }
+}
+
+struct Waiting {
+    tx: oneshot::Sender<CommandReply>,
+    command: IpcCommand,
+}
+
+#[derive(Clone, Default)]
+pub(super) struct PendingReplies {
+    inner: Arc<Mutex<HashMap<String, Waiting>>>,
+}
+
+impl PendingReplies {
+    pub(super) fn register(&self, message: &IpcMessage) -> oneshot::Receiver<CommandReply> {
+        let (tx, rx) = oneshot::channel();
+        // Evento não tem resposta: o `tx` cai aqui e quem espera vê o
+        // canal fechado.
+        let IpcMessageContent::Command(command) = &message.content else {
+            return rx;
+        };
+        if let Ok(mut pending) = self.inner.lock() {
+            pending.insert(
+                message.id.clone(),
+                Waiting {
+                    tx,
+                    command: command.clone(),
+                },
+            );
+        }
+        rx
+    }
+
+    /// Quem desistiu (timeout, envio falhou) tira a entrada pra o mapa
+    /// não crescer com respostas que nunca vêm.
+    pub(super) fn forget(&self, command_id: &str) {
+        if let Ok(mut pending) = self.inner.lock() {
+            pending.remove(command_id);
+        }
+    }
+
+    /// Chamado pelo dispatcher pra todo evento; só `CommandResult` com
+    /// alguém esperando consome algo.
+    pub(super) fn resolve(&self, event: &IpcEvent) {
+        let IpcEvent::CommandResult {
+            command_id,
+            success,
+            data,
+            error,
+        } = event
+        else {
+            return;
+        };
+        let Some(waiting) = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|mut p| p.remove(command_id))
+        else {
+            return;
+        };
+        let reply = match CommandResultData::parse(&waiting.command, *success, data.clone()) {
+            Ok(data) => CommandReply {
+                success: *success,
+                data,
+                error: error.clone(),
+            },
+            // Formato que o comando não usa: protocolo divergiu, e quem
+            // espera não teria como usar o resultado.
+            Err(e) => {
+                tracing::warn!("malformed CommandResult data for {command_id}: {e}");
+                CommandReply {
+                    success: false,
+                    data: CommandResultData::Ack,
+                    error: Some(format!("malformed reply from nanachi: {e}")),
+                }
+            }
+        };
+        let _ = waiting.tx.send(reply);
+    }
+}
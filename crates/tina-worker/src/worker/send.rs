@@ -0,0 +1,230 @@
+// Outgoing text and what happens when it doesn't go through. The
+// optimistic row goes in first (and is echoed to the UI), then the
+// caller waits for nanachi's `CommandResult`: `Ok` means whatsmeow
+// accepted the message. A rejected send flips the row to `failed`,
+// tells the UI which message (and which text) didn't make it, so it
+// can offer `retry_send` — which re-sends under the same ID, keeping
+// the echo — and returns `WorkerError::SendFailed`. No answer at all
+// is `IpcError::Timeout`; the row stays `pending` then. The wait runs
+// in its own task, so a caller that gives up (a dropped future, a
+// closed window) doesn't leave a rejected send looking `pending`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use tina_core::{IpcCommand, IpcMessage, MessageType, WaIdentity};
+use tina_db::TinaDb;
+use tina_ipc::IpcError;
+
+use crate::error::{Result, WorkerError};
+use crate::events::WorkerEvent;
+
+use super::core::TinaWorker;
+
+/// Past this nanachi is assumed to still be trying; the row stays
+/// `pending` and a late receipt can still settle it.
+const SEND_REPLY_TIMEOUT: Duration = Duration::from_secs(90);
+
+impl TinaWorker {
+    /// Sends `SendMessage` for an already inserted pending row and
+    /// waits for nanachi to accept or reject it.
+    pub(super) async fn dispatch_text_send(
+        &self,
+        account_id: &str,
+        to: &str,
+        content: &str,
+        mentioned_jids: &[String],
+        local_id: &str,
+    ) -> Result<()> {
+        let sent = self
+            .begin_text_send(account_id, to, content, mentioned_jids, local_id)
+            .await?;
+        self.finish_text_send(sent).await
+    }
+
+    /// First half of `dispatch_text_send`: writes the command and
+    /// returns without waiting. A write failure is already reported.
+    pub(super) async fn begin_text_send(
+        &self,
+        account_id: &str,
+        to: &str,
+        content: &str,
+        mentioned_jids: &[String],
+        local_id: &str,
+    ) -> Result<InFlightSend> {
+        let message = IpcMessage::new_command(IpcCommand::SendMessage {
+            account_id: account_id.to_string(),
+            to: WaIdentity::parse(to),
+            content: content.to_string(),
+            mentioned_jids: mentioned_jids
+                .iter()
+                .map(|j| WaIdentity::parse(j))
+                .collect(),
+            local_id: Some(local_id.to_string()),
+        });
+        let command_id = message.id.clone();
+        let failed = FailedSend {
+            account_id: account_id.to_string(),
+            chat_id: to.to_string(),
+            message_id: local_id.to_string(),
+            content: content.to_string(),
+        };
+
+        let sent = match self.command_sender().await {
+            Ok(sender) => {
+                let reply = self.replies.register(&message);
+                match sender.send_message(message).await {
+                    Ok(()) => Ok(reply),
+                    Err(e) => {
+                        self.replies.forget(&command_id);
+                        Err(e.into())
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        };
+        let reply = match sent {
+            Ok(reply) => reply,
+            Err(e) => {
+                failed.report(&self.db, &self.event_tx, e.to_string()).await;
+                return Err(e);
+            }
+        };
+        let (outcome_tx, outcome) = oneshot::channel();
+        let deadline = Instant::now() + SEND_REPLY_TIMEOUT;
+        let db = self.db.clone();
+        let event_tx = self.event_tx.clone();
+        let replies = self.replies.clone();
+        tokio::spawn(async move {
+            let error = match tokio::time::timeout_at(deadline, reply).await {
+                Ok(Ok(reply)) if reply.success => {
+                    let _ = outcome_tx.send(SendOutcome::Sent);
+                    return;
+                }
+                Ok(Ok(reply)) => reply.error.unwrap_or_else(|| "send failed".to_string()),
+                // Dispatcher caiu com o nanachi antes da resposta.
+                Ok(Err(_)) => "nanachi stopped before confirming the send".to_string(),
+                Err(_) => {
+                    replies.forget(&command_id);
+                    tracing::warn!("send {}: no reply from nanachi", failed.message_id);
+                    let _ = outcome_tx.send(SendOutcome::NoReply);
+                    return;
+                }
+            };
+            failed.report(&db, &event_tx, error.clone()).await;
+            let _ = outcome_tx.send(SendOutcome::Rejected(error));
+        });
+        Ok(InFlightSend { outcome })
+    }
+
+    /// Second half: waits for nanachi's verdict on `sent`. Dropping
+    /// the future only stops the waiting; the verdict is still applied.
+    pub(super) async fn finish_text_send(&self, sent: InFlightSend) -> Result<()> {
+        match sent.outcome.await {
+            Ok(SendOutcome::Sent) => Ok(()),
+            Ok(SendOutcome::Rejected(error)) => Err(WorkerError::SendFailed(error)),
+            // Sem veredito (ou a task morreu com o runtime).
+            Ok(SendOutcome::NoReply) | Err(_) => Err(IpcError::Timeout.into()),
+        }
+    }
+
+    /// Re-sends one of our text messages that ended up `failed`, under
+    /// the same message ID, so the existing echo flips back to
+    /// `pending` instead of a second bubble appearing.
+    pub async fn retry_send(&self, account_id: &str, message_id: &str) -> Result<()> {
+        self.ensure_online()?;
+        let row = self
+            .db
+            .get_message_rows_by_ids(account_id, &[message_id.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WorkerError::MessageNotFound(message_id.to_string()))?;
+        if !row.is_from_me
+            || row.delivery_status != "failed"
+            || MessageType::from_raw(&row.message_type) != MessageType::Text
+        {
+            return Err(WorkerError::NotRetryable(message_id.to_string()));
+        }
+        let mentioned: Vec<String> = row
+            .mentions_json
+            .as_deref()
+            .and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default();
+
+        let ids = [message_id.to_string()];
+        self.db
+            .update_delivery_status(account_id, &ids, "pending")
+            .await?;
+        let _ = self
+            .event_tx
+            .send(WorkerEvent::ReceiptUpdate {
+                account_id: account_id.to_string(),
+                message_ids: ids.to_vec(),
+                status: "pending".to_string(),
+            })
+            .await;
+
+        let content = row.content.unwrap_or_default();
+        self.dispatch_text_send(account_id, &row.chat_id, &content, &mentioned, message_id)
+            .await
+    }
+}
+
+/// A `SendMessage` already written. A task waits for its
+/// `CommandResult` (the deadline counts from the write), marks the row
+/// on a rejection and leaves the verdict here.
+pub(super) struct InFlightSend {
+    outcome: oneshot::Receiver<SendOutcome>,
+}
+
+enum SendOutcome {
+    Sent,
+    Rejected(String),
+    NoReply,
+}
+
+/// The message a failed send was about.
+struct FailedSend {
+    account_id: String,
+    chat_id: String,
+    message_id: String,
+    content: String,
+}
+
+impl FailedSend {
+    /// Marca a linha como `failed` e avisa a UI: o `ReceiptUpdate`
+    /// pinta a bolha, o `MessageSendFailed` oferece o reenvio.
+    async fn report(self, db: &Arc<TinaDb>, event_tx: &mpsc::Sender<WorkerEvent>, error: String) {
+        tracing::warn!("send {} failed: {error}", self.message_id);
+        let ids = vec![self.message_id.clone()];
+        match db
+            .update_delivery_status(&self.account_id, &ids, "failed")
+            .await
+        {
+            Ok(n) if n > 0 => {
+                let _ = event_tx
+                    .send(WorkerEvent::ReceiptUpdate {
+                        account_id: self.account_id.clone(),
+                        message_ids: ids,
+                        status: "failed".to_string(),
+                    })
+                    .await;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("mark {} failed: {e}", self.message_id),
+        }
+        let _ = event_tx
+            .send(WorkerEvent::MessageSendFailed {
+                account_id: self.account_id,
+                chat_id: self.chat_id,
+                message_id: self.message_id,
+                content: self.content,
+                error,
+            })
+            .await;
+    }
+}
@@ -0,0 +1,43 @@
+// Cancelled history syncs. `cancel_sync` marks the account here before
+// telling nanachi to stop, so the history chunks already in the pipe
+// are dropped by the dispatcher instead of inserted, and the late
+// `HistorySyncProgress` / `HistorySyncComplete` lines don't reopen the
+// syncing scene. Realtime messages (no `chunk_index`) still go through.
+// Batches flushed before the cancel stay. The mark lasts until the
+// account is started again or nanachi stops.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub(super) struct SyncCancels {
+    accounts: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SyncCancels {
+    pub(super) fn cancel(&self, account_id: &str) {
+        if let Ok(mut a) = self.accounts.lock() {
+            a.insert(account_id.to_string());
+        }
+    }
+
+    pub(super) fn is_cancelled(&self, account_id: &str) -> bool {
+        self.accounts
+            .lock()
+            .map(|a| a.contains(account_id))
+            .unwrap_or(false)
+    }
+
+    /// Nova sessão da conta: um sync novo pode começar.
+    pub(super) fn clear(&self, account_id: &str) {
+        if let Ok(mut a) = self.accounts.lock() {
+            a.remove(account_id);
+        }
+    }
+
+    pub(super) fn reset(&self) {
+        if let Ok(mut a) = self.accounts.lock() {
+            a.clear();
+        }
+    }
+}
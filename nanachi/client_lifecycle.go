@@ -10,9 +10,11 @@ import (
 	"time"
 
 	"go.mau.fi/whatsmeow"
+	"go.mau.fi/whatsmeow/appstate"
 	"go.mau.fi/whatsmeow/proto/waE2E"
 	"go.mau.fi/whatsmeow/store"
 	"go.mau.fi/whatsmeow/types"
+	"google.golang.org/protobuf/proto"
 )
 
 // Client encapsula um *whatsmeow.Client por account_id.
@@ -29,6 +31,11 @@ type Client struct {
 	// running, those events would prematurely close the syncing scene
 	// while the device is still streaming INITIAL_BOOTSTRAP chunks.
 	historySyncSeen atomic.Bool
+	// historyCancelled is set by CancelSync: the user gave up on the
+	// history sync, so further HistorySync blobs for this session are
+	// dropped instead of streamed. A new Client (StartAccount) starts
+	// clean.
+	historyCancelled atomic.Bool
 	// fallbackScheduled guards `scheduleFallbackHistoryComplete` so
 	// repeated AppStateSyncComplete events don't queue overlapping
 	// timers (whatsmeow fires it once per app-state name on every
@@ -130,17 +137,32 @@ func (c *Client) consumeQR(ch <-chan whatsmeow.QRChannelItem) {
 	for evt := range ch {
 		switch evt.Event {
 		case "code":
-			emitQR(c.accountID, evt.Code)
+			emitQR(c.accountID, evt.Code, evt.Timeout)
 		case "success":
 			// pareou — Connected será emitido pelo handler de eventos.
 			return
-		case "timeout", "err-client-outdated", "err-scanned-without-multidevice":
+		case "timeout":
+			// Last code expired. The Rust side times each code and
+			// reports QrExpired itself; the UI asks for a new round
+			// via GetQrCode.
+			return
+		case "err-client-outdated", "err-scanned-without-multidevice":
 			emitError(&c.accountID, fmt.Sprintf("pairing %s", evt.Event))
 			return
 		}
 	}
 }
 
+// refreshQR drops the current socket and reconnects, which opens a
+// new QR channel for an unpaired device.
+func (c *Client) refreshQR() error {
+	if c.wa.Store.ID != nil {
+		return errors.New("account already paired")
+	}
+	c.wa.Disconnect()
+	return c.connect(context.Background())
+}
+
 func (c *Client) disconnect(reason string) {
 	c.wa.Disconnect()
 	emitDisconnected(c.accountID, reason)
@@ -174,6 +196,81 @@ func (c *Client) markRead(p MarkReadPayload) error {
 	return c.wa.MarkRead(ctx, ids, time.Now(), chatJID, senderJID)
 }
 
+// setTyping publishes a composing/paused chat presence to `p.To`.
+// Stateless: the Rust side owns the debounce, we just forward.
+func (c *Client) setTyping(p SetTypingPayload) error {
+	if !c.wa.IsConnected() {
+		return errors.New("client not connected")
+	}
+	jid, err := types.ParseJID(p.To)
+	if err != nil {
+		return fmt.Errorf("invalid jid: %w", err)
+	}
+	state := types.ChatPresencePaused
+	if p.IsTyping {
+		state = types.ChatPresenceComposing
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
+	defer cancel()
+	return c.wa.SendChatPresence(ctx, jid, state, types.ChatPresenceMediaText)
+}
+
+// pinMessage pins (or unpins) a message for everyone in the chat.
+// WhatsApp caps pins at 3 per chat; the Rust side enforces that
+// before we get here, so the server's own rejection stays rare.
+func (c *Client) pinMessage(p PinMessagePayload) error {
+	if !c.wa.IsConnected() {
+		return errors.New("client not connected")
+	}
+	chat, err := types.ParseJID(p.ChatJID)
+	if err != nil {
+		return fmt.Errorf("invalid chat jid: %w", err)
+	}
+	sender := types.EmptyJID
+	if p.SenderJID != "" {
+		if sender, err = types.ParseJID(p.SenderJID); err != nil {
+			return fmt.Errorf("invalid sender jid: %w", err)
+		}
+	}
+	pinType := waE2E.PinInChatMessage_UNPIN_FOR_ALL
+	if p.Pin {
+		pinType = waE2E.PinInChatMessage_PIN_FOR_ALL
+	}
+	msg := &waE2E.Message{
+		PinInChatMessage: &waE2E.PinInChatMessage{
+			Key:               c.wa.BuildMessageKey(chat, sender, types.MessageID(p.MessageID)),
+			Type:              pinType.Enum(),
+			SenderTimestampMS: proto.Int64(time.Now().UnixMilli()),
+		},
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+	_, err = c.wa.SendMessage(ctx, chat, msg)
+	return err
+}
+
+// starMessage flips the "starred" flag through an app-state patch so
+// the phone and other companions pick it up on their next sync.
+func (c *Client) starMessage(p StarMessagePayload) error {
+	if !c.wa.IsConnected() {
+		return errors.New("client not connected")
+	}
+	chat, err := types.ParseJID(p.ChatJID)
+	if err != nil {
+		return fmt.Errorf("invalid chat jid: %w", err)
+	}
+	sender := types.EmptyJID
+	if !p.FromMe && p.SenderJID != "" {
+		if sender, err = types.ParseJID(p.SenderJID); err != nil {
+			return fmt.Errorf("invalid sender jid: %w", err)
+		}
+	}
+	patch := appstate.BuildStar(chat, sender, types.MessageID(p.MessageID), p.FromMe, p.Star)
+	ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+	return c.wa.SendAppState(ctx, patch)
+}
+
 func (c *Client) send(to, content, localID string, mentioned []string) (bool, error) {
 	jid, err := types.ParseJID(to)
 	if err != nil {
@@ -0,0 +1,47 @@
+package main
+
+import (
+	"context"
+	"time"
+
+	"go.mau.fi/whatsmeow/appstate"
+)
+
+// Refetch on demand (GetContacts / GetGroups), for when push events
+// were missed. Unlike reconcile this goes to the server first and
+// sends no progress events: the Rust side waits on the CommandResult.
+// The rows still go out as the usual chunked ContactsUpsert /
+// GroupsUpsert — one line carrying every group would overflow the
+// pipe — so the result only reports how many were sent. They precede
+// it on the wire, so the worker has them by the time it sees the count.
+
+const pullTimeout = 60 * time.Second
+
+// pullContacts re-sincroniza o app state de contatos (nomes da agenda)
+// com o servidor e re-emite o store inteiro.
+func (c *Client) pullContacts() (int, error) {
+	ctx, cancel := context.WithTimeout(context.Background(), pullTimeout)
+	defer cancel()
+	if err := c.wa.FetchAppState(ctx, appstate.WAPatchCriticalUnblockLow, true, false); err != nil {
+		// O store local ainda vale alguma coisa; segue com ele.
+		c.mgr.logger.Warnf("contacts app state resync: %v", err)
+	}
+	contacts, err := c.contactsFromStore(ctx)
+	if err != nil {
+		return 0, err
+	}
+	c.emitContactChunks(contacts, nil)
+	return len(contacts), nil
+}
+
+// pullGroups busca os grupos no servidor (metadados + participantes).
+func (c *Client) pullGroups() (int, error) {
+	ctx, cancel := context.WithTimeout(context.Background(), pullTimeout)
+	defer cancel()
+	groups, err := c.wa.GetJoinedGroups(ctx)
+	if err != nil {
+		return 0, err
+	}
+	c.emitGroupChunks(groups, nil)
+	return len(groups), nil
+}
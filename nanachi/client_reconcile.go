@@ -24,11 +24,26 @@ func (c *Client) reconcile() {
 
 func (c *Client) reconcileContacts(ctx context.Context) {
 	emitReconcileProgress(c.accountID, "Lendo contatos do WhatsApp…", 0, 0, true)
-	all, err := c.wa.Store.Contacts.GetAllContacts(ctx)
+	out, err := c.contactsFromStore(ctx)
 	if err != nil {
 		emitError(&c.accountID, fmt.Sprintf("reconcile contacts: %v", err))
 		return
 	}
+	total := len(out)
+	emitReconcileProgress(c.accountID,
+		fmt.Sprintf("Importando %d contatos…", total), 0, total, false)
+	c.emitContactChunks(out, func(done, total int) {
+		emitReconcileProgress(c.accountID,
+			"Importando contatos…", done, total, false)
+	})
+}
+
+// contactsFromStore lê todos os contatos que o whatsmeow guardou.
+func (c *Client) contactsFromStore(ctx context.Context) ([]ContactData, error) {
+	all, err := c.wa.Store.Contacts.GetAllContacts(ctx)
+	if err != nil {
+		return nil, err
+	}
 	out := make([]ContactData, 0, len(all))
 	for jid, info := range all {
 		cd := ContactData{JID: jid.String()}
@@ -54,26 +69,26 @@ func (c *Client) reconcileContacts(ctx context.Context) {
 		}
 		out = append(out, cd)
 	}
-	total := len(out)
-	emitReconcileProgress(c.accountID,
-		fmt.Sprintf("Importando %d contatos…", total), 0, total, false)
+	return out, nil
+}
+
+// emitContactChunks manda os contatos em lotes de 200; onBatch (pode
+// ser nil) recebe o progresso depois de cada lote.
+func (c *Client) emitContactChunks(contacts []ContactData, onBatch func(done, total int)) {
+	total := len(contacts)
 	for i := 0; i < total; i += 200 {
 		j := i + 200
 		if j > total {
 			j = total
 		}
-		emitContacts(c.accountID, out[i:j])
-		emitReconcileProgress(c.accountID,
-			"Importando contatos…", j, total, false)
+		emitContacts(c.accountID, contacts[i:j])
+		if onBatch != nil {
+			onBatch(j, total)
+		}
 	}
 }
 
 func (c *Client) reconcileGroups(ctx context.Context) {
-	// Cada GroupData carrega a lista completa de participantes; um pacote
-	// com 144 grupos vira uma linha JSON de vários MB e estoura o buffer
-	// do pipe stdout. Chunkamos em batches pequenos.
-	const groupBatch = 10
-
 	emitReconcileProgress(c.accountID, "Carregando grupos…", 0, 0, true)
 	groups, err := c.wa.GetJoinedGroups(ctx)
 	if err != nil {
@@ -83,6 +98,20 @@ func (c *Client) reconcileGroups(ctx context.Context) {
 	total := len(groups)
 	emitReconcileProgress(c.accountID,
 		fmt.Sprintf("Importando %d grupos…", total), 0, total, false)
+	c.emitGroupChunks(groups, func(done, total int) {
+		emitReconcileProgress(c.accountID, "Importando grupos…", done, total, false)
+	})
+}
+
+// emitGroupChunks manda os grupos (e os participantes como contatos) em
+// lotes pequenos; onBatch (pode ser nil) recebe o progresso.
+func (c *Client) emitGroupChunks(groups []*types.GroupInfo, onBatch func(done, total int)) {
+	// Cada GroupData carrega a lista completa de participantes; um pacote
+	// com 144 grupos vira uma linha JSON de vários MB e estoura o buffer
+	// do pipe stdout. Chunkamos em batches pequenos.
+	const groupBatch = 10
+
+	total := len(groups)
 	for i := 0; i < total; i += groupBatch {
 		j := i + groupBatch
 		if j > total {
@@ -98,7 +127,9 @@ func (c *Client) reconcileGroups(ctx context.Context) {
 		if len(contacts) > 0 {
 			emitContacts(c.accountID, contacts)
 		}
-		emitReconcileProgress(c.accountID, "Importando grupos…", j, total, false)
+		if onBatch != nil {
+			onBatch(j, total)
+		}
 	}
 }
 
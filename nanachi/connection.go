@@ -66,14 +66,8 @@ func (c *Client) fetchAllGroups() {
 		emitError(&c.accountID, fmt.Sprintf("get joined groups: %v", err))
 		return
 	}
-	mapped := make([]GroupData, 0, len(groups))
-	contacts := make([]ContactData, 0)
-	for _, g := range groups {
-		mapped = append(mapped, groupFromInfo(g))
-		contacts = append(contacts, participantContacts(g)...)
-	}
-	emitGroups(c.accountID, mapped)
-	emitContacts(c.accountID, contacts)
+	// Em lotes, como no reconcile: o worker conta o progresso por flush.
+	c.emitGroupChunks(groups, nil)
 }
 
 func (c *Client) fetchAllNewsletters() {
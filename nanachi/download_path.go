@@ -2,7 +2,6 @@ package main
 
 import (
 	"mime"
-	"os"
 	"path/filepath"
 	"strings"
 
@@ -10,19 +9,18 @@ import (
 	"go.mau.fi/whatsmeow/proto/waE2E"
 )
 
-// mediaCacheDir devolve `~/.local/share/tina/media/`. Casa com a raiz
-// que `directories::ProjectDirs::from("com.br","zesmoi","tina")` resolve
-// no Linux (qualifier/organization são ignorados — só o nome é usado),
-// mantendo todos os artefatos do app sob o mesmo diretório do `tina.db`.
+// mediaCacheDir devolve `<dataDir>/media/` (por padrão
+// `~/.local/share/tina/media/`). Casa com a raiz que
+// `directories::ProjectDirs::from("com.br","zesmoi","tina")` resolve no
+// Linux (qualifier/organization são ignorados — só o nome é usado), ou
+// com `TINA_DATA_DIR`, mantendo todos os artefatos do app sob o mesmo
+// diretório do `tina.db`.
 func mediaCacheDir() (string, error) {
-	if xdg := os.Getenv("XDG_DATA_HOME"); xdg != "" {
-		return filepath.Join(xdg, "tina", "media"), nil
-	}
-	home, err := os.UserHomeDir()
+	dir, err := dataDir()
 	if err != nil {
 		return "", err
 	}
-	return filepath.Join(home, ".local", "share", "tina", "media"), nil
+	return filepath.Join(dir, "media"), nil
 }
 
 // extensionFor escolhe uma extensão a partir do mimetype com prioridade
@@ -42,6 +42,14 @@ func (c *Client) handleEvent(rawEvt any) {
 	case *events.StreamReplaced:
 		emitDisconnected(c.accountID, "stream replaced")
 
+	case *events.TemporaryBan:
+		emitDisconnected(c.accountID, "temporary ban: "+evt.String())
+
+	case *events.ConnectFailure:
+		// 401/402 chegam como LoggedOut/TemporaryBan; aqui sobram
+		// cliente desatualizado e erros do servidor (5xx).
+		emitDisconnected(c.accountID, fmt.Sprintf("connect failure %d", int(evt.Reason)))
+
 	case *events.Message:
 		c.handleMessage(evt)
 
@@ -140,6 +148,12 @@ func receiptStatus(t types.ReceiptType) string {
 }
 
 func (c *Client) handleMessage(evt *events.Message) {
+	// Edições chegam como protocolMessage apontando pro ID original;
+	// não viram linha nova.
+	if ed := mapEdit(evt); ed != nil {
+		emitMessageEdited(c.accountID, *ed)
+		return
+	}
 	if md := mapMessage(evt); md != nil {
 		// Cache the proto for later DownloadMedia requests. Cheap when
 		// it's a non-media payload (rememberForDownload short-circuits).
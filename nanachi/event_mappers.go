@@ -3,6 +3,7 @@ package main
 import (
 	"time"
 
+	"go.mau.fi/whatsmeow/proto/waE2E"
 	"go.mau.fi/whatsmeow/proto/waWeb"
 	"go.mau.fi/whatsmeow/types"
 	"go.mau.fi/whatsmeow/types/events"
@@ -187,6 +188,42 @@ func mapMessage(evt *events.Message) *MessageData {
 	return &md
 }
 
+// messageEdit é o payload de MessageEdited.
+type messageEdit struct {
+	ChatJID    string
+	MessageID  string
+	NewContent string
+	EditedAt   int64
+}
+
+// mapEdit devolve a edição quando evt é um MESSAGE_EDIT (whatsmeow já
+// desembrulha o EditedMessage; o protocolMessage fica em evt.Message).
+// Edições sem texto legível (ex.: só legenda vazia) são ignoradas.
+func mapEdit(evt *events.Message) *messageEdit {
+	pm := evt.Message.GetProtocolMessage()
+	if pm.GetType() != waE2E.ProtocolMessage_MESSAGE_EDIT {
+		return nil
+	}
+	targetID := pm.GetKey().GetID()
+	if targetID == "" {
+		return nil
+	}
+	content, _ := extractContent(pm.GetEditedMessage())
+	if content == "" {
+		return nil
+	}
+	editedAt := pm.GetTimestampMS() / 1000
+	if editedAt <= 0 {
+		editedAt = evt.Info.Timestamp.Unix()
+	}
+	return &messageEdit{
+		ChatJID:    evt.Info.Chat.String(),
+		MessageID:  targetID,
+		NewContent: content,
+		EditedAt:   editedAt,
+	}
+}
+
 func mapWebMessageInfo(chat types.JID, wmi *waWeb.WebMessageInfo) *MessageData {
 	key := wmi.GetKey()
 	if key == nil || key.GetID() == "" {
@@ -0,0 +1,96 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"time"
+
+	"go.mau.fi/whatsmeow/proto/waE2E"
+	"go.mau.fi/whatsmeow/types"
+	"google.golang.org/protobuf/proto"
+)
+
+// forwardMessage re-sends a stored message to every JID in p.To. Media
+// goes out with the original upload (same URL / media key), so nothing
+// is re-uploaded. A failing recipient doesn't stop the others; only a
+// source we can't rebuild fails the whole command.
+func (c *Client) forwardMessage(p ForwardMessagePayload) ([]ForwardResult, error) {
+	if !c.wa.IsConnected() {
+		return nil, errors.New("client not connected")
+	}
+	src, err := loadMessageProto(c.accountID, p.MessageID, p.RawJSON)
+	if err != nil {
+		// Texto puro não tem proto guardado — o conteúdo basta.
+		if p.Content == nil || *p.Content == "" {
+			return nil, fmt.Errorf("forward %s: %w", p.MessageID, err)
+		}
+		src = &waE2E.Message{Conversation: proto.String(*p.Content)}
+	}
+	fwd := forwardedCopy(src)
+
+	results := make([]ForwardResult, 0, len(p.To))
+	for _, to := range p.To {
+		r := ForwardResult{To: to}
+		jid, err := types.ParseJID(to)
+		if err != nil {
+			r.Error = strPtr(fmt.Sprintf("invalid jid: %v", err))
+			results = append(results, r)
+			continue
+		}
+		ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+		resp, err := c.wa.SendMessage(ctx, jid, fwd)
+		cancel()
+		if err != nil {
+			r.Error = strPtr(err.Error())
+			results = append(results, r)
+			continue
+		}
+		ts := resp.Timestamp.Unix()
+		if ts <= 0 {
+			ts = time.Now().Unix()
+		}
+		id := resp.ID
+		r.MessageID = &id
+		r.Timestamp = &ts
+		rememberForDownload(c.accountID, id, fwd)
+		results = append(results, r)
+	}
+	return results, nil
+}
+
+// forwardedCopy clones m with a fresh ContextInfo flagged as forwarded.
+// The original's reply / mention context doesn't travel with a forward.
+// A bare Conversation can't carry ContextInfo, so it becomes an
+// ExtendedTextMessage.
+func forwardedCopy(m *waE2E.Message) *waE2E.Message {
+	out := proto.Clone(m).(*waE2E.Message)
+	if out.Conversation != nil {
+		out.ExtendedTextMessage = &waE2E.ExtendedTextMessage{Text: out.Conversation}
+		out.Conversation = nil
+	}
+	score := getContextInfo(m).GetForwardingScore() + 1
+	ci := &waE2E.ContextInfo{
+		IsForwarded:     proto.Bool(true),
+		ForwardingScore: proto.Uint32(score),
+	}
+	switch {
+	case out.ExtendedTextMessage != nil:
+		out.ExtendedTextMessage.ContextInfo = ci
+	case out.ImageMessage != nil:
+		out.ImageMessage.ContextInfo = ci
+	case out.VideoMessage != nil:
+		out.VideoMessage.ContextInfo = ci
+	case out.AudioMessage != nil:
+		out.AudioMessage.ContextInfo = ci
+	case out.DocumentMessage != nil:
+		out.DocumentMessage.ContextInfo = ci
+	case out.StickerMessage != nil:
+		out.StickerMessage.ContextInfo = ci
+	case out.ContactMessage != nil:
+		out.ContactMessage.ContextInfo = ci
+	case out.LocationMessage != nil:
+		out.LocationMessage.ContextInfo = ci
+	}
+	return out
+}
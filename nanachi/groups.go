@@ -0,0 +1,106 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"os"
+	"time"
+
+	"go.mau.fi/whatsmeow"
+	"go.mau.fi/whatsmeow/types"
+)
+
+// groupInviteLink returns the group's invite link; with reset the
+// current one is revoked and the new link returned instead. Only
+// admins may do either — whatsmeow reports that as
+// ErrGroupInviteLinkUnauthorized (see groupErrorReason).
+func (c *Client) groupInviteLink(p GroupInviteLinkPayload, reset bool) (string, error) {
+	if !c.wa.IsConnected() {
+		return "", errors.New("client not connected")
+	}
+	jid, err := types.ParseJID(p.GroupJID)
+	if err != nil {
+		return "", fmt.Errorf("invalid group jid: %w", err)
+	}
+	if jid.Server != types.GroupServer {
+		return "", fmt.Errorf("%s is not a group", p.GroupJID)
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 15*time.Second)
+	defer cancel()
+	return c.wa.GetGroupInviteLink(ctx, jid, reset)
+}
+
+// errAlreadyMember is joinGroupByLink's refusal when the link points
+// at a group we're already in. whatsmeow would just hand back the JID.
+var errAlreadyMember = errors.New("already a member of this group")
+
+// joinGroupByLink joins the group behind an invite link and returns its
+// metadata, ready for the worker to write as if it came in a
+// GroupsUpsert. The link is resolved first so a group we're already in
+// is refused instead of silently "joined" again.
+func (c *Client) joinGroupByLink(p JoinGroupByLinkPayload) (GroupData, error) {
+	if !c.wa.IsConnected() {
+		return GroupData{}, errors.New("client not connected")
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 15*time.Second)
+	defer cancel()
+	preview, err := c.wa.GetGroupInfoFromLink(ctx, p.Link)
+	if err != nil {
+		return GroupData{}, err
+	}
+	if c.isParticipant(preview) {
+		return groupFromInfo(preview), errAlreadyMember
+	}
+	jid, err := c.wa.JoinGroupWithLink(ctx, p.Link)
+	if err != nil {
+		return GroupData{}, err
+	}
+	info, err := c.wa.GetGroupInfo(ctx, jid)
+	if err != nil {
+		// Entramos; só a metadata completa falhou. O preview do link
+		// basta pra criar o chat, o próximo reconcile completa.
+		fmt.Fprintf(os.Stderr, "nanachi: group info %s after join: %v\n", jid, err)
+		return groupFromInfo(preview), nil
+	}
+	return groupFromInfo(info), nil
+}
+
+// isParticipant reports whether our own account (by phone JID or LID)
+// is in the group's participant list.
+func (c *Client) isParticipant(g *types.GroupInfo) bool {
+	var own []string
+	if id := c.wa.Store.ID; id != nil {
+		own = append(own, id.User)
+	}
+	if lid := c.wa.Store.LID; !lid.IsEmpty() {
+		own = append(own, lid.User)
+	}
+	for _, p := range g.Participants {
+		for _, user := range own {
+			if p.JID.User == user || p.PhoneNumber.User == user {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// groupErrorReason maps whatsmeow's group errors onto the stable
+// `reason` codes the worker turns into typed errors. Empty when the
+// error has no code of its own.
+func groupErrorReason(err error) string {
+	switch {
+	case errors.Is(err, whatsmeow.ErrGroupInviteLinkUnauthorized):
+		return "not_admin"
+	case errors.Is(err, whatsmeow.ErrNotInGroup), errors.Is(err, whatsmeow.ErrGroupNotFound):
+		return "not_in_group"
+	case errors.Is(err, errAlreadyMember):
+		return "already_member"
+	case errors.Is(err, whatsmeow.ErrInviteLinkInvalid):
+		return "invalid_link"
+	case errors.Is(err, whatsmeow.ErrInviteLinkRevoked):
+		return "link_revoked"
+	}
+	return ""
+}
@@ -92,6 +92,13 @@ func computeReadWatermark(conv *waHistorySync.Conversation) (int64, bool) {
 }
 
 func (c *Client) onHistorySync(evt *events.HistorySync) {
+	if c.historyCancelled.Load() {
+		fmt.Fprintf(os.Stderr,
+			"[sync] HistorySync chunk dropped: account=%s cancelled\n",
+			c.accountID,
+		)
+		return
+	}
 	syncType := evt.Data.GetSyncType().String()
 	progress := evt.Data.GetProgress()
 	conv := evt.Data.GetConversations()
@@ -109,6 +116,7 @@ func (c *Client) onHistorySync(evt *events.HistorySync) {
 		c.accountID, syncType, progress, len(conv),
 	)
 	total := 0
+	chunk := 0
 	pins := make([]chatPinItem, 0)
 	for _, conversation := range conv {
 		chatJID, err := types.ParseJID(conversation.GetID())
@@ -164,17 +172,21 @@ func (c *Client) onHistorySync(evt *events.HistorySync) {
 		// Chunkamos pra não estourar o buffer do pipe stdout (~64KB no
 		// Linux): conversation com 5k mensagens vira uma linha JSON de
 		// vários MB e bloqueia o Go até o Rust drenar. 500 msg/lote é
-		// um sweet spot empírico.
+		// um sweet spot empírico. O índice corre pelo blob inteiro; o
+		// Rust aplica cada lote e avisa o progresso entre eles.
 		const msgBatch = 500
 		for i := 0; i < len(msgs); i += msgBatch {
 			j := i + msgBatch
 			if j > len(msgs) {
 				j = len(msgs)
 			}
-			emitMessages(c.accountID, msgs[i:j])
+			emitMessagesChunk(c.accountID, msgs[i:j], chunk, false)
+			chunk++
 		}
 		total += len(msgs)
 	}
+	// Marcador final (vazio): só aqui sabemos que o blob acabou.
+	emitMessagesChunk(c.accountID, nil, chunk, true)
 	c.historyCount.Add(int64(total))
 	// Emite depois de processar o chunk: o contador já inclui as mensagens
 	// deste chunk. A UI atualiza a barra e o label de progresso juntos.
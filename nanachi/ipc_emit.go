@@ -24,9 +24,10 @@ func emit(eventType string, payload any) {
 	}
 
 	msg := IpcMessage{
-		ID:      newID(),
-		Type:    eventType,
-		Payload: body,
+		ID:              newID(),
+		ProtocolVersion: protocolVersion,
+		Type:            eventType,
+		Payload:         body,
 	}
 	line, err := json.Marshal(msg)
 	if err != nil {
@@ -38,15 +39,21 @@ func emit(eventType string, payload any) {
 	line = append(line, '\n')
 	stdoutMu.Lock()
 	defer stdoutMu.Unlock()
-	os.Stdout.Write(line)
+	ipcOut.Write(line)
 }
 
 func emitReady(accountID string) {
 	emit("Ready", map[string]string{"account_id": accountID})
 }
 
-func emitQR(accountID, qr string) {
-	emit("QrCode", map[string]string{"account_id": accountID, "qr": qr})
+// emitQR forwards one pairing code with its validity window so the
+// Rust side can tell when it goes stale.
+func emitQR(accountID, qr string, timeout time.Duration) {
+	emit("QrCode", map[string]any{
+		"account_id":   accountID,
+		"qr":           qr,
+		"timeout_secs": int64(timeout.Seconds()),
+	})
 }
 
 func emitConnected(accountID string, phone, jid, pushName *string) {
@@ -96,6 +103,21 @@ func emitMessages(accountID string, messages []MessageData) {
 	})
 }
 
+// emitMessagesChunk manda um lote de um stream de history sync.
+// `isLast` fecha o stream e pode vir com `messages` vazio — o Rust
+// flusha na hora em vez de esperar a janela.
+func emitMessagesChunk(accountID string, messages []MessageData, index int, isLast bool) {
+	if messages == nil {
+		messages = []MessageData{}
+	}
+	emit("MessagesUpsert", map[string]any{
+		"account_id":  accountID,
+		"messages":    messages,
+		"chunk_index": index,
+		"is_last":     isLast,
+	})
+}
+
 func emitHistorySyncComplete(accountID string, count int) {
 	emit("HistorySyncComplete", map[string]any{
 		"account_id":     accountID,
@@ -190,6 +212,16 @@ func emitReceiptUpdate(accountID string, messageIDs []string, status string) {
 	})
 }
 
+func emitMessageEdited(accountID string, ed messageEdit) {
+	emit("MessageEdited", map[string]any{
+		"account_id":  accountID,
+		"chat_jid":    ed.ChatJID,
+		"message_id":  ed.MessageID,
+		"new_content": ed.NewContent,
+		"edited_at":   ed.EditedAt,
+	})
+}
+
 func emitMediaDownloadProgress(accountID, messageID string, current, total int64) {
 	emit("MediaDownloadProgress", map[string]any{
 		"account_id": accountID,
@@ -233,6 +265,45 @@ func emitAvatarFailed(accountID, jid, err string) {
 	})
 }
 
+func emitGroupInviteLink(accountID, groupJID, link string) {
+	emit("GroupInviteLink", map[string]any{
+		"account_id": accountID,
+		"group_jid":  groupJID,
+		"link":       link,
+	})
+}
+
+func emitProfileResult(accountID, jid string, p ProfileData) {
+	emit("ProfileResult", map[string]any{
+		"account_id": accountID,
+		"jid":        jid,
+		"img_url":    p.ImgURL,
+		"status":     p.Status,
+		"name":       p.Name,
+	})
+}
+
+// emitNumberCheckResult answers CheckNumber; jid is empty when the
+// number isn't registered.
+func emitNumberCheckResult(accountID, phone, jid string) {
+	var j *string
+	if jid != "" {
+		j = &jid
+	}
+	emit("NumberCheckResult", map[string]any{
+		"account_id": accountID,
+		"phone":      phone,
+		"exists":     j != nil,
+		"jid":        j,
+	})
+}
+
+// emitCommandRefused fails a command with a stable `data.reason` the
+// Rust side can match on, next to the human-readable error.
+func emitCommandRefused(commandID, reason string, err error) {
+	emitCommandResult(commandID, false, map[string]any{"reason": reason}, strPtr(err.Error()))
+}
+
 func emitCommandResult(commandID string, success bool, data any, errStr *string) {
 	emit("CommandResult", map[string]any{
 		"command_id": commandID,
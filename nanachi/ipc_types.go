@@ -2,15 +2,23 @@ package main
 
 import (
 	"encoding/json"
+	"io"
+	"os"
 	"sync"
 )
 
+// protocolVersion acompanha tina_core::PROTOCOL_VERSION. Sobe junto
+// com o lado Rust quando um campo muda de nome, some ou muda de sentido;
+// campo opcional novo não precisa.
+const protocolVersion = 1
+
 // IpcMessage espelha o formato {id, type, payload} usado pelo lado Rust.
 // Comandos chegam como type+payload; eventos saem da mesma forma.
 type IpcMessage struct {
-	ID      string          `json:"id"`
-	Type    string          `json:"type"`
-	Payload json.RawMessage `json:"payload,omitempty"`
+	ID              string          `json:"id"`
+	ProtocolVersion int             `json:"protocol_version,omitempty"`
+	Type            string          `json:"type"`
+	Payload         json.RawMessage `json:"payload,omitempty"`
 }
 
 // Comandos do Rust → Go.
@@ -20,12 +28,18 @@ type StartAccountPayload struct {
 
 type StopAccountPayload struct {
 	AccountID string `json:"account_id"`
+	// Reason vai no Disconnected; vazio = parada pelo usuário.
+	Reason string `json:"reason,omitempty"`
 }
 
 type LogoutPayload struct {
 	AccountID string `json:"account_id"`
 }
 
+type GetQrCodePayload struct {
+	AccountID string `json:"account_id"`
+}
+
 type SendMessagePayload struct {
 	AccountID string `json:"account_id"`
 	To        string `json:"to"`
@@ -54,6 +68,95 @@ type MarkReadPayload struct {
 	MessageIDs []string `json:"message_ids"`
 }
 
+// MarkChatsReadPayload mirrors `IpcCommand::MarkChatsRead`: one
+// MarkRead per entry, all for the same account.
+type MarkChatsReadPayload struct {
+	AccountID string            `json:"account_id"`
+	Chats     []ReadReceiptItem `json:"chats"`
+}
+
+// ReadReceiptItem mirrors `ReadReceiptItem`.
+type ReadReceiptItem struct {
+	ChatJID    string   `json:"chat_jid"`
+	SenderJID  string   `json:"sender_jid"`
+	MessageIDs []string `json:"message_ids"`
+}
+
+// SetTypingPayload mirrors `IpcCommand::SetTyping`. `IsTyping=false`
+// sends `paused`, clearing the indicator on the peer's side.
+type SetTypingPayload struct {
+	AccountID string `json:"account_id"`
+	To        string `json:"to"`
+	IsTyping  bool   `json:"is_typing"`
+}
+
+// PinMessagePayload mirrors `IpcCommand::PinMessage`. `SenderJID` is
+// empty for our own messages (the key is built with FromMe=true).
+type PinMessagePayload struct {
+	AccountID string `json:"account_id"`
+	ChatJID   string `json:"chat_jid"`
+	MessageID string `json:"message_id"`
+	SenderJID string `json:"sender_jid,omitempty"`
+	Pin       bool   `json:"pin"`
+}
+
+// StarMessagePayload mirrors `IpcCommand::StarMessage`.
+type StarMessagePayload struct {
+	AccountID string `json:"account_id"`
+	ChatJID   string `json:"chat_jid"`
+	MessageID string `json:"message_id"`
+	SenderJID string `json:"sender_jid,omitempty"`
+	FromMe    bool   `json:"from_me"`
+	Star      bool   `json:"star"`
+}
+
+// ForwardMessagePayload mirrors `IpcCommand::ForwardMessage`.
+type ForwardMessagePayload struct {
+	AccountID string   `json:"account_id"`
+	FromChat  string   `json:"from_chat"`
+	MessageID string   `json:"message_id"`
+	To        []string `json:"to"`
+	RawJSON   *string  `json:"raw_json,omitempty"`
+	Content   *string  `json:"content,omitempty"`
+}
+
+// GroupInviteLinkPayload mirrors `IpcCommand::GetGroupInviteLink` and
+// `IpcCommand::RevokeGroupInviteLink`.
+type GroupInviteLinkPayload struct {
+	AccountID string `json:"account_id"`
+	GroupJID  string `json:"group_jid"`
+}
+
+// ProfilePayload mirrors `IpcCommand::GetProfile`.
+type ProfilePayload struct {
+	AccountID string `json:"account_id"`
+	JID       string `json:"jid"`
+}
+
+// CheckNumberPayload mirrors `IpcCommand::CheckNumber`. Phone is
+// digits only (country code first); the `+` is added here.
+type CheckNumberPayload struct {
+	AccountID string `json:"account_id"`
+	Phone     string `json:"phone"`
+}
+
+// JoinGroupByLinkPayload mirrors `IpcCommand::JoinGroupByLink`. Link
+// is the full `https://chat.whatsapp.com/<code>` URL; whatsmeow strips
+// the prefix itself.
+type JoinGroupByLinkPayload struct {
+	AccountID string `json:"account_id"`
+	Link      string `json:"link"`
+}
+
+// ForwardResult is one entry of the ForwardMessage CommandResult
+// data: either the new message's ID + timestamp or an error.
+type ForwardResult struct {
+	To        string  `json:"to"`
+	MessageID *string `json:"message_id,omitempty"`
+	Timestamp *int64  `json:"timestamp,omitempty"`
+	Error     *string `json:"error,omitempty"`
+}
+
 // SendMediaPayload mirrors `IpcCommand::SendMedia` from the Rust side.
 // `Kind` is one of: image, video, audio, voice, sticker, document.
 type SendMediaPayload struct {
@@ -133,5 +236,15 @@ type MessageData struct {
 }
 
 // stdoutMu protege stdout de escritas concorrentes (cada goroutine de
-// evento emite linhas independentemente).
+// evento emite linhas independentemente). Também guarda ipcOut.
 var stdoutMu sync.Mutex
+
+// ipcOut é para onde os eventos vão: stdout por padrão, a conexão do
+// cliente no modo -listen (io.Discard enquanto ninguém está conectado).
+var ipcOut io.Writer = os.Stdout
+
+func setIpcOut(w io.Writer) {
+	stdoutMu.Lock()
+	defer stdoutMu.Unlock()
+	ipcOut = w
+}
@@ -0,0 +1,58 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"net"
+	"os"
+)
+
+// serveTCP runs nanachi as a standalone service: the same line-delimited
+// JSON as stdin/stdout, over one TCP client at a time. Accounts stay
+// connected between clients; events emitted while nobody is attached
+// are dropped (the client reconciles on connect). The socket has no
+// authentication — anyone who can reach the port controls the WhatsApp
+// sessions — so only loopback addresses are accepted.
+func serveTCP(mgr *Manager, addr string) error {
+	if err := requireLoopback(addr); err != nil {
+		return err
+	}
+	ln, err := net.Listen("tcp", addr)
+	if err != nil {
+		return fmt.Errorf("listen %s: %w", addr, err)
+	}
+	defer ln.Close()
+	// "localhost" pode resolver pra outra coisa: confere o que abriu.
+	if tcp, ok := ln.Addr().(*net.TCPAddr); !ok || !tcp.IP.IsLoopback() {
+		return fmt.Errorf("refusing to serve on non-loopback %s", ln.Addr())
+	}
+	fmt.Fprintf(os.Stderr, "nanachi: listening on %s\n", ln.Addr())
+
+	setIpcOut(io.Discard)
+	for {
+		conn, err := ln.Accept()
+		if err != nil {
+			return fmt.Errorf("accept: %w", err)
+		}
+		fmt.Fprintf(os.Stderr, "nanachi: client %s connected\n", conn.RemoteAddr())
+		setIpcOut(conn)
+		emitReady("")
+		readCommands(mgr, conn)
+		setIpcOut(io.Discard)
+		conn.Close()
+		fmt.Fprintf(os.Stderr, "nanachi: client %s disconnected\n", conn.RemoteAddr())
+	}
+}
+
+// requireLoopback rejects listen addresses that would expose the socket
+// beyond this machine, including an empty host (all interfaces).
+func requireLoopback(addr string) error {
+	tcp, err := net.ResolveTCPAddr("tcp", addr)
+	if err != nil {
+		return fmt.Errorf("listen %s: %w", addr, err)
+	}
+	if tcp.IP == nil || !tcp.IP.IsLoopback() {
+		return fmt.Errorf("refusing to listen on %s: use a loopback address such as 127.0.0.1", addr)
+	}
+	return nil
+}
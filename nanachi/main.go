@@ -4,7 +4,10 @@ import (
 	"bufio"
 	"context"
 	"encoding/json"
+	"errors"
+	"flag"
 	"fmt"
+	"io"
 	"os"
 	"os/signal"
 	"path/filepath"
@@ -14,7 +17,13 @@ import (
 	"go.mau.fi/whatsmeow/store/sqlstore"
 )
 
+// dataDir is the base of everything nanachi writes. TINA_DATA_DIR wins
+// (the Rust side resolves the same variable for tina.db), then the XDG
+// default.
 func dataDir() (string, error) {
+	if dir := os.Getenv("TINA_DATA_DIR"); dir != "" {
+		return dir, nil
+	}
 	if xdg := os.Getenv("XDG_DATA_HOME"); xdg != "" {
 		return filepath.Join(xdg, "tina"), nil
 	}
@@ -26,6 +35,10 @@ func dataDir() (string, error) {
 }
 
 func main() {
+	listenAddr := flag.String("listen", "",
+		"serve IPC on this host:port instead of stdin/stdout (e.g. 127.0.0.1:7777)")
+	flag.Parse()
+
 	dir, err := dataDir()
 	if err != nil {
 		emitError(nil, fmt.Sprintf("failed to resolve data dir: %v", err))
@@ -58,9 +71,23 @@ func main() {
 		os.Exit(0)
 	}()
 
+	if *listenAddr != "" {
+		if err := serveTCP(mgr, *listenAddr); err != nil {
+			fmt.Fprintf(os.Stderr, "nanachi: %v\n", err)
+			mgr.shutdown()
+			os.Exit(1)
+		}
+		return
+	}
+
 	emitReady("")
+	readCommands(mgr, os.Stdin)
+	mgr.shutdown()
+}
 
-	scanner := bufio.NewScanner(os.Stdin)
+// readCommands lê comandos (uma linha JSON cada) até EOF.
+func readCommands(mgr *Manager, r io.Reader) {
+	scanner := bufio.NewScanner(r)
 	scanner.Buffer(make([]byte, 64*1024), 4*1024*1024)
 	for scanner.Scan() {
 		line := scanner.Bytes()
@@ -74,8 +101,6 @@ func main() {
 		}
 		handleCommand(mgr, msg)
 	}
-
-	mgr.shutdown()
 }
 
 func handleCommand(mgr *Manager, msg IpcMessage) {
@@ -98,7 +123,11 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
 			return
 		}
-		mgr.stopAccount(p.AccountID, "Stopped by user")
+		reason := p.Reason
+		if reason == "" {
+			reason = "Stopped by user"
+		}
+		mgr.stopAccount(p.AccountID, reason)
 		emitCommandResult(msg.ID, true, nil, nil)
 
 	case "Logout":
@@ -113,6 +142,18 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 		}
 		emitCommandResult(msg.ID, true, nil, nil)
 
+	case "GetQrCode":
+		var p GetQrCodePayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		if err := mgr.refreshQR(p.AccountID); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		emitCommandResult(msg.ID, true, nil, nil)
+
 	case "Reconcile":
 		var p struct {
 			AccountID string `json:"account_id"`
@@ -127,6 +168,41 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 		}
 		emitCommandResult(msg.ID, true, nil, nil)
 
+	case "CancelSync":
+		var p struct {
+			AccountID string `json:"account_id"`
+		}
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		if err := mgr.cancelHistorySync(p.AccountID); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		emitCommandResult(msg.ID, true, nil, nil)
+
+	case "GetContacts", "GetGroups":
+		var p struct {
+			AccountID string `json:"account_id"`
+		}
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		pull := mgr.pullContacts
+		if msg.Type == "GetGroups" {
+			pull = mgr.pullGroups
+		}
+		go func() {
+			n, err := pull(p.AccountID)
+			if err != nil {
+				emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				return
+			}
+			emitCommandResult(msg.ID, true, map[string]any{"count": n}, nil)
+		}()
+
 	case "SendMessage":
 		var p SendMessagePayload
 		if err := json.Unmarshal(msg.Payload, &p); err != nil {
@@ -154,6 +230,79 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 			}
 		}()
 
+	case "MarkChatsRead":
+		var p MarkChatsReadPayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		emitCommandResult(msg.ID, true, nil, nil)
+		go func() {
+			if err := mgr.markChatsRead(p); err != nil {
+				emitError(&p.AccountID, fmt.Sprintf("mark chats read: %v", err))
+			}
+		}()
+
+	case "PinMessage":
+		var p PinMessagePayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		emitCommandResult(msg.ID, true, nil, nil)
+		go func() {
+			if err := mgr.pinMessage(p); err != nil {
+				emitError(&p.AccountID, fmt.Sprintf("pin message: %v", err))
+			}
+		}()
+
+	case "StarMessage":
+		var p StarMessagePayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		emitCommandResult(msg.ID, true, nil, nil)
+		go func() {
+			if err := mgr.starMessage(p); err != nil {
+				emitError(&p.AccountID, fmt.Sprintf("star message: %v", err))
+			}
+		}()
+
+	case "ForwardMessage":
+		var p ForwardMessagePayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		// Unlike the other sends, the CommandResult waits for the
+		// outcome: the Rust side writes the local copies from the
+		// per-recipient results. One upload per recipient for media,
+		// so it runs off the IPC loop.
+		go func() {
+			results, err := mgr.forwardMessage(p)
+			if err != nil {
+				emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				return
+			}
+			emitCommandResult(msg.ID, true, map[string]any{"results": results}, nil)
+		}()
+
+	case "SetTyping":
+		var p SetTypingPayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		// Presence is fire-and-forget; a failure here is not worth an
+		// Error event (the peer just won't see the indicator).
+		emitCommandResult(msg.ID, true, nil, nil)
+		go func() {
+			if err := mgr.setTyping(p); err != nil {
+				fmt.Fprintf(os.Stderr, "set typing: %v\n", err)
+			}
+		}()
+
 	case "SendMedia":
 		var p SendMediaPayload
 		if err := json.Unmarshal(msg.Payload, &p); err != nil {
@@ -226,6 +375,41 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 			}
 		}()
 
+	case "GetProfile":
+		var p ProfilePayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		go func() {
+			profile, err := mgr.profile(p)
+			if err != nil {
+				emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				return
+			}
+			// O ProfileResult sai antes: o worker grava o contato e só
+			// então lê a linha de volta, ao ver o CommandResult.
+			emitProfileResult(p.AccountID, p.JID, profile)
+			emitCommandResult(msg.ID, true, nil, nil)
+		}()
+
+	case "CheckNumber":
+		var p CheckNumberPayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		go func() {
+			jid, err := mgr.checkNumber(p)
+			if err != nil {
+				emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				return
+			}
+			// Como no GetProfile: o resultado antes do CommandResult.
+			emitNumberCheckResult(p.AccountID, p.Phone, jid)
+			emitCommandResult(msg.ID, true, nil, nil)
+		}()
+
 	case "RefreshChat":
 		var p struct {
 			AccountID string `json:"account_id"`
@@ -238,6 +422,52 @@ func handleCommand(mgr *Manager, msg IpcMessage) {
 		emitCommandResult(msg.ID, true, nil, nil)
 		go refreshChat(mgr, p.AccountID, p.ChatJID)
 
+	case "GetGroupInviteLink", "RevokeGroupInviteLink":
+		var p GroupInviteLinkPayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		reset := msg.Type == "RevokeGroupInviteLink"
+		go func() {
+			link, err := mgr.groupInviteLink(p, reset)
+			if err != nil {
+				if reason := groupErrorReason(err); reason != "" {
+					emitCommandRefused(msg.ID, reason, err)
+				} else {
+					emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				}
+				return
+			}
+			emitGroupInviteLink(p.AccountID, p.GroupJID, link)
+			emitCommandResult(msg.ID, true, map[string]any{"link": link}, nil)
+		}()
+
+	case "JoinGroupByLink":
+		var p JoinGroupByLinkPayload
+		if err := json.Unmarshal(msg.Payload, &p); err != nil {
+			emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+			return
+		}
+		go func() {
+			group, err := mgr.joinGroupByLink(p)
+			switch {
+			case errors.Is(err, errAlreadyMember):
+				emitCommandResult(msg.ID, false, map[string]any{
+					"reason":    "already_member",
+					"group_jid": group.JID,
+				}, strPtr(err.Error()))
+			case err != nil:
+				if reason := groupErrorReason(err); reason != "" {
+					emitCommandRefused(msg.ID, reason, err)
+				} else {
+					emitCommandResult(msg.ID, false, nil, strPtr(err.Error()))
+				}
+			default:
+				emitCommandResult(msg.ID, true, map[string]any{"group": group}, nil)
+			}
+		}()
+
 	case "Shutdown":
 		emitCommandResult(msg.ID, true, nil, nil)
 		mgr.shutdown()
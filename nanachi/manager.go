@@ -97,6 +97,18 @@ func (m *Manager) stopAccount(accountID, reason string) {
 	client.disconnect(reason)
 }
 
+// refreshQR restarts pairing for an unpaired account. Codes from the
+// previous QR channel are all expired by the time the UI asks.
+func (m *Manager) refreshQR(accountID string) error {
+	m.mu.Lock()
+	client := m.clients[accountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not started")
+	}
+	return client.refreshQR()
+}
+
 func (m *Manager) logoutAccount(accountID string) error {
 	m.mu.Lock()
 	client := m.clients[accountID]
@@ -129,6 +141,39 @@ func (m *Manager) reconcileAccount(accountID string) error {
 	return nil
 }
 
+// cancelHistorySync stops streaming HistorySync for the rest of the
+// session. Chunks already written stay on the Rust side.
+func (m *Manager) cancelHistorySync(accountID string) error {
+	m.mu.Lock()
+	client := m.clients[accountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not connected")
+	}
+	client.historyCancelled.Store(true)
+	return nil
+}
+
+func (m *Manager) pullContacts(accountID string) (int, error) {
+	m.mu.Lock()
+	client := m.clients[accountID]
+	m.mu.Unlock()
+	if client == nil {
+		return 0, errors.New("account not connected")
+	}
+	return client.pullContacts()
+}
+
+func (m *Manager) pullGroups(accountID string) (int, error) {
+	m.mu.Lock()
+	client := m.clients[accountID]
+	m.mu.Unlock()
+	if client == nil {
+		return 0, errors.New("account not connected")
+	}
+	return client.pullGroups()
+}
+
 func (m *Manager) sendMessage(accountID, to, content, localID string, mentioned []string) (bool, error) {
 	m.mu.Lock()
 	client := m.clients[accountID]
@@ -159,6 +204,117 @@ func (m *Manager) markRead(p MarkReadPayload) error {
 	return client.markRead(p)
 }
 
+// markChatsRead sends every receipt of the batch even when one fails;
+// the first error is reported with the number that failed.
+func (m *Manager) markChatsRead(p MarkChatsReadPayload) error {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not connected")
+	}
+	var firstErr error
+	failed := 0
+	for _, chat := range p.Chats {
+		err := client.markRead(MarkReadPayload{
+			AccountID:  p.AccountID,
+			ChatJID:    chat.ChatJID,
+			SenderJID:  chat.SenderJID,
+			MessageIDs: chat.MessageIDs,
+		})
+		if err != nil {
+			failed++
+			if firstErr == nil {
+				firstErr = err
+			}
+		}
+	}
+	if firstErr != nil {
+		return fmt.Errorf("%d of %d chats: %w", failed, len(p.Chats), firstErr)
+	}
+	return nil
+}
+
+func (m *Manager) setTyping(p SetTypingPayload) error {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not connected")
+	}
+	return client.setTyping(p)
+}
+
+func (m *Manager) pinMessage(p PinMessagePayload) error {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not connected")
+	}
+	return client.pinMessage(p)
+}
+
+func (m *Manager) starMessage(p StarMessagePayload) error {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return errors.New("account not connected")
+	}
+	return client.starMessage(p)
+}
+
+func (m *Manager) forwardMessage(p ForwardMessagePayload) ([]ForwardResult, error) {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return nil, errors.New("account not connected")
+	}
+	return client.forwardMessage(p)
+}
+
+func (m *Manager) groupInviteLink(p GroupInviteLinkPayload, reset bool) (string, error) {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return "", errors.New("account not connected")
+	}
+	return client.groupInviteLink(p, reset)
+}
+
+func (m *Manager) profile(p ProfilePayload) (ProfileData, error) {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return ProfileData{}, errors.New("account not connected")
+	}
+	return client.profile(p)
+}
+
+func (m *Manager) checkNumber(p CheckNumberPayload) (string, error) {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return "", errors.New("account not connected")
+	}
+	return client.checkNumber(p)
+}
+
+func (m *Manager) joinGroupByLink(p JoinGroupByLinkPayload) (GroupData, error) {
+	m.mu.Lock()
+	client := m.clients[p.AccountID]
+	m.mu.Unlock()
+	if client == nil {
+		return GroupData{}, errors.New("account not connected")
+	}
+	return client.joinGroupByLink(p)
+}
+
 func (m *Manager) shutdown() {
 	m.mu.Lock()
 	clients := make([]*Client, 0, len(m.clients))
@@ -0,0 +1,82 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"time"
+
+	"go.mau.fi/whatsmeow"
+	"go.mau.fi/whatsmeow/types"
+)
+
+// ProfileData is what GetProfile answers with. A nil field is one the
+// contact's privacy settings hide, or that simply isn't set.
+type ProfileData struct {
+	ImgURL *string `json:"img_url"`
+	Status *string `json:"status"`
+	Name   *string `json:"name"`
+}
+
+// profile busca recado e foto do contato no servidor; o nome vem do
+// push name que o whatsmeow guardou. Foto escondida pela privacidade
+// (ou inexistente) não é erro: volta nil.
+func (c *Client) profile(p ProfilePayload) (ProfileData, error) {
+	if !c.wa.IsConnected() {
+		return ProfileData{}, errors.New("client not connected")
+	}
+	jid, err := types.ParseJID(p.JID)
+	if err != nil {
+		return ProfileData{}, fmt.Errorf("invalid jid: %w", err)
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 15*time.Second)
+	defer cancel()
+
+	var out ProfileData
+	infos, err := c.wa.GetUserInfo(ctx, []types.JID{jid})
+	if err != nil {
+		return ProfileData{}, fmt.Errorf("get user info: %w", err)
+	}
+	if info, ok := infos[jid]; ok && info.Status != "" {
+		s := info.Status
+		out.Status = &s
+	}
+
+	pic, err := c.wa.GetProfilePictureInfo(ctx, jid, &whatsmeow.GetProfilePictureParams{})
+	switch {
+	case errors.Is(err, whatsmeow.ErrProfilePictureNotSet),
+		errors.Is(err, whatsmeow.ErrProfilePictureUnauthorized):
+	case err != nil:
+		return ProfileData{}, fmt.Errorf("get profile picture: %w", err)
+	case pic != nil && pic.URL != "":
+		u := pic.URL
+		out.ImgURL = &u
+	}
+
+	if info, err := c.wa.Store.Contacts.GetContact(ctx, jid); err == nil && info.PushName != "" {
+		n := info.PushName
+		out.Name = &n
+	}
+	return out, nil
+}
+
+// checkNumber pergunta ao servidor se o número tem WhatsApp. Devolve o
+// JID canônico, ou "" se não tem. O worker espaça as consultas: muitas
+// seguidas fazem o WhatsApp marcar a conta.
+func (c *Client) checkNumber(p CheckNumberPayload) (string, error) {
+	if !c.wa.IsConnected() {
+		return "", errors.New("client not connected")
+	}
+	ctx, cancel := context.WithTimeout(context.Background(), 15*time.Second)
+	defer cancel()
+	resp, err := c.wa.IsOnWhatsApp(ctx, []string{"+" + p.Phone})
+	if err != nil {
+		return "", fmt.Errorf("is on whatsapp: %w", err)
+	}
+	for _, r := range resp {
+		if r.IsIn {
+			return r.JID.ToNonAD().String(), nil
+		}
+	}
+	return "", nil
+}
@@ -136,13 +136,64 @@ func extractThumbnail(m *waE2E.Message) []byte {
 	return nil
 }
 
+// stripStructured keeps the fields the Rust side renders for
+// non-media messages with more than a line of text (location pin,
+// contact card, poll) — see `tina_core::parse_db_message`. Returns nil
+// for every other kind.
+func stripStructured(m *waE2E.Message) *waE2E.Message {
+	if m == nil {
+		return nil
+	}
+	var out waE2E.Message
+	switch {
+	case m.LocationMessage != nil:
+		x := m.LocationMessage
+		out.LocationMessage = &waE2E.LocationMessage{
+			DegreesLatitude:  x.DegreesLatitude,
+			DegreesLongitude: x.DegreesLongitude,
+			Name:             x.Name,
+			Address:          x.Address,
+		}
+	case m.LiveLocationMessage != nil:
+		x := m.LiveLocationMessage
+		out.LiveLocationMessage = &waE2E.LiveLocationMessage{
+			DegreesLatitude:  x.DegreesLatitude,
+			DegreesLongitude: x.DegreesLongitude,
+		}
+	case m.ContactMessage != nil:
+		x := m.ContactMessage
+		out.ContactMessage = &waE2E.ContactMessage{
+			DisplayName: x.DisplayName,
+			Vcard:       x.Vcard,
+		}
+	case m.PollCreationMessage != nil:
+		x := m.PollCreationMessage
+		opts := make([]*waE2E.PollCreationMessage_Option, 0, len(x.GetOptions()))
+		for _, o := range x.GetOptions() {
+			opts = append(opts, &waE2E.PollCreationMessage_Option{OptionName: o.OptionName})
+		}
+		out.PollCreationMessage = &waE2E.PollCreationMessage{
+			Name:                   x.Name,
+			Options:                opts,
+			SelectableOptionsCount: x.SelectableOptionsCount,
+		}
+	default:
+		return nil
+	}
+	return &out
+}
+
 // marshalProto turns the download-only subset of a `*waE2E.Message`
-// (see `stripForDownload`) into a JSON string for persistence in the
+// (see `stripForDownload`) — or, for location/contact/poll, the
+// `stripStructured` subset — into a JSON string for persistence in the
 // `messages.raw_json` column. We round-trip through `unmarshalProto`
 // when DownloadMedia hits a row whose proto isn't in the in-memory
 // cache. `protojson` keeps field names stable across proto upgrades.
 func marshalProto(m *waE2E.Message) (string, bool) {
 	stripped := stripForDownload(m)
+	if stripped == nil {
+		stripped = stripStructured(m)
+	}
 	if stripped == nil {
 		return "", false
 	}